@@ -0,0 +1,6 @@
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    /// CHECK: owner and discriminator verified in the handler
+    pub pool: AccountInfo<'info>,
+    pub user: Signer<'info>,
+}
@@ -0,0 +1,5 @@
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub pool: AccountInfo<'info>,
+    pub user: Signer<'info>,
+}
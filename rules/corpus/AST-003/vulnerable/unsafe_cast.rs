@@ -0,0 +1,3 @@
+pub fn header(data: &[u8]) -> u64 {
+    unsafe { *(data.as_ptr() as *const u64) }
+}
@@ -0,0 +1,8 @@
+#[derive(Accounts)]
+pub struct Transfer<'info> {
+    #[account(mut, has_one = owner)]
+    pub from: Account<'info, Vault>,
+    #[account(mut)]
+    pub to: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
@@ -0,0 +1,6 @@
+pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+    require!(ctx.accounts.position.deposited >= amount, ErrorCode::Insufficient);
+    token::transfer(ctx.accounts.transfer_ctx(), amount)?;
+    ctx.accounts.position.deposited -= amount;
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    token::transfer(ctx.accounts.deposit_ctx(), amount)?;
+    ctx.accounts.pool_vault.reload()?;
+    let total = ctx.accounts.pool_vault.amount;
+    emit!(Deposited { amount, total });
+    Ok(())
+}
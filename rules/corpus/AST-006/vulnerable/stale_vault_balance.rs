@@ -0,0 +1,6 @@
+pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    token::transfer(ctx.accounts.deposit_ctx(), amount)?;
+    let total = ctx.accounts.pool_vault.amount;
+    emit!(Deposited { amount, total });
+    Ok(())
+}
@@ -0,0 +1,11 @@
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    /// CHECK: owned by the oracle program, deserialized in the handler
+    #[account(owner = oracle_program::ID)]
+    pub oracle: AccountInfo<'info>,
+}
+
+pub fn liquidate(ctx: Context<Liquidate>) -> Result<()> {
+    let price = PriceAccount::try_from_slice(&ctx.accounts.oracle.data.borrow())?;
+    Ok(())
+}
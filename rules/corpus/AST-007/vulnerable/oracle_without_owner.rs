@@ -0,0 +1,10 @@
+#[derive(Accounts)]
+pub struct Liquidate<'info> {
+    /// CHECK: price account, deserialized in the handler
+    pub oracle: AccountInfo<'info>,
+}
+
+pub fn liquidate(ctx: Context<Liquidate>) -> Result<()> {
+    let price = PriceAccount::try_from_slice(&ctx.accounts.oracle.data.borrow())?;
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+pub fn swap(ctx: Context<Swap>, amount_in: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let price = ctx
+        .accounts
+        .price_update
+        .get_price_no_older_than(&clock, MAX_AGE, &FEED_ID)?;
+    require!(price.conf < MAX_CONF, SwapError::PriceTooUncertain);
+    let amount_out = amount_in
+        .checked_mul(price.price as u64)
+        .ok_or(SwapError::Overflow)?;
+    transfer_out(&ctx, amount_out)
+}
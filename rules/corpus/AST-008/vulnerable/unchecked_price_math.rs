@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+pub fn swap(ctx: Context<Swap>, amount_in: u64) -> Result<()> {
+    let feed = load_price_feed_from_account_info(&ctx.accounts.oracle).unwrap();
+    let price = feed.get_price_unchecked();
+    let amount_out = amount_in
+        .checked_mul(price.price as u64)
+        .ok_or(SwapError::Overflow)?;
+    transfer_out(&ctx, amount_out)
+}
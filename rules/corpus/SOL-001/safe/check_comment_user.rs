@@ -0,0 +1,6 @@
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    /// CHECK: only receives lamports
+    #[account(mut)]
+    pub user: AccountInfo<'info>,
+}
@@ -0,0 +1,12 @@
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump = vault.bump,
+        has_one = pool,
+        constraint = vault.authority == authority.key()
+            @ ErrorCode::Unauthorized,
+    )]
+    pub authority: AccountInfo<'info>,
+}
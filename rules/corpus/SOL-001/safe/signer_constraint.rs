@@ -0,0 +1,8 @@
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, has_one = authority)]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: must sign; compared against vault.authority by has_one
+    #[account(signer)]
+    pub authority: AccountInfo<'info>,
+}
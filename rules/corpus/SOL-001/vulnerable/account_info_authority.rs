@@ -0,0 +1,8 @@
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+
+
+    pub authority: AccountInfo<'info>,
+}
@@ -0,0 +1,7 @@
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, has_one = authority, close = authority)]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: compared against vault.authority by has_one
+    pub authority: AccountInfo<'info>,
+}
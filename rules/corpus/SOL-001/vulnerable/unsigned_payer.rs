@@ -0,0 +1,7 @@
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub payer: UncheckedAccount<'info>,
+}
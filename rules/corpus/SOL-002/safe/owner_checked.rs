@@ -0,0 +1,9 @@
+pub fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault_info = next_account_info(iter)?;
+    if vault_info.owner != program_id {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    Ok(())
+}
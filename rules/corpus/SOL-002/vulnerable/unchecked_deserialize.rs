@@ -0,0 +1,11 @@
+pub fn withdraw(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let vault_info = next_account_info(iter)?;
+    let authority = next_account_info(iter)?;
+
+    let vault = Vault::try_from_slice(&vault_info.data.borrow())?;
+    if vault.authority != *authority.key || !authority.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
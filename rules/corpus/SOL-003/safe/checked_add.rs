@@ -0,0 +1,4 @@
+pub fn deposit(vault: &mut Vault, amount: u64) -> Option<()> {
+    vault.total = amount.checked_add(vault.total)?;
+    Some(())
+}
@@ -0,0 +1,3 @@
+pub fn deposit(vault: &mut Vault, amount: u64) {
+    vault.total = amount + balance(vault);
+}
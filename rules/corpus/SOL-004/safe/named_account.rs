@@ -0,0 +1,4 @@
+pub fn settle(ctx: Context<Settle>) -> Result<()> {
+    let oracle = &ctx.accounts.oracle;
+    read_price(oracle)
+}
@@ -0,0 +1,4 @@
+pub fn settle(ctx: Context<Settle>) -> Result<()> {
+    let oracle = &ctx.remaining_accounts[0];
+    read_price(oracle)
+}
@@ -0,0 +1,3 @@
+pub fn signer_seeds(vault: &Vault) -> [&[u8]; 2] {
+    [b"vault", std::slice::from_ref(&vault.bump)]
+}
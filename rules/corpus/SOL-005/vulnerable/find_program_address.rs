@@ -0,0 +1,4 @@
+pub fn derive(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    let (pda, _bump) = Pubkey::find_program_address(&[b"vault", user.as_ref()], program_id);
+    pda
+}
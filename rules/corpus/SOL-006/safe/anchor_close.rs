@@ -0,0 +1,6 @@
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut, close = owner)]
+    pub position: Account<'info, Position>,
+    pub owner: Signer<'info>,
+}
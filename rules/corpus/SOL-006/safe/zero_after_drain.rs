@@ -0,0 +1,6 @@
+pub fn close_position(position: &AccountInfo, dest: &AccountInfo) -> ProgramResult {
+    let lamports = position.lamports();
+    position.sub_lamports(lamports)?;
+    position.data.borrow_mut().fill(0);
+    Ok(())
+}
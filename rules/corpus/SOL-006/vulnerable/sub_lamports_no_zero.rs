@@ -0,0 +1,8 @@
+pub fn close_position(position: &AccountInfo, dest: &AccountInfo) -> ProgramResult {
+    let lamports = position.lamports();
+    position.sub_lamports(lamports)?;
+    dest.add_lamports(lamports)?;
+
+
+    Ok(())
+}
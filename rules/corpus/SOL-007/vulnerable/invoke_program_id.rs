@@ -0,0 +1,3 @@
+pub fn forward(program_id: Pubkey, accounts: &[AccountInfo], data: Vec<u8>) -> ProgramResult {
+    invoke(&Instruction { program_id, accounts: metas(accounts), data }, accounts)
+}
@@ -0,0 +1,3 @@
+pub fn relay(accounts: &[AccountInfo], target_program: &AccountInfo, ix: Instruction) -> ProgramResult {
+    invoke(&ix_for(target_program.key, ix), accounts)
+}
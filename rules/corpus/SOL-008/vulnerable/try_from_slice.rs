@@ -0,0 +1,4 @@
+pub fn load_config(account: &AccountInfo) -> Result<Config, ProgramError> {
+    let data = account.try_borrow_data()?;
+    Config::try_from_slice(&data).map_err(|_| ProgramError::InvalidAccountData)
+}
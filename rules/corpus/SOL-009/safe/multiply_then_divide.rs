@@ -0,0 +1,3 @@
+pub fn fee(amount: u64, rate_bps: u64) -> u64 {
+    (amount * rate_bps) / BPS_DENOMINATOR
+}
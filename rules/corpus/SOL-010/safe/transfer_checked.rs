@@ -0,0 +1,3 @@
+pub fn pay_out(token_program: &Pubkey, src: &Pubkey, mint: &Pubkey, dst: &Pubkey, auth: &Pubkey, amount: u64, decimals: u8) -> Result<Instruction, ProgramError> {
+    spl_token::instruction::transfer_checked(token_program, src, mint, dst, auth, &[], amount, decimals)
+}
@@ -0,0 +1,3 @@
+pub fn pay_out(token_program: &Pubkey, src: &Pubkey, dst: &Pubkey, auth: &Pubkey, amount: u64) -> Result<Instruction, ProgramError> {
+    spl_token::instruction::transfer(token_program, src, dst, auth, &[], amount)
+}
@@ -0,0 +1,8 @@
+#[derive(Accounts)]
+pub struct CreateVault<'info> {
+    #[account(init, payer = user, space = 8 + Vault::INIT_SPACE)]
+    pub vault: Account<'info, Vault>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
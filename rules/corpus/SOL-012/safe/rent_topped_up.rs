@@ -0,0 +1,6 @@
+pub fn grow(config: &AccountInfo, payer: &AccountInfo, new_len: usize) -> ProgramResult {
+    let needed = Rent::get()?.minimum_balance(new_len);
+    top_up(payer, config, needed)?;
+    config.realloc(new_len, false)?;
+    Ok(())
+}
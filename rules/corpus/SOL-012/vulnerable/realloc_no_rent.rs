@@ -0,0 +1,4 @@
+pub fn grow(config: &AccountInfo, new_len: usize) -> ProgramResult {
+    config.realloc(new_len, false)?;
+    Ok(())
+}
@@ -0,0 +1,5 @@
+pub fn set_name(state: &mut State, name: &str) -> Result<()> {
+    require!(name.len() <= MAX_NAME_LEN, ErrorCode::NameTooLong);
+    state.name[..name.len()].copy_from_slice(name.as_bytes());
+    Ok(())
+}
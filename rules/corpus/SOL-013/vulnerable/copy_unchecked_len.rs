@@ -0,0 +1,3 @@
+pub fn set_name(state: &mut State, name: &str) {
+    state.name[..name.len()].copy_from_slice(name.as_bytes());
+}
@@ -0,0 +1,6 @@
+pub fn deposit_to_strategy(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    token::approve(ctx.accounts.approve_ctx(), amount)?;
+    strategy::cpi::pull(ctx.accounts.into_pull_ctx(), amount)?;
+    token::revoke(ctx.accounts.revoke_ctx())?;
+    Ok(())
+}
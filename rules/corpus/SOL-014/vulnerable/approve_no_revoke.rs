@@ -0,0 +1,12 @@
+pub fn deposit_to_strategy(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Approve {
+            to: ctx.accounts.vault_tokens.to_account_info(),
+            delegate: ctx.accounts.strategy.to_account_info(),
+            authority: ctx.accounts.vault_authority.to_account_info(),
+        },
+    );
+    token::approve(cpi_ctx, amount)?;
+    strategy::cpi::pull(ctx.accounts.into_pull_ctx(), amount)
+}
@@ -0,0 +1,5 @@
+pub fn enable_auto_compound(ctx: Context<Enable>, amount: u64) -> Result<()> {
+    require!(amount <= ctx.accounts.position.pending, ErrorCode::TooMuch);
+    token::approve(ctx.accounts.approve_ctx(), amount)?;
+    Ok(())
+}
@@ -0,0 +1,7 @@
+pub fn enable_auto_compound(ctx: Context<Enable>) -> Result<()> {
+    token::approve(
+        ctx.accounts.approve_ctx(),
+        u64::MAX,
+    )?;
+    Ok(())
+}
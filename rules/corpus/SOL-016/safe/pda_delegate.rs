@@ -0,0 +1,8 @@
+#[derive(Accounts)]
+pub struct Delegate<'info> {
+    pub owner: Signer<'info>,
+    #[account(seeds = [b"delegate", escrow.key().as_ref()], bump)]
+    /// CHECK: PDA, signs via seeds
+    pub delegate: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
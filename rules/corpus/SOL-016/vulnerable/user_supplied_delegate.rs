@@ -0,0 +1,10 @@
+#[derive(Accounts)]
+pub struct Delegate<'info> {
+    #[account(mut, has_one = owner)]
+    pub escrow: Account<'info, Escrow>,
+    pub owner: Signer<'info>,
+
+    /// CHECK: any delegate
+    pub delegate: UncheckedAccount<'info>,
+    pub token_program: Program<'info, Token>,
+}
@@ -0,0 +1,15 @@
+#[derive(Accounts)]
+pub struct Register<'info> {
+    #[account(init_if_needed, payer = user, space = 8 + Profile::INIT_SPACE)]
+    pub profile: Account<'info, Profile>,
+    #[account(mut)]
+    pub user: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register(ctx: Context<Register>) -> Result<()> {
+    let profile = &mut ctx.accounts.profile;
+    profile.owner = ctx.accounts.user.key();
+    profile.points = 0;
+    Ok(())
+}
@@ -0,0 +1,10 @@
+pub fn verify_signatures(accounts: &[AccountInfo]) -> ProgramResult {
+    let iter = &mut accounts.iter();
+    let ix_sysvar = next_account_info(iter)?;
+    let current = load_current_index_checked(ix_sysvar)?;
+    let secp_ix = load_instruction_at_checked((current - 1) as usize, ix_sysvar)?;
+    if secp_ix.program_id != secp256k1_program::id() {
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(())
+}
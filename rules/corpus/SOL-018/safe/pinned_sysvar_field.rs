@@ -0,0 +1,8 @@
+#[derive(Accounts)]
+pub struct Verify<'info> {
+    pub payer: Signer<'info>,
+
+    #[account(address = sysvar::instructions::ID)]
+    /// CHECK: address pinned to the instructions sysvar
+    pub instructions: AccountInfo<'info>,
+}
@@ -0,0 +1,7 @@
+pub fn process_transfer(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let treasury = &accounts[0];
+    if treasury.key != &TREASURY_ID {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
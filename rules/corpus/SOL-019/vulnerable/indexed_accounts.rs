@@ -0,0 +1,8 @@
+pub fn process_transfer(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let source = &accounts[0];
+    let destination = &accounts[1];
+
+    **source.try_borrow_mut_lamports()? -= amount;
+    **destination.try_borrow_mut_lamports()? += amount;
+    Ok(())
+}
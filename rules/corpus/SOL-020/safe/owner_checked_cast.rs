@@ -0,0 +1,11 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub fn process_deposit(vault_info: &AccountInfo, amount: u64) -> ProgramResult {
+    let mut data = vault_info.try_borrow_mut_data()?;
+    if !vault_info.is_owned_by(&crate::ID) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let vault = Vault::from_bytes_mut(&mut data);
+    vault.balance = vault.balance.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+    Ok(())
+}
@@ -0,0 +1,14 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+};
+
+pub fn process_close(accounts: &[AccountInfo]) -> ProgramResult {
+    let account_iter = &mut accounts.iter();
+
+    let escrow = next_account_info(account_iter)?;
+
+    let destination = next_account_info(account_iter)?;
+
+    transfer_all_lamports(escrow, destination)
+}
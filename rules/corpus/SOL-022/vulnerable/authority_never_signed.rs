@@ -0,0 +1,12 @@
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pub fn process_withdraw(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let [vault, authority, destination] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    let state = Vault::load(vault)?;
+    if state.authority != *authority.key() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    move_lamports(vault, destination, amount)
+}
@@ -0,0 +1,9 @@
+use solana_sdk::signature::{Keypair, read_keypair_file};
+
+pub fn admin(path: &str) -> Keypair {
+    read_keypair_file(path).expect("admin keypair")
+}
+
+pub fn from_env(bytes: &[u8]) -> Keypair {
+    Keypair::from_bytes(bytes).unwrap()
+}
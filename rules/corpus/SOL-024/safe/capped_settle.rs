@@ -0,0 +1,14 @@
+use anchor_lang::prelude::*;
+
+const MAX_POSITIONS: usize = 8;
+
+pub fn settle_all(ctx: Context<Settle>) -> Result<()> {
+    require!(
+        ctx.remaining_accounts.len() <= MAX_POSITIONS,
+        ErrorCode::TooManyAccounts
+    );
+    for position in ctx.remaining_accounts.iter() {
+        settle_position(position)?;
+    }
+    Ok(())
+}
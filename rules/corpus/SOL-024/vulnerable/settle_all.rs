@@ -0,0 +1,8 @@
+use anchor_lang::prelude::*;
+
+pub fn settle_all(ctx: Context<Settle>) -> Result<()> {
+    for position in ctx.remaining_accounts.iter() {
+        settle_position(position)?;
+    }
+    Ok(())
+}
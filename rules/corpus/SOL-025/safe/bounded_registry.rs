@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_MEMBERS: usize = 64;
+
+pub fn join(ctx: Context<Join>) -> Result<()> {
+    let registry = &mut ctx.accounts.registry;
+    require!(registry.members.len() < MAX_MEMBERS, ErrorCode::RegistryFull);
+    registry.members.push(ctx.accounts.user.key());
+    Ok(())
+}
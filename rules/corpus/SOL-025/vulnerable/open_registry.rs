@@ -0,0 +1,7 @@
+use anchor_lang::prelude::*;
+
+pub fn join(ctx: Context<Join>) -> Result<()> {
+    let member = ctx.accounts.user.key();
+    ctx.accounts.registry.members.push(member);
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use solana_program::{instruction::Instruction, program::invoke};
+
+pub fn forward(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let ix = Instruction {
+        program_id: spl_token::ID,
+        accounts: metas(accounts),
+        data: data.to_vec(),
+    };
+    invoke(&ix, accounts)
+}
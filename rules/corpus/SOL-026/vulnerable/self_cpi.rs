@@ -0,0 +1,10 @@
+use solana_program::{instruction::Instruction, program::invoke};
+
+pub fn forward(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let ix = Instruction {
+        program_id: *program_id,
+        accounts: metas(accounts),
+        data: data.to_vec(),
+    };
+    invoke(&ix, accounts)
+}
@@ -0,0 +1,8 @@
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    pub pool: Account<'info, Pool>,
+    #[account(mut, constraint = user_token.mint == pool.mint @ ErrorCode::WrongMint)]
+    pub user_token: Account<'info, TokenAccount>,
+}
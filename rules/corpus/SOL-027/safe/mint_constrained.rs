@@ -0,0 +1,15 @@
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, has_one = mint)]
+    pub pool: Account<'info, Pool>,
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = user,
+    )]
+    pub user_token: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+}
@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut, has_one = vault)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub user_token: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+}
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(has_one = vault)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+}
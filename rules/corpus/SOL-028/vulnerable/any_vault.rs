@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    pub pool: Account<'info, Pool>,
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub pool_vault: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+}
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+    )]
+    pub user_ata: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+}
@@ -0,0 +1,13 @@
+use solana_program::account_info::{AccountInfo, next_account_info};
+use spl_associated_token_account::get_associated_token_address;
+
+pub fn claim(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let mint = next_account_info(accounts_iter)?;
+    let user_ata = next_account_info(accounts_iter)?;
+    if *user_ata.key != get_associated_token_address(user.key, mint.key) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    transfer_from_vault(user_ata, amount)
+}
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    pub mint: Account<'info, Mint>,
+    #[account(mut, token::mint = mint)]
+    pub user_ata: Account<'info, TokenAccount>,
+    pub user: Signer<'info>,
+}
@@ -0,0 +1,8 @@
+use solana_program::account_info::{AccountInfo, next_account_info};
+
+pub fn claim(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let accounts_iter = &mut accounts.iter();
+    let user = next_account_info(accounts_iter)?;
+    let user_ata = next_account_info(accounts_iter)?;
+    transfer_from_vault(user_ata, amount)
+}
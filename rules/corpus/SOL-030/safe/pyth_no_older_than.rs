@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+pub fn borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    let price = ctx
+        .accounts
+        .price_update
+        .get_price_no_older_than(&clock, MAX_AGE, &FEED_ID)?;
+    let max_borrow = collateral_value(&ctx.accounts.obligation, price.price, price.conf);
+    require!(amount <= max_borrow, LendingError::BorrowTooLarge);
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+use switchboard_solana::AggregatorAccountData;
+
+pub fn settle(ctx: Context<Settle>) -> Result<()> {
+    let feed = ctx.accounts.aggregator.load()?;
+    feed.check_staleness(Clock::get()?.unix_timestamp, 300)?;
+    let result = feed.get_result()?;
+    ctx.accounts.market.settle_price = result.try_into()?;
+    Ok(())
+}
@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+use pyth_sdk_solana::load_price_feed_from_account_info;
+
+pub fn borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
+    let feed = load_price_feed_from_account_info(&ctx.accounts.price_feed).unwrap();
+    let price = feed.get_price_unchecked();
+    let max_borrow = collateral_value(&ctx.accounts.obligation, price.price, price.conf);
+    require!(amount <= max_borrow, LendingError::BorrowTooLarge);
+    Ok(())
+}
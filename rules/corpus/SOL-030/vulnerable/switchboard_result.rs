@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+use switchboard_solana::AggregatorAccountData;
+
+pub fn settle(ctx: Context<Settle>) -> Result<()> {
+    let feed = ctx.accounts.aggregator.load()?;
+    let result = feed.get_result()?;
+    feed.check_confidence_interval(SwitchboardDecimal::from_f64(0.8))?;
+    ctx.accounts.market.settle_price = result.try_into()?;
+    Ok(())
+}
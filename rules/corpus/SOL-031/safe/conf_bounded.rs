@@ -0,0 +1,17 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+pub fn liquidate(ctx: Context<Liquidate>) -> Result<()> {
+    let clock = Clock::get()?;
+    let price = ctx
+        .accounts
+        .price_update
+        .get_price_no_older_than(&clock, MAX_AGE, &FEED_ID)?;
+    require!(
+        price.conf * 10_000 <= price.price as u64 * MAX_CONF_BPS,
+        LendingError::PriceTooUncertain
+    );
+    let collateral = ctx.accounts.obligation.collateral_amount as i64 * (price.price - price.conf as i64);
+    require!(collateral < ctx.accounts.obligation.debt_value(), LendingError::Healthy);
+    Ok(())
+}
@@ -0,0 +1,13 @@
+use anchor_lang::prelude::*;
+use pyth_solana_receiver_sdk::price_update::PriceUpdateV2;
+
+pub fn liquidate(ctx: Context<Liquidate>) -> Result<()> {
+    let clock = Clock::get()?;
+    let price = ctx
+        .accounts
+        .price_update
+        .get_price_no_older_than(&clock, MAX_AGE, &FEED_ID)?;
+    let collateral = ctx.accounts.obligation.collateral_amount as i64 * price.price;
+    require!(collateral < ctx.accounts.obligation.debt_value(), LendingError::Healthy);
+    Ok(())
+}
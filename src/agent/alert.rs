@@ -0,0 +1,271 @@
+//! Immediate alerts for confirmed Critical findings. A full run can take
+//! hours; these fire from Phase 3 as soon as validation confirms a Critical,
+//! instead of waiting for the report.
+//!
+//! Delivery is best-effort: a failing webhook or mailer is logged and the run
+//! carries on.
+
+use crate::config::AlertConfig;
+use crate::http::HttpClient;
+use crate::security::{SecurityFinding, ValidationStatus};
+use serde::Serialize;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+/// Prefix of the stdout marker line, for grepping logs and CI output.
+pub const MARKER: &str = "SOLGUARD-ALERT";
+
+/// Lines of code shown either side of the flagged line.
+const EVIDENCE_CONTEXT: usize = 2;
+
+/// One confirmed Critical, as sent to every channel.
+#[derive(Debug, Serialize)]
+pub struct Alert {
+    /// One-line summary; Slack/Discord-style webhooks display this field.
+    pub text: String,
+    pub repo: String,
+    pub title: String,
+    pub severity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub file: String,
+    pub line: usize,
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<String>,
+    /// Source lines around the finding, `>` marking the flagged one.
+    pub evidence: String,
+}
+
+impl Alert {
+    pub fn new(repo: &str, repo_path: &Path, finding: &SecurityFinding) -> Self {
+        let file = crate::paths::to_slash(&finding.file_path);
+        Self {
+            text: format!(
+                "Confirmed {} in {repo}: {} ({file}:{})",
+                finding.severity, finding.title, finding.line_number
+            ),
+            repo: repo.to_string(),
+            title: finding.title.clone(),
+            severity: finding.severity.clone(),
+            id: finding.id.clone(),
+            file,
+            line: finding.line_number,
+            description: finding.description.clone(),
+            reasoning: finding.validation_reasoning.clone(),
+            evidence: evidence_snippet(repo_path, finding).unwrap_or_default(),
+        }
+    }
+
+    fn email(&self, to: &[String]) -> String {
+        format!(
+            "To: {}\nSubject: [SolGuard] {}\nContent-Type: text/plain; charset=utf-8\n\n{}\n\n{}\n\n{}\n{}\n",
+            to.join(", "),
+            self.text,
+            self.description,
+            self.reasoning.as_deref().unwrap_or(""),
+            format_args!("{}:{}", self.file, self.line),
+            self.evidence,
+        )
+    }
+}
+
+/// Whether a finding warrants an immediate alert.
+pub fn is_alertable(finding: &SecurityFinding) -> bool {
+    finding.validation_status == ValidationStatus::Confirmed
+        && crate::severity::normalize(&finding.severity) == "Critical"
+}
+
+/// Sends alerts to whichever channels `[alerts]` enables.
+pub struct Alerter<'a> {
+    config: &'a AlertConfig,
+    http: &'a HttpClient,
+    /// The report itself is going to stdout; put markers on stderr instead.
+    marker_to_stderr: bool,
+    sent: usize,
+}
+
+impl<'a> Alerter<'a> {
+    pub fn new(config: &'a AlertConfig, http: &'a HttpClient, marker_to_stderr: bool) -> Self {
+        Self {
+            config,
+            http,
+            marker_to_stderr,
+            sent: 0,
+        }
+    }
+
+    /// Alert on every confirmed Critical in a freshly validated repo.
+    pub async fn notify(&mut self, repo: &str, repo_path: &Path, findings: &[SecurityFinding]) {
+        for finding in findings.iter().filter(|f| is_alertable(f)) {
+            self.send(&Alert::new(repo, repo_path, finding)).await;
+        }
+    }
+
+    /// Number of alerts raised so far.
+    pub fn sent(&self) -> usize {
+        self.sent
+    }
+
+    async fn send(&mut self, alert: &Alert) {
+        self.sent += 1;
+        info!(repo = %alert.repo, title = %alert.title, "confirmed critical finding");
+        if self.config.stdout {
+            let line = format!("{MARKER} {}", alert.text);
+            if self.marker_to_stderr {
+                eprintln!("{line}");
+            } else {
+                println!("{line}");
+            }
+        }
+        if let Some(url) = &self.config.webhook_url {
+            let result = match serde_json::to_string(alert) {
+                Ok(body) => self.http.post_json_raw(url, &body, &[]).await.map(|_| ()),
+                Err(e) => Err(crate::error::Error::parse(e.to_string())),
+            };
+            if let Err(e) = result {
+                warn!(error = %e, "alert webhook failed");
+            }
+        }
+        if !self.config.email_to.is_empty()
+            && let Err(e) =
+                sendmail(&self.config.sendmail, &alert.email(&self.config.email_to)).await
+        {
+            warn!(error = %e, mailer = %self.config.sendmail, "alert email failed");
+        }
+    }
+}
+
+/// Hand a complete message (headers included) to `sendmail -t`.
+async fn sendmail(program: &str, message: &str) -> std::io::Result<()> {
+    let mut child = tokio::process::Command::new(program)
+        .arg("-t")
+        .stdin(std::process::Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(message.as_bytes()).await?;
+    }
+    let status = child.wait().await?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(format!(
+            "{program} exited with {status}"
+        )))
+    }
+}
+
+/// The flagged line with a little context. Finding paths are either relative
+/// to the repo or already include it.
+fn evidence_snippet(repo_path: &Path, finding: &SecurityFinding) -> Option<String> {
+    let content = std::fs::read_to_string(repo_path.join(&finding.file_path))
+        .or_else(|_| std::fs::read_to_string(&finding.file_path))
+        .ok()?;
+    let line = finding.line_number.max(1);
+    let start = line.saturating_sub(EVIDENCE_CONTEXT).max(1);
+    let snippet: Vec<String> = content
+        .lines()
+        .enumerate()
+        .skip(start - 1)
+        .take(line + EVIDENCE_CONTEXT + 1 - start)
+        .map(|(i, text)| {
+            let marker = if i + 1 == line { '>' } else { ' ' };
+            format!("{marker}{:>5} | {text}", i + 1)
+        })
+        .collect();
+    (!snippet.is_empty()).then(|| snippet.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn finding(severity: &str, status: ValidationStatus) -> SecurityFinding {
+        SecurityFinding {
+            title: "Unchecked withdraw authority".into(),
+            severity: severity.into(),
+            description: "Anyone can drain the vault.".into(),
+            file_path: PathBuf::from("src/lib.rs"),
+            line_number: 4,
+            validation_status: status,
+            validation_reasoning: Some("No signer check on `authority`.".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn only_confirmed_criticals_alert() {
+        assert!(is_alertable(&finding(
+            "Critical",
+            ValidationStatus::Confirmed
+        )));
+        assert!(is_alertable(&finding(
+            "CRITICAL",
+            ValidationStatus::Confirmed
+        )));
+        assert!(!is_alertable(&finding("High", ValidationStatus::Confirmed)));
+        assert!(!is_alertable(&finding(
+            "Critical",
+            ValidationStatus::Unvalidated
+        )));
+        assert!(!is_alertable(&finding(
+            "Critical",
+            ValidationStatus::Disputed
+        )));
+    }
+
+    #[test]
+    fn alert_carries_evidence_from_repo_relative_path() {
+        let repo = std::env::temp_dir().join("solguard-alert-evidence");
+        let _ = std::fs::remove_dir_all(&repo);
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        std::fs::write(
+            repo.join("src/lib.rs"),
+            "use anchor_lang::prelude::*;\n\npub fn withdraw(ctx: Context<W>) -> Result<()> {\n    transfer(ctx.accounts.vault, amount)?;\n    Ok(())\n}\n",
+        )
+        .unwrap();
+
+        let alert = Alert::new(
+            "vault",
+            &repo,
+            &finding("Critical", ValidationStatus::Confirmed),
+        );
+        assert_eq!(
+            alert.text,
+            "Confirmed Critical in vault: Unchecked withdraw authority (src/lib.rs:4)"
+        );
+        let lines: Vec<&str> = alert.evidence.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert!(lines[0].starts_with("     2 |"));
+        assert_eq!(
+            lines[2],
+            ">    4 |     transfer(ctx.accounts.vault, amount)?;"
+        );
+        assert!(lines[4].starts_with("     6 | }"));
+
+        let json: serde_json::Value = serde_json::to_value(&alert).unwrap();
+        assert_eq!(json["repo"], "vault");
+        assert_eq!(json["line"], 4);
+        assert!(json.get("id").is_none());
+
+        let email = alert.email(&["sec@example.com".into()]);
+        assert!(email.starts_with("To: sec@example.com\nSubject: [SolGuard] Confirmed Critical"));
+        assert!(email.contains("No signer check"));
+        std::fs::remove_dir_all(repo).unwrap();
+    }
+
+    #[test]
+    fn missing_source_gives_empty_evidence() {
+        let alert = Alert::new(
+            "vault",
+            Path::new("/nonexistent"),
+            &SecurityFinding {
+                file_path: PathBuf::from("programs/gone/src/lib.rs"),
+                ..finding("Critical", ValidationStatus::Confirmed)
+            },
+        );
+        assert!(alert.evidence.is_empty());
+    }
+}
@@ -0,0 +1,294 @@
+//! Startup capability check: which pipeline phases can run with the
+//! credentials present, and how the run degrades when some are missing.
+
+use super::PipelineOptions;
+use crate::LlmOverride;
+use crate::config::{Config, ModelConfig, ModelsConfig};
+use crate::llm::{Provider, TaskKind};
+use std::collections::HashMap;
+
+/// Every task kind a [`crate::llm::ModelRouter`] routes.
+const TASKS: [TaskKind; 8] = [
+    TaskKind::NarrativeSynthesis,
+    TaskKind::NarrativeDiscovery,
+    TaskKind::DeepInvestigation,
+    TaskKind::Validation,
+    TaskKind::CrossReference,
+    TaskKind::Triage,
+    TaskKind::Summary,
+    TaskKind::Translation,
+];
+
+/// Credentials available to this run.
+#[derive(Debug, Clone, Default)]
+pub struct Capabilities {
+    /// A GitHub token is configured (`GITHUB_TOKEN` or `github.token`).
+    pub github: bool,
+    /// LLM key env vars required by `[llm]` / `[models]` that are unset.
+    pub missing_llm_keys: Vec<String>,
+    /// The unset key of the client each task kind is routed to.
+    missing_by_task: HashMap<TaskKind, String>,
+}
+
+/// The `[models]` entry `kind` is routed to, following
+/// [`TaskKind::fallback`] like the router does.
+fn routed(models: &ModelsConfig, kind: TaskKind) -> Option<&ModelConfig> {
+    let entry = |kind| match kind {
+        TaskKind::NarrativeSynthesis => &models.narrative,
+        TaskKind::NarrativeDiscovery => &models.discovery,
+        TaskKind::DeepInvestigation => &models.investigation,
+        TaskKind::Validation => &models.validation,
+        TaskKind::CrossReference => &models.cross_reference,
+        TaskKind::Triage => &models.triage,
+        TaskKind::Summary => &models.summary,
+        TaskKind::Translation => &models.translation,
+    };
+    entry(kind)
+        .as_ref()
+        .or_else(|| kind.fallback().and_then(|k| entry(k).as_ref()))
+}
+
+impl Capabilities {
+    pub fn detect(cfg: &Config, llm_override: Option<&LlmOverride>) -> Self {
+        let required = TASKS
+            .into_iter()
+            .map(|kind| {
+                let routed = cfg.models.as_ref().and_then(|m| routed(m, kind));
+                match (llm_override, routed) {
+                    (Some(ov), _) => (kind, ov.provider.clone(), None),
+                    (None, Some(mc)) => (kind, mc.provider.clone(), mc.api_key_env.clone()),
+                    (None, None) => (kind, cfg.llm.provider.clone(), cfg.llm.api_key_env.clone()),
+                }
+            })
+            .collect();
+        Self::from_env(!cfg.github.token.is_empty(), required, |var| {
+            std::env::var(var).is_ok_and(|v| !v.is_empty())
+        })
+    }
+
+    fn from_env(
+        github: bool,
+        required: Vec<(TaskKind, Provider, Option<String>)>,
+        is_set: impl Fn(&str) -> bool,
+    ) -> Self {
+        let mut missing_llm_keys = Vec::new();
+        let mut missing_by_task = HashMap::new();
+        for (kind, provider, env) in required {
+            if matches!(provider, Provider::ClaudeCode) {
+                continue;
+            }
+            let var = env.unwrap_or_else(|| provider.default_api_key_env().into());
+            if is_set(&var) {
+                continue;
+            }
+            if !missing_llm_keys.contains(&var) {
+                missing_llm_keys.push(var.clone());
+            }
+            missing_by_task.insert(kind, var);
+        }
+        Self {
+            github,
+            missing_llm_keys,
+            missing_by_task,
+        }
+    }
+
+    pub fn llm(&self) -> bool {
+        self.missing_llm_keys.is_empty()
+    }
+
+    /// The unset keys of the clients `kinds` are routed to, if any.
+    fn missing_for(&self, kinds: &[TaskKind]) -> Option<String> {
+        let mut missing: Vec<&str> = Vec::new();
+        for var in kinds.iter().filter_map(|k| self.missing_by_task.get(k)) {
+            if !missing.contains(&var.as_str()) {
+                missing.push(var);
+            }
+        }
+        (!missing.is_empty()).then(|| missing.join(", "))
+    }
+
+    /// Turn off every phase whose routed client has no key, recording why in
+    /// `opts.degraded` for the report banner.
+    pub fn degrade(&self, opts: &mut PipelineOptions) {
+        let narrative_llm =
+            self.missing_for(&[TaskKind::NarrativeSynthesis, TaskKind::NarrativeDiscovery]);
+        if opts.narratives_from.is_none() && !(self.github && narrative_llm.is_none()) {
+            let mut missing = Vec::new();
+            if !self.github {
+                missing.push("GITHUB_TOKEN".to_string());
+            }
+            missing.extend(narrative_llm);
+            opts.skip_narratives = true;
+            opts.degraded.push(format!(
+                "Narrative detection skipped (missing {}): only configured always_scan targets were scanned.",
+                missing.join(", ")
+            ));
+        }
+        if opts.deep {
+            if let Some(missing) = self.missing_for(&[TaskKind::DeepInvestigation]) {
+                opts.deep = false;
+                opts.degraded.push(format!(
+                    "Deep agent review and validation skipped (missing {missing}): static analysis only."
+                ));
+            } else if !opts.skip_validation
+                && let Some(missing) = self.missing_for(&[TaskKind::Validation])
+            {
+                opts.skip_validation = true;
+                opts.degraded.push(format!(
+                    "Validation skipped (missing {missing}): deep findings are unvalidated."
+                ));
+            }
+        }
+        if !opts.skip_cross_ref
+            && let Some(missing) = self.missing_for(&[TaskKind::CrossReference])
+        {
+            opts.skip_cross_ref = true;
+            opts.degraded.push(format!(
+                "Cross-reference summaries skipped (missing {missing}): risk scores are deterministic."
+            ));
+        }
+    }
+
+    /// One `(phase, status)` row per pipeline phase for the given options.
+    pub fn phase_matrix(&self, opts: &PipelineOptions) -> Vec<(&'static str, String)> {
+        let narratives = if opts.narratives_from.is_some() {
+            "from file"
+        } else if opts.skip_narratives {
+            "SKIPPED"
+        } else {
+            "run"
+        };
+        let targets = if opts.skip_narratives {
+            "always_scan only"
+        } else {
+            "run"
+        };
+        let deep = if opts.deep { "run" } else { "SKIPPED" };
+        let validation = if opts.deep && !opts.skip_validation {
+            "run"
+        } else {
+            "SKIPPED"
+        };
+        let cross_ref = if opts.skip_cross_ref {
+            "scoring only"
+        } else {
+            "run"
+        };
+        vec![
+            ("Narrative detection", narratives.into()),
+            ("Target selection", targets.into()),
+            ("Static scan", "run".into()),
+            ("Deep agent review", deep.into()),
+            ("Validation", validation.into()),
+            ("Cross-reference", cross_ref.into()),
+            ("Report", "run".into()),
+        ]
+    }
+
+    /// Human-readable credential + phase matrix for stderr.
+    pub fn render_matrix(&self, opts: &PipelineOptions) -> String {
+        let status = |ok: bool| if ok { "set" } else { "MISSING" };
+        let mut out = String::from("Capability check:\n");
+        out.push_str(&format!(
+            "  {:<22} {}\n",
+            "GITHUB_TOKEN",
+            status(self.github)
+        ));
+        let llm_label = if self.llm() {
+            "LLM keys".to_string()
+        } else {
+            format!("LLM keys ({})", self.missing_llm_keys.join(", "))
+        };
+        out.push_str(&format!("  {:<22} {}\n", llm_label, status(self.llm())));
+        out.push_str("Phases:\n");
+        for (phase, state) in self.phase_matrix(opts) {
+            out.push_str(&format!("  {phase:<22} {state}\n"));
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn caps(github: bool, set: &[&str]) -> Capabilities {
+        let set: Vec<String> = set.iter().map(|s| s.to_string()).collect();
+        Capabilities::from_env(
+            github,
+            TASKS
+                .into_iter()
+                .map(|kind| match kind {
+                    TaskKind::CrossReference => (kind, Provider::Groq, None),
+                    TaskKind::Summary => (kind, Provider::ClaudeCode, None),
+                    _ => (kind, Provider::OpenRouter, None),
+                })
+                .collect(),
+            |v| set.iter().any(|s| s == v),
+        )
+    }
+
+    #[test]
+    fn missing_keys_detected() {
+        let c = caps(true, &["OPENROUTER_API_KEY"]);
+        assert_eq!(c.missing_llm_keys, vec!["GROQ_API_KEY"]);
+        assert!(!c.llm());
+        assert!(caps(true, &["OPENROUTER_API_KEY", "GROQ_API_KEY"]).llm());
+    }
+
+    #[test]
+    fn no_llm_key_degrades_to_static_only() {
+        let mut opts = PipelineOptions {
+            deep: true,
+            ..Default::default()
+        };
+        caps(true, &[]).degrade(&mut opts);
+        assert!(opts.skip_narratives);
+        assert!(!opts.deep);
+        assert!(opts.skip_cross_ref);
+        assert_eq!(opts.degraded.len(), 3);
+        assert!(opts.degraded[0].contains("OPENROUTER_API_KEY"));
+    }
+
+    #[test]
+    fn missing_github_token_only_skips_narratives() {
+        let mut opts = PipelineOptions {
+            deep: true,
+            ..Default::default()
+        };
+        let c = caps(false, &["OPENROUTER_API_KEY", "GROQ_API_KEY"]);
+        c.degrade(&mut opts);
+        assert!(opts.skip_narratives && opts.deep && !opts.skip_cross_ref);
+        assert_eq!(opts.degraded.len(), 1);
+        assert!(
+            c.render_matrix(&opts)
+                .contains("GITHUB_TOKEN           MISSING")
+        );
+    }
+
+    #[test]
+    fn missing_provider_only_skips_its_phase() {
+        let mut opts = PipelineOptions {
+            deep: true,
+            ..Default::default()
+        };
+        caps(true, &["OPENROUTER_API_KEY"]).degrade(&mut opts);
+        assert!(!opts.skip_narratives && opts.deep && !opts.skip_validation);
+        assert!(opts.skip_cross_ref);
+        assert_eq!(opts.degraded.len(), 1);
+        assert!(opts.degraded[0].contains("GROQ_API_KEY"));
+    }
+
+    #[test]
+    fn full_credentials_run_everything() {
+        let mut opts = PipelineOptions {
+            deep: true,
+            ..Default::default()
+        };
+        let c = caps(true, &["OPENROUTER_API_KEY", "GROQ_API_KEY"]);
+        c.degrade(&mut opts);
+        assert!(opts.degraded.is_empty());
+        assert!(c.phase_matrix(&opts).iter().all(|(_, s)| s == "run"));
+    }
+}
@@ -3,8 +3,7 @@ use crate::narrative::Narrative;
 use crate::security::{SecurityFinding, ValidationStatus};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
 use tracing::info;
 
 #[allow(dead_code)] // available for downstream consumers
@@ -34,6 +33,23 @@ pub async fn analyze(
     narratives: &mut [Narrative],
     findings: &[SecurityFinding],
     router: &ModelRouter,
+) -> Result<Vec<NarrativeFindingLink>> {
+    link_and_score(narratives, findings, Some(router)).await
+}
+
+/// Deterministic cross-reference only: link and score without the LLM
+/// relevance summaries (`run --skip-cross-ref`).
+pub async fn analyze_offline(
+    narratives: &mut [Narrative],
+    findings: &[SecurityFinding],
+) -> Result<Vec<NarrativeFindingLink>> {
+    link_and_score(narratives, findings, None).await
+}
+
+async fn link_and_score(
+    narratives: &mut [Narrative],
+    findings: &[SecurityFinding],
+    router: Option<&ModelRouter>,
 ) -> Result<Vec<NarrativeFindingLink>> {
     info!(
         narratives = narratives.len(),
@@ -44,43 +60,17 @@ pub async fn analyze(
     let mut all_links = Vec::new();
 
     for (ni, narrative) in narratives.iter_mut().enumerate() {
-        // Tail repo names from active_repos (e.g. "owner/repo" -> "repo")
-        let repo_tails: Vec<&str> = narrative
-            .active_repos
+        let terms = risk_terms(narrative, findings);
+        let risk_score: f64 = terms.iter().map(RiskTerm::contribution).sum();
+        let matched: Vec<(usize, String)> = terms
             .iter()
-            .filter_map(|ar| ar.split('/').next_back())
+            .map(|t| (t.finding_idx, t.repo.clone()))
             .collect();
 
-        // Match findings to this narrative by repo name
-        let mut matched: Vec<(usize, String)> = Vec::new(); // (finding_idx, repo_name)
-        for (fi, finding) in findings.iter().enumerate() {
-            let repo = repo_name_from_path(&finding.file_path);
-            if repo_tails.iter().any(|tail| *tail == repo) {
-                matched.push((fi, repo));
-            }
-        }
-
-        // Risk score computation
-        let mut risk_score = 0.0f64;
-
         // Aggregate per-repo finding indices
         let mut repo_finding_map: HashMap<String, Vec<usize>> = HashMap::new();
-
-        for &(fi, ref repo) in &matched {
-            let finding = &findings[fi];
-
-            let severity_weight = match finding.severity.as_str() {
-                "Critical" => 10.0,
-                "High" => 5.0,
-                "Medium" => 2.0,
-                "Low" => 0.5,
-                _ => 0.0,
-            };
-
-            let vm = validation_multiplier(&finding.validation_status);
-            risk_score += severity_weight * vm * narrative.confidence;
-
-            repo_finding_map.entry(repo.clone()).or_default().push(fi);
+        for (fi, repo) in &matched {
+            repo_finding_map.entry(repo.clone()).or_default().push(*fi);
         }
 
         let risk_level = if risk_score >= 20.0 {
@@ -104,10 +94,11 @@ pub async fn analyze(
         narrative.repo_findings = repo_findings.clone();
 
         // Try LLM relevance summary if CrossReference client is configured
-        let llm_summary = if !matched.is_empty() {
-            try_llm_relevance(narrative, &matched, findings, router).await
-        } else {
-            None
+        let llm_summary = match router {
+            Some(router) if !matched.is_empty() => {
+                try_llm_relevance(narrative, &matched, findings, router).await
+            }
+            _ => None,
         };
 
         // Build links
@@ -141,6 +132,65 @@ pub async fn analyze(
     Ok(all_links)
 }
 
+/// One linked finding's share of a narrative's risk score. The score is the
+/// sum of [`RiskTerm::contribution`] over the narrative's terms.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RiskTerm {
+    pub finding_idx: usize,
+    /// Repo the finding was matched through.
+    pub repo: String,
+    pub severity_weight: f64,
+    pub validation_multiplier: f64,
+    pub template_multiplier: f64,
+    /// The narrative's confidence, applied to every term.
+    pub confidence: f64,
+}
+
+impl RiskTerm {
+    pub fn contribution(&self) -> f64 {
+        self.severity_weight
+            * self.validation_multiplier
+            * self.template_multiplier
+            * self.confidence
+    }
+}
+
+/// Match findings to `narrative` by repo name and break down what each adds
+/// to its risk score. A template-level finding matches through any of its
+/// forks but is counted once, weighted by how many forks the narrative has.
+pub fn risk_terms(narrative: &Narrative, findings: &[SecurityFinding]) -> Vec<RiskTerm> {
+    // Tail repo names from active_repos (e.g. "owner/repo" -> "repo")
+    let repo_tails: Vec<&str> = narrative
+        .active_repos
+        .iter()
+        .map(|ar| crate::paths::target_name(ar))
+        .collect();
+
+    let mut terms = Vec::new();
+    for (fi, finding) in findings.iter().enumerate() {
+        let candidates = if finding.forks.is_empty() {
+            vec![crate::paths::repo_name(&finding.file_path)]
+        } else {
+            finding.forks.clone()
+        };
+        let hits: Vec<String> = candidates
+            .into_iter()
+            .filter(|repo| repo_tails.iter().any(|tail| tail == repo))
+            .collect();
+        if let Some(repo) = hits.first() {
+            terms.push(RiskTerm {
+                finding_idx: fi,
+                repo: repo.clone(),
+                severity_weight: severity_weight(&finding.severity),
+                validation_multiplier: validation_multiplier(&finding.validation_status),
+                template_multiplier: template_multiplier(hits.len()),
+                confidence: narrative.confidence,
+            });
+        }
+    }
+    terms
+}
+
 /// Attempt a single LLM call per narrative for relevance summary.
 /// Returns None if no CrossReference client is configured or the call fails.
 async fn try_llm_relevance(
@@ -188,6 +238,159 @@ async fn try_llm_relevance(
     }
 }
 
+/// A root cause shared by findings that no narrative claimed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OrphanTheme {
+    pub theme: String,
+    pub summary: String,
+    /// Indices into the findings the themes were built from.
+    pub findings: Vec<usize>,
+}
+
+/// Below this many orphans the flat list is already readable.
+const MIN_ORPHANS_TO_CLUSTER: usize = 6;
+/// Orphans listed in the clustering prompt, most severe first.
+const MAX_ORPHANS_IN_PROMPT: usize = 80;
+const MAX_THEMES: usize = 5;
+
+/// Indices of findings linked to no narrative, the report's "Unlinked
+/// Findings".
+pub fn orphan_indices(narratives: &[Narrative], finding_count: usize) -> Vec<usize> {
+    let linked: HashSet<usize> = narratives
+        .iter()
+        .flat_map(|n| n.repo_findings.iter().flat_map(|(_, v)| v.iter().copied()))
+        .collect();
+    (0..finding_count).filter(|i| !linked.contains(i)).collect()
+}
+
+/// Record on each finding's provenance the first (riskiest, once sorted)
+/// narrative linking it. Unlinked findings keep no narrative.
+pub fn record_provenance(narratives: &[Narrative], findings: &mut [SecurityFinding]) {
+    for (n_idx, n) in narratives.iter().enumerate().rev() {
+        for &i in n.repo_findings.iter().flat_map(|(_, v)| v) {
+            if let Some(p) = findings.get_mut(i).and_then(|f| f.provenance.as_mut()) {
+                p.narrative_index = Some(n_idx);
+                p.narrative = Some(n.title.clone());
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ThemeResponse {
+    themes: Vec<ThemeEntry>,
+}
+
+#[derive(Deserialize)]
+struct ThemeEntry {
+    theme: String,
+    #[serde(default)]
+    summary: String,
+    /// 1-based numbers from the prompt's list.
+    findings: Vec<usize>,
+}
+
+/// Group the orphan findings into 3-5 root-cause themes with one
+/// CrossReference call. Empty when there are too few orphans to bother or
+/// the call fails; the report then shows the flat list alone.
+pub async fn cluster_orphans(
+    narratives: &[Narrative],
+    findings: &[SecurityFinding],
+    router: &ModelRouter,
+) -> Vec<OrphanTheme> {
+    let mut orphans = orphan_indices(narratives, findings.len());
+    if orphans.len() < MIN_ORPHANS_TO_CLUSTER {
+        return Vec::new();
+    }
+    orphans.sort_by_key(|&i| crate::severity::rank(&findings[i].severity));
+    orphans.truncate(MAX_ORPHANS_IN_PROMPT);
+
+    let listing: Vec<String> = orphans
+        .iter()
+        .enumerate()
+        .map(|(n, &fi)| {
+            let f = &findings[fi];
+            format!(
+                "{}. [{}] {} ({}): {}",
+                n + 1,
+                f.severity,
+                f.title,
+                crate::paths::repo_name(&f.file_path),
+                f.description.lines().next().unwrap_or_default()
+            )
+        })
+        .collect();
+    let prompt = format!(
+        "Security findings not linked to any ecosystem narrative:\n{}\n\n\
+         Group them into 3-5 root-cause themes (e.g. \"accounts trusted without owner checks\"). \
+         A finding belongs to at most one theme; leave out findings that fit none. \
+         Don't quote file paths or code. Respond with JSON only: \
+         {{\"themes\": [{{\"theme\": \"short name\", \"summary\": \"one sentence on the shared cause \
+         and fix\", \"findings\": [1, 4]}}]}}",
+        listing.join("\n")
+    );
+    let client = router.client_for(TaskKind::CrossReference);
+    match client
+        .complete_json::<ThemeResponse>(
+            "You are a Solana security analyst. Group findings by root cause.",
+            &prompt,
+        )
+        .await
+    {
+        Ok(response) => {
+            let themes = themes_from(response, &orphans);
+            info!(
+                orphans = orphans.len(),
+                themes = themes.len(),
+                "cross-reference: orphan findings clustered"
+            );
+            themes
+        }
+        Err(e) => {
+            tracing::debug!(error = %e, "orphan clustering failed, report keeps the flat list");
+            Vec::new()
+        }
+    }
+}
+
+/// Map the response's finding numbers back to indices, keeping each finding
+/// in its first theme and dropping themes left empty. Largest first.
+fn themes_from(response: ThemeResponse, orphans: &[usize]) -> Vec<OrphanTheme> {
+    let mut assigned = HashSet::new();
+    let mut themes: Vec<OrphanTheme> = response
+        .themes
+        .into_iter()
+        .filter_map(|entry| {
+            let findings: Vec<usize> = entry
+                .findings
+                .iter()
+                .filter_map(|n| orphans.get(n.checked_sub(1)?).copied())
+                .filter(|fi| assigned.insert(*fi))
+                .collect();
+            (!findings.is_empty() && !entry.theme.trim().is_empty()).then(|| OrphanTheme {
+                theme: entry.theme.trim().to_string(),
+                summary: entry.summary.trim().to_string(),
+                findings,
+            })
+        })
+        .collect();
+    themes.sort_by_key(|t| std::cmp::Reverse(t.findings.len()));
+    themes.truncate(MAX_THEMES);
+    themes
+}
+
+/// Risk points per finding. A scoring scale, deliberately steeper than
+/// [`crate::severity::weight`], over the same normalized labels.
+fn severity_weight(severity: &str) -> f64 {
+    match crate::severity::normalize(severity) {
+        "Critical" => 10.0,
+        "High" => 5.0,
+        "Medium" => 2.0,
+        "Low" => 0.5,
+        _ => 0.0,
+    }
+}
+
 fn validation_multiplier(status: &ValidationStatus) -> f64 {
     match status {
         ValidationStatus::Confirmed => 1.0,
@@ -197,28 +400,11 @@ fn validation_multiplier(status: &ValidationStatus) -> f64 {
     }
 }
 
-/// Extract repo name from a finding's file path — same logic as output/mod.rs.
-fn repo_name_from_path(path: &Path) -> String {
-    let path_str = path.to_string_lossy();
-    // Look for "repos/<name>/..." pattern
-    if let Some(idx) = path_str.find("repos/") {
-        let after = &path_str[idx + 6..];
-        if let Some(slash) = after.find('/') {
-            return after[..slash].to_string();
-        }
-        return after.to_string();
-    }
-    // Fallback: first meaningful path component
-    path.components()
-        .find_map(|c| {
-            let s = c.as_os_str().to_string_lossy();
-            if s != "." && s != ".." && s != "repos" {
-                Some(s.to_string())
-            } else {
-                None
-            }
-        })
-        .unwrap_or_else(|| "unknown".into())
+/// Weight of a template-level finding inherited by `forks` of a narrative's
+/// repos: sublinear, so a popular vulnerable template raises risk without
+/// swamping the score the way one-finding-per-fork did.
+fn template_multiplier(forks: usize) -> f64 {
+    1.0 + 0.5 * (forks.max(1) as f64).log2()
 }
 
 #[cfg(test)]
@@ -234,24 +420,81 @@ mod tests {
     }
 
     #[test]
-    fn repo_name_from_repos_prefix() {
-        assert_eq!(
-            repo_name_from_path(Path::new("repos/my-repo/src/lib.rs")),
-            "my-repo"
-        );
+    fn orphan_themes_map_back_to_findings() {
+        let narratives = vec![Narrative {
+            repo_findings: vec![("vault".into(), vec![1, 3])],
+            ..Default::default()
+        }];
+        let orphans = orphan_indices(&narratives, 6);
+        assert_eq!(orphans, vec![0, 2, 4, 5]);
+
+        let response: ThemeResponse = serde_json::from_str(
+            r#"{"themes": [
+                {"theme": "Missing owner checks", "summary": "Accounts are trusted.", "findings": [1, 3, 9]},
+                {"theme": "Unchecked math", "summary": "", "findings": [2, 3, 4]},
+                {"theme": "Leftovers", "findings": [0, 1]}
+            ]}"#,
+        )
+        .unwrap();
+        let themes = themes_from(response, &orphans);
+        // Out-of-range numbers dropped, finding 3 kept in its first theme,
+        // and a theme with nothing left removed.
         assert_eq!(
-            repo_name_from_path(Path::new("repos/other-repo/programs/vault.rs")),
-            "other-repo"
+            themes
+                .iter()
+                .map(|t| (t.theme.as_str(), t.findings.clone()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Missing owner checks", vec![0, 4]),
+                ("Unchecked math", vec![2, 5]),
+            ]
         );
     }
 
     #[test]
-    fn repo_name_fallback_no_repos_prefix() {
-        assert_eq!(repo_name_from_path(Path::new("src/lib.rs")), "src");
+    fn provenance_records_first_linking_narrative() {
+        use crate::security::{Provenance, ScanType, Selection};
+        let narratives = vec![
+            Narrative {
+                title: "Perps".into(),
+                repo_findings: vec![("drift".into(), vec![1])],
+                ..Default::default()
+            },
+            Narrative {
+                title: "Vaults".into(),
+                repo_findings: vec![("drift".into(), vec![1]), ("vault".into(), vec![2])],
+                ..Default::default()
+            },
+        ];
+        let mut findings: Vec<SecurityFinding> = (0..3)
+            .map(|_| SecurityFinding {
+                provenance: Some(Provenance::new(Selection::Narrative, ScanType::Static)),
+                ..Default::default()
+            })
+            .collect();
+        record_provenance(&narratives, &mut findings);
+
+        let linked: Vec<_> = findings
+            .iter()
+            .map(|f| {
+                let p = f.provenance.as_ref().unwrap();
+                (p.narrative_index, p.narrative.as_deref())
+            })
+            .collect();
+        assert_eq!(
+            linked,
+            vec![
+                (None, None),
+                (Some(0), Some("Perps")),
+                (Some(1), Some("Vaults"))
+            ]
+        );
     }
 
     #[test]
-    fn repo_name_just_filename() {
-        assert_eq!(repo_name_from_path(Path::new("lib.rs")), "lib.rs");
+    fn template_multiplier_is_sublinear() {
+        assert!((template_multiplier(1) - 1.0).abs() < f64::EPSILON);
+        assert!((template_multiplier(4) - 2.0).abs() < f64::EPSILON);
+        assert!(template_multiplier(16) < 16.0);
     }
 }
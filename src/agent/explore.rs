@@ -0,0 +1,91 @@
+//! Exploration mode (`run --explore`): a zero-cost run for sizing up a new
+//! ecosystem segment before committing paid budget.
+//!
+//! Every task is routed to one OpenRouter `:free` model, the run is capped
+//! to static analysis plus LLM triage (narratives and cross-reference
+//! summaries), and the report carries a disclaimer that results are below
+//! audit quality.
+
+use super::PipelineOptions;
+use crate::LlmOverride;
+use crate::config::{Config, ExploreConfig};
+use crate::llm::{LlmClient, ModelRouter, Provider};
+use anyhow::{Result, bail};
+
+/// Report banner for exploration runs.
+pub const DISCLAIMER: &str = "Exploration quality: produced with free models, static analysis and LLM triage only. No deep agent review or validation ran; treat narratives and findings as leads, not audit results.";
+
+/// OpenRouter marks zero-cost model variants with this suffix.
+const FREE_SUFFIX: &str = ":free";
+
+pub fn is_free(model: &str) -> bool {
+    model.ends_with(FREE_SUFFIX)
+}
+
+/// The model for this run: `--model` if given, else `[explore] model`.
+/// Anything that isn't a `:free` variant is refused rather than billed.
+pub fn model(cfg: &ExploreConfig, requested: Option<String>) -> Result<String> {
+    let model = requested.unwrap_or_else(|| cfg.model.clone());
+    if !is_free(&model) {
+        bail!("--explore only runs {FREE_SUFFIX} models, got `{model}`");
+    }
+    Ok(model)
+}
+
+/// Override pinning narrative detection (and the capability check) to `model`.
+pub fn llm_override(model: &str) -> LlmOverride {
+    LlmOverride {
+        provider: Provider::OpenRouter,
+        model: model.to_string(),
+    }
+}
+
+/// Cap the run to static analysis plus triage.
+pub fn restrict(opts: &mut PipelineOptions) {
+    opts.deep = false;
+    opts.skip_validation = true;
+    opts.explore = true;
+}
+
+/// A router sending every task kind to `model`. `[llm]` and `[models]` are
+/// ignored apart from `max_tokens` and the context limit.
+pub fn router(cfg: &Config, model: &str) -> Result<ModelRouter> {
+    let client = LlmClient::from_config(
+        Provider::OpenRouter,
+        model.to_string(),
+        cfg.llm.max_tokens,
+        None,
+        None,
+    )?
+    .with_context_limit(cfg.llm.context_limit(model));
+    Ok(ModelRouter::new(client))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_free_models_accepted() {
+        let cfg = ExploreConfig::default();
+        assert!(is_free(&model(&cfg, None).unwrap()));
+        assert_eq!(
+            model(&cfg, Some("qwen/qwen3-coder:free".into())).unwrap(),
+            "qwen/qwen3-coder:free"
+        );
+        let err = model(&cfg, Some("anthropic/claude-opus-4".into())).unwrap_err();
+        assert!(err.to_string().contains(":free"));
+    }
+
+    #[test]
+    fn restrict_caps_to_static_and_triage() {
+        let mut opts = PipelineOptions {
+            deep: true,
+            ..Default::default()
+        };
+        restrict(&mut opts);
+        assert!(!opts.deep && opts.skip_validation && opts.explore);
+        // Cross-reference summaries are the triage pass; they still run.
+        assert!(!opts.skip_cross_ref);
+    }
+}
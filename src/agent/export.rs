@@ -0,0 +1,498 @@
+//! Export of confirmed findings to issue trackers (`[export]`): Linear
+//! issues and Notion database rows, with the description, reasoning and
+//! code evidence that copying by hand tends to lose.
+//!
+//! Exports are idempotent. Each item carries the finding's fingerprint (its
+//! run-memory ID, else [`SecurityFinding::fingerprint`]); an export first
+//! looks the fingerprint up and updates the matching issue or row instead
+//! of creating a second one. Like alerts, failures are logged and counted,
+//! never fatal.
+
+use super::alert::Alert;
+use crate::config::{ExportConfig, LinearExportConfig, NotionExportConfig, NotionPropertyMap};
+use crate::http::HttpClient;
+use crate::security::{SecurityFinding, ValidationStatus};
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Marker line in Linear descriptions; the issue search matches on it.
+pub const FINGERPRINT_MARKER: &str = "solguard-fingerprint:";
+
+const LINEAR_URL: &str = "https://api.linear.app/graphql";
+const NOTION_URL: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+/// Notion rejects rich-text objects longer than this.
+const NOTION_TEXT_LIMIT: usize = 2000;
+
+/// One confirmed finding, flattened for a tracker.
+#[derive(Debug, Clone)]
+pub struct ExportItem {
+    pub fingerprint: String,
+    pub title: String,
+    pub severity: String,
+    pub repo: String,
+    /// `file:line`.
+    pub location: String,
+    pub tags: Vec<String>,
+    pub description: String,
+    pub reasoning: Option<String>,
+    pub remediation: String,
+    pub evidence: String,
+}
+
+impl ExportItem {
+    pub fn new(repo: &str, repo_path: &Path, finding: &SecurityFinding, fingerprint: &str) -> Self {
+        let alert = Alert::new(repo, repo_path, finding);
+        Self {
+            fingerprint: fingerprint.to_string(),
+            title: format!("{} ({repo})", finding.title),
+            severity: crate::severity::normalize(&finding.severity).to_string(),
+            repo: repo.to_string(),
+            location: format!("{}:{}", alert.file, alert.line),
+            tags: finding.tags.clone(),
+            description: alert.description,
+            reasoning: alert.reasoning,
+            remediation: finding.remediation.clone(),
+            evidence: alert.evidence,
+        }
+    }
+
+    /// Issue body: the finding, its evidence and the fingerprint marker.
+    pub fn markdown(&self) -> String {
+        let mut out = format!(
+            "**{}** in `{}` at `{}`\n\n{}\n",
+            self.severity, self.repo, self.location, self.description
+        );
+        if let Some(reasoning) = &self.reasoning {
+            out.push_str(&format!("\n**Validation:** {reasoning}\n"));
+        }
+        if !self.evidence.is_empty() {
+            out.push_str(&format!("\n```rust\n{}\n```\n", self.evidence));
+        }
+        if !self.remediation.is_empty() {
+            out.push_str(&format!("\n**Remediation:** {}\n", self.remediation));
+        }
+        out.push_str(&format!("\n{FINGERPRINT_MARKER} {}\n", self.fingerprint));
+        out
+    }
+}
+
+/// Export key of each finding, in order: the run-memory ID when assigned,
+/// else the repo-relative fingerprint run memory would assign it from.
+pub fn fingerprints(repo: &str, repo_path: &Path, findings: &[SecurityFinding]) -> Vec<String> {
+    let root = repo_path
+        .canonicalize()
+        .unwrap_or_else(|_| repo_path.to_path_buf());
+    let mut out = vec![String::new(); findings.len()];
+    for (i, fingerprint) in crate::memory::fingerprints_under(repo, Some(&root), findings) {
+        out[i] = findings[i].id.clone().unwrap_or(fingerprint);
+    }
+    out
+}
+
+/// What an export did, summed over targets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportSummary {
+    pub created: usize,
+    pub updated: usize,
+    pub failed: usize,
+}
+
+impl std::fmt::Display for ExportSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} created, {} updated, {} failed",
+            self.created, self.updated, self.failed
+        )
+    }
+}
+
+enum Upsert {
+    Created,
+    Updated,
+}
+
+/// Sends confirmed findings to whichever trackers `[export]` configures and
+/// has credentials for.
+pub struct Exporter<'a> {
+    http: &'a HttpClient,
+    linear: Option<(&'a LinearExportConfig, String)>,
+    notion: Option<(&'a NotionExportConfig, String)>,
+    summary: ExportSummary,
+}
+
+impl<'a> Exporter<'a> {
+    /// Targets whose credential variable is unset are skipped with a warning.
+    pub fn new(config: &'a ExportConfig, http: &'a HttpClient) -> Self {
+        let linear = config
+            .linear
+            .as_ref()
+            .and_then(|c| credential(&c.api_key_env, "Linear").map(|key| (c, key)));
+        let notion = config
+            .notion
+            .as_ref()
+            .and_then(|c| credential(&c.token_env, "Notion").map(|token| (c, token)));
+        Self {
+            http,
+            linear,
+            notion,
+            summary: ExportSummary::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.linear.is_some() || self.notion.is_some()
+    }
+
+    pub fn summary(&self) -> ExportSummary {
+        self.summary
+    }
+
+    /// Create or update an issue/row for every confirmed finding of `repo`.
+    pub async fn export(&mut self, repo: &str, repo_path: &Path, findings: &[SecurityFinding]) {
+        if !self.is_enabled() {
+            return;
+        }
+        let prints = fingerprints(repo, repo_path, findings);
+        for (finding, fingerprint) in findings.iter().zip(&prints) {
+            if finding.validation_status != ValidationStatus::Confirmed {
+                continue;
+            }
+            let item = ExportItem::new(repo, repo_path, finding, fingerprint);
+            if let Some((cfg, key)) = &self.linear {
+                let result = self.linear_upsert(cfg, key, &item).await;
+                self.tally("Linear", &item, result);
+            }
+            if let Some((cfg, token)) = &self.notion {
+                let result = self.notion_upsert(cfg, token, &item).await;
+                self.tally("Notion", &item, result);
+            }
+        }
+    }
+
+    fn tally(&mut self, tracker: &str, item: &ExportItem, result: Result<Upsert>) {
+        match result {
+            Ok(Upsert::Created) => self.summary.created += 1,
+            Ok(Upsert::Updated) => self.summary.updated += 1,
+            Err(e) => {
+                self.summary.failed += 1;
+                warn!(tracker, title = %item.title, error = %e, "finding export failed");
+            }
+        }
+    }
+
+    async fn linear_upsert(
+        &self,
+        cfg: &LinearExportConfig,
+        key: &str,
+        item: &ExportItem,
+    ) -> Result<Upsert> {
+        let search = json!({
+            "query": "query($team: ID!, $marker: String!) { issues(first: 1, filter: { team: { id: { eq: $team } }, description: { contains: $marker } }) { nodes { id } } }",
+            "variables": {
+                "team": cfg.team_id,
+                "marker": format!("{FINGERPRINT_MARKER} {}", item.fingerprint),
+            },
+        });
+        let found = self.linear_call(key, &search).await?;
+        let input = linear_issue_input(cfg, item);
+        match existing_linear_issue(&found) {
+            Some(id) => {
+                let update = json!({
+                    "query": "mutation($id: String!, $input: IssueUpdateInput!) { issueUpdate(id: $id, input: $input) { success } }",
+                    "variables": { "id": id, "input": input },
+                });
+                self.linear_call(key, &update).await?;
+                Ok(Upsert::Updated)
+            }
+            None => {
+                let mut input = input;
+                input["teamId"] = json!(cfg.team_id);
+                let create = json!({
+                    "query": "mutation($input: IssueCreateInput!) { issueCreate(input: $input) { success issue { identifier } } }",
+                    "variables": { "input": input },
+                });
+                let created = self.linear_call(key, &create).await?;
+                if let Some(identifier) = created
+                    .pointer("/data/issueCreate/issue/identifier")
+                    .and_then(Value::as_str)
+                {
+                    info!(issue = identifier, title = %item.title, "Linear issue created");
+                }
+                Ok(Upsert::Created)
+            }
+        }
+    }
+
+    /// POST a GraphQL request; GraphQL errors arrive with a 200 status.
+    async fn linear_call(&self, key: &str, body: &Value) -> Result<Value> {
+        let response = self
+            .http
+            .post_json_raw(LINEAR_URL, &body.to_string(), &[("Authorization", key)])
+            .await?;
+        let value: Value = serde_json::from_str(&response).context("Linear response")?;
+        if let Some(errors) = value.get("errors") {
+            bail!("Linear API: {errors}");
+        }
+        Ok(value)
+    }
+
+    async fn notion_upsert(
+        &self,
+        cfg: &NotionExportConfig,
+        token: &str,
+        item: &ExportItem,
+    ) -> Result<Upsert> {
+        let auth = format!("Bearer {token}");
+        let headers = [
+            ("Authorization", auth.as_str()),
+            ("Notion-Version", NOTION_VERSION),
+        ];
+        let query = json!({
+            "filter": {
+                "property": cfg.properties.fingerprint,
+                "rich_text": { "equals": item.fingerprint },
+            },
+            "page_size": 1,
+        });
+        let found = self
+            .http
+            .post_json_raw(
+                &format!("{NOTION_URL}/databases/{}/query", cfg.database_id),
+                &query.to_string(),
+                &headers,
+            )
+            .await?;
+        let found: Value = serde_json::from_str(&found).context("Notion response")?;
+        let properties = notion_properties(&cfg.properties, item);
+        match found.pointer("/results/0/id").and_then(Value::as_str) {
+            // Properties only: the page body is left as the triager edited it.
+            Some(page) => {
+                self.http
+                    .patch_json_raw(
+                        &format!("{NOTION_URL}/pages/{page}"),
+                        &json!({ "properties": properties }).to_string(),
+                        &headers,
+                    )
+                    .await?;
+                Ok(Upsert::Updated)
+            }
+            None => {
+                let page = json!({
+                    "parent": { "database_id": cfg.database_id },
+                    "properties": properties,
+                    "children": notion_children(item),
+                });
+                self.http
+                    .post_json_raw(&format!("{NOTION_URL}/pages"), &page.to_string(), &headers)
+                    .await?;
+                Ok(Upsert::Created)
+            }
+        }
+    }
+}
+
+fn credential(var: &str, tracker: &str) -> Option<String> {
+    match std::env::var(var) {
+        Ok(value) if !value.trim().is_empty() => Some(value),
+        _ => {
+            warn!(
+                tracker,
+                var, "export configured but credential variable unset; skipping"
+            );
+            None
+        }
+    }
+}
+
+/// `IssueCreateInput`/`IssueUpdateInput` fields shared by create and update.
+fn linear_issue_input(cfg: &LinearExportConfig, item: &ExportItem) -> Value {
+    let mut labels = cfg.label_ids.clone();
+    if let Some(label) = cfg.severity_labels.get(&item.severity) {
+        labels.push(label.clone());
+    }
+    let mut input = json!({
+        "title": item.title,
+        "description": item.markdown(),
+    });
+    if !labels.is_empty() {
+        input["labelIds"] = json!(labels);
+    }
+    if let Some(priority) = cfg.priority.get(&item.severity) {
+        input["priority"] = json!(priority);
+    }
+    input
+}
+
+fn existing_linear_issue(response: &Value) -> Option<&str> {
+    response
+        .pointer("/data/issues/nodes/0/id")
+        .and_then(Value::as_str)
+}
+
+fn rich_text(text: &str) -> Value {
+    let content: String = text.chars().take(NOTION_TEXT_LIMIT).collect();
+    json!([{ "type": "text", "text": { "content": content } }])
+}
+
+/// Row properties per `[export.notion.properties]`; empty names are skipped.
+fn notion_properties(map: &NotionPropertyMap, item: &ExportItem) -> Value {
+    let mut props = serde_json::Map::new();
+    let mut set = |name: &str, value: Value| {
+        if !name.is_empty() {
+            props.insert(name.to_string(), value);
+        }
+    };
+    set(&map.title, json!({ "title": rich_text(&item.title) }));
+    set(
+        &map.fingerprint,
+        json!({ "rich_text": rich_text(&item.fingerprint) }),
+    );
+    set(
+        &map.severity,
+        json!({ "select": { "name": item.severity } }),
+    );
+    set(&map.repo, json!({ "rich_text": rich_text(&item.repo) }));
+    set(
+        &map.location,
+        json!({ "rich_text": rich_text(&item.location) }),
+    );
+    let tags: Vec<Value> = item.tags.iter().map(|t| json!({ "name": t })).collect();
+    set(&map.tags, json!({ "multi_select": tags }));
+    Value::Object(props)
+}
+
+/// Page body of a new row: description, validation reasoning, evidence and
+/// remediation.
+fn notion_children(item: &ExportItem) -> Value {
+    let paragraph = |text: &str| json!({ "object": "block", "type": "paragraph", "paragraph": { "rich_text": rich_text(text) } });
+    let mut blocks = vec![paragraph(&item.description)];
+    if let Some(reasoning) = &item.reasoning {
+        blocks.push(paragraph(&format!("Validation: {reasoning}")));
+    }
+    if !item.evidence.is_empty() {
+        blocks.push(json!({
+            "object": "block",
+            "type": "code",
+            "code": { "language": "rust", "rich_text": rich_text(&item.evidence) },
+        }));
+    }
+    if !item.remediation.is_empty() {
+        blocks.push(paragraph(&format!("Remediation: {}", item.remediation)));
+    }
+    Value::Array(blocks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn finding() -> SecurityFinding {
+        SecurityFinding {
+            title: "Unchecked withdraw authority".into(),
+            severity: "CRITICAL".into(),
+            description: "Anyone can drain the vault.".into(),
+            file_path: PathBuf::from("programs/vault/src/lib.rs"),
+            line_number: 4,
+            remediation: "Require the vault authority to sign.".into(),
+            validation_status: ValidationStatus::Confirmed,
+            validation_reasoning: Some("No signer check on `authority`.".into()),
+            tags: vec!["treasury".into()],
+            ..Default::default()
+        }
+    }
+
+    fn linear_config() -> LinearExportConfig {
+        toml::from_str(
+            r#"
+team_id = "team-1"
+label_ids = ["lbl-solguard"]
+severity_labels = { Critical = "lbl-critical" }
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn fingerprints_prefer_ids_and_number_duplicates() {
+        let with_id = SecurityFinding {
+            id: Some("SG-2026-vault-0001".into()),
+            ..finding()
+        };
+        let later = SecurityFinding {
+            line_number: 9,
+            ..finding()
+        };
+        let repo = Path::new("/nonexistent");
+        let prints = fingerprints("vault", repo, &[later.clone(), with_id, finding()]);
+        assert_eq!(prints[1], "SG-2026-vault-0001");
+        // The ID'd finding still takes occurrence 0 of its key.
+        assert_eq!(prints[2], finding().fingerprint("vault", 1));
+        assert_eq!(prints[0], later.fingerprint("vault", 2));
+        // Keyed on the repo-relative path, like run memory.
+        let absolute = SecurityFinding {
+            file_path: repo.join(&finding().file_path),
+            ..finding()
+        };
+        assert_eq!(
+            fingerprints("vault", repo, &[absolute]),
+            [finding().fingerprint("vault", 0)]
+        );
+    }
+
+    #[test]
+    fn linear_input_maps_labels_priority_and_marker() {
+        let item = ExportItem::new("vault", Path::new("/nonexistent"), &finding(), "fp-1");
+        assert_eq!(item.severity, "Critical");
+        assert_eq!(item.location, "programs/vault/src/lib.rs:4");
+
+        let input = linear_issue_input(&linear_config(), &item);
+        assert_eq!(input["title"], "Unchecked withdraw authority (vault)");
+        assert_eq!(input["priority"], 1);
+        assert_eq!(input["labelIds"], json!(["lbl-solguard", "lbl-critical"]));
+        let body = input["description"].as_str().unwrap();
+        assert!(body.starts_with("**Critical** in `vault` at `programs/vault/src/lib.rs:4`"));
+        assert!(body.contains("**Validation:** No signer check"));
+        assert!(body.ends_with("solguard-fingerprint: fp-1\n"));
+
+        let found = json!({ "data": { "issues": { "nodes": [{ "id": "iss-7" }] } } });
+        assert_eq!(existing_linear_issue(&found), Some("iss-7"));
+        let none = json!({ "data": { "issues": { "nodes": [] } } });
+        assert_eq!(existing_linear_issue(&none), None);
+    }
+
+    #[test]
+    fn notion_properties_follow_mapping() {
+        let item = ExportItem::new("vault", Path::new("/nonexistent"), &finding(), "fp-1");
+        let map = NotionPropertyMap {
+            title: "Finding".into(),
+            repo: String::new(),
+            ..Default::default()
+        };
+        let props = notion_properties(&map, &item);
+        assert_eq!(
+            props["Finding"]["title"][0]["text"]["content"],
+            "Unchecked withdraw authority (vault)"
+        );
+        assert_eq!(
+            props["Fingerprint"]["rich_text"][0]["text"]["content"],
+            "fp-1"
+        );
+        assert_eq!(props["Severity"]["select"]["name"], "Critical");
+        assert_eq!(props["Tags"]["multi_select"][0]["name"], "treasury");
+        assert!(props.get("Repo").is_none());
+
+        let blocks = notion_children(&item);
+        // Description, reasoning, remediation; no evidence without source.
+        assert_eq!(blocks.as_array().unwrap().len(), 3);
+        let long = rich_text(&"x".repeat(3000));
+        assert_eq!(
+            long[0]["text"]["content"].as_str().unwrap().len(),
+            NOTION_TEXT_LIMIT
+        );
+    }
+}
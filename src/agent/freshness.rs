@@ -0,0 +1,144 @@
+//! Repo activity gate for target selection. Narratives often name repos that
+//! stopped moving long ago; those are skipped or scanned static-only so the
+//! deep-review budget goes to live code.
+
+use super::repo_kind::SkipReason;
+use crate::config::{StalePolicy, TargetsConfig};
+use chrono::{DateTime, Utc};
+use std::path::Path;
+
+/// What target selection decided for one repo after checking its activity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Activity {
+    /// Committed to within `max_inactive_days` (or age unknown).
+    Active,
+    /// Listed in `always_scan`, so never gated.
+    Exempt,
+    /// Stale under `stale_policy = "downrank"`: static scan only.
+    Downranked,
+    /// Stale under `stale_policy = "skip"`: not scanned.
+    Skipped,
+    /// Archived, docs-only or an awesome-list: not cloned or scanned.
+    Excluded(SkipReason),
+}
+
+impl Activity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Exempt => "always_scan",
+            Self::Downranked => "stale, static only",
+            Self::Skipped => "stale, skipped",
+            Self::Excluded(reason) => reason.label(),
+        }
+    }
+}
+
+/// A selected target with its last-commit age, listed in the report.
+#[derive(Debug, Clone)]
+pub struct TargetActivity {
+    pub repo: String,
+    /// Days since the last commit; `None` when the checkout has no git history.
+    pub last_commit_days: Option<i64>,
+    pub activity: Activity,
+}
+
+/// Commit time of `HEAD` in a checkout. Works on `--depth 1` clones.
+pub async fn last_commit_time(repo_path: &Path) -> Option<DateTime<Utc>> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["log", "-1", "--format=%ct"])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let secs: i64 = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    DateTime::from_timestamp(secs, 0)
+}
+
+/// Gate one repo by its last-commit age. Unknown ages pass: a missing git
+/// history says nothing about whether the code is maintained.
+pub fn classify(repo: &str, age_days: Option<i64>, cfg: &TargetsConfig) -> Activity {
+    if cfg.always_scan.iter().any(|name| name == repo) {
+        return Activity::Exempt;
+    }
+    let stale = cfg.max_inactive_days > 0
+        && age_days.is_some_and(|days| days > i64::from(cfg.max_inactive_days));
+    match (stale, cfg.stale_policy) {
+        (false, _) => Activity::Active,
+        (true, StalePolicy::Skip) => Activity::Skipped,
+        (true, StalePolicy::Downrank) => Activity::Downranked,
+    }
+}
+
+/// Check a checked-out target: its age, and what the gate decided.
+pub async fn check(repo: &str, repo_path: &Path, cfg: &TargetsConfig) -> TargetActivity {
+    let last_commit_days = last_commit_time(repo_path)
+        .await
+        .map(|t| (Utc::now() - t).num_days());
+    TargetActivity {
+        repo: repo.to_string(),
+        last_commit_days,
+        activity: classify(repo, last_commit_days, cfg),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn targets(policy: StalePolicy) -> TargetsConfig {
+        TargetsConfig {
+            always_scan: vec!["raydium-cp-swap".into()],
+            max_inactive_days: 180,
+            stale_policy: policy,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stale_repos_follow_policy() {
+        let skip = targets(StalePolicy::Skip);
+        assert_eq!(classify("amm", Some(30), &skip), Activity::Active);
+        assert_eq!(classify("amm", Some(400), &skip), Activity::Skipped);
+        assert_eq!(
+            classify("amm", Some(400), &targets(StalePolicy::Downrank)),
+            Activity::Downranked
+        );
+    }
+
+    #[test]
+    fn always_scan_and_unknown_age_are_not_gated() {
+        let cfg = targets(StalePolicy::Skip);
+        assert_eq!(
+            classify("raydium-cp-swap", Some(1000), &cfg),
+            Activity::Exempt
+        );
+        assert_eq!(classify("amm", None, &cfg), Activity::Active);
+    }
+
+    #[test]
+    fn zero_disables_gate() {
+        let cfg = TargetsConfig {
+            max_inactive_days: 0,
+            ..Default::default()
+        };
+        assert_eq!(classify("amm", Some(5000), &cfg), Activity::Active);
+    }
+
+    #[tokio::test]
+    async fn non_git_checkout_has_unknown_age() {
+        let dir = std::env::temp_dir().join(format!("solguard-fresh-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let t = check("plain", &dir, &TargetsConfig::default()).await;
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(t.last_commit_days, None);
+        assert_eq!(t.activity, Activity::Active);
+    }
+}
@@ -1,37 +1,96 @@
 // Autonomous orchestration: narrative → target selection → scan → validate → cross-ref → report
 
+pub mod alert;
+pub mod capabilities;
 pub mod cross_ref;
+pub mod explore;
+pub mod export;
+pub mod freshness;
+pub mod repo_kind;
+pub mod repo_summary;
+pub mod spend;
+pub mod urgency;
+pub mod workspace;
 
 use crate::LlmOverride;
-use crate::config::Config;
+use crate::config::{AgentReviewConfig, Config};
 use crate::llm::{ModelRouter, TaskKind};
-use crate::memory::{RepoResult, RunHistory, RunMemory};
+use crate::memory::{BudgetSplit, CarriedTarget, RepoResult, RunHistory, RunMemory};
+use crate::narrative::rpc_pool::RpcPool;
 use crate::narrative::{self, Narrative};
-use crate::output;
-use crate::security::{self, agent_review::ScanContext};
+use crate::output::{self, file::OutputFile};
+use crate::paths;
+use crate::progress::{Progress, ProgressEvent};
+use crate::security::{
+    self, Provenance, ScanType, SecurityFinding, Selection,
+    agent_review::{RepoBudget, ScanContext},
+    cross_repo::{self, CrossRepoGraph, RepoPrograms},
+};
 use anyhow::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tracing::info;
 
+/// Below this, a repo's share of the run budget isn't worth starting an
+/// investigation; it is scanned static-only.
+const MIN_REPO_BUDGET_USD: f64 = 0.05;
+
+/// Which phases of the full pipeline to run.
+#[derive(Debug, Clone, Default)]
+pub struct PipelineOptions {
+    /// Multi-turn agent investigation instead of static-only scanning.
+    pub deep: bool,
+    /// Skip LLM validation; findings stay unvalidated (and capped).
+    pub skip_validation: bool,
+    /// Skip LLM relevance summaries; findings are still linked and scored.
+    pub skip_cross_ref: bool,
+    /// Load narratives from a `narratives` JSON dump instead of detecting them.
+    pub narratives_from: Option<PathBuf>,
+    /// Run without narratives (missing credentials); only `always_scan` targets.
+    pub skip_narratives: bool,
+    /// Why phases were disabled, shown as a "degraded run" banner in the report.
+    pub degraded: Vec<String>,
+    /// Where live progress events go; silent by default.
+    pub progress: Progress,
+    /// Report grouping; `None` uses `[report] group_by`.
+    pub group_by: Option<crate::config::GroupBy>,
+    /// Free-model exploration run; the report carries a quality disclaimer.
+    pub explore: bool,
+    /// Skip the `[budget] confirm_above_usd` confirmation (`--yes`).
+    pub assume_yes: bool,
+    /// Clone into a fresh per-run workspace with a provenance manifest
+    /// instead of reusing `repos_dir` checkouts.
+    pub isolate: bool,
+    /// Post-processors registered in code; `[[hooks.post_process]]` commands
+    /// run after them.
+    pub hooks: crate::hooks::HookRegistry,
+    /// Repos scanned at once; `None` uses `[agent_review] parallel_repos`.
+    pub jobs: Option<usize>,
+}
+
 /// Run the full autonomous pipeline.
 ///
-/// 1. Detect narratives (what's growing in the Solana ecosystem)
+/// 1. Detect narratives (what's growing in the Solana ecosystem), or load them
 /// 2. Identify active repos from narratives
 /// 3. Clone + scan with narrative context + validate per-repo
 /// 4. Cross-reference: narratives × findings with risk scoring
 /// 5. Generate narrative-centric intelligence report
 pub async fn run_full_pipeline(
     config_path: PathBuf,
-    output_path: PathBuf,
+    output: OutputFile,
     repos_dir: PathBuf,
     llm_override: Option<LlmOverride>,
     router: ModelRouter,
-    deep: bool,
+    opts: PipelineOptions,
 ) -> Result<()> {
+    output.check()?;
     info!("SolGuard autonomous pipeline starting");
+    let started = Instant::now();
+    let deep = opts.deep;
+    let progress = &opts.progress;
 
     // Load run memory from previous runs
-    let run_memory = RunMemory::load_or_default();
+    let mut run_memory = RunMemory::load_or_default();
     if run_memory.total_runs > 0 {
         info!(
             runs = run_memory.total_runs,
@@ -44,13 +103,27 @@ pub async fn run_full_pipeline(
     let mut run_history = RunHistory::new();
 
     // Phase 1: Narrative detection
-    info!("Phase 1: Detecting narratives...");
-    let mut narratives =
-        narrative::run_narrative_pipeline(&config_path, llm_override.as_ref(), &router).await?;
+    phase(progress, 1, "narratives");
+    let mut narratives: Vec<Narrative> = match &opts.narratives_from {
+        Some(path) => {
+            info!(path = %path.display(), "Phase 1: Loading narratives from file...");
+            serde_json::from_str(&std::fs::read_to_string(path)?)?
+        }
+        None if opts.skip_narratives => {
+            tracing::warn!("Phase 1: Narrative detection skipped (missing credentials)");
+            Vec::new()
+        }
+        None => {
+            info!("Phase 1: Detecting narratives...");
+            narrative::run_narrative_pipeline(&config_path, llm_override.as_ref(), Some(&router))
+                .await?
+        }
+    };
     info!(count = narratives.len(), "narratives detected");
 
     // Phase 2: Target selection from narratives
     info!("Phase 2: Selecting scan targets...");
+    phase(progress, 2, "target selection");
     let mut targets: Vec<String> = narratives
         .iter()
         .flat_map(|n| n.active_repos.iter().cloned())
@@ -59,7 +132,7 @@ pub async fn run_full_pipeline(
     targets.dedup();
 
     // Load config once for targets + agent_review
-    let cfg = Config::load(&config_path).unwrap_or_default();
+    let cfg = Config::load(&config_path)?;
 
     // Inject known-good targets from config
     if let Some(ref cfg_repos_dir) = cfg.targets.repos_dir {
@@ -68,11 +141,7 @@ pub async fn run_full_pipeline(
             .map(|p| p.join(cfg_repos_dir))
             .unwrap_or_else(|| cfg_repos_dir.clone());
         for name in &cfg.targets.always_scan {
-            if base.join(name).is_dir()
-                && !targets
-                    .iter()
-                    .any(|t| t.split('/').next_back() == Some(name))
-            {
+            if base.join(name).is_dir() && !targets.iter().any(|t| paths::target_name(t) == name) {
                 targets.push(name.clone());
             }
         }
@@ -80,7 +149,7 @@ pub async fn run_full_pipeline(
     // Filter out blocklisted repos (consistently failing in previous runs)
     let pre_filter = targets.len();
     targets.retain(|t| {
-        let name = t.split('/').next_back().unwrap_or(t);
+        let name = paths::target_name(t);
         !run_memory.repo_blocklist.iter().any(|b| b == name)
     });
     if targets.len() < pre_filter {
@@ -89,6 +158,23 @@ pub async fn run_full_pipeline(
             "filtered blocklisted repos from memory"
         );
     }
+    // Targets carried over from the previous run keep their narrative even
+    // if this run's narratives no longer mention them.
+    let mut scan_narratives = narratives.clone();
+    scan_narratives.extend(urgency::carried_narratives(&narratives, &run_memory));
+
+    // Most urgent first; the previous run's deferred and carried-over
+    // targets rejoin here.
+    run_memory.deferred_targets =
+        urgency::schedule(&mut targets, &scan_narratives, &run_memory, &cfg.targets);
+    let previous_carry = std::mem::take(&mut run_memory.carried_over);
+    let this_run = run_memory.total_runs + 1;
+    if !run_memory.deferred_targets.is_empty() {
+        info!(
+            count = run_memory.deferred_targets.len(),
+            "deferred low-urgency targets to the next run"
+        );
+    }
 
     run_history.signals_collected = narratives.len();
     info!(
@@ -101,7 +187,15 @@ pub async fn run_full_pipeline(
         "Phase 3: Scanning targets{}...",
         if deep { " (deep agent review)" } else { "" }
     );
+    phase(progress, 3, "scan");
     std::fs::create_dir_all(&repos_dir)?;
+    let workspace = if opts.isolate {
+        let ws = workspace::Workspace::create(&repos_dir)?;
+        info!(dir = %ws.dir.display(), "cloning into isolated workspace");
+        Some(ws)
+    } else {
+        None
+    };
 
     // Resolve config repos_dir relative to config file for known-good target lookup
     let known_good_base = cfg.targets.repos_dir.as_ref().map(|rd| {
@@ -112,222 +206,782 @@ pub async fn run_full_pipeline(
     });
 
     let default_agent_config = if deep {
-        cfg.agent_review
+        cfg.agent_review.clone()
     } else {
         crate::config::AgentReviewConfig::default()
     };
 
+    // Concurrent deep reviews draw on one pool, so parallelism can't spend
+    // more than a sequential run would.
+    let mut run_budget_usd = 0.0;
+    if deep {
+        let estimate = spend::SpendEstimate {
+            repos: targets
+                .iter()
+                .map(|t| {
+                    let name = paths::target_name(t);
+                    let usd = match narrative_for(&scan_narratives, name) {
+                        Some(n) => {
+                            security::agent_review::compute_budget(n.confidence, targets.len()).1
+                        }
+                        None => default_agent_config.cost_limit_usd,
+                    };
+                    (name.to_string(), usd)
+                })
+                .collect(),
+        };
+        spend::confirm(&estimate, cfg.budget.confirm_above_usd, opts.assume_yes)?;
+        run_budget_usd = cfg
+            .budget
+            .max_run_usd
+            .map_or(estimate.total(), |cap| cap.min(estimate.total()));
+    }
+
+    let http = crate::http::HttpClient::new("st-solguard/0.1.0")?;
+    let jobs = opts.jobs.unwrap_or(cfg.agent_review.parallel_repos).max(1);
+    if jobs > 1 {
+        info!(jobs, "scanning targets in parallel");
+    }
+    let run_budget = spend::RunBudget::new(run_budget_usd);
+    let rpc = RpcPool::new(&cfg.solana, &http);
+    let phase3 = ScanPhase {
+        cfg: &cfg,
+        router: &router,
+        narratives: &scan_narratives,
+        opts: &opts,
+        deep,
+        total: targets.len(),
+        repos_dir: &repos_dir,
+        known_good_base: known_good_base.as_deref(),
+        default_agent_config: &default_agent_config,
+        rpc: &rpc,
+        http: &http,
+        workspace: tokio::sync::Mutex::new(workspace),
+        memory: std::sync::Mutex::new(&mut run_memory),
+        notifiers: tokio::sync::Mutex::new((
+            alert::Alerter::new(&cfg.alerts, &http, output.is_stdout()),
+            export::Exporter::new(&cfg.export, &http),
+        )),
+        siblings: std::sync::Mutex::new(Vec::new()),
+        programs: std::sync::Mutex::new(Vec::new()),
+        budget: &run_budget,
+        deadline: cfg
+            .budget
+            .max_run_minutes
+            .map(|minutes| started + Duration::from_secs(minutes * 60)),
+        permits: tokio::sync::Semaphore::new(jobs),
+    };
+    let outcomes = futures_util::future::join_all(
+        targets
+            .iter()
+            .enumerate()
+            .map(|(index, target)| phase3.scan_target(index, target)),
+    )
+    .await;
+
+    let ScanPhase {
+        workspace,
+        notifiers,
+        programs,
+        ..
+    } = phase3;
+    let workspace = workspace.into_inner();
+    let (alerter, exporter) = notifiers.into_inner();
+    let programs = programs.into_inner().unwrap_or_else(|e| e.into_inner());
+    let cross_repo_graphs: Vec<CrossRepoGraph> = scan_narratives
+        .iter()
+        .map(|n| {
+            let members: Vec<RepoPrograms> = programs
+                .iter()
+                .filter(|p| in_narrative(n, &p.repo))
+                .cloned()
+                .collect();
+            CrossRepoGraph::build(&n.title, &members)
+        })
+        .filter(|g| !g.is_empty())
+        .collect();
+
+    // Merged in target order, so the report doesn't depend on which scan
+    // finished first.
     let mut all_findings = Vec::new();
-    for target in &targets {
-        let repo_name = target.split('/').next_back().unwrap_or(target);
+    let mut scanned_repos: Vec<(String, PathBuf)> = Vec::new();
+    let mut target_activity: Vec<freshness::TargetActivity> = Vec::new();
+    let mut validation_samples = Vec::new();
+    let mut trust_graphs = Vec::new();
+    let mut carried_over = Vec::new();
+    for (target, outcome) in targets.iter().zip(outcomes) {
+        let outcome = outcome?;
+        if let Some(reason) = outcome.carried_over {
+            let since_run = previous_carry
+                .iter()
+                .find(|c| &c.target == target)
+                .map_or(this_run, |c| c.since_run);
+            carried_over.push(carry(target, reason, &scan_narratives, since_run));
+        }
+        target_activity.extend(outcome.activity);
+        validation_samples.extend(outcome.samples);
+        trust_graphs.extend(outcome.trust_graph);
+        if let Some(result) = outcome.result {
+            if let Some(path) = outcome.scanned {
+                scanned_repos.push((result.name.clone(), path));
+            }
+            run_history.repo_results.push(result);
+        }
+        all_findings.extend(outcome.findings);
+    }
+    if deep {
+        info!(
+            spent = format!("${:.2}", run_budget.spent()),
+            budget = format!("${run_budget_usd:.2}"),
+            "run deep-review spend"
+        );
+    }
+
+    if alerter.sent() > 0 {
+        info!(
+            alerts = alerter.sent(),
+            "confirmed critical findings alerted"
+        );
+    }
+    if exporter.is_enabled() {
+        info!(summary = %exporter.summary(), "confirmed findings exported");
+    }
+
+    if !carried_over.is_empty() {
+        info!(
+            count = carried_over.len(),
+            "carried unscanned targets over to the next run"
+        );
+    }
+    // Everything queued for the next run, for the report.
+    let deferred = carried_over
+        .iter()
+        .cloned()
+        .chain(
+            run_memory
+                .deferred_targets
+                .iter()
+                .map(|t| carry(t, "low urgency", &scan_narratives, this_run)),
+        )
+        .collect();
+    run_memory.carried_over = carried_over;
+
+    // Forks of one template would otherwise repeat the same finding per repo
+    let mut all_findings = security::forks::dedup_fork_findings(all_findings, &scanned_repos);
+    crate::tags::tag_findings(&mut all_findings, &cfg.tagging.rules);
+    let mut hooks = opts.hooks.clone();
+    hooks.extend_from_config(&cfg.hooks);
+    let mut all_findings = hooks.run(all_findings)?;
+
+    // Phase 4: Cross-reference narratives with security findings
+    info!("Phase 4: Cross-referencing narratives with security findings...");
+    phase(progress, 4, "cross-reference");
+    let (_links, orphan_themes) = if opts.skip_cross_ref {
+        (
+            cross_ref::analyze_offline(&mut narratives, &all_findings).await?,
+            Vec::new(),
+        )
+    } else {
+        let links = cross_ref::analyze(&mut narratives, &all_findings, &router).await?;
+        let themes = cross_ref::cluster_orphans(&narratives, &all_findings, &router).await;
+        (links, themes)
+    };
+
+    // Sort narratives by risk_score descending for the report
+    narratives.sort_by(|a, b| {
+        b.risk_score
+            .partial_cmp(&a.risk_score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    cross_ref::record_provenance(&narratives, &mut all_findings);
+
+    // Phase 5: Generate combined report
+    info!("Phase 5: Generating combined report...");
+    phase(progress, 5, "report");
+    run_history.narrative_budgets = crate::memory::budget_by_narrative(&run_history.repo_results);
+    let notices = output::ReportNotices {
+        budgets: run_history.repo_results.clone(),
+        degraded: opts.degraded.clone(),
+        targets: target_activity,
+        group_by: opts.group_by.unwrap_or(cfg.report.group_by),
+        exploration: opts.explore,
+        validation_samples,
+        trust_graphs,
+        cross_repo: cross_repo_graphs,
+        manifest: workspace.as_ref().map(workspace::Workspace::manifest_path),
+        orphan_themes,
+        deferred,
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(
+        &narratives,
+        &all_findings,
+        Some(&run_memory),
+        &notices,
+    )?;
+
+    output.write(&html)?;
+
+    info!(path = %output, "combined report written");
+    progress.emit(ProgressEvent::Finished {
+        report: output.to_string(),
+        narratives: narratives.len(),
+        findings: all_findings.len(),
+    });
+
+    // Save run history and update memory for future runs
+    run_history.total_findings = all_findings.len();
+    run_history.narratives = narratives.iter().map(Into::into).collect();
+    if let Err(e) = run_history.save() {
+        tracing::warn!(error = %e, "failed to save run history");
+    }
+    run_memory.update_from_run(&run_history);
+    run_memory
+        .pending_generations
+        .extend(router.drain_generations());
+    if let Err(e) = run_memory.save() {
+        tracing::warn!(error = %e, "failed to save run memory");
+    }
+
+    Ok(())
+}
+
+/// Phase 3 state shared by every target's scan. Targets run concurrently up
+/// to `permits`; what they share mutably sits behind locks.
+struct ScanPhase<'a> {
+    cfg: &'a Config,
+    router: &'a ModelRouter,
+    narratives: &'a [Narrative],
+    opts: &'a PipelineOptions,
+    deep: bool,
+    total: usize,
+    repos_dir: &'a Path,
+    known_good_base: Option<&'a Path>,
+    default_agent_config: &'a AgentReviewConfig,
+    /// Shared so every target sees which RPC endpoints are cooling down.
+    rpc: &'a RpcPool,
+    /// For GitHub repo metadata, checked before cloning.
+    http: &'a crate::http::HttpClient,
+    workspace: tokio::sync::Mutex<Option<workspace::Workspace>>,
+    memory: std::sync::Mutex<&'a mut RunMemory>,
+    notifiers: tokio::sync::Mutex<(alert::Alerter<'a>, export::Exporter<'a>)>,
+    /// "[severity] title" of findings from repos finished so far, shown to
+    /// later investigations.
+    siblings: std::sync::Mutex<Vec<String>>,
+    /// Program IDs of the narrative repos checked out so far, for calls
+    /// between them.
+    programs: std::sync::Mutex<Vec<RepoPrograms>>,
+    budget: &'a spend::RunBudget,
+    /// From `[budget] max_run_minutes`; targets not started by then are
+    /// carried over.
+    deadline: Option<Instant>,
+    permits: tokio::sync::Semaphore,
+}
+
+/// What one target contributed to the run.
+#[derive(Default)]
+struct TargetOutcome {
+    /// None for targets skipped as inactive, which aren't a repo result.
+    /// Targets with no code to audit are one, marked `skipped`.
+    result: Option<RepoResult>,
+    activity: Option<freshness::TargetActivity>,
+    findings: Vec<SecurityFinding>,
+    /// Checkout that was scanned, for fork dedup.
+    scanned: Option<PathBuf>,
+    samples: Vec<security::sampling::PatternSample>,
+    trust_graph: Option<security::trust_graph::TrustGraph>,
+    /// Why the target wasn't deep-reviewed, when it is queued for the next run.
+    carried_over: Option<&'static str>,
+}
+
+impl TargetOutcome {
+    fn failed(repo_name: &str, error: &str) -> Self {
+        Self {
+            result: Some(RepoResult {
+                name: repo_name.to_string(),
+                findings_count: 0,
+                errors: vec![error.to_string()],
+                finding_ids: Vec::new(),
+                skipped: None,
+                waived: Vec::new(),
+                summary: None,
+                budget: None,
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn excluded(repo_name: &str, reason: repo_kind::SkipReason) -> Self {
+        Self {
+            result: Some(RepoResult {
+                name: repo_name.to_string(),
+                findings_count: 0,
+                errors: Vec::new(),
+                finding_ids: Vec::new(),
+                skipped: Some(reason),
+                waived: Vec::new(),
+                summary: None,
+                budget: None,
+            }),
+            activity: Some(freshness::TargetActivity {
+                repo: repo_name.to_string(),
+                last_commit_days: None,
+                activity: freshness::Activity::Excluded(reason),
+            }),
+            ..Default::default()
+        }
+    }
+}
+
+impl ScanPhase<'_> {
+    /// Clone, scan, validate and post-process one target once a permit is free.
+    async fn scan_target(&self, index: usize, target: &str) -> Result<TargetOutcome> {
+        let _permit = self.permits.acquire().await?;
+        let (cfg, opts, progress) = (self.cfg, self.opts, &self.opts.progress);
+        let repo_name = paths::target_name(target);
+        progress.emit(ProgressEvent::RepoStarted {
+            repo: repo_name.to_string(),
+            index: index + 1,
+            total: self.total,
+        });
+        if self
+            .deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            info!(repo = %target, "run time limit reached, carrying over to the next run");
+            skipped(progress, repo_name, "run time limit reached");
+            return Ok(TargetOutcome {
+                carried_over: Some("run time limit reached"),
+                ..Default::default()
+            });
+        }
+
+        // Archived, docs-only and awesome-list repos have nothing to audit
+        let exempt =
+            !target.contains('/') || cfg.targets.always_scan.iter().any(|n| n == repo_name);
+        let metadata = if exempt {
+            None
+        } else {
+            repo_kind::fetch(self.http, target, &cfg.github.token).await
+        };
+        if let Some(reason) = metadata.as_ref().and_then(repo_kind::classify) {
+            return Ok(exclude(progress, target, reason));
+        }
 
         // Known-good targets (bare names) resolve from config repos_dir
         let repo_path = if !target.contains('/') {
-            if let Some(ref base) = known_good_base {
+            if let Some(base) = self.known_good_base {
                 let p = base.join(repo_name);
                 if p.is_dir() {
                     p
                 } else {
-                    repos_dir.join(repo_name)
+                    self.repos_dir.join(repo_name)
                 }
             } else {
-                repos_dir.join(repo_name)
+                self.repos_dir.join(repo_name)
             }
+        } else if let Some(ws) = &*self.workspace.lock().await {
+            ws.repo_path(repo_name)
         } else {
-            repos_dir.join(repo_name)
+            self.repos_dir.join(repo_name)
         };
 
         if !repo_path.exists() {
             if !target.contains('/') {
                 tracing::warn!(repo = %target, "known-good target not found locally, skipping");
-                run_history.repo_results.push(RepoResult {
-                    name: repo_name.to_string(),
-                    findings_count: 0,
-                    errors: vec!["not found locally".into()],
-                });
-                continue;
+                skipped(progress, repo_name, "not found locally");
+                return Ok(TargetOutcome::failed(repo_name, "not found locally"));
             }
             info!(repo = %target, "cloning repository");
+            let url = format!("https://github.com/{target}");
             let status = tokio::process::Command::new("git")
-                .args([
-                    "clone",
-                    "--depth",
-                    "1",
-                    &format!("https://github.com/{target}"),
-                    &repo_path.to_string_lossy(),
-                ])
+                .args(["clone", "--depth", "1", &url])
+                .arg(&repo_path)
                 .status()
                 .await?;
 
             if !status.success() {
                 tracing::warn!(repo = %target, "failed to clone, skipping");
-                run_history.repo_results.push(RepoResult {
-                    name: repo_name.to_string(),
-                    findings_count: 0,
-                    errors: vec!["clone failed".into()],
-                });
-                continue;
+                skipped(progress, repo_name, "clone failed");
+                return Ok(TargetOutcome::failed(repo_name, "clone failed"));
+            }
+            if let Some(ws) = &mut *self.workspace.lock().await {
+                ws.record(repo_name, &url).await?;
             }
         }
 
-        // Build narrative-informed scan context + dynamic budget
-        let (scan_ctx, repo_agent_config) = if deep {
-            let narrative = narratives.iter().find(|n| {
-                n.active_repos
-                    .iter()
-                    .any(|ar| ar.split('/').next_back() == Some(repo_name))
-            });
-            match narrative {
-                Some(n) => {
-                    let (budget_turns, budget_cost) =
-                        security::agent_review::compute_budget(n.confidence, targets.len());
-
-                    let siblings: Vec<String> = all_findings
-                        .iter()
-                        .take(10)
-                        .map(|f: &security::SecurityFinding| {
-                            format!("[{}] {}", f.severity, f.title)
-                        })
-                        .collect();
+        if !exempt
+            && metadata.is_none()
+            && let Some(reason) = repo_kind::classify_checkout(&repo_path)
+        {
+            return Ok(exclude(progress, target, reason));
+        }
 
-                    let ctx = ScanContext {
-                        protocol_category: infer_protocol_category(n),
-                        narrative_summary: Some(n.summary.clone()),
-                        sibling_findings: siblings,
-                    };
+        // Freshness gate: abandoned repos don't get deep-scan budget
+        let activity = freshness::check(repo_name, &repo_path, &cfg.targets).await;
+        let gate = activity.activity;
+        let mut outcome = TargetOutcome {
+            activity: Some(activity),
+            ..Default::default()
+        };
+        if gate == freshness::Activity::Skipped {
+            info!(repo = %target, "last commit older than max_inactive_days, skipping");
+            skipped(progress, repo_name, "inactive");
+            return Ok(outcome);
+        }
+        let mut deep = self.deep && gate != freshness::Activity::Downranked;
 
-                    let cfg = crate::config::AgentReviewConfig {
-                        max_turns: budget_turns,
-                        max_tokens: default_agent_config.max_tokens,
-                        cost_limit_usd: budget_cost,
-                    };
-                    (Some(ctx), cfg)
-                }
-                None => (
-                    None,
-                    crate::config::AgentReviewConfig {
-                        max_turns: default_agent_config.max_turns,
-                        max_tokens: default_agent_config.max_tokens,
-                        cost_limit_usd: default_agent_config.cost_limit_usd,
-                    },
-                ),
+        // Build narrative-informed scan context + dynamic budget
+        let narrative = narrative_for(self.narratives, repo_name);
+        let repo_programs = narrative.map(|_| {
+            let indexed = RepoPrograms::index(repo_name, &repo_path);
+            self.programs
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(indexed.clone());
+            indexed
+        });
+        let mut repo_agent_config = self.default_agent_config.clone();
+        let scan_ctx = match narrative.filter(|_| deep) {
+            Some(n) => {
+                let (budget_turns, budget_cost) =
+                    security::agent_review::compute_budget(n.confidence, self.total);
+                repo_agent_config.max_turns = budget_turns;
+                repo_agent_config.cost_limit_usd = budget_cost;
+
+                let siblings = self
+                    .siblings
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .iter()
+                    .take(10)
+                    .cloned()
+                    .collect();
+                Some(ScanContext {
+                    protocol_category: infer_protocol_category(n),
+                    narrative_summary: Some(n.summary.clone()),
+                    sibling_findings: siblings,
+                    cross_repo_calls: repo_programs
+                        .as_ref()
+                        .map(|p| self.cross_repo_calls(p))
+                        .unwrap_or_default(),
+                    ..Default::default()
+                })
             }
+            None => None,
+        };
+
+        // Concurrent repos draw their budgets from the run's pool
+        let reserved = if deep {
+            self.budget.reserve(repo_agent_config.cost_limit_usd)
         } else {
-            (
-                None,
-                crate::config::AgentReviewConfig {
-                    max_turns: default_agent_config.max_turns,
-                    max_tokens: default_agent_config.max_tokens,
-                    cost_limit_usd: default_agent_config.cost_limit_usd,
-                },
-            )
+            0.0
         };
+        if deep && reserved < MIN_REPO_BUDGET_USD {
+            tracing::warn!(
+                repo = %target,
+                "run budget exhausted, scanning static-only and carrying over to the next run"
+            );
+            self.budget.settle(reserved, 0.0);
+            outcome.carried_over = Some("run budget exhausted");
+            deep = false;
+        }
+        repo_agent_config.cost_limit_usd = reserved.min(repo_agent_config.cost_limit_usd);
 
+        // Investigation and validation share one budget per repo
+        let budget = RepoBudget::new(&repo_agent_config);
         let result = if deep {
-            let llm = router.client_for(TaskKind::DeepInvestigation);
-            security::scan_repo_deep(&repo_path, llm, &repo_agent_config, scan_ctx.as_ref()).await
+            let llm = self.router.client_for(TaskKind::DeepInvestigation);
+            security::scan_repo_deep_with_stats(
+                &repo_path,
+                llm,
+                &budget.investigation(),
+                &cfg.scan,
+                scan_ctx.as_ref(),
+                self.rpc,
+                progress,
+            )
+            .await
+            .map(|(findings, mut stats)| (findings, std::mem::take(&mut stats.waived), Some(stats)))
         } else {
-            security::scan_repo(&repo_path).await
+            security::scan_repo_waived(&repo_path, &cfg.scan)
+                .await
+                .map(|scan| (scan.findings, scan.waived, None))
         };
 
-        match result {
-            Ok(mut findings) => {
-                let mut repo_errors = Vec::new();
-                let is_program = security::is_solana_project(&repo_path);
-                let mut validated = false;
-
-                // Only validate Solana program repos in deep mode
-                if deep && !findings.is_empty() && is_program {
-                    info!(repo = %target, count = findings.len(), "validating findings");
-                    match security::validator::validate_findings(
-                        &mut findings,
-                        &router,
-                        &repo_path,
-                        &repo_agent_config,
-                    )
-                    .await
-                    {
-                        Ok(()) => validated = true,
-                        Err(e) => {
-                            repo_errors.push(format!("validation: {e}"));
-                            tracing::warn!(
-                                repo = %target, error = %e,
-                                "validation failed, keeping unvalidated"
-                            );
-                        }
-                    }
+        let (mut findings, waived, investigation) = match result {
+            Ok(scanned) => scanned,
+            Err(e) => {
+                tracing::warn!(repo = %target, error = %e, "scan failed");
+                if deep {
+                    // Unknown spend: keep the whole reservation.
+                    self.budget.settle(reserved, reserved);
                 }
+                progress.emit(ProgressEvent::RepoFinished {
+                    repo: repo_name.to_string(),
+                    findings: 0,
+                    error: Some(e.to_string()),
+                });
+                outcome.result = TargetOutcome::failed(repo_name, &e.to_string()).result;
+                return Ok(outcome);
+            }
+        };
 
-                // Cap unvalidated findings to prevent report pollution
-                if !validated && findings.len() > 50 {
-                    findings.sort_by(|a, b| {
-                        security::severity_weight(&b.severity)
-                            .cmp(&security::severity_weight(&a.severity))
-                    });
-                    findings.truncate(50);
-                    tracing::warn!(repo = %target, "capped unvalidated findings at 50");
+        let mut repo_errors = Vec::new();
+        let is_program = security::is_solana_project(&repo_path);
+        let mut validated = false;
+        let selection = if narrative.is_some() {
+            Selection::Narrative
+        } else if cfg.targets.always_scan.iter().any(|n| n == repo_name) {
+            Selection::AlwaysScan
+        } else {
+            Selection::Deferred
+        };
+        security::stamp_provenance(&mut findings, selection);
+        let mut split = investigation.as_ref().map(|inv| {
+            let validation = budget.validation(inv);
+            BudgetSplit {
+                total_turns: budget.max_turns,
+                total_cost_usd: budget.cost_limit_usd,
+                investigation_turns: inv.turns,
+                investigation_cost_usd: inv.total_cost_usd,
+                validation_turn_limit: validation.max_turns,
+                validation_cost_limit_usd: validation.cost_limit_usd,
+                // Only narrative repos get a confidence-scaled budget.
+                narrative: scan_ctx.as_ref().and(narrative.map(|n| n.title.clone())),
+                narrative_confidence: scan_ctx.as_ref().and(narrative.map(|n| n.confidence)),
+                static_merge: Some(inv.static_merge),
+                ..Default::default()
+            }
+        });
+
+        // Only validate Solana program repos in deep mode
+        if let Some(inv) = &investigation
+            && !opts.skip_validation
+            && !findings.is_empty()
+            && is_program
+        {
+            info!(repo = %target, count = findings.len(), "validating findings");
+            // Verdicts survive a restarted run: findings validated at
+            // this commit before aren't paid for again.
+            let commit = workspace::head_sha(&repo_path).await;
+            if let Some(commit) = &commit {
+                let reused = self
+                    .memory
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .reuse_verdicts(repo_name, commit, &mut findings);
+                if reused > 0 {
+                    info!(repo = %target, reused, "reusing cached verdicts");
                 }
-
-                let count = findings.len();
-                info!(repo = %target, findings = count, "scan complete");
-                all_findings.extend(findings);
-                run_history.repo_results.push(RepoResult {
-                    name: repo_name.to_string(),
-                    findings_count: count,
-                    errors: repo_errors,
-                });
             }
-            Err(e) => {
-                tracing::warn!(repo = %target, error = %e, "scan failed");
-                run_history.repo_results.push(RepoResult {
-                    name: repo_name.to_string(),
-                    findings_count: 0,
-                    errors: vec![e.to_string()],
-                });
+            let validation = security::validator::annotate_sampled(
+                &mut findings,
+                self.router,
+                &repo_path,
+                &budget.validation(inv),
+                &cfg.validation,
+                // Recomputed: repos checked out since the investigation count too.
+                &cross_repo::context_section(
+                    &repo_programs
+                        .as_ref()
+                        .map(|p| self.cross_repo_calls(p))
+                        .unwrap_or_default(),
+                ),
+            )
+            .await;
+            if validation.is_ok()
+                && let Some(commit) = &commit
+            {
+                let mut memory = self.memory.lock().unwrap_or_else(|e| e.into_inner());
+                memory.record_verdicts(repo_name, commit, &findings);
+                if let Err(e) = memory.save() {
+                    tracing::warn!(error = %e, "failed to save cached verdicts");
+                }
+            }
+            security::validator::apply_verdicts(&mut findings);
+            match validation {
+                Ok((spent, samples)) => {
+                    validated = true;
+                    outcome.samples = samples
+                        .into_iter()
+                        .map(|s| security::sampling::PatternSample {
+                            repo: repo_name.to_string(),
+                            ..s
+                        })
+                        .collect();
+                    if let Some(split) = &mut split {
+                        split.validation_turns = spent.turns;
+                        split.validation_cost_usd = spent.total_cost_usd;
+                    }
+                }
+                Err(e) => {
+                    repo_errors.push(format!("validation: {e}"));
+                    tracing::warn!(
+                        repo = %target, error = %e,
+                        "validation failed, keeping unvalidated"
+                    );
+                }
             }
         }
-    }
-
-    // Phase 4: Cross-reference narratives with security findings
-    info!("Phase 4: Cross-referencing narratives with security findings...");
-    let _links = cross_ref::analyze(&mut narratives, &all_findings, &router).await?;
+        if deep {
+            let spent = split
+                .as_ref()
+                .map_or(0.0, |s| s.investigation_cost_usd + s.validation_cost_usd);
+            self.budget.settle(reserved, spent);
+        }
 
-    // Sort narratives by risk_score descending for the report
-    narratives.sort_by(|a, b| {
-        b.risk_score
-            .partial_cmp(&a.risk_score)
-            .unwrap_or(std::cmp::Ordering::Equal)
-    });
+        // Cap unvalidated findings to prevent report pollution
+        if !validated && findings.len() > 50 {
+            findings.sort_by(|a, b| {
+                crate::severity::weight(&b.severity).cmp(&crate::severity::weight(&a.severity))
+            });
+            findings.truncate(50);
+            tracing::warn!(repo = %target, "capped unvalidated findings at 50");
+        }
 
-    // Phase 5: Generate combined report
-    info!("Phase 5: Generating combined report...");
-    let html = output::render_combined_report(&narratives, &all_findings, Some(&run_memory))?;
+        // Deployment posture is orthogonal to code review — never validated or capped
+        if is_program {
+            let mut posture = security::posture::check_repo(&repo_path, self.rpc).await;
+            for f in &mut posture {
+                f.provenance = Some(Provenance::new(selection, ScanType::Posture));
+            }
+            findings.extend(posture);
+            outcome.trust_graph = Some(security::trust_graph::build(repo_name, &repo_path));
+        }
+        let summary = if cfg.report.repo_summaries {
+            repo_summary::generate(
+                self.router.client_for(TaskKind::Summary),
+                repo_name,
+                &repo_path,
+                &findings,
+                outcome.trust_graph.as_ref(),
+            )
+            .await
+        } else {
+            None
+        };
 
-    if let Some(parent) = output_path.parent() {
-        std::fs::create_dir_all(parent)?;
+        self.memory
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .assign_finding_ids(repo_name, &repo_path, &mut findings);
+        if validated {
+            let (alerter, exporter) = &mut *self.notifiers.lock().await;
+            alerter.notify(repo_name, &repo_path, &findings).await;
+            exporter.export(repo_name, &repo_path, &findings).await;
+        }
+        self.siblings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .extend(
+                findings
+                    .iter()
+                    .map(|f| format!("[{}] {}", f.severity, f.title)),
+            );
+
+        let count = findings.len();
+        info!(repo = %target, findings = count, "scan complete");
+        if let Some(split) = &split {
+            info!(
+                repo = %target,
+                investigation = format!("{} turns/${:.2}", split.investigation_turns, split.investigation_cost_usd),
+                validation = format!("{} turns/${:.2}", split.validation_turns, split.validation_cost_usd),
+                budget = format!("{} turns/${:.2}", split.total_turns, split.total_cost_usd),
+                "budget split"
+            );
+        }
+        progress.emit(ProgressEvent::RepoFinished {
+            repo: repo_name.to_string(),
+            findings: count,
+            error: None,
+        });
+        let finding_ids = findings.iter().filter_map(|f| f.id.clone()).collect();
+        outcome.findings = findings;
+        outcome.scanned = Some(repo_path);
+        outcome.result = Some(RepoResult {
+            name: repo_name.to_string(),
+            findings_count: count,
+            errors: repo_errors,
+            finding_ids,
+            skipped: None,
+            budget: split,
+            waived,
+            summary,
+        });
+        Ok(outcome)
     }
-    std::fs::write(&output_path, &html)?;
-
-    info!(path = %output_path.display(), "combined report written");
-    println!("SolGuard report: {}", output_path.display());
-    println!(
-        "  {} narratives, {} security findings",
-        narratives.len(),
-        all_findings.len()
-    );
 
-    // Save run history and update memory for future runs
-    run_history.total_findings = all_findings.len();
-    if let Err(e) = run_history.save() {
-        tracing::warn!(error = %e, "failed to save run history");
+    /// Calls between `repo` and the repos checked out so far that share a
+    /// narrative with it. Repos checked out later aren't known yet; the
+    /// report's graph covers them all.
+    fn cross_repo_calls(&self, repo: &RepoPrograms) -> Vec<String> {
+        let programs = self.programs.lock().unwrap_or_else(|e| e.into_inner());
+        let peers: Vec<&RepoPrograms> = programs
+            .iter()
+            .filter(|p| {
+                p.repo != repo.repo
+                    && self
+                        .narratives
+                        .iter()
+                        .any(|n| in_narrative(n, &repo.repo) && in_narrative(n, &p.repo))
+            })
+            .collect();
+        cross_repo::calls_involving(repo, &peers)
+            .iter()
+            .map(|c| c.describe())
+            .collect()
     }
-    let mut run_memory = run_memory;
-    run_memory.update_from_run(&run_history);
-    if let Err(e) = run_memory.save() {
-        tracing::warn!(error = %e, "failed to save run memory");
+}
+
+/// Queue entry for `target`, with its narrative narrowed to it.
+fn carry(target: &str, reason: &str, narratives: &[Narrative], since_run: u32) -> CarriedTarget {
+    CarriedTarget {
+        target: target.to_string(),
+        reason: reason.to_string(),
+        narrative: narrative_for(narratives, paths::target_name(target)).map(|n| Narrative {
+            active_repos: vec![target.to_string()],
+            mismatched_repos: Vec::new(),
+            finding_count: 0,
+            repo_findings: Vec::new(),
+            ..n.clone()
+        }),
+        since_run,
     }
+}
 
-    Ok(())
+/// The narrative whose active repos include `repo` (a bare name).
+fn narrative_for<'a>(narratives: &'a [Narrative], repo: &str) -> Option<&'a Narrative> {
+    narratives.iter().find(|n| in_narrative(n, repo))
+}
+
+fn in_narrative(narrative: &Narrative, repo: &str) -> bool {
+    narrative
+        .active_repos
+        .iter()
+        .any(|ar| paths::target_name(ar) == repo)
+}
+
+fn phase(progress: &Progress, phase: u8, name: &str) {
+    progress.emit(ProgressEvent::Phase {
+        phase,
+        name: name.to_string(),
+    });
+}
+
+fn skipped(progress: &Progress, repo: &str, reason: &str) {
+    progress.emit(ProgressEvent::RepoSkipped {
+        repo: repo.to_string(),
+        reason: reason.to_string(),
+    });
+}
+
+/// Skip a target with no code to audit, recording why.
+fn exclude(progress: &Progress, target: &str, reason: repo_kind::SkipReason) -> TargetOutcome {
+    let repo_name = paths::target_name(target);
+    info!(repo = %target, reason = reason.label(), "nothing to audit, skipping");
+    skipped(progress, repo_name, reason.label());
+    TargetOutcome::excluded(repo_name, reason)
 }
 
 /// Infer protocol category from narrative content for scan context.
@@ -349,6 +1003,8 @@ pub fn infer_protocol_category(narrative: &Narrative) -> Option<String> {
         Some("Privacy".into())
     } else if text.contains("bridge") || text.contains("cross-chain") {
         Some("Bridge".into())
+    } else if text.contains("vault") || text.contains("escrow") || text.contains("custody") {
+        Some("Vault/Escrow".into())
     } else {
         None
     }
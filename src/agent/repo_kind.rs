@@ -0,0 +1,205 @@
+//! Targets with nothing to audit: archived (read-only) repos, docs-only
+//! repos and awesome-lists. Narratives name them as readily as programs, and
+//! scanning one spends a clone and a pipeline slot to produce an empty
+//! result.
+//!
+//! `owner/name` targets are checked against GitHub's repo metadata before
+//! cloning. When that is unavailable (rate limit, network), the checkout is
+//! checked instead: a tree without a single source file is a docs repo, or
+//! an awesome-list when its README says so. `always_scan` targets are never
+//! checked.
+
+use crate::http::HttpClient;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use walkdir::WalkDir;
+
+/// Why a target was skipped without being scanned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    Archived,
+    DocsOnly,
+    AwesomeList,
+}
+
+impl SkipReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Archived => "archived",
+            Self::DocsOnly => "docs only",
+            Self::AwesomeList => "awesome list",
+        }
+    }
+}
+
+/// The fields of `GET /repos/{owner}/{name}` that tell code from the rest.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct RepoMetadata {
+    pub name: String,
+    #[serde(default)]
+    pub archived: bool,
+    /// GitHub's primary language; `None` when it detected no code.
+    #[serde(default)]
+    pub language: Option<String>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+}
+
+/// Primary languages of prose and web pages rather than programs.
+const DOCS_LANGUAGES: &[&str] = &["Markdown", "MDX", "HTML", "CSS", "TeX", "reStructuredText"];
+
+/// Extensions of source files in any language a target might hold code in.
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "nr", "py", "ts", "tsx", "js", "jsx", "mjs", "go", "sol", "move", "c", "h", "cpp",
+    "java", "kt", "swift", "zig",
+];
+
+const SKIPPED_DIRS: &[&str] = &[".git", "node_modules", "target"];
+
+/// Metadata of `owner/name`, or `None` if GitHub couldn't be asked.
+pub async fn fetch(http: &HttpClient, target: &str, token: &str) -> Option<RepoMetadata> {
+    let url = format!("https://api.github.com/repos/{target}");
+    let result = if token.is_empty() {
+        http.get_json(&url).await
+    } else {
+        http.get_json_authed(&url, token).await
+    };
+    match result {
+        Ok(meta) => Some(meta),
+        Err(e) => {
+            tracing::debug!(repo = %target, error = %e, "GitHub repo metadata unavailable");
+            None
+        }
+    }
+}
+
+/// Skip reason from GitHub metadata. An archived repo is skipped whatever
+/// it holds: nothing found in it can be fixed.
+pub fn classify(meta: &RepoMetadata) -> Option<SkipReason> {
+    let name = meta.name.to_lowercase();
+    let topic = |wanted: &[&str]| meta.topics.iter().any(|t| wanted.contains(&t.as_str()));
+    if meta.archived {
+        return Some(SkipReason::Archived);
+    }
+    if name.starts_with("awesome") || topic(&["awesome", "awesome-list"]) {
+        return Some(SkipReason::AwesomeList);
+    }
+    let docs_named = name == "docs"
+        || name.ends_with("-docs")
+        || name.ends_with("documentation")
+        || topic(&["docs", "documentation"]);
+    let rust = meta.language.as_deref() == Some("Rust");
+    let docs_language = meta
+        .language
+        .as_deref()
+        .is_some_and(|l| DOCS_LANGUAGES.contains(&l));
+    if (docs_named && !rust) || docs_language {
+        return Some(SkipReason::DocsOnly);
+    }
+    None
+}
+
+/// Skip reason from a checkout, for targets GitHub metadata couldn't
+/// classify: only a tree without any source file is skipped.
+pub fn classify_checkout(repo_path: &Path) -> Option<SkipReason> {
+    let has_source = WalkDir::new(repo_path)
+        .into_iter()
+        .filter_entry(|e| {
+            !(e.file_type().is_dir()
+                && SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(|e| e.ok())
+        .any(|e| {
+            e.file_type().is_file()
+                && e.path()
+                    .extension()
+                    .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext.to_string_lossy().as_ref()))
+        });
+    if has_source {
+        return None;
+    }
+    let readme = ["README.md", "readme.md", "README"]
+        .iter()
+        .find_map(|name| std::fs::read_to_string(repo_path.join(name)).ok())
+        .unwrap_or_default();
+    let title = readme
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .unwrap_or_default()
+        .trim_start_matches('#')
+        .trim()
+        .to_lowercase();
+    Some(if title.starts_with("awesome") {
+        SkipReason::AwesomeList
+    } else {
+        SkipReason::DocsOnly
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta(name: &str, language: Option<&str>, topics: &[&str]) -> RepoMetadata {
+        RepoMetadata {
+            name: name.into(),
+            archived: false,
+            language: language.map(String::from),
+            topics: topics.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn classifies_from_github_metadata() {
+        let archived = RepoMetadata {
+            archived: true,
+            ..meta("vault", Some("Rust"), &[])
+        };
+        assert_eq!(classify(&archived), Some(SkipReason::Archived));
+        assert_eq!(
+            classify(&meta("awesome-solana", None, &[])),
+            Some(SkipReason::AwesomeList)
+        );
+        assert_eq!(
+            classify(&meta("solana-resources", None, &["awesome-list"])),
+            Some(SkipReason::AwesomeList)
+        );
+        assert_eq!(
+            classify(&meta("marginfi-docs", Some("TypeScript"), &[])),
+            Some(SkipReason::DocsOnly)
+        );
+        assert_eq!(
+            classify(&meta("developer-content", Some("MDX"), &[])),
+            Some(SkipReason::DocsOnly)
+        );
+        // Code, or nothing to go on (empty and unusual repos): scanned.
+        assert_eq!(classify(&meta("vault", Some("Rust"), &["docs"])), None);
+        assert_eq!(
+            classify(&meta("jupiter-sdk", Some("TypeScript"), &[])),
+            None
+        );
+        assert_eq!(classify(&meta("new-program", None, &[])), None);
+    }
+
+    #[test]
+    fn classifies_checkouts_without_sources() {
+        let root = std::env::temp_dir().join(format!("solguard-repo-kind-{}", std::process::id()));
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write("README.md", "\n# Awesome Solana [![Awesome](badge.svg)]\n");
+        write("node_modules/lib/index.js", "module.exports = {};\n");
+        assert_eq!(classify_checkout(&root), Some(SkipReason::AwesomeList));
+
+        write("README.md", "# Protocol docs\n");
+        write("guides/deposit.md", "How to deposit\n");
+        assert_eq!(classify_checkout(&root), Some(SkipReason::DocsOnly));
+
+        write("programs/vault/src/lib.rs", "pub fn f() {}\n");
+        assert_eq!(classify_checkout(&root), None);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
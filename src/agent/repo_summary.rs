@@ -0,0 +1,205 @@
+//! Executive summary of one scanned repo, written after validation by the
+//! `summary` model: what the program is, where its risk sits and how it
+//! stands overall, in 5-8 sentences at the top of the repo's section of the
+//! report. Without it the report goes straight from a severity table into
+//! finding lists.
+
+use crate::llm::LlmClient;
+use crate::security::trust_graph::{AccountKind, TrustGraph};
+use crate::security::{SecurityFinding, ValidationStatus, framework, workspace};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+/// Findings listed in the prompt, most severe first.
+const MAX_FINDINGS_IN_PROMPT: usize = 25;
+/// Handlers named in the prompt.
+const MAX_HANDLERS_IN_PROMPT: usize = 20;
+/// Sentences kept from the response.
+const MAX_SENTENCES: usize = 8;
+
+const SYSTEM: &str = "You are a Solana security auditor writing the executive summary \
+                      of one repository in an audit report. Plain prose, no headings, \
+                      lists or markdown.";
+
+/// The summary, or `None` when the call fails or returns nothing usable;
+/// the report then shows the repo without one.
+pub async fn generate(
+    llm: &LlmClient,
+    repo: &str,
+    repo_path: &Path,
+    findings: &[SecurityFinding],
+    graph: Option<&TrustGraph>,
+) -> Option<String> {
+    let prompt = prompt(repo, repo_path, findings, graph);
+    match llm.complete(SYSTEM, &prompt).await {
+        Ok(text) => tidy(&text),
+        Err(e) => {
+            tracing::debug!(repo, error = %e, "repo summary failed, report shows none");
+            None
+        }
+    }
+}
+
+fn prompt(
+    repo: &str,
+    repo_path: &Path,
+    findings: &[SecurityFinding],
+    graph: Option<&TrustGraph>,
+) -> String {
+    let mut facts = Vec::new();
+    if let Some(framework) = framework::detect(repo_path) {
+        facts.push(format!("Framework: {}", framework.label()));
+    }
+    let programs: Vec<String> = workspace::programs(repo_path)
+        .into_iter()
+        .map(|p| p.name)
+        .collect();
+    if !programs.is_empty() {
+        facts.push(format!("Program crates: {}", programs.join(", ")));
+    }
+    if let Some(graph) = graph.filter(|g| !g.is_empty()) {
+        let handlers: Vec<&str> = graph
+            .handlers
+            .iter()
+            .take(MAX_HANDLERS_IN_PROMPT)
+            .map(|h| h.name.as_str())
+            .collect();
+        facts.push(format!(
+            "Instruction handlers ({}): {}",
+            graph.handlers.len(),
+            handlers.join(", ")
+        ));
+        let cpis: BTreeSet<&str> = graph
+            .handlers
+            .iter()
+            .flat_map(|h| h.cpis.iter().map(|c| c.program.as_str()))
+            .collect();
+        if !cpis.is_empty() {
+            facts.push(format!(
+                "CPI targets: {}",
+                cpis.into_iter().collect::<Vec<_>>().join(", ")
+            ));
+        }
+        let unchecked = graph
+            .handlers
+            .iter()
+            .flat_map(|h| &h.accounts)
+            .filter(|a| a.kind == AccountKind::Unchecked)
+            .count();
+        if unchecked > 0 {
+            facts.push(format!("Unchecked accounts across handlers: {unchecked}"));
+        }
+    }
+
+    let mut kept: Vec<&SecurityFinding> = findings
+        .iter()
+        .filter(|f| f.validation_status != ValidationStatus::Dismissed)
+        .collect();
+    kept.sort_by_key(|f| crate::severity::rank(&f.severity));
+    let listing: Vec<String> = kept
+        .iter()
+        .take(MAX_FINDINGS_IN_PROMPT)
+        .map(|f| {
+            let status = match f.validation_status {
+                ValidationStatus::Confirmed => ", confirmed",
+                ValidationStatus::Disputed => ", disputed",
+                _ => "",
+            };
+            format!(
+                "- [{}{status}] {}: {}",
+                f.severity,
+                f.title,
+                f.description.lines().next().unwrap_or_default()
+            )
+        })
+        .collect();
+    let findings_section = if listing.is_empty() {
+        "No findings.".to_string()
+    } else {
+        let more = kept.len().saturating_sub(MAX_FINDINGS_IN_PROMPT);
+        let mut section = listing.join("\n");
+        if more > 0 {
+            section.push_str(&format!("\n- ... and {more} less severe"));
+        }
+        section
+    };
+    format!(
+        "Repository: {repo}\n{}\n\nFindings after validation ({} total):\n{findings_section}\n\n\
+         Write a 5-8 sentence executive summary of this repository for the audit report: \
+         what it is and how it is built, its key risks and where they concentrate, and its \
+         overall security posture. Weigh confirmed findings over unvalidated ones and don't \
+         restate every finding. Don't quote file paths or code.",
+        facts.join("\n"),
+        kept.len(),
+    )
+}
+
+/// Whitespace collapsed, surrounding quotes and markdown emphasis dropped,
+/// cut to [`MAX_SENTENCES`].
+fn tidy(text: &str) -> Option<String> {
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    let text = text.trim_matches('"').replace("**", "");
+    let mut sentences = 0;
+    let mut end = text.len();
+    for (i, c) in text.char_indices() {
+        if matches!(c, '.' | '!' | '?')
+            && text[i + 1..].starts_with(' ')
+            && text[i + 1..].trim_start().starts_with(char::is_uppercase)
+        {
+            sentences += 1;
+            if sentences == MAX_SENTENCES {
+                end = i + 1;
+                break;
+            }
+        }
+    }
+    let text = text[..end].trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prompt_lists_surviving_findings_most_severe_first() {
+        let finding = |title: &str, severity: &str, status| SecurityFinding {
+            title: title.into(),
+            severity: severity.into(),
+            description: "First line.\nSecond line.".into(),
+            validation_status: status,
+            ..Default::default()
+        };
+        let findings = [
+            finding(
+                "Unchecked fee math",
+                "Medium",
+                ValidationStatus::Unvalidated,
+            ),
+            finding("Missing signer", "Critical", ValidationStatus::Confirmed),
+            finding("Stale oracle", "High", ValidationStatus::Dismissed),
+        ];
+        let prompt = prompt("vault", Path::new("/nonexistent"), &findings, None);
+        let signer = prompt.find("- [Critical, confirmed] Missing signer: First line.");
+        let fee = prompt.find("- [Medium] Unchecked fee math");
+        assert!(signer.unwrap() < fee.unwrap(), "{prompt}");
+        assert!(!prompt.contains("Stale oracle"));
+        assert!(prompt.contains("(2 total)"));
+        assert!(!prompt.contains("No findings."));
+        let empty = super::prompt("empty", Path::new("/nonexistent"), &[], None);
+        assert!(empty.contains("No findings."));
+    }
+
+    #[test]
+    fn tidy_collapses_and_caps_sentences() {
+        let long: String = (1..=10).map(|i| format!("Sentence {i}. ")).collect();
+        let tidied = tidy(&format!("\"{long}\"")).unwrap();
+        assert!(tidied.starts_with("Sentence 1."));
+        assert!(tidied.ends_with("Sentence 8."));
+        assert_eq!(
+            tidy("The **vault** holds\n  funds, e.g. SOL.").as_deref(),
+            Some("The vault holds funds, e.g. SOL.")
+        );
+        assert!(tidy("  \n ").is_none());
+    }
+}
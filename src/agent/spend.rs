@@ -0,0 +1,157 @@
+//! Spend guardrail: before a deep run starts, sum the per-repo budgets it
+//! may spend and require `--yes` (or an interactive "y") when the total is
+//! above `[budget] confirm_above_usd`. A misconfigured `cost_limit_usd`
+//! multiplied across dozens of targets is otherwise only noticed on the bill.
+
+use anyhow::{Result, bail};
+use std::fmt::Write as _;
+use std::io::{BufRead, IsTerminal, Write as _};
+use std::sync::Mutex;
+
+/// Upper bound on a run's deep-review spend, per repo. Repos later skipped
+/// as inactive or downranked to static-only spend nothing.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpendEstimate {
+    /// Repo name and its budget in USD (investigation plus validation).
+    pub repos: Vec<(String, f64)>,
+}
+
+impl SpendEstimate {
+    pub fn total(&self) -> f64 {
+        self.repos.iter().map(|(_, usd)| usd).sum()
+    }
+
+    /// Per-repo breakdown, largest first, with the total.
+    pub fn render(&self) -> String {
+        let mut rows: Vec<&(String, f64)> = self.repos.iter().collect();
+        rows.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let width = rows.iter().map(|(r, _)| r.len()).max().unwrap_or(0).max(5);
+        let mut out = String::from("Estimated deep-review spend (upper bound):\n");
+        for (repo, usd) in rows {
+            let _ = writeln!(out, "  {repo:<width$}  ${usd:>7.2}");
+        }
+        let _ = writeln!(out, "  {:<width$}  ${:>7.2}", "total", self.total());
+        out
+    }
+}
+
+/// Let the run proceed if the estimate is within `threshold_usd`, `--yes`
+/// was given, or the user confirms on a terminal. Otherwise fail with the
+/// breakdown so nothing is spent.
+pub fn confirm(estimate: &SpendEstimate, threshold_usd: f64, assume_yes: bool) -> Result<()> {
+    if estimate.total() <= threshold_usd || assume_yes {
+        return Ok(());
+    }
+    eprint!("{}", estimate.render());
+    let stdin = std::io::stdin();
+    if !stdin.is_terminal() {
+        bail!(
+            "estimated spend ${:.2} exceeds [budget] confirm_above_usd (${threshold_usd:.2}); \
+             pass --yes to proceed",
+            estimate.total()
+        );
+    }
+    eprint!("This exceeds [budget] confirm_above_usd (${threshold_usd:.2}). Proceed? [y/N] ");
+    std::io::stderr().flush()?;
+    let mut answer = String::new();
+    stdin.lock().read_line(&mut answer)?;
+    if !is_yes(&answer) {
+        bail!("aborted: deep run not confirmed");
+    }
+    Ok(())
+}
+
+/// A deep run's review budget, shared by repos scanning concurrently. Each
+/// repo reserves its budget before starting and settles what it actually
+/// spent when done, so the run total holds however many scans overlap.
+#[derive(Debug)]
+pub struct RunBudget {
+    total_usd: f64,
+    /// (reserved or spent so far, spent by settled repos)
+    state: Mutex<(f64, f64)>,
+}
+
+impl RunBudget {
+    pub fn new(total_usd: f64) -> Self {
+        Self {
+            total_usd,
+            state: Mutex::new((0.0, 0.0)),
+        }
+    }
+
+    /// Up to `usd` of what is left; zero once the run's budget is committed.
+    pub fn reserve(&self, usd: f64) -> f64 {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let granted = usd.min((self.total_usd - state.0).max(0.0)).max(0.0);
+        state.0 += granted;
+        granted
+    }
+
+    /// Replace a reservation with what was spent against it. Agents check
+    /// limits between turns, so `spent` may exceed `reserved`.
+    pub fn settle(&self, reserved: f64, spent: f64) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.0 += spent - reserved;
+        state.1 += spent;
+    }
+
+    /// Spent by repos settled so far.
+    pub fn spent(&self) -> f64 {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).1
+    }
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate() -> SpendEstimate {
+        SpendEstimate {
+            repos: vec![
+                ("jito-programs".into(), 5.0),
+                ("raydium-cp-swap".into(), 14.14),
+                ("switchboard".into(), 5.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn renders_breakdown_largest_first() {
+        let text = estimate().render();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[1], "  raydium-cp-swap  $  14.14");
+        assert_eq!(lines[2], "  jito-programs    $   5.00");
+        assert_eq!(lines.last().unwrap(), &"  total            $  24.14");
+    }
+
+    #[test]
+    fn within_threshold_or_yes_proceeds() {
+        assert!(confirm(&estimate(), 50.0, false).is_ok());
+        assert!(confirm(&estimate(), 10.0, true).is_ok());
+        assert!(confirm(&SpendEstimate::default(), 0.0, false).is_ok());
+    }
+
+    #[test]
+    fn run_budget_reserves_from_one_pool() {
+        let budget = RunBudget::new(10.0);
+        assert!((budget.reserve(6.0) - 6.0).abs() < 1e-9);
+        // A concurrent repo only gets what the first left.
+        assert!((budget.reserve(6.0) - 4.0).abs() < 1e-9);
+        assert_eq!(budget.reserve(1.0), 0.0);
+        // Unspent reservation returns to the pool; overshoot comes out of it.
+        budget.settle(6.0, 2.5);
+        assert!((budget.reserve(5.0) - 3.5).abs() < 1e-9);
+        budget.settle(4.0, 4.5);
+        assert!((budget.spent() - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn answers() {
+        assert!(is_yes("y\n") && is_yes(" YES "));
+        assert!(!is_yes("\n") && !is_yes("no") && !is_yes("yep"));
+    }
+}
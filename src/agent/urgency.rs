@@ -0,0 +1,312 @@
+//! Urgency tiers for target selection. Targets are scanned most urgent first,
+//! so when the budget runs out it is the low-urgency repos that miss out:
+//!
+//! - `immediate`: the narrative is accelerating and either cites a TVL of at
+//!   least `immediate_tvl_usd` or the repo had findings last time it was scanned.
+//! - `defer`: the narrative isn't accelerating, its TVL is below that bar, and
+//!   the repo scanned clean within `rescan_after_runs` runs.
+//! - `this-run`: everything else, including `always_scan` repos and repos
+//!   deferred by the previous run, which are never deferred twice in a row.
+//!
+//! With `defer_low_urgency` on, `defer`-tier repos are queued in run memory
+//! for the next run instead of being scanned last. Targets the previous run
+//! ran out of budget or time for are carried over as `immediate`.
+
+use super::narrative_for;
+use crate::config::TargetsConfig;
+use crate::memory::{RepoScan, RunMemory};
+use crate::narrative::Narrative;
+use crate::paths;
+use tracing::info;
+
+/// How soon a target should be scanned. Ordered most urgent first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tier {
+    Immediate,
+    ThisRun,
+    Defer,
+}
+
+impl Tier {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Immediate => "immediate",
+            Self::ThisRun => "this-run",
+            Self::Defer => "defer",
+        }
+    }
+}
+
+/// A target's tier and why it got it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Urgency {
+    pub tier: Tier,
+    pub reason: String,
+}
+
+/// Tier for one target. `runs_completed` is `RunMemory::total_runs` before
+/// this run; `queued` is whether the previous run deferred it.
+pub fn classify(
+    narrative: Option<&Narrative>,
+    last_scan: Option<&RepoScan>,
+    runs_completed: u32,
+    queued: bool,
+    cfg: &TargetsConfig,
+) -> Urgency {
+    let urgency = |tier, reason: String| Urgency { tier, reason };
+    if queued {
+        return urgency(Tier::ThisRun, "deferred by the previous run".into());
+    }
+    let Some(n) = narrative else {
+        return urgency(Tier::ThisRun, "no narrative".into());
+    };
+    let accelerating = n.trend.eq_ignore_ascii_case("accelerating");
+    let high_tvl =
+        cfg.immediate_tvl_usd > 0.0 && n.tvl_usd.is_some_and(|tvl| tvl >= cfg.immediate_tvl_usd);
+    let had_findings = last_scan.is_some_and(|s| s.findings > 0);
+
+    if accelerating && high_tvl {
+        return urgency(
+            Tier::Immediate,
+            format!("accelerating narrative, {}", tvl_text(n)),
+        );
+    }
+    if accelerating && had_findings {
+        return urgency(
+            Tier::Immediate,
+            "accelerating narrative, findings last scan".into(),
+        );
+    }
+    if let Some(scan) = last_scan
+        && !accelerating
+        && !high_tvl
+        && scan.findings == 0
+    {
+        let runs_ago = (runs_completed + 1).saturating_sub(scan.run);
+        if runs_ago <= cfg.rescan_after_runs {
+            let plural = if runs_ago == 1 { "" } else { "s" };
+            return urgency(
+                Tier::Defer,
+                format!(
+                    "{} narrative, clean scan {runs_ago} run{plural} ago",
+                    n.trend.to_lowercase()
+                ),
+            );
+        }
+    }
+    let reason = if accelerating {
+        "accelerating narrative".into()
+    } else if high_tvl {
+        tvl_text(n)
+    } else {
+        format!("{} narrative", n.trend.to_lowercase())
+    };
+    urgency(Tier::ThisRun, reason)
+}
+
+fn tvl_text(n: &Narrative) -> String {
+    format!("${:.0}M TVL", n.tvl_usd.unwrap_or_default() / 1e6)
+}
+
+/// Add the targets the previous run deferred or carried over (unless since
+/// blocklisted), tier everything, and order
+/// `targets` most urgent first. Returns the targets to queue for the next
+/// run (removed from `targets`); empty unless `defer_low_urgency` is on.
+pub fn schedule(
+    targets: &mut Vec<String>,
+    narratives: &[Narrative],
+    memory: &RunMemory,
+    cfg: &TargetsConfig,
+) -> Vec<String> {
+    let carried = memory.carried_over.iter().map(|c| &c.target);
+    for queued in carried.chain(&memory.deferred_targets) {
+        let name = paths::target_name(queued);
+        if !targets.iter().any(|t| paths::target_name(t) == name)
+            && !memory.repo_blocklist.iter().any(|b| b == name)
+        {
+            targets.push(queued.clone());
+        }
+    }
+
+    let mut tiered: Vec<(Tier, bool, String)> = targets
+        .drain(..)
+        .map(|target| {
+            let name = paths::target_name(&target);
+            let queued = memory
+                .deferred_targets
+                .iter()
+                .any(|q| paths::target_name(q) == name);
+            let carried = memory
+                .carried_over
+                .iter()
+                .find(|c| paths::target_name(&c.target) == name);
+            let u = match carried {
+                Some(c) => Urgency {
+                    tier: Tier::Immediate,
+                    reason: format!("carried over: {}", c.reason),
+                },
+                None => classify(
+                    narrative_for(narratives, name),
+                    memory.repo_scans.get(name),
+                    memory.total_runs,
+                    queued,
+                    cfg,
+                ),
+            };
+            info!(repo = %target, tier = u.tier.label(), reason = %u.reason, "target urgency");
+            (u.tier, carried.is_some(), target)
+        })
+        .collect();
+    // Stable, so targets keep their order within a tier; carried-over
+    // targets, already passed over once, lead theirs.
+    tiered.sort_by_key(|(tier, carried, _)| (*tier, !carried));
+
+    let mut deferred = Vec::new();
+    for (tier, _, target) in tiered {
+        if tier == Tier::Defer && cfg.defer_low_urgency {
+            deferred.push(target);
+        } else {
+            targets.push(target);
+        }
+    }
+    deferred
+}
+
+/// Narratives of carried-over targets that no current narrative covers,
+/// each narrowed to its target, so those targets keep their context.
+pub fn carried_narratives(narratives: &[Narrative], memory: &RunMemory) -> Vec<Narrative> {
+    memory
+        .carried_over
+        .iter()
+        .filter(|c| narrative_for(narratives, paths::target_name(&c.target)).is_none())
+        .filter_map(|c| c.narrative.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::CarriedTarget;
+
+    fn narrative(trend: &str, tvl_usd: Option<f64>, repos: &[&str]) -> Narrative {
+        Narrative {
+            title: format!("{trend} narrative"),
+            trend: trend.into(),
+            tvl_usd,
+            active_repos: repos.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn scan(run: u32, findings: usize) -> RepoScan {
+        RepoScan { run, findings }
+    }
+
+    #[test]
+    fn tiers_follow_trend_tvl_and_history() {
+        let cfg = TargetsConfig::default();
+        let hot = narrative("Accelerating", Some(250e6), &[]);
+        let rising = narrative("Accelerating", Some(5e6), &[]);
+        let stable = narrative("Stable", None, &[]);
+        let tier = |n: &Narrative, s: Option<RepoScan>, queued| {
+            classify(Some(n), s.as_ref(), 10, queued, &cfg).tier
+        };
+
+        let immediate = classify(Some(&hot), None, 10, false, &cfg);
+        assert_eq!(immediate.tier, Tier::Immediate);
+        assert_eq!(immediate.reason, "accelerating narrative, $250M TVL");
+        assert_eq!(tier(&rising, Some(scan(9, 2)), false), Tier::Immediate);
+        assert_eq!(tier(&rising, None, false), Tier::ThisRun);
+        assert_eq!(tier(&rising, Some(scan(10, 0)), false), Tier::ThisRun);
+
+        let deferred = classify(Some(&stable), Some(&scan(10, 0)), 10, false, &cfg);
+        assert_eq!(deferred.tier, Tier::Defer);
+        assert_eq!(deferred.reason, "stable narrative, clean scan 1 run ago");
+        // Too long ago, had findings, never scanned, or already deferred once.
+        assert_eq!(tier(&stable, Some(scan(7, 0)), false), Tier::ThisRun);
+        assert_eq!(tier(&stable, Some(scan(10, 1)), false), Tier::ThisRun);
+        assert_eq!(tier(&stable, None, false), Tier::ThisRun);
+        assert_eq!(tier(&stable, Some(scan(10, 0)), true), Tier::ThisRun);
+        // High TVL keeps a stable narrative's repos in this run.
+        let big = narrative("Stable", Some(1e9), &[]);
+        assert_eq!(tier(&big, Some(scan(10, 0)), false), Tier::ThisRun);
+        assert_eq!(
+            classify(None, Some(&scan(10, 0)), 10, false, &cfg).tier,
+            Tier::ThisRun
+        );
+    }
+
+    #[test]
+    fn schedule_orders_by_tier_and_queues_deferred() {
+        let narratives = vec![
+            narrative("Stable", None, &["acme/amm", "acme/vault"]),
+            narrative("Accelerating", Some(2e9), &["acme/perps"]),
+        ];
+        let mut memory = RunMemory {
+            total_runs: 4,
+            deferred_targets: vec!["acme/lending".into(), "acme/dead".into()],
+            repo_blocklist: vec!["dead".into()],
+            ..Default::default()
+        };
+        memory.repo_scans.insert("amm".into(), scan(4, 0));
+        let mut targets: Vec<String> = ["acme/amm", "acme/perps", "acme/vault"]
+            .map(String::from)
+            .to_vec();
+
+        let mut cfg = TargetsConfig::default();
+        let mut ordered = targets.clone();
+        assert!(schedule(&mut ordered, &narratives, &memory, &cfg).is_empty());
+        assert_eq!(
+            ordered,
+            vec!["acme/perps", "acme/vault", "acme/lending", "acme/amm"]
+        );
+
+        cfg.defer_low_urgency = true;
+        let queued = schedule(&mut targets, &narratives, &memory, &cfg);
+        assert_eq!(queued, vec!["acme/amm"]);
+        assert_eq!(targets, vec!["acme/perps", "acme/vault", "acme/lending"]);
+    }
+
+    #[test]
+    fn carried_over_targets_lead_with_their_narrative() {
+        let narratives = vec![narrative("Accelerating", Some(2e9), &["acme/perps"])];
+        let oracles = Narrative {
+            title: "Oracle networks".into(),
+            active_repos: vec!["acme/oracle".into()],
+            ..Default::default()
+        };
+        let memory = RunMemory {
+            carried_over: vec![
+                CarriedTarget {
+                    target: "acme/oracle".into(),
+                    reason: "run budget exhausted".into(),
+                    narrative: Some(oracles),
+                    since_run: 3,
+                },
+                CarriedTarget {
+                    target: "acme/perps".into(),
+                    reason: "run time limit reached".into(),
+                    narrative: None,
+                    since_run: 3,
+                },
+            ],
+            deferred_targets: vec!["acme/amm".into()],
+            ..Default::default()
+        };
+        let mut targets = vec!["acme/vault".to_string(), "acme/perps".to_string()];
+        schedule(
+            &mut targets,
+            &narratives,
+            &memory,
+            &TargetsConfig::default(),
+        );
+        assert_eq!(
+            targets,
+            vec!["acme/perps", "acme/oracle", "acme/vault", "acme/amm"]
+        );
+
+        let carried = carried_narratives(&narratives, &memory);
+        assert_eq!(carried.len(), 1);
+        assert_eq!(carried[0].title, "Oracle networks");
+    }
+}
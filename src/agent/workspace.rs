@@ -0,0 +1,161 @@
+//! Per-run clone workspaces (`run --isolate`).
+//!
+//! By default every run clones into, and reuses, the shared `repos/`
+//! directory, so two runs can scan different checkouts of the same repo
+//! and an interrupted clone is picked up half-written. An isolated run
+//! clones each target fresh into `repos/runs/<run-id>/` and records where
+//! every checkout came from in that directory's `manifest.json`.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Provenance of every clone in a workspace.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub run_id: String,
+    pub created_at: String,
+    pub repos: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub repo: String,
+    pub url: String,
+    /// `HEAD` of the clone; `None` if git couldn't resolve it.
+    pub sha: Option<String>,
+    pub cloned_at: String,
+    /// Checkout size on disk, `.git` included.
+    pub size_bytes: u64,
+}
+
+pub struct Workspace {
+    pub dir: PathBuf,
+    manifest: Manifest,
+}
+
+impl Workspace {
+    /// A fresh `runs/<run-id>` directory under `repos_dir`, with an empty
+    /// manifest written.
+    pub fn create(repos_dir: &Path) -> Result<Self> {
+        let runs = repos_dir.join("runs");
+        std::fs::create_dir_all(&runs).with_context(|| format!("creating {}", runs.display()))?;
+        let stamp = format!(
+            "{}-{}",
+            Utc::now().format("%Y%m%dT%H%M%SZ"),
+            std::process::id()
+        );
+        // `create_dir` fails on an existing directory, so two runs never share one.
+        let mut run_id = stamp.clone();
+        let mut n = 1;
+        while let Err(e) = std::fs::create_dir(runs.join(&run_id)) {
+            if e.kind() != std::io::ErrorKind::AlreadyExists {
+                return Err(e).with_context(|| format!("creating workspace in {}", runs.display()));
+            }
+            run_id = format!("{stamp}-{n}");
+            n += 1;
+        }
+        let workspace = Self {
+            dir: runs.join(&run_id),
+            manifest: Manifest {
+                run_id,
+                created_at: Utc::now().to_rfc3339(),
+                repos: Vec::new(),
+            },
+        };
+        workspace.save()?;
+        Ok(workspace)
+    }
+
+    pub fn repo_path(&self, repo_name: &str) -> PathBuf {
+        self.dir.join(repo_name)
+    }
+
+    pub fn manifest_path(&self) -> PathBuf {
+        self.dir.join(MANIFEST_FILE)
+    }
+
+    pub fn manifest(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    /// Add a finished clone to the manifest and rewrite it, so the manifest
+    /// is complete up to the last clone even if the run dies later.
+    pub async fn record(&mut self, repo: &str, url: &str) -> Result<()> {
+        let path = self.repo_path(repo);
+        self.manifest.repos.push(ManifestEntry {
+            repo: repo.to_string(),
+            url: url.to_string(),
+            sha: head_sha(&path).await,
+            cloned_at: Utc::now().to_rfc3339(),
+            size_bytes: dir_size(&path),
+        });
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        let path = self.manifest_path();
+        std::fs::write(&path, serde_json::to_string_pretty(&self.manifest)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+/// Full hash of the commit checked out at `repo_path`.
+pub(crate) async fn head_sha(repo_path: &Path) -> Option<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .await
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Total size of the regular files under `path`.
+pub fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn workspaces_are_unique_and_record_provenance() {
+        let repos = std::env::temp_dir().join(format!("solguard-workspace-{}", std::process::id()));
+        let mut a = Workspace::create(&repos).unwrap();
+        let b = Workspace::create(&repos).unwrap();
+        assert_ne!(a.dir, b.dir);
+        assert!(a.dir.starts_with(repos.join("runs")));
+
+        std::fs::create_dir_all(a.repo_path("vault/src")).unwrap();
+        std::fs::write(a.repo_path("vault/src/lib.rs"), "fn main() {}\n").unwrap();
+        a.record("vault", "https://github.com/acme/vault")
+            .await
+            .unwrap();
+
+        let saved: Manifest =
+            serde_json::from_str(&std::fs::read_to_string(a.manifest_path()).unwrap()).unwrap();
+        std::fs::remove_dir_all(&repos).ok();
+        assert_eq!(&saved, a.manifest());
+        assert_eq!(saved.repos.len(), 1);
+        let entry = &saved.repos[0];
+        assert_eq!(entry.url, "https://github.com/acme/vault");
+        assert_eq!(entry.size_bytes, 13);
+        assert!(head_sha(&repos.join("missing")).await.is_none());
+    }
+}
@@ -0,0 +1,373 @@
+//! Differences between two saved runs (`~/.solguard/history`): how each
+//! narrative's confidence and risk score moved, and which findings each repo
+//! gained or lost.
+//!
+//! Narratives are matched by title, ignoring case and punctuation; a
+//! narrative the synthesizer renamed shows up as one gone and one new.
+//! Findings are matched by stable ID, so churn is only known for repos both
+//! runs scanned successfully and recorded IDs for.
+
+use crate::memory::{RepoResult, RunHistory};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RunComparison {
+    pub run_a: String,
+    pub run_b: String,
+    pub narratives: Vec<NarrativeDelta>,
+    pub repos: Vec<RepoChurn>,
+}
+
+/// One narrative across both runs; `None` sides are runs it was absent from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NarrativeDelta {
+    pub title: String,
+    pub confidence_a: Option<f64>,
+    pub confidence_b: Option<f64>,
+    pub confidence_delta: Option<f64>,
+    pub risk_score_a: Option<f64>,
+    pub risk_score_b: Option<f64>,
+    pub risk_score_delta: Option<f64>,
+    pub risk_level_a: Option<String>,
+    pub risk_level_b: Option<String>,
+}
+
+/// One repo's findings across both runs. Counts are `None` for runs that
+/// didn't scan the repo or failed to.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RepoChurn {
+    pub repo: String,
+    pub findings_a: Option<usize>,
+    pub findings_b: Option<usize>,
+    /// `None` when a side is missing, or saved before finding IDs were
+    /// recorded.
+    pub churn: Option<Churn>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Churn {
+    /// IDs only in the second run.
+    pub new: Vec<String>,
+    /// IDs only in the first run.
+    pub resolved: Vec<String>,
+    pub persisting: usize,
+}
+
+/// Compare run `a` with the later run `b`.
+pub fn compare(a: &RunHistory, b: &RunHistory) -> RunComparison {
+    RunComparison {
+        run_a: a.timestamp.clone(),
+        run_b: b.timestamp.clone(),
+        narratives: narrative_deltas(a, b),
+        repos: repo_churn(a, b),
+    }
+}
+
+/// Narratives of `b` in report order (riskiest first), then those only `a` had.
+fn narrative_deltas(a: &RunHistory, b: &RunHistory) -> Vec<NarrativeDelta> {
+    let mut remaining: Vec<_> = a.narratives.iter().collect();
+    let mut deltas = Vec::new();
+    for nb in &b.narratives {
+        let na = remaining
+            .iter()
+            .position(|na| title_key(&na.title) == title_key(&nb.title))
+            .map(|i| remaining.remove(i));
+        deltas.push(NarrativeDelta {
+            title: nb.title.clone(),
+            confidence_a: na.map(|n| n.confidence),
+            confidence_b: Some(nb.confidence),
+            confidence_delta: na.map(|n| nb.confidence - n.confidence),
+            risk_score_a: na.map(|n| n.risk_score),
+            risk_score_b: Some(nb.risk_score),
+            risk_score_delta: na.map(|n| nb.risk_score - n.risk_score),
+            risk_level_a: na.map(|n| n.risk_level.clone()),
+            risk_level_b: Some(nb.risk_level.clone()),
+        });
+    }
+    deltas.extend(remaining.into_iter().map(|na| NarrativeDelta {
+        title: na.title.clone(),
+        confidence_a: Some(na.confidence),
+        confidence_b: None,
+        confidence_delta: None,
+        risk_score_a: Some(na.risk_score),
+        risk_score_b: None,
+        risk_score_delta: None,
+        risk_level_a: Some(na.risk_level.clone()),
+        risk_level_b: None,
+    }));
+    deltas
+}
+
+fn title_key(title: &str) -> String {
+    title
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Every repo either run scanned, by name.
+fn repo_churn(a: &RunHistory, b: &RunHistory) -> Vec<RepoChurn> {
+    let scanned = |run: &RunHistory| -> BTreeMap<String, RepoResult> {
+        run.repo_results
+            .iter()
+            .filter(|r| r.was_scanned())
+            .map(|r| (r.name.clone(), r.clone()))
+            .collect()
+    };
+    let (scanned_a, scanned_b) = (scanned(a), scanned(b));
+    let names: BTreeSet<&String> = a
+        .repo_results
+        .iter()
+        .chain(&b.repo_results)
+        .map(|r| &r.name)
+        .collect();
+    names
+        .into_iter()
+        .map(|name| {
+            let (ra, rb) = (scanned_a.get(name), scanned_b.get(name));
+            RepoChurn {
+                repo: name.clone(),
+                findings_a: ra.map(|r| r.findings_count),
+                findings_b: rb.map(|r| r.findings_count),
+                churn: ra.zip(rb).and_then(|(ra, rb)| churn(ra, rb)),
+            }
+        })
+        .collect()
+}
+
+fn churn(a: &RepoResult, b: &RepoResult) -> Option<Churn> {
+    let ids = |r: &RepoResult| {
+        (r.findings_count == 0 || !r.finding_ids.is_empty())
+            .then(|| r.finding_ids.iter().cloned().collect::<BTreeSet<_>>())
+    };
+    let (ids_a, ids_b) = (ids(a)?, ids(b)?);
+    Some(Churn {
+        new: ids_b.difference(&ids_a).cloned().collect(),
+        resolved: ids_a.difference(&ids_b).cloned().collect(),
+        persisting: ids_a.intersection(&ids_b).count(),
+    })
+}
+
+impl RunComparison {
+    /// Narrative and repo tables, with each repo's new and resolved IDs
+    /// listed under it.
+    pub fn render(&self) -> String {
+        let mut out = format!("{} -> {}\n", self.run_a, self.run_b);
+
+        if self.narratives.is_empty() {
+            out.push_str("\nNo narratives recorded in either run.\n");
+        } else {
+            let width = column_width(
+                self.narratives.iter().map(|n| n.title.as_str()),
+                "narrative",
+            );
+            let _ = writeln!(
+                out,
+                "\n{:<width$}  {:>17}  {:>8}  {:>19}  {:>8}  risk level",
+                "narrative", "confidence", "delta", "risk score", "delta"
+            );
+            for n in &self.narratives {
+                let level = match (&n.risk_level_a, &n.risk_level_b) {
+                    (Some(a), Some(b)) if a != b => format!("{a} -> {b}"),
+                    (_, Some(level)) | (Some(level), None) => level.clone(),
+                    (None, None) => String::new(),
+                };
+                let _ = writeln!(
+                    out,
+                    "{:<width$}  {:>17}  {:>8}  {:>19}  {:>8}  {level}",
+                    n.title,
+                    transition(n.confidence_a, n.confidence_b, 2),
+                    delta(n.confidence_delta, 2),
+                    transition(n.risk_score_a, n.risk_score_b, 1),
+                    delta(n.risk_score_delta, 1),
+                );
+            }
+        }
+
+        if self.repos.is_empty() {
+            out.push_str("\nNo repos scanned in either run.\n");
+            return out;
+        }
+        let width = column_width(self.repos.iter().map(|r| r.repo.as_str()), "repo");
+        let _ = writeln!(
+            out,
+            "\n{:<width$}  {:>10}  {:>4}  {:>8}  {:>10}",
+            "repo", "findings", "new", "resolved", "persisting"
+        );
+        for r in &self.repos {
+            let count = |c: Option<usize>| c.map_or("-".to_string(), |c| c.to_string());
+            let (new, resolved, persisting) = match &r.churn {
+                Some(c) => (
+                    c.new.len().to_string(),
+                    c.resolved.len().to_string(),
+                    c.persisting.to_string(),
+                ),
+                None => ("-".into(), "-".into(), "-".into()),
+            };
+            let _ = writeln!(
+                out,
+                "{:<width$}  {:>10}  {new:>4}  {resolved:>8}  {persisting:>10}",
+                r.repo,
+                format!("{} -> {}", count(r.findings_a), count(r.findings_b)),
+            );
+            if let Some(c) = &r.churn {
+                for id in &c.new {
+                    let _ = writeln!(out, "  + {id}");
+                }
+                for id in &c.resolved {
+                    let _ = writeln!(out, "  - {id}");
+                }
+            }
+        }
+        out
+    }
+}
+
+fn column_width<'a>(values: impl Iterator<Item = &'a str>, header: &str) -> usize {
+    values.map(str::len).max().unwrap_or(0).max(header.len())
+}
+
+fn transition(a: Option<f64>, b: Option<f64>, precision: usize) -> String {
+    let side = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{v:.precision$}"));
+    format!("{} -> {}", side(a), side(b))
+}
+
+fn delta(d: Option<f64>, precision: usize) -> String {
+    d.map_or("-".to_string(), |d| format!("{d:+.precision$}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::NarrativeOutcome;
+
+    fn narrative(title: &str, confidence: f64, risk_score: f64, level: &str) -> NarrativeOutcome {
+        NarrativeOutcome {
+            title: title.into(),
+            confidence,
+            risk_score,
+            risk_level: level.into(),
+        }
+    }
+
+    fn repo(name: &str, ids: &[&str], error: Option<&str>) -> RepoResult {
+        RepoResult {
+            name: name.into(),
+            findings_count: ids.len(),
+            errors: error.map(String::from).into_iter().collect(),
+            budget: None,
+            finding_ids: ids.iter().map(|id| id.to_string()).collect(),
+            skipped: None,
+            waived: Vec::new(),
+            summary: None,
+        }
+    }
+
+    fn run(
+        timestamp: &str,
+        narratives: Vec<NarrativeOutcome>,
+        repos: Vec<RepoResult>,
+    ) -> RunHistory {
+        RunHistory {
+            timestamp: timestamp.into(),
+            narratives,
+            repo_results: repos,
+            ..RunHistory::new()
+        }
+    }
+
+    #[test]
+    fn diffs_narratives_and_finding_churn() {
+        let a = run(
+            "20261001_120000",
+            vec![
+                narrative("Restaking Vaults", 0.6, 8.0, "Medium"),
+                narrative("Memecoin Launchpads", 0.7, 3.0, "Low"),
+            ],
+            vec![
+                repo("vault", &["SG-2026-vault-0001", "SG-2026-vault-0002"], None),
+                repo("launchpad", &[], Some("clone failed")),
+                repo("oracle", &["SG-2026-oracle-0001"], None),
+            ],
+        );
+        let b = run(
+            "20261008_120000",
+            vec![
+                narrative("restaking vaults", 0.8, 21.5, "Critical"),
+                narrative("Perp DEXes", 0.5, 0.0, "None"),
+            ],
+            vec![
+                repo("vault", &["SG-2026-vault-0002", "SG-2026-vault-0003"], None),
+                repo("launchpad", &["SG-2026-launchpad-0001"], None),
+                RepoResult {
+                    finding_ids: Vec::new(),
+                    skipped: None,
+                    waived: Vec::new(),
+                    summary: None,
+                    ..repo("oracle", &["x", "y"], None)
+                },
+            ],
+        );
+        let cmp = compare(&a, &b);
+
+        let titles: Vec<_> = cmp.narratives.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            ["restaking vaults", "Perp DEXes", "Memecoin Launchpads"]
+        );
+        let restaking = &cmp.narratives[0];
+        assert!((restaking.confidence_delta.unwrap() - 0.2).abs() < 1e-9);
+        assert!((restaking.risk_score_delta.unwrap() - 13.5).abs() < 1e-9);
+        assert_eq!(cmp.narratives[1].confidence_a, None);
+        assert_eq!(cmp.narratives[2].confidence_b, None);
+
+        let repos: Vec<_> = cmp.repos.iter().map(|r| r.repo.as_str()).collect();
+        assert_eq!(repos, ["launchpad", "oracle", "vault"]);
+        // A failed scan doesn't resolve anything.
+        assert_eq!(cmp.repos[0].findings_a, None);
+        assert_eq!(cmp.repos[0].churn, None);
+        // Counts without IDs: no churn.
+        assert_eq!(cmp.repos[1].findings_b, Some(2));
+        assert_eq!(cmp.repos[1].churn, None);
+        assert_eq!(
+            cmp.repos[2].churn,
+            Some(Churn {
+                new: vec!["SG-2026-vault-0003".into()],
+                resolved: vec!["SG-2026-vault-0001".into()],
+                persisting: 1,
+            })
+        );
+
+        let table = cmp.render();
+        assert!(table.starts_with("20261001_120000 -> 20261008_120000\n"));
+        assert!(table.contains("0.60 -> 0.80"));
+        assert!(table.contains("+13.5"));
+        assert!(table.contains("Medium -> Critical"));
+        assert!(table.contains("  + SG-2026-vault-0003\n  - SG-2026-vault-0001\n"));
+    }
+
+    #[test]
+    fn loads_run_by_timestamp_prefix() {
+        let dir = std::env::temp_dir().join(format!("solguard-history-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for timestamp in ["20261001_120000", "20261001_180000", "20261002_090000"] {
+            let json = serde_json::to_string(&run(timestamp, Vec::new(), Vec::new())).unwrap();
+            std::fs::write(dir.join(format!("{timestamp}.json")), json).unwrap();
+        }
+        assert_eq!(
+            RunHistory::load_from(&dir, "20261001").unwrap().timestamp,
+            "20261001_180000"
+        );
+        assert_eq!(
+            RunHistory::load_from(&dir, "20261002_090000")
+                .unwrap()
+                .timestamp,
+            "20261002_090000"
+        );
+        assert!(RunHistory::load_from(&dir, "2025").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
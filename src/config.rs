@@ -1,10 +1,19 @@
 use crate::error::{Error, Result};
+use crate::security::Severity;
+use crate::security::custom_patterns::{self, CustomPattern};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-#[derive(Debug, Deserialize)]
+/// Prefix for environment overrides: `SOLGUARD_LLM__MODEL` → `llm.model`.
+pub const ENV_PREFIX: &str = "SOLGUARD_";
+
+#[derive(Debug, Default, Deserialize)]
 pub struct Config {
+    #[serde(default)]
     pub github: GitHubConfig,
+    #[serde(default)]
     pub solana: SolanaConfig,
     #[serde(default)]
     pub social: SocialConfig,
@@ -12,6 +21,9 @@ pub struct Config {
     pub defi_llama: DefiLlamaConfig,
     #[serde(default)]
     pub discovery: DiscoveryConfig,
+    #[serde(default)]
+    pub governance: GovernanceConfig,
+    #[serde(default)]
     pub llm: LlmConfig,
     #[serde(default)]
     pub models: Option<ModelsConfig>,
@@ -19,18 +31,539 @@ pub struct Config {
     pub agent_review: AgentReviewConfig,
     #[serde(default)]
     pub targets: TargetsConfig,
+    #[serde(default)]
+    pub tagging: TaggingConfig,
+    #[serde(default, alias = "scanner")]
+    pub scan: ScanConfig,
+    #[serde(default)]
+    pub alerts: AlertConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub watch: WatchConfig,
+    #[serde(default)]
+    pub report: ReportConfig,
+    #[serde(default)]
+    pub explore: ExploreConfig,
+    #[serde(default)]
+    pub validation: ValidationConfig,
+    #[serde(default)]
+    pub budget: BudgetConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub narrative_quality: NarrativeQualityConfig,
 }
 
-#[derive(Debug, Deserialize, Default)]
+/// Post-synthesis narrative scoring (`[narrative_quality]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct NarrativeQualityConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Narratives scoring below this (0.0–1.0) are dropped.
+    #[serde(default = "default_min_quality")]
+    pub min_score: f64,
+    /// Also have the triage model rate each narrative; the final score is
+    /// the mean of its rating and the deterministic one.
+    #[serde(default)]
+    pub llm_critic: bool,
+}
+
+impl Default for NarrativeQualityConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_score: default_min_quality(),
+            llm_critic: false,
+        }
+    }
+}
+
+fn default_min_quality() -> f64 {
+    0.4
+}
+
+/// Spend guardrail for deep runs (`[budget]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct BudgetConfig {
+    /// Deep runs whose summed per-repo budgets exceed this need `--yes` or
+    /// interactive confirmation.
+    #[serde(default = "default_confirm_above_usd")]
+    pub confirm_above_usd: f64,
+    /// Cap on a deep run's total review spend, shared by all repos. Unset,
+    /// the cap is the sum of per-repo budgets.
+    #[serde(default)]
+    pub max_run_usd: Option<f64>,
+    /// Wall-clock limit on a run. Targets not started by then are carried
+    /// over to the next run; scans already in flight finish.
+    #[serde(default)]
+    pub max_run_minutes: Option<u64>,
+}
+
+impl Default for BudgetConfig {
+    fn default() -> Self {
+        Self {
+            confirm_above_usd: default_confirm_above_usd(),
+            max_run_usd: None,
+            max_run_minutes: None,
+        }
+    }
+}
+
+fn default_confirm_above_usd() -> f64 {
+    50.0
+}
+
+/// Stratified validation sampling (`[validation]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ValidationConfig {
+    /// Repos with more findings than this are sampled instead of validated
+    /// in full.
+    #[serde(default = "default_sample_above")]
+    pub sample_above: usize,
+    /// Share of each pattern's Medium/Low/Info findings validated when
+    /// sampling. Critical and High are always validated.
+    #[serde(default = "default_sample_percent")]
+    pub sample_percent: u32,
+    /// Withhold the investigator's severity from the validator and have it
+    /// assign its own, shown next to the investigator's in the report.
+    #[serde(default)]
+    pub blind: bool,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            sample_above: default_sample_above(),
+            sample_percent: default_sample_percent(),
+            blind: false,
+        }
+    }
+}
+
+fn default_sample_above() -> usize {
+    50
+}
+fn default_sample_percent() -> u32 {
+    20
+}
+
+/// Zero-cost exploration runs (`[explore]`, `run --explore`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ExploreConfig {
+    /// OpenRouter model every task is routed to; must be a `:free` variant.
+    #[serde(default = "default_model")]
+    pub model: String,
+}
+
+impl Default for ExploreConfig {
+    fn default() -> Self {
+        Self {
+            model: default_model(),
+        }
+    }
+}
+
+/// Report presentation (`[report]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReportConfig {
+    #[serde(default)]
+    pub group_by: GroupBy,
+    /// An executive summary of each scanned repo from the `summary` model,
+    /// at the top of its section: one call per repo.
+    #[serde(default = "default_true")]
+    pub repo_summaries: bool,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            group_by: GroupBy::default(),
+            repo_summaries: true,
+        }
+    }
+}
+
+/// How findings of one repo are grouped in the report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// Same title. Static findings over-group; agent findings rarely share one.
+    #[default]
+    Title,
+    /// Same scanner pattern; findings without one (agent review) by title.
+    Pattern,
+    /// Same source file.
+    File,
+    /// Same instruction handler, else enclosing function.
+    Instruction,
+    /// Every finding on its own.
+    None,
+}
+
+/// Mid-run alerts for confirmed Critical findings (`[alerts]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct AlertConfig {
+    /// Print a `SOLGUARD-ALERT` line as soon as a Critical is confirmed.
+    #[serde(default = "default_true")]
+    pub stdout: bool,
+    /// POST a JSON alert here. The payload carries a `text` field, so Slack
+    /// and Discord-compatible incoming webhooks render it as-is.
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Mail the alert to these addresses through the local `sendmail`.
+    #[serde(default)]
+    pub email_to: Vec<String>,
+    #[serde(default = "default_sendmail")]
+    pub sendmail: String,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self {
+            stdout: true,
+            webhook_url: None,
+            email_to: Vec::new(),
+            sendmail: default_sendmail(),
+        }
+    }
+}
+
+/// On-chain anomaly watch (`[watch]`, used by `watch-chain`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchConfig {
+    /// Seconds between polls.
+    #[serde(default = "default_watch_interval")]
+    pub interval_secs: u64,
+    /// Readings kept per metric as its rolling baseline.
+    #[serde(default = "default_watch_window")]
+    pub window: usize,
+    /// Readings needed before a metric can alert.
+    #[serde(default = "default_watch_min_samples")]
+    pub min_samples: usize,
+    /// Standard deviations from the baseline mean that count as anomalous.
+    #[serde(default = "default_watch_z")]
+    pub z_threshold: f64,
+    /// Relative change in activity (0.5 = up or down by half) also required.
+    #[serde(default = "default_watch_min_change")]
+    pub min_change: f64,
+    /// Rise in failure rate, in absolute terms (0.1 = ten points), also required.
+    #[serde(default = "default_watch_min_failure_jump")]
+    pub min_failure_jump: f64,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: default_watch_interval(),
+            window: default_watch_window(),
+            min_samples: default_watch_min_samples(),
+            z_threshold: default_watch_z(),
+            min_change: default_watch_min_change(),
+            min_failure_jump: default_watch_min_failure_jump(),
+        }
+    }
+}
+
+fn default_watch_interval() -> u64 {
+    300
+}
+fn default_watch_window() -> usize {
+    24
+}
+fn default_watch_min_samples() -> usize {
+    6
+}
+fn default_watch_z() -> f64 {
+    3.0
+}
+fn default_watch_min_change() -> f64 {
+    0.5
+}
+fn default_watch_min_failure_jump() -> f64 {
+    0.1
+}
+
+/// Tracker export of confirmed findings (`[export]`). Each target is off
+/// until its section is present.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExportConfig {
+    #[serde(default)]
+    pub linear: Option<LinearExportConfig>,
+    #[serde(default)]
+    pub notion: Option<NotionExportConfig>,
+}
+
+/// Linear issues (`[export.linear]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct LinearExportConfig {
+    pub team_id: String,
+    /// Environment variable holding the Linear API key.
+    #[serde(default = "default_linear_api_key_env")]
+    pub api_key_env: String,
+    /// Label IDs put on every exported issue.
+    #[serde(default)]
+    pub label_ids: Vec<String>,
+    /// Extra label ID per severity (`Critical = "..."`).
+    #[serde(default)]
+    pub severity_labels: HashMap<String, String>,
+    /// Linear priority per severity: 1 urgent, 2 high, 3 medium, 4 low.
+    #[serde(default = "default_linear_priority")]
+    pub priority: HashMap<String, u8>,
+}
+
+/// Notion database rows (`[export.notion]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct NotionExportConfig {
+    pub database_id: String,
+    /// Environment variable holding the Notion integration token.
+    #[serde(default = "default_notion_token_env")]
+    pub token_env: String,
+    #[serde(default)]
+    pub properties: NotionPropertyMap,
+}
+
+/// Database property each finding field is written to. The title and
+/// fingerprint properties are required; an empty name skips the field.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NotionPropertyMap {
+    /// Title property.
+    pub title: String,
+    /// Rich-text property holding the idempotency fingerprint.
+    pub fingerprint: String,
+    /// Select property.
+    pub severity: String,
+    /// Rich-text property.
+    pub repo: String,
+    /// Rich-text property, `file:line`.
+    pub location: String,
+    /// Multi-select property.
+    pub tags: String,
+}
+
+impl Default for NotionPropertyMap {
+    fn default() -> Self {
+        Self {
+            title: "Name".into(),
+            fingerprint: "Fingerprint".into(),
+            severity: "Severity".into(),
+            repo: "Repo".into(),
+            location: "Location".into(),
+            tags: "Tags".into(),
+        }
+    }
+}
+
+/// Static scanner options (`[scan]`, or `[scanner]`).
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScanConfig {
+    /// Also run the AST scan over `cargo expand` output, to catch handlers and
+    /// account structs generated by macros. Needs `cargo-expand` installed;
+    /// skipped with a warning otherwise.
+    #[serde(default)]
+    pub expand_macros: bool,
+    /// Local RustSec advisory-db checkout for the dependency audit;
+    /// `~/.solguard/advisory-db` when unset.
+    #[serde(default)]
+    pub advisory_db: Option<PathBuf>,
+    /// Pattern ID → lines either side of a match searched for evidence that
+    /// suppresses it, overriding the pattern's own window (3 for most).
+    #[serde(default)]
+    pub suppress_window: HashMap<String, usize>,
+    /// Static findings below this confidence are dropped. Non-Solana repos'
+    /// findings are scored 0.2.
+    #[serde(default = "default_min_confidence")]
+    pub min_confidence: f64,
+    /// Pattern IDs whose findings are dropped (`["SOL-003"]`).
+    #[serde(default)]
+    pub disabled_patterns: Vec<String>,
+    /// Pattern ID → severity its findings are reported at.
+    #[serde(default)]
+    pub severity_overrides: HashMap<String, Severity>,
+    /// Reuse per-file results for files whose content hasn't changed.
+    #[serde(default = "default_true")]
+    pub cache: bool,
+    /// Where cached results live; `~/.solguard/cache` when unset.
+    #[serde(default)]
+    pub cache_dir: Option<PathBuf>,
+    /// Source files larger than this are skipped.
+    #[serde(default = "default_max_file_bytes")]
+    pub max_file_bytes: u64,
+    /// Source files collected per repo before the walk stops.
+    #[serde(default = "default_max_files")]
+    pub max_files: usize,
+    /// Repo-relative globs a source file must match one of to be scanned
+    /// (`["programs/**"]`); every file when empty.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Repo-relative globs of files never scanned. Replaces the default list
+    /// of test, build and client directories when set.
+    #[serde(default = "default_exclude")]
+    pub exclude: Vec<String>,
+    /// TOML file of the team's own regex patterns, scanned alongside the
+    /// built-in ones; relative to the config file.
+    #[serde(default)]
+    pub patterns: Option<PathBuf>,
+    /// `patterns`, compiled by [`Config::load`].
+    #[serde(skip)]
+    pub custom_patterns: Vec<CustomPattern>,
+    /// Only scan under this repo-relative directory (`scan --path`).
+    #[serde(skip)]
+    pub subdir: Option<PathBuf>,
+}
+
+fn default_exclude() -> Vec<String> {
+    crate::security::DEFAULT_EXCLUDED_DIRS
+        .iter()
+        .map(|d| format!("**/{d}/**"))
+        .chain(["**/*_test.rs".into(), "**/*_tests.rs".into()])
+        .collect()
+}
+
+fn default_max_file_bytes() -> u64 {
+    2 * 1024 * 1024
+}
+
+fn default_max_files() -> usize {
+    20_000
+}
+
+fn default_min_confidence() -> f64 {
+    crate::security::MIN_CONFIDENCE
+}
+
+impl Default for ScanConfig {
+    fn default() -> Self {
+        Self {
+            expand_macros: false,
+            advisory_db: None,
+            suppress_window: HashMap::new(),
+            min_confidence: default_min_confidence(),
+            disabled_patterns: Vec::new(),
+            severity_overrides: HashMap::new(),
+            cache: true,
+            cache_dir: None,
+            max_file_bytes: default_max_file_bytes(),
+            max_files: default_max_files(),
+            include: Vec::new(),
+            exclude: default_exclude(),
+            patterns: None,
+            custom_patterns: Vec::new(),
+            subdir: None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
 pub struct TargetsConfig {
     #[serde(default)]
     pub always_scan: Vec<String>,
     #[serde(default)]
     pub repos_dir: Option<PathBuf>,
+    /// Repos whose last commit is older than this are stale. 0 disables the gate;
+    /// `always_scan` repos are exempt.
+    #[serde(default = "default_max_inactive_days")]
+    pub max_inactive_days: u32,
+    #[serde(default)]
+    pub stale_policy: StalePolicy,
+    /// What to do with narrative repos unrelated to their narrative.
+    #[serde(default)]
+    pub repo_verification: RepoVerification,
+    /// Queue `defer`-tier repos for the next run instead of scanning them
+    /// last in this one.
+    #[serde(default)]
+    pub defer_low_urgency: bool,
+    /// Narrative TVL (USD) at which an accelerating narrative's repos are
+    /// scanned first. 0 ignores TVL.
+    #[serde(default = "default_immediate_tvl_usd")]
+    pub immediate_tvl_usd: f64,
+    /// A repo of a non-accelerating narrative that scanned clean within this
+    /// many runs is `defer`-tier.
+    #[serde(default = "default_rescan_after_runs")]
+    pub rescan_after_runs: u32,
+}
+
+impl Default for TargetsConfig {
+    fn default() -> Self {
+        Self {
+            always_scan: Vec::new(),
+            repos_dir: None,
+            max_inactive_days: default_max_inactive_days(),
+            stale_policy: StalePolicy::default(),
+            repo_verification: RepoVerification::default(),
+            defer_low_urgency: false,
+            immediate_tvl_usd: default_immediate_tvl_usd(),
+            rescan_after_runs: default_rescan_after_runs(),
+        }
+    }
+}
+
+/// What target selection does with a stale repo.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StalePolicy {
+    /// Don't scan it at all.
+    #[default]
+    Skip,
+    /// Scan it in its usual turn, static analysis only — no deep review
+    /// budget. Staleness is only known once the repo is cloned, so it is
+    /// not moved to the end of the queue.
+    Downrank,
+}
+
+/// How narrative synthesis treats an `active_repos` entry whose GitHub
+/// description and topics don't relate to the narrative.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RepoVerification {
+    /// Remove it from the narrative, so it is neither scanned for nor scored
+    /// against it.
+    #[default]
+    Drop,
+    /// Keep it, but list it as mismatched in the report.
+    Flag,
+    /// No check.
+    Off,
+}
+
+/// Config-driven tag rules (`[[tagging.rules]]`).
+#[derive(Debug, Deserialize, Default)]
+pub struct TaggingConfig {
+    #[serde(default)]
+    pub rules: Vec<TagRule>,
+}
+
+/// Attach `tag` to findings whose path matches any of `paths` (globs) and to
+/// narratives whose protocol category matches any of `categories`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TagRule {
+    pub tag: String,
+    #[serde(default)]
+    pub paths: Vec<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+}
+
+/// Finding post-processors (`[[hooks.post_process]]`), see [`crate::hooks`].
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct HooksConfig {
+    #[serde(default)]
+    pub post_process: Vec<CommandHookConfig>,
+}
+
+/// An external command run on the findings: JSON array in on stdin, JSON
+/// array out on stdout.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CommandHookConfig {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
 }
 
 /// Configuration for the multi-turn agent security review.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct AgentReviewConfig {
     #[serde(default = "default_max_turns")]
     pub max_turns: u32,
@@ -40,6 +573,21 @@ pub struct AgentReviewConfig {
     pub max_tokens: u32,
     #[serde(default = "default_cost_limit")]
     pub cost_limit_usd: f64,
+    /// Seconds a single tool call may run before the model gets a timeout
+    /// error instead of its result.
+    #[serde(default = "default_tool_timeout")]
+    pub tool_timeout_secs: u64,
+    /// Seconds a single LLM turn may take; a timed-out turn counts as a
+    /// failed call.
+    #[serde(default = "default_turn_timeout")]
+    pub turn_timeout_secs: u64,
+    /// Repos `run` investigates at once (`--jobs` overrides). Their deep
+    /// reviews share the run's budget.
+    #[serde(default = "default_parallel_repos")]
+    pub parallel_repos: usize,
+    /// Which static findings deep mode keeps next to the agent's.
+    #[serde(default)]
+    pub static_merge: StaticMergeConfig,
 }
 
 impl Default for AgentReviewConfig {
@@ -48,10 +596,60 @@ impl Default for AgentReviewConfig {
             max_turns: default_max_turns(),
             max_tokens: default_agent_max_tokens(),
             cost_limit_usd: default_cost_limit(),
+            tool_timeout_secs: default_tool_timeout(),
+            turn_timeout_secs: default_turn_timeout(),
+            parallel_repos: default_parallel_repos(),
+            static_merge: StaticMergeConfig::default(),
         }
     }
 }
 
+impl AgentReviewConfig {
+    pub fn tool_timeout(&self) -> Duration {
+        Duration::from_secs(self.tool_timeout_secs.max(1))
+    }
+
+    pub fn turn_timeout(&self) -> Duration {
+        Duration::from_secs(self.turn_timeout_secs.max(1))
+    }
+}
+
+/// `[agent_review.static_merge]`: after an investigation, static findings
+/// at or above `min_severity` that no agent finding covers are added to the
+/// agent's; the rest are dropped.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaticMergeConfig {
+    #[serde(default = "default_static_merge_severity")]
+    pub min_severity: Severity,
+    #[serde(default)]
+    pub dedup: StaticDedup,
+}
+
+impl Default for StaticMergeConfig {
+    fn default() -> Self {
+        Self {
+            min_severity: default_static_merge_severity(),
+            dedup: StaticDedup::default(),
+        }
+    }
+}
+
+/// When a static finding counts as covered by an agent finding.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum StaticDedup {
+    /// An agent finding's title contains the static title, or it is in the
+    /// same file.
+    #[default]
+    TitleOrFile,
+    /// An agent finding's title contains the static title.
+    Title,
+    /// An agent finding is in the same file.
+    File,
+    /// Never: every static finding at or above `min_severity` is kept.
+    Off,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct GitHubConfig {
     #[serde(default = "default_github_token")]
@@ -64,16 +662,43 @@ pub struct GitHubConfig {
     pub lookback_days: u32,
     #[serde(default = "default_max_repos")]
     pub max_repos: u32,
+    /// `owner/name` repos whose releases, contributors and issues are
+    /// always collected as development-velocity signals.
+    #[serde(default)]
+    pub tracked_repos: Vec<String>,
+    /// Most-starred discovered repos also collected, besides the tracked
+    /// ones; three GitHub requests each.
+    #[serde(default = "default_activity_repos")]
+    pub activity_repos: usize,
+    /// Days of activity the velocity signals cover.
+    #[serde(default = "default_activity_days")]
+    pub activity_days: u32,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct SolanaConfig {
     #[serde(default = "default_rpc_url")]
     pub rpc_url: String,
+    /// RPC endpoints tried in order, rotating away from one that rate limits,
+    /// times out or reports itself unhealthy. Empty means `rpc_url` alone.
+    #[serde(default)]
+    pub rpc_endpoints: Vec<RpcEndpoint>,
     #[serde(default = "default_programs")]
     pub tracked_programs: Vec<TrackedProgram>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct RpcEndpoint {
+    /// Shown in logs instead of the URL, which often carries an API key.
+    /// Defaults to the URL's host.
+    #[serde(default)]
+    pub name: Option<String>,
+    pub url: String,
+    /// Requests per second this endpoint is allowed; unlimited when unset.
+    #[serde(default)]
+    pub requests_per_second: Option<f64>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct TrackedProgram {
     pub name: String,
@@ -83,6 +708,14 @@ pub struct TrackedProgram {
 
 #[derive(Debug, Deserialize)]
 pub struct SocialConfig {
+    /// Scrape `sources` for signals. Off by default: LLM discovery covers
+    /// the same ground with richer signals.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Translate article titles from non-English sources to English with
+    /// the LLM before aggregation. Without an LLM the originals are kept.
+    #[serde(default)]
+    pub translate: bool,
     #[serde(default = "default_sources")]
     pub sources: Vec<SocialSource>,
 }
@@ -90,6 +723,8 @@ pub struct SocialConfig {
 impl Default for SocialConfig {
     fn default() -> Self {
         Self {
+            enabled: false,
+            translate: false,
             sources: default_sources(),
         }
     }
@@ -102,6 +737,10 @@ pub struct SocialSource {
     #[serde(default = "default_source_type")]
     #[allow(dead_code)]
     pub source_type: String,
+    /// Language of the source (`zh`, `ko`, ...); detected from the script
+    /// of its titles when unset.
+    #[serde(default)]
+    pub language: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -120,6 +759,47 @@ pub struct DiscoveryConfig {
     pub max_signals: usize,
 }
 
+/// Governance monitoring (`[governance]`): protocol forums and Realms DAOs.
+#[derive(Debug, Deserialize)]
+pub struct GovernanceConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Forum topics with no posts in this window are not considered active.
+    #[serde(default = "default_governance_lookback_days")]
+    pub lookback_days: u32,
+    #[serde(default)]
+    pub sources: Vec<GovernanceSource>,
+}
+
+impl Default for GovernanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            lookback_days: default_governance_lookback_days(),
+            sources: Vec::new(),
+        }
+    }
+}
+
+/// One protocol's governance venues. Either or both of `forum_url` / `realm`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct GovernanceSource {
+    pub name: String,
+    /// Signal category, so proposals group with the protocol's other signals.
+    #[serde(default = "default_governance_category")]
+    pub category: String,
+    /// Discourse forum base URL (e.g. `https://forum.marinade.finance`).
+    pub forum_url: Option<String>,
+    /// Realms DAO: the realm account address.
+    pub realm: Option<String>,
+    /// SPL Governance program instance that owns the realm.
+    #[serde(default = "default_governance_program")]
+    pub governance_program: String,
+    /// Repo (`owner/name`) whose code the DAO governs; named in signals so
+    /// upcoming upgrades can become scan targets.
+    pub repo: Option<String>,
+}
+
 impl Default for DiscoveryConfig {
     fn default() -> Self {
         Self {
@@ -148,6 +828,17 @@ pub struct LlmConfig {
     pub max_tokens: u32,
     pub api_key_env: Option<String>,
     pub base_url: Option<String>,
+    /// Context window per model name, in tokens (`[llm.context_limits]`).
+    /// Overrides the built-in table; applies to `[models]` clients too.
+    #[serde(default)]
+    pub context_limits: HashMap<String, u32>,
+}
+
+impl LlmConfig {
+    /// Configured context window for `model`, if any.
+    pub fn context_limit(&self, model: &str) -> Option<u32> {
+        self.context_limits.get(model).copied()
+    }
 }
 
 /// Per-task model configuration for the `[models]` config section.
@@ -162,6 +853,9 @@ pub struct ModelConfig {
 }
 
 /// Task-specific model routing: overrides `[llm]` for specific pipeline stages.
+///
+/// Unset stages use the `[llm]` client, except `triage`, `summary` and
+/// `translation`, which first fall back to `cross_reference` if that is set.
 #[derive(Debug, Deserialize)]
 pub struct ModelsConfig {
     pub narrative: Option<ModelConfig>,
@@ -169,6 +863,9 @@ pub struct ModelsConfig {
     pub investigation: Option<ModelConfig>,
     pub validation: Option<ModelConfig>,
     pub cross_reference: Option<ModelConfig>,
+    pub triage: Option<ModelConfig>,
+    pub summary: Option<ModelConfig>,
+    pub translation: Option<ModelConfig>,
 }
 
 // Defaults
@@ -187,6 +884,12 @@ fn default_lookback_days() -> u32 {
 fn default_max_repos() -> u32 {
     30
 }
+fn default_activity_repos() -> usize {
+    10
+}
+fn default_activity_days() -> u32 {
+    14
+}
 fn default_rpc_url() -> String {
     std::env::var("SOLANA_RPC_URL").unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".into())
 }
@@ -214,6 +917,7 @@ fn default_sources() -> Vec<SocialSource> {
         name: "Helius Blog".into(),
         url: "https://www.helius.dev/blog".into(),
         source_type: "blog".into(),
+        language: None,
     }]
 }
 fn default_source_type() -> String {
@@ -228,9 +932,42 @@ fn default_top_protocols() -> usize {
 fn default_true() -> bool {
     true
 }
+fn default_linear_api_key_env() -> String {
+    "LINEAR_API_KEY".into()
+}
+fn default_notion_token_env() -> String {
+    "NOTION_TOKEN".into()
+}
+fn default_linear_priority() -> HashMap<String, u8> {
+    [("Critical", 1), ("High", 2), ("Medium", 3), ("Low", 4)]
+        .into_iter()
+        .map(|(s, p)| (s.to_string(), p))
+        .collect()
+}
+fn default_sendmail() -> String {
+    "sendmail".into()
+}
 fn default_max_signals() -> usize {
     15
 }
+fn default_max_inactive_days() -> u32 {
+    365
+}
+fn default_immediate_tvl_usd() -> f64 {
+    100_000_000.0
+}
+fn default_rescan_after_runs() -> u32 {
+    3
+}
+fn default_governance_lookback_days() -> u32 {
+    14
+}
+fn default_governance_category() -> String {
+    "Governance".into()
+}
+fn default_governance_program() -> String {
+    "GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw".into()
+}
 fn default_model() -> String {
     "arcee-ai/trinity-large-preview:free".into()
 }
@@ -246,43 +983,118 @@ fn default_agent_max_tokens() -> u32 {
 fn default_cost_limit() -> f64 {
     20.0
 }
+fn default_tool_timeout() -> u64 {
+    30
+}
+fn default_turn_timeout() -> u64 {
+    600
+}
+
+fn default_parallel_repos() -> usize {
+    1
+}
+
+fn default_static_merge_severity() -> Severity {
+    Severity::High
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            token: default_github_token(),
+            topics: default_topics(),
+            min_stars: default_min_stars(),
+            lookback_days: default_lookback_days(),
+            max_repos: default_max_repos(),
+            tracked_repos: Vec::new(),
+            activity_repos: default_activity_repos(),
+            activity_days: default_activity_days(),
+        }
+    }
+}
+
+impl Default for SolanaConfig {
+    fn default() -> Self {
+        Self {
+            rpc_url: default_rpc_url(),
+            rpc_endpoints: Vec::new(),
+            tracked_programs: default_programs(),
+        }
+    }
+}
 
-impl Default for Config {
+impl Default for LlmConfig {
     fn default() -> Self {
         Self {
-            github: GitHubConfig {
-                token: default_github_token(),
-                topics: default_topics(),
-                min_stars: default_min_stars(),
-                lookback_days: default_lookback_days(),
-                max_repos: default_max_repos(),
-            },
-            solana: SolanaConfig {
-                rpc_url: default_rpc_url(),
-                tracked_programs: default_programs(),
-            },
-            social: SocialConfig::default(),
-            defi_llama: DefiLlamaConfig::default(),
-            discovery: DiscoveryConfig::default(),
-            llm: LlmConfig {
-                provider: crate::llm::Provider::default(),
-                model: default_model(),
-                max_tokens: default_max_tokens(),
-                api_key_env: None,
-                base_url: None,
-            },
-            models: None,
-            agent_review: AgentReviewConfig::default(),
-            targets: TargetsConfig::default(),
+            provider: crate::llm::Provider::default(),
+            model: default_model(),
+            max_tokens: default_max_tokens(),
+            api_key_env: None,
+            base_url: None,
+            context_limits: HashMap::new(),
         }
     }
 }
 
 impl Config {
+    /// Load config in layers: built-in defaults, then the TOML file (if it
+    /// exists), then `SOLGUARD_*` environment variables. A missing file is not
+    /// an error, so containers can be configured from the environment alone.
     pub fn load(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)
-            .map_err(|e| Error::config(format!("Failed to read config {}: {e}", path.display())))?;
-        toml::from_str(&content).map_err(|e| Error::config(format!("Failed to parse config: {e}")))
+        let table = match std::fs::read_to_string(path) {
+            Ok(content) => toml::from_str::<toml::Table>(&content)
+                .map_err(|e| Error::config(format!("Failed to parse config: {e}")))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tracing::debug!(path = %path.display(), "config file not found, using defaults + env");
+                toml::Table::new()
+            }
+            Err(e) => {
+                return Err(Error::config(format!(
+                    "Failed to read config {}: {e}",
+                    path.display()
+                )));
+            }
+        };
+        let mut config = Self::from_table(table, std::env::vars())?;
+        if let Some(patterns) = &config.scan.patterns {
+            let patterns = path.parent().unwrap_or(Path::new("")).join(patterns);
+            config.scan.custom_patterns =
+                custom_patterns::load(&patterns).map_err(|e| Error::config(format!("{e:#}")))?;
+        }
+        Ok(config)
+    }
+
+    /// Deserialize a parsed config table with the `SOLGUARD_*` variables in
+    /// `vars` layered on top. An override stays a string unless the field it
+    /// sets rejects one, and is then read as a TOML literal: a numeric
+    /// `SOLGUARD_GITHUB__TOKEN` is still a token, while
+    /// `SOLGUARD_AGENT_REVIEW__MAX_TURNS=12` is a number.
+    pub fn from_table(
+        mut table: toml::Table,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Self> {
+        let mut overrides = apply_env_overrides(&mut table, vars)?;
+        loop {
+            let err = match serde_path_to_error::deserialize(toml::Value::Table(table.clone())) {
+                Ok(config) => return Ok(config),
+                Err(e) => e,
+            };
+            let at = err.path().to_string();
+            let Some(i) = overrides.iter().position(|(path, _)| path.join(".") == at) else {
+                return Err(Error::config(format!(
+                    "Failed to parse config: {} in `{at}`",
+                    err.into_inner()
+                )));
+            };
+            // Each override is retried once, as a literal.
+            let (path, raw) = overrides.swap_remove(i);
+            let (leaf, parents) = path
+                .split_last()
+                .expect("override paths have >= 2 segments");
+            table_at(&mut table, parents)
+                .map_err(Error::config)?
+                .insert(leaf.clone(), parse_env_value(&raw));
+        }
     }
 
     pub fn validate(&self) -> Result<()> {
@@ -295,6 +1107,69 @@ impl Config {
     }
 }
 
+/// Overlay `SOLGUARD_SECTION__KEY=value` variables onto a parsed config table.
+///
+/// `__` separates nesting levels and names are lowercased, so
+/// `SOLGUARD_MODELS__VALIDATION__MODEL` sets `models.validation.model`. Values
+/// are inserted as strings; returns each override's path and raw value so
+/// [`Config::from_table`] can reread the ones whose field isn't a string.
+pub fn apply_env_overrides(
+    table: &mut toml::Table,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<Vec<(Vec<String>, String)>> {
+    let mut overrides = Vec::new();
+    for (key, raw) in vars {
+        let Some(rest) = key.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let path: Vec<String> = rest.split("__").map(|s| s.to_lowercase()).collect();
+        if path.len() < 2 || path.iter().any(|s| s.is_empty()) {
+            continue;
+        }
+        let (leaf, parents) = path.split_last().expect("path has >= 2 segments");
+        table_at(table, parents)
+            .map_err(|e| Error::config(format!("{key}: {e}")))?
+            .insert(leaf.clone(), toml::Value::String(raw.clone()));
+        overrides.push((path, raw));
+    }
+    Ok(overrides)
+}
+
+/// The table at `path` under `table`, creating missing levels.
+fn table_at<'a>(
+    table: &'a mut toml::Table,
+    path: &[String],
+) -> std::result::Result<&'a mut toml::Table, String> {
+    let mut node = table;
+    for segment in path {
+        let entry = node
+            .entry(segment.clone())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        node = entry
+            .as_table_mut()
+            .ok_or_else(|| format!("`{segment}` is not a table in config"))?;
+    }
+    Ok(node)
+}
+
+/// An override as a TOML literal (`30`, `true`, `["a", "b"]`); anything that
+/// doesn't parse is a string, and unparseable comma lists become string arrays.
+fn parse_env_value(raw: &str) -> toml::Value {
+    if let Ok(mut t) = toml::from_str::<toml::Table>(&format!("v = {raw}"))
+        && let Some(v) = t.remove("v")
+    {
+        return v;
+    }
+    if raw.contains(',') {
+        return toml::Value::Array(
+            raw.split(',')
+                .map(|s| toml::Value::String(s.trim().to_string()))
+                .collect(),
+        );
+    }
+    toml::Value::String(raw.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,6 +1185,14 @@ min_stars = 10
 [solana]
 rpc_url = "https://api.mainnet-beta.solana.com"
 
+[[solana.rpc_endpoints]]
+name = "helius"
+url = "https://mainnet.helius-rpc.com/?api-key=k"
+requests_per_second = 10
+
+[[solana.rpc_endpoints]]
+url = "https://api.mainnet-beta.solana.com"
+
 [[solana.tracked_programs]]
 name = "Test"
 address = "11111111111111111111111111111111"
@@ -329,11 +1212,32 @@ provider = "openrouter"
 model = "test-model"
 max_tokens = 2048
 
+[llm.context_limits]
+"test-model" = 32000
+
 [agent_review]
 max_turns = 15
 max_tokens = 4096
 cost_limit_usd = 10.0
 
+[targets]
+max_inactive_days = 90
+stale_policy = "downrank"
+repo_verification = "flag"
+
+[alerts]
+stdout = false
+webhook_url = "https://hooks.example.com/solguard"
+email_to = ["sec@example.com"]
+
+[[tagging.rules]]
+tag = "treasury"
+paths = ["**/treasury/**"]
+
+[[tagging.rules]]
+tag = "defi"
+categories = ["DEX", "Lending"]
+
 [models]
 [models.narrative]
 provider = "groq"
@@ -352,6 +1256,27 @@ model = "claude-sonnet-4-5-20250929"
         assert!(models.narrative.is_some());
         assert!(models.validation.is_some());
         assert!(models.investigation.is_none());
+        assert_eq!(config.tagging.rules.len(), 2);
+        assert_eq!(config.tagging.rules[0].paths, vec!["**/treasury/**"]);
+        assert!(config.tagging.rules[0].categories.is_empty());
+        assert_eq!(config.tagging.rules[1].categories, vec!["DEX", "Lending"]);
+        assert_eq!(config.targets.max_inactive_days, 90);
+        assert_eq!(config.targets.stale_policy, StalePolicy::Downrank);
+        assert_eq!(config.targets.repo_verification, RepoVerification::Flag);
+        assert!(!config.alerts.stdout);
+        assert_eq!(
+            config.alerts.webhook_url.as_deref(),
+            Some("https://hooks.example.com/solguard")
+        );
+        assert_eq!(config.alerts.email_to, vec!["sec@example.com"]);
+        assert_eq!(config.alerts.sendmail, "sendmail");
+        assert_eq!(config.llm.context_limit("test-model"), Some(32000));
+        assert_eq!(config.llm.context_limit("qwen3-32b"), None);
+        let endpoints = &config.solana.rpc_endpoints;
+        assert_eq!(endpoints.len(), 2);
+        assert_eq!(endpoints[0].name.as_deref(), Some("helius"));
+        assert_eq!(endpoints[0].requests_per_second, Some(10.0));
+        assert!(endpoints[1].name.is_none() && endpoints[1].requests_per_second.is_none());
     }
 
     #[test]
@@ -368,9 +1293,67 @@ model = "test"
 "#;
         let config: Config = toml::from_str(toml).unwrap();
         assert!(config.models.is_none());
+        assert!(config.tagging.rules.is_empty());
         assert_eq!(config.agent_review.max_turns, 30);
         assert_eq!(config.agent_review.max_tokens, 8192);
         assert!((config.agent_review.cost_limit_usd - 20.0).abs() < f64::EPSILON);
+        assert_eq!(config.agent_review.parallel_repos, 1);
+        assert!(config.budget.max_run_usd.is_none());
+        assert_eq!(config.targets.max_inactive_days, 365);
+        assert_eq!(config.targets.stale_policy, StalePolicy::Skip);
+        assert_eq!(config.targets.repo_verification, RepoVerification::Drop);
+        assert!(config.alerts.stdout);
+        assert!(config.alerts.webhook_url.is_none());
+        assert!(config.export.linear.is_none() && config.export.notion.is_none());
+    }
+
+    #[test]
+    fn export_sections_fill_defaults() {
+        let toml = r#"
+[export.linear]
+team_id = "team-1"
+priority = { Critical = 2 }
+
+[export.notion]
+database_id = "db-1"
+[export.notion.properties]
+title = "Finding"
+tags = ""
+"#;
+        let config: Config = toml::from_str(toml).unwrap();
+        let linear = config.export.linear.unwrap();
+        assert_eq!(linear.api_key_env, "LINEAR_API_KEY");
+        assert_eq!(linear.priority.get("Critical"), Some(&2));
+        assert!(!linear.priority.contains_key("High"));
+        let notion = config.export.notion.unwrap();
+        assert_eq!(notion.token_env, "NOTION_TOKEN");
+        assert_eq!(notion.properties.title, "Finding");
+        assert_eq!(notion.properties.fingerprint, "Fingerprint");
+        assert!(notion.properties.tags.is_empty());
+    }
+
+    #[test]
+    fn scanner_section_tunes_noise() {
+        let toml = r#"
+[scanner]
+min_confidence = 0.7
+disabled_patterns = ["SOL-003"]
+[scanner.severity_overrides]
+"SOL-005" = "Low"
+"#;
+        let scan = toml::from_str::<Config>(toml).unwrap().scan;
+        assert!((scan.min_confidence - 0.7).abs() < f64::EPSILON);
+        assert_eq!(scan.disabled_patterns, vec!["SOL-003"]);
+        assert_eq!(scan.severity_overrides.get("SOL-005"), Some(&Severity::Low));
+        let defaults = toml::from_str::<Config>(
+            "[scan]
+expand_macros = true
+",
+        )
+        .unwrap()
+        .scan;
+        assert!((defaults.min_confidence - 0.55).abs() < f64::EPSILON);
+        assert!(defaults.disabled_patterns.is_empty());
     }
 
     #[test]
@@ -379,6 +1362,128 @@ model = "test"
         assert_eq!(arc.max_turns, 30);
         assert_eq!(arc.max_tokens, 8192);
         assert!((arc.cost_limit_usd - 20.0).abs() < f64::EPSILON);
+        assert_eq!(arc.static_merge.min_severity, Severity::High);
+        assert_eq!(arc.static_merge.dedup, StaticDedup::TitleOrFile);
+    }
+
+    #[test]
+    fn static_merge_config_parses() {
+        let config: Config = toml::from_str(
+            "[agent_review.static_merge]\nmin_severity = \"Medium\"\ndedup = \"title\"\n",
+        )
+        .unwrap();
+        let merge = config.agent_review.static_merge;
+        assert_eq!(merge.min_severity, Severity::Medium);
+        assert_eq!(merge.dedup, StaticDedup::Title);
+        assert_eq!(config.agent_review.max_turns, 30);
+    }
+
+    fn env(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn env_only_config_parses() {
+        let config = Config::from_table(
+            toml::Table::new(),
+            env(&[
+                ("SOLGUARD_GITHUB__TOKEN", "ghp_env"),
+                ("SOLGUARD_GITHUB__TOPICS", "solana, anchor"),
+                ("SOLGUARD_LLM__MODEL", "test-model"),
+                ("SOLGUARD_LLM__PROVIDER", "groq"),
+                ("SOLGUARD_AGENT_REVIEW__MAX_TURNS", "12"),
+                ("SOLGUARD_MODELS__VALIDATION__MODEL", "val-model"),
+                ("UNRELATED", "ignored"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.github.token, "ghp_env");
+        assert_eq!(config.github.topics, vec!["solana", "anchor"]);
+        assert_eq!(config.llm.model, "test-model");
+        assert!(matches!(config.llm.provider, crate::llm::Provider::Groq));
+        assert_eq!(config.agent_review.max_turns, 12);
+        let validation = config.models.unwrap().validation.unwrap();
+        assert_eq!(validation.model, "val-model");
+        assert_eq!(config.solana.tracked_programs.len(), 3);
+    }
+
+    #[test]
+    fn env_overrides_file_values() {
+        let table: toml::Table = toml::from_str(
+            r#"
+[llm]
+model = "from-file"
+max_tokens = 2048
+"#,
+        )
+        .unwrap();
+        let config = Config::from_table(
+            table,
+            env(&[
+                ("SOLGUARD_LLM__MODEL", "from-env"),
+                ("SOLGUARD_GITHUB__TOPICS", r#"["defi"]"#),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.llm.model, "from-env");
+        assert_eq!(config.llm.max_tokens, 2048);
+        assert_eq!(config.github.topics, vec!["defi"]);
+    }
+
+    #[test]
+    fn env_overrides_keep_strings_that_look_like_literals() {
+        let config = Config::from_table(
+            toml::Table::new(),
+            env(&[
+                ("SOLGUARD_GITHUB__TOKEN", "1234567890"),
+                ("SOLGUARD_LLM__MODEL", "true"),
+                ("SOLGUARD_LLM__BASE_URL", "http://a,b"),
+                ("SOLGUARD_GITHUB__MIN_STARS", "7"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(config.github.token, "1234567890");
+        assert_eq!(config.llm.model, "true");
+        assert_eq!(config.llm.base_url.as_deref(), Some("http://a,b"));
+        assert_eq!(config.github.min_stars, 7);
+
+        let err = Config::from_table(
+            toml::Table::new(),
+            env(&[("SOLGUARD_GITHUB__MIN_STARS", "many")]),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("github.min_stars"));
+    }
+
+    #[test]
+    fn env_override_into_scalar_is_rejected() {
+        let mut table: toml::Table = toml::from_str("llm = \"oops\"").unwrap();
+        let err = apply_env_overrides(&mut table, env(&[("SOLGUARD_LLM__MODEL", "m")]));
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn load_compiles_patterns_next_to_the_config() {
+        let dir = std::env::temp_dir().join(format!("solguard-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("config.toml"),
+            "[scan]\npatterns = \"patterns.toml\"\n",
+        )
+        .unwrap();
+        let pattern = |regex: &str| {
+            format!("[[pattern]]\nid = \"ACME-001\"\nregex = '{regex}'\nseverity = \"High\"\n")
+        };
+        std::fs::write(dir.join("patterns.toml"), pattern(r"unchecked_price\(")).unwrap();
+        let loaded = Config::load(&dir.join("config.toml")).map(|c| c.scan.custom_patterns.len());
+        std::fs::write(dir.join("patterns.toml"), pattern("(unclosed")).unwrap();
+        let broken = Config::load(&dir.join("config.toml"));
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(loaded.unwrap(), 1);
+        assert!(broken.unwrap_err().to_string().contains("ACME-001: regex"));
     }
 
     #[test]
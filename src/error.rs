@@ -24,6 +24,17 @@ pub enum Error {
         retry_after_secs: Option<u64>,
     },
 
+    /// The request would not fit the model's context window. Raised before
+    /// sending, so callers can compact the conversation and retry.
+    #[error(
+        "Prompt too large for {model}: ~{estimated_tokens} tokens exceeds context limit {limit}"
+    )]
+    ContextOverflow {
+        model: String,
+        estimated_tokens: u32,
+        limit: u32,
+    },
+
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 }
@@ -0,0 +1,206 @@
+//! Post-processing hooks on the final findings, run after tagging and before
+//! anything is reported. They carry team policy the pipeline shouldn't know
+//! about: re-rating findings by path, dropping deprecated modules, attaching
+//! internal asset metadata.
+//!
+//! A hook implements [`FindingHook`]. Library users register their own on
+//! [`HookRegistry`]; everyone else configures external commands in
+//! `[[hooks.post_process]]`, which get the findings as a JSON array on stdin
+//! and print the (possibly edited) array on stdout. Hooks run in order, each
+//! on the previous one's output. A failing hook fails the run rather than
+//! letting findings through unprocessed.
+
+use crate::config::{CommandHookConfig, HooksConfig};
+use crate::security::SecurityFinding;
+use anyhow::{Context, Result, bail};
+use std::fmt;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::Arc;
+use tracing::info;
+
+/// A post-processor over the complete findings list.
+pub trait FindingHook: Send + Sync {
+    /// Shown in logs and errors.
+    fn name(&self) -> &str;
+    fn process(&self, findings: Vec<SecurityFinding>) -> Result<Vec<SecurityFinding>>;
+}
+
+/// Hooks to run, in registration order.
+#[derive(Clone, Default)]
+pub struct HookRegistry {
+    hooks: Vec<Arc<dyn FindingHook>>,
+}
+
+impl fmt::Debug for HookRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.hooks.iter().map(|h| h.name()))
+            .finish()
+    }
+}
+
+impl HookRegistry {
+    /// The command hooks from `[[hooks.post_process]]`.
+    pub fn from_config(config: &HooksConfig) -> Self {
+        let mut registry = Self::default();
+        registry.extend_from_config(config);
+        registry
+    }
+
+    /// Append the command hooks from `[[hooks.post_process]]`.
+    pub fn extend_from_config(&mut self, config: &HooksConfig) {
+        for hook in &config.post_process {
+            self.register(CommandHook::new(hook.clone()));
+        }
+    }
+
+    pub fn register(&mut self, hook: impl FindingHook + 'static) {
+        self.hooks.push(Arc::new(hook));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Pass `findings` through every hook.
+    pub fn run(&self, mut findings: Vec<SecurityFinding>) -> Result<Vec<SecurityFinding>> {
+        for hook in &self.hooks {
+            let before = findings.len();
+            findings = hook
+                .process(findings)
+                .with_context(|| format!("post-processing hook `{}`", hook.name()))?;
+            info!(
+                hook = hook.name(),
+                before,
+                after = findings.len(),
+                "post-processing hook applied"
+            );
+        }
+        Ok(findings)
+    }
+}
+
+/// An external program that filters findings as JSON, stdin to stdout.
+pub struct CommandHook {
+    config: CommandHookConfig,
+}
+
+impl CommandHook {
+    pub fn new(config: CommandHookConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl FindingHook for CommandHook {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn process(&self, findings: Vec<SecurityFinding>) -> Result<Vec<SecurityFinding>> {
+        let input = serde_json::to_vec(&findings)?;
+        let mut child = Command::new(&self.config.command)
+            .args(&self.config.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("starting `{}`", self.config.command))?;
+        // Write from another thread so a hook that streams its output before
+        // reading all of its input can't deadlock on a full pipe.
+        let mut stdin = child.stdin.take().context("hook stdin")?;
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let output = child.wait_with_output()?;
+        // A hook may exit without reading its input; its status decides.
+        let _ = writer.join();
+        if !output.status.success() {
+            bail!(
+                "exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        serde_json::from_slice(&output.stdout).context("hook output is not a findings array")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Resever;
+
+    impl FindingHook for Resever {
+        fn name(&self) -> &str {
+            "resever"
+        }
+
+        fn process(&self, findings: Vec<SecurityFinding>) -> Result<Vec<SecurityFinding>> {
+            Ok(findings
+                .into_iter()
+                .filter(|f| !f.file_path.starts_with("legacy"))
+                .map(|mut f| {
+                    f.severity = "Low".into();
+                    f
+                })
+                .collect())
+        }
+    }
+
+    fn finding(path: &str) -> SecurityFinding {
+        SecurityFinding {
+            title: "Missing Owner Check".into(),
+            severity: "High".into(),
+            file_path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hooks_run_in_order() {
+        let mut registry = HookRegistry::default();
+        registry.register(Resever);
+        let tag = |tag: &str| CommandHookConfig {
+            name: tag.into(),
+            command: "sed".into(),
+            args: vec![format!(r#"s/"tags":\[\]/"tags":["{tag}"]/g"#)],
+        };
+        registry.extend_from_config(&HooksConfig {
+            post_process: vec![tag("reviewed")],
+        });
+        assert_eq!(format!("{registry:?}"), r#"["resever", "reviewed"]"#);
+
+        let out = registry
+            .run(vec![finding("legacy/lib.rs"), finding("vault/lib.rs")])
+            .unwrap();
+        assert_eq!(out.len(), 1);
+        assert_eq!(out[0].severity, "Low");
+        assert_eq!(out[0].tags, vec!["reviewed"]);
+    }
+
+    #[test]
+    fn failing_command_fails_the_run() {
+        let hooks = HooksConfig {
+            post_process: vec![CommandHookConfig {
+                name: "strict".into(),
+                command: "sh".into(),
+                args: vec!["-c".into(), "echo denied >&2; exit 3".into()],
+            }],
+        };
+        let err = HookRegistry::from_config(&hooks)
+            .run(vec![finding("vault/lib.rs")])
+            .unwrap_err();
+        let message = format!("{err:#}");
+        assert!(message.contains("hook `strict`"), "{message}");
+        assert!(message.contains("denied"), "{message}");
+
+        let garbage = HooksConfig {
+            post_process: vec![CommandHookConfig {
+                name: "garbage".into(),
+                command: "echo".into(),
+                args: vec!["not json".into()],
+            }],
+        };
+        assert!(HookRegistry::from_config(&garbage).run(Vec::new()).is_err());
+    }
+}
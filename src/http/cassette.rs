@@ -0,0 +1,209 @@
+//! Record/replay of external HTTP traffic for offline tests.
+//!
+//! In record mode every successful response is written to
+//! `<dir>/<method>_<host>_<path>-<n>.json`, where `n` counts repeated calls to
+//! the same endpoint. Replay mode serves those files back in the same order
+//! and never touches the network; a missing fixture is an error.
+//!
+//! Query strings and request bodies are stored for inspection but are not part
+//! of the key, so fixtures survive date-stamped queries and prompt changes.
+//!
+//! Enable with `--features recording` and set `SOLGUARD_HTTP_RECORD=<dir>` or
+//! `SOLGUARD_HTTP_REPLAY=<dir>` (or attach one via `HttpClient::with_cassette`).
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+pub const RECORD_ENV: &str = "SOLGUARD_HTTP_RECORD";
+pub const REPLAY_ENV: &str = "SOLGUARD_HTTP_REPLAY";
+
+/// Query parameters whose values are masked before a URL is written to disk.
+const SECRET_PARAMS: &[&str] = &["key", "token", "secret", "auth"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Record,
+    Replay,
+}
+
+/// One captured request/response pair, as stored on disk.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Interaction {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub request_body: Option<String>,
+    pub response: String,
+}
+
+#[derive(Debug)]
+pub struct Cassette {
+    mode: Mode,
+    dir: PathBuf,
+    counters: Mutex<HashMap<String, usize>>,
+}
+
+impl Cassette {
+    pub fn new(mode: Mode, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            mode,
+            dir: dir.into(),
+            counters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Process-wide cassette from `SOLGUARD_HTTP_REPLAY` / `SOLGUARD_HTTP_RECORD`.
+    /// Shared so call counters stay consistent across every `HttpClient`.
+    pub fn from_env() -> Option<Arc<Self>> {
+        static GLOBAL: OnceLock<Option<Arc<Cassette>>> = OnceLock::new();
+        GLOBAL
+            .get_or_init(|| {
+                let (mode, dir) = if let Ok(dir) = std::env::var(REPLAY_ENV) {
+                    (Mode::Replay, dir)
+                } else {
+                    (Mode::Record, std::env::var(RECORD_ENV).ok()?)
+                };
+                tracing::info!(?mode, dir = %dir, "HTTP cassette enabled");
+                Some(Arc::new(Self::new(mode, dir)))
+            })
+            .clone()
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Fixture path for the next call to this request's endpoint.
+    pub fn next_path(&self, request: &reqwest::Request) -> PathBuf {
+        let url = request.url();
+        let slug = fixture_slug(
+            request.method().as_str(),
+            url.host_str().unwrap_or("unknown"),
+            url.path(),
+        );
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        let n = counters.entry(slug.clone()).or_insert(0);
+        let path = self.dir.join(format!("{slug}-{n}.json"));
+        *n += 1;
+        path
+    }
+
+    pub fn replay(&self, path: &Path) -> Result<String> {
+        let raw = std::fs::read_to_string(path)
+            .map_err(|e| Error::http(format!("replay: no fixture at {}: {e}", path.display())))?;
+        let interaction: Interaction = serde_json::from_str(&raw)
+            .map_err(|e| Error::parse(format!("replay fixture {}: {e}", path.display())))?;
+        Ok(interaction.response)
+    }
+
+    pub fn record(&self, path: &Path, request: &reqwest::Request, response: &str) -> Result<()> {
+        let interaction = Interaction {
+            method: request.method().to_string(),
+            url: redact_url(request.url()),
+            request_body: request
+                .body()
+                .and_then(|b| b.as_bytes())
+                .map(|b| String::from_utf8_lossy(b).into_owned()),
+            response: response.to_string(),
+        };
+        std::fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_string_pretty(&interaction)
+            .map_err(|e| Error::parse(format!("serialize fixture: {e}")))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// `GET https://api.llama.fi/v2/chains` → `get_api.llama.fi_v2_chains`.
+fn fixture_slug(method: &str, host: &str, path: &str) -> String {
+    let raw = format!("{}_{host}{}", method.to_lowercase(), path.replace('/', "_"));
+    let slug: String = raw
+        .trim_end_matches('_')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    slug.chars().take(120).collect()
+}
+
+fn redact_url(url: &reqwest::Url) -> String {
+    let mut url = url.clone();
+    if url.query().is_none() {
+        return url.to_string();
+    }
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .map(|(k, v)| {
+            let secret = SECRET_PARAMS.iter().any(|s| k.to_lowercase().contains(s));
+            let value = if secret {
+                "REDACTED".into()
+            } else {
+                v.into_owned()
+            };
+            (k.into_owned(), value)
+        })
+        .collect();
+    url.query_pairs_mut().clear().extend_pairs(pairs);
+    url.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slug_from_endpoint() {
+        assert_eq!(
+            fixture_slug("GET", "api.llama.fi", "/v2/chains"),
+            "get_api.llama.fi_v2_chains"
+        );
+        assert_eq!(
+            fixture_slug("POST", "openrouter.ai", "/api/v1/chat/completions"),
+            "post_openrouter.ai_api_v1_chat_completions"
+        );
+        assert_eq!(fixture_slug("GET", "example.com", "/"), "get_example.com");
+    }
+
+    #[test]
+    fn repeated_calls_get_sequential_paths() {
+        let cassette = Cassette::new(Mode::Replay, "fixtures");
+        let client = reqwest::Client::new();
+        let req = |q: &str| {
+            client
+                .get(format!("https://api.github.com/search/repositories?q={q}"))
+                .build()
+                .unwrap()
+        };
+        let first = cassette.next_path(&req("a"));
+        let second = cassette.next_path(&req("b"));
+        assert!(first.ends_with("get_api.github.com_search_repositories-0.json"));
+        assert!(second.ends_with("get_api.github.com_search_repositories-1.json"));
+    }
+
+    #[test]
+    fn secrets_redacted_from_recorded_url() {
+        let url =
+            reqwest::Url::parse("https://rpc.example.com/?api-key=abc123&cluster=main").unwrap();
+        let redacted = redact_url(&url);
+        assert!(!redacted.contains("abc123"));
+        assert!(redacted.contains("cluster=main"));
+    }
+
+    #[test]
+    fn missing_fixture_is_an_error() {
+        let cassette = Cassette::new(Mode::Replay, "/nonexistent/cassette");
+        assert!(
+            cassette
+                .replay(Path::new("/nonexistent/cassette/x-0.json"))
+                .is_err()
+        );
+    }
+}
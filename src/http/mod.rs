@@ -1,3 +1,6 @@
+#[cfg(feature = "recording")]
+pub mod cassette;
+
 use crate::error::{Error, Result};
 use reqwest::{Client, StatusCode, header};
 use serde::de::DeserializeOwned;
@@ -10,6 +13,8 @@ pub struct HttpClient {
     client: Client,
     max_retries: u32,
     base_delay_ms: u64,
+    #[cfg(feature = "recording")]
+    cassette: Option<std::sync::Arc<cassette::Cassette>>,
 }
 
 impl HttpClient {
@@ -24,9 +29,26 @@ impl HttpClient {
             client,
             max_retries: 5,
             base_delay_ms: 1000,
+            #[cfg(feature = "recording")]
+            cassette: cassette::Cassette::from_env(),
         })
     }
 
+    /// Route every request through `cassette` (record or replay), overriding
+    /// any cassette picked up from the environment.
+    #[cfg(feature = "recording")]
+    pub fn with_cassette(mut self, cassette: std::sync::Arc<cassette::Cassette>) -> Self {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    /// Give up after `max_retries` retries. Callers with somewhere else to
+    /// send the request (another RPC endpoint) retry less.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
     pub async fn get_text(&self, url: &str) -> Result<String> {
         self.request_with_retry(|| self.client.get(url)).await
     }
@@ -49,16 +71,61 @@ impl HttpClient {
         serde_json::from_str(&body).map_err(|e| Error::parse(format!("JSON parse: {e}")))
     }
 
+    /// Binary download (crate tarballs). Not retried or recorded: the
+    /// text-based retry and cassette paths don't apply to binary bodies.
+    pub async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
+        let resp = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| Error::http(e.to_string()))?;
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(Error::api_with_status(
+                extract_domain(url),
+                body,
+                status.as_u16(),
+            ));
+        }
+        resp.bytes()
+            .await
+            .map(|b| b.to_vec())
+            .map_err(|e| Error::http(e.to_string()))
+    }
+
     pub async fn post_json_raw(
         &self,
         url: &str,
         body: &str,
         headers: &[(&str, &str)],
+    ) -> Result<String> {
+        self.send_json(reqwest::Method::POST, url, body, headers)
+            .await
+    }
+
+    pub async fn patch_json_raw(
+        &self,
+        url: &str,
+        body: &str,
+        headers: &[(&str, &str)],
+    ) -> Result<String> {
+        self.send_json(reqwest::Method::PATCH, url, body, headers)
+            .await
+    }
+
+    async fn send_json(
+        &self,
+        method: reqwest::Method,
+        url: &str,
+        body: &str,
+        headers: &[(&str, &str)],
     ) -> Result<String> {
         self.request_with_retry(|| {
             let mut req = self
                 .client
-                .post(url)
+                .request(method.clone(), url)
                 .header(header::CONTENT_TYPE, "application/json")
                 .body(body.to_string());
             for (k, v) in headers {
@@ -70,6 +137,27 @@ impl HttpClient {
     }
 
     async fn request_with_retry<F>(&self, build: F) -> Result<String>
+    where
+        F: Fn() -> reqwest::RequestBuilder,
+    {
+        #[cfg(feature = "recording")]
+        if let Some(ref cassette) = self.cassette {
+            let request = build().build().map_err(|e| Error::http(e.to_string()))?;
+            let path = cassette.next_path(&request);
+            if cassette.mode() == cassette::Mode::Replay {
+                return cassette.replay(&path);
+            }
+            let result = self.send_with_retry(&build).await;
+            if let Ok(ref body) = result {
+                cassette.record(&path, &request, body)?;
+            }
+            return result;
+        }
+
+        self.send_with_retry(&build).await
+    }
+
+    async fn send_with_retry<F>(&self, build: &F) -> Result<String>
     where
         F: Fn() -> reqwest::RequestBuilder,
     {
@@ -89,14 +177,17 @@ impl HttpClient {
                         retry_after_secs,
                         ref platform,
                     }) => {
-                        let wait = retry_after_secs.unwrap_or(delay / 1000).max(1);
-                        warn!(attempt, wait_secs = wait, platform = %platform, "rate limited, backing off");
-                        sleep(Duration::from_secs(wait)).await;
-                        delay = (delay * 2).min(60_000);
                         last_error = Error::RateLimit {
                             platform: platform.clone(),
                             retry_after_secs,
                         };
+                        if attempt == self.max_retries {
+                            break;
+                        }
+                        let wait = retry_after_secs.unwrap_or(delay / 1000).max(1);
+                        warn!(attempt, wait_secs = wait, platform = %platform, "rate limited, backing off");
+                        sleep(Duration::from_secs(wait)).await;
+                        delay = (delay * 2).min(60_000);
                         continue;
                     }
                     other => return other,
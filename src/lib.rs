@@ -1,12 +1,18 @@
 pub mod agent;
+pub mod compare;
 pub mod config;
 pub mod error;
+pub mod hooks;
 pub mod http;
 pub mod llm;
 pub mod memory;
 pub mod narrative;
 pub mod output;
+pub mod paths;
+pub mod progress;
 pub mod security;
+pub mod severity;
+pub mod tags;
 
 /// CLI override for LLM provider/model.
 pub struct LlmOverride {
@@ -4,7 +4,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use std::collections::HashMap;
 use std::path::Path;
-use tracing::{debug, warn};
+use std::sync::Mutex;
+use tracing::{debug, info, warn};
 
 /// Per-call context for provider-specific behavior.
 ///
@@ -40,7 +41,7 @@ impl Provider {
         }
     }
 
-    fn default_api_key_env(&self) -> &'static str {
+    pub fn default_api_key_env(&self) -> &'static str {
         match self {
             Self::Anthropic => "ANTHROPIC_API_KEY",
             Self::OpenRouter => "OPENROUTER_API_KEY",
@@ -62,7 +63,8 @@ pub struct ToolDef {
 }
 
 /// Content block in a conversation message.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlock {
     Text {
         text: String,
@@ -80,14 +82,15 @@ pub enum ContentBlock {
 }
 
 /// Role in a conversation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Role {
     User,
     Assistant,
 }
 
 /// A message in a multi-turn conversation.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConversationMessage {
     pub role: Role,
     pub content: Vec<ContentBlock>,
@@ -123,6 +126,21 @@ pub struct LlmClient {
     max_tokens: u32,
     base_url: String,
     http: HttpClient,
+    /// Observed billed/estimated ratio for this model, applied to budget math.
+    cost_factor: f64,
+    /// Context window in tokens; `None` skips the pre-send size check.
+    context_limit: Option<u32>,
+    /// OpenRouter generations issued by this client, pending cost reconciliation.
+    generations: Mutex<Vec<GenerationRecord>>,
+}
+
+/// One billed generation and what we estimated it cost, for later reconciliation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationRecord {
+    pub id: String,
+    pub model: String,
+    /// Uncalibrated `estimate_cost_usd` output.
+    pub estimated_usd: f64,
 }
 
 // -- Anthropic simple completion wire types --
@@ -191,6 +209,9 @@ struct OpenAiMessage {
 
 #[derive(Deserialize)]
 struct OpenAiConvResponse {
+    /// Generation ID (OpenRouter exposes billed cost per ID).
+    #[serde(default)]
+    id: Option<String>,
     choices: Vec<OpenAiConvChoice>,
     usage: Option<OpenAiConvUsage>,
 }
@@ -246,6 +267,7 @@ impl LlmClient {
     ) -> Result<Self> {
         let http = HttpClient::new("st-solguard/0.1.0")?;
         let base_url = base_url.unwrap_or_else(|| provider.default_base_url().into());
+        let context_limit = default_context_limit(&model);
         Ok(Self {
             provider,
             api_key,
@@ -253,9 +275,36 @@ impl LlmClient {
             max_tokens,
             base_url,
             http,
+            cost_factor: 1.0,
+            context_limit,
+            generations: Mutex::new(Vec::new()),
         })
     }
 
+    /// Scale cost estimates by a calibration factor (see `RunMemory::cost_factor`).
+    pub fn with_cost_factor(mut self, factor: f64) -> Self {
+        self.cost_factor = factor;
+        self
+    }
+
+    /// Override the built-in context window for this model (`[llm.context_limits]`).
+    pub fn with_context_limit(mut self, limit: Option<u32>) -> Self {
+        if limit.is_some() {
+            self.context_limit = limit;
+        }
+        self
+    }
+
+    /// Calibrated cost estimate for a call made with this client.
+    pub fn estimate_cost(&self, usage: &Usage) -> f64 {
+        estimate_cost_usd(usage, &self.model) * self.cost_factor
+    }
+
+    /// Take the generation records accumulated since the last drain.
+    pub fn drain_generations(&self) -> Vec<GenerationRecord> {
+        std::mem::take(&mut *self.generations.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
     /// Build from config, reading the API key from the specified env var.
     pub fn from_config(
         provider: Provider,
@@ -400,6 +449,8 @@ impl LlmClient {
     ///
     /// The caller manages conversation history and tool dispatch. This method
     /// handles wire format translation for both Anthropic and OpenAI providers.
+    /// Returns [`Error::ContextOverflow`] without sending if the prompt plus
+    /// `max_tokens` would exceed the model's context window.
     pub async fn converse(
         &self,
         system: &str,
@@ -407,12 +458,23 @@ impl LlmClient {
         tools: &[ToolDef],
         context: Option<&ConverseContext<'_>>,
     ) -> Result<ConversationResponse> {
+        let estimated_tokens = estimate_prompt_tokens(system, messages, tools);
         debug!(
             provider = ?self.provider,
             model = %self.model,
             turns = messages.len(),
+            estimated_tokens,
             "converse"
         );
+        if let Some(limit) = self.context_limit
+            && estimated_tokens.saturating_add(self.max_tokens) > limit
+        {
+            return Err(Error::ContextOverflow {
+                model: self.model.clone(),
+                estimated_tokens,
+                limit,
+            });
+        }
         match self.provider {
             Provider::Anthropic => self.converse_anthropic(system, messages, tools).await,
             Provider::OpenRouter | Provider::OpenAi | Provider::Groq => {
@@ -425,6 +487,36 @@ impl LlmClient {
         }
     }
 
+    /// [`converse`](Self::converse), compacting old tool results in place
+    /// whenever the prompt doesn't fit, until it does or nothing is left to
+    /// elide. Agent loops use this so a long investigation degrades instead
+    /// of aborting on a provider 400.
+    pub async fn converse_compacting(
+        &self,
+        system: &str,
+        messages: &mut [ConversationMessage],
+        tools: &[ToolDef],
+        context: Option<&ConverseContext<'_>>,
+    ) -> Result<ConversationResponse> {
+        loop {
+            match self.converse(system, messages, tools, context).await {
+                Err(Error::ContextOverflow {
+                    estimated_tokens,
+                    limit,
+                    ..
+                }) if compact_history(messages, KEEP_RECENT_MESSAGES) > 0 => {
+                    info!(
+                        model = %self.model,
+                        estimated_tokens,
+                        limit,
+                        "prompt over context limit, compacted older tool results"
+                    );
+                }
+                result => return result,
+            }
+        }
+    }
+
     async fn converse_anthropic(
         &self,
         system: &str,
@@ -646,6 +738,19 @@ impl LlmClient {
             })
             .unwrap_or_default();
 
+        if matches!(self.provider, Provider::OpenRouter)
+            && let Some(id) = resp.id
+        {
+            self.generations
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(GenerationRecord {
+                    id,
+                    model: self.model.clone(),
+                    estimated_usd: estimate_cost_usd(&usage, &self.model),
+                });
+        }
+
         Ok(ConversationResponse {
             content,
             stop_reason,
@@ -715,8 +820,8 @@ impl LlmClient {
         let augmented_system = format!(
             "{system}\n\n\
              Available tools: Read (view files), Grep (search code), Glob (find files).\n\
-             Do NOT reference list_files, read_file, search_code, get_file_structure — \
-             use Read, Grep, Glob instead."
+             Do NOT reference list_files, read_file, search_code, get_file_structure, \
+             read_project_config, cargo_metadata, program_state — use Read, Grep, Glob instead."
         );
 
         let mut cmd = tokio::process::Command::new("claude");
@@ -919,6 +1024,24 @@ pub enum TaskKind {
     Validation,
     /// Cross-reference analysis between narratives and findings.
     CrossReference,
+    /// Cheap first-pass triage (e.g. which files or findings deserve a closer look).
+    Triage,
+    /// Short prose summaries for reports.
+    Summary,
+    /// Translating non-English signal text to English.
+    Translation,
+}
+
+impl TaskKind {
+    /// Route to try when this task has no client of its own. The cheap passes
+    /// share the cross-reference model (typically the small, fast one) before
+    /// falling back to the default client.
+    pub fn fallback(self) -> Option<TaskKind> {
+        match self {
+            Self::Triage | Self::Summary | Self::Translation => Some(Self::CrossReference),
+            _ => None,
+        }
+    }
 }
 
 /// Routes LLM requests to different models based on task kind.
@@ -940,9 +1063,13 @@ impl ModelRouter {
         self
     }
 
-    /// Get the LLM client for a specific task kind. Falls back to the default.
+    /// Get the LLM client for a specific task kind. Falls back along
+    /// [`TaskKind::fallback`], then to the default.
     pub fn client_for(&self, kind: TaskKind) -> &LlmClient {
-        self.clients.get(&kind).unwrap_or(&self.default)
+        self.clients
+            .get(&kind)
+            .or_else(|| kind.fallback().and_then(|k| self.clients.get(&k)))
+            .unwrap_or(&self.default)
     }
 
     /// Get the default LLM client.
@@ -950,6 +1077,102 @@ impl ModelRouter {
     pub fn default_client(&self) -> &LlmClient {
         &self.default
     }
+
+    /// Drain pending generation records from every routed client.
+    pub fn drain_generations(&self) -> Vec<GenerationRecord> {
+        std::iter::once(&self.default)
+            .chain(self.clients.values())
+            .flat_map(|c| c.drain_generations())
+            .collect()
+    }
+}
+
+/// Fetch the billed cost of one OpenRouter generation.
+pub async fn fetch_openrouter_cost(http: &HttpClient, api_key: &str, id: &str) -> Result<f64> {
+    #[derive(Deserialize)]
+    struct GenerationStats {
+        data: GenerationData,
+    }
+    #[derive(Deserialize)]
+    struct GenerationData {
+        total_cost: f64,
+    }
+    let url = format!("https://openrouter.ai/api/v1/generation?id={id}");
+    let stats: GenerationStats = http.get_json_authed(&url, api_key).await?;
+    Ok(stats.data.total_cost)
+}
+
+/// Rough characters per token. Deliberately low: code and JSON tokenize
+/// denser than prose, and overestimating only compacts a turn early.
+const CHARS_PER_TOKEN: usize = 3;
+
+/// Messages at the tail of a conversation that compaction never touches, so
+/// the model keeps the tool results it is currently reasoning about.
+const KEEP_RECENT_MESSAGES: usize = 2;
+
+/// Tool results at or under this size are left alone by compaction.
+const MIN_COMPACT_CHARS: usize = 200;
+
+/// Provider-agnostic estimate of a request's prompt size in tokens: system
+/// prompt, every message block, and the tool schemas.
+pub fn estimate_prompt_tokens(
+    system: &str,
+    messages: &[ConversationMessage],
+    tools: &[ToolDef],
+) -> u32 {
+    let message_chars: usize = messages
+        .iter()
+        .flat_map(|m| &m.content)
+        .map(|b| match b {
+            ContentBlock::Text { text } => text.len(),
+            ContentBlock::ToolUse { name, input, .. } => name.len() + input.to_string().len(),
+            ContentBlock::ToolResult { content, .. } => content.len(),
+        })
+        .sum();
+    let tool_chars: usize = tools
+        .iter()
+        .map(|t| t.name.len() + t.description.len() + t.input_schema.to_string().len())
+        .sum();
+    let chars = system.len() + message_chars + tool_chars;
+    u32::try_from(chars.div_ceil(CHARS_PER_TOKEN)).unwrap_or(u32::MAX)
+}
+
+/// Replace large tool results with a short placeholder, oldest first,
+/// skipping the first message (the task) and the last `keep_recent`.
+/// Returns the number of characters removed; 0 means nothing was left to elide.
+pub fn compact_history(messages: &mut [ConversationMessage], keep_recent: usize) -> usize {
+    let end = messages.len().saturating_sub(keep_recent);
+    let mut freed = 0;
+    for msg in messages.iter_mut().take(end).skip(1) {
+        for block in &mut msg.content {
+            if let ContentBlock::ToolResult { content, .. } = block
+                && content.len() > MIN_COMPACT_CHARS
+            {
+                let placeholder = format!(
+                    "[elided to fit the context window: {} chars of earlier tool output]",
+                    content.len()
+                );
+                freed += content.len().saturating_sub(placeholder.len());
+                *content = placeholder;
+            }
+        }
+    }
+    freed
+}
+
+/// Built-in context windows by model family; `None` for unknown models,
+/// which skips the guard unless `[llm.context_limits]` names them.
+fn default_context_limit(model: &str) -> Option<u32> {
+    let m = model.to_lowercase();
+    if m.contains("claude") || matches!(m.as_str(), "opus" | "sonnet" | "haiku") {
+        Some(200_000)
+    } else if m.contains("gemini") {
+        Some(1_000_000)
+    } else if m.contains("gpt-4o") || m.contains("llama-3.1") || m.contains("llama-3.3") {
+        Some(128_000)
+    } else {
+        None
+    }
 }
 
 /// Estimate cost in USD for a single API call based on token usage and model.
@@ -1010,6 +1233,24 @@ mod tests {
         assert!((cost - 0.0525).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn estimate_cost_applies_calibration() {
+        let usage = Usage {
+            input_tokens: 1000,
+            output_tokens: 500,
+        };
+        let client = LlmClient::new(
+            Provider::Anthropic,
+            "test-key".into(),
+            "claude-opus-4-20250514".into(),
+            1024,
+            None,
+        )
+        .unwrap()
+        .with_cost_factor(2.0);
+        assert!((client.estimate_cost(&usage) - 0.105).abs() < 1e-12);
+    }
+
     #[test]
     fn estimate_cost_free_model() {
         let usage = Usage {
@@ -1048,4 +1289,97 @@ mod tests {
         };
         assert!((estimate_cost_usd(&zero, "opus") - 0.0).abs() < f64::EPSILON);
     }
+
+    fn tool_result(len: usize) -> ConversationMessage {
+        ConversationMessage {
+            role: Role::User,
+            content: vec![ContentBlock::ToolResult {
+                tool_use_id: "t".into(),
+                content: "x".repeat(len),
+                is_error: false,
+            }],
+        }
+    }
+
+    fn text(role: Role, text: &str) -> ConversationMessage {
+        ConversationMessage {
+            role,
+            content: vec![ContentBlock::Text { text: text.into() }],
+        }
+    }
+
+    #[test]
+    fn prompt_estimate_counts_every_block() {
+        let messages = vec![text(Role::User, &"a".repeat(300)), tool_result(600)];
+        assert_eq!(estimate_prompt_tokens(&"s".repeat(99), &messages, &[]), 333);
+    }
+
+    #[test]
+    fn compaction_spares_task_and_recent_messages() {
+        let mut messages = vec![
+            text(Role::User, &"task ".repeat(100)),
+            tool_result(5000),
+            tool_result(100),
+            text(Role::Assistant, "thinking"),
+            tool_result(5000),
+        ];
+        let freed = compact_history(&mut messages, 2);
+        assert!(freed > 4000);
+        let ContentBlock::ToolResult { content, .. } = &messages[1].content[0] else {
+            panic!("expected tool result");
+        };
+        assert!(content.starts_with("[elided"));
+        // Small and recent results untouched; second pass has nothing left.
+        assert_eq!(estimate_prompt_tokens("", &messages[2..3], &[]), 34);
+        assert_eq!(estimate_prompt_tokens("", &messages[4..], &[]), 1667);
+        assert_eq!(compact_history(&mut messages, 2), 0);
+    }
+
+    #[test]
+    fn default_context_limits_by_family() {
+        assert_eq!(
+            default_context_limit("anthropic/claude-opus-4"),
+            Some(200_000)
+        );
+        assert_eq!(default_context_limit("opus"), Some(200_000));
+        assert_eq!(default_context_limit("llama-3.1-8b-instant"), Some(128_000));
+        assert_eq!(
+            default_context_limit("arcee-ai/trinity-large-preview:free"),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_prompt_refused_before_sending() {
+        let client = LlmClient::new(
+            Provider::OpenAi,
+            "k".into(),
+            "local-model".into(),
+            100,
+            Some("http://localhost:1".into()),
+        )
+        .unwrap()
+        .with_context_limit(Some(1000));
+        let messages = vec![text(Role::User, "go"), tool_result(6000)];
+        let err = client.converse("", &messages, &[], None).await.unwrap_err();
+        assert!(matches!(err, Error::ContextOverflow { limit: 1000, .. }));
+
+        // The task message alone is too big: compaction elides what it can,
+        // then gives up with the same error rather than sending.
+        let mut messages = vec![
+            text(Role::User, &"task ".repeat(1000)),
+            tool_result(6000),
+            text(Role::Assistant, "more"),
+            text(Role::User, "continue"),
+        ];
+        let err = client
+            .converse_compacting("", &mut messages, &[], None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::ContextOverflow { .. }));
+        let ContentBlock::ToolResult { content, .. } = &messages[1].content[0] else {
+            panic!("expected tool result");
+        };
+        assert!(content.starts_with("[elided"));
+    }
 }
@@ -2,13 +2,27 @@ use st_solguard::*;
 
 use anyhow::Result;
 use clap::Parser;
+use output::file::OutputFile;
+use progress::{Progress, ProgressEvent};
 use std::path::PathBuf;
 use tracing::info;
 
+/// Connects an `LlmClient` from `(provider, model, max_tokens, api_key_env, base_url)`.
+type Connect =
+    fn(llm::Provider, String, u32, Option<String>, Option<String>) -> error::Result<llm::LlmClient>;
+
 /// Build an LlmClient from config + optional CLI override.
 fn build_llm_client(
     llm_config: &config::LlmConfig,
     llm_override: Option<&LlmOverride>,
+) -> Result<llm::LlmClient> {
+    connect_llm_client(llm_config, llm_override, llm::LlmClient::from_config)
+}
+
+fn connect_llm_client(
+    llm_config: &config::LlmConfig,
+    llm_override: Option<&LlmOverride>,
+    connect: Connect,
 ) -> Result<llm::LlmClient> {
     let provider = llm_override
         .map(|o| o.provider.clone())
@@ -16,25 +30,61 @@ fn build_llm_client(
     let model = llm_override
         .map(|o| o.model.clone())
         .unwrap_or_else(|| llm_config.model.clone());
-    let client = llm::LlmClient::from_config(
+    let context_limit = llm_config.context_limit(&model);
+    let client = connect(
         provider,
         model,
         llm_config.max_tokens,
         llm_config.api_key_env.clone(),
         llm_config.base_url.clone(),
     )?;
-    Ok(client)
+    Ok(calibrated(client).with_context_limit(context_limit))
+}
+
+/// Like `LlmClient::from_config`, but a missing key yields a keyless client
+/// instead of an error. Only phases `Capabilities::degrade` turned off are
+/// routed to one.
+fn connect_or_keyless(
+    provider: llm::Provider,
+    model: String,
+    max_tokens: u32,
+    api_key_env: Option<String>,
+    base_url: Option<String>,
+) -> error::Result<llm::LlmClient> {
+    llm::LlmClient::from_config(
+        provider.clone(),
+        model.clone(),
+        max_tokens,
+        api_key_env,
+        base_url.clone(),
+    )
+    .or_else(|_| llm::LlmClient::new(provider, String::new(), model, max_tokens, base_url))
+}
+
+/// Apply the per-model cost correction learned by `reconcile-costs`.
+fn calibrated(client: llm::LlmClient) -> llm::LlmClient {
+    let factor = memory::RunMemory::load_or_default().cost_factor(client.model());
+    client.with_cost_factor(factor)
 }
 
 /// Build a ModelRouter from config, with optional CLI override.
 ///
 /// If `--model` is set, all task kinds use the override. Otherwise, per-task
-/// models from `[models]` override the default `[llm]` client.
+/// models from `[models]` override the default `[llm]` client; unset cheap
+/// passes (triage, summary, translation) reuse `cross_reference` first.
 fn build_model_router(
     cfg: &config::Config,
     llm_override: Option<&LlmOverride>,
 ) -> Result<llm::ModelRouter> {
-    let default = build_llm_client(&cfg.llm, llm_override)?;
+    connect_model_router(cfg, llm_override, llm::LlmClient::from_config)
+}
+
+fn connect_model_router(
+    cfg: &config::Config,
+    llm_override: Option<&LlmOverride>,
+    connect: Connect,
+) -> Result<llm::ModelRouter> {
+    let default = connect_llm_client(&cfg.llm, llm_override, connect)?;
 
     // CLI override applies uniformly — no per-task routing
     if llm_override.is_some() {
@@ -44,23 +94,27 @@ fn build_model_router(
     let mut router = llm::ModelRouter::new(default);
 
     if let Some(ref models) = cfg.models {
-        let pairs: [(&Option<config::ModelConfig>, llm::TaskKind); 5] = [
+        let pairs: [(&Option<config::ModelConfig>, llm::TaskKind); 8] = [
             (&models.narrative, llm::TaskKind::NarrativeSynthesis),
             (&models.discovery, llm::TaskKind::NarrativeDiscovery),
             (&models.investigation, llm::TaskKind::DeepInvestigation),
             (&models.validation, llm::TaskKind::Validation),
             (&models.cross_reference, llm::TaskKind::CrossReference),
+            (&models.triage, llm::TaskKind::Triage),
+            (&models.summary, llm::TaskKind::Summary),
+            (&models.translation, llm::TaskKind::Translation),
         ];
         for (model_cfg, kind) in pairs {
             if let Some(mc) = model_cfg {
-                let client = llm::LlmClient::from_config(
+                let client = connect(
                     mc.provider.clone(),
                     mc.model.clone(),
                     mc.max_tokens.unwrap_or(cfg.llm.max_tokens),
                     mc.api_key_env.clone(),
                     mc.base_url.clone(),
-                )?;
-                router = router.with_client(kind, client);
+                )?
+                .with_context_limit(cfg.llm.context_limit(&mc.model));
+                router = router.with_client(kind, calibrated(client));
             }
         }
     }
@@ -106,10 +160,14 @@ enum Command {
         #[arg(short, long, default_value = "config.toml")]
         config: PathBuf,
 
-        /// Output path for the combined HTML report
+        /// Output path for the combined HTML report (`-` for stdout)
         #[arg(short, long, default_value = "solguard-report.html")]
         output: PathBuf,
 
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+
         /// Directory to clone repos into for scanning
         #[arg(long, default_value = "repos")]
         repos_dir: PathBuf,
@@ -129,6 +187,43 @@ enum Command {
         /// Force static-only scanning (disables deep agent review)
         #[arg(long)]
         static_only: bool,
+
+        /// Skip LLM validation of deep-review findings
+        #[arg(long)]
+        skip_validation: bool,
+
+        /// Skip LLM relevance summaries in cross-referencing (scoring still runs)
+        #[arg(long)]
+        skip_cross_ref: bool,
+
+        /// Reuse narratives from a JSON file (output of `narratives`) instead of detecting them
+        #[arg(long)]
+        narratives_from: Option<PathBuf>,
+
+        /// Group report findings by title, pattern, file, instruction or none
+        /// (overrides `[report] group_by`)
+        #[arg(long, value_enum)]
+        group_by: Option<config::GroupBy>,
+
+        /// Zero-cost exploration: route every task to an OpenRouter `:free`
+        /// model (`--model` or `[explore] model`), run static analysis and
+        /// triage only, and mark the report as exploration quality
+        #[arg(long, conflicts_with = "provider")]
+        explore: bool,
+
+        /// Start deep scans without confirming, even above `[budget] confirm_above_usd`
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Clone every target fresh into `<repos-dir>/runs/<run-id>/` and write
+        /// a provenance manifest there, instead of reusing shared checkouts
+        #[arg(long)]
+        isolate: bool,
+
+        /// Repos to scan at once; their deep reviews share the run budget
+        /// (overrides `[agent_review] parallel_repos`)
+        #[arg(short, long)]
+        jobs: Option<usize>,
     },
 
     /// Run narrative detection only
@@ -144,17 +239,39 @@ enum Command {
         /// LLM model override
         #[arg(long)]
         model: Option<String>,
+
+        /// Write narratives to file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+
+        /// Only keep results carrying one of these tags (repeatable)
+        #[arg(long = "filter-tag")]
+        filter_tag: Vec<String>,
+
+        /// Skip every LLM call: no discovery, and templated narratives built
+        /// from signal categories and keywords instead of synthesis
+        #[arg(long, conflicts_with_all = ["provider", "model"])]
+        no_llm: bool,
     },
 
     /// Scan a specific repo for vulnerabilities
     Scan {
-        /// Path to the repository
+        /// Path to the repository, or a git URL to clone
+        #[arg(value_name = "REPO_PATH_OR_URL")]
         repo_path: PathBuf,
 
         /// Use multi-turn agent investigation (LLM-powered deep review)
         #[arg(long)]
         deep: bool,
 
+        /// Also scan `cargo expand` output for macro-generated handlers (needs cargo-expand)
+        #[arg(long)]
+        expand_macros: bool,
+
         /// LLM provider override: anthropic, openrouter, openai
         #[arg(long)]
         provider: Option<String>,
@@ -170,12 +287,103 @@ enum Command {
         /// Write findings to file instead of stdout
         #[arg(short, long)]
         output: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+
+        /// Only keep results carrying one of these tags (repeatable)
+        #[arg(long = "filter-tag")]
+        filter_tag: Vec<String>,
+
+        /// Write suggested fixes (SOL-003, SOL-010) as `.patch` files to this directory
+        #[arg(long)]
+        patches_dir: Option<PathBuf>,
+
+        /// Only report findings missing from this baseline; records it on first use
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Only report findings on lines changed since this git ref (e.g. `origin/main`)
+        #[arg(long, value_name = "BASE_REF")]
+        diff: Option<String>,
+
+        /// Scan this commit, branch or tag (checked out in a temporary worktree)
+        #[arg(long, alias = "ref")]
+        rev: Option<String>,
+
+        /// Only scan this repo-relative subdirectory (e.g. `programs/vault` in a monorepo)
+        #[arg(long = "path", value_name = "SUBDIR")]
+        subdir: Option<PathBuf>,
+
+        /// Look up who can upgrade or close each declared program on chain (`[solana] rpc_url`)
+        #[arg(long)]
+        posture: bool,
+    },
+
+    /// Download a crate from crates.io and scan its source
+    ScanCrate {
+        /// Crate and exact version, e.g. `spl-token@4.0.0`
+        #[arg(value_name = "NAME@VERSION")]
+        krate: security::crates_io::CrateSpec,
+
+        /// Use multi-turn agent investigation (LLM-powered deep review)
+        #[arg(long)]
+        deep: bool,
+
+        /// LLM provider override: anthropic, openrouter, openai
+        #[arg(long)]
+        provider: Option<String>,
+
+        /// LLM model override
+        #[arg(long)]
+        model: Option<String>,
+
+        /// Path to config file (for agent_review settings)
+        #[arg(short, long, default_value = "config.toml")]
+        config: PathBuf,
+
+        /// Write findings to file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+
+        /// Only keep results carrying one of these tags (repeatable)
+        #[arg(long = "filter-tag")]
+        filter_tag: Vec<String>,
+    },
+
+    /// Static-scan a repo at a series of tags or commits and show when each finding appeared or disappeared
+    HistoryScan {
+        /// Path to the repository, or a git URL to clone
+        #[arg(value_name = "REPO_PATH_OR_URL")]
+        repo_path: PathBuf,
+
+        /// Revisions to scan: a range (`v1.0..v1.4`) or a comma-separated list of tags or commits
+        #[arg(long, alias = "revs", value_name = "REVS")]
+        tags: String,
+
+        /// Path to config file (for `[scan]` settings)
+        #[arg(short, long, default_value = "config.toml")]
+        config: PathBuf,
+
+        /// Write the timeline to file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
     },
 
     /// Investigate a repo with the multi-turn security agent (deep review only)
     Investigate {
-        /// Path to the repository
-        repo_path: PathBuf,
+        /// Path to the repository (with --resume, overrides where the session's repo now is)
+        #[arg(required_unless_present = "resume")]
+        repo_path: Option<PathBuf>,
 
         /// LLM provider override: anthropic, openrouter, openai
         #[arg(long)]
@@ -200,6 +408,36 @@ enum Command {
         /// Write findings to file instead of stdout
         #[arg(long)]
         output: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+
+        /// Only keep results carrying one of these tags (repeatable)
+        #[arg(long = "filter-tag")]
+        filter_tag: Vec<String>,
+
+        /// Area the agent should prioritise, e.g. "liquidation math" (repeatable)
+        #[arg(long)]
+        focus: Vec<String>,
+
+        /// Protocol category whose built-in focus areas to add (dex, lending,
+        /// staking, vault, nft, ...)
+        #[arg(long)]
+        category: Option<String>,
+
+        /// Use only --focus areas, not the --category ones
+        #[arg(long, requires = "focus")]
+        focus_only: bool,
+
+        /// Investigate this commit, branch or tag (checked out in a temporary worktree)
+        #[arg(long, alias = "ref")]
+        rev: Option<String>,
+
+        /// Continue a checkpointed session (`~/.solguard/sessions/<id>.json`)
+        /// with a fresh --max-turns/--cost-limit budget
+        #[arg(long, value_name = "SESSION_ID", conflicts_with_all = ["focus", "category", "rev"])]
+        resume: Option<String>,
     },
 
     /// Test a repo: investigate → validate findings → summary (development/calibration)
@@ -230,6 +468,22 @@ enum Command {
         /// Write results to file instead of stdout
         #[arg(long)]
         output: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+
+        /// Append the calibration summary to a benchmark log (`.csv`, else JSON Lines)
+        #[arg(long)]
+        benchmark_log: Option<PathBuf>,
+
+        /// Score recall against a ground-truth pack (JSON list of the repo's known vulnerabilities)
+        #[arg(long)]
+        ground_truth: Option<PathBuf>,
+
+        /// Don't record the run in the model leaderboard (`~/.solguard/leaderboard.jsonl`)
+        #[arg(long)]
+        no_leaderboard: bool,
     },
 
     /// Render a report from pre-computed analysis files (no LLM calls)
@@ -238,13 +492,262 @@ enum Command {
         #[arg(long)]
         narratives: PathBuf,
 
-        /// Path to security findings JSON file
+        /// Path to findings JSON from `scan`, `investigate` or `test`, or raw agent findings
         #[arg(long)]
         findings: PathBuf,
 
-        /// Output path for the combined HTML report
+        /// Output path for the combined HTML report (`-` for stdout)
         #[arg(short, long, default_value = "solguard-report.html")]
         output: PathBuf,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+
+        /// Only keep results carrying one of these tags (repeatable)
+        #[arg(long = "filter-tag")]
+        filter_tag: Vec<String>,
+
+        /// Who the report is for: `public` omits file paths, code and exploit
+        /// scenarios; `partner` keeps paths but drops attack steps
+        #[arg(long, value_enum, default_value_t = output::Audience::Internal)]
+        audience: output::Audience,
+
+        /// Group findings by title, pattern, file, instruction or none
+        /// (overrides `[report] group_by`)
+        #[arg(long, value_enum)]
+        group_by: Option<config::GroupBy>,
+
+        /// Path to config file (for `[report]` settings)
+        #[arg(short, long, default_value = "config.toml")]
+        config: PathBuf,
+    },
+
+    /// Compare billed LLM costs with estimates and update per-model correction factors
+    ReconcileCosts {
+        /// Model to record a manual billing entry for
+        #[arg(long, requires_all = ["estimated", "billed"])]
+        model: Option<String>,
+
+        /// Estimated cost in USD (as reported by solguard) for the manual entry
+        #[arg(long)]
+        estimated: Option<f64>,
+
+        /// Billed cost in USD from the provider dashboard for the manual entry
+        #[arg(long)]
+        billed: Option<f64>,
+
+        /// Fetch billed costs for pending OpenRouter generations (needs OPENROUTER_API_KEY)
+        #[arg(long)]
+        openrouter: bool,
+    },
+
+    /// Re-run finding extraction and verdict parsing over saved transcripts (no LLM calls)
+    ReplayFindings {
+        /// Session JSON files (investigation/validation transcripts + expectations)
+        #[arg(required = true)]
+        sessions: Vec<PathBuf>,
+
+        /// Write replay results to file instead of stdout
+        #[arg(long)]
+        output: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Static pattern tooling
+    Rules {
+        #[command(subcommand)]
+        command: RulesCommand,
+    },
+
+    /// Inspect the signals saved by past narrative runs
+    Signals {
+        #[command(subcommand)]
+        command: SignalsCommand,
+    },
+
+    /// Inspect or edit run memory (`~/.solguard/memory.json`)
+    Memory {
+        #[command(subcommand)]
+        command: MemoryCommand,
+    },
+
+    /// Narrative confidence, risk score and per-repo finding changes between two saved runs
+    CompareRuns {
+        /// Earlier run: timestamp or prefix (e.g. 20260301), from `~/.solguard/history`
+        run_a: String,
+
+        /// Later run: timestamp or prefix
+        run_b: String,
+
+        /// Print the comparison as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Compare models across recorded `test` runs
+    Models {
+        #[command(subcommand)]
+        command: ModelsCommand,
+    },
+
+    /// Print a repo's trust-boundary diagram (handlers, accounts, CPIs) as Mermaid
+    Graph {
+        /// Path to the repository
+        repo_path: PathBuf,
+    },
+
+    /// Write cargo-fuzz harness skeletons, one target per Anchor instruction handler
+    Fuzz {
+        /// Path to the repository
+        repo_path: PathBuf,
+
+        /// Only handlers with findings in this JSON (from `scan` or `extract --findings`)
+        #[arg(long)]
+        findings: Option<PathBuf>,
+
+        /// Directory to write one fuzz project per program into
+        #[arg(long, default_value = "fuzz")]
+        out: PathBuf,
+
+        /// Overwrite generated files that already exist
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Recover the narratives and findings embedded in a rendered HTML report
+    Extract {
+        /// Report HTML produced by `run` or `render`
+        report: PathBuf,
+
+        /// Write narratives JSON here (usable with `render --narratives`)
+        #[arg(long)]
+        narratives: Option<PathBuf>,
+
+        /// Write findings JSON here (usable with `render --findings`)
+        #[arg(long)]
+        findings: Option<PathBuf>,
+
+        /// Overwrite the output file if it already exists
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Create or update Linear issues / Notion rows for confirmed findings (`[export]`)
+    Export {
+        /// Findings JSON from `scan` or `extract --findings`
+        findings: PathBuf,
+
+        /// Repository the findings came from; evidence snippets are read from it
+        #[arg(long)]
+        repo_path: PathBuf,
+
+        /// Repo name for titles and fingerprints (default: the directory name)
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Path to config file (for `[export]` settings)
+        #[arg(short, long, default_value = "config.toml")]
+        config: PathBuf,
+    },
+
+    /// Poll tracked programs on chain and alert on activity or failure-rate anomalies
+    WatchChain {
+        /// Path to config file (for `[solana]`, `[watch]` and `[alerts]` settings)
+        #[arg(short, long, default_value = "config.toml")]
+        config: PathBuf,
+
+        /// Seconds between polls (overrides `[watch] interval_secs`)
+        #[arg(long)]
+        interval: Option<u64>,
+
+        /// Poll once, update the baselines and exit (for cron)
+        #[arg(long)]
+        once: bool,
+
+        /// Baselines file (default: ~/.solguard/chain_baselines.json)
+        #[arg(long)]
+        baselines: Option<PathBuf>,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum SignalsCommand {
+    /// List saved runs, oldest first
+    List,
+
+    /// Print a run's signals with their metrics and URLs
+    Show {
+        /// Only show signals from this source
+        #[arg(long, value_enum)]
+        source: Option<narrative::signals::SignalSource>,
+
+        /// Run timestamp or prefix (e.g. 20260301); defaults to the newest run
+        #[arg(long)]
+        run: Option<String>,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum MemoryCommand {
+    /// Inspect or edit the repos skipped by future runs
+    Blocklist {
+        #[command(subcommand)]
+        command: BlocklistCommand,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum BlocklistCommand {
+    /// Print blocklisted repos, one per line
+    List,
+
+    /// Skip a repo in future runs
+    Add {
+        /// Repo name or `owner/name`
+        repo: String,
+    },
+
+    /// Scan a repo again, forgetting the failures that blocklisted it
+    Remove {
+        /// Repo name or `owner/name`
+        repo: String,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum ModelsCommand {
+    /// Per-model recall, precision, false-positive rate, cost and turns
+    Leaderboard {
+        /// Only count runs on this repo
+        #[arg(long)]
+        repo: Option<String>,
+
+        /// Leaderboard log (default: ~/.solguard/leaderboard.jsonl)
+        #[arg(long)]
+        log: Option<PathBuf>,
+
+        /// Print the standings as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(clap::Subcommand)]
+enum RulesCommand {
+    /// Run every pattern over its vulnerable/safe corpus and report misses
+    Test {
+        /// Corpus root with one `<PATTERN-ID>/{vulnerable,safe}/` directory per pattern
+        /// (repeatable; snippets for the same pattern are pooled)
+        #[arg(long, default_value = security::rule_corpus::DEFAULT_CORPUS)]
+        corpus: Vec<PathBuf>,
+
+        /// Print the per-pattern results as JSON
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -266,50 +769,251 @@ async fn main() -> Result<()> {
         Command::Run {
             config,
             output,
+            force,
             repos_dir,
             provider,
             model,
             deep,
             static_only,
+            skip_validation,
+            skip_cross_ref,
+            narratives_from,
+            group_by,
+            explore,
+            yes,
+            isolate,
+            jobs,
         } => {
-            let llm_override = make_llm_override(provider, model);
-            let cfg = config::Config::load(&config).unwrap_or_default();
-            let router = build_model_router(&cfg, llm_override.as_ref())?;
-            let deep = deep && !static_only;
-            agent::run_full_pipeline(config, output, repos_dir, llm_override, router, deep).await
+            let output = OutputFile::new(output, force);
+            output.check()?;
+            let cfg = config::Config::load(&config)?;
+            let explore_model = if explore {
+                Some(agent::explore::model(&cfg.explore, model.clone())?)
+            } else {
+                None
+            };
+            let llm_override = match &explore_model {
+                Some(m) => Some(agent::explore::llm_override(m)),
+                None => make_llm_override(provider, model),
+            };
+            let (progress, events) = Progress::channel();
+            let mut opts = agent::PipelineOptions {
+                deep: deep && !static_only,
+                skip_validation,
+                skip_cross_ref,
+                narratives_from,
+                progress,
+                group_by,
+                assume_yes: yes,
+                isolate,
+                jobs,
+                ..Default::default()
+            };
+            if explore_model.is_some() {
+                agent::explore::restrict(&mut opts);
+            }
+            let caps = agent::capabilities::Capabilities::detect(&cfg, llm_override.as_ref());
+            caps.degrade(&mut opts);
+            eprint!("{}", caps.render_matrix(&opts));
+            let router = match &explore_model {
+                Some(m) if caps.llm() => agent::explore::router(&cfg, m)?,
+                _ => connect_model_router(&cfg, llm_override.as_ref(), connect_or_keyless)?,
+            };
+            let printer = tokio::spawn(print_progress(events));
+            let result =
+                agent::run_full_pipeline(config, output, repos_dir, llm_override, router, opts)
+                    .await;
+            // The pipeline dropped its sender, so the printer drains and exits.
+            let _ = printer.await;
+            result
         }
         Command::Narratives {
             config,
             provider,
             model,
+            output,
+            force,
+            filter_tag,
+            no_llm,
         } => {
+            let output = OutputFile::from_arg(output, force);
+            output.check()?;
             let llm_override = make_llm_override(provider, model);
-            let cfg = config::Config::load(&config).unwrap_or_default();
-            let router = build_model_router(&cfg, llm_override.as_ref())?;
-            let narratives =
-                narrative::run_narrative_pipeline(&config, llm_override.as_ref(), &router).await?;
+            let cfg = config::Config::load(&config)?;
+            let router = if no_llm {
+                None
+            } else {
+                Some(build_model_router(&cfg, llm_override.as_ref())?)
+            };
+            let mut narratives =
+                narrative::run_narrative_pipeline(&config, llm_override.as_ref(), router.as_ref())
+                    .await?;
+            if let Some(router) = &router {
+                record_generations(router.drain_generations());
+            }
+            narratives.retain(|n| tags::matches_filter(&n.tags, &filter_tag));
             let json = serde_json::to_string_pretty(&narratives)?;
-            println!("{json}");
+            write_or_print(&json, &output)?;
             Ok(())
         }
         Command::Scan {
             repo_path,
             deep,
+            expand_macros,
             provider,
             model,
             config,
             output,
+            force,
+            filter_tag,
+            patches_dir,
+            baseline,
+            diff,
+            rev,
+            subdir,
+            posture,
         } => {
-            let findings = if deep {
-                let cfg = config::Config::load(&config).unwrap_or_default();
-                let llm_override = make_llm_override(provider, model);
-                let llm = build_llm_client(&cfg.llm, llm_override.as_ref())?;
-                security::scan_repo_deep(&repo_path, &llm, &cfg.agent_review, None).await?
-            } else {
-                security::scan_repo(&repo_path).await?
+            let output = OutputFile::from_arg(output, force);
+            output.check()?;
+            let mut cfg = config::Config::load(&config)?;
+            cfg.scan.expand_macros |= expand_macros;
+            cfg.scan.subdir = subdir;
+            let llm_override = make_llm_override(provider, model);
+            // A URL is cloned and always scanned through a worktree, so
+            // findings carry repo-relative paths and the commit.
+            let remote = match repo_path.to_str().filter(|p| security::revision::is_url(p)) {
+                Some(url) => Some(security::revision::RemoteClone::fetch(url).await?),
+                None => None,
             };
+            let repo_path = remote.as_ref().map_or(repo_path, |r| r.dir.clone());
+            let rev = rev.or_else(|| remote.is_some().then(|| "HEAD".to_string()));
+            let worktree = match &rev {
+                Some(rev) => Some(security::revision::Worktree::checkout(&repo_path, rev).await?),
+                None => None,
+            };
+            // Everything that reads source reads the checked-out revision.
+            let scan_root = worktree.as_ref().map_or(repo_path.as_path(), |w| &w.root);
+            let changes = match &diff {
+                Some(base) => Some(security::diff::ChangedLines::since(scan_root, base).await?),
+                None => None,
+            };
+            if let Some(dir) = &cfg.scan.subdir
+                && !scan_root.join(dir).is_dir()
+            {
+                anyhow::bail!("--path {} is not a directory in the repo", dir.display());
+            }
+            if changes.as_ref().is_some_and(|c| c.is_empty()) {
+                eprintln!("No changes since {}", diff.as_deref().unwrap_or_default());
+                write_or_print("[]", &output)?;
+                return Ok(());
+            }
+            // Point the agent at the changed files and the subdirectory; the
+            // static scan still reads everything the `[scan]` filters allow.
+            let mut focus = Vec::new();
+            if let Some(c) = &changes {
+                let files: Vec<String> = c.files().map(|f| f.display().to_string()).collect();
+                focus.push(format!(
+                    "Code changed in this pull request: {}. Report only vulnerabilities the change introduces.",
+                    files.join(", ")
+                ));
+            }
+            if let Some(dir) = &cfg.scan.subdir {
+                focus.push(format!(
+                    "Only the code under `{}`. Read the rest of the repo for context, but report only vulnerabilities in that directory.",
+                    dir.display()
+                ));
+            }
+            let scan_context = (!focus.is_empty()).then(|| security::agent_review::ScanContext {
+                focus,
+                ..Default::default()
+            });
+            let mut findings =
+                scan_path(scan_root, &cfg, deep, llm_override, scan_context.as_ref()).await?;
+            if posture {
+                let http = http::HttpClient::new("st-solguard/0.1.0")?;
+                let rpc = narrative::rpc_pool::RpcPool::new(&cfg.solana, &http);
+                findings.extend(security::posture::check_repo(scan_root, &rpc).await);
+            }
+            if let Some(worktree) = &worktree {
+                worktree.attribute(&mut findings);
+            }
+            tags::tag_findings(&mut findings, &cfg.tagging.rules);
+            assign_finding_ids(&repo_path, &mut findings);
+            let mut findings = hooks::HookRegistry::from_config(&cfg.hooks).run(findings)?;
+            if let Some(changes) = &changes {
+                let dropped = changes.retain_introduced(&mut findings, scan_root);
+                eprintln!("{dropped} finding(s) outside the diff dropped");
+            }
+            if let Some(path) = &baseline {
+                match security::baseline::apply(path, scan_root, &mut findings)? {
+                    security::baseline::Outcome::Recorded(n) => {
+                        eprintln!("Baseline of {n} finding(s) recorded to {}", path.display());
+                    }
+                    security::baseline::Outcome::Filtered { suppressed } => {
+                        eprintln!("{suppressed} baselined finding(s) suppressed");
+                    }
+                }
+            }
+            findings.retain(|f| tags::matches_filter(&f.tags, &filter_tag));
+            if let Some(dir) = &patches_dir {
+                let written = security::patch::write_all(&findings, dir, force)?;
+                eprintln!(
+                    "{} patch file(s) written to {}",
+                    written.len(),
+                    dir.display()
+                );
+            }
+            let json = serde_json::to_string_pretty(&findings)?;
+            write_or_print(&json, &output)?;
+            Ok(())
+        }
+        Command::HistoryScan {
+            repo_path,
+            tags,
+            config,
+            output,
+            force,
+        } => {
+            let output = OutputFile::from_arg(output, force);
+            output.check()?;
+            let cfg = config::Config::load(&config)?;
+            let remote = match repo_path.to_str().filter(|p| security::revision::is_url(p)) {
+                Some(url) => Some(security::revision::RemoteClone::fetch(url).await?),
+                None => None,
+            };
+            let repo_path = remote.as_ref().map_or(repo_path, |r| r.dir.clone());
+            let revs = security::history::resolve(&repo_path, &tags).await?;
+            eprintln!("Scanning {} revision(s)", revs.len());
+            let timeline = security::history::scan(&repo_path, &revs, &cfg.scan).await?;
+            eprintln!("{}", timeline.summary());
+            write_or_print(&serde_json::to_string_pretty(&timeline)?, &output)?;
+            Ok(())
+        }
+        Command::ScanCrate {
+            krate,
+            deep,
+            provider,
+            model,
+            config,
+            output,
+            force,
+            filter_tag,
+        } => {
+            let output = OutputFile::from_arg(output, force);
+            output.check()?;
+            let cfg = config::Config::load(&config)?;
+            let llm_override = make_llm_override(provider, model);
+            let workdir = std::env::temp_dir().join(format!(
+                "solguard-{}-{}",
+                krate.dir_name(),
+                std::process::id()
+            ));
+            let result = scan_crate(&krate, &workdir, &cfg, deep, llm_override).await;
+            let _ = std::fs::remove_dir_all(&workdir);
+            let mut findings = result?;
+            findings.retain(|f| tags::matches_filter(&f.tags, &filter_tag));
             let json = serde_json::to_string_pretty(&findings)?;
-            write_or_print(&json, output.as_deref())?;
+            write_or_print(&json, &output)?;
             Ok(())
         }
         Command::Investigate {
@@ -320,10 +1024,27 @@ async fn main() -> Result<()> {
             cost_limit,
             config,
             output,
+            force,
+            filter_tag,
+            focus,
+            category,
+            focus_only,
+            rev,
+            resume,
         } => {
-            let cfg = config::Config::load(&config).unwrap_or_default();
+            let output = OutputFile::from_arg(output, force);
+            output.check()?;
+            let cfg = config::Config::load(&config)?;
             let llm_override = make_llm_override(provider, model);
             let llm = build_llm_client(&cfg.llm, llm_override.as_ref())?;
+            let scan_ctx = (!focus.is_empty() || category.is_some()).then(|| {
+                security::agent_review::ScanContext {
+                    protocol_category: category,
+                    focus,
+                    focus_only,
+                    ..Default::default()
+                }
+            });
             let mut agent_config = cfg.agent_review;
             if let Some(turns) = max_turns {
                 agent_config.max_turns = turns;
@@ -331,9 +1052,55 @@ async fn main() -> Result<()> {
             if let Some(limit) = cost_limit {
                 agent_config.cost_limit_usd = limit;
             }
-            let findings = security::scan_repo_deep(&repo_path, &llm, &agent_config, None).await?;
+            let http = http::HttpClient::new("st-solguard/0.1.0")?;
+            let rpc = narrative::rpc_pool::RpcPool::new(&cfg.solana, &http);
+            let (mut findings, repo_path) = if let Some(id) = resume {
+                let mut session = security::session::Session::load(&id)?;
+                if let Some(path) = repo_path {
+                    session.repo_path = path.canonicalize().unwrap_or(path);
+                }
+                let repo_path = session.repo_path.clone();
+                let findings = security::resume_deep(
+                    session,
+                    &llm,
+                    &agent_config,
+                    &cfg.scan,
+                    &rpc,
+                    &Progress::default(),
+                )
+                .await?;
+                (findings, repo_path)
+            } else {
+                let repo_path = repo_path.expect("clap requires repo_path without --resume");
+                let worktree = match &rev {
+                    Some(rev) => {
+                        Some(security::revision::Worktree::checkout(&repo_path, rev).await?)
+                    }
+                    None => None,
+                };
+                let scan_root = worktree.as_ref().map_or(repo_path.as_path(), |w| &w.root);
+                let mut findings = security::scan_repo_deep(
+                    scan_root,
+                    &llm,
+                    &agent_config,
+                    &cfg.scan,
+                    scan_ctx.as_ref(),
+                    &rpc,
+                    &Progress::default(),
+                )
+                .await?;
+                if let Some(worktree) = &worktree {
+                    worktree.attribute(&mut findings);
+                }
+                (findings, repo_path)
+            };
+            record_generations(llm.drain_generations());
+            tags::tag_findings(&mut findings, &cfg.tagging.rules);
+            assign_finding_ids(&repo_path, &mut findings);
+            let mut findings = hooks::HookRegistry::from_config(&cfg.hooks).run(findings)?;
+            findings.retain(|f| tags::matches_filter(&f.tags, &filter_tag));
             let json = serde_json::to_string_pretty(&findings)?;
-            write_or_print(&json, output.as_deref())?;
+            write_or_print(&json, &output)?;
             Ok(())
         }
         Command::Test {
@@ -344,8 +1111,18 @@ async fn main() -> Result<()> {
             cost_limit,
             config,
             output,
+            force,
+            benchmark_log,
+            ground_truth,
+            no_leaderboard,
         } => {
-            let cfg = config::Config::load(&config).unwrap_or_default();
+            let output = OutputFile::from_arg(output, force);
+            output.check()?;
+            let ground_truth = ground_truth
+                .map(|path| security::benchmark::GroundTruthPack::load(&path))
+                .transpose()?;
+            let started = std::time::Instant::now();
+            let cfg = config::Config::load(&config)?;
             let llm_override = make_llm_override(provider, model);
             let llm = build_llm_client(&cfg.llm, llm_override.as_ref())?;
             let mut agent_config = cfg.agent_review;
@@ -358,9 +1135,18 @@ async fn main() -> Result<()> {
 
             // Phase 1: Investigate
             info!("phase 1: investigating repository");
-            let (findings, inv_stats) =
-                security::agent_review::investigate(&llm, &repo_path, &agent_config, None, None)
-                    .await?;
+            let http = http::HttpClient::new("st-solguard/0.1.0")?;
+            let rpc = narrative::rpc_pool::RpcPool::new(&cfg.solana, &http);
+            let (findings, inv_stats) = security::agent_review::investigate(
+                &llm,
+                &repo_path,
+                &agent_config,
+                None,
+                None,
+                &rpc,
+                &Progress::default(),
+            )
+            .await?;
             info!(
                 findings = findings.len(),
                 turns = inv_stats.turns,
@@ -370,44 +1156,557 @@ async fn main() -> Result<()> {
 
             // Phase 2: Validate
             info!("phase 2: validating findings");
-            let validated =
-                security::validator::validate(&llm, &repo_path, &findings, &agent_config).await?;
-            let confirmed = validated
-                .iter()
-                .filter(|v| matches!(v.verdict, security::validator::Verdict::Confirmed))
-                .count();
-            let disputed = validated
-                .iter()
-                .filter(|v| matches!(v.verdict, security::validator::Verdict::Disputed))
-                .count();
-            let dismissed = validated
-                .iter()
-                .filter(|v| matches!(v.verdict, security::validator::Verdict::Dismissed))
-                .count();
-            info!(confirmed, disputed, dismissed, "validation complete");
+            let (validated, val_stats) = security::validator::validate_with_stats(
+                &llm,
+                &repo_path,
+                &findings,
+                &agent_config,
+            )
+            .await?;
+
+            let repo = repo_path
+                .canonicalize()
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+                .unwrap_or_else(|| repo_path.display().to_string());
+            let mut summary = security::benchmark::CalibrationSummary::new(
+                &repo,
+                llm.model(),
+                &validated,
+                &inv_stats,
+                &val_stats,
+                started.elapsed(),
+            );
+            if let Some(pack) = &ground_truth {
+                summary = summary.with_ground_truth(pack, &validated);
+            }
+            info!(
+                confirmed = summary.confirmed,
+                disputed = summary.disputed,
+                dismissed = summary.dismissed,
+                "validation complete"
+            );
+            eprint!("{}", summary.render());
+            record_generations(llm.drain_generations());
+            if let Some(path) = benchmark_log {
+                summary.append_to(&path)?;
+                eprintln!("Benchmark row appended to {}", path.display());
+            }
+            if !no_leaderboard {
+                summary.append_to(&security::benchmark::leaderboard_path())?;
+            }
 
             let json = serde_json::to_string_pretty(&validated)?;
-            write_or_print(&json, output.as_deref())?;
+            write_or_print(&json, &output)?;
             Ok(())
         }
         Command::Render {
             narratives,
             findings,
             output,
-        } => render_from_files(narratives, findings, output),
+            force,
+            filter_tag,
+            audience,
+            group_by,
+            config,
+        } => {
+            let cfg = config::Config::load(&config)?;
+            let notices = output::ReportNotices {
+                audience,
+                group_by: group_by.unwrap_or(cfg.report.group_by),
+                ..Default::default()
+            };
+            render_from_files(
+                narratives,
+                findings,
+                OutputFile::new(output, force),
+                &filter_tag,
+                &notices,
+            )
+        }
+        Command::ReconcileCosts {
+            model,
+            estimated,
+            billed,
+            openrouter,
+        } => reconcile_costs(model, estimated, billed, openrouter).await,
+        Command::ReplayFindings {
+            sessions,
+            output,
+            force,
+        } => replay_findings(&sessions, OutputFile::from_arg(output, force)),
+        Command::Rules {
+            command: RulesCommand::Test { corpus, json },
+        } => test_rules(&corpus, json),
+        Command::Models {
+            command: ModelsCommand::Leaderboard { repo, log, json },
+        } => {
+            let log = log.unwrap_or_else(security::benchmark::leaderboard_path);
+            let mut runs = security::benchmark::load_runs(&log)?;
+            if let Some(repo) = &repo {
+                runs.retain(|r| &r.repo == repo);
+            }
+            let standings = security::benchmark::leaderboard(&runs);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&standings)?);
+            } else if standings.is_empty() {
+                eprintln!(
+                    "No runs recorded in {}; `test` adds one per run.",
+                    log.display()
+                );
+            } else {
+                print!("{}", security::benchmark::render_leaderboard(&standings));
+            }
+            Ok(())
+        }
+        Command::Signals { command } => {
+            let dir = narrative::signals::signals_dir();
+            match command {
+                SignalsCommand::List => {
+                    for run in narrative::signals::list_runs(&dir)? {
+                        println!("{run}");
+                    }
+                }
+                SignalsCommand::Show { source, run } => {
+                    let set = narrative::signals::load(&dir, run.as_deref())?;
+                    print!("{}", set.render(source));
+                }
+            }
+            Ok(())
+        }
+        Command::CompareRuns { run_a, run_b, json } => {
+            let a = memory::RunHistory::load(&run_a)?;
+            let b = memory::RunHistory::load(&run_b)?;
+            let comparison = compare::compare(&a, &b);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&comparison)?);
+            } else {
+                print!("{}", comparison.render());
+            }
+            Ok(())
+        }
+        Command::Memory {
+            command: MemoryCommand::Blocklist { command },
+        } => {
+            let mut run_memory = memory::RunMemory::load_or_default();
+            match command {
+                BlocklistCommand::List => {
+                    for repo in &run_memory.repo_blocklist {
+                        println!("{repo}");
+                    }
+                }
+                BlocklistCommand::Add { repo } => {
+                    if run_memory.block(&repo) {
+                        run_memory.save()?;
+                        eprintln!("Blocklisted {}", paths::target_name(&repo));
+                    } else {
+                        eprintln!("{} is already blocklisted", paths::target_name(&repo));
+                    }
+                }
+                BlocklistCommand::Remove { repo } => {
+                    // Saved either way: its error counts are cleared too.
+                    let removed = run_memory.unblock(&repo);
+                    run_memory.save()?;
+                    if !removed {
+                        anyhow::bail!("{} is not blocklisted", paths::target_name(&repo));
+                    }
+                    eprintln!("Removed {} from the blocklist", paths::target_name(&repo));
+                }
+            }
+            Ok(())
+        }
+        Command::Graph { repo_path } => {
+            let name = repo_path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| "repo".into());
+            let graph = security::trust_graph::build(&name, &repo_path);
+            if graph.is_empty() {
+                anyhow::bail!("no #[program] module found in {}", repo_path.display());
+            }
+            print!("{}", graph.to_mermaid());
+            Ok(())
+        }
+        Command::Fuzz {
+            repo_path,
+            findings,
+            out,
+            force,
+        } => write_fuzz_harnesses(&repo_path, findings.as_deref(), &out, force),
+        Command::Extract {
+            report,
+            narratives,
+            findings,
+            force,
+        } => extract_report(
+            &report,
+            narratives.map(|p| OutputFile::new(p, force)),
+            findings.map(|p| OutputFile::new(p, force)),
+        ),
+        Command::Export {
+            findings,
+            repo_path,
+            repo,
+            config,
+        } => export_findings(&findings, &repo_path, repo, &config).await,
+        Command::WatchChain {
+            config,
+            interval,
+            once,
+            baselines,
+        } => {
+            let mut cfg = config::Config::load(&config)?;
+            if let Some(secs) = interval {
+                cfg.watch.interval_secs = secs;
+            }
+            let baselines =
+                baselines.unwrap_or_else(narrative::chain_watch::default_baselines_path);
+            let http = http::HttpClient::new("st-solguard/0.1.0")?;
+            let raised = narrative::chain_watch::run(&cfg, &http, &baselines, once).await?;
+            eprintln!("{raised} on-chain anomaly alert(s) raised");
+            Ok(())
+        }
     }
 }
 
-fn write_or_print(json: &str, output: Option<&std::path::Path>) -> Result<()> {
-    match output {
-        Some(path) => {
-            if let Some(parent) = path.parent() {
-                std::fs::create_dir_all(parent)?;
+/// One-off tracker export of a saved findings file.
+async fn export_findings(
+    findings_path: &std::path::Path,
+    repo_path: &std::path::Path,
+    repo: Option<String>,
+    config_path: &std::path::Path,
+) -> Result<()> {
+    let cfg = config::Config::load(config_path)?;
+    let findings: Vec<security::SecurityFinding> =
+        serde_json::from_str(&std::fs::read_to_string(findings_path)?)?;
+    let repo = repo.unwrap_or_else(|| {
+        repo_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "repo".into())
+    });
+    let http = http::HttpClient::new("st-solguard/0.1.0")?;
+    let mut exporter = agent::export::Exporter::new(&cfg.export, &http);
+    if !exporter.is_enabled() {
+        anyhow::bail!(
+            "no export target: configure [export.linear] or [export.notion] and set its credential variable"
+        );
+    }
+    exporter.export(&repo, repo_path, &findings).await;
+    let summary = exporter.summary();
+    eprintln!("Exported confirmed findings: {summary}");
+    if summary.failed > 0 {
+        anyhow::bail!("{} export(s) failed", summary.failed);
+    }
+    Ok(())
+}
+
+/// The CLI's view of pipeline progress: one stderr line per target, then
+/// where the report went. Phases, turns and tool calls are already in the log.
+async fn print_progress(mut events: tokio::sync::mpsc::UnboundedReceiver<ProgressEvent>) {
+    while let Some(event) = events.recv().await {
+        match event {
+            ProgressEvent::RepoStarted { repo, index, total } => {
+                eprintln!("[{index}/{total}] {repo}");
+            }
+            ProgressEvent::RepoSkipped { repo, reason } => {
+                eprintln!("  {repo}: skipped ({reason})");
+            }
+            ProgressEvent::RepoFinished {
+                repo,
+                findings,
+                error,
+            } => match error {
+                Some(e) => eprintln!("  {repo}: failed: {e}"),
+                None => eprintln!("  {repo}: {findings} findings"),
+            },
+            ProgressEvent::Finished {
+                report,
+                narratives,
+                findings,
+            } => {
+                eprintln!("SolGuard report: {report}");
+                eprintln!("  {narratives} narratives, {findings} security findings");
+            }
+            ProgressEvent::Phase { .. }
+            | ProgressEvent::Turn { .. }
+            | ProgressEvent::ToolCall { .. } => {}
+        }
+    }
+}
+
+/// Replay saved sessions and fail if any expectation no longer holds.
+fn replay_findings(sessions: &[PathBuf], output: OutputFile) -> Result<()> {
+    output.check()?;
+    let mut outcomes = Vec::new();
+    for path in sessions {
+        let outcome = security::replay::ReplaySession::load(path)?.replay();
+        if outcome.mismatches.is_empty() {
+            eprintln!(
+                "ok    {} ({} findings, {} verdicts)",
+                outcome.name,
+                outcome.findings.len(),
+                outcome.verdicts_parsed
+            );
+        } else {
+            eprintln!("FAIL  {}", outcome.name);
+            for m in &outcome.mismatches {
+                eprintln!("        {m}");
             }
-            std::fs::write(path, json)?;
-            eprintln!("Findings written to {}", path.display());
         }
-        None => println!("{json}"),
+        outcomes.push(outcome);
+    }
+    let json = serde_json::to_string_pretty(&outcomes)?;
+    write_or_print(&json, &output)?;
+
+    let failed = outcomes.iter().filter(|o| !o.mismatches.is_empty()).count();
+    if failed > 0 {
+        anyhow::bail!("{failed} of {} sessions failed", outcomes.len());
+    }
+    Ok(())
+}
+
+/// Score every pattern against the corpus; fails on any false negative,
+/// false positive or unknown pattern directory.
+fn test_rules(corpus: &[PathBuf], json: bool) -> Result<()> {
+    let report = security::rule_corpus::run(corpus)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        print!("{}", report.render());
+    }
+    if !report.passed() {
+        anyhow::bail!("rule corpus failed");
+    }
+    Ok(())
+}
+
+/// Generate fuzz projects for `repo_path` and write them under `out`.
+fn write_fuzz_harnesses(
+    repo_path: &std::path::Path,
+    findings_path: Option<&std::path::Path>,
+    out: &std::path::Path,
+    force: bool,
+) -> Result<()> {
+    let findings: Option<Vec<security::SecurityFinding>> = findings_path
+        .map(|p| -> Result<_> { Ok(serde_json::from_str(&std::fs::read_to_string(p)?)?) })
+        .transpose()?;
+    let projects = security::fuzz::generate(repo_path, findings.as_deref());
+    if projects.is_empty() {
+        anyhow::bail!(
+            "no {}Anchor instruction handlers found in {}",
+            if findings.is_some() { "flagged " } else { "" },
+            repo_path.display()
+        );
+    }
+    let files: Vec<(OutputFile, &String)> = projects
+        .iter()
+        .flat_map(|p| {
+            p.files.iter().map(|(rel, content)| {
+                (
+                    OutputFile::new(out.join(&p.program).join(rel), force),
+                    content,
+                )
+            })
+        })
+        .collect();
+    for (file, _) in &files {
+        file.check()?;
+    }
+    for (file, content) in &files {
+        file.write(content)?;
+    }
+    for p in &projects {
+        let dir = out.join(&p.program);
+        eprintln!(
+            "{}: {} targets in {} ({})",
+            p.program,
+            p.targets.len(),
+            dir.display(),
+            p.targets.join(", ")
+        );
+        eprintln!(
+            "  cd {} && cargo +nightly fuzz run {}",
+            dir.display(),
+            p.targets[0]
+        );
+    }
+    Ok(())
+}
+
+/// Print the embedded data as one JSON document, or split it into the two
+/// files `render` takes when either path is given.
+fn extract_report(
+    report: &std::path::Path,
+    narratives_path: Option<OutputFile>,
+    findings_path: Option<OutputFile>,
+) -> Result<()> {
+    for out in narratives_path.iter().chain(&findings_path) {
+        out.check()?;
+    }
+    let html = std::fs::read_to_string(report)?;
+    let data = output::embed::extract(&html)
+        .map_err(|e| anyhow::anyhow!("{}: {e:#}", report.display()))?;
+
+    if narratives_path.is_none() && findings_path.is_none() {
+        println!("{}", serde_json::to_string_pretty(&data)?);
+        return Ok(());
+    }
+    for (path, json) in [
+        (
+            narratives_path,
+            serde_json::to_string_pretty(&data.narratives)?,
+        ),
+        (findings_path, serde_json::to_string_pretty(&data.findings)?),
+    ] {
+        if let Some(out) = path {
+            out.write(json)?;
+            eprintln!("Written to {out}");
+        }
+    }
+    eprintln!(
+        "Extracted {} narratives, {} findings",
+        data.narratives.len(),
+        data.findings.len()
+    );
+    Ok(())
+}
+
+/// Fold billed costs into run memory and print the resulting correction factors.
+async fn reconcile_costs(
+    model: Option<String>,
+    estimated: Option<f64>,
+    billed: Option<f64>,
+    openrouter: bool,
+) -> Result<()> {
+    let mut run_memory = memory::RunMemory::load_or_default();
+    if let (Some(model), Some(estimated), Some(billed)) = (model, estimated, billed) {
+        run_memory.record_billing(&model, estimated, billed);
+    }
+    if openrouter {
+        let api_key = std::env::var("OPENROUTER_API_KEY")
+            .map_err(|_| anyhow::anyhow!("OPENROUTER_API_KEY is not set"))?;
+        let http = http::HttpClient::new("st-solguard/0.1.0")?;
+        let pending = std::mem::take(&mut run_memory.pending_generations);
+        let total = pending.len();
+        for generation in pending {
+            match llm::fetch_openrouter_cost(&http, &api_key, &generation.id).await {
+                Ok(cost) => {
+                    run_memory.record_billing(&generation.model, generation.estimated_usd, cost)
+                }
+                Err(e) => {
+                    // Stats lag behind completion; keep the record for the next attempt.
+                    tracing::warn!(id = %generation.id, error = %e, "generation stats unavailable");
+                    run_memory.pending_generations.push(generation);
+                }
+            }
+        }
+        eprintln!(
+            "Reconciled {} of {total} OpenRouter generations",
+            total - run_memory.pending_generations.len()
+        );
+    }
+    run_memory.save()?;
+
+    let mut models: Vec<_> = run_memory.cost_calibration.iter().collect();
+    models.sort_by_key(|(m, _)| m.as_str());
+    for (model, cal) in models {
+        println!(
+            "{model}: estimated ${:.4}, billed ${:.4} over {} samples -> factor {:.2}",
+            cal.estimated_usd,
+            cal.billed_usd,
+            cal.samples,
+            run_memory.cost_factor(model)
+        );
+    }
+    Ok(())
+}
+
+/// Assign stable finding IDs from run memory, keyed by the repo directory name.
+/// Static scan of `repo_path`, or the full deep scan with `deep`.
+async fn scan_path(
+    repo_path: &std::path::Path,
+    cfg: &config::Config,
+    deep: bool,
+    llm_override: Option<LlmOverride>,
+    scan_context: Option<&security::agent_review::ScanContext>,
+) -> Result<Vec<security::SecurityFinding>> {
+    if deep {
+        let llm = build_llm_client(&cfg.llm, llm_override.as_ref())?;
+        let http = http::HttpClient::new("st-solguard/0.1.0")?;
+        let rpc = narrative::rpc_pool::RpcPool::new(&cfg.solana, &http);
+        let findings = security::scan_repo_deep(
+            repo_path,
+            &llm,
+            &cfg.agent_review,
+            &cfg.scan,
+            scan_context,
+            &rpc,
+            &Progress::default(),
+        )
+        .await;
+        record_generations(llm.drain_generations());
+        findings
+    } else {
+        security::scan_repo_with(repo_path, &cfg.scan).await
+    }
+}
+
+/// Download and unpack `krate` under `workdir`, scan it, and report findings
+/// under `<name>-<version>/...` paths tagged `crate:<name>@<version>`.
+async fn scan_crate(
+    krate: &security::crates_io::CrateSpec,
+    workdir: &std::path::Path,
+    cfg: &config::Config,
+    deep: bool,
+    llm_override: Option<LlmOverride>,
+) -> Result<Vec<security::SecurityFinding>> {
+    let http = http::HttpClient::new("st-solguard/0.1.0")?;
+    let root = security::crates_io::fetch(&http, krate, workdir).await?;
+    let mut findings = scan_path(&root, cfg, deep, llm_override, None).await?;
+    for f in &mut findings {
+        // Static paths include the temp dir; agent paths are crate-relative.
+        f.file_path = match f.file_path.strip_prefix(workdir) {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => PathBuf::from(krate.dir_name()).join(&f.file_path),
+        };
+        f.tags.push(krate.tag());
+    }
+    tags::tag_findings(&mut findings, &cfg.tagging.rules);
+    assign_finding_ids(&root, &mut findings);
+    hooks::HookRegistry::from_config(&cfg.hooks).run(findings)
+}
+
+fn assign_finding_ids(repo_path: &std::path::Path, findings: &mut [security::SecurityFinding]) {
+    let repo = repo_path
+        .canonicalize()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| "repo".into());
+    let mut run_memory = memory::RunMemory::load_or_default();
+    run_memory.assign_finding_ids(&repo, repo_path, findings);
+    if let Err(e) = run_memory.save() {
+        tracing::warn!(error = %e, "failed to persist finding IDs");
+    }
+}
+
+/// Queue a command's LLM generations in run memory for `reconcile-costs`,
+/// as `run` does at the end of the pipeline.
+fn record_generations(generations: Vec<llm::GenerationRecord>) {
+    if generations.is_empty() {
+        return;
+    }
+    let mut run_memory = memory::RunMemory::load_or_default();
+    run_memory.pending_generations.extend(generations);
+    if let Err(e) = run_memory.save() {
+        tracing::warn!(error = %e, "failed to persist LLM generations");
+    }
+}
+
+fn write_or_print(json: &str, output: &OutputFile) -> Result<()> {
+    output.write(json)?;
+    if !output.is_stdout() {
+        eprintln!("Written to {output}");
     }
     Ok(())
 }
@@ -415,24 +1714,24 @@ fn write_or_print(json: &str, output: Option<&std::path::Path>) -> Result<()> {
 fn render_from_files(
     narratives_path: PathBuf,
     findings_path: PathBuf,
-    output_path: PathBuf,
+    output: OutputFile,
+    filter_tag: &[String],
+    notices: &output::ReportNotices,
 ) -> Result<()> {
-    let narratives: Vec<narrative::Narrative> =
-        serde_json::from_str(&std::fs::read_to_string(&narratives_path)?)?;
-    let findings: Vec<security::SecurityFinding> =
-        serde_json::from_str(&std::fs::read_to_string(&findings_path)?)?;
+    output.check()?;
+    let narratives = output::inputs::load_narratives(&narratives_path)?;
+    let findings = output::inputs::load_findings(&findings_path)?;
+    output::inputs::check_links(&narratives, findings.len())?;
+    let (narratives, findings) = tags::filter_report(narratives, findings, filter_tag);
 
-    let html = output::render_combined_report(&narratives, &findings, None)?;
-    if let Some(parent) = output_path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-    std::fs::write(&output_path, &html)?;
+    let html = output::render_combined_report_with_notices(&narratives, &findings, None, notices)?;
+    output.write(&html)?;
 
-    println!(
-        "Report rendered: {} ({} narratives, {} findings)",
-        output_path.display(),
+    eprintln!(
+        "Report rendered: {output} ({} narratives, {} findings, {} audience)",
         narratives.len(),
-        findings.len()
+        findings.len(),
+        notices.audience
     );
     Ok(())
 }
@@ -4,18 +4,119 @@
 // RunMemory aggregates across runs (blocklist, error patterns, reliability).
 // Storage: ~/.solguard/history/{timestamp}.json (per-run) + ~/.solguard/memory.json (aggregate).
 
-use chrono::Utc;
+use crate::agent::repo_kind::SkipReason;
+use crate::llm::GenerationRecord;
+use crate::security::ignore::Waiver;
+use crate::security::{SecurityFinding, StaticMerge, ValidationRecord, ValidationStatus};
+use chrono::{Datelike, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::info;
 
 /// Per-repo outcome from a single pipeline run.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RepoResult {
     pub name: String,
     pub findings_count: usize,
     pub errors: Vec<String>,
+    /// Deep-review budget split; absent for static-only scans.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget: Option<BudgetSplit>,
+    /// Stable IDs of the findings the scan produced, for churn between runs.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub finding_ids: Vec<String>,
+    /// Set when the target was recognised as having no code to audit and
+    /// wasn't cloned or scanned.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<SkipReason>,
+    /// Findings `solguard-ignore` comments waived, listed in the report.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub waived: Vec<Waiver>,
+    /// Executive summary written after validation; see
+    /// [`crate::agent::repo_summary`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+}
+
+impl RepoResult {
+    /// Whether the run actually scanned the repo's code. A failed clone or
+    /// checkout says nothing about the code, and a skipped target has none.
+    pub fn was_scanned(&self) -> bool {
+        self.skipped.is_none() && (self.errors.is_empty() || self.findings_count > 0)
+    }
+}
+
+/// How one repo's deep-review budget was divided and actually spent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BudgetSplit {
+    pub total_turns: u32,
+    pub total_cost_usd: f64,
+    pub investigation_turns: u32,
+    pub investigation_cost_usd: f64,
+    /// Validation allowance after unspent investigation budget rolled over.
+    pub validation_turn_limit: u32,
+    pub validation_cost_limit_usd: f64,
+    pub validation_turns: u32,
+    pub validation_cost_usd: f64,
+    /// Narrative whose confidence sized the budget; `None` when the repo got
+    /// the default `[agent_review]` budget.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub narrative: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub narrative_confidence: Option<f64>,
+    /// Static findings kept and dropped next to the agent's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub static_merge: Option<StaticMerge>,
+}
+
+impl BudgetSplit {
+    pub fn spent_turns(&self) -> u32 {
+        self.investigation_turns + self.validation_turns
+    }
+
+    pub fn spent_cost_usd(&self) -> f64 {
+        self.investigation_cost_usd + self.validation_cost_usd
+    }
+}
+
+/// Deep-review budget of one narrative's repos, allocated vs spent.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NarrativeBudget {
+    /// `None` for repos budgeted without a narrative.
+    pub narrative: Option<String>,
+    pub confidence: Option<f64>,
+    pub repos: usize,
+    pub budget_turns: u32,
+    pub budget_cost_usd: f64,
+    pub spent_turns: u32,
+    pub spent_cost_usd: f64,
+}
+
+/// Sum each narrative's repo budget splits, in order of first appearance;
+/// repos without a split (static scans, failures) are left out.
+pub fn budget_by_narrative(results: &[RepoResult]) -> Vec<NarrativeBudget> {
+    let mut out: Vec<NarrativeBudget> = Vec::new();
+    for split in results.iter().filter_map(|r| r.budget.as_ref()) {
+        let i = match out.iter().position(|b| b.narrative == split.narrative) {
+            Some(i) => i,
+            None => {
+                out.push(NarrativeBudget {
+                    narrative: split.narrative.clone(),
+                    confidence: split.narrative_confidence,
+                    ..Default::default()
+                });
+                out.len() - 1
+            }
+        };
+        let b = &mut out[i];
+        b.repos += 1;
+        b.budget_turns += split.total_turns;
+        b.budget_cost_usd += split.total_cost_usd;
+        b.spent_turns += split.spent_turns();
+        b.spent_cost_usd += split.spent_cost_usd();
+    }
+    out
 }
 
 /// Per-run snapshot: everything that happened in one pipeline execution.
@@ -25,6 +126,33 @@ pub struct RunHistory {
     pub signals_collected: usize,
     pub total_findings: usize,
     pub repo_results: Vec<RepoResult>,
+    /// [`budget_by_narrative`] over `repo_results`, filled in before saving.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub narrative_budgets: Vec<NarrativeBudget>,
+    /// Narratives as reported, after cross-referencing with findings.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub narratives: Vec<NarrativeOutcome>,
+}
+
+/// A narrative's confidence and risk at the end of a run.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct NarrativeOutcome {
+    pub title: String,
+    pub confidence: f64,
+    pub risk_score: f64,
+    #[serde(default)]
+    pub risk_level: String,
+}
+
+impl From<&crate::narrative::Narrative> for NarrativeOutcome {
+    fn from(n: &crate::narrative::Narrative) -> Self {
+        Self {
+            title: n.title.clone(),
+            confidence: n.confidence,
+            risk_score: n.risk_score,
+            risk_level: n.risk_level.clone(),
+        }
+    }
 }
 
 impl Default for RunHistory {
@@ -40,6 +168,8 @@ impl RunHistory {
             signals_collected: 0,
             total_findings: 0,
             repo_results: Vec::new(),
+            narrative_budgets: Vec::new(),
+            narratives: Vec::new(),
         }
     }
 
@@ -53,6 +183,33 @@ impl RunHistory {
         info!(path = %path.display(), "saved run history");
         Ok(())
     }
+
+    /// Load the newest saved run whose timestamp starts with `run`
+    /// (`20260301` matches every run that day).
+    pub fn load(run: &str) -> anyhow::Result<Self> {
+        Self::load_from(&history_dir(), run)
+    }
+
+    pub fn load_from(dir: &std::path::Path, run: &str) -> anyhow::Result<Self> {
+        use anyhow::Context;
+        let mut runs: Vec<String> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+            .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .filter(|t| t.starts_with(run))
+            .collect();
+        runs.sort();
+        let Some(timestamp) = runs.pop() else {
+            anyhow::bail!("no saved run `{run}` in {}", dir.display());
+        };
+        let path = dir.join(format!("{timestamp}.json"));
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+    }
 }
 
 /// Aggregate memory across all runs. Persisted at ~/.solguard/memory.json.
@@ -67,8 +224,81 @@ pub struct RunMemory {
     pub source_reliability: HashMap<String, f64>,
     /// Pattern ID → (hits, confirmed). Tracks which vulnerability patterns produce confirmed findings.
     pub pattern_hit_rates: HashMap<String, (u32, u32)>,
+    /// Finding fingerprint → stable ID, assigned the first time the finding is seen.
+    #[serde(default)]
+    pub finding_ids: HashMap<String, String>,
+    /// `{year}-{repo}` → last issued ID sequence number.
+    #[serde(default)]
+    pub finding_id_counters: HashMap<String, u32>,
+    /// Model → observed billed vs estimated cost, from `reconcile-costs`.
+    #[serde(default)]
+    pub cost_calibration: HashMap<String, CostCalibration>,
+    /// OpenRouter generations not yet reconciled against billing.
+    #[serde(default)]
+    pub pending_generations: Vec<GenerationRecord>,
+    /// Repo name → its most recent completed scan, for urgency tiers.
+    #[serde(default)]
+    pub repo_scans: HashMap<String, RepoScan>,
+    /// Targets deferred by urgency tiering, scanned by the next run.
+    #[serde(default)]
+    pub deferred_targets: Vec<String>,
+    /// Targets a run selected but ran out of budget or time for, scanned
+    /// first by the next run.
+    #[serde(default)]
+    pub carried_over: Vec<CarriedTarget>,
+    /// `{commit}:{fingerprint}` → validator verdict, reused instead of paying
+    /// to re-validate the same finding at the same commit.
+    #[serde(default)]
+    pub verdict_cache: HashMap<String, CachedVerdict>,
+}
+
+/// A validator verdict on one finding.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CachedVerdict {
+    pub status: ValidationStatus,
+    #[serde(default)]
+    pub reasoning: Option<String>,
+    /// Validator model and verdict time, carried into reused findings'
+    /// provenance.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validated_at: Option<String>,
+}
+
+/// A target a run selected but didn't deep-review.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CarriedTarget {
+    /// `owner/name`, or a bare `always_scan` name.
+    pub target: String,
+    pub reason: String,
+    /// The narrative that selected it, narrowed to this target, so the next
+    /// run can budget and brief the agent even if the narrative is gone.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub narrative: Option<crate::narrative::Narrative>,
+    /// Value of `total_runs` for the run that first carried it over.
+    pub since_run: u32,
+}
+
+/// The most recent completed scan of one repo.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepoScan {
+    /// Value of `total_runs` for the run that scanned it.
+    pub run: u32,
+    pub findings: usize,
+}
+
+/// Running totals of estimated vs billed spend for one model.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CostCalibration {
+    pub estimated_usd: f64,
+    pub billed_usd: f64,
+    pub samples: u32,
 }
 
+/// Correction factors are clamped so one bad sample can't zero or explode budgets.
+const COST_FACTOR_RANGE: (f64, f64) = (0.1, 10.0);
+
 impl RunMemory {
     /// Load aggregate memory from disk, or return defaults for first run.
     pub fn load_or_default() -> Self {
@@ -102,6 +332,139 @@ impl RunMemory {
                     self.repo_blocklist.push(repo.name.clone());
                 }
             }
+            if repo.was_scanned() {
+                self.repo_scans.insert(
+                    repo.name.clone(),
+                    RepoScan {
+                        run: self.total_runs,
+                        findings: repo.findings_count,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Blocklist `repo` (`owner/name` or `name`) by hand. Returns whether it
+    /// was newly added.
+    pub fn block(&mut self, repo: &str) -> bool {
+        let name = crate::paths::target_name(repo);
+        if self.repo_blocklist.iter().any(|b| b == name) {
+            return false;
+        }
+        self.repo_blocklist.push(name.to_string());
+        true
+    }
+
+    /// Take `repo` off the blocklist and forget its recorded errors, so one
+    /// more failure doesn't put it straight back. Returns whether it was
+    /// blocklisted.
+    pub fn unblock(&mut self, repo: &str) -> bool {
+        let name = crate::paths::target_name(repo);
+        let before = self.repo_blocklist.len();
+        self.repo_blocklist.retain(|b| b != name);
+        let prefix = format!("{name}:");
+        self.error_memory.retain(|key, _| !key.starts_with(&prefix));
+        self.repo_blocklist.len() < before
+    }
+
+    /// Give each finding from `repo` a stable ID like `SG-2025-jupiter-0007`.
+    ///
+    /// Known fingerprints reuse their ID; new ones get the next number for the
+    /// current year and repo. Fingerprints use paths relative to `root`, so
+    /// scanning the repo by a relative or an absolute path gives the same IDs.
+    /// Call `save` afterwards to persist new IDs.
+    pub fn assign_finding_ids(
+        &mut self,
+        repo: &str,
+        root: &Path,
+        findings: &mut [SecurityFinding],
+    ) {
+        let slug = id_slug(repo);
+        let year = Utc::now().year();
+        let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+        for (i, fingerprint) in fingerprints_under(repo, Some(&root), findings) {
+            let id = match self.finding_ids.get(&fingerprint) {
+                Some(id) => id.clone(),
+                None => {
+                    let counter = self
+                        .finding_id_counters
+                        .entry(format!("{year}-{slug}"))
+                        .or_insert(0);
+                    *counter += 1;
+                    let id = format!("SG-{year}-{slug}-{:04}", *counter);
+                    self.finding_ids.insert(fingerprint, id.clone());
+                    id
+                }
+            };
+            findings[i].id = Some(id);
+        }
+    }
+
+    /// Give findings of `repo` at `commit` that an earlier run validated
+    /// their stored verdict, marked `validation_cached`. Returns how many.
+    pub fn reuse_verdicts(
+        &self,
+        repo: &str,
+        commit: &str,
+        findings: &mut [SecurityFinding],
+    ) -> usize {
+        let mut reused = 0;
+        for (i, fingerprint) in fingerprints(repo, findings) {
+            if let Some(cached) = self.verdict_cache.get(&format!("{commit}:{fingerprint}")) {
+                let f = &mut findings[i];
+                f.validation_status = cached.status.clone();
+                f.validation_reasoning = cached.reasoning.clone();
+                f.validation_cached = true;
+                if let Some(p) = &mut f.provenance {
+                    p.validation = Some(ValidationRecord {
+                        verdict: cached.status.clone(),
+                        model: cached.model.clone(),
+                        validated_at: cached.validated_at.clone(),
+                    });
+                }
+                reused += 1;
+            }
+        }
+        reused
+    }
+
+    /// Store the verdicts the validator just gave findings of `repo` at
+    /// `commit`. Call before Dismissed findings are dropped.
+    pub fn record_verdicts(&mut self, repo: &str, commit: &str, findings: &[SecurityFinding]) {
+        for (i, fingerprint) in fingerprints(repo, findings) {
+            let f = &findings[i];
+            if f.validation_cached || f.validation_status == ValidationStatus::Unvalidated {
+                continue;
+            }
+            let record = f.provenance.as_ref().and_then(|p| p.validation.as_ref());
+            self.verdict_cache.insert(
+                format!("{commit}:{fingerprint}"),
+                CachedVerdict {
+                    status: f.validation_status.clone(),
+                    reasoning: f.validation_reasoning.clone(),
+                    model: record.and_then(|r| r.model.clone()),
+                    validated_at: record.and_then(|r| r.validated_at.clone()),
+                },
+            );
+        }
+    }
+
+    /// Add an observed (estimated, billed) cost pair for `model`.
+    pub fn record_billing(&mut self, model: &str, estimated_usd: f64, billed_usd: f64) {
+        let cal = self.cost_calibration.entry(model.to_string()).or_default();
+        cal.estimated_usd += estimated_usd;
+        cal.billed_usd += billed_usd;
+        cal.samples += 1;
+    }
+
+    /// Billed/estimated ratio for `model`, or 1.0 when there is no usable data.
+    pub fn cost_factor(&self, model: &str) -> f64 {
+        match self.cost_calibration.get(model) {
+            Some(cal) if cal.samples > 0 && cal.estimated_usd > 0.0 => {
+                (cal.billed_usd / cal.estimated_usd).clamp(COST_FACTOR_RANGE.0, COST_FACTOR_RANGE.1)
+            }
+            _ => 1.0,
         }
     }
 
@@ -118,7 +481,68 @@ impl RunMemory {
     }
 }
 
-fn solguard_dir() -> PathBuf {
+/// (index, [`SecurityFinding::fingerprint`]) of each finding, ordered by
+/// line so duplicate-keyed findings get stable occurrence numbers.
+fn fingerprints(repo: &str, findings: &[SecurityFinding]) -> Vec<(usize, String)> {
+    fingerprints_under(repo, None, findings)
+}
+
+/// [`fingerprints`] keyed on paths relative to `canonical_root`, when given.
+pub(crate) fn fingerprints_under(
+    repo: &str,
+    canonical_root: Option<&Path>,
+    findings: &[SecurityFinding],
+) -> Vec<(usize, String)> {
+    let path = |f: &SecurityFinding| match canonical_root {
+        Some(root) => repo_relative(&f.file_path, root),
+        None => f.file_path.clone(),
+    };
+    let mut order: Vec<usize> = (0..findings.len()).collect();
+    order.sort_by_key(|&i| (findings[i].file_path.clone(), findings[i].line_number));
+    let mut seen: HashMap<(String, String, Option<String>), usize> = HashMap::new();
+    order
+        .into_iter()
+        .map(|i| {
+            let f = &findings[i];
+            let path = path(f);
+            let base = (
+                crate::paths::to_slash(&path),
+                f.title.clone(),
+                f.function.clone(),
+            );
+            let occurrence = seen.entry(base).or_insert(0);
+            let fingerprint = f.fingerprint_at(repo, &path, *occurrence);
+            *occurrence += 1;
+            (i, fingerprint)
+        })
+        .collect()
+}
+
+/// `path` relative to `canonical_root`. A path that is neither under the root
+/// once resolved nor already relative to it is returned as-is.
+fn repo_relative(path: &Path, canonical_root: &Path) -> PathBuf {
+    if path.is_relative() && canonical_root.join(path).exists() {
+        return path.to_path_buf();
+    }
+    let resolved = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    match resolved.strip_prefix(canonical_root) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/// Lowercase alphanumeric-and-dash form of a repo name for use inside IDs.
+pub(crate) fn id_slug(repo: &str) -> String {
+    let slug: String = repo
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() { "repo".into() } else { slug }
+}
+
+pub(crate) fn solguard_dir() -> PathBuf {
     dirs_or_home().join(".solguard")
 }
 
@@ -181,7 +605,14 @@ mod tests {
                     name: "bad-repo".into(),
                     findings_count: 0,
                     errors: vec!["clone failed".into()],
+                    finding_ids: Vec::new(),
+                    skipped: None,
+                    waived: Vec::new(),
+                    summary: None,
+                    budget: None,
                 }],
+                narrative_budgets: Vec::new(),
+                narratives: Vec::new(),
             };
             mem.update_from_run(&history);
         }
@@ -203,7 +634,14 @@ mod tests {
                     name: "flaky-repo".into(),
                     findings_count: 0,
                     errors: vec!["timeout".into()],
+                    finding_ids: Vec::new(),
+                    skipped: None,
+                    waived: Vec::new(),
+                    summary: None,
+                    budget: None,
                 }],
+                narrative_budgets: Vec::new(),
+                narratives: Vec::new(),
             };
             mem.update_from_run(&history);
         }
@@ -222,12 +660,100 @@ mod tests {
                 name: "good-repo".into(),
                 findings_count: 3,
                 errors: vec![],
+                finding_ids: Vec::new(),
+                skipped: None,
+                waived: Vec::new(),
+                summary: None,
+                budget: None,
             }],
+            narrative_budgets: Vec::new(),
+            narratives: Vec::new(),
         };
         mem.update_from_run(&history);
         assert!(mem.repo_blocklist.is_empty());
     }
 
+    #[test]
+    fn records_last_completed_scan() {
+        let mut mem = RunMemory::default();
+        let result = |name: &str, findings_count, error: Option<&str>| RepoResult {
+            name: name.into(),
+            findings_count,
+            errors: error.map(String::from).into_iter().collect(),
+            finding_ids: Vec::new(),
+            skipped: None,
+            waived: Vec::new(),
+            summary: None,
+            budget: None,
+        };
+        let mut history = RunHistory::new();
+        history.repo_results = vec![result("amm", 2, None), result("vault", 0, None)];
+        mem.update_from_run(&history);
+        history.repo_results = vec![
+            result("amm", 0, None),
+            result("vault", 0, Some("clone failed")),
+            RepoResult {
+                skipped: Some(SkipReason::Archived),
+                waived: Vec::new(),
+                summary: None,
+                ..result("old-vault", 0, None)
+            },
+        ];
+        mem.update_from_run(&history);
+
+        assert_eq!(
+            mem.repo_scans["amm"],
+            RepoScan {
+                run: 2,
+                findings: 0
+            }
+        );
+        // The failed clone keeps the earlier scan.
+        assert_eq!(
+            mem.repo_scans["vault"],
+            RepoScan {
+                run: 1,
+                findings: 0
+            }
+        );
+        assert!(!mem.repo_scans.contains_key("old-vault"));
+    }
+
+    #[test]
+    fn manual_block_and_unblock() {
+        let mut mem = RunMemory::default();
+        assert!(mem.block("acme/waste"));
+        assert!(!mem.block("waste"));
+        assert_eq!(mem.repo_blocklist, vec!["waste"]);
+
+        let failing = RunHistory {
+            timestamp: "test".into(),
+            signals_collected: 0,
+            total_findings: 0,
+            repo_results: vec![RepoResult {
+                name: "flaky".into(),
+                findings_count: 0,
+                errors: vec!["clone failed".into()],
+                finding_ids: Vec::new(),
+                skipped: None,
+                waived: Vec::new(),
+                summary: None,
+                budget: None,
+            }],
+            narrative_budgets: Vec::new(),
+            narratives: Vec::new(),
+        };
+        for _ in 0..3 {
+            mem.update_from_run(&failing);
+        }
+        assert!(mem.repo_blocklist.contains(&"flaky".to_string()));
+        assert!(mem.unblock("acme/flaky"));
+        assert!(!mem.unblock("flaky"));
+        // Error counts start over: one more failure doesn't re-block.
+        mem.update_from_run(&failing);
+        assert_eq!(mem.repo_blocklist, vec!["waste"]);
+    }
+
     #[test]
     fn no_duplicate_blocklist_entries() {
         let mut mem = RunMemory::default();
@@ -241,7 +767,14 @@ mod tests {
                     name: "bad-repo".into(),
                     findings_count: 0,
                     errors: vec!["clone failed".into()],
+                    finding_ids: Vec::new(),
+                    skipped: None,
+                    waived: Vec::new(),
+                    summary: None,
+                    budget: None,
                 }],
+                narrative_budgets: Vec::new(),
+                narratives: Vec::new(),
             };
             mem.update_from_run(&history);
         }
@@ -256,6 +789,209 @@ mod tests {
     }
 
     #[test]
+    fn budgets_summed_per_narrative() {
+        let repo = |name: &str, narrative: Option<&str>, turns: u32, spent: u32| RepoResult {
+            name: name.into(),
+            findings_count: 0,
+            errors: vec![],
+            finding_ids: Vec::new(),
+            skipped: None,
+            waived: Vec::new(),
+            summary: None,
+            budget: Some(BudgetSplit {
+                total_turns: turns,
+                total_cost_usd: f64::from(turns) / 2.0,
+                investigation_turns: spent,
+                investigation_cost_usd: 1.0,
+                validation_turns: 1,
+                validation_cost_usd: 0.5,
+                narrative: narrative.map(String::from),
+                narrative_confidence: narrative.map(|_| 0.9),
+                ..Default::default()
+            }),
+        };
+        let results = vec![
+            repo("a", Some("Restaking"), 20, 12),
+            repo("b", None, 10, 4),
+            repo("c", Some("Restaking"), 20, 18),
+            RepoResult {
+                name: "static".into(),
+                findings_count: 3,
+                errors: vec![],
+                finding_ids: Vec::new(),
+                skipped: None,
+                waived: Vec::new(),
+                summary: None,
+                budget: None,
+            },
+        ];
+        let budgets = budget_by_narrative(&results);
+        assert_eq!(budgets.len(), 2);
+        assert_eq!(budgets[0].narrative.as_deref(), Some("Restaking"));
+        assert_eq!(budgets[0].confidence, Some(0.9));
+        assert_eq!(budgets[0].repos, 2);
+        assert_eq!(budgets[0].budget_turns, 40);
+        assert_eq!(budgets[0].spent_turns, 32);
+        assert!((budgets[0].spent_cost_usd - 3.0).abs() < 1e-9);
+        assert_eq!(budgets[1].narrative, None);
+        assert_eq!(budgets[1].repos, 1);
+    }
+
+    #[test]
+    fn budget_split_round_trips_and_is_optional() {
+        let repo = RepoResult {
+            name: "vault".into(),
+            findings_count: 2,
+            errors: vec![],
+            finding_ids: Vec::new(),
+            skipped: None,
+            waived: Vec::new(),
+            summary: None,
+            budget: Some(BudgetSplit {
+                total_turns: 20,
+                total_cost_usd: 10.0,
+                investigation_turns: 9,
+                investigation_cost_usd: 4.0,
+                validation_turn_limit: 11,
+                validation_cost_limit_usd: 6.0,
+                validation_turns: 3,
+                validation_cost_usd: 1.2,
+                narrative: Some("Restaking".into()),
+                narrative_confidence: Some(0.8),
+                static_merge: Some(StaticMerge {
+                    merged: 1,
+                    below_threshold: 3,
+                    dominated: 2,
+                }),
+            }),
+        };
+        let json = serde_json::to_string(&repo).unwrap();
+        let back: RepoResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.budget, repo.budget);
+
+        // Histories written before budgets were tracked still load.
+        let old: RepoResult =
+            serde_json::from_str(r#"{"name":"x","findings_count":0,"errors":[]}"#).unwrap();
+        assert!(old.budget.is_none());
+    }
+
+    fn finding(title: &str, line: usize) -> SecurityFinding {
+        SecurityFinding {
+            title: title.into(),
+            file_path: PathBuf::from("repos/jupiter/src/lib.rs"),
+            line_number: line,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn finding_ids_stable_across_runs() {
+        let mut mem = RunMemory::default();
+        let year = Utc::now().year();
+
+        let mut first = vec![finding("Missing Signer", 10), finding("Overflow", 20)];
+        mem.assign_finding_ids("Jupiter", Path::new(""), &mut first);
+        assert_eq!(
+            first[0].id.as_deref(),
+            Some(format!("SG-{year}-jupiter-0001").as_str())
+        );
+        assert_eq!(
+            first[1].id.as_deref(),
+            Some(format!("SG-{year}-jupiter-0002").as_str())
+        );
+
+        // Lines shift and a new finding appears: old IDs survive, new one gets 0003.
+        let mut second = vec![
+            finding("New Bug", 5),
+            finding("Overflow", 42),
+            finding("Missing Signer", 30),
+        ];
+        mem.assign_finding_ids("Jupiter", Path::new(""), &mut second);
+        assert_eq!(second[2].id, first[0].id);
+        assert_eq!(second[1].id, first[1].id);
+        assert_eq!(
+            second[0].id.as_deref(),
+            Some(format!("SG-{year}-jupiter-0003").as_str())
+        );
+    }
+
+    #[test]
+    fn duplicate_findings_get_distinct_ids() {
+        let mut mem = RunMemory::default();
+        let mut findings = vec![finding("Overflow", 30), finding("Overflow", 10)];
+        mem.assign_finding_ids("jupiter", Path::new(""), &mut findings);
+        assert_ne!(findings[0].id, findings[1].id);
+        // The earlier line gets the first sequence number.
+        assert!(findings[1].id.as_deref().unwrap().ends_with("-0001"));
+    }
+
+    #[test]
+    fn verdicts_reused_at_same_commit_only() {
+        use crate::security::Provenance;
+        let mut mem = RunMemory::default();
+        let mut validated = vec![finding("Overflow", 10), finding("Missing Signer", 20)];
+        validated[0].validation_status = ValidationStatus::Dismissed;
+        validated[0].validation_reasoning = Some("checked_add upstream".into());
+        let record = ValidationRecord {
+            verdict: ValidationStatus::Dismissed,
+            model: Some("claude-sonnet-4-5".into()),
+            validated_at: Some("2026-03-01T12:00:00+00:00".into()),
+        };
+        validated[0].provenance = Some(Provenance {
+            validation: Some(record.clone()),
+            ..Default::default()
+        });
+        mem.record_verdicts("jupiter", "abc123", &validated);
+        assert_eq!(mem.verdict_cache.len(), 1);
+
+        let mut rerun = vec![finding("Missing Signer", 20), finding("Overflow", 12)];
+        rerun[1].provenance = Some(Provenance::default());
+        assert_eq!(mem.reuse_verdicts("jupiter", "abc123", &mut rerun), 1);
+        // Provenance keeps the model and time of the original verdict.
+        assert_eq!(
+            rerun[1].provenance.as_ref().unwrap().validation,
+            Some(record)
+        );
+        assert!(rerun[1].validation_cached);
+        assert_eq!(rerun[1].validation_status, ValidationStatus::Dismissed);
+        assert_eq!(
+            rerun[1].validation_reasoning.as_deref(),
+            Some("checked_add upstream")
+        );
+        assert!(!rerun[0].validation_cached);
+
+        // Reused verdicts aren't recorded again; a new commit starts over.
+        mem.record_verdicts("jupiter", "abc123", &rerun);
+        assert_eq!(mem.verdict_cache.len(), 1);
+        let mut moved = vec![finding("Overflow", 10)];
+        assert_eq!(mem.reuse_verdicts("jupiter", "def456", &mut moved), 0);
+    }
+
+    #[test]
+    fn id_slug_sanitizes() {
+        assert_eq!(id_slug("Raydium_CP.Swap"), "raydium-cp-swap");
+        assert_eq!(id_slug("///"), "repo");
+    }
+
+    #[test]
+    fn cost_factor_from_billing() {
+        let mut mem = RunMemory::default();
+        assert!((mem.cost_factor("m") - 1.0).abs() < f64::EPSILON);
+
+        mem.record_billing("m", 1.0, 1.5);
+        mem.record_billing("m", 1.0, 2.5);
+        assert!((mem.cost_factor("m") - 2.0).abs() < 1e-9);
+
+        // Free-model estimates are zero: no factor can be derived.
+        mem.record_billing("free", 0.0, 0.3);
+        assert!((mem.cost_factor("free") - 1.0).abs() < f64::EPSILON);
+
+        mem.record_billing("wild", 0.01, 5.0);
+        assert!((mem.cost_factor("wild") - 10.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn serde_roundtrip() {
         let mut mem = RunMemory::default();
         mem.total_runs = 5;
@@ -7,7 +7,6 @@ pub struct SignalGroup {
     pub signals: Vec<usize>,
     pub source_diversity: usize,
     pub total_signals: usize,
-    #[allow(dead_code)]
     pub key_metrics: Vec<Metric>,
 }
 
@@ -68,6 +67,7 @@ pub fn aggregate(signals: &[Signal]) -> Vec<SignalGroup> {
         b.source_diversity
             .cmp(&a.source_diversity)
             .then(b.total_signals.cmp(&a.total_signals))
+            .then_with(|| a.category.cmp(&b.category))
     });
 
     groups
@@ -99,6 +99,7 @@ pub fn signals_to_json(
                         }).collect::<Vec<_>>(),
                         "url": s.url,
                         "timestamp": s.timestamp.to_rfc3339(),
+                        "language": s.language,
                     })
                 })
                 .collect();
@@ -0,0 +1,411 @@
+//! Continuous on-chain anomaly watch (`watch-chain`).
+//!
+//! Polls the `[solana]` RPC on an interval for network non-vote TPS and each
+//! tracked program's transaction rate and failure rate, and compares every
+//! reading with a rolling baseline of the ones before it. A reading far
+//! outside its baseline, both in standard deviations and in absolute terms,
+//! is announced through the `[alerts]` stdout and webhook channels. None of
+//! the narrative pipeline runs. Baselines are saved after every poll, so a
+//! restarted watcher doesn't start cold.
+
+use super::rpc_pool::RpcPool;
+use super::solana_rpc;
+use crate::agent::alert::MARKER;
+use crate::config::{AlertConfig, Config, SolanaConfig, WatchConfig};
+use crate::http::HttpClient;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Where baselines persist between runs.
+pub fn default_baselines_path() -> PathBuf {
+    crate::memory::solguard_dir().join("chain_baselines.json")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Transaction throughput; spikes and drops both alert.
+    Activity,
+    /// Share of failed transactions; only rises alert.
+    FailureRate,
+}
+
+/// One metric's value at one poll.
+#[derive(Debug, Clone)]
+pub struct Reading {
+    /// Stable baseline key, e.g. `program:<address>:tx_per_hour`.
+    pub key: String,
+    pub label: String,
+    pub kind: MetricKind,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    Spike,
+    Drop,
+}
+
+/// A reading far outside its baseline, as sent to every channel.
+#[derive(Debug, Serialize)]
+pub struct Anomaly {
+    /// One-line summary; Slack/Discord-style webhooks display this field.
+    pub text: String,
+    pub metric: String,
+    pub label: String,
+    pub direction: Direction,
+    pub value: f64,
+    /// Mean of the baseline window.
+    pub baseline: f64,
+    pub timestamp: String,
+}
+
+/// Rolling window of past readings per metric key.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baselines {
+    metrics: BTreeMap<String, VecDeque<f64>>,
+}
+
+impl Baselines {
+    /// Saved baselines, or empty ones if there are none or they're unreadable.
+    pub fn load(path: &Path) -> Self {
+        let Ok(text) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&text).unwrap_or_else(|e| {
+            warn!(path = %path.display(), error = %e, "unreadable chain baselines, starting fresh");
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// Check `reading` against its baseline, then add it to the window.
+    pub fn observe(&mut self, reading: &Reading, config: &WatchConfig) -> Option<Anomaly> {
+        let history = self.metrics.entry(reading.key.clone()).or_default();
+        let anomaly = detect(history, reading, config);
+        history.push_back(reading.value);
+        while history.len() > config.window.max(1) {
+            history.pop_front();
+        }
+        anomaly
+    }
+}
+
+fn detect(history: &VecDeque<f64>, reading: &Reading, config: &WatchConfig) -> Option<Anomaly> {
+    if history.is_empty() || history.len() < config.min_samples {
+        return None;
+    }
+    let n = history.len() as f64;
+    let mean = history.iter().sum::<f64>() / n;
+    let std_dev = (history.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n).sqrt();
+    let delta = reading.value - mean;
+    // A perfectly flat baseline makes any change infinitely many deviations
+    // out; the absolute threshold below still has to be met.
+    let outlier = if std_dev > 0.0 {
+        delta.abs() / std_dev >= config.z_threshold
+    } else {
+        delta != 0.0
+    };
+    if !outlier {
+        return None;
+    }
+
+    let (direction, text) = match reading.kind {
+        MetricKind::Activity => {
+            if mean <= 0.0 || (delta / mean).abs() < config.min_change {
+                return None;
+            }
+            let direction = if delta > 0.0 {
+                Direction::Spike
+            } else {
+                Direction::Drop
+            };
+            let verb = match direction {
+                Direction::Spike => "spiked",
+                Direction::Drop => "dropped",
+            };
+            let text = format!(
+                "{} {verb} to {:.0} (baseline {mean:.0}, {:+.0}%)",
+                reading.label,
+                reading.value,
+                delta / mean * 100.0
+            );
+            (direction, text)
+        }
+        MetricKind::FailureRate => {
+            if delta < config.min_failure_jump {
+                return None;
+            }
+            let text = format!(
+                "{} jumped to {:.1}% (baseline {:.1}%)",
+                reading.label,
+                reading.value * 100.0,
+                mean * 100.0
+            );
+            (Direction::Spike, text)
+        }
+    };
+
+    Some(Anomaly {
+        text,
+        metric: reading.key.clone(),
+        label: reading.label.clone(),
+        direction,
+        value: reading.value,
+        baseline: mean,
+        timestamp: Utc::now().to_rfc3339(),
+    })
+}
+
+/// One poll of every watched metric. Sources that fail are logged and left
+/// out of this poll.
+pub async fn sample(config: &SolanaConfig, rpc: &RpcPool) -> Vec<Reading> {
+    let mut readings = Vec::new();
+
+    match solana_rpc::network_tps(rpc).await {
+        Ok(Some(tps)) => readings.push(Reading {
+            key: "network:non_vote_tps".into(),
+            label: "Network non-vote TPS".into(),
+            kind: MetricKind::Activity,
+            value: tps.avg_non_vote_tps,
+        }),
+        Ok(None) => {}
+        Err(e) => warn!(error = %e, "failed to get performance samples"),
+    }
+
+    for program in &config.tracked_programs {
+        match solana_rpc::get_program_activity(rpc, &program.address).await {
+            Ok(activity) => {
+                // Without a time span the rate is meaningless.
+                if activity.tx_per_hour > 0.0 {
+                    readings.push(Reading {
+                        key: format!("program:{}:tx_per_hour", program.address),
+                        label: format!("{} tx/hr", program.name),
+                        kind: MetricKind::Activity,
+                        value: activity.tx_per_hour,
+                    });
+                }
+                if activity.tx_count > 0 {
+                    readings.push(Reading {
+                        key: format!("program:{}:failure_rate", program.address),
+                        label: format!("{} failure rate", program.name),
+                        kind: MetricKind::FailureRate,
+                        value: activity.failure_rate(),
+                    });
+                }
+            }
+            Err(e) => {
+                warn!(program = %program.name, error = %e, "failed to get program activity");
+            }
+        }
+    }
+
+    readings
+}
+
+/// Announce an anomaly. Delivery is best-effort, as for finding alerts.
+async fn send(config: &AlertConfig, http: &HttpClient, anomaly: &Anomaly) {
+    info!(metric = %anomaly.metric, value = anomaly.value, baseline = anomaly.baseline, "on-chain anomaly");
+    if config.stdout {
+        println!("{MARKER} {}", anomaly.text);
+    }
+    if let Some(url) = &config.webhook_url {
+        let result = match serde_json::to_string(anomaly) {
+            Ok(body) => http.post_json_raw(url, &body, &[]).await.map(|_| ()),
+            Err(e) => Err(crate::error::Error::parse(e.to_string())),
+        };
+        if let Err(e) = result {
+            warn!(error = %e, "alert webhook failed");
+        }
+    }
+}
+
+/// Poll until interrupted, or once with `once`. Returns the number of
+/// anomalies raised.
+pub async fn run(
+    config: &Config,
+    http: &HttpClient,
+    baselines_path: &Path,
+    once: bool,
+) -> Result<usize> {
+    let mut baselines = Baselines::load(baselines_path);
+    let interval = Duration::from_secs(config.watch.interval_secs.max(1));
+    let mut raised = 0;
+    let rpc = RpcPool::new(&config.solana, http);
+
+    loop {
+        rpc.check_health().await;
+        let readings = sample(&config.solana, &rpc).await;
+        let mut anomalies = 0;
+        for reading in &readings {
+            if let Some(anomaly) = baselines.observe(reading, &config.watch) {
+                send(&config.alerts, http, &anomaly).await;
+                anomalies += 1;
+            }
+        }
+        raised += anomalies;
+        baselines.save(baselines_path)?;
+        info!(
+            readings = readings.len(),
+            anomalies, "chain watch poll complete"
+        );
+
+        if once {
+            return Ok(raised);
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = tokio::signal::ctrl_c() => {
+                info!("chain watch interrupted");
+                return Ok(raised);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(kind: MetricKind, value: f64) -> Reading {
+        Reading {
+            key: "program:JUP:x".into(),
+            label: "Jupiter tx/hr".into(),
+            kind,
+            value,
+        }
+    }
+
+    fn warm(baselines: &mut Baselines, kind: MetricKind, values: &[f64], config: &WatchConfig) {
+        for &v in values {
+            assert!(baselines.observe(&reading(kind, v), config).is_none());
+        }
+    }
+
+    #[test]
+    fn activity_spikes_and_drops_alert_after_warmup() {
+        let config = WatchConfig::default();
+        let mut baselines = Baselines::default();
+        // Too few readings yet to judge even a large jump.
+        warm(
+            &mut baselines,
+            MetricKind::Activity,
+            &[1000.0, 1100.0, 5000.0, 900.0, 1000.0],
+            &config,
+        );
+        warm(
+            &mut baselines,
+            MetricKind::Activity,
+            &[1000.0, 1050.0, 950.0],
+            &config,
+        );
+
+        let spike = baselines
+            .observe(&reading(MetricKind::Activity, 12000.0), &config)
+            .unwrap();
+        assert_eq!(spike.direction, Direction::Spike);
+        assert!(
+            spike
+                .text
+                .starts_with("Jupiter tx/hr spiked to 12000 (baseline")
+        );
+
+        let mut baselines = Baselines::default();
+        warm(
+            &mut baselines,
+            MetricKind::Activity,
+            &[1000.0, 1050.0, 950.0, 1000.0, 1020.0, 980.0],
+            &config,
+        );
+        let drop = baselines
+            .observe(&reading(MetricKind::Activity, 100.0), &config)
+            .unwrap();
+        assert_eq!(drop.direction, Direction::Drop);
+        assert_eq!(
+            drop.text,
+            "Jupiter tx/hr dropped to 100 (baseline 1000, -90%)"
+        );
+    }
+
+    #[test]
+    fn small_moves_on_a_flat_baseline_stay_quiet() {
+        let config = WatchConfig::default();
+        let mut baselines = Baselines::default();
+        warm(&mut baselines, MetricKind::Activity, &[100.0; 6], &config);
+        // Infinitely many deviations out, but only 20% up.
+        assert!(
+            baselines
+                .observe(&reading(MetricKind::Activity, 120.0), &config)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn failure_rate_alerts_only_on_large_rises() {
+        let config = WatchConfig::default();
+        let mut baselines = Baselines::default();
+        warm(
+            &mut baselines,
+            MetricKind::FailureRate,
+            &[0.02, 0.03, 0.02, 0.01, 0.02, 0.03],
+            &config,
+        );
+        // Far outside the spread but only five points up.
+        assert!(
+            baselines
+                .observe(&reading(MetricKind::FailureRate, 0.07), &config)
+                .is_none()
+        );
+        let jump = baselines
+            .observe(&reading(MetricKind::FailureRate, 0.4), &config)
+            .unwrap();
+        assert_eq!(jump.direction, Direction::Spike);
+        assert!(
+            jump.text
+                .starts_with("Jupiter tx/hr jumped to 40.0% (baseline")
+        );
+        // Failure rates falling is good news.
+        assert!(
+            baselines
+                .observe(&reading(MetricKind::FailureRate, 0.0), &config)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn window_rolls_and_baselines_persist() {
+        let config = WatchConfig {
+            window: 3,
+            ..Default::default()
+        };
+        let mut baselines = Baselines::default();
+        for v in [1.0, 2.0, 3.0, 4.0, 5.0] {
+            baselines.observe(&reading(MetricKind::Activity, v), &config);
+        }
+        assert_eq!(
+            baselines.metrics["program:JUP:x"],
+            VecDeque::from([3.0, 4.0, 5.0])
+        );
+
+        let path = std::env::temp_dir()
+            .join(format!("solguard-chain-watch-{}", std::process::id()))
+            .join("baselines.json");
+        baselines.save(&path).unwrap();
+        let loaded = Baselines::load(&path);
+        std::fs::remove_dir_all(path.parent().unwrap()).ok();
+        assert_eq!(loaded.metrics, baselines.metrics);
+        assert!(Baselines::load(&path).metrics.is_empty());
+    }
+}
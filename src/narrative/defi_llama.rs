@@ -71,6 +71,7 @@ pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<
             ],
             url: Some("https://defillama.com/chain/Solana".into()),
             timestamp: Utc::now(),
+            language: None,
         });
     }
 
@@ -129,6 +130,7 @@ pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<
             ],
             url: Some("https://defillama.com/chain/Solana".into()),
             timestamp: Utc::now(),
+            language: None,
         });
 
         // Category breakdown
@@ -173,6 +175,7 @@ pub async fn collect(config: &DefiLlamaConfig, http: &HttpClient) -> Result<Vec<
                 .collect(),
             url: Some("https://defillama.com/chain/Solana".into()),
             timestamp: Utc::now(),
+            language: None,
         });
     }
 
@@ -118,6 +118,7 @@ pub async fn discover(llm: &LlmClient, config: &DiscoveryConfig) -> Result<Vec<S
                 .collect(),
             url: s.url,
             timestamp: now,
+            language: None,
         })
         .collect();
 
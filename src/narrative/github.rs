@@ -37,6 +37,8 @@ pub struct DiscoveredRepo {
     pub language: String,
     pub stars: u64,
     pub description: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub topics: Vec<String>,
 }
 
 pub struct GitHubData {
@@ -69,6 +71,7 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<GitHubD
                     language: "Rust".into(),
                     stars: repo.stargazers_count,
                     description: repo.description.clone().unwrap_or_default(),
+                    topics: repo.topics.clone().unwrap_or_default(),
                 });
             }
         }
@@ -91,6 +94,7 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<GitHubD
             }],
             url: Some(format!("https://github.com/topics/{topic}?o=desc&s=stars")),
             timestamp: Utc::now(),
+            language: None,
         });
 
         // Per-repo signals grouped by category
@@ -137,6 +141,7 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<GitHubD
                 ],
                 url: None,
                 timestamp: Utc::now(),
+                language: None,
             });
         }
     }
@@ -157,6 +162,7 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<GitHubD
                 language: "Rust".into(),
                 stars: repo.stargazers_count,
                 description: repo.description.clone().unwrap_or_default(),
+                topics: repo.topics.clone().unwrap_or_default(),
             });
         }
     }
@@ -191,9 +197,33 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<GitHubD
             }],
             url: Some("https://github.com/topics/solana?o=desc&s=updated".into()),
             timestamp: Utc::now(),
+            language: None,
         });
     }
 
+    // Development velocity of the tracked repos and the most-starred
+    // discovered ones.
+    let mut by_stars: Vec<&DiscoveredRepo> = discovered_repos.iter().collect();
+    by_stars.sort_by_key(|r| std::cmp::Reverse(r.stars));
+    let mut velocity_repos: Vec<&str> = config.tracked_repos.iter().map(String::as_str).collect();
+    for repo in by_stars.into_iter().take(config.activity_repos) {
+        if !velocity_repos.contains(&repo.name.as_str()) {
+            velocity_repos.push(&repo.name);
+        }
+    }
+    let since = Utc::now() - chrono::Duration::days(config.activity_days as i64);
+    let activity = futures_util::future::join_all(
+        velocity_repos
+            .iter()
+            .map(|repo| repo_activity(http, &config.token, repo, since)),
+    )
+    .await;
+    for (repo, activity) in velocity_repos.iter().zip(activity) {
+        if let Some(signal) = activity.and_then(|a| a.signal(repo, config.activity_days)) {
+            signals.push(signal);
+        }
+    }
+
     info!(
         signal_count = signals.len(),
         repos = discovered_repos.len(),
@@ -205,6 +235,235 @@ pub async fn collect(config: &GitHubConfig, http: &HttpClient) -> Result<GitHubD
     })
 }
 
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    published_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    draft: bool,
+}
+
+/// An entry of `/stats/contributors`: one author's commits per week.
+#[derive(Deserialize)]
+struct ContributorStats {
+    /// Null for commits whose email matches no GitHub account.
+    #[serde(default)]
+    author: Option<serde_json::Value>,
+    weeks: Vec<Week>,
+}
+
+#[derive(Deserialize)]
+struct Week {
+    /// Start of the week, Unix seconds.
+    w: i64,
+    /// Commits.
+    c: u64,
+}
+
+#[derive(Deserialize)]
+struct Issue {
+    created_at: DateTime<Utc>,
+    #[serde(default)]
+    closed_at: Option<DateTime<Utc>>,
+    /// Set on pull requests, which the issues endpoint also lists.
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+/// Issues listed per request; a busier repo's counts are lower bounds.
+const ISSUES_PER_PAGE: usize = 100;
+
+/// A repo's development activity over the window starting at `since`.
+#[derive(Debug, Default, PartialEq)]
+struct RepoActivity {
+    /// Tags of the releases published in the window, oldest first.
+    releases: Vec<String>,
+    /// Authors with commits in the window.
+    active_contributors: usize,
+    /// Of those, authors whose first commit is in the window.
+    new_contributors: usize,
+    /// Authors with commits in the window before this one, but none in it.
+    churned_contributors: usize,
+    issues_opened: usize,
+    issues_closed: usize,
+    /// The issue listing hit [`ISSUES_PER_PAGE`].
+    issues_capped: bool,
+}
+
+/// Releases, contributor churn and issue velocity of `repo` since `since`;
+/// `None` when GitHub answered none of the three requests.
+async fn repo_activity(
+    http: &HttpClient,
+    token: &str,
+    repo: &str,
+    since: DateTime<Utc>,
+) -> Option<RepoActivity> {
+    let releases_url = format!("{GITHUB_API}/repos/{repo}/releases?per_page=30");
+    let stats_url = format!("{GITHUB_API}/repos/{repo}/stats/contributors");
+    let issues_url = format!(
+        "{GITHUB_API}/repos/{repo}/issues?state=all&since={}&per_page={ISSUES_PER_PAGE}",
+        since.format("%Y-%m-%dT%H:%M:%SZ")
+    );
+    let (releases, stats, issues) = tokio::join!(
+        http.get_json_authed::<Vec<Release>>(&releases_url, token),
+        // 202 with an empty body while GitHub computes the stats: a parse
+        // error, and no churn figures this run.
+        http.get_json_authed::<Vec<ContributorStats>>(&stats_url, token),
+        http.get_json_authed::<Vec<Issue>>(&issues_url, token),
+    );
+    for e in [
+        releases.as_ref().err(),
+        stats.as_ref().err(),
+        issues.as_ref().err(),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        tracing::debug!(repo, error = %e, "GitHub activity request failed");
+    }
+    if releases.is_err() && stats.is_err() && issues.is_err() {
+        return None;
+    }
+    let window = Utc::now() - since;
+    let mut activity = RepoActivity::default();
+    if let Ok(releases) = releases {
+        activity.releases = releases_since(&releases, since);
+    }
+    if let Ok(stats) = stats {
+        (
+            activity.active_contributors,
+            activity.new_contributors,
+            activity.churned_contributors,
+        ) = contributor_churn(&stats, since, window);
+    }
+    if let Ok(issues) = issues {
+        activity.issues_capped = issues.len() >= ISSUES_PER_PAGE;
+        let issues = issues.iter().filter(|i| i.pull_request.is_none());
+        for issue in issues {
+            activity.issues_opened += usize::from(issue.created_at >= since);
+            activity.issues_closed += usize::from(issue.closed_at.is_some_and(|c| c >= since));
+        }
+    }
+    Some(activity)
+}
+
+/// Tags of the published releases since `since`, oldest first.
+fn releases_since(releases: &[Release], since: DateTime<Utc>) -> Vec<String> {
+    let mut recent: Vec<(&DateTime<Utc>, &str)> = releases
+        .iter()
+        .filter(|r| !r.draft)
+        .filter_map(|r| Some((r.published_at.as_ref()?, r.tag_name.as_str())))
+        .filter(|(at, _)| **at >= since)
+        .collect();
+    recent.sort();
+    recent.into_iter().map(|(_, tag)| tag.to_string()).collect()
+}
+
+/// Active, new and churned contributors: authors with commits in the
+/// window starting at `since`, those of them with none before it, and
+/// authors with commits in the equally long window before but none since.
+fn contributor_churn(
+    stats: &[ContributorStats],
+    since: DateTime<Utc>,
+    window: chrono::Duration,
+) -> (usize, usize, usize) {
+    let since = since.timestamp();
+    let previous = since - window.num_seconds();
+    let (mut active, mut new, mut churned) = (0, 0, 0);
+    for author in stats.iter().filter(|s| s.author.is_some()) {
+        let weeks: Vec<i64> = author
+            .weeks
+            .iter()
+            .filter(|w| w.c > 0)
+            .map(|w| w.w)
+            .collect();
+        let Some(&first) = weeks.iter().min() else {
+            continue;
+        };
+        if weeks.iter().any(|&w| w >= since) {
+            active += 1;
+            new += usize::from(first >= since);
+        } else if weeks.iter().any(|&w| w >= previous) {
+            churned += 1;
+        }
+    }
+    (active, new, churned)
+}
+
+impl RepoActivity {
+    /// "Protocol X shipped 3 releases in 14 days with 4 new contributors";
+    /// `None` for a repo with nothing to report.
+    fn signal(&self, repo: &str, days: u32) -> Option<Signal> {
+        let releases = self.releases.len();
+        if releases == 0
+            && self.new_contributors == 0
+            && self.issues_opened == 0
+            && self.issues_closed == 0
+        {
+            return None;
+        }
+        let plural = |n: usize, word: &str| format!("{n} {word}{}", if n == 1 { "" } else { "s" });
+        let mut title = if releases > 0 {
+            format!(
+                "{repo} shipped {} in {days} days",
+                plural(releases, "release")
+            )
+        } else {
+            format!("{repo}: no releases in {days} days")
+        };
+        if self.new_contributors > 0 {
+            title.push_str(&format!(
+                " with {}",
+                plural(self.new_contributors, "new contributor")
+            ));
+        }
+        let bound = if self.issues_capped { "at least " } else { "" };
+        let mut description = Vec::new();
+        if releases > 0 {
+            description.push(format!("Releases: {}.", self.releases.join(", ")));
+        }
+        description.push(format!(
+            "Contributors: {} active, {} new, {} inactive since the previous {days} days.",
+            self.active_contributors, self.new_contributors, self.churned_contributors
+        ));
+        description.push(format!(
+            "Issues: {bound}{} opened, {bound}{} closed.",
+            self.issues_opened, self.issues_closed
+        ));
+        let metric = |name: &str, value: usize, unit: &str| Metric {
+            name: name.into(),
+            value: value as f64,
+            unit: unit.into(),
+        };
+        Some(Signal {
+            source: SignalSource::GitHub,
+            category: "Development Velocity".into(),
+            title,
+            description: description.join(" "),
+            metrics: vec![
+                metric("releases", releases, "releases"),
+                metric(
+                    "active_contributors",
+                    self.active_contributors,
+                    "contributors",
+                ),
+                metric("new_contributors", self.new_contributors, "contributors"),
+                metric(
+                    "churned_contributors",
+                    self.churned_contributors,
+                    "contributors",
+                ),
+                metric("issues_opened", self.issues_opened, "issues"),
+                metric("issues_closed", self.issues_closed, "issues"),
+            ],
+            url: Some(format!("https://github.com/{repo}")),
+            timestamp: Utc::now(),
+            language: None,
+        })
+    }
+}
+
 fn categorize_repo(repo: &RepoItem) -> String {
     let topics = repo.topics.as_deref().unwrap_or(&[]);
     let desc = repo.description.as_deref().unwrap_or("").to_lowercase();
@@ -285,3 +544,69 @@ fn categorize_repo(repo: &RepoItem) -> String {
 
     "General Solana".into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86_400;
+
+    #[test]
+    fn counts_recent_releases_and_contributor_churn() {
+        let now = Utc::now();
+        let since = now - chrono::Duration::days(14);
+        let release = |tag: &str, days_ago: i64, draft| Release {
+            tag_name: tag.into(),
+            published_at: Some(now - chrono::Duration::days(days_ago)),
+            draft,
+        };
+        let releases = [
+            release("v1.3.0", 2, false),
+            release("v1.3.1", 1, true),
+            release("v1.2.0", 9, false),
+            release("v1.1.0", 40, false),
+        ];
+        assert_eq!(releases_since(&releases, since), ["v1.2.0", "v1.3.0"]);
+
+        let author = |login: &str, weeks_ago: &[i64]| ContributorStats {
+            author: Some(serde_json::json!({ "login": login })),
+            weeks: weeks_ago
+                .iter()
+                .map(|&d| Week {
+                    w: now.timestamp() - d * DAY,
+                    c: 3,
+                })
+                .collect(),
+        };
+        let stats = [
+            author("core", &[60, 20, 5]),
+            author("newcomer", &[3]),
+            author("left", &[20]),
+            author("long-gone", &[90]),
+        ];
+        let (active, new, churned) = contributor_churn(&stats, since, chrono::Duration::days(14));
+        assert_eq!((active, new, churned), (2, 1, 1));
+    }
+
+    #[test]
+    fn velocity_signal_headline() {
+        let activity = RepoActivity {
+            releases: vec!["v1.2.0".into(), "v1.2.1".into(), "v1.3.0".into()],
+            active_contributors: 9,
+            new_contributors: 4,
+            churned_contributors: 2,
+            issues_opened: 21,
+            issues_closed: 15,
+            issues_capped: false,
+        };
+        let signal = activity.signal("marginfi/protocol", 14).unwrap();
+        assert_eq!(
+            signal.title,
+            "marginfi/protocol shipped 3 releases in 14 days with 4 new contributors"
+        );
+        assert!(signal.description.contains("9 active, 4 new, 2 inactive"));
+        assert!(signal.description.contains("21 opened, 15 closed"));
+        assert_eq!(signal.metrics.len(), 6);
+        assert!(RepoActivity::default().signal("quiet/repo", 14).is_none());
+    }
+}
@@ -0,0 +1,421 @@
+//! Governance signals: active proposals on protocol forums and Realms DAOs.
+//!
+//! Forums are read through Discourse's `/latest.json`. Realms are read
+//! directly from SPL Governance accounts over RPC: the realm's governances
+//! are listed by account type (program, mint, token or generic governance),
+//! and each governance's proposals are counted by state. Proposal kind comes
+//! from the topic title on forums and from the governance type on chain.
+
+use super::rpc_pool::RpcPool;
+use super::types::{Metric, Signal, SignalSource};
+use crate::config::{GovernanceConfig, GovernanceSource};
+use crate::error::Result;
+use crate::http::HttpClient;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+/// SPL Governance account-type discriminators (first byte of account data).
+const PROPOSAL_V2: u8 = 14;
+const GOVERNANCES: [(u8, ProposalKind); 4] = [
+    (18, ProposalKind::Other),          // GovernanceV2
+    (19, ProposalKind::ProgramUpgrade), // ProgramGovernanceV2
+    (20, ProposalKind::TreasuryMove),   // MintGovernanceV2
+    (21, ProposalKind::TreasuryMove),   // TokenGovernanceV2
+];
+/// ProposalV2 layout: account_type (1) + governance (32) + governing_token_mint (32), then state.
+const PROPOSAL_STATE_OFFSET: usize = 65;
+/// Voting, Succeeded, Executing, ExecutingWithErrors.
+const ACTIVE_STATES: [u8; 4] = [2, 3, 4, 8];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProposalKind {
+    ProgramUpgrade,
+    TreasuryMove,
+    ParameterChange,
+    Other,
+}
+
+impl ProposalKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::ProgramUpgrade => "program upgrade",
+            Self::TreasuryMove => "treasury",
+            Self::ParameterChange => "parameter change",
+            Self::Other => "general",
+        }
+    }
+}
+
+/// Classify a proposal by its title. Upgrades win over treasury, which wins
+/// over parameters: "Fund the v2 program upgrade" is about code first.
+pub fn classify(title: &str) -> ProposalKind {
+    let lower = title.to_lowercase();
+    let any = |words: &[&str]| words.iter().any(|w| lower.contains(w));
+    if any(&["upgrade", "deploy", "migrat", "program"]) {
+        ProposalKind::ProgramUpgrade
+    } else if any(&[
+        "treasury", "grant", "fund", "budget", "buyback", "transfer", "allocat",
+    ]) {
+        ProposalKind::TreasuryMove
+    } else if any(&[
+        "parameter",
+        "fee",
+        "rate",
+        "limit",
+        "cap ",
+        "threshold",
+        "ltv",
+        "collateral",
+        "oracle",
+        "emission",
+        "reward",
+    ]) {
+        ProposalKind::ParameterChange
+    } else {
+        ProposalKind::Other
+    }
+}
+
+pub async fn collect(
+    config: &GovernanceConfig,
+    rpc: &RpcPool,
+    http: &HttpClient,
+) -> Result<Vec<Signal>> {
+    if !config.enabled || config.sources.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let cutoff = Utc::now() - chrono::Duration::days(config.lookback_days as i64);
+    let mut signals = Vec::new();
+
+    for source in &config.sources {
+        if let Some(ref forum) = source.forum_url {
+            let url = format!("{}/latest.json", forum.trim_end_matches('/'));
+            match http.get_json::<Latest>(&url).await {
+                Ok(latest) => {
+                    signals.extend(forum_signals(source, &latest.topic_list.topics, cutoff))
+                }
+                Err(e) => {
+                    warn!(source = %source.name, url = %url, error = %e, "failed to read forum")
+                }
+            }
+        }
+        if let Some(ref realm) = source.realm {
+            match realm_signal(source, realm, rpc).await {
+                Ok(sig) => signals.extend(sig),
+                Err(e) => {
+                    warn!(source = %source.name, realm = %realm, error = %e, "failed to read realm")
+                }
+            }
+        }
+    }
+
+    info!(signal_count = signals.len(), "collected governance signals");
+    Ok(signals)
+}
+
+#[derive(Deserialize)]
+struct Latest {
+    topic_list: TopicList,
+}
+
+#[derive(Deserialize)]
+struct TopicList {
+    #[serde(default)]
+    topics: Vec<Topic>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Topic {
+    title: String,
+    #[serde(default)]
+    posts_count: u32,
+    last_posted_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    closed: bool,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    pinned: bool,
+}
+
+/// One signal per proposal kind with recent, open forum topics.
+fn forum_signals(
+    source: &GovernanceSource,
+    topics: &[Topic],
+    cutoff: DateTime<Utc>,
+) -> Vec<Signal> {
+    let mut by_kind: BTreeMap<ProposalKind, Vec<&Topic>> = BTreeMap::new();
+    for topic in topics {
+        let recent = topic.last_posted_at.is_some_and(|t| t >= cutoff);
+        if recent && !topic.closed && !topic.archived && !topic.pinned {
+            by_kind
+                .entry(classify(&topic.title))
+                .or_default()
+                .push(topic);
+        }
+    }
+
+    by_kind
+        .into_iter()
+        .map(|(kind, topics)| {
+            let titles: Vec<&str> = topics.iter().take(5).map(|t| t.title.as_str()).collect();
+            let posts: u32 = topics.iter().map(|t| t.posts_count).sum();
+            Signal {
+                source: SignalSource::Governance,
+                category: source.category.clone(),
+                title: format!(
+                    "{} governance: {} active {} proposals",
+                    source.name,
+                    topics.len(),
+                    kind.label()
+                ),
+                description: format!(
+                    "Forum topics: {}.{}",
+                    titles.join("; "),
+                    repo_note(source, kind)
+                ),
+                metrics: vec![
+                    Metric {
+                        name: "active_proposals".into(),
+                        value: topics.len() as f64,
+                        unit: "proposals".into(),
+                    },
+                    Metric {
+                        name: "discussion_posts".into(),
+                        value: posts as f64,
+                        unit: "posts".into(),
+                    },
+                ],
+                url: source.forum_url.clone(),
+                timestamp: Utc::now(),
+                language: None,
+            }
+        })
+        .collect()
+}
+
+/// Count the realm's active on-chain proposals by governance kind.
+async fn realm_signal(
+    source: &GovernanceSource,
+    realm: &str,
+    rpc: &RpcPool,
+) -> Result<Option<Signal>> {
+    let program = &source.governance_program;
+    let mut active: BTreeMap<ProposalKind, usize> = BTreeMap::new();
+
+    for (account_type, kind) in GOVERNANCES {
+        let governances = program_accounts(rpc, program, account_type, realm, 0).await?;
+        for (governance, _) in governances {
+            let proposals = program_accounts(
+                rpc,
+                program,
+                PROPOSAL_V2,
+                &governance,
+                PROPOSAL_STATE_OFFSET,
+            )
+            .await?;
+            let n = proposals
+                .iter()
+                .filter(|(_, state)| state.is_some_and(|s| ACTIVE_STATES.contains(&s)))
+                .count();
+            if n > 0 {
+                *active.entry(kind).or_default() += n;
+            }
+        }
+    }
+
+    let total: usize = active.values().sum();
+    if total == 0 {
+        return Ok(None);
+    }
+    let breakdown: Vec<String> = active
+        .iter()
+        .map(|(kind, n)| format!("{n} {}", kind.label()))
+        .collect();
+    let notes: String = active.keys().map(|k| repo_note(source, *k)).collect();
+    Ok(Some(Signal {
+        source: SignalSource::Governance,
+        category: source.category.clone(),
+        title: format!("{} DAO: {total} active on-chain proposals", source.name),
+        description: format!(
+            "Realm {realm} proposals in voting or execution: {}.{notes}",
+            breakdown.join(", ")
+        ),
+        metrics: active
+            .iter()
+            .map(|(kind, n)| Metric {
+                name: format!("active_{}", kind.label().replace(' ', "_")),
+                value: *n as f64,
+                unit: "proposals".into(),
+            })
+            .collect(),
+        url: Some(format!("https://app.realms.today/dao/{realm}")),
+        timestamp: Utc::now(),
+        language: None,
+    }))
+}
+
+/// Accounts of `account_type` whose first field (offset 1) is `parent`,
+/// returned with the byte at `slice_offset`.
+async fn program_accounts(
+    rpc: &RpcPool,
+    program: &str,
+    account_type: u8,
+    parent: &str,
+    slice_offset: usize,
+) -> Result<Vec<(String, Option<u8>)>> {
+    #[derive(Deserialize)]
+    struct KeyedAccount {
+        pubkey: String,
+        account: AccountData,
+    }
+    #[derive(Deserialize)]
+    struct AccountData {
+        data: (String, String),
+    }
+
+    let accounts: Vec<KeyedAccount> = rpc
+        .call(
+            "getProgramAccounts",
+            serde_json::json!([program, {
+                "encoding": "base64",
+                "dataSlice": {"offset": slice_offset, "length": 1},
+                "filters": [
+                    {"memcmp": {"offset": 0, "bytes": base58_byte(account_type)}},
+                    {"memcmp": {"offset": 1, "bytes": parent}},
+                ],
+            }]),
+        )
+        .await?;
+    Ok(accounts
+        .into_iter()
+        .map(|a| (a.pubkey, first_byte_base64(&a.account.data.0)))
+        .collect())
+}
+
+fn repo_note(source: &GovernanceSource, kind: ProposalKind) -> String {
+    match (&source.repo, kind) {
+        (Some(repo), ProposalKind::ProgramUpgrade) => {
+            format!(" Code changes expected in {repo}.")
+        }
+        _ => String::new(),
+    }
+}
+
+/// Base58 of a single byte below 58 — enough for account-type discriminators.
+fn base58_byte(v: u8) -> String {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    debug_assert!(v < 58);
+    if v == 0 {
+        // A leading zero byte is encoded as '1'.
+        return "1".into();
+    }
+    (ALPHABET[v as usize] as char).to_string()
+}
+
+/// First byte of base64 data (4 chars encode 3 bytes; the first needs 2 chars).
+fn first_byte_base64(data: &str) -> Option<u8> {
+    let value = |c: u8| -> Option<u8> {
+        Some(match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        })
+    };
+    let bytes = data.as_bytes();
+    let (hi, lo) = (value(*bytes.first()?)?, value(*bytes.get(1)?)?);
+    Some((hi << 2) | (lo >> 4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source() -> GovernanceSource {
+        GovernanceSource {
+            name: "Marinade".into(),
+            category: "Staking".into(),
+            forum_url: Some("https://forum.marinade.finance".into()),
+            realm: None,
+            governance_program: String::new(),
+            repo: Some("marinade-finance/liquid-staking-program".into()),
+        }
+    }
+
+    fn topic(title: &str, days_ago: i64, closed: bool) -> Topic {
+        Topic {
+            title: title.into(),
+            posts_count: 3,
+            last_posted_at: Some(Utc::now() - chrono::Duration::days(days_ago)),
+            closed,
+            archived: false,
+            pinned: false,
+        }
+    }
+
+    #[test]
+    fn proposals_classified_by_title() {
+        assert_eq!(
+            classify("MIP-12: Upgrade staking program to v2"),
+            ProposalKind::ProgramUpgrade
+        );
+        assert_eq!(
+            classify("Treasury diversification into USDC"),
+            ProposalKind::TreasuryMove
+        );
+        assert_eq!(
+            classify("Lower the protocol fee to 4%"),
+            ProposalKind::ParameterChange
+        );
+        assert_eq!(classify("Community call notes"), ProposalKind::Other);
+    }
+
+    #[test]
+    fn forum_signals_group_recent_open_topics() {
+        let topics = vec![
+            topic("Upgrade the staking program", 1, false),
+            topic("Reduce withdrawal fee", 2, false),
+            topic("Raise deposit cap to 10M SOL", 3, false),
+            topic("Deploy v1 (done)", 1, true),
+            topic("Old fee discussion", 60, false),
+        ];
+        let cutoff = Utc::now() - chrono::Duration::days(14);
+        let signals = forum_signals(&source(), &topics, cutoff);
+        assert_eq!(signals.len(), 2);
+
+        let upgrade = &signals[0];
+        assert_eq!(upgrade.source, SignalSource::Governance);
+        assert_eq!(upgrade.category, "Staking");
+        assert!(upgrade.title.contains("1 active program upgrade"));
+        assert!(
+            upgrade
+                .description
+                .contains("marinade-finance/liquid-staking-program")
+        );
+        assert!(signals[1].title.contains("2 active parameter change"));
+    }
+
+    #[test]
+    fn discourse_latest_parsed() {
+        let json = r#"{"topic_list":{"topics":[{"id":1,"title":"Fee change","slug":"fee-change",
+            "posts_count":4,"last_posted_at":"2025-06-01T12:00:00.000Z","closed":false}]}}"#;
+        let latest: Latest = serde_json::from_str(json).unwrap();
+        assert_eq!(latest.topic_list.topics.len(), 1);
+        assert_eq!(latest.topic_list.topics[0].posts_count, 4);
+    }
+
+    #[test]
+    fn account_filter_encoding() {
+        assert_eq!(base58_byte(PROPOSAL_V2), "F");
+        assert_eq!(base58_byte(18), "K");
+        assert_eq!(first_byte_base64("Ag=="), Some(2));
+        assert_eq!(first_byte_base64("CA=="), Some(8));
+        assert_eq!(first_byte_base64(""), None);
+    }
+}
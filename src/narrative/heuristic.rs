@@ -0,0 +1,253 @@
+//! LLM-free narratives (`narratives --no-llm`), also the fallback when
+//! LLM synthesis fails.
+//!
+//! Each aggregated signal category with at least [`MIN_SIGNALS`] signals
+//! becomes one narrative, titled by the keywords most shared across its
+//! signal titles. Confidence is computed from source diversity and volume;
+//! the trend reflects corroboration, since signals carry no time series.
+//! Output is deterministic for a given signal set.
+
+use super::aggregator::SignalGroup;
+use super::github::DiscoveredRepo;
+use super::repo_check;
+use super::synthesizer::SynthesizedNarrative;
+use super::types::{Metric, Signal, SignalSource, TrendDirection};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+/// Tag carried by every heuristic narrative.
+pub const TAG: &str = "heuristic";
+
+const MIN_SIGNALS: usize = 2;
+const MAX_NARRATIVES: usize = 8;
+const MAX_KEYWORDS: usize = 3;
+const MAX_REPOS: usize = 5;
+
+/// Templated narratives for `groups`, most confident first.
+pub fn narratives(
+    signals: &[Signal],
+    groups: &[SignalGroup],
+    repos: &[DiscoveredRepo],
+) -> Vec<SynthesizedNarrative> {
+    let mut out: Vec<SynthesizedNarrative> = groups
+        .iter()
+        .filter(|g| g.total_signals >= MIN_SIGNALS)
+        .map(|g| narrative(signals, g, repos))
+        .collect();
+    out.sort_by(|a, b| {
+        b.confidence
+            .total_cmp(&a.confidence)
+            .then_with(|| a.title.cmp(&b.title))
+    });
+    out.truncate(MAX_NARRATIVES);
+    out
+}
+
+fn narrative(
+    signals: &[Signal],
+    g: &SignalGroup,
+    repos: &[DiscoveredRepo],
+) -> SynthesizedNarrative {
+    let members: Vec<&Signal> = g.signals.iter().map(|&i| &signals[i]).collect();
+    let keywords = top_keywords(&members);
+    let sources: BTreeSet<String> = members.iter().map(|s| s.source.to_string()).collect();
+    let lead = members
+        .iter()
+        .max_by_key(|s| (s.metrics.len(), std::cmp::Reverse(s.title.len())))
+        .map_or("", |s| s.title.as_str());
+
+    let mut metrics = g.key_metrics.clone();
+    metrics.sort_by(|a, b| a.name.cmp(&b.name));
+    let metrics_text = if metrics.is_empty() {
+        String::new()
+    } else {
+        let shown: Vec<String> = metrics.iter().take(3).map(Metric::to_string).collect();
+        format!(" Key metrics: {}.", shown.join(", "))
+    };
+    let title = if keywords.is_empty() {
+        format!("{} activity", g.category)
+    } else {
+        format!("{} activity: {}", g.category, keywords.join(", "))
+    };
+    let summary = format!(
+        "{} signals from {} source{} ({}) cluster around {}, led by \"{lead}\".{metrics_text} \
+         Security angle: {}",
+        g.total_signals,
+        g.source_diversity,
+        if g.source_diversity == 1 { "" } else { "s" },
+        sources.into_iter().collect::<Vec<_>>().join(", "),
+        g.category,
+        security_angle(&g.category),
+    );
+
+    let mut cluster_words: HashSet<String> = keywords.iter().cloned().collect();
+    cluster_words.extend(repo_check::keywords(&g.category));
+    let active_repos = repos
+        .iter()
+        .filter(|r| !(r.description.trim().is_empty() && r.topics.is_empty()))
+        .filter(|r| repo_check::related(&cluster_words, r))
+        .take(MAX_REPOS)
+        .map(|r| r.name.clone())
+        .collect();
+
+    SynthesizedNarrative {
+        title,
+        summary,
+        confidence: confidence(g.source_diversity, g.total_signals),
+        trend: trend(&members),
+        key_metrics: metrics,
+        active_repos,
+        tags: vec![g.category.clone(), TAG.to_string()],
+    }
+}
+
+/// 0.35 for one source with two signals, +0.15 per extra source, +0.02 per
+/// signal up to ten; capped at 0.9 since nothing here was cross-checked.
+pub fn confidence(source_diversity: usize, total_signals: usize) -> f64 {
+    let score = 0.15 * source_diversity as f64 + 0.02 * total_signals.min(10) as f64 + 0.16;
+    (score * 100.0).round().min(90.0) / 100.0
+}
+
+/// Corroboration stands in for momentum: one source is `Emerging`, three or
+/// more with a quantitative source are `Accelerating`.
+fn trend(members: &[&Signal]) -> TrendDirection {
+    let sources: HashSet<SignalSource> = members.iter().map(|s| s.source).collect();
+    let quantitative = sources.iter().any(|s| {
+        matches!(
+            s,
+            SignalSource::GitHub | SignalSource::SolanaOnchain | SignalSource::DeFiLlama
+        )
+    });
+    match sources.len() {
+        1 => TrendDirection::Emerging,
+        n if n >= 3 && quantitative => TrendDirection::Accelerating,
+        _ => TrendDirection::Stable,
+    }
+}
+
+/// Words shared by the most signal titles; ties alphabetical.
+fn top_keywords(members: &[&Signal]) -> Vec<String> {
+    let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+    for s in members {
+        for word in repo_check::keywords(&s.title) {
+            if word.len() >= 3 && !word.chars().all(|c| c.is_ascii_digit()) {
+                *counts.entry(word).or_default() += 1;
+            }
+        }
+    }
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+        .into_iter()
+        .take(MAX_KEYWORDS)
+        .map(|(w, _)| w)
+        .collect()
+}
+
+fn security_angle(category: &str) -> &'static str {
+    match category {
+        "DeFi" => {
+            "new and forked DeFi programs concentrate value in freshly written, often unaudited code."
+        }
+        "Staking" => {
+            "stake pools and LST programs hold delegated SOL; authority and accounting bugs are high-value targets."
+        }
+        "NFT & Gaming" => {
+            "marketplace and game programs handle escrow and royalties with many account relationships to get wrong."
+        }
+        "DePIN" => {
+            "reward distribution programs trust off-chain attestations; oracle and signer checks carry the risk."
+        }
+        "AI & Agents" => {
+            "agent-controlled wallets and programs widen who can sign, so authority checks matter more."
+        }
+        "Payments" => {
+            "payment flows move stablecoins at volume; token-account and mint validation are the attack surface."
+        }
+        "Governance" => {
+            "governance programs can upgrade or drain treasuries; proposal execution paths need scrutiny."
+        }
+        "Infrastructure" => {
+            "shared tooling and SDKs propagate bugs into every program built on them."
+        }
+        _ => {
+            "more activity means more new code shipping, and with it more unaudited attack surface."
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::narrative::aggregator;
+    use chrono::{TimeZone, Utc};
+
+    fn signal(source: SignalSource, category: &str, title: &str) -> Signal {
+        Signal {
+            source,
+            category: category.into(),
+            title: title.into(),
+            description: String::new(),
+            metrics: vec![Metric {
+                name: "stars_7d".into(),
+                value: 120.0,
+                unit: String::new(),
+            }],
+            url: None,
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(),
+            language: None,
+        }
+    }
+
+    fn repo(name: &str, description: &str) -> DiscoveredRepo {
+        DiscoveredRepo {
+            name: name.into(),
+            language: "Rust".into(),
+            stars: 10,
+            description: description.into(),
+            topics: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn clusters_categories_into_templated_narratives() {
+        let signals = vec![
+            signal(
+                SignalSource::GitHub,
+                "Lending",
+                "Perps lending vaults trending",
+            ),
+            signal(SignalSource::DeFiLlama, "Dexes", "Perps DEX volume up"),
+            signal(SignalSource::Discovery, "DeFi", "Lending perps launch"),
+            signal(SignalSource::GitHub, "NFT", "Compressed NFT mints"),
+        ];
+        let groups = aggregator::aggregate(&signals);
+        let repos = vec![
+            repo("acme/perp-engine", "Perpetual futures engine"),
+            repo("acme/cnft-tools", "Compressed NFT tooling"),
+            repo("acme/no-meta", ""),
+        ];
+        let out = narratives(&signals, &groups, &repos);
+        // The single NFT signal is below MIN_SIGNALS.
+        assert_eq!(out.len(), 1);
+        let n = &out[0];
+        assert_eq!(n.title, "DeFi activity: perps, lending, dex");
+        assert!(n.summary.starts_with(
+            "3 signals from 3 sources (DeFiLlama, Discovery, GitHub) cluster around DeFi"
+        ));
+        assert!(n.summary.contains("Key metrics: stars_7d: 360.0."));
+        assert_eq!(n.confidence, 0.67);
+        assert_eq!(n.trend, TrendDirection::Accelerating);
+        assert_eq!(n.active_repos, vec!["acme/perp-engine"]);
+        assert_eq!(n.tags, vec!["DeFi", TAG]);
+
+        let again = narratives(&signals, &aggregator::aggregate(&signals), &repos);
+        assert_eq!(again[0].summary, n.summary);
+    }
+
+    #[test]
+    fn confidence_grows_with_source_diversity() {
+        assert_eq!(confidence(1, 2), 0.35);
+        assert_eq!(confidence(2, 2), 0.5);
+        assert!(confidence(4, 50) <= 0.9);
+    }
+}
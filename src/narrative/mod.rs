@@ -1,10 +1,16 @@
 mod aggregator;
+pub mod chain_watch;
 mod defi_llama;
 mod discovery;
 mod github;
-#[allow(dead_code)]
+mod governance;
+mod heuristic;
+mod quality;
+mod repo_check;
+pub mod rpc_pool;
+pub mod signals;
 mod social;
-mod solana_rpc;
+pub(crate) mod solana_rpc;
 mod synthesizer;
 mod types;
 
@@ -17,7 +23,7 @@ use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tracing::info;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Narrative {
     pub title: String,
     pub summary: String,
@@ -32,13 +38,30 @@ pub struct Narrative {
     pub risk_level: String,
     #[serde(default)]
     pub repo_findings: Vec<(String, Vec<usize>)>,
+    /// Free-form labels from tag rules or LLM suggestion.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Assigned repos whose GitHub description and topics share nothing with
+    /// the narrative. Dropped from `active_repos` unless the policy is `flag`.
+    #[serde(default)]
+    pub mismatched_repos: Vec<String>,
+    /// Largest TVL (USD) among the narrative's key metrics, if it cites one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tvl_usd: Option<f64>,
+    /// Score from the post-synthesis quality gate (0.0–1.0); `None` for
+    /// heuristic narratives and runs with the gate off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<f64>,
 }
 
 /// Run the full narrative detection pipeline from config.
+///
+/// Without a `router` no LLM is called: discovery is skipped and narratives
+/// are built by [`heuristic`], which is also the fallback when synthesis fails.
 pub async fn run_narrative_pipeline(
     config_path: &Path,
     llm_override: Option<&LlmOverride>,
-    router: &ModelRouter,
+    router: Option<&ModelRouter>,
 ) -> Result<Vec<Narrative>> {
     info!("narrative pipeline: starting");
 
@@ -51,14 +74,46 @@ pub async fn run_narrative_pipeline(
     }
 
     let http = HttpClient::new("st-solguard/0.1.0").map_err(|e| anyhow::anyhow!("{e}"))?;
-
-    // Collect signals from all sources in parallel — discovery replaces social
-    let discovery_llm = router.client_for(TaskKind::NarrativeDiscovery);
-    let (discovery_result, github_result, solana_result, defi_llama_result) = tokio::join!(
-        discovery::discover(discovery_llm, &config.discovery),
+    let rpc = rpc_pool::RpcPool::new(&config.solana, &http);
+    rpc.check_health().await;
+
+    // Collect signals from all sources in parallel. Discovery replaces blog
+    // scraping unless `[social] enabled` turns it back on.
+    let discovery = async {
+        match router {
+            Some(r) => {
+                discovery::discover(
+                    r.client_for(TaskKind::NarrativeDiscovery),
+                    &config.discovery,
+                )
+                .await
+            }
+            None => Ok(Vec::new()),
+        }
+    };
+    let social = async {
+        if !config.social.enabled {
+            return Ok(Vec::new());
+        }
+        let translator = router
+            .filter(|_| config.social.translate)
+            .map(|r| r.client_for(TaskKind::Translation));
+        social::collect(&config.social, &http, translator).await
+    };
+    let (
+        discovery_result,
+        social_result,
+        github_result,
+        solana_result,
+        defi_llama_result,
+        governance_result,
+    ) = tokio::join!(
+        discovery,
+        social,
         github::collect(&config.github, &http),
-        solana_rpc::collect(&config.solana, &http),
+        solana_rpc::collect(&config.solana, &rpc),
         defi_llama::collect(&config.defi_llama, &http),
+        governance::collect(&config.governance, &rpc, &http),
     );
 
     let mut signals = Vec::new();
@@ -72,6 +127,11 @@ pub async fn run_narrative_pipeline(
         Err(e) => tracing::warn!(error = %e, "discovery signal collection failed"),
     }
 
+    match social_result {
+        Ok(sigs) => signals.extend(sigs),
+        Err(e) => tracing::warn!(error = %e, "social signal collection failed"),
+    }
+
     match github_result {
         Ok(data) => {
             signals.extend(data.signals);
@@ -90,12 +150,23 @@ pub async fn run_narrative_pipeline(
         Err(e) => tracing::warn!(error = %e, "DeFiLlama signal collection failed"),
     }
 
+    match governance_result {
+        Ok(sigs) => signals.extend(sigs),
+        Err(e) => tracing::warn!(error = %e, "governance signal collection failed"),
+    }
+
     info!(
         signals = signals.len(),
         repos = discovered_repos.len(),
         "signal collection complete"
     );
 
+    let signal_set = signals::SignalSet::new(signals);
+    if let Err(e) = signal_set.save_to(&signals::signals_dir()) {
+        tracing::warn!(error = %e, "failed to save signals");
+    }
+    let signals = signal_set.signals;
+
     if signals.is_empty() {
         tracing::warn!("no signals collected — returning empty narratives");
         return Ok(Vec::new());
@@ -105,15 +176,27 @@ pub async fn run_narrative_pipeline(
     let groups = aggregator::aggregate(&signals);
     let signals_json = aggregator::signals_to_json(&signals, &groups, &discovered_repos);
 
-    // Synthesize narratives via LLM
-    let synth_llm = router.client_for(TaskKind::NarrativeSynthesis);
-
-    let synthesized = synthesizer::identify_narratives(synth_llm, &signals_json)
-        .await
-        .map_err(|e| anyhow::anyhow!("{e}"))?;
+    // Synthesize narratives via LLM, or from templates without one
+    let synthesized = match router {
+        Some(r) => {
+            let synth_llm = r.client_for(TaskKind::NarrativeSynthesis);
+            match synthesizer::identify_narratives(synth_llm, &signals_json).await {
+                Ok(n) => n,
+                Err(e) => {
+                    tracing::warn!(error = %e, "narrative synthesis failed, using heuristic narratives");
+                    heuristic::narratives(&signals, &groups, &discovered_repos)
+                }
+            }
+        }
+        None => {
+            info!("building heuristic narratives (no LLM)");
+            heuristic::narratives(&signals, &groups, &discovered_repos)
+        }
+    };
 
     // Map to solguard Narrative type with LLM-assigned repos
-    let narratives = synthesized
+    let metric_counts: Vec<usize> = synthesized.iter().map(|n| n.key_metrics.len()).collect();
+    let mut narratives: Vec<Narrative> = synthesized
         .into_iter()
         .map(|n| Narrative {
             title: n.title,
@@ -125,8 +208,37 @@ pub async fn run_narrative_pipeline(
             risk_score: 0.0,
             risk_level: String::new(),
             repo_findings: Vec::new(),
+            tags: n.tags.iter().map(|t| crate::tags::normalize(t)).collect(),
+            mismatched_repos: Vec::new(),
+            tvl_usd: n
+                .key_metrics
+                .iter()
+                .filter_map(types::Metric::tvl_usd)
+                .reduce(f64::max),
+            quality: None,
         })
         .collect();
+    repo_check::verify_assignments(
+        &mut narratives,
+        &discovered_repos,
+        config.targets.repo_verification,
+    );
+    crate::tags::tag_narratives(&mut narratives, &config.tagging.rules);
+
+    let gate = &config.narrative_quality;
+    if gate.enabled {
+        let mut scores: Vec<quality::QualityScore> = narratives
+            .iter()
+            .zip(&metric_counts)
+            .map(|(n, &metrics)| quality::score(n, metrics, &signals))
+            .collect();
+        if gate.llm_critic
+            && let Some(r) = router
+        {
+            quality::critique(r.client_for(TaskKind::Triage), &narratives, &mut scores).await;
+        }
+        narratives = quality::apply(narratives, &scores, gate.min_score);
+    }
 
     info!("narrative pipeline complete");
     Ok(narratives)
@@ -0,0 +1,320 @@
+//! Post-synthesis quality gate. A vague narrative ("the Solana ecosystem is
+//! growing") still claims scan targets and a report section, so each
+//! synthesized narrative is scored and those below `[narrative_quality]
+//! min_score` are dropped.
+//!
+//! The deterministic score weighs four things:
+//!
+//! - source diversity (0.3): how many signal sources (GitHub, DeFiLlama, ...)
+//!   have a signal sharing two keywords with the narrative, up to three;
+//! - metrics (0.25): key metrics cited, or at least numbers in the summary;
+//! - repo relevance (0.25): the share of assigned repos that passed repo
+//!   verification; none assigned scores 0;
+//! - specificity (0.2): distinctive words in the title, up to three.
+//!
+//! With `llm_critic`, a cheap model also rates each narrative and the final
+//! score is the mean of the two. Heuristic narratives are built from signal
+//! groups by rule and pass unscored.
+
+use super::Narrative;
+use super::heuristic::TAG as HEURISTIC_TAG;
+use super::repo_check::{keywords, stem};
+use super::types::Signal;
+use crate::llm::LlmClient;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tracing::{info, warn};
+
+/// Score components, each in 0.0–1.0.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct QualityScore {
+    pub source_diversity: f64,
+    pub metrics: f64,
+    pub repo_relevance: f64,
+    pub specificity: f64,
+    /// The LLM critic's rating, when it ran and rated this narrative.
+    pub critic: Option<f64>,
+}
+
+impl QualityScore {
+    /// Weighted deterministic score, averaged with the critic's when present.
+    pub fn total(&self) -> f64 {
+        let deterministic = 0.3 * self.source_diversity
+            + 0.25 * self.metrics
+            + 0.25 * self.repo_relevance
+            + 0.2 * self.specificity;
+        match self.critic {
+            Some(critic) => (deterministic + critic) / 2.0,
+            None => deterministic,
+        }
+    }
+
+    /// The weakest components, for the rejection log.
+    fn weaknesses(&self) -> Vec<&'static str> {
+        [
+            (self.source_diversity, "few sources"),
+            (self.metrics, "no metrics"),
+            (self.repo_relevance, "no relevant repos"),
+            (self.specificity, "generic title"),
+        ]
+        .into_iter()
+        .filter(|(score, _)| *score < 0.5)
+        .map(|(_, label)| label)
+        .collect()
+    }
+}
+
+/// Score one narrative against the signals it was synthesized from.
+/// `metric_count` is the number of key metrics synthesis cited for it.
+pub fn score(narrative: &Narrative, metric_count: usize, signals: &[Signal]) -> QualityScore {
+    let text = format!("{} {}", narrative.title, narrative.summary);
+    let narrative_stems = stems(&text);
+    let sources: HashSet<_> = signals
+        .iter()
+        .filter(|s| {
+            let signal = stems(&format!("{} {}", s.title, s.description));
+            signal.intersection(&narrative_stems).count() >= 2
+        })
+        .map(|s| s.source)
+        .collect();
+
+    let metrics = if metric_count > 0 {
+        1.0
+    } else if narrative.summary.chars().any(|c| c.is_ascii_digit()) {
+        0.5
+    } else {
+        0.0
+    };
+
+    let mismatched = narrative
+        .mismatched_repos
+        .iter()
+        .filter(|r| !narrative.active_repos.contains(r))
+        .count();
+    let relevant = narrative
+        .active_repos
+        .iter()
+        .filter(|r| !narrative.mismatched_repos.contains(r))
+        .count();
+    let assigned = narrative.active_repos.len() + mismatched;
+    let repo_relevance = if assigned == 0 {
+        0.0
+    } else {
+        relevant as f64 / assigned as f64
+    };
+
+    let distinctive = keywords(&narrative.title)
+        .iter()
+        .filter(|w| w.len() >= 4)
+        .count();
+
+    QualityScore {
+        source_diversity: sources.len().min(3) as f64 / 3.0,
+        metrics,
+        repo_relevance,
+        specificity: distinctive.min(3) as f64 / 3.0,
+        critic: None,
+    }
+}
+
+fn stems(text: &str) -> HashSet<String> {
+    keywords(text)
+        .iter()
+        .filter(|w| w.len() >= 4)
+        .map(|w| stem(w))
+        .collect()
+}
+
+/// Drop narratives scoring below `min_score`, recording the score on those
+/// kept. `scores` is parallel to `narratives`; heuristic narratives pass.
+pub fn apply(
+    narratives: Vec<Narrative>,
+    scores: &[QualityScore],
+    min_score: f64,
+) -> Vec<Narrative> {
+    let before = narratives.len();
+    let kept: Vec<Narrative> = narratives
+        .into_iter()
+        .zip(scores)
+        .filter_map(|(mut n, score)| {
+            if n.tags.iter().any(|t| t == HEURISTIC_TAG) {
+                return Some(n);
+            }
+            let total = score.total();
+            if total < min_score {
+                warn!(
+                    narrative = %n.title,
+                    score = format!("{total:.2}"),
+                    weaknesses = ?score.weaknesses(),
+                    "narrative rejected below quality threshold"
+                );
+                return None;
+            }
+            n.quality = Some(total);
+            Some(n)
+        })
+        .collect();
+    if kept.len() < before {
+        info!(
+            rejected = before - kept.len(),
+            kept = kept.len(),
+            "narrative quality gate"
+        );
+    }
+    kept
+}
+
+const CRITIC_PROMPT: &str = r#"You review narratives written by a Solana ecosystem analyst before they steer a security scan. Rate each from 0.0 to 1.0:
+
+- 1.0: specific (names protocols, repos or mechanisms), quantified, and states a concrete security angle.
+- 0.5: a real trend, but generic in places or missing numbers.
+- 0.0: vague filler ("the Solana ecosystem is growing"), unsupported, or with no security relevance.
+
+Respond in JSON: {"ratings": [{"index": 1, "score": 0.7}]} with one entry per narrative, using the numbers given."#;
+
+#[derive(Deserialize)]
+struct CriticResponse {
+    ratings: Vec<CriticRating>,
+}
+
+#[derive(Deserialize)]
+struct CriticRating {
+    index: usize,
+    score: f64,
+}
+
+/// Ask `llm` to rate each narrative, filling in [`QualityScore::critic`].
+/// On failure the scores stay deterministic.
+pub async fn critique(llm: &LlmClient, narratives: &[Narrative], scores: &mut [QualityScore]) {
+    if narratives.is_empty() {
+        return;
+    }
+    let listing: String = narratives
+        .iter()
+        .enumerate()
+        .map(|(i, n)| format!("{}. {}\n{}\n\n", i + 1, n.title, n.summary))
+        .collect();
+    match llm
+        .complete_json::<CriticResponse>(CRITIC_PROMPT, &listing)
+        .await
+    {
+        Ok(response) => apply_ratings(&response.ratings, scores),
+        Err(e) => warn!(error = %e, "narrative critic failed, using deterministic scores"),
+    }
+}
+
+fn apply_ratings(ratings: &[CriticRating], scores: &mut [QualityScore]) {
+    for rating in ratings {
+        if let Some(score) = rating.index.checked_sub(1).and_then(|i| scores.get_mut(i)) {
+            score.critic = Some(rating.score.clamp(0.0, 1.0));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::narrative::types::SignalSource;
+
+    fn signal(source: SignalSource, title: &str) -> Signal {
+        Signal {
+            source,
+            category: "defi".into(),
+            title: title.into(),
+            description: String::new(),
+            metrics: Vec::new(),
+            url: None,
+            timestamp: chrono::Utc::now(),
+            language: None,
+        }
+    }
+
+    fn signals() -> Vec<Signal> {
+        vec![
+            signal(
+                SignalSource::GitHub,
+                "Orca whirlpools concentrated liquidity fork",
+            ),
+            signal(
+                SignalSource::DeFiLlama,
+                "Concentrated liquidity TVL up on Raydium",
+            ),
+            signal(SignalSource::Discovery, "Validator client diversity report"),
+        ]
+    }
+
+    #[test]
+    fn specific_narrative_passes_and_vague_one_is_rejected() {
+        let specific = Narrative {
+            title: "Concentrated Liquidity Migration on Orca and Raydium".into(),
+            summary: "CLMM TVL grew 42% as LPs moved to concentrated liquidity pools.".into(),
+            active_repos: vec!["orca-so/whirlpools".into()],
+            ..Default::default()
+        };
+        let vague = Narrative {
+            title: "Solana Ecosystem Is Growing".into(),
+            summary: "Activity across the ecosystem keeps increasing.".into(),
+            ..Default::default()
+        };
+        let specific_score = score(&specific, 2, &signals());
+        let vague_score = score(&vague, 0, &signals());
+        assert!((specific_score.source_diversity - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(specific_score.metrics, 1.0);
+        assert_eq!(specific_score.repo_relevance, 1.0);
+        assert_eq!(specific_score.specificity, 1.0);
+        assert_eq!(vague_score.specificity, 0.0);
+        assert_eq!(
+            vague_score.weaknesses(),
+            vec![
+                "few sources",
+                "no metrics",
+                "no relevant repos",
+                "generic title"
+            ]
+        );
+
+        let heuristic = Narrative {
+            title: "defi".into(),
+            tags: vec![HEURISTIC_TAG.into()],
+            ..Default::default()
+        };
+        let kept = apply(
+            vec![specific, vague, heuristic],
+            &[specific_score, vague_score, QualityScore::default()],
+            0.4,
+        );
+        let titles: Vec<&str> = kept.iter().map(|n| n.title.as_str()).collect();
+        assert_eq!(
+            titles,
+            vec![
+                "Concentrated Liquidity Migration on Orca and Raydium",
+                "defi"
+            ]
+        );
+        assert!((kept[0].quality.unwrap() - 0.9).abs() < 1e-9);
+        assert_eq!(kept[1].quality, None);
+    }
+
+    #[test]
+    fn mismatched_repos_and_critic_lower_the_score() {
+        let narrative = Narrative {
+            title: "Concentrated Liquidity Migration on Orca".into(),
+            summary: "Whirlpools TVL doubled to 120M USD.".into(),
+            active_repos: vec!["orca-so/whirlpools".into()],
+            mismatched_repos: vec!["solana-labs/wallet-adapter".into()],
+            ..Default::default()
+        };
+        let mut scores = vec![score(&narrative, 0, &signals())];
+        assert_eq!(scores[0].repo_relevance, 0.5);
+        assert_eq!(scores[0].metrics, 0.5);
+        let deterministic = scores[0].total();
+
+        let ratings: CriticResponse = serde_json::from_str(
+            r#"{"ratings": [{"index": 1, "score": 0.1}, {"index": 9, "score": 1}]}"#,
+        )
+        .unwrap();
+        apply_ratings(&ratings.ratings, &mut scores);
+        assert_eq!(scores[0].critic, Some(0.1));
+        assert!((scores[0].total() - (deterministic + 0.1) / 2.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,356 @@
+//! Post-synthesis check that each narrative's `active_repos` actually relate to
+//! it. The LLM regularly attaches an unrelated SDK or tooling repo to a DeFi
+//! narrative; its findings would then be scored against that narrative.
+//!
+//! A repo passes when its GitHub description, topics or name share a keyword
+//! or a sector (DeFi, staking, NFT, ...) with the narrative's title, summary
+//! and tags. Repos without metadata can't be checked and always pass.
+
+use super::Narrative;
+use super::github::DiscoveredRepo;
+use crate::config::RepoVerification;
+use std::collections::HashSet;
+use tracing::{info, warn};
+
+/// Words too generic to say two texts are about the same thing.
+const STOPWORDS: &[&str] = &[
+    "solana",
+    "protocol",
+    "protocols",
+    "program",
+    "programs",
+    "project",
+    "projects",
+    "growth",
+    "growing",
+    "ecosystem",
+    "based",
+    "built",
+    "with",
+    "from",
+    "into",
+    "this",
+    "that",
+    "their",
+    "these",
+    "across",
+    "more",
+    "new",
+    "rust",
+    "anchor",
+    "onchain",
+    "chain",
+    "network",
+    "open",
+    "source",
+    "users",
+    "user",
+    "activity",
+    "increasing",
+    "emerging",
+    "rapid",
+    "rapidly",
+    "adoption",
+    "platform",
+    "official",
+    "repository",
+    "library",
+    "the",
+    "and",
+    "for",
+];
+
+/// Sector families: a narrative and repo that both hit the same family are
+/// related even without a shared word ("AMM" repo, "DEX volume" narrative).
+const SECTORS: &[&[&str]] = &[
+    &[
+        "defi",
+        "dex",
+        "amm",
+        "swap",
+        "lending",
+        "borrow",
+        "yield",
+        "perp",
+        "perps",
+        "perpetual",
+        "liquidity",
+        "orderbook",
+        "clmm",
+        "vault",
+        "margin",
+        "derivatives",
+    ],
+    &[
+        "staking",
+        "stake",
+        "lst",
+        "restaking",
+        "validator",
+        "liquid",
+    ],
+    &[
+        "nft",
+        "nfts",
+        "metaplex",
+        "collectible",
+        "marketplace",
+        "gaming",
+        "game",
+        "metaverse",
+    ],
+    &[
+        "depin",
+        "iot",
+        "helium",
+        "hivemapper",
+        "physical",
+        "wireless",
+    ],
+    &[
+        "payments",
+        "payment",
+        "payfi",
+        "stablecoin",
+        "stablecoins",
+        "remittance",
+    ],
+    &["ai", "agent", "agents", "llm", "autonomous", "inference"],
+    &[
+        "privacy",
+        "zk",
+        "zero-knowledge",
+        "confidential",
+        "shielded",
+        "mixer",
+    ],
+    &["oracle", "oracles", "price", "feed", "feeds"],
+    &[
+        "bridge",
+        "bridging",
+        "cross-chain",
+        "wormhole",
+        "interoperability",
+    ],
+    &["governance", "dao", "daos", "realms", "voting"],
+    &["memecoin", "memecoins", "launchpad", "bonding", "pump"],
+];
+
+/// Check every narrative's `active_repos` against discovered repo metadata,
+/// recording mismatches and (under `Drop`) removing them. Returns the number
+/// of mismatches found.
+pub fn verify_assignments(
+    narratives: &mut [Narrative],
+    repos: &[DiscoveredRepo],
+    policy: RepoVerification,
+) -> usize {
+    if policy == RepoVerification::Off {
+        return 0;
+    }
+    let mut total = 0;
+    for narrative in narratives.iter_mut() {
+        let narrative_text = format!(
+            "{} {} {}",
+            narrative.title,
+            narrative.summary,
+            narrative.tags.join(" ")
+        );
+        let keywords = keywords(&narrative_text);
+        let mismatched: Vec<String> = narrative
+            .active_repos
+            .iter()
+            .filter(|assigned| {
+                find_repo(repos, assigned).is_some_and(|meta| !related(&keywords, meta))
+            })
+            .cloned()
+            .collect();
+        if mismatched.is_empty() {
+            continue;
+        }
+        warn!(
+            narrative = %narrative.title,
+            repos = ?mismatched,
+            ?policy,
+            "repos unrelated to narrative"
+        );
+        if policy == RepoVerification::Drop {
+            narrative.active_repos.retain(|r| !mismatched.contains(r));
+        }
+        total += mismatched.len();
+        narrative.mismatched_repos = mismatched;
+    }
+    if total > 0 {
+        info!(count = total, "narrative repo verification done");
+    }
+    total
+}
+
+/// Metadata for an assigned repo (`owner/name`, or a bare `name`).
+fn find_repo<'a>(repos: &'a [DiscoveredRepo], assigned: &str) -> Option<&'a DiscoveredRepo> {
+    let assigned = assigned.trim().to_lowercase();
+    repos.iter().find(|r| {
+        let full = r.name.to_lowercase();
+        full == assigned || crate::paths::target_name(&full) == assigned
+    })
+}
+
+/// Whether a repo shares a keyword or a sector with the narrative. Repos with
+/// no description and no topics are unverifiable and pass.
+pub(super) fn related(narrative: &HashSet<String>, repo: &DiscoveredRepo) -> bool {
+    if repo.description.trim().is_empty() && repo.topics.is_empty() {
+        return true;
+    }
+    let repo_text = format!(
+        "{} {} {}",
+        repo.description,
+        repo.topics.join(" "),
+        crate::paths::target_name(&repo.name)
+    );
+    let repo_keywords = keywords(&repo_text);
+    let stems = |words: &HashSet<String>| -> HashSet<String> {
+        words
+            .iter()
+            .filter(|w| w.len() >= 4)
+            .map(|w| stem(w))
+            .collect()
+    };
+    if !stems(narrative).is_disjoint(&stems(&repo_keywords)) {
+        return true;
+    }
+    SECTORS.iter().any(|family| {
+        let hits = |words: &HashSet<String>| words.iter().any(|w| family.contains(&w.as_str()));
+        hits(narrative) && hits(&repo_keywords)
+    })
+}
+
+/// Lowercase words of `text` (letters, digits, `-`), minus stopwords.
+/// `owner/repo-name` style names split on `-` and `_` too.
+pub(super) fn keywords(text: &str) -> HashSet<String> {
+    let lower = text.to_lowercase();
+    let mut words: HashSet<String> = HashSet::new();
+    for token in lower.split(|c: char| !(c.is_alphanumeric() || c == '-')) {
+        let token = token.trim_matches('-');
+        if token.is_empty() {
+            continue;
+        }
+        words.insert(token.to_string());
+        if token.contains('-') {
+            words.extend(token.split('-').filter(|p| !p.is_empty()).map(String::from));
+        }
+    }
+    words.retain(|w| w.len() >= 2 && !STOPWORDS.contains(&w.as_str()));
+    words
+}
+
+/// Crude stem: the first five characters, so "lending"/"lender" and
+/// "staking"/"stakes" meet.
+pub(super) fn stem(word: &str) -> String {
+    word.chars().take(5).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str, description: &str, topics: &[&str]) -> DiscoveredRepo {
+        DiscoveredRepo {
+            name: name.into(),
+            language: "Rust".into(),
+            stars: 10,
+            description: description.into(),
+            topics: topics.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    fn narrative(title: &str, summary: &str, repos: &[&str]) -> Narrative {
+        Narrative {
+            title: title.into(),
+            summary: summary.into(),
+            active_repos: repos.iter().map(|r| r.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn discovered() -> Vec<DiscoveredRepo> {
+        vec![
+            repo(
+                "raydium-io/raydium-cp-swap",
+                "Constant product AMM",
+                &["amm", "solana"],
+            ),
+            repo(
+                "acme/ts-sdk",
+                "TypeScript SDK and CLI tooling for Solana developers",
+                &["sdk"],
+            ),
+            repo("kamino/klend", "Kamino lending program", &[]),
+            repo("quiet/no-meta", "", &[]),
+        ]
+    }
+
+    #[test]
+    fn unrelated_sdk_dropped_from_defi_narrative() {
+        let mut narratives = vec![narrative(
+            "DEX Volume Surge",
+            "Swap volume on Solana DEXes keeps climbing as lending rates rise.",
+            &[
+                "raydium-io/raydium-cp-swap",
+                "acme/ts-sdk",
+                "klend",
+                "quiet/no-meta",
+            ],
+        )];
+        let n = verify_assignments(&mut narratives, &discovered(), RepoVerification::Drop);
+        assert_eq!(n, 1);
+        assert_eq!(narratives[0].mismatched_repos, vec!["acme/ts-sdk"]);
+        assert_eq!(
+            narratives[0].active_repos,
+            vec!["raydium-io/raydium-cp-swap", "klend", "quiet/no-meta"]
+        );
+    }
+
+    #[test]
+    fn flag_keeps_repo_and_off_skips_check() {
+        let make = || {
+            vec![narrative(
+                "Lending Markets",
+                "Borrowing demand grows.",
+                &["acme/ts-sdk"],
+            )]
+        };
+        let mut flagged = make();
+        verify_assignments(&mut flagged, &discovered(), RepoVerification::Flag);
+        assert_eq!(flagged[0].active_repos, vec!["acme/ts-sdk"]);
+        assert_eq!(flagged[0].mismatched_repos, vec!["acme/ts-sdk"]);
+
+        let mut off = make();
+        assert_eq!(
+            verify_assignments(&mut off, &discovered(), RepoVerification::Off),
+            0
+        );
+        assert!(off[0].mismatched_repos.is_empty());
+    }
+
+    #[test]
+    fn unknown_repos_pass() {
+        let mut narratives = vec![narrative("NFT Markets", "Collectibles", &["someone/else"])];
+        assert_eq!(
+            verify_assignments(&mut narratives, &discovered(), RepoVerification::Drop),
+            0
+        );
+        assert_eq!(narratives[0].active_repos, vec!["someone/else"]);
+    }
+
+    #[test]
+    fn developer_tooling_narrative_keeps_sdk() {
+        let mut narratives = vec![narrative(
+            "Developer Tooling",
+            "New SDK releases and TypeScript clients.",
+            &["acme/ts-sdk"],
+        )];
+        assert_eq!(
+            verify_assignments(&mut narratives, &discovered(), RepoVerification::Drop),
+            0
+        );
+    }
+}
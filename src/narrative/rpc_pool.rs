@@ -0,0 +1,374 @@
+//! Solana JSON-RPC over a pool of endpoints. Each request goes to the first
+//! endpoint, in config order, that is neither cooling down nor over its rate
+//! budget. An endpoint that rate limits, times out, fails at the HTTP level
+//! or reports itself behind cools down with exponential backoff while the
+//! others take its traffic.
+
+use crate::config::SolanaConfig;
+use crate::error::{Error, Result};
+use crate::http::HttpClient;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// JSON-RPC error code of a node that is behind or otherwise unhealthy.
+const NODE_UNHEALTHY: i64 = -32005;
+/// Cool-down after an endpoint's first consecutive failure, doubled for
+/// each further one.
+const BASE_COOLDOWN: Duration = Duration::from_secs(2);
+const MAX_COOLDOWN: Duration = Duration::from_secs(120);
+
+#[derive(Serialize)]
+struct RpcRequest<'a> {
+    jsonrpc: &'a str,
+    id: u64,
+    method: &'a str,
+    params: serde_json::Value,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    #[serde(default)]
+    code: i64,
+    message: String,
+}
+
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+    http: HttpClient,
+}
+
+struct Endpoint {
+    name: String,
+    url: String,
+    /// Minimum spacing between requests, from `requests_per_second`.
+    interval: Option<Duration>,
+    health: Mutex<Health>,
+}
+
+#[derive(Default)]
+struct Health {
+    /// Earliest time the rate budget allows another request.
+    next_slot: Option<Instant>,
+    cooldown_until: Option<Instant>,
+    /// Consecutive failures, reset by a success.
+    failures: u32,
+}
+
+impl Health {
+    fn ready_at(&self) -> Option<Instant> {
+        self.next_slot.max(self.cooldown_until)
+    }
+}
+
+impl RpcPool {
+    /// `[[solana.rpc_endpoints]]`, or `rpc_url` alone when none are listed.
+    /// With more than one endpoint a failing request moves on to the next
+    /// endpoint instead of retrying the same one.
+    pub fn new(config: &SolanaConfig, http: &HttpClient) -> Self {
+        let endpoints: Vec<Endpoint> = if config.rpc_endpoints.is_empty() {
+            vec![Endpoint::new(None, &config.rpc_url, None)]
+        } else {
+            config
+                .rpc_endpoints
+                .iter()
+                .map(|e| Endpoint::new(e.name.as_deref(), &e.url, e.requests_per_second))
+                .collect()
+        };
+        let http = if endpoints.len() > 1 {
+            http.clone().with_max_retries(0)
+        } else {
+            http.clone()
+        };
+        Self { endpoints, http }
+    }
+
+    /// Call `method` on the first available endpoint, rotating through the
+    /// pool on endpoint failures. Errors the node returns for the request
+    /// itself are not retried.
+    pub async fn call<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T> {
+        let request = RpcRequest {
+            jsonrpc: "2.0",
+            id: 1,
+            method,
+            params,
+        };
+        let body =
+            serde_json::to_string(&request).map_err(|e| Error::parse(format!("serialize: {e}")))?;
+
+        let mut last_error = Error::http("no RPC endpoint attempted");
+        for _ in 0..self.attempts() {
+            let index = loop {
+                match self.acquire(Instant::now()) {
+                    Ok(index) => break index,
+                    Err(until) => {
+                        tokio::time::sleep_until(tokio::time::Instant::from_std(until)).await
+                    }
+                }
+            };
+            let endpoint = &self.endpoints[index];
+            match self.send(endpoint, &body).await {
+                Ok(result) => {
+                    self.succeed(index);
+                    return Ok(result);
+                }
+                Err(e) if is_endpoint_fault(&e) => {
+                    warn!(endpoint = %endpoint.name, method, error = %e, "RPC endpoint failed");
+                    self.fail(index, retry_after(&e), Instant::now());
+                    last_error = e;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Err(last_error)
+    }
+
+    /// Probe every endpoint with `getHealth` and cool down the ones that
+    /// fail, so the first real requests skip them. A single endpoint has
+    /// nothing to rotate to and is not probed.
+    pub async fn check_health(&self) {
+        if self.endpoints.len() < 2 {
+            return;
+        }
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"getHealth"}"#;
+        let results = futures_util::future::join_all(
+            self.endpoints
+                .iter()
+                .map(|endpoint| self.send::<String>(endpoint, body)),
+        )
+        .await;
+        let now = Instant::now();
+        let mut healthy = 0;
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(_) => {
+                    self.succeed(index);
+                    healthy += 1;
+                }
+                Err(e) => {
+                    warn!(endpoint = %self.endpoints[index].name, error = %e, "RPC endpoint unhealthy");
+                    self.fail(index, retry_after(&e), now);
+                }
+            }
+        }
+        info!(
+            healthy,
+            total = self.endpoints.len(),
+            "RPC endpoints checked"
+        );
+    }
+
+    /// Twice round the pool, so an endpoint that failed once gets a second
+    /// chance after its cool-down. A single endpoint relies on the HTTP
+    /// client's own retries.
+    fn attempts(&self) -> usize {
+        match self.endpoints.len() {
+            1 => 1,
+            n => n * 2,
+        }
+    }
+
+    /// Reserve a request on the first endpoint that can take one now, or
+    /// return when the earliest one will be able to.
+    fn acquire(&self, now: Instant) -> std::result::Result<usize, Instant> {
+        let mut earliest: Option<Instant> = None;
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            let mut health = endpoint.health.lock().unwrap_or_else(|e| e.into_inner());
+            match health.ready_at().filter(|&at| at > now) {
+                None => {
+                    health.next_slot = endpoint.interval.map(|interval| now + interval);
+                    return Ok(index);
+                }
+                Some(at) => earliest = Some(earliest.map_or(at, |e| e.min(at))),
+            }
+        }
+        Err(earliest.expect("pool has at least one endpoint"))
+    }
+
+    fn succeed(&self, index: usize) {
+        let mut health = self.endpoints[index]
+            .health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        health.failures = 0;
+        health.cooldown_until = None;
+    }
+
+    /// Cool the endpoint down for `retry_after`, or for the backoff of its
+    /// consecutive failures. A lone endpoint never cools down: there is no
+    /// other to send to, and the HTTP client already backed off.
+    fn fail(&self, index: usize, retry_after: Option<Duration>, now: Instant) {
+        if self.endpoints.len() < 2 {
+            return;
+        }
+        let mut health = self.endpoints[index]
+            .health
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        health.failures += 1;
+        let backoff = BASE_COOLDOWN.saturating_mul(1 << (health.failures - 1).min(8));
+        health.cooldown_until = Some(now + retry_after.unwrap_or(backoff).min(MAX_COOLDOWN));
+    }
+
+    async fn send<T: DeserializeOwned>(&self, endpoint: &Endpoint, body: &str) -> Result<T> {
+        let resp_text = self.http.post_json_raw(&endpoint.url, body, &[]).await?;
+        let resp: RpcResponse<T> = serde_json::from_str(&resp_text)
+            .map_err(|e| Error::parse(format!("parse RPC: {e}")))?;
+
+        if let Some(err) = resp.error {
+            if err.code == NODE_UNHEALTHY {
+                // A lagging node is an endpoint failure, like a 503.
+                return Err(Error::api_with_status(&endpoint.name, err.message, 503));
+            }
+            return Err(Error::api("solana-rpc", err.message));
+        }
+        resp.result
+            .ok_or_else(|| Error::parse("RPC response missing result"))
+    }
+}
+
+impl Endpoint {
+    fn new(name: Option<&str>, url: &str, requests_per_second: Option<f64>) -> Self {
+        let name = name.map(String::from).unwrap_or_else(|| {
+            url.split("//")
+                .nth(1)
+                .and_then(|s| s.split(['/', '?']).next())
+                .unwrap_or(url)
+                .to_string()
+        });
+        Self {
+            name,
+            url: url.to_string(),
+            interval: requests_per_second
+                .filter(|rps| *rps > 0.0)
+                .map(|rps| Duration::from_secs_f64(1.0 / rps)),
+            health: Mutex::default(),
+        }
+    }
+}
+
+/// Whether `e` is the endpoint's fault rather than the request's: rate
+/// limits, transport failures (timeouts, refused connections) and non-2xx
+/// responses. JSON-RPC errors for the request come back as 200s.
+fn is_endpoint_fault(e: &Error) -> bool {
+    matches!(
+        e,
+        Error::RateLimit { .. }
+            | Error::Http(_)
+            | Error::Api {
+                status_code: Some(_),
+                ..
+            }
+    )
+}
+
+fn retry_after(e: &Error) -> Option<Duration> {
+    match e {
+        Error::RateLimit {
+            retry_after_secs: Some(secs),
+            ..
+        } => Some(Duration::from_secs(*secs)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RpcEndpoint;
+
+    fn pool(endpoints: &[(&str, Option<f64>)]) -> RpcPool {
+        let config = SolanaConfig {
+            rpc_endpoints: endpoints
+                .iter()
+                .map(|&(url, requests_per_second)| RpcEndpoint {
+                    name: None,
+                    url: url.into(),
+                    requests_per_second,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        RpcPool::new(&config, &HttpClient::new("test").unwrap())
+    }
+
+    #[test]
+    fn rotates_away_from_failing_endpoint_until_cooled_down() {
+        let pool = pool(&[
+            ("https://a.example/?api-key=k", None),
+            ("https://b.example", None),
+        ]);
+        assert_eq!(pool.endpoints[0].name, "a.example");
+        let now = Instant::now();
+        assert_eq!(pool.acquire(now), Ok(0));
+
+        pool.fail(0, None, now);
+        assert_eq!(pool.acquire(now), Ok(1));
+        assert_eq!(pool.acquire(now + BASE_COOLDOWN), Ok(0));
+
+        // Consecutive failures back off further; a success resets.
+        pool.fail(0, None, now);
+        assert_eq!(pool.acquire(now + BASE_COOLDOWN), Ok(1));
+        assert_eq!(pool.acquire(now + BASE_COOLDOWN * 2), Ok(0));
+        pool.succeed(0);
+        assert_eq!(pool.acquire(now), Ok(0));
+
+        // Retry-After wins over the backoff.
+        pool.fail(0, Some(Duration::from_secs(30)), now);
+        assert_eq!(pool.acquire(now + Duration::from_secs(29)), Ok(1));
+
+        // Everything cooling down: wait for the first to recover.
+        pool.fail(1, None, now);
+        assert_eq!(pool.acquire(now), Err(now + BASE_COOLDOWN));
+    }
+
+    #[test]
+    fn rate_budget_spills_over_to_next_endpoint() {
+        let pool = pool(&[
+            ("https://a.example", Some(2.0)),
+            ("https://b.example", None),
+        ]);
+        let now = Instant::now();
+        assert_eq!(pool.acquire(now), Ok(0));
+        assert_eq!(pool.acquire(now), Ok(1));
+        assert_eq!(pool.acquire(now + Duration::from_millis(500)), Ok(0));
+
+        let single = self::pool(&[("https://a.example", Some(4.0))]);
+        assert_eq!(single.acquire(now), Ok(0));
+        assert_eq!(single.acquire(now), Err(now + Duration::from_millis(250)));
+        // A lone endpoint is never benched.
+        single.fail(0, None, now);
+        assert_eq!(single.acquire(now + Duration::from_millis(250)), Ok(0));
+    }
+
+    #[test]
+    fn classifies_endpoint_faults() {
+        assert!(is_endpoint_fault(&Error::RateLimit {
+            platform: "rpc".into(),
+            retry_after_secs: Some(3),
+        }));
+        assert!(is_endpoint_fault(&Error::http("operation timed out")));
+        assert!(is_endpoint_fault(&Error::api_with_status(
+            "rpc",
+            "bad gateway",
+            502
+        )));
+        assert!(!is_endpoint_fault(&Error::api(
+            "solana-rpc",
+            "Invalid param"
+        )));
+        assert!(!is_endpoint_fault(&Error::parse("parse RPC")));
+    }
+}
@@ -0,0 +1,188 @@
+//! Persisted signal sets, so the evidence behind a narrative can be checked
+//! after the fact (`solguard signals show`) without re-running collection.
+//!
+//! Every narrative pipeline run writes the signals it collected to
+//! `~/.solguard/signals/{timestamp}.json`, using the same timestamp format as
+//! the run history.
+
+pub use super::types::{Metric, Signal, SignalSource};
+use anyhow::{Context, Result, bail};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// The signals collected by one narrative pipeline run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalSet {
+    pub timestamp: String,
+    pub signals: Vec<Signal>,
+}
+
+impl SignalSet {
+    pub fn new(signals: Vec<Signal>) -> Self {
+        Self {
+            timestamp: Utc::now().format("%Y%m%d_%H%M%S").to_string(),
+            signals,
+        }
+    }
+
+    /// Write to `dir/{timestamp}.json`.
+    pub fn save_to(&self, dir: &Path) -> Result<PathBuf> {
+        std::fs::create_dir_all(dir)?;
+        let path = dir.join(format!("{}.json", self.timestamp));
+        std::fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        info!(path = %path.display(), signals = self.signals.len(), "saved signals");
+        Ok(path)
+    }
+
+    /// Human-readable listing, optionally limited to one source.
+    pub fn render(&self, source: Option<SignalSource>) -> String {
+        let mut out = String::new();
+        let shown: Vec<&Signal> = self
+            .signals
+            .iter()
+            .filter(|s| source.is_none_or(|src| s.source == src))
+            .collect();
+        let _ = writeln!(
+            out,
+            "run {}: {} of {} signals",
+            self.timestamp,
+            shown.len(),
+            self.signals.len()
+        );
+        for s in shown {
+            let _ = writeln!(out, "\n[{}] {} ({})", s.source, s.title, s.category);
+            if let Some(lang) = &s.language {
+                let _ = writeln!(out, "  language: {lang}");
+            }
+            if let Some(url) = &s.url {
+                let _ = writeln!(out, "  {url}");
+            }
+            let _ = writeln!(
+                out,
+                "  collected {}",
+                s.timestamp.format("%Y-%m-%d %H:%M UTC")
+            );
+            for m in &s.metrics {
+                let _ = writeln!(out, "  - {m}");
+            }
+            if !s.description.is_empty() {
+                let _ = writeln!(out, "  {}", s.description.replace('\n', "\n  "));
+            }
+        }
+        out
+    }
+}
+
+/// Default location of saved signal sets.
+pub fn signals_dir() -> PathBuf {
+    crate::memory::solguard_dir().join("signals")
+}
+
+/// Timestamps of the signal sets in `dir`, oldest first.
+pub fn list_runs(dir: &Path) -> Result<Vec<String>> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut runs: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .filter_map(|p| p.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .collect();
+    runs.sort();
+    Ok(runs)
+}
+
+/// Load the newest run whose timestamp starts with `run` (`20260301`
+/// matches every run that day), or the newest run overall.
+pub fn load(dir: &Path, run: Option<&str>) -> Result<SignalSet> {
+    let runs = list_runs(dir)?;
+    let Some(timestamp) = runs
+        .iter()
+        .rev()
+        .find(|t| run.is_none_or(|r| t.starts_with(r)))
+    else {
+        match run {
+            Some(r) => bail!("no saved signals for run `{r}` in {}", dir.display()),
+            None => bail!(
+                "no saved signals in {}: run `narratives` or `run` first",
+                dir.display()
+            ),
+        }
+    };
+    let path = dir.join(format!("{timestamp}.json"));
+    let json =
+        std::fs::read_to_string(&path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("parsing {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn signal(source: SignalSource, title: &str) -> Signal {
+        Signal {
+            source,
+            category: "Lending".into(),
+            title: title.into(),
+            description: "Stars doubled in a week".into(),
+            metrics: vec![Metric {
+                name: "stars_7d".into(),
+                value: 120.0,
+                unit: String::new(),
+            }],
+            url: Some(format!("https://github.com/acme/{title}")),
+            timestamp: Utc.with_ymd_and_hms(2026, 3, 1, 12, 0, 0).unwrap(),
+            language: None,
+        }
+    }
+
+    #[test]
+    fn saves_lists_and_loads_by_prefix() {
+        let dir = std::env::temp_dir().join("solguard-signal-store");
+        let _ = std::fs::remove_dir_all(&dir);
+        for (ts, title) in [("20260301_120000", "old"), ("20260302_090000", "new")] {
+            SignalSet {
+                timestamp: ts.into(),
+                signals: vec![signal(SignalSource::GitHub, title)],
+            }
+            .save_to(&dir)
+            .unwrap();
+        }
+        assert_eq!(
+            list_runs(&dir).unwrap(),
+            vec!["20260301_120000", "20260302_090000"]
+        );
+        assert_eq!(load(&dir, None).unwrap().signals[0].title, "new");
+        assert_eq!(
+            load(&dir, Some("20260301")).unwrap().signals[0].title,
+            "old"
+        );
+        let err = load(&dir, Some("2025")).unwrap_err();
+        assert!(err.to_string().contains("no saved signals for run `2025`"));
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(load(&dir, None).is_err());
+    }
+
+    #[test]
+    fn render_filters_by_source() {
+        let set = SignalSet {
+            timestamp: "20260301_120000".into(),
+            signals: vec![
+                signal(SignalSource::GitHub, "vault"),
+                signal(SignalSource::DeFiLlama, "tvl"),
+            ],
+        };
+        let text = set.render(Some(SignalSource::GitHub));
+        assert!(text.starts_with("run 20260301_120000: 1 of 2 signals"));
+        assert!(text.contains("[GitHub] vault (Lending)"));
+        assert!(text.contains("https://github.com/acme/vault"));
+        assert!(text.contains("- stars_7d: 120.0"));
+        assert!(!text.contains("tvl"));
+        assert!(set.render(None).contains("[DeFiLlama] tvl"));
+    }
+}
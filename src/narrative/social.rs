@@ -1,17 +1,56 @@
+//! Blog and news scraping (`[social]`).
+//!
+//! Each source becomes one signal listing its recent article titles. Sources
+//! that aren't in English are tagged with their language and, with
+//! `translate` on, their titles are translated to English first, so the
+//! Solana keyword filter and the narrative LLM see them on equal terms with
+//! English sources.
+
 use super::types::{Metric, Signal, SignalSource};
-use crate::config::SocialConfig;
-use crate::error::Result;
+use crate::config::{SocialConfig, SocialSource};
+use crate::error::{Error, Result};
 use crate::http::HttpClient;
+use crate::llm::LlmClient;
 use chrono::Utc;
 use scraper::{Html, Selector};
+use serde::Deserialize;
 use tracing::{info, warn};
 
-pub async fn collect(config: &SocialConfig, http: &HttpClient) -> Result<Vec<Signal>> {
+const TRANSLATE_PROMPT: &str = "You translate Solana and crypto article titles to English. \
+Keep project, token and protocol names as they are. \
+Return JSON: {\"translations\": [\"...\"]}, one entry per input title, in the same order.";
+
+/// Scrape every configured source. `translator` translates non-English
+/// titles; pass `None` to keep them as published.
+pub async fn collect(
+    config: &SocialConfig,
+    http: &HttpClient,
+    translator: Option<&LlmClient>,
+) -> Result<Vec<Signal>> {
     let mut signals = Vec::new();
 
     for source in &config.sources {
-        match scrape_source(http, &source.name, &source.url).await {
-            Ok(mut source_signals) => signals.append(&mut source_signals),
+        match scrape_titles(http, &source.url).await {
+            Ok(titles) => {
+                let language = source_language(source, &titles);
+                let (titles, translated) = match (language, translator) {
+                    (Some(lang), Some(llm)) => match translate(llm, lang, &titles).await {
+                        Ok(t) => (t, true),
+                        Err(e) => {
+                            warn!(source = %source.name, language = lang, error = %e, "translation failed, keeping original titles");
+                            (titles, false)
+                        }
+                    },
+                    _ => (titles, false),
+                };
+                signals.extend(build_signal(
+                    &source.name,
+                    &source.url,
+                    titles,
+                    language,
+                    translated,
+                ));
+            }
             Err(e) => {
                 warn!(source = %source.name, url = %source.url, error = %e, "failed to scrape, skipping");
             }
@@ -22,7 +61,8 @@ pub async fn collect(config: &SocialConfig, http: &HttpClient) -> Result<Vec<Sig
     Ok(signals)
 }
 
-async fn scrape_source(http: &HttpClient, name: &str, url: &str) -> Result<Vec<Signal>> {
+/// Article titles on a blog index page, deduplicated.
+async fn scrape_titles(http: &HttpClient, url: &str) -> Result<Vec<String>> {
     let html_text = http.get_text(url).await?;
     let document = Html::parse_document(&html_text);
 
@@ -42,8 +82,7 @@ async fn scrape_source(http: &HttpClient, name: &str, url: &str) -> Result<Vec<S
         if let Ok(selector) = Selector::parse(sel_str) {
             for element in document.select(&selector) {
                 let title = element.text().collect::<String>().trim().to_string();
-                let _href = element.value().attr("href").unwrap_or("").to_string();
-                if !title.is_empty() && title.len() > 5 {
+                if !title.is_empty() && title.chars().count() > 5 {
                     articles.push(title);
                 }
             }
@@ -55,6 +94,86 @@ async fn scrape_source(http: &HttpClient, name: &str, url: &str) -> Result<Vec<S
 
     articles.sort();
     articles.dedup();
+    Ok(articles)
+}
+
+/// The configured language, else the one detected from `titles`; `None`
+/// for English.
+fn source_language<'a>(source: &'a SocialSource, titles: &[String]) -> Option<&'a str> {
+    let lang = match &source.language {
+        Some(lang) => lang.as_str(),
+        None => detect_language(titles),
+    };
+    (!lang.eq_ignore_ascii_case("en")).then_some(lang)
+}
+
+/// Language of `texts` by the script most of their letters are in. Latin
+/// script counts as English; other Latin-script languages must be set per
+/// source.
+pub fn detect_language(texts: &[String]) -> &'static str {
+    let (mut latin, mut han, mut kana, mut hangul, mut cyrillic) = (0, 0, 0, 0, 0);
+    for c in texts.iter().flat_map(|t| t.chars()) {
+        match c {
+            'a'..='z' | 'A'..='Z' => latin += 1,
+            '\u{3040}'..='\u{30ff}' => kana += 1,
+            '\u{ac00}'..='\u{d7af}' | '\u{1100}'..='\u{11ff}' => hangul += 1,
+            '\u{4e00}'..='\u{9fff}' | '\u{3400}'..='\u{4dbf}' => han += 1,
+            '\u{0400}'..='\u{04ff}' => cyrillic += 1,
+            _ => {}
+        }
+    }
+    // Japanese mixes kana into Han text; any real share of kana decides it.
+    let ja = if kana > 0 && kana * 5 >= han {
+        kana + han
+    } else {
+        0
+    };
+    let candidates = [("ja", ja), ("ko", hangul), ("zh", han), ("ru", cyrillic)];
+    match candidates.iter().max_by_key(|(_, n)| *n) {
+        // Latin words (project names, "Solana") are common in CJK titles,
+        // so a script needs only a third of the letters to win.
+        Some((lang, n)) if *n > 0 && n * 2 >= latin => lang,
+        _ => "en",
+    }
+}
+
+#[derive(Deserialize)]
+struct Translations {
+    translations: Vec<String>,
+}
+
+async fn translate(llm: &LlmClient, language: &str, titles: &[String]) -> Result<Vec<String>> {
+    if titles.is_empty() {
+        return Ok(Vec::new());
+    }
+    let input = serde_json::to_string(titles).map_err(|e| Error::parse(e.to_string()))?;
+    let out: Translations = llm
+        .complete_json(
+            TRANSLATE_PROMPT,
+            &format!("Source language: {language}\nTitles: {input}"),
+        )
+        .await?;
+    if out.translations.len() != titles.len() {
+        return Err(Error::parse(format!(
+            "expected {} translations, got {}",
+            titles.len(),
+            out.translations.len()
+        )));
+    }
+    Ok(out.translations)
+}
+
+/// One signal summarizing a source's articles; `None` without any.
+fn build_signal(
+    name: &str,
+    url: &str,
+    articles: Vec<String>,
+    language: Option<&str>,
+    translated: bool,
+) -> Option<Signal> {
+    if articles.is_empty() {
+        return None;
+    }
 
     let solana_articles: Vec<String> = articles
         .iter()
@@ -76,25 +195,26 @@ async fn scrape_source(http: &HttpClient, name: &str, url: &str) -> Result<Vec<S
         .collect();
     let solana_count = solana_articles.len();
 
-    if articles.is_empty() {
-        return Ok(Vec::new());
-    }
-
     let source = if !solana_articles.is_empty() {
         &solana_articles
     } else {
         &articles
     };
     let titles: Vec<String> = source.iter().take(10).cloned().collect();
+    let note = match language {
+        Some(lang) if translated => format!(" (translated from {lang})"),
+        Some(lang) => format!(" (in {lang})"),
+        None => String::new(),
+    };
 
-    Ok(vec![Signal {
+    Some(Signal {
         source: SignalSource::Social,
         category: format!("Blog: {name}"),
         title: format!(
             "{name}: {} recent articles ({solana_count} Solana-related)",
             articles.len()
         ),
-        description: format!("Recent topics: {}", titles.join("; ")),
+        description: format!("Recent topics{note}: {}", titles.join("; ")),
         metrics: vec![
             Metric {
                 name: "total_articles".into(),
@@ -109,5 +229,98 @@ async fn scrape_source(http: &HttpClient, name: &str, url: &str) -> Result<Vec<S
         ],
         url: Some(url.to_string()),
         timestamp: Utc::now(),
-    }])
+        language: language.map(String::from),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn titles(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_language_by_script() {
+        assert_eq!(
+            detect_language(&titles(&["Solana DeFi TVL hits new high"])),
+            "en"
+        );
+        assert_eq!(
+            detect_language(&titles(&[
+                "Solana 生态 DeFi 锁仓量创新高",
+                "Jupiter 推出新功能"
+            ])),
+            "zh"
+        );
+        assert_eq!(
+            detect_language(&titles(&[
+                "솔라나 생태계 디파이 성장",
+                "Jupiter 신규 기능 출시"
+            ])),
+            "ko"
+        );
+        assert_eq!(
+            detect_language(&titles(&["ソラナのステーキングが急増"])),
+            "ja"
+        );
+        assert_eq!(detect_language(&[]), "en");
+    }
+
+    #[test]
+    fn source_language_prefers_config() {
+        let source = SocialSource {
+            name: "Blog".into(),
+            url: "https://example.com".into(),
+            source_type: "blog".into(),
+            language: Some("es".into()),
+        };
+        assert_eq!(source_language(&source, &titles(&["Hola"])), Some("es"));
+        let detected = SocialSource {
+            language: None,
+            ..source
+        };
+        assert_eq!(source_language(&detected, &titles(&["Hello"])), None);
+        assert_eq!(
+            source_language(&detected, &titles(&["索拉纳质押激增"])),
+            Some("zh")
+        );
+    }
+
+    #[test]
+    fn translated_signal_is_tagged_with_original_language() {
+        let signal = build_signal(
+            "Odaily",
+            "https://example.com",
+            titles(&["Solana staking surges", "Exchange listings this week"]),
+            Some("zh"),
+            true,
+        )
+        .unwrap();
+        assert_eq!(signal.language.as_deref(), Some("zh"));
+        assert_eq!(signal.title, "Odaily: 2 recent articles (1 Solana-related)");
+        assert_eq!(
+            signal.description,
+            "Recent topics (translated from zh): Solana staking surges"
+        );
+        let json = serde_json::to_value(&signal).unwrap();
+        assert_eq!(json["language"], "zh");
+
+        let english = build_signal(
+            "Helius",
+            "https://example.com",
+            titles(&["Solana"]),
+            None,
+            false,
+        )
+        .unwrap();
+        assert!(
+            serde_json::to_value(&english)
+                .unwrap()
+                .get("language")
+                .is_none()
+        );
+        assert!(build_signal("Empty", "https://example.com", Vec::new(), None, false).is_none());
+    }
 }
@@ -1,30 +1,11 @@
+use super::rpc_pool::RpcPool;
 use super::types::{Metric, Signal, SignalSource};
 use crate::config::SolanaConfig;
-use crate::error::{Error, Result};
-use crate::http::HttpClient;
+use crate::error::Result;
 use chrono::Utc;
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use tracing::info;
 
-#[derive(Serialize)]
-struct RpcRequest<'a> {
-    jsonrpc: &'a str,
-    id: u64,
-    method: &'a str,
-    params: serde_json::Value,
-}
-
-#[derive(Deserialize)]
-struct RpcResponse<T> {
-    result: Option<T>,
-    error: Option<RpcError>,
-}
-
-#[derive(Deserialize)]
-struct RpcError {
-    message: String,
-}
-
 #[derive(Deserialize)]
 #[allow(dead_code)]
 struct PerformanceSample {
@@ -64,45 +45,22 @@ struct SupplyValue {
     non_circulating: u64,
 }
 
-pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Signal>> {
+pub async fn collect(config: &SolanaConfig, rpc: &RpcPool) -> Result<Vec<Signal>> {
     let mut signals = Vec::new();
 
     // Recent performance samples (TPS)
-    let perf_samples = rpc_call::<Vec<PerformanceSample>>(
-        &config.rpc_url,
-        http,
-        "getRecentPerformanceSamples",
-        serde_json::json!([10]),
-    )
-    .await?;
-
-    if !perf_samples.is_empty() {
-        let avg_tps: f64 = perf_samples
-            .iter()
-            .map(|s| s.num_transactions as f64 / s.sample_period_secs as f64)
-            .sum::<f64>()
-            / perf_samples.len() as f64;
-
-        let non_vote_tps_values: Vec<f64> = perf_samples
-            .iter()
-            .filter_map(|s| {
-                s.num_non_vote_transactions
-                    .map(|nv| nv as f64 / s.sample_period_secs as f64)
-            })
-            .collect();
-        let avg_non_vote_tps: f64 = if non_vote_tps_values.is_empty() {
-            0.0
-        } else {
-            non_vote_tps_values.iter().sum::<f64>() / non_vote_tps_values.len() as f64
-        };
-
+    if let Some(tps) = network_tps(rpc).await? {
+        let NetworkTps {
+            avg_tps,
+            avg_non_vote_tps,
+            samples,
+        } = tps;
         signals.push(Signal {
             source: SignalSource::SolanaOnchain,
             category: "Network Performance".into(),
             title: format!("Solana TPS: {avg_tps:.0} total, {avg_non_vote_tps:.0} non-vote"),
             description: format!(
-                "Average over {} recent samples. Non-vote TPS indicates real user activity.",
-                perf_samples.len()
+                "Average over {samples} recent samples. Non-vote TPS indicates real user activity."
             ),
             metrics: vec![
                 Metric {
@@ -118,12 +76,12 @@ pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Sig
             ],
             url: Some("https://explorer.solana.com/".into()),
             timestamp: Utc::now(),
+            language: None,
         });
     }
 
     // Epoch info
-    let epoch: EpochInfo =
-        rpc_call(&config.rpc_url, http, "getEpochInfo", serde_json::json!([])).await?;
+    let epoch: EpochInfo = rpc.call("getEpochInfo", serde_json::json!([])).await?;
     let epoch_progress = epoch.slot_index as f64 / epoch.slots_in_epoch as f64 * 100.0;
 
     signals.push(Signal {
@@ -154,11 +112,11 @@ pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Sig
         ],
         url: Some("https://explorer.solana.com/".into()),
         timestamp: Utc::now(),
+        language: None,
     });
 
     // SOL supply
-    let supply: Supply =
-        rpc_call(&config.rpc_url, http, "getSupply", serde_json::json!([])).await?;
+    let supply: Supply = rpc.call("getSupply", serde_json::json!([])).await?;
     let circulating_pct = supply.value.circulating as f64 / supply.value.total as f64 * 100.0;
 
     signals.push(Signal {
@@ -188,11 +146,12 @@ pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Sig
         ],
         url: None,
         timestamp: Utc::now(),
+        language: None,
     });
 
     // Tracked program activity (paginated for real counts)
     for program in &config.tracked_programs {
-        match get_program_activity(&config.rpc_url, http, &program.address).await {
+        match get_program_activity(rpc, &program.address).await {
             Ok(activity) => {
                 let title = if activity.tx_per_hour > 0.0 {
                     let time_str = if activity.time_span_hours < 1.0 {
@@ -236,6 +195,7 @@ pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Sig
                         program.address
                     )),
                     timestamp: Utc::now(),
+                    language: None,
                 });
             }
             Err(e) => {
@@ -251,24 +211,79 @@ pub async fn collect(config: &SolanaConfig, http: &HttpClient) -> Result<Vec<Sig
     Ok(signals)
 }
 
-struct ProgramActivity {
-    tx_count: usize,
-    tx_per_hour: f64,
-    time_span_hours: f64,
+pub(crate) struct NetworkTps {
+    pub avg_tps: f64,
+    pub avg_non_vote_tps: f64,
+    pub samples: usize,
 }
 
-async fn get_program_activity(
-    rpc_url: &str,
-    http: &HttpClient,
-    address: &str,
-) -> Result<ProgramActivity> {
-    #[derive(Deserialize)]
-    struct SigInfo {
-        signature: String,
-        #[serde(rename = "blockTime")]
-        block_time: Option<i64>,
+/// Average TPS over the last ten performance samples; `None` if the node
+/// returned none.
+pub(crate) async fn network_tps(rpc: &RpcPool) -> Result<Option<NetworkTps>> {
+    let perf_samples: Vec<PerformanceSample> = rpc
+        .call("getRecentPerformanceSamples", serde_json::json!([10]))
+        .await?;
+    if perf_samples.is_empty() {
+        return Ok(None);
     }
 
+    let avg_tps: f64 = perf_samples
+        .iter()
+        .map(|s| s.num_transactions as f64 / s.sample_period_secs as f64)
+        .sum::<f64>()
+        / perf_samples.len() as f64;
+
+    let non_vote_tps_values: Vec<f64> = perf_samples
+        .iter()
+        .filter_map(|s| {
+            s.num_non_vote_transactions
+                .map(|nv| nv as f64 / s.sample_period_secs as f64)
+        })
+        .collect();
+    let avg_non_vote_tps: f64 = if non_vote_tps_values.is_empty() {
+        0.0
+    } else {
+        non_vote_tps_values.iter().sum::<f64>() / non_vote_tps_values.len() as f64
+    };
+
+    Ok(Some(NetworkTps {
+        avg_tps,
+        avg_non_vote_tps,
+        samples: perf_samples.len(),
+    }))
+}
+
+pub(crate) struct ProgramActivity {
+    pub tx_count: usize,
+    /// Sampled transactions that failed on chain.
+    pub failed_count: usize,
+    pub tx_per_hour: f64,
+    pub time_span_hours: f64,
+}
+
+impl ProgramActivity {
+    /// Share of sampled transactions that failed; 0 with none sampled.
+    pub fn failure_rate(&self) -> f64 {
+        if self.tx_count == 0 {
+            0.0
+        } else {
+            self.failed_count as f64 / self.tx_count as f64
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SigInfo {
+    signature: String,
+    #[serde(rename = "blockTime")]
+    block_time: Option<i64>,
+    /// Set when the transaction failed.
+    #[serde(default)]
+    err: Option<serde_json::Value>,
+}
+
+/// Activity over the program's most recent signatures (up to 1000).
+pub(crate) async fn get_program_activity(rpc: &RpcPool, address: &str) -> Result<ProgramActivity> {
     let mut all_sigs = Vec::new();
     let mut before: Option<String> = None;
 
@@ -279,7 +294,7 @@ async fn get_program_activity(
             serde_json::json!([address, {"limit": 100}])
         };
 
-        let sigs: Vec<SigInfo> = rpc_call(rpc_url, http, "getSignaturesForAddress", params).await?;
+        let sigs: Vec<SigInfo> = rpc.call("getSignaturesForAddress", params).await?;
         let batch_len = sigs.len();
         if let Some(last) = sigs.last() {
             before = Some(last.signature.clone());
@@ -290,8 +305,13 @@ async fn get_program_activity(
         }
     }
 
-    let tx_count = all_sigs.len();
-    let timestamps: Vec<i64> = all_sigs.iter().filter_map(|s| s.block_time).collect();
+    Ok(summarize(&all_sigs))
+}
+
+/// Signatures come newest first.
+fn summarize(sigs: &[SigInfo]) -> ProgramActivity {
+    let tx_count = sigs.len();
+    let timestamps: Vec<i64> = sigs.iter().filter_map(|s| s.block_time).collect();
     let (tx_per_hour, time_span_hours) = if timestamps.len() >= 2 {
         let newest = timestamps[0];
         let oldest = timestamps[timestamps.len() - 1];
@@ -302,34 +322,33 @@ async fn get_program_activity(
         (0.0, 0.0)
     };
 
-    Ok(ProgramActivity {
+    ProgramActivity {
         tx_count,
+        failed_count: sigs.iter().filter(|s| s.err.is_some()).count(),
         tx_per_hour,
         time_span_hours,
-    })
+    }
 }
 
-async fn rpc_call<T: serde::de::DeserializeOwned>(
-    rpc_url: &str,
-    http: &HttpClient,
-    method: &str,
-    params: serde_json::Value,
-) -> Result<T> {
-    let request = RpcRequest {
-        jsonrpc: "2.0",
-        id: 1,
-        method,
-        params,
-    };
-    let body =
-        serde_json::to_string(&request).map_err(|e| Error::parse(format!("serialize: {e}")))?;
-    let resp_text = http.post_json_raw(rpc_url, &body, &[]).await?;
-    let resp: RpcResponse<T> =
-        serde_json::from_str(&resp_text).map_err(|e| Error::parse(format!("parse RPC: {e}")))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if let Some(err) = resp.error {
-        return Err(Error::api("solana-rpc", err.message));
+    #[test]
+    fn summarizes_rate_and_failures() {
+        let sigs: Vec<SigInfo> = serde_json::from_value(serde_json::json!([
+            {"signature": "c", "blockTime": 7200, "err": null},
+            {"signature": "b", "blockTime": 3600, "err": {"InstructionError": [0, "Custom"]}},
+            {"signature": "a", "blockTime": 0, "err": null},
+            {"signature": "z", "blockTime": 0}
+        ]))
+        .unwrap();
+        let activity = summarize(&sigs);
+        assert_eq!(activity.tx_count, 4);
+        assert_eq!(activity.failed_count, 1);
+        assert_eq!(activity.failure_rate(), 0.25);
+        assert_eq!(activity.time_span_hours, 2.0);
+        assert_eq!(activity.tx_per_hour, 2.0);
+        assert_eq!(summarize(&[]).failure_rate(), 0.0);
     }
-    resp.result
-        .ok_or_else(|| Error::parse("RPC response missing result"))
 }
@@ -26,7 +26,8 @@ For each narrative you identify, provide:
 4. Which signal indices support this narrative (from the input data)
 5. Trend direction: "Accelerating" (growing faster), "Stable" (steady), "Decelerating" (slowing), "Emerging" (too early to tell, but signals present)
 6. Key quantitative metrics that back the narrative
-7. Active repositories — pick ONLY from the "discovered_repos" list in the input. Associate each repo with the narrative it most relates to. A repo can appear in multiple narratives if relevant, but don't assign repos to narratives they're unrelated to. If no discovered repos relate to a narrative, use an empty list.
+7. Tags — 1-4 short lowercase labels for slicing results (e.g. "defi", "staking", "infra", "nft")
+8. Active repositories — pick ONLY from the "discovered_repos" list in the input. Associate each repo with the narrative it most relates to. A repo can appear in multiple narratives if relevant, but don't assign repos to narratives they're unrelated to. If no discovered repos relate to a narrative, use an empty list.
 
 Analysis framework — apply these lenses to each narrative:
 - **Historical context:** Is this trend new, or a continuation/acceleration of something established? Reference prior ecosystem state where the data allows.
@@ -44,6 +45,7 @@ Respond in JSON:
       "supporting_signals": [0, 3, 7],
       "trend": "Accelerating",
       "key_metrics": [{"name": "...", "value": 123.4, "unit": "..."}],
+      "tags": ["defi", "dex"],
       "active_repos": ["owner/repo-name", "owner/other-repo"]
     }
   ]
@@ -76,6 +78,8 @@ struct RawNarrative {
     key_metrics: Vec<RawMetric>,
     #[serde(default)]
     active_repos: Vec<String>,
+    #[serde(default)]
+    tags: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -91,9 +95,9 @@ pub struct SynthesizedNarrative {
     pub summary: String,
     pub confidence: f64,
     pub trend: TrendDirection,
-    #[allow(dead_code)]
     pub key_metrics: Vec<Metric>,
     pub active_repos: Vec<String>,
+    pub tags: Vec<String>,
 }
 
 pub async fn identify_narratives(
@@ -127,6 +131,7 @@ pub async fn identify_narratives(
                 })
                 .collect(),
             active_repos: n.active_repos,
+            tags: n.tags,
         })
         .collect();
 
@@ -10,15 +10,23 @@ pub struct Signal {
     pub metrics: Vec<Metric>,
     pub url: Option<String>,
     pub timestamp: DateTime<Utc>,
+    /// Original language (`zh`, `ko`, ...) of a non-English source; title and
+    /// description are in English when it was translated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
 pub enum SignalSource {
+    #[value(name = "github")]
     GitHub,
+    #[value(name = "onchain")]
     SolanaOnchain,
     Social,
+    #[value(name = "defillama")]
     DeFiLlama,
     Discovery,
+    Governance,
 }
 
 impl std::fmt::Display for SignalSource {
@@ -29,6 +37,7 @@ impl std::fmt::Display for SignalSource {
             Self::Social => write!(f, "Social"),
             Self::DeFiLlama => write!(f, "DeFiLlama"),
             Self::Discovery => write!(f, "Discovery"),
+            Self::Governance => write!(f, "Governance"),
         }
     }
 }
@@ -50,6 +59,24 @@ impl std::fmt::Display for Metric {
     }
 }
 
+impl Metric {
+    /// The value in USD when this is a TVL metric (`tvl` in the name, a USD
+    /// unit with an optional `K`/`M`/`B` scale).
+    pub fn tvl_usd(&self) -> Option<f64> {
+        if !self.name.to_ascii_lowercase().contains("tvl") {
+            return None;
+        }
+        let scale = match self.unit.trim().to_ascii_uppercase().as_str() {
+            "USD" | "$" => 1.0,
+            "K USD" => 1e3,
+            "M USD" => 1e6,
+            "B USD" => 1e9,
+            _ => return None,
+        };
+        Some(self.value * scale)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TrendDirection {
     Accelerating,
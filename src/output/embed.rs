@@ -0,0 +1,118 @@
+//! Structured data embedded in the HTML report, so an archived report can be
+//! re-rendered or diffed without the JSON it was built from.
+//!
+//! The narratives and findings are serialized to JSON, gzipped, base64-encoded
+//! and placed in a `<script type="application/gzip+base64" id="solguard-data">`
+//! tag. [`extract`] reverses that.
+
+use crate::narrative::Narrative;
+use crate::security::SecurityFinding;
+use anyhow::{Context, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use flate2::Compression;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// `id` of the script tag carrying the payload.
+pub const SCRIPT_ID: &str = "solguard-data";
+/// Bumped when the payload layout changes incompatibly.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The structured data behind a report. Narrative `repo_findings` indices
+/// refer to positions in `findings`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmbeddedData {
+    pub version: u32,
+    pub narratives: Vec<Narrative>,
+    pub findings: Vec<SecurityFinding>,
+}
+
+/// Gzip + base64 payload for the report's data script tag.
+pub fn encode(narratives: &[Narrative], findings: &[SecurityFinding]) -> Result<String> {
+    #[derive(Serialize)]
+    struct Borrowed<'a> {
+        version: u32,
+        narratives: &'a [Narrative],
+        findings: &'a [SecurityFinding],
+    }
+    let json = serde_json::to_vec(&Borrowed {
+        version: FORMAT_VERSION,
+        narratives,
+        findings,
+    })?;
+    let mut gz = GzEncoder::new(Vec::new(), Compression::best());
+    gz.write_all(&json)?;
+    Ok(STANDARD.encode(gz.finish()?))
+}
+
+/// Recover the embedded data from a rendered report.
+pub fn extract(html: &str) -> Result<EmbeddedData> {
+    let marker = format!("id=\"{SCRIPT_ID}\"");
+    let tag_start = html.find(&marker).context(
+        "no embedded data: report predates data embedding or was not rendered by SolGuard",
+    )?;
+    let body_start = tag_start + html[tag_start..].find('>').context("malformed data tag")? + 1;
+    let body_len = html[body_start..]
+        .find("</script>")
+        .context("unterminated data tag")?;
+    let payload: String = html[body_start..body_start + body_len]
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+
+    let compressed = STANDARD
+        .decode(payload)
+        .context("embedded data is not valid base64")?;
+    let mut json = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut json)
+        .context("embedded data is not valid gzip")?;
+    let data: EmbeddedData = serde_json::from_slice(&json).context("embedded data JSON")?;
+    if data.version > FORMAT_VERSION {
+        anyhow::bail!(
+            "embedded data format v{} is newer than this build supports (v{FORMAT_VERSION})",
+            data.version
+        );
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn round_trip() {
+        let narratives = vec![Narrative {
+            title: "Liquid staking".into(),
+            repo_findings: vec![("vault".into(), vec![0])],
+            ..Default::default()
+        }];
+        let findings = vec![SecurityFinding {
+            title: "Missing signer".into(),
+            severity: "High".into(),
+            file_path: PathBuf::from("repos/vault/src/lib.rs"),
+            ..Default::default()
+        }];
+        let payload = encode(&narratives, &findings).unwrap();
+        let html = format!(
+            "<html><script type=\"application/gzip+base64\" id=\"{SCRIPT_ID}\">\n{payload}\n</script></html>"
+        );
+        let data = extract(&html).unwrap();
+        assert_eq!(data.version, FORMAT_VERSION);
+        assert_eq!(data.narratives[0].title, "Liquid staking");
+        assert_eq!(data.narratives[0].repo_findings[0].1, vec![0]);
+        assert_eq!(data.findings[0].title, "Missing signer");
+    }
+
+    #[test]
+    fn missing_or_corrupt_payload_errors() {
+        assert!(extract("<html></html>").is_err());
+        let corrupt = format!("<script id=\"{SCRIPT_ID}\">not base64!</script>");
+        assert!(extract(&corrupt).is_err());
+    }
+}
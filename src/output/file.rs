@@ -0,0 +1,182 @@
+//! Where CLI commands put their output. Every command that writes a report or
+//! JSON goes through [`OutputFile`], so they all behave the same way:
+//!
+//! - `-` means stdout;
+//! - an existing file is never replaced unless `--force` was given;
+//! - files are written to a temp file next to the target and renamed into
+//!   place, so a crash mid-write leaves the old file (or nothing), never a
+//!   truncated report.
+
+use anyhow::{Context, Result, bail};
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// A command's output destination: stdout, or a file written atomically.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputFile {
+    /// `None` is stdout.
+    path: Option<PathBuf>,
+    force: bool,
+}
+
+impl OutputFile {
+    /// `-` is stdout; anything else is a file path.
+    pub fn new(path: impl Into<PathBuf>, force: bool) -> Self {
+        let path = path.into();
+        Self {
+            path: (path.as_os_str() != "-").then_some(path),
+            force,
+        }
+    }
+
+    /// Stdout when no path was given.
+    pub fn from_arg(path: Option<PathBuf>, force: bool) -> Self {
+        path.map_or_else(Self::stdout, |p| Self::new(p, force))
+    }
+
+    pub fn stdout() -> Self {
+        Self {
+            path: None,
+            force: false,
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    pub fn is_stdout(&self) -> bool {
+        self.path.is_none()
+    }
+
+    /// Fail if writing would replace an existing file without `--force`.
+    /// Call this before doing the work, so a long run doesn't end in a refusal.
+    pub fn check(&self) -> Result<()> {
+        match &self.path {
+            Some(path) if !self.force && path.exists() => bail!(
+                "{} already exists (use --force to overwrite, or `-` for stdout)",
+                path.display()
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    /// Write `contents`, creating parent directories as needed.
+    pub fn write(&self, contents: impl AsRef<[u8]>) -> Result<()> {
+        let contents = contents.as_ref();
+        let Some(path) = &self.path else {
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(contents)?;
+            if !contents.ends_with(b"\n") {
+                stdout.write_all(b"\n")?;
+            }
+            return Ok(stdout.flush()?);
+        };
+        self.check()?;
+        write_atomic(path, contents).with_context(|| format!("writing {}", path.display()))
+    }
+}
+
+impl fmt::Display for OutputFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}", path.display()),
+            None => f.write_str("stdout"),
+        }
+    }
+}
+
+/// Write to a temp file in the target's directory, sync it, then rename it
+/// over `path`. Rename within one directory is atomic on every platform we
+/// run on.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => {
+            std::fs::create_dir_all(parent)?;
+            parent
+        }
+        _ => Path::new("."),
+    };
+    let name = path
+        .file_name()
+        .ok_or_else(|| std::io::Error::other("output path has no file name"))?;
+    let tmp = dir.join(format!(
+        ".{}.{}.tmp",
+        name.to_string_lossy(),
+        std::process::id()
+    ));
+
+    let result = (|| {
+        let mut file = std::fs::File::create(&tmp)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp, path)
+    })();
+    if result.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("solguard-output-{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn dash_is_stdout() {
+        assert!(OutputFile::new("-", false).is_stdout());
+        assert!(OutputFile::from_arg(None, true).is_stdout());
+        assert!(!OutputFile::new("report.html", false).is_stdout());
+        assert_eq!(OutputFile::stdout().to_string(), "stdout");
+    }
+
+    #[test]
+    fn refuses_overwrite_without_force() {
+        let dir = scratch("overwrite");
+        let path = dir.join("nested/report.json");
+
+        OutputFile::new(&path, false).write("first").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+
+        let err = OutputFile::new(&path, false).write("second").unwrap_err();
+        assert!(err.to_string().contains("--force"));
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "first");
+
+        OutputFile::new(&path, true).write("second").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+
+        // No temp files left behind.
+        let leftovers: Vec<_> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .collect();
+        assert!(leftovers.is_empty());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn failed_write_keeps_existing_file() {
+        let dir = scratch("failed");
+        std::fs::create_dir_all(&dir).unwrap();
+        // A directory where the file should go: the rename fails.
+        let target = dir.join("report.html");
+        std::fs::create_dir_all(target.join("occupied")).unwrap();
+        assert!(write_atomic(&target, b"data").is_err());
+        assert!(target.join("occupied").is_dir());
+        let leftovers = std::fs::read_dir(&dir)
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().ends_with(".tmp"))
+            .count();
+        assert_eq!(leftovers, 0);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,182 @@
+//! The JSON files `render` builds a report from.
+//!
+//! Findings come in three shapes depending on the command that wrote them:
+//! `scan` and `investigate` write [`SecurityFinding`]s, the deep-review agent's
+//! raw output is [`AgentFinding`]s, and `test` writes [`ValidatedFinding`]s.
+//! The shape is detected from the first element and converted; anything else
+//! is rejected with the list of accepted formats. Narrative `repo_findings`
+//! indices are checked against the findings, since narratives paired with a
+//! findings file from another run would link the wrong findings.
+
+use crate::narrative::Narrative;
+use crate::security::SecurityFinding;
+use crate::security::agent_review::AgentFinding;
+use crate::security::validator::ValidatedFinding;
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+use std::path::Path;
+
+const FINDINGS_FORMATS: &str = "`render --findings` takes a JSON array of one of:
+  - findings from `scan` or `investigate` (objects with `file_path` and `line_number`)
+  - agent findings (objects with `affected_files` and `evidence`)
+  - validated findings from `test` (objects with `finding`, `verdict` and `reasoning`)";
+
+/// Read a findings file in any of the accepted shapes.
+pub fn load_findings(path: &Path) -> Result<Vec<SecurityFinding>> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    parse_findings(&text).with_context(|| format!("{}: unrecognized findings file", path.display()))
+}
+
+/// Parse findings JSON in any of the accepted shapes.
+pub fn parse_findings(text: &str) -> Result<Vec<SecurityFinding>> {
+    let items: Vec<Value> = match serde_json::from_str(text) {
+        Ok(Value::Array(items)) => items,
+        Ok(_) => bail!("expected a JSON array\n{FINDINGS_FORMATS}"),
+        Err(e) => bail!("invalid JSON: {e}\n{FINDINGS_FORMATS}"),
+    };
+    let Some(first) = items.first() else {
+        return Ok(Vec::new());
+    };
+    let has = |key: &str| first.get(key).is_some();
+    if has("repo_findings") || has("active_repos") {
+        bail!("this is a narratives file; pass it with `--narratives`\n{FINDINGS_FORMATS}");
+    }
+    let convert = |shape: &str, result: serde_json::Result<Vec<SecurityFinding>>| {
+        result.map_err(|e| anyhow::anyhow!("parsing {shape}: {e}\n{FINDINGS_FORMATS}"))
+    };
+    if has("verdict") && has("finding") {
+        convert(
+            "validated findings",
+            deserialize_all::<ValidatedFinding>(items),
+        )
+    } else if has("affected_files") {
+        convert("agent findings", deserialize_all::<AgentFinding>(items))
+    } else if has("file_path") {
+        convert("findings", deserialize_all::<SecurityFinding>(items))
+    } else {
+        bail!("the first element matches none of the accepted formats\n{FINDINGS_FORMATS}")
+    }
+}
+
+/// Deserialize every element as `T`; an error names the element.
+fn deserialize_all<T>(items: Vec<Value>) -> serde_json::Result<Vec<SecurityFinding>>
+where
+    T: serde::de::DeserializeOwned + Into<SecurityFinding>,
+{
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(i, item)| {
+            serde_json::from_value::<T>(item)
+                .map(Into::into)
+                .map_err(|e| serde::de::Error::custom(format!("element {i}: {e}")))
+        })
+        .collect()
+}
+
+/// Read a narratives file.
+pub fn load_narratives(path: &Path) -> Result<Vec<Narrative>> {
+    let text =
+        std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+    serde_json::from_str(&text).with_context(|| {
+        format!(
+            "{}: `render --narratives` takes the JSON array of narratives written by `narratives` \
+             or `extract --narratives`",
+            path.display()
+        )
+    })
+}
+
+/// Fail when a narrative links a finding index past the end of the findings.
+pub fn check_links(narratives: &[Narrative], findings: usize) -> Result<()> {
+    let mut broken = Vec::new();
+    for n in narratives {
+        for (repo, indices) in &n.repo_findings {
+            for i in indices.iter().filter(|&&i| i >= findings) {
+                broken.push(format!("'{}' links finding {i} of {repo}", n.title));
+            }
+        }
+    }
+    if !broken.is_empty() {
+        bail!(
+            "narratives link findings the findings file doesn't have ({findings} findings): {}. \
+             Render narratives with the findings from the same run.",
+            broken.join("; ")
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::ValidationStatus;
+
+    #[test]
+    fn accepts_each_findings_shape() {
+        let scan = r#"[{"title": "Missing Signer", "severity": "High", "description": "",
+                        "file_path": "src/lib.rs", "line_number": 12, "remediation": ""}]"#;
+        let agent = r#"[{"title": "Oracle staleness", "severity": "critical", "description": "d",
+                         "evidence": [], "attack_scenario": "", "remediation": "r",
+                         "confidence": 0.8, "affected_files": ["programs/vault/src/oracle.rs"]}]"#;
+        let validated = format!(
+            r#"[{{"finding": {}, "verdict": "Disputed", "reasoning": "guarded upstream"}}]"#,
+            &agent[1..agent.len() - 1]
+        );
+
+        let scan = parse_findings(scan).unwrap();
+        assert_eq!(scan[0].line_number, 12);
+        let agent = parse_findings(agent).unwrap();
+        assert_eq!(agent[0].severity, "Critical");
+        assert_eq!(
+            agent[0].file_path,
+            Path::new("programs/vault/src/oracle.rs")
+        );
+        let validated = parse_findings(&validated).unwrap();
+        assert_eq!(validated[0].validation_status, ValidationStatus::Disputed);
+        assert_eq!(
+            validated[0].validation_reasoning.as_deref(),
+            Some("guarded upstream")
+        );
+        assert!(parse_findings("[]").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_other_json_with_the_accepted_formats() {
+        for (text, reason) in [
+            (r#"{"findings": []}"#, "expected a JSON array"),
+            (r#"[{"title": "t", "active_repos": []}]"#, "narratives file"),
+            (r#"[{"name": "x"}]"#, "none of the accepted formats"),
+            (
+                r#"[{"affected_files": ["a.rs"]}]"#,
+                "parsing agent findings: element 0",
+            ),
+            ("[", "invalid JSON"),
+        ] {
+            let message = format!("{:#}", parse_findings(text).unwrap_err());
+            assert!(message.contains(reason), "{message}");
+            assert!(
+                message.contains("validated findings from `test`"),
+                "{message}"
+            );
+        }
+    }
+
+    #[test]
+    fn out_of_range_links_are_reported() {
+        let narrative = Narrative {
+            title: "Lending".into(),
+            repo_findings: vec![("vault".into(), vec![0, 2]), ("pool".into(), vec![5])],
+            ..Default::default()
+        };
+        assert!(check_links(std::slice::from_ref(&narrative), 6).is_ok());
+        let message = check_links(&[narrative], 2).unwrap_err().to_string();
+        assert!(message.contains("(2 findings)"), "{message}");
+        assert!(
+            message.contains("'Lending' links finding 2 of vault"),
+            "{message}"
+        );
+        assert!(message.contains("finding 5 of pool"), "{message}");
+    }
+}
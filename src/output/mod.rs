@@ -1,9 +1,24 @@
-use crate::memory::RunMemory;
+use crate::agent::cross_ref::{self, OrphanTheme, RiskTerm};
+use crate::agent::freshness::{Activity, TargetActivity};
+use crate::config::GroupBy;
+use crate::memory::{self, CarriedTarget, RepoResult, RunMemory};
 use crate::narrative::Narrative;
-use crate::security::{SecurityFinding, ValidationStatus};
+use crate::security::cross_repo::CrossRepoGraph;
+use crate::security::sampling::PatternSample;
+use crate::security::trust_graph::TrustGraph;
+use crate::security::{ScanType, SecurityFinding, ValidationStatus};
+use crate::severity;
 use askama::Template;
 use chrono::Utc;
 use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+pub mod embed;
+pub mod file;
+pub mod inputs;
+pub mod redact;
+
+pub use redact::Audience;
 
 #[derive(Template)]
 #[template(path = "solguard_report.html")]
@@ -23,11 +38,40 @@ struct SolGuardReport {
     has_validation: bool,
     narratives: Vec<NarrativeView>,
     repo_summaries: Vec<RepoSummary>,
+    /// Executive summaries of the scanned repos, above the per-repo counts;
+    /// empty for the public audience.
+    repo_overviews: Vec<RepoOverview>,
+    instruction_summaries: Vec<InstructionSummary>,
     orphan_groups: Vec<GroupedFinding>,
     orphan_count: usize,
+    /// Root-cause themes over the orphans; empty when none were generated.
+    orphan_themes: Vec<ThemeView>,
     learning_total_runs: u32,
     learning_repos_blocklisted: usize,
     learning_errors_learned: usize,
+    degraded: Vec<String>,
+    targets: Vec<TargetView>,
+    /// Targets queued for the next run.
+    deferred: Vec<DeferredView>,
+    samples: Vec<SampleView>,
+    /// Per-repo agreement of blind validation with the investigator.
+    blind_reviews: Vec<BlindReviewView>,
+    /// Deep-review budget per narrative, each followed by its repos.
+    budgets: Vec<BudgetView>,
+    /// Repos with findings waived by `solguard-ignore` comments.
+    waivers: Vec<WaiverView>,
+    /// Mermaid trust-boundary diagrams; empty for the public audience.
+    trust_graphs: Vec<GraphView>,
+    /// Calls between repos of one narrative, per narrative.
+    cross_repo: Vec<CrossRepoView>,
+    /// Clone manifest of an isolated run; empty otherwise or when paths are hidden.
+    manifest_path: String,
+    /// Banner for a redacted (non-internal) report.
+    audience_notice: String,
+    /// Disclaimer for a `--explore` run; empty otherwise.
+    exploration_notice: String,
+    /// Gzip + base64 narratives and findings, see [`embed`].
+    embedded_data: String,
 }
 
 #[allow(dead_code)] // fields used by Askama template
@@ -43,10 +87,33 @@ struct NarrativeView {
     risk_class: String,
     grouped_findings: Vec<GroupedFinding>,
     repo_context: String,
+    /// Repos whose metadata didn't match the narrative (flag policy), or that
+    /// were dropped from it (drop policy).
+    mismatched_repos: String,
+    tags: Vec<String>,
+    /// Per-finding score terms, largest first.
+    risk_terms: Vec<RiskTermView>,
+    risk_terms_total_fmt: String,
+    /// Set when the terms don't add up to the stored score.
+    risk_terms_note: String,
+}
+
+#[allow(dead_code)] // fields used by Askama template
+struct RiskTermView {
+    finding: String,
+    repo: String,
+    severity: String,
+    severity_weight: String,
+    validation: String,
+    validation_multiplier: String,
+    template_multiplier: String,
+    confidence: String,
+    contribution: String,
 }
 
 #[allow(dead_code)] // fields used by Askama template
 struct FindingView {
+    id: String,
     title: String,
     severity: String,
     severity_class: String,
@@ -58,6 +125,17 @@ struct FindingView {
     validation_class: String,
     validation_reasoning: String,
     provenance: String,
+    location: String,
+    tags: Vec<String>,
+    /// Fork repos sharing a template-level finding, comma-separated.
+    forks: String,
+    /// Grouping keys; `file` is empty when the audience hides paths.
+    pattern_id: String,
+    file: String,
+    /// Suggested fix as a unified diff; empty when there is none.
+    patch: String,
+    /// "validator Medium vs investigator High (-1)"; empty unless reviewed blind.
+    blind_review: String,
 }
 
 #[allow(dead_code)] // fields used by Askama template
@@ -67,9 +145,21 @@ struct GroupedFinding {
     severity_class: String,
     repo: String,
     count: usize,
+    /// "3 instances", "3 in src/lib.rs", ...; shown when `count > 1`.
+    badge: String,
     example: FindingView,
 }
 
+#[allow(dead_code)] // fields used by Askama template
+struct ThemeView {
+    theme: String,
+    summary: String,
+    count: usize,
+    /// Highest severity among the theme's findings.
+    severity: String,
+    severity_class: String,
+}
+
 #[allow(dead_code)] // fields used by Askama template
 struct RepoSummary {
     name: String,
@@ -78,10 +168,174 @@ struct RepoSummary {
     medium: usize,
     low: usize,
     total: usize,
+    /// Upgrade authority posture of the repo's deployed programs, if checked.
+    upgrade_posture: String,
+    /// Per-program counts, when the findings span more than one workspace
+    /// program crate.
+    programs: Vec<ProgramSummary>,
+}
+
+#[allow(dead_code)] // fields used by Askama template
+struct RepoOverview {
+    repo: String,
+    summary: String,
+}
+
+#[allow(dead_code)] // fields used by Askama template
+struct ProgramSummary {
+    /// Program crate name; files outside any program are "other".
+    name: String,
+    critical: usize,
+    high: usize,
+    medium: usize,
+    low: usize,
+    total: usize,
+}
+
+/// A selected scan target and its last-commit age.
+#[allow(dead_code)] // fields used by Askama template
+struct TargetView {
+    repo: String,
+    last_commit: String,
+    status: String,
+    /// Highlighted: scanned static-only or not at all.
+    stale: bool,
+}
+
+#[allow(dead_code)] // fields used by Askama template
+struct DeferredView {
+    target: String,
+    narrative: String,
+    reason: String,
+}
+
+#[allow(dead_code)] // fields used by Askama template
+struct SampleView {
+    repo: String,
+    pattern: String,
+    total: usize,
+    sampled: usize,
+    confirmed: usize,
+    disputed: usize,
+    dismissed: usize,
+    precision: String,
+    estimate: String,
+}
+
+/// How a repo's blind-reviewed findings were rated by the validator against
+/// the investigator.
+#[allow(dead_code)] // fields used by Askama template
+struct BlindReviewView {
+    repo: String,
+    reviewed: usize,
+    agreed: usize,
+    /// Validator rated it more severe.
+    higher: usize,
+    lower: usize,
+    /// Mean validator-minus-investigator delta in levels, signed.
+    mean_delta: String,
+}
+
+/// Budget row: a narrative total, or one of its repos when `is_repo`.
+#[allow(dead_code)] // fields used by Askama template
+struct BudgetView {
+    label: String,
+    confidence: String,
+    is_repo: bool,
+    budget_turns: u32,
+    spent_turns: u32,
+    budget_cost: String,
+    spent_cost: String,
+    /// "merged / dropped" static findings; repo rows only.
+    static_merge: String,
+    static_merge_detail: String,
+}
+
+/// A repo's findings waived by `solguard-ignore` comments.
+#[allow(dead_code)] // fields used by Askama template
+struct WaiverView {
+    repo: String,
+    count: usize,
+    /// "SOL-003 ×2, AST-001".
+    patterns: String,
+    /// Each waiver; empty when the audience doesn't see paths.
+    entries: Vec<WaiverEntryView>,
+}
+
+#[allow(dead_code)] // fields used by Askama template
+struct WaiverEntryView {
+    location: String,
+    pattern_id: String,
+    reason: String,
+}
+
+#[allow(dead_code)] // fields used by Askama template
+struct GraphView {
+    repo: String,
+    mermaid: String,
+}
+
+#[allow(dead_code)] // fields used by Askama template
+struct CrossRepoView {
+    narrative: String,
+    mermaid: String,
+    calls: Vec<CrossRepoCallView>,
+    /// Whether calls show where they are referenced.
+    located: bool,
+}
+
+#[allow(dead_code)] // fields used by Askama template
+struct CrossRepoCallView {
+    caller: String,
+    callee: String,
+    program_id: String,
+    /// `file:line` in the caller; empty when paths are hidden.
+    location: String,
+}
+
+/// Run-level context for the report beyond narratives and findings.
+#[derive(Debug, Default)]
+pub struct ReportNotices {
+    /// Phases skipped for missing credentials; shown as a "degraded run" banner.
+    pub degraded: Vec<String>,
+    /// Selected targets with their activity, listed under "Scan Targets".
+    pub targets: Vec<TargetActivity>,
+    /// Who the report is for; anything but `Internal` redacts findings.
+    pub audience: Audience,
+    /// How findings are grouped under each narrative and repo.
+    pub group_by: GroupBy,
+    /// Free-model exploration run; adds an "exploration quality" disclaimer.
+    pub exploration: bool,
+    /// Per-pattern verdicts from sampled validation, extrapolated in the report.
+    pub validation_samples: Vec<PatternSample>,
+    /// Per-repo results; their budget splits fill "Budget Allocation".
+    pub budgets: Vec<RepoResult>,
+    /// Per-repo trust-boundary graphs, drawn under "Trust Boundaries".
+    pub trust_graphs: Vec<TrustGraph>,
+    /// Per-narrative graphs of repos calling each other's programs, drawn
+    /// under "Cross-Repo Calls".
+    pub cross_repo: Vec<CrossRepoGraph>,
+    /// `manifest.json` of an isolated run's workspace, linked under "Scan Targets".
+    pub manifest: Option<PathBuf>,
+    /// Root-cause themes over the findings no narrative claimed, shown above
+    /// "Unlinked Findings".
+    pub orphan_themes: Vec<OrphanTheme>,
+    /// Targets queued for the next run, listed under "Deferred Targets".
+    pub deferred: Vec<CarriedTarget>,
+}
+
+/// Findings rolled up per Anchor instruction handler.
+#[allow(dead_code)] // fields used by Askama template
+struct InstructionSummary {
+    repo: String,
+    instruction: String,
+    count: usize,
+    severity: String,
+    severity_class: String,
 }
 
 fn severity_class(severity: &str) -> String {
-    match severity {
+    match severity::normalize(severity) {
         "Critical" => "text-red-500".into(),
         "High" => "text-orange-400".into(),
         "Medium" => "text-yellow-400".into(),
@@ -109,6 +363,15 @@ fn validation_class(status: &ValidationStatus) -> String {
     }
 }
 
+/// The recorded scan type, else inferred: agent findings have no line.
+fn scan_type_label(f: &SecurityFinding) -> &'static str {
+    match &f.provenance {
+        Some(p) => p.scan_type.label(),
+        None if f.line_number == 0 => ScanType::DeepReview.label(),
+        None => ScanType::Static.label(),
+    }
+}
+
 fn validation_badge(status: &ValidationStatus) -> String {
     match status {
         ValidationStatus::Confirmed => "Confirmed".into(),
@@ -118,71 +381,192 @@ fn validation_badge(status: &ValidationStatus) -> String {
     }
 }
 
-/// Extract repo name from a finding's file path (first component under repos/).
-fn repo_name(f: &SecurityFinding) -> String {
-    let path = f.file_path.to_string_lossy();
-    if let Some(idx) = path.find("repos/") {
-        let after = &path[idx + 6..];
-        if let Some(slash) = after.find('/') {
-            return after[..slash].to_string();
+fn theme_to_view(theme: &OrphanTheme, findings: &[SecurityFinding]) -> ThemeView {
+    let severity = theme
+        .findings
+        .iter()
+        .filter_map(|&i| findings.get(i))
+        .map(|f| severity::normalize(&f.severity))
+        .min_by_key(|s| severity::rank(s))
+        .unwrap_or("Info")
+        .to_string();
+    ThemeView {
+        theme: theme.theme.clone(),
+        summary: theme.summary.clone(),
+        count: theme.findings.len(),
+        severity_class: severity_class(&severity),
+        severity,
+    }
+}
+
+/// Human-readable code location: instruction handler if known, else enclosing
+/// function and the call chain reaching it.
+fn location_label(f: &SecurityFinding) -> String {
+    match (&f.instruction, &f.function) {
+        (Some(ix), _) => format!("{ix} handler"),
+        (None, Some(func)) if f.entry_path.len() > 1 => {
+            format!("fn {func}, via {}", f.entry_path.join(" → "))
         }
-        return after.to_string();
+        (None, Some(func)) => format!("fn {func}"),
+        (None, None) => String::new(),
     }
-    f.file_path
-        .components()
-        .find_map(|c| {
-            let s = c.as_os_str().to_string_lossy();
-            if s != "." && s != ".." && s != "repos" {
-                Some(s.to_string())
-            } else {
-                None
-            }
-        })
-        .unwrap_or_else(|| "unknown".into())
 }
 
-fn finding_to_view(f: &SecurityFinding, provenance: String) -> FindingView {
+/// Roll findings up by (repo, instruction) so the report can say
+/// "withdraw handler has 3 issues". Findings outside a handler are skipped.
+fn summarize_instructions(findings: &[SecurityFinding]) -> Vec<InstructionSummary> {
+    let mut map: BTreeMap<(String, String), (usize, String)> = BTreeMap::new();
+    for f in findings {
+        let Some(ref ix) = f.instruction else {
+            continue;
+        };
+        let entry = map
+            .entry((crate::paths::repo_name(&f.file_path), ix.clone()))
+            .or_insert((0, f.severity.clone()));
+        entry.0 += 1;
+        if severity::rank(&f.severity) < severity::rank(&entry.1) {
+            entry.1 = f.severity.clone();
+        }
+    }
+    let mut summaries: Vec<InstructionSummary> = map
+        .into_iter()
+        .map(
+            |((repo, instruction), (count, severity))| InstructionSummary {
+                repo,
+                instruction,
+                count,
+                severity_class: severity_class(&severity),
+                severity,
+            },
+        )
+        .collect();
+    summaries.sort_by_key(|s| (severity::rank(&s.severity), std::cmp::Reverse(s.count)));
+    summaries
+}
+
+fn finding_to_view(f: &SecurityFinding, provenance: String, show_paths: bool) -> FindingView {
     FindingView {
+        id: f.id.clone().unwrap_or_default(),
         title: f.title.clone(),
         severity_class: severity_class(&f.severity),
         description: f.description.clone(),
         remediation: f.remediation.clone(),
-        file_location: format!("{}:{}", f.file_path.display(), f.line_number),
-        repo: repo_name(f),
-        validation_badge: validation_badge(&f.validation_status),
+        file_location: if show_paths {
+            format!("{}:{}", f.file_path.display(), f.line_number)
+        } else {
+            String::new()
+        },
+        repo: crate::paths::repo_name(&f.file_path),
+        validation_badge: if f.validation_cached {
+            format!("{} (cached)", validation_badge(&f.validation_status))
+        } else {
+            validation_badge(&f.validation_status)
+        },
         validation_class: validation_class(&f.validation_status),
         validation_reasoning: f.validation_reasoning.clone().unwrap_or_default(),
         severity: f.severity.clone(),
         provenance,
+        location: location_label(f),
+        tags: f.tags.clone(),
+        forks: f.forks.join(", "),
+        pattern_id: f.pattern_id.clone().unwrap_or_default(),
+        file: if show_paths {
+            crate::paths::to_slash(&f.file_path)
+        } else {
+            String::new()
+        },
+        patch: f.patch.clone().unwrap_or_default(),
+        blind_review: f
+            .blind_review
+            .as_ref()
+            .map(|b| {
+                format!(
+                    "validator {} vs investigator {} ({:+})",
+                    b.validator,
+                    b.investigator,
+                    b.delta()
+                )
+            })
+            .unwrap_or_default(),
     }
 }
 
-fn group_findings(findings: Vec<FindingView>) -> Vec<GroupedFinding> {
-    let mut map: BTreeMap<(String, String), Vec<FindingView>> = BTreeMap::new();
+fn blind_review_views(findings: &[SecurityFinding]) -> Vec<BlindReviewView> {
+    let mut deltas: BTreeMap<String, Vec<i8>> = BTreeMap::new();
     for f in findings {
-        map.entry((f.title.clone(), f.repo.clone()))
-            .or_default()
+        if let Some(b) = &f.blind_review {
+            deltas
+                .entry(crate::paths::repo_name(&f.file_path))
+                .or_default()
+                .push(b.delta());
+        }
+    }
+    deltas
+        .into_iter()
+        .map(|(repo, d)| BlindReviewView {
+            repo,
+            reviewed: d.len(),
+            agreed: d.iter().filter(|&&x| x == 0).count(),
+            higher: d.iter().filter(|&&x| x > 0).count(),
+            lower: d.iter().filter(|&&x| x < 0).count(),
+            mean_delta: format!(
+                "{:+.1}",
+                d.iter().map(|&x| f64::from(x)).sum::<f64>() / d.len() as f64
+            ),
+        })
+        .collect()
+}
+
+/// Grouping key within a repo and the label naming the group. Findings with
+/// nothing to group on get a key of their own.
+fn group_key(f: &FindingView, by: GroupBy, index: usize) -> (String, String) {
+    let own = || (format!("\0{index}"), String::new());
+    match by {
+        GroupBy::Title => (f.title.clone(), String::new()),
+        GroupBy::Pattern if f.pattern_id.is_empty() => (format!("\0{}", f.title), String::new()),
+        GroupBy::Pattern => (f.pattern_id.clone(), f.pattern_id.clone()),
+        GroupBy::File if f.file.is_empty() => own(),
+        GroupBy::File => (f.file.clone(), f.file.clone()),
+        GroupBy::Instruction if f.location.is_empty() => own(),
+        GroupBy::Instruction => (f.location.clone(), f.location.clone()),
+        GroupBy::None => own(),
+    }
+}
+
+fn group_findings(findings: Vec<FindingView>, by: GroupBy) -> Vec<GroupedFinding> {
+    let mut map: BTreeMap<(String, String), (String, Vec<FindingView>)> = BTreeMap::new();
+    for (i, f) in findings.into_iter().enumerate() {
+        let (key, label) = group_key(&f, by, i);
+        map.entry((key, f.repo.clone()))
+            .or_insert_with(|| (label, Vec::new()))
+            .1
             .push(f);
     }
     let mut groups: Vec<GroupedFinding> = map
         .into_iter()
-        .map(|((title, repo), mut members)| {
-            members.sort_by(|a, b| severity_order(&a.severity).cmp(&severity_order(&b.severity)));
+        .map(|((_, repo), (label, mut members))| {
+            members.sort_by_key(|m| severity::rank(&m.severity));
             let count = members.len();
             let severity = members[0].severity.clone();
             let severity_class = members[0].severity_class.clone();
             let example = members.into_iter().next().unwrap();
+            let badge = match by {
+                _ if label.is_empty() => format!("{count} instances"),
+                GroupBy::Pattern => format!("{count} \u{d7} {label}"),
+                _ => format!("{count} in {label}"),
+            };
             GroupedFinding {
-                title,
+                title: example.title.clone(),
                 severity,
                 severity_class,
                 repo,
                 count,
+                badge,
                 example,
             }
         })
         .collect();
-    groups.sort_by(|a, b| severity_order(&a.severity).cmp(&severity_order(&b.severity)));
+    groups.sort_by_key(|g| severity::rank(&g.severity));
     groups
 }
 
@@ -207,6 +591,20 @@ pub fn render_combined_report(
     findings: &[SecurityFinding],
     run_memory: Option<&RunMemory>,
 ) -> anyhow::Result<String> {
+    render_combined_report_with_notices(narratives, findings, run_memory, &ReportNotices::default())
+}
+
+/// Like [`render_combined_report`], with run notices: a "degraded run" banner,
+/// the scan target activity table and the audience to redact for. Default
+/// notices render neither table nor banner and redact nothing.
+pub fn render_combined_report_with_notices(
+    narratives: &[Narrative],
+    findings: &[SecurityFinding],
+    run_memory: Option<&RunMemory>,
+    notices: &ReportNotices,
+) -> anyhow::Result<String> {
+    let findings = &redact::redact_findings(findings, notices.audience)[..];
+    let show_paths = notices.audience.shows_paths();
     // Build narrative views with linked findings
     let narrative_views: Vec<NarrativeView> = narratives
         .iter()
@@ -215,11 +613,7 @@ pub fn render_combined_report(
             for (_, indices) in &n.repo_findings {
                 for &idx in indices {
                     if let Some(f) = findings.get(idx) {
-                        let scan_type = if f.line_number == 0 {
-                            "deep agent review"
-                        } else {
-                            "static scan"
-                        };
+                        let scan_type = scan_type_label(f);
                         let provenance = format!(
                             "Source: {} ({}%) \u{2192} target selection \u{2192} {} \u{2192} {}",
                             n.title,
@@ -227,7 +621,7 @@ pub fn render_combined_report(
                             scan_type,
                             validation_badge(&f.validation_status),
                         );
-                        linked.push(finding_to_view(f, provenance));
+                        linked.push(finding_to_view(f, provenance, show_paths));
                     }
                 }
             }
@@ -255,6 +649,7 @@ pub fn render_combined_report(
                 n.trend,
                 repos_display,
             );
+            let (risk_terms, risk_terms_total_fmt, risk_terms_note) = risk_breakdown(n, findings);
             NarrativeView {
                 title: n.title.clone(),
                 summary: n.summary.clone(),
@@ -265,44 +660,51 @@ pub fn render_combined_report(
                 risk_score_fmt: format!("{:.1}", n.risk_score),
                 risk_level: rl.to_string(),
                 risk_class: risk_class(rl),
-                grouped_findings: cap_groups_per_repo(group_findings(linked)),
+                grouped_findings: cap_groups_per_repo(group_findings(linked, notices.group_by)),
                 repo_context,
+                mismatched_repos: n.mismatched_repos.join(", "),
+                tags: n.tags.clone(),
+                risk_terms,
+                risk_terms_total_fmt,
+                risk_terms_note,
             }
         })
         .collect();
 
     // Orphan findings: not linked to any narrative — render under "Additional Findings"
-    let linked_indices: std::collections::HashSet<usize> = narratives
-        .iter()
-        .flat_map(|n| n.repo_findings.iter().flat_map(|(_, v)| v.iter().copied()))
-        .collect();
-    let orphan_views: Vec<FindingView> = findings
-        .iter()
-        .enumerate()
-        .filter(|(i, _)| !linked_indices.contains(i))
-        .map(|(_, f)| {
-            let scan_type = if f.line_number == 0 {
-                "deep agent review"
-            } else {
-                "static scan"
-            };
+    let orphan_views: Vec<FindingView> = cross_ref::orphan_indices(narratives, findings.len())
+        .into_iter()
+        .map(|i| &findings[i])
+        .map(|f| {
+            let scan_type = scan_type_label(f);
             let provenance = format!(
                 "Source: {} \u{2192} {}",
                 scan_type,
                 validation_badge(&f.validation_status),
             );
-            finding_to_view(f, provenance)
+            finding_to_view(f, provenance, show_paths)
         })
         .collect();
     let orphan_count = orphan_views.len();
-    let orphan_groups = cap_groups_per_repo(group_findings(orphan_views));
+    let orphan_groups = cap_groups_per_repo(group_findings(orphan_views, notices.group_by));
+    let orphan_themes = notices
+        .orphan_themes
+        .iter()
+        .map(|t| theme_to_view(t, findings))
+        .collect();
 
     // Severity counts
-    let severity_critical = findings.iter().filter(|f| f.severity == "Critical").count();
-    let severity_high = findings.iter().filter(|f| f.severity == "High").count();
-    let severity_medium = findings.iter().filter(|f| f.severity == "Medium").count();
-    let severity_low = findings.iter().filter(|f| f.severity == "Low").count();
-    let severity_info = findings.iter().filter(|f| f.severity == "Info").count();
+    let mut by_severity = [0usize; severity::LEVELS.len()];
+    for f in findings {
+        by_severity[severity::rank(&f.severity)] += 1;
+    }
+    let [
+        severity_critical,
+        severity_high,
+        severity_medium,
+        severity_low,
+        severity_info,
+    ] = by_severity;
     let critical_count = severity_critical + severity_high;
 
     // Validation counts
@@ -320,31 +722,44 @@ pub fn render_combined_report(
 
     // Per-repo summaries
     let mut repo_map: BTreeMap<String, [usize; 5]> = BTreeMap::new();
+    let mut program_map: BTreeMap<String, BTreeMap<Option<&str>, [usize; 5]>> = BTreeMap::new();
+    let mut postures: BTreeMap<String, Vec<&str>> = BTreeMap::new();
     for f in findings {
-        let name = repo_name(f);
-        let counts = repo_map.entry(name).or_insert([0; 5]);
-        match f.severity.as_str() {
-            "Critical" => counts[0] += 1,
-            "High" => counts[1] += 1,
-            "Medium" => counts[2] += 1,
-            "Low" => counts[3] += 1,
-            _ => counts[4] += 1,
+        let name = crate::paths::repo_name(&f.file_path);
+        program_map
+            .entry(name.clone())
+            .or_default()
+            .entry(f.program.as_deref())
+            .or_insert([0; 5])[severity::rank(&f.severity)] += 1;
+        if let Some(label) = crate::security::posture::posture_label(f) {
+            let labels = postures.entry(name.clone()).or_default();
+            if !labels.contains(&label) {
+                labels.push(label);
+            }
         }
+        let counts = repo_map.entry(name).or_insert([0; 5]);
+        counts[severity::rank(&f.severity)] += 1;
     }
     let mut repo_summaries: Vec<RepoSummary> = repo_map
         .into_iter()
         .map(|(name, c)| RepoSummary {
-            name,
             critical: c[0],
             high: c[1],
             medium: c[2],
             low: c[3],
             total: c.iter().sum(),
+            upgrade_posture: postures
+                .get(&name)
+                .map(|l| l.join(", "))
+                .unwrap_or_default(),
+            programs: program_summaries(program_map.remove(&name).unwrap_or_default()),
+            name,
         })
         .collect();
-    repo_summaries.sort_by(|a, b| b.total.cmp(&a.total));
+    repo_summaries.sort_by_key(|r| std::cmp::Reverse(r.total));
 
     let repo_count = repo_summaries.len();
+    let instruction_summaries = summarize_instructions(findings);
 
     let (learning_total_runs, learning_repos_blocklisted, learning_errors_learned) =
         match run_memory {
@@ -372,11 +787,95 @@ pub fn render_combined_report(
         has_validation,
         narratives: narrative_views,
         repo_summaries,
+        instruction_summaries,
         orphan_groups,
         orphan_count,
+        orphan_themes,
         learning_total_runs,
         learning_repos_blocklisted,
         learning_errors_learned,
+        degraded: notices.degraded.clone(),
+        targets: notices.targets.iter().map(target_to_view).collect(),
+        deferred: notices
+            .deferred
+            .iter()
+            .map(|c| DeferredView {
+                target: c.target.clone(),
+                narrative: c
+                    .narrative
+                    .as_ref()
+                    .map_or_else(|| "-".into(), |n| n.title.clone()),
+                reason: c.reason.clone(),
+            })
+            .collect(),
+        samples: notices
+            .validation_samples
+            .iter()
+            .map(sample_to_view)
+            .collect(),
+        blind_reviews: blind_review_views(findings),
+        budgets: budget_views(&notices.budgets),
+        waivers: waiver_views(&notices.budgets, show_paths),
+        repo_overviews: notices
+            .budgets
+            .iter()
+            .filter_map(|r| {
+                Some(RepoOverview {
+                    repo: r.name.clone(),
+                    summary: redact::redact_summary(r.summary.as_deref()?, notices.audience)?,
+                })
+            })
+            .collect(),
+        trust_graphs: if show_paths {
+            notices
+                .trust_graphs
+                .iter()
+                .filter(|g| !g.is_empty())
+                .map(|g| GraphView {
+                    repo: g.repo.clone(),
+                    mermaid: g.to_mermaid(),
+                })
+                .collect()
+        } else {
+            Vec::new()
+        },
+        cross_repo: notices
+            .cross_repo
+            .iter()
+            .filter(|g| !g.is_empty())
+            .map(|g| CrossRepoView {
+                narrative: g.narrative.clone(),
+                mermaid: g.to_mermaid(),
+                calls: g
+                    .calls
+                    .iter()
+                    .map(|c| CrossRepoCallView {
+                        caller: c.caller.clone(),
+                        callee: c.callee.clone(),
+                        program_id: c.program_id.clone(),
+                        location: if show_paths {
+                            format!("{}:{}", crate::paths::to_slash(&c.file_path), c.line_number)
+                        } else {
+                            String::new()
+                        },
+                    })
+                    .collect(),
+                located: show_paths,
+            })
+            .collect(),
+        manifest_path: match &notices.manifest {
+            Some(path) if show_paths => {
+                crate::paths::to_slash(&std::path::absolute(path).unwrap_or_else(|_| path.clone()))
+            }
+            _ => String::new(),
+        },
+        audience_notice: notices.audience.notice().to_string(),
+        exploration_notice: if notices.exploration {
+            crate::agent::explore::DISCLAIMER.to_string()
+        } else {
+            String::new()
+        },
+        embedded_data: embed::encode(narratives, findings)?,
     };
 
     report
@@ -384,14 +883,212 @@ pub fn render_combined_report(
         .map_err(|e| anyhow::anyhow!("template render: {e}"))
 }
 
-fn severity_order(severity: &str) -> u8 {
-    match severity {
-        "Critical" => 0,
-        "High" => 1,
-        "Medium" => 2,
-        "Low" => 3,
-        _ => 4,
+/// Rows for a repo's programs; none unless its findings span two or more
+/// (findings outside any program count as "other").
+fn program_summaries(counts: BTreeMap<Option<&str>, [usize; 5]>) -> Vec<ProgramSummary> {
+    if counts.len() < 2 {
+        return Vec::new();
     }
+    let mut counts: Vec<_> = counts.into_iter().collect();
+    // Largest programs first; files outside any program last.
+    counts.sort_by_key(|(name, c)| (name.is_none(), std::cmp::Reverse(c.iter().sum::<usize>())));
+    counts
+        .into_iter()
+        .map(|(name, c)| ProgramSummary {
+            name: name.unwrap_or("other").to_string(),
+            critical: c[0],
+            high: c[1],
+            medium: c[2],
+            low: c[3],
+            total: c.iter().sum(),
+        })
+        .collect()
+}
+
+/// Score terms for the findings linked to `n`, their formatted sum, and a
+/// note when the sum differs from `n.risk_score` (findings dropped by
+/// `--filter-tag` after scoring).
+fn risk_breakdown(
+    n: &Narrative,
+    findings: &[SecurityFinding],
+) -> (Vec<RiskTermView>, String, String) {
+    let linked: std::collections::HashSet<usize> = n
+        .repo_findings
+        .iter()
+        .flat_map(|(_, v)| v.iter().copied())
+        .collect();
+    let mut terms: Vec<RiskTerm> = cross_ref::risk_terms(n, findings)
+        .into_iter()
+        .filter(|t| linked.contains(&t.finding_idx))
+        .collect();
+    terms.sort_by(|a, b| b.contribution().total_cmp(&a.contribution()));
+    let total: f64 = terms.iter().map(RiskTerm::contribution).sum();
+    let note = if terms.is_empty() || (total - n.risk_score).abs() < 0.05 {
+        String::new()
+    } else {
+        format!(
+            "Stored score {:.1} was computed over findings not shown in this report.",
+            n.risk_score
+        )
+    };
+    let views = terms
+        .iter()
+        .map(|t| {
+            let f = &findings[t.finding_idx];
+            RiskTermView {
+                finding: match &f.id {
+                    Some(id) => format!("{id} {}", f.title),
+                    None => f.title.clone(),
+                },
+                repo: t.repo.clone(),
+                severity: f.severity.clone(),
+                severity_weight: format!("{}", t.severity_weight),
+                validation: validation_badge(&f.validation_status),
+                validation_multiplier: format!("\u{d7}{}", t.validation_multiplier),
+                template_multiplier: format!("\u{d7}{:.2}", t.template_multiplier),
+                confidence: format!("\u{d7}{:.2}", t.confidence),
+                contribution: format!("{:.2}", t.contribution()),
+            }
+        })
+        .collect();
+    (views, format!("{total:.1}"), note)
+}
+
+fn target_to_view(t: &TargetActivity) -> TargetView {
+    TargetView {
+        repo: t.repo.clone(),
+        last_commit: match t.last_commit_days {
+            _ if matches!(t.activity, Activity::Excluded(_)) => "not cloned".into(),
+            Some(0) => "today".into(),
+            Some(1) => "1 day ago".into(),
+            Some(days) => format!("{days} days ago"),
+            None => "unknown".into(),
+        },
+        status: t.activity.label().into(),
+        stale: matches!(
+            t.activity,
+            Activity::Downranked | Activity::Skipped | Activity::Excluded(_)
+        ),
+    }
+}
+
+fn sample_to_view(s: &PatternSample) -> SampleView {
+    let unsampled = s.total - s.sampled;
+    SampleView {
+        repo: s.repo.clone(),
+        pattern: s.pattern.clone(),
+        total: s.total,
+        sampled: s.sampled,
+        confirmed: s.confirmed,
+        disputed: s.disputed,
+        dismissed: s.dismissed,
+        precision: s
+            .precision()
+            .map(|p| format!("{:.0}%", p * 100.0))
+            .unwrap_or_else(|| "n/a".into()),
+        estimate: match s.estimated_real() {
+            Some(n) => format!("~{n} of {unsampled}"),
+            None => format!("? of {unsampled}"),
+        },
+    }
+}
+
+fn budget_views(results: &[RepoResult]) -> Vec<BudgetView> {
+    let mut views = Vec::new();
+    for n in memory::budget_by_narrative(results) {
+        views.push(BudgetView {
+            label: n
+                .narrative
+                .clone()
+                .unwrap_or_else(|| "(no narrative)".into()),
+            confidence: n
+                .confidence
+                .map(|c| format!("{:.0}%", c * 100.0))
+                .unwrap_or_else(|| "default".into()),
+            is_repo: false,
+            budget_turns: n.budget_turns,
+            spent_turns: n.spent_turns,
+            budget_cost: format!("${:.2}", n.budget_cost_usd),
+            spent_cost: format!("${:.2}", n.spent_cost_usd),
+            static_merge: String::new(),
+            static_merge_detail: String::new(),
+        });
+        for r in results {
+            let Some(split) = r.budget.as_ref().filter(|s| s.narrative == n.narrative) else {
+                continue;
+            };
+            views.push(BudgetView {
+                label: r.name.clone(),
+                confidence: String::new(),
+                is_repo: true,
+                budget_turns: split.total_turns,
+                spent_turns: split.spent_turns(),
+                budget_cost: format!("${:.2}", split.total_cost_usd),
+                spent_cost: format!("${:.2}", split.spent_cost_usd()),
+                static_merge: split
+                    .static_merge
+                    .map(|m| format!("{} / {}", m.merged, m.dropped()))
+                    .unwrap_or_default(),
+                static_merge_detail: split
+                    .static_merge
+                    .map(|m| {
+                        format!(
+                            "{} below the severity threshold, {} covered by agent findings",
+                            m.below_threshold, m.dominated
+                        )
+                    })
+                    .unwrap_or_default(),
+            });
+        }
+    }
+    views
+}
+
+fn waiver_views(results: &[RepoResult], show_paths: bool) -> Vec<WaiverView> {
+    results
+        .iter()
+        .filter(|r| !r.waived.is_empty())
+        .map(|r| {
+            let mut per_pattern: BTreeMap<&str, usize> = BTreeMap::new();
+            for w in &r.waived {
+                *per_pattern.entry(&w.pattern_id).or_default() += 1;
+            }
+            let patterns = per_pattern
+                .into_iter()
+                .map(|(id, n)| match n {
+                    1 => id.to_string(),
+                    n => format!("{id} \u{d7}{n}"),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+            let entries = if show_paths {
+                r.waived
+                    .iter()
+                    .map(|w| WaiverEntryView {
+                        location: format!(
+                            "{}:{}",
+                            crate::paths::to_slash(&w.file_path),
+                            w.line_number
+                        ),
+                        pattern_id: w.pattern_id.clone(),
+                        reason: if w.reason.is_empty() {
+                            "(no reason given)".into()
+                        } else {
+                            w.reason.clone()
+                        },
+                    })
+                    .collect()
+            } else {
+                Vec::new()
+            };
+            WaiverView {
+                repo: r.name.clone(),
+                count: r.waived.len(),
+                patterns,
+                entries,
+            }
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -409,6 +1106,7 @@ mod tests {
             remediation: "fix it".into(),
             validation_status: ValidationStatus::Unvalidated,
             validation_reasoning: None,
+            ..Default::default()
         }
     }
 
@@ -442,30 +1140,91 @@ mod tests {
     }
 
     #[test]
-    fn severity_order_values() {
-        assert_eq!(severity_order("Critical"), 0);
-        assert_eq!(severity_order("High"), 1);
-        assert_eq!(severity_order("Medium"), 2);
-        assert_eq!(severity_order("Low"), 3);
-        assert_eq!(severity_order("Info"), 4);
-        assert!(severity_order("Critical") < severity_order("High"));
+    fn location_label_prefers_instruction() {
+        let f = SecurityFinding {
+            function: Some("withdraw".into()),
+            instruction: Some("withdraw".into()),
+            ..make_test_finding()
+        };
+        assert_eq!(location_label(&f), "withdraw handler");
+        let f = SecurityFinding {
+            function: Some("helper".into()),
+            ..make_test_finding()
+        };
+        assert_eq!(location_label(&f), "fn helper");
+        let f = SecurityFinding {
+            function: Some("credit".into()),
+            entry_path: vec!["vault::deposit".into(), "deposit::credit".into()],
+            ..make_test_finding()
+        };
+        assert_eq!(
+            location_label(&f),
+            "fn credit, via vault::deposit → deposit::credit"
+        );
+        assert_eq!(location_label(&make_test_finding()), "");
     }
 
     #[test]
-    fn repo_name_from_repos_path() {
-        let f = SecurityFinding {
-            file_path: PathBuf::from("repos/my-repo/src/lib.rs"),
+    fn instructions_summarized_per_repo() {
+        let in_withdraw = |severity: &str| SecurityFinding {
+            severity: severity.into(),
+            instruction: Some("withdraw".into()),
             ..make_test_finding()
         };
-        assert_eq!(repo_name(&f), "my-repo");
+        let findings = vec![
+            in_withdraw("Medium"),
+            in_withdraw("Critical"),
+            in_withdraw("Low"),
+            make_test_finding(),
+        ];
+        let summaries = summarize_instructions(&findings);
+        assert_eq!(summaries.len(), 1);
+        assert_eq!(summaries[0].instruction, "withdraw");
+        assert_eq!(summaries[0].repo, "test-repo");
+        assert_eq!(summaries[0].count, 3);
+        assert_eq!(summaries[0].severity, "Critical");
     }
 
     #[test]
-    fn repo_name_fallback() {
-        let f = SecurityFinding {
-            file_path: PathBuf::from("src/lib.rs"),
-            ..make_test_finding()
+    fn grouping_strategies() {
+        let finding = |title: &str, pattern: Option<&str>, file: &str, ix: Option<&str>| {
+            let f = SecurityFinding {
+                title: title.into(),
+                pattern_id: pattern.map(String::from),
+                file_path: PathBuf::from(format!("repos/amm/{file}")),
+                instruction: ix.map(String::from),
+                ..make_test_finding()
+            };
+            finding_to_view(&f, String::new(), true)
         };
-        assert_eq!(repo_name(&f), "src");
+        let views = || {
+            vec![
+                finding("Missing signer", Some("SOL-001"), "src/a.rs", Some("swap")),
+                finding("Missing signer", Some("SOL-001"), "src/b.rs", Some("swap")),
+                finding("Oracle can be stale", None, "src/a.rs", Some("deposit")),
+                finding("Oracle may be stale", None, "src/a.rs", None),
+            ]
+        };
+        let counts = |by| {
+            let mut c: Vec<usize> = group_findings(views(), by)
+                .iter()
+                .map(|g| g.count)
+                .collect();
+            c.sort_unstable();
+            c
+        };
+        assert_eq!(counts(GroupBy::Title), vec![1, 1, 2]);
+        assert_eq!(counts(GroupBy::Pattern), vec![1, 1, 2]);
+        assert_eq!(counts(GroupBy::File), vec![1, 3]);
+        assert_eq!(counts(GroupBy::Instruction), vec![1, 1, 2]);
+        assert_eq!(counts(GroupBy::None), vec![1, 1, 1, 1]);
+
+        let by_file = group_findings(views(), GroupBy::File);
+        let a = by_file.iter().find(|g| g.count == 3).unwrap();
+        assert_eq!(a.badge, "3 in repos/amm/src/a.rs");
+        let by_pattern = group_findings(views(), GroupBy::Pattern);
+        assert!(by_pattern.iter().any(|g| g.badge == "2 \u{d7} SOL-001"));
+        let by_title = group_findings(views(), GroupBy::Title);
+        assert!(by_title.iter().any(|g| g.badge == "2 instances"));
     }
 }
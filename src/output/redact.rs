@@ -0,0 +1,248 @@
+//! Audience-specific report variants, so a report can be shared outside the
+//! team without hand-editing the HTML.
+//!
+//! Redaction is applied to the findings themselves before rendering, so the
+//! data embedded for `solguard extract` is redacted too.
+//!
+//! | audience   | file paths | code snippets | attack steps |
+//! |------------|------------|---------------|--------------|
+//! | `public`   | no         | no            | no           |
+//! | `partner`  | yes        | yes           | no           |
+//! | `internal` | yes        | yes           | yes          |
+//!
+//! Attack steps are the validator's reasoning and any description paragraph
+//! that opens with an attack/exploit/PoC heading.
+
+use crate::security::SecurityFinding;
+use std::fmt;
+use std::path::PathBuf;
+
+/// Who a rendered report is for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum Audience {
+    /// Responsible disclosure: no file paths, code or exploit scenarios.
+    Public,
+    /// Paths and code, but no attack steps.
+    Partner,
+    /// Everything.
+    #[default]
+    Internal,
+}
+
+impl Audience {
+    pub fn shows_paths(self) -> bool {
+        self != Audience::Public
+    }
+
+    /// Banner text for a redacted report; empty for `Internal`.
+    pub fn notice(self) -> &'static str {
+        match self {
+            Audience::Public => {
+                "Public report: file paths, code snippets and exploit scenarios have been removed."
+            }
+            Audience::Partner => "Partner report: attack steps have been removed.",
+            Audience::Internal => "",
+        }
+    }
+}
+
+impl fmt::Display for Audience {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Audience::Public => "public",
+            Audience::Partner => "partner",
+            Audience::Internal => "internal",
+        })
+    }
+}
+
+/// Labels that open an attack walkthrough ("Attack scenario:", "## Exploit").
+/// The paragraph they start is dropped for `Partner` and `Public`.
+const ATTACK_MARKERS: &[&str] = &[
+    "attack",
+    "exploit",
+    "proof of concept",
+    "poc",
+    "steps to reproduce",
+];
+
+/// Longest label (in words) still treated as a heading rather than prose.
+const MAX_LABEL_WORDS: usize = 4;
+
+/// A copy of `findings` with everything `audience` shouldn't see removed.
+pub fn redact_findings(findings: &[SecurityFinding], audience: Audience) -> Vec<SecurityFinding> {
+    findings
+        .iter()
+        .map(|f| redact_finding(f, audience))
+        .collect()
+}
+
+fn redact_finding(f: &SecurityFinding, audience: Audience) -> SecurityFinding {
+    let mut f = f.clone();
+    if audience == Audience::Internal {
+        return f;
+    }
+    f.validation_reasoning = None;
+    f.description = strip_attack_steps(&f.description);
+    f.remediation = strip_attack_steps(&f.remediation);
+    if audience == Audience::Public {
+        // Keep just the repo, which the report groups by.
+        f.file_path = PathBuf::from(crate::paths::repo_name(&f.file_path));
+        f.function = None;
+        f.instruction = None;
+        f.entry_path.clear();
+        f.description = strip_code_blocks(&f.description);
+        f.remediation = strip_code_blocks(&f.remediation);
+        f.patch = None;
+    }
+    f
+}
+
+/// A generated repo summary as `audience` may see it: attack steps removed
+/// for `Partner`, and nothing for `Public`, since summaries name the files
+/// and handlers the risk sits in.
+pub fn redact_summary(summary: &str, audience: Audience) -> Option<String> {
+    match audience {
+        Audience::Internal => Some(summary.to_string()),
+        Audience::Partner => Some(strip_attack_steps(summary)),
+        Audience::Public => None,
+    }
+}
+
+/// Drop every paragraph that starts with an [`ATTACK_MARKERS`] heading:
+/// a short label ending in `:` or a whole short line, ignoring Markdown
+/// `#`, `*`, `-` and list numbering.
+fn strip_attack_steps(text: &str) -> String {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut skipping = false;
+    for line in text.lines() {
+        if line.trim().is_empty() {
+            skipping = false;
+            kept.push(line);
+            continue;
+        }
+        if is_attack_heading(line) {
+            skipping = true;
+        }
+        if !skipping {
+            kept.push(line);
+        }
+    }
+    collapse_blank_lines(&kept)
+}
+
+fn is_attack_heading(line: &str) -> bool {
+    let markup = |c: char| c.is_whitespace() || matches!(c, '#' | '*' | '-' | '.' | '_');
+    let text = line
+        .trim_start_matches(|c: char| markup(c) || c.is_ascii_digit())
+        .to_lowercase();
+    let label = text
+        .split(':')
+        .next()
+        .unwrap_or_default()
+        .trim_matches(markup);
+    label.split_whitespace().count() <= MAX_LABEL_WORDS
+        && ATTACK_MARKERS.iter().any(|m| label.starts_with(m))
+}
+
+/// Remove fenced (```` ``` ````) code blocks.
+fn strip_code_blocks(text: &str) -> String {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if !in_fence {
+            kept.push(line);
+        }
+    }
+    collapse_blank_lines(&kept)
+}
+
+/// Join lines, squeezing the blank runs left behind by removed paragraphs.
+fn collapse_blank_lines(lines: &[&str]) -> String {
+    let mut out: Vec<&str> = Vec::new();
+    for line in lines {
+        if line.trim().is_empty() && out.last().is_none_or(|l| l.trim().is_empty()) {
+            continue;
+        }
+        out.push(line);
+    }
+    while out.last().is_some_and(|l| l.trim().is_empty()) {
+        out.pop();
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::ValidationStatus;
+
+    fn finding() -> SecurityFinding {
+        SecurityFinding {
+            title: "Missing signer check".into(),
+            severity: "Critical".into(),
+            description: "The withdraw handler never checks `authority`.\n\n\
+                          Attack scenario:\n1. Call withdraw with any authority.\n2. Drain the vault.\n\n\
+                          ```rust\npub fn withdraw(ctx: Context<W>) {}\n```"
+                .into(),
+            file_path: PathBuf::from("repos/vault/programs/vault/src/lib.rs"),
+            line_number: 42,
+            remediation: "Add `Signer<'info>` to the accounts struct.".into(),
+            validation_status: ValidationStatus::Confirmed,
+            validation_reasoning: Some("An attacker passes their own key as authority.".into()),
+            function: Some("withdraw".into()),
+            instruction: Some("withdraw".into()),
+            patch: Some("--- a/programs/vault/src/lib.rs\n".into()),
+            entry_path: vec!["vault::withdraw".into()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn internal_is_untouched() {
+        let f = finding();
+        assert_eq!(
+            serde_json::to_value(redact_finding(&f, Audience::Internal)).unwrap(),
+            serde_json::to_value(&f).unwrap()
+        );
+    }
+
+    #[test]
+    fn partner_drops_attack_steps_only() {
+        let f = redact_finding(&finding(), Audience::Partner);
+        assert_eq!(f.file_path, finding().file_path);
+        assert_eq!(f.instruction.as_deref(), Some("withdraw"));
+        assert!(f.validation_reasoning.is_none());
+        assert!(!f.description.contains("Drain the vault"));
+        assert!(f.description.contains("never checks `authority`"));
+        assert!(f.description.contains("```rust"));
+        assert!(f.patch.is_some());
+    }
+
+    #[test]
+    fn public_drops_paths_code_and_attack_steps() {
+        let f = redact_finding(&finding(), Audience::Public);
+        assert_eq!(f.file_path, PathBuf::from("vault"));
+        assert_eq!(crate::paths::repo_name(&f.file_path), "vault");
+        assert!(f.function.is_none() && f.instruction.is_none() && f.entry_path.is_empty());
+        assert!(f.validation_reasoning.is_none() && f.patch.is_none());
+        assert_eq!(
+            f.description,
+            "The withdraw handler never checks `authority`."
+        );
+        assert_eq!(f.validation_status, ValidationStatus::Confirmed);
+    }
+
+    #[test]
+    fn marker_paragraph_ends_at_blank_line() {
+        let text = "Intro.\n\n## Exploit\nStep one.\nStep two.\n\nImpact: total loss.";
+        assert_eq!(strip_attack_steps(text), "Intro.\n\nImpact: total loss.");
+        // Prose mentioning an exploit is not a heading.
+        let prose = "Exploitable by anyone who can call the withdraw handler.";
+        assert_eq!(strip_attack_steps(prose), prose);
+    }
+}
@@ -0,0 +1,160 @@
+//! Platform-agnostic path helpers. Findings, tag globs and agent tool output
+//! all use forward slashes regardless of the OS the repo was cloned on, and
+//! paths written on Windows (`repos\vault\src\lib.rs`) parse the same on Unix.
+
+use std::path::{Component, Path};
+
+/// The path as a forward-slash string. Non-UTF8 bytes are replaced, so use
+/// this for matching and display, never to reopen the file.
+pub fn to_slash(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Normal components of `path`, splitting on both separators and dropping
+/// roots, drive prefixes (`C:`) and `.`/`..`.
+pub fn components(path: &Path) -> Vec<String> {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(s) => Some(s.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .flat_map(|s| {
+            s.split(['/', '\\'])
+                .filter(|p| !p.is_empty() && *p != "." && *p != ".." && !p.ends_with(':'))
+                .map(String::from)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Repo a finding belongs to: the component after the first `repos`
+/// directory (any name ending in `repos`, e.g. `solguard-repos`), else the
+/// first component of the path.
+pub fn repo_name(path: &Path) -> String {
+    let parts = components(path);
+    if let Some(i) = parts.iter().position(|p| p.ends_with("repos"))
+        && let Some(name) = parts.get(i + 1)
+    {
+        return name.clone();
+    }
+    parts.into_iter().next().unwrap_or_else(|| "unknown".into())
+}
+
+/// Repo name of a scan target or narrative repo: `owner/name` -> `name`;
+/// bare names are returned as-is.
+pub fn target_name(target: &str) -> &str {
+    target.rsplit('/').next().unwrap_or(target)
+}
+
+/// Whether any directory of `path` (not the file name) is one of `dirs`.
+pub fn has_dir(path: &Path, dirs: &[&str]) -> bool {
+    let parts = components(path);
+    let Some((_, parent_dirs)) = parts.split_last() else {
+        return false;
+    };
+    parent_dirs.iter().any(|d| dirs.contains(&d.as_str()))
+}
+
+/// Whether repo-relative `path` matches `glob` (see [`crate::tags::glob_match`]).
+/// A leading `**/` also matches at the top level, so `**/target/**` covers
+/// `target/debug/build.rs`.
+pub fn matches_glob(glob: &str, path: &Path) -> bool {
+    let path = to_slash(path);
+    crate::tags::glob_match(glob, &path)
+        || glob
+            .strip_prefix("**/")
+            .is_some_and(|rest| crate::tags::glob_match(rest, &path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn repo_name_from_either_separator() {
+        assert_eq!(repo_name(Path::new("repos/vault/src/lib.rs")), "vault");
+        assert_eq!(repo_name(Path::new(r"repos\vault\src\lib.rs")), "vault");
+        assert_eq!(
+            repo_name(Path::new(r"C:\work\solguard-repos\vault\src\lib.rs")),
+            "vault"
+        );
+        assert_eq!(repo_name(Path::new("/abs/repos/amm/lib.rs")), "amm");
+        assert_eq!(repo_name(Path::new("./src/lib.rs")), "src");
+        assert_eq!(repo_name(Path::new("")), "unknown");
+        assert_eq!(repo_name(Path::new("lib.rs")), "lib.rs");
+    }
+
+    #[test]
+    fn target_name_drops_owner() {
+        assert_eq!(target_name("solana-labs/vault"), "vault");
+        assert_eq!(target_name("vault"), "vault");
+        assert_eq!(target_name(""), "");
+    }
+
+    #[test]
+    fn dirs_matched_by_component_not_substring() {
+        assert!(has_dir(Path::new("programs/tests/helpers.rs"), &["tests"]));
+        assert!(has_dir(Path::new(r"programs\tests\helpers.rs"), &["tests"]));
+        assert!(!has_dir(Path::new("programs/attests/lib.rs"), &["tests"]));
+        assert!(!has_dir(Path::new("tests"), &["tests"]));
+    }
+
+    #[test]
+    fn globs_match_at_any_depth() {
+        assert!(matches_glob(
+            "**/target/**",
+            Path::new("target/debug/build.rs")
+        ));
+        assert!(matches_glob(
+            "**/target/**",
+            Path::new("programs/a/target/x.rs")
+        ));
+        assert!(!matches_glob(
+            "**/test/**",
+            Path::new("programs/attest/lib.rs")
+        ));
+        assert!(matches_glob("**/*_test.rs", Path::new("src/vault_test.rs")));
+        assert!(matches_glob(
+            "programs/**",
+            Path::new(r"programs\vault\lib.rs")
+        ));
+        assert!(!matches_glob(
+            "programs/**",
+            Path::new("sdk/programs/lib.rs")
+        ));
+    }
+
+    #[test]
+    fn to_slash_normalizes_backslashes() {
+        assert_eq!(to_slash(Path::new(r"repos\a\b.rs")), "repos/a/b.rs");
+        assert_eq!(to_slash(&PathBuf::from("repos/a/b.rs")), "repos/a/b.rs");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_native_paths() {
+        let p = PathBuf::from(r"C:\Users\dev\repos\vault\programs\vault\src\lib.rs");
+        assert_eq!(repo_name(&p), "vault");
+        assert_eq!(
+            to_slash(&p),
+            "C:/Users/dev/repos/vault/programs/vault/src/lib.rs"
+        );
+        assert!(has_dir(
+            Path::new(r"vault\target\debug\build.rs"),
+            &["target"]
+        ));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_components_survive() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        let p = Path::new("repos")
+            .join(OsStr::from_bytes(b"va\xffult"))
+            .join("src/lib.rs");
+        assert_eq!(repo_name(&p), "va\u{fffd}ult");
+        assert!(!has_dir(&p, &["tests"]));
+    }
+}
@@ -0,0 +1,140 @@
+//! Live progress for library embedders. A GUI or service gets a stream of
+//! [`ProgressEvent`]s (phase, repo, turn, cost) instead of parsing tracing
+//! logs; the CLI's `run` output is just one consumer of the same stream.
+//!
+//! ```no_run
+//! # async fn demo() {
+//! let (progress, mut events) = st_solguard::progress::Progress::channel();
+//! let opts = st_solguard::agent::PipelineOptions {
+//!     progress,
+//!     ..Default::default()
+//! };
+//! tokio::spawn(async move {
+//!     while let Some(event) = events.recv().await {
+//!         println!("{event:?}");
+//!     }
+//! });
+//! # let _ = opts;
+//! # }
+//! ```
+
+use serde::Serialize;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+/// One step of a pipeline, scan or investigation.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgressEvent {
+    /// A pipeline phase (1-5) started.
+    Phase { phase: u8, name: String },
+    /// Scanning of the `index`-th (1-based) of `total` targets started.
+    RepoStarted {
+        repo: String,
+        index: usize,
+        total: usize,
+    },
+    /// A target was not scanned (clone failed, stale, not found).
+    RepoSkipped { repo: String, reason: String },
+    /// An agent investigation turn finished.
+    Turn {
+        repo: String,
+        turn: u32,
+        max_turns: u32,
+        cost_usd: f64,
+    },
+    /// The agent called a tool.
+    ToolCall { repo: String, tool: String },
+    /// A target finished scanning (and validating).
+    RepoFinished {
+        repo: String,
+        findings: usize,
+        error: Option<String>,
+    },
+    /// The report was written.
+    Finished {
+        report: String,
+        narratives: usize,
+        findings: usize,
+    },
+}
+
+/// Sending half of a progress stream. The default sends nowhere, so callers
+/// that don't care pass `&Progress::default()`.
+#[derive(Debug, Clone, Default)]
+pub struct Progress {
+    tx: Option<UnboundedSender<ProgressEvent>>,
+}
+
+impl Progress {
+    /// A progress handle and the receiver its events arrive on. Unbounded:
+    /// events are small and a slow consumer must never stall a scan.
+    pub fn channel() -> (Self, UnboundedReceiver<ProgressEvent>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        (Self { tx: Some(tx) }, rx)
+    }
+
+    /// Send an event. A dropped receiver is not an error: the run carries on.
+    pub fn emit(&self, event: ProgressEvent) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn events_arrive_in_order_and_stream_ends_with_senders() {
+        let (progress, mut rx) = Progress::channel();
+        let clone = progress.clone();
+        progress.emit(ProgressEvent::Phase {
+            phase: 3,
+            name: "scan".into(),
+        });
+        clone.emit(ProgressEvent::ToolCall {
+            repo: "vault".into(),
+            tool: "Read".into(),
+        });
+        drop(progress);
+        drop(clone);
+
+        assert!(matches!(
+            rx.recv().await,
+            Some(ProgressEvent::Phase { phase: 3, .. })
+        ));
+        assert!(matches!(
+            rx.recv().await,
+            Some(ProgressEvent::ToolCall { .. })
+        ));
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[test]
+    fn default_and_closed_receivers_are_silent() {
+        Progress::default().emit(ProgressEvent::RepoSkipped {
+            repo: "x".into(),
+            reason: "stale".into(),
+        });
+        let (progress, rx) = Progress::channel();
+        drop(rx);
+        progress.emit(ProgressEvent::RepoSkipped {
+            repo: "x".into(),
+            reason: "stale".into(),
+        });
+    }
+
+    #[test]
+    fn serializes_with_event_tag() {
+        let json = serde_json::to_value(ProgressEvent::Turn {
+            repo: "vault".into(),
+            turn: 2,
+            max_turns: 20,
+            cost_usd: 0.5,
+        })
+        .unwrap();
+        assert_eq!(json["event"], "turn");
+        assert_eq!(json["max_turns"], 20);
+    }
+}
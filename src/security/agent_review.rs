@@ -4,28 +4,43 @@
 //!
 //! The agent loop sends the conversation to the LLM, executes tool calls,
 //! appends results, and repeats until the LLM produces a final answer or
-//! a hard stop is hit (max turns, cost limit).
+//! a hard stop is hit (max turns, cost limit). Each tool call and each LLM
+//! turn also has a wall-clock timeout (`tool_timeout_secs`,
+//! `turn_timeout_secs`).
 
 use crate::config::AgentReviewConfig;
 use crate::llm::{
     ContentBlock, ConversationMessage, ConverseContext, LlmClient, Role, StopReason, Usage,
-    estimate_cost_usd,
 };
+use crate::narrative::rpc_pool::RpcPool;
+use crate::progress::{Progress, ProgressEvent};
 use crate::security::agent_tools;
+use crate::security::session::Session;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::Path;
+use std::time::Duration;
 use tracing::{info, warn};
 
 /// Context from narrative detection to focus the security scan.
+#[derive(Debug, Clone, Default)]
 pub struct ScanContext {
     pub protocol_category: Option<String>,
     pub narrative_summary: Option<String>,
     pub sibling_findings: Vec<String>,
+    /// "repo A calls repo B's program" lines for this repo, see
+    /// [`cross_repo`](super::cross_repo).
+    pub cross_repo_calls: Vec<String>,
+    /// Auditor-supplied focus areas (`investigate --focus`), listed before
+    /// any category focus.
+    pub focus: Vec<String>,
+    /// Use `focus` instead of the protocol category's exploit checklist.
+    pub focus_only: bool,
 }
 
-/// Compute investigation budget based on narrative confidence and target count.
+/// Compute a repo's deep-review budget (investigation + validation, see
+/// [`RepoBudget`]) based on narrative confidence and target count.
 pub fn compute_budget(confidence: f64, repo_count: usize) -> (u32, f64) {
     let depth = confidence * (1.0 / (repo_count as f64).sqrt());
     (
@@ -34,6 +49,58 @@ pub fn compute_budget(confidence: f64, repo_count: usize) -> (u32, f64) {
     )
 }
 
+/// Share of a repo's budget set aside for investigation. Validation gets the
+/// rest plus whatever investigation leaves unspent.
+pub const INVESTIGATION_SHARE: f64 = 0.7;
+
+/// One repo's deep-review budget, shared by investigation and validation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RepoBudget {
+    pub max_turns: u32,
+    pub cost_limit_usd: f64,
+    max_tokens: u32,
+    tool_timeout_secs: u64,
+    turn_timeout_secs: u64,
+}
+
+impl RepoBudget {
+    /// Treat `config`'s limits (e.g. from [`compute_budget`]) as the repo total.
+    pub fn new(config: &AgentReviewConfig) -> Self {
+        Self {
+            max_turns: config.max_turns,
+            cost_limit_usd: config.cost_limit_usd,
+            max_tokens: config.max_tokens,
+            tool_timeout_secs: config.tool_timeout_secs,
+            turn_timeout_secs: config.turn_timeout_secs,
+        }
+    }
+
+    /// Investigation's up-front slice of the budget.
+    pub fn investigation(&self) -> AgentReviewConfig {
+        AgentReviewConfig {
+            max_turns: ((f64::from(self.max_turns) * INVESTIGATION_SHARE).round() as u32).max(1),
+            max_tokens: self.max_tokens,
+            cost_limit_usd: self.cost_limit_usd * INVESTIGATION_SHARE,
+            tool_timeout_secs: self.tool_timeout_secs,
+            turn_timeout_secs: self.turn_timeout_secs,
+            ..Default::default()
+        }
+    }
+
+    /// Everything investigation didn't spend. Investigation only checks its
+    /// limits between turns and can overshoot, so this bottoms out at zero.
+    pub fn validation(&self, investigation: &ReviewStats) -> AgentReviewConfig {
+        AgentReviewConfig {
+            max_turns: self.max_turns.saturating_sub(investigation.turns),
+            max_tokens: self.max_tokens,
+            cost_limit_usd: (self.cost_limit_usd - investigation.total_cost_usd).max(0.0),
+            tool_timeout_secs: self.tool_timeout_secs,
+            turn_timeout_secs: self.turn_timeout_secs,
+            ..Default::default()
+        }
+    }
+}
+
 /// A verified finding from the agent review.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentFinding {
@@ -45,24 +112,79 @@ pub struct AgentFinding {
     pub remediation: String,
     pub confidence: f64,
     pub affected_files: Vec<String>,
+    /// Optional free-form labels suggested by the agent (e.g. "oracle", "admin").
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 /// Cumulative cost and usage stats for an agent review session.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ReviewStats {
     pub turns: u32,
     pub total_input_tokens: u32,
     pub total_output_tokens: u32,
     pub total_cost_usd: f64,
     pub tool_calls: u32,
+    /// Wall-clock time spent in tool calls.
+    pub tool_time: Duration,
+    /// Tool calls slower than [`agent_tools::SLOW_TOOL`], timeouts included.
+    pub slow_tool_calls: u32,
+    pub tool_timeouts: u32,
+    pub turn_timeouts: u32,
+    /// Set by the deep scan after the investigation; zero otherwise.
+    pub static_merge: super::StaticMerge,
+    /// Static findings `solguard-ignore` comments waived; set by the deep
+    /// scan.
+    pub waived: Vec<super::ignore::Waiver>,
 }
 
 impl ReviewStats {
-    fn accumulate(&mut self, usage: &Usage, model: &str) {
+    fn accumulate(&mut self, usage: &Usage, cost_usd: f64) {
         self.turns += 1;
         self.total_input_tokens += usage.input_tokens;
         self.total_output_tokens += usage.output_tokens;
-        self.total_cost_usd += estimate_cost_usd(usage, model);
+        self.total_cost_usd += cost_usd;
+    }
+
+    pub(crate) fn record_tool(&mut self, tool: &str, outcome: &agent_tools::ToolOutcome) {
+        self.tool_time += outcome.elapsed;
+        if outcome.timed_out {
+            self.tool_timeouts += 1;
+            warn!(tool, "tool call timed out");
+        }
+        if outcome.elapsed >= agent_tools::SLOW_TOOL {
+            self.slow_tool_calls += 1;
+            if !outcome.timed_out {
+                warn!(tool, secs = outcome.elapsed.as_secs_f64(), "slow tool call");
+            }
+        }
+    }
+
+    /// Add another pass's tool and timeout counts (not turns or cost).
+    pub(crate) fn add_timings(&mut self, other: &ReviewStats) {
+        self.tool_time += other.tool_time;
+        self.slow_tool_calls += other.slow_tool_calls;
+        self.tool_timeouts += other.tool_timeouts;
+        self.turn_timeouts += other.turn_timeouts;
+    }
+}
+
+/// Run one LLM turn, failing it after `limit`.
+pub(crate) async fn timed_turn<T>(
+    limit: Duration,
+    stats: &mut ReviewStats,
+    turn: impl Future<Output = crate::error::Result<T>>,
+) -> crate::error::Result<T> {
+    match tokio::time::timeout(limit, turn).await {
+        Ok(result) => result,
+        Err(_) => {
+            stats.turn_timeouts += 1;
+            Err(crate::error::Error::http(format!(
+                "LLM turn timed out after {}s",
+                limit.as_secs()
+            )))
+        }
     }
 }
 
@@ -87,6 +209,7 @@ You are looking for REAL, EXPLOITABLE vulnerabilities — not style issues or th
 - PDA derivation: are all seeds verified? Can an attacker supply a different PDA?
 - Account reinitialization: can closed accounts be reopened?
 - Remaining accounts: are they validated or used blindly?
+- Token accounts: is each one's mint and owner checked? Can a caller pass their own token account as the vault or fee destination, a token account for a mint they control, or a non-canonical account where an associated token account is expected?
 
 ### 4. Check Arithmetic Safety
 - Token amount calculations: overflow/underflow possible?
@@ -102,7 +225,8 @@ You are looking for REAL, EXPLOITABLE vulnerabilities — not style issues or th
 
 - Only report findings you can trace through actual code. NO hypothetical vulnerabilities.
 - For each finding, you MUST identify the specific file(s) and line(s) where the vulnerability exists.
-- Start by reading the top-level structure (Cargo.toml, lib.rs or main entry point) to understand the program architecture.
+- Start with `read_project_config` for the programs, their declared IDs and toolchain, then read lib.rs or the main entry point to understand the program architecture. Use `cargo_metadata` when a finding depends on which anchor-lang, SPL or solana-program version or feature flag is in use.
+- Use `program_state` on a declared program ID or account address to check what is actually deployed — whether the program is live and who can upgrade it, or what a config account holds — when that decides whether an issue is exploitable. Source is the ground truth for the vulnerability itself.
 - Follow cross-file references — most real vulnerabilities span multiple files.
 - Read complete functions, not just signatures. The vulnerability is usually in the implementation details.
 - If scanner triage results are provided, investigate each one but be skeptical — most are false positives. Verify by reading the actual code context.
@@ -121,11 +245,14 @@ When you have completed your investigation, output your findings as a JSON array
     "attack_scenario": "Step-by-step how an attacker exploits this",
     "remediation": "Specific code change to fix it",
     "confidence": 0.95,
-    "affected_files": ["programs/vault/src/processor.rs", "programs/vault/src/state.rs"]
+    "affected_files": ["programs/vault/src/processor.rs", "programs/vault/src/state.rs"],
+    "tags": ["treasury", "access-control"]
   }
 ]
 ```
 
+`tags` is optional: short lowercase labels for the affected subsystem or bug class.
+
 If you find NO real vulnerabilities, output an empty array: `[]`
 
 Confidence scale:
@@ -135,7 +262,46 @@ Confidence scale:
 - Below 0.5: Don't report it
 "#;
 
-/// Run a multi-turn agent investigation of a repository.
+/// Initial-message sections for a [`ScanContext`]: auditor focus, the
+/// protocol category's exploit checklist, narrative, sibling findings and
+/// calls to or from sibling repos' programs.
+fn context_section(ctx: &ScanContext) -> String {
+    let mut out = String::new();
+    if !ctx.focus.is_empty() {
+        out.push_str(
+            "\n\n## Auditor Focus\n\
+             The auditor asked for these areas specifically. Cover each one in depth \
+             before anything else:",
+        );
+        for area in &ctx.focus {
+            out.push_str(&format!("\n- {area}"));
+        }
+    }
+    // With nothing to replace it by, the category focus stays.
+    let replaced = ctx.focus_only && !ctx.focus.is_empty();
+    if let Some(category) = &ctx.protocol_category
+        && !replaced
+    {
+        let checklist = super::checklists::for_category(category).render();
+        out.push_str(&format!(
+            "\n\n## Protocol Context\nCategory: {category}\n{checklist}"
+        ));
+    }
+    if let Some(ref summary) = ctx.narrative_summary {
+        out.push_str(&format!("\n\nNarrative context: {summary}"));
+    }
+    if !ctx.sibling_findings.is_empty() {
+        out.push_str("\n\n## Findings from sibling repos in this narrative:");
+        for sf in &ctx.sibling_findings {
+            out.push_str(&format!("\n- {sf}"));
+        }
+    }
+    out.push_str(&super::cross_repo::context_section(&ctx.cross_repo_calls));
+    out
+}
+
+/// Run a multi-turn agent investigation of a repository, checkpointed as a
+/// [`Session`] after every turn.
 ///
 /// Returns the extracted findings and cumulative session stats.
 pub async fn investigate(
@@ -144,11 +310,9 @@ pub async fn investigate(
     config: &AgentReviewConfig,
     triage_context: Option<&str>,
     scan_context: Option<&ScanContext>,
+    rpc: &RpcPool,
+    progress: &Progress,
 ) -> Result<(Vec<AgentFinding>, ReviewStats)> {
-    let tools = agent_tools::tool_definitions();
-    let mut messages: Vec<ConversationMessage> = Vec::new();
-    let mut stats = ReviewStats::default();
-
     // Build initial user message
     let repo_name = repo_path
         .file_name()
@@ -178,73 +342,98 @@ pub async fn investigate(
         ));
     }
 
-    if let Some(ctx) = scan_context {
-        if let Some(ref category) = ctx.protocol_category {
-            let focus = match category.to_lowercase().as_str() {
-                cat if cat.contains("dex") || cat.contains("amm") || cat.contains("swap") => {
-                    "Focus areas: sandwich attack vectors, LP manipulation, price oracle dependencies, slippage calculations, front-running opportunities"
-                }
-                cat if cat.contains("lend") || cat.contains("borrow") => {
-                    "Focus areas: liquidation logic correctness, interest rate manipulation, collateral valuation, bad debt scenarios, flash loan interactions"
-                }
-                cat if cat.contains("privacy") || cat.contains("mixer") => {
-                    "Focus areas: Merkle root commitment integrity, cryptographic proof verification, nullifier handling, deposit/withdrawal privacy guarantees"
-                }
-                cat if cat.contains("stak") || cat.contains("liquid") => {
-                    "Focus areas: reward distribution fairness, unstake timing attacks, slashing condition handling, validator selection manipulation"
-                }
-                cat if cat.contains("nft") || cat.contains("market") => {
-                    "Focus areas: royalty bypass, listing/delisting race conditions, bid manipulation, metadata integrity"
-                }
-                _ => {
-                    "Focus areas: access control, fund flow authorization, state transition integrity"
-                }
-            };
-            initial_msg.push_str(&format!(
-                "\n\n## Protocol Context\nCategory: {category}\n{focus}"
-            ));
-        }
-        if let Some(ref summary) = ctx.narrative_summary {
-            initial_msg.push_str(&format!("\n\nNarrative context: {summary}"));
-        }
-        if !ctx.sibling_findings.is_empty() {
-            initial_msg.push_str("\n\n## Findings from sibling repos in this narrative:");
-            for sf in &ctx.sibling_findings {
-                initial_msg.push_str(&format!("\n- {sf}"));
-            }
-        }
+    let graph = super::trust_graph::build(&repo_name, repo_path);
+    if !graph.is_empty() {
+        initial_msg.push_str(&graph.context_section());
     }
 
-    messages.push(ConversationMessage {
-        role: Role::User,
-        content: vec![ContentBlock::Text { text: initial_msg }],
-    });
+    if let Some(ctx) = scan_context {
+        initial_msg.push_str(&context_section(ctx));
+    }
 
+    let mut session = Session::new(&repo_name, &repo_abs, llm.model(), initial_msg);
+    session.checkpoint().await;
     info!(
         repo = %repo_name,
+        session = %session.id,
         max_turns = config.max_turns,
         cost_limit = config.cost_limit_usd,
         "starting agent investigation"
     );
+    run_session(llm, session, config, rpc, progress).await
+}
 
-    // History for stuck-loop detection: (tool_name, hash_of_input)
-    let mut recent_calls: Vec<(String, u64)> = Vec::new();
+/// Continue a checkpointed investigation. `config`'s turn and cost limits
+/// apply to the turns from here on, on top of what the session already spent.
+pub async fn resume(
+    llm: &LlmClient,
+    mut session: Session,
+    config: &AgentReviewConfig,
+    rpc: &RpcPool,
+    progress: &Progress,
+) -> Result<(Vec<AgentFinding>, ReviewStats)> {
+    // The model can't be handed a conversation that ends on its own turn.
+    if session
+        .messages
+        .last()
+        .is_some_and(|m| m.role == Role::Assistant)
+    {
+        session.messages.push(ConversationMessage {
+            role: Role::User,
+            content: vec![ContentBlock::Text {
+                text: "The investigation was interrupted and resumes now with a fresh budget. \
+                       Pick up where you left off: verify anything you had not finished \
+                       tracing, then output your final findings as a JSON array."
+                    .into(),
+            }],
+        });
+    }
+    session.finished = false;
+    info!(
+        repo = %session.repo_name(),
+        session = %session.id,
+        turns_so_far = session.stats.turns,
+        spent_so_far = format!("${:.4}", session.stats.total_cost_usd),
+        max_turns = config.max_turns,
+        cost_limit = config.cost_limit_usd,
+        "resuming agent investigation"
+    );
+    run_session(llm, session, config, rpc, progress).await
+}
+
+/// The agent loop over `session`, which is saved after every turn and once
+/// more, marked finished, when findings have been extracted. `rpc` backs the
+/// `program_state` tool.
+async fn run_session(
+    llm: &LlmClient,
+    mut session: Session,
+    config: &AgentReviewConfig,
+    rpc: &RpcPool,
+    progress: &Progress,
+) -> Result<(Vec<AgentFinding>, ReviewStats)> {
+    let mut tools = agent_tools::tool_definitions();
+    tools.push(agent_tools::program_state_definition());
+    let repo_name = session.repo_name();
+    let repo_path = session.repo_path.clone();
+    let repo_path = repo_path.as_path();
+    // Limits count from where a resumed session left off.
+    let (start_turns, start_cost) = (session.stats.turns, session.stats.total_cost_usd);
 
     // Agent loop
     loop {
         // Hard stop: max turns
-        if stats.turns >= config.max_turns {
+        if session.stats.turns - start_turns >= config.max_turns {
             warn!(
-                turns = stats.turns,
+                turns = session.stats.turns,
                 "hit max turns limit, extracting findings"
             );
             break;
         }
 
         // Hard stop: cost limit
-        if stats.total_cost_usd >= config.cost_limit_usd {
+        if session.stats.total_cost_usd - start_cost >= config.cost_limit_usd {
             warn!(
-                cost = stats.total_cost_usd,
+                cost = session.stats.total_cost_usd,
                 limit = config.cost_limit_usd,
                 "hit cost limit, extracting findings"
             );
@@ -253,26 +442,40 @@ pub async fn investigate(
 
         // Send conversation to LLM
         let ctx = ConverseContext { repo_path };
-        let response = match llm
-            .converse(SYSTEM_PROMPT, &messages, &tools, Some(&ctx))
-            .await
+        let response = match timed_turn(
+            config.turn_timeout(),
+            &mut session.stats,
+            llm.converse_compacting(SYSTEM_PROMPT, &mut session.messages, &tools, Some(&ctx)),
+        )
+        .await
         {
             Ok(r) => r,
             Err(e) => {
                 warn!(error = %e, "LLM converse failed");
                 // If we already have some conversation, try to extract findings
-                if stats.turns > 0 {
+                if session.stats.turns > start_turns {
                     break;
                 }
                 return Err(e.into());
             }
         };
 
-        stats.accumulate(&response.usage, llm.model());
+        session
+            .stats
+            .accumulate(&response.usage, llm.estimate_cost(&response.usage));
+        progress.emit(ProgressEvent::Turn {
+            repo: repo_name.clone(),
+            turn: session.stats.turns - start_turns,
+            max_turns: config.max_turns,
+            cost_usd: session.stats.total_cost_usd,
+        });
 
         info!(
-            cost = format!("${:.4}", stats.total_cost_usd),
-            "turn {}/{}: {:?}", stats.turns, config.max_turns, response.stop_reason,
+            cost = format!("${:.4}", session.stats.total_cost_usd),
+            "turn {}/{}: {:?}",
+            session.stats.turns - start_turns,
+            config.max_turns,
+            response.stop_reason,
         );
 
         // Check if the response contains tool use calls
@@ -288,7 +491,7 @@ pub async fn investigate(
             .collect();
 
         // Add assistant message to history
-        messages.push(ConversationMessage {
+        session.messages.push(ConversationMessage {
             role: Role::Assistant,
             content: response.content,
         });
@@ -305,8 +508,9 @@ pub async fn investigate(
             let mut hasher = DefaultHasher::new();
             input.to_string().hash(&mut hasher);
             let input_hash = hasher.finish();
-            recent_calls.push((name.clone(), input_hash));
-            let count = recent_calls
+            session.recent_calls.push((name.clone(), input_hash));
+            let count = session
+                .recent_calls
                 .iter()
                 .filter(|(n, h)| n == name && *h == input_hash)
                 .count();
@@ -326,17 +530,18 @@ pub async fn investigate(
                     is_error: false,
                 });
             }
-            messages.push(ConversationMessage {
+            session.messages.push(ConversationMessage {
                 role: Role::User,
                 content: tool_results,
             });
+            session.checkpoint().await;
             continue;
         }
 
         // Execute tools and collect results
         let mut tool_results = Vec::new();
         for (id, name, input) in &tool_uses {
-            stats.tool_calls += 1;
+            session.stats.tool_calls += 1;
 
             // Guard: malformed tool input
             if !input.is_object() {
@@ -351,36 +556,49 @@ pub async fn investigate(
             }
 
             info!(tool = %name, "executing tool");
+            progress.emit(ProgressEvent::ToolCall {
+                repo: repo_name.clone(),
+                tool: name.clone(),
+            });
 
-            let (result, is_error) = agent_tools::dispatch(repo_path, name, input);
+            let outcome = agent_tools::dispatch_timed(
+                repo_path,
+                name,
+                input,
+                config.tool_timeout(),
+                Some(rpc),
+            )
+            .await;
+            session.stats.record_tool(name, &outcome);
 
             // Summarize result for logging
-            let summary = summarize_tool_result(name, &result);
+            let summary = summarize_tool_result(name, &outcome.result);
             info!(tool = %name, "{summary}");
 
             tool_results.push(ContentBlock::ToolResult {
                 tool_use_id: id.clone(),
-                content: result,
-                is_error,
+                content: outcome.result,
+                is_error: outcome.is_error,
             });
         }
 
         // Add tool results as user message
-        messages.push(ConversationMessage {
+        session.messages.push(ConversationMessage {
             role: Role::User,
             content: tool_results,
         });
+        session.checkpoint().await;
     }
 
     // Extract findings from the conversation
-    let mut findings = extract_findings(&messages);
+    let mut findings = extract_findings(&session.messages);
 
     // If no findings extracted and the model was still investigating (never EndTurned),
     // force one final turn asking for the summary — call converse() without tools so
     // the model MUST produce text.
     if findings.is_empty() {
         info!("no findings extracted, forcing summary turn");
-        messages.push(ConversationMessage {
+        session.messages.push(ConversationMessage {
             role: Role::User,
             content: vec![ContentBlock::Text {
                 text: "You have run out of investigation turns. Based on everything you have \
@@ -391,11 +609,22 @@ pub async fn investigate(
             }],
         });
         let ctx = ConverseContext { repo_path };
-        if let Ok(response) = llm
-            .converse(SYSTEM_PROMPT, &messages, &[], Some(&ctx))
-            .await
+        if let Ok(response) = timed_turn(
+            config.turn_timeout(),
+            &mut session.stats,
+            llm.converse_compacting(SYSTEM_PROMPT, &mut session.messages, &[], Some(&ctx)),
+        )
+        .await
         {
-            stats.accumulate(&response.usage, llm.model());
+            session
+                .stats
+                .accumulate(&response.usage, llm.estimate_cost(&response.usage));
+            progress.emit(ProgressEvent::Turn {
+                repo: repo_name.clone(),
+                turn: session.stats.turns - start_turns,
+                max_turns: config.max_turns,
+                cost_usd: session.stats.total_cost_usd,
+            });
             // Log what the model actually said for debugging
             for block in &response.content {
                 if let ContentBlock::Text { text } = block {
@@ -403,24 +632,30 @@ pub async fn investigate(
                     info!(len = text.len(), "forced summary response: {preview}");
                 }
             }
-            messages.push(ConversationMessage {
+            session.messages.push(ConversationMessage {
                 role: Role::Assistant,
                 content: response.content,
             });
-            findings = extract_findings(&messages);
+            findings = extract_findings(&session.messages);
             info!(findings = findings.len(), "forced summary extracted");
         }
     }
 
     info!(
         findings = findings.len(),
-        turns = stats.turns,
-        tool_calls = stats.tool_calls,
-        cost = format!("${:.4}", stats.total_cost_usd),
+        turns = session.stats.turns,
+        tool_calls = session.stats.tool_calls,
+        tool_secs = session.stats.tool_time.as_secs(),
+        slow_tool_calls = session.stats.slow_tool_calls,
+        tool_timeouts = session.stats.tool_timeouts,
+        turn_timeouts = session.stats.turn_timeouts,
+        cost = format!("${:.4}", session.stats.total_cost_usd),
         "agent investigation complete"
     );
 
-    Ok((findings, stats))
+    session.finished = true;
+    session.checkpoint().await;
+    Ok((findings, session.stats))
 }
 
 /// Produce a brief log-friendly summary of a tool result.
@@ -442,6 +677,27 @@ fn summarize_tool_result(tool: &str, result: &str) -> String {
             let n = result.lines().count();
             format!("{n} structure items")
         }
+        "call_graph" => {
+            let n = result.lines().filter(|l| l.starts_with("fn ")).count();
+            format!("{n} functions")
+        }
+        "read_project_config" => {
+            let n = result.matches("\"declared_ids\"").count();
+            format!("{n} programs")
+        }
+        "cargo_metadata" => {
+            let n = result.matches("\"program\":").count();
+            format!("{n} crates")
+        }
+        agent_tools::PROGRAM_STATE => {
+            if result.contains("\"deployment\"") {
+                "deployed program".into()
+            } else if result.contains("\"exists\": false") {
+                "no account".into()
+            } else {
+                "account state".into()
+            }
+        }
         _ => {
             let len = result.len();
             format!("{len} bytes")
@@ -453,7 +709,7 @@ fn summarize_tool_result(tool: &str, result: &str) -> String {
 ///
 /// Looks for JSON arrays in the last assistant text block, falling back
 /// to scanning earlier messages if the final message doesn't contain findings.
-fn extract_findings(messages: &[ConversationMessage]) -> Vec<AgentFinding> {
+pub(crate) fn extract_findings(messages: &[ConversationMessage]) -> Vec<AgentFinding> {
     // Search assistant messages in reverse order for a JSON findings array
     for msg in messages.iter().rev() {
         if msg.role != Role::Assistant {
@@ -524,8 +780,8 @@ pub fn format_triage_context(findings: &[super::SecurityFinding]) -> String {
 
     let mut ranked: Vec<_> = findings.iter().collect();
     ranked.sort_by(|a, b| {
-        super::severity_weight(&b.severity)
-            .cmp(&super::severity_weight(&a.severity))
+        crate::severity::weight(&b.severity)
+            .cmp(&crate::severity::weight(&a.severity))
             .then(a.line_number.cmp(&b.line_number))
     });
     let total = ranked.len();
@@ -559,6 +815,134 @@ mod tests {
     use crate::security::{SecurityFinding, ValidationStatus};
     use std::path::PathBuf;
 
+    // -- RepoBudget --
+
+    #[test]
+    fn repo_budget_rolls_unspent_investigation_into_validation() {
+        let budget = RepoBudget::new(&AgentReviewConfig {
+            max_turns: 20,
+            max_tokens: 4096,
+            cost_limit_usd: 10.0,
+            ..Default::default()
+        });
+        let inv = budget.investigation();
+        assert_eq!(inv.max_turns, 14);
+        assert!((inv.cost_limit_usd - 7.0).abs() < 1e-9);
+        assert_eq!(inv.max_tokens, 4096);
+
+        // Investigation used its whole slice: validation keeps the 30%.
+        let full = ReviewStats {
+            turns: 14,
+            total_cost_usd: 7.0,
+            ..Default::default()
+        };
+        let val = budget.validation(&full);
+        assert_eq!(val.max_turns, 6);
+        assert!((val.cost_limit_usd - 3.0).abs() < 1e-9);
+
+        // Early finish: the remainder rolls over.
+        let early = ReviewStats {
+            turns: 4,
+            total_cost_usd: 1.5,
+            ..Default::default()
+        };
+        let val = budget.validation(&early);
+        assert_eq!(val.max_turns, 16);
+        assert!((val.cost_limit_usd - 8.5).abs() < 1e-9);
+
+        // Overshoot never goes negative.
+        let over = ReviewStats {
+            turns: 25,
+            total_cost_usd: 12.0,
+            ..Default::default()
+        };
+        let val = budget.validation(&over);
+        assert_eq!(val.max_turns, 0);
+        assert_eq!(val.cost_limit_usd, 0.0);
+    }
+
+    // -- timeouts --
+
+    #[tokio::test]
+    async fn timeouts_and_slow_tools_are_counted() {
+        let mut stats = ReviewStats::default();
+        let ok = timed_turn(Duration::from_secs(5), &mut stats, async { Ok(1) }).await;
+        assert_eq!(ok.unwrap(), 1);
+        let hung = timed_turn(
+            Duration::from_millis(10),
+            &mut stats,
+            std::future::pending::<crate::error::Result<()>>(),
+        )
+        .await;
+        assert!(hung.unwrap_err().to_string().contains("timed out after"));
+        assert_eq!(stats.turn_timeouts, 1);
+
+        let outcome = |secs, timed_out| agent_tools::ToolOutcome {
+            result: String::new(),
+            is_error: timed_out,
+            elapsed: Duration::from_secs(secs),
+            timed_out,
+        };
+        stats.record_tool("read_file", &outcome(1, false));
+        stats.record_tool("search_code", &outcome(8, false));
+        stats.record_tool("search_code", &outcome(30, true));
+        assert_eq!(stats.tool_time, Duration::from_secs(39));
+        assert_eq!((stats.slow_tool_calls, stats.tool_timeouts), (2, 1));
+    }
+
+    // -- context_section --
+
+    #[test]
+    fn focus_listed_alongside_category() {
+        let ctx = ScanContext {
+            protocol_category: Some("Lending".into()),
+            focus: vec!["liquidation math".into(), "oracle staleness".into()],
+            ..Default::default()
+        };
+        let text = context_section(&ctx);
+        assert!(text.contains("## Auditor Focus"));
+        assert!(text.contains("\n- liquidation math\n- oracle staleness"));
+        assert!(text.contains("Category: Lending"));
+        assert!(text.find("Auditor Focus") < text.find("Protocol Context"));
+        assert!(text.contains("**Oracle price manipulation**"));
+        assert!(text.contains("Mango Markets"));
+    }
+
+    #[test]
+    fn focus_only_replaces_category() {
+        let ctx = ScanContext {
+            protocol_category: Some("Lending".into()),
+            focus: vec!["oracle staleness".into()],
+            focus_only: true,
+            ..Default::default()
+        };
+        let text = context_section(&ctx);
+        assert!(text.contains("- oracle staleness"));
+        assert!(!text.contains("Protocol Context"));
+
+        // Nothing to replace the category with: it stays.
+        let ctx = ScanContext {
+            focus: Vec::new(),
+            ..ctx
+        };
+        assert!(context_section(&ctx).contains("Category: Lending"));
+    }
+
+    #[test]
+    fn cross_repo_calls_follow_sibling_findings() {
+        let ctx = ScanContext {
+            sibling_findings: vec!["[High] Stale oracle".into()],
+            cross_repo_calls: vec![
+                "acme/vault calls acme/lending's program `Lend1` (src/cpi.rs:12)".into(),
+            ],
+            ..Default::default()
+        };
+        let text = context_section(&ctx);
+        assert!(text.find("sibling repos") < text.find("## Cross-Repo Calls"));
+        assert!(text.contains("\n- acme/vault calls acme/lending's program"));
+        assert!(!context_section(&ScanContext::default()).contains("Cross-Repo"));
+    }
+
     // -- compute_budget --
 
     #[test]
@@ -674,6 +1058,7 @@ mod tests {
             remediation: "add check".into(),
             validation_status: ValidationStatus::Unvalidated,
             validation_reasoning: None,
+            ..Default::default()
         }];
         let text = format_triage_context(&findings);
         assert!(text.contains("[High]"));
@@ -1,18 +1,32 @@
 //! Tools for the security review agent to investigate repositories.
 //!
-//! Four tools operate on a cloned repo directory: `list_files`, `read_file`,
-//! `search_code`, and `get_file_structure`. All paths are resolved relative
-//! to the repo root with traversal protection.
+//! Seven tools operate on a cloned repo directory: `list_files`, `read_file`,
+//! `search_code`, `get_file_structure`, `call_graph`, `read_project_config`
+//! and `cargo_metadata`. All paths are resolved relative to the repo root
+//! with traversal protection. An eighth, `program_state`, reads deployed
+//! account state over RPC; only the investigation agent is offered it.
+//!
+//! The agent loops run tools through [`dispatch_timed`]: the repo tools are
+//! plain blocking filesystem code, so they run on the blocking pool under a
+//! per-call timeout instead of on the async runtime.
 
 use crate::llm::ToolDef;
+use crate::narrative::rpc_pool::RpcPool;
 use serde_json::{Value, json};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
 use tracing::debug;
 use walkdir::WalkDir;
 
 /// Max chars returned from any single tool invocation.
 const MAX_RESULT_CHARS: usize = 5000;
 
+/// Tool calls taking longer than this count as slow in `ReviewStats`.
+pub const SLOW_TOOL: Duration = Duration::from_secs(5);
+
+/// Name of the RPC-backed tool, dispatched apart from the repo tools.
+pub const PROGRAM_STATE: &str = "program_state";
+
 /// Build the tool definitions sent to the LLM.
 pub fn tool_definitions() -> Vec<ToolDef> {
     vec![
@@ -101,9 +115,95 @@ pub fn tool_definitions() -> Vec<ToolDef> {
                 "required": ["path"]
             }),
         },
+        ToolDef {
+            name: "call_graph".into(),
+            description: "Look up a function in the repository's cross-file call graph: its \
+                          callers, its callees, and the shortest call chain from an instruction \
+                          handler or entrypoint. Use to check whether a suspicious helper is \
+                          actually reachable by a transaction."
+                .into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "function": {
+                        "type": "string",
+                        "description": "Function name, optionally module-qualified (e.g. 'handler' or 'deposit::handler')."
+                    }
+                },
+                "required": ["function"]
+            }),
+        },
+        ToolDef {
+            name: "read_project_config".into(),
+            description: "Summarize the project configuration in one call: each program crate \
+                          with its framework, anchor-lang version and features, cargo features, \
+                          `declare_id!` IDs (with any #[cfg] gate) and Anchor.toml ID per \
+                          cluster, plus Anchor.toml's toolchain, provider cluster and [features]. \
+                          Use instead of reading Anchor.toml and Cargo.toml files by hand."
+                .into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {}
+            }),
+        },
+        ToolDef {
+            name: "cargo_metadata".into(),
+            description: "List the Cargo workspace: each member crate (program or not) with its \
+                          [dependencies] and [features], the root [workspace.dependencies], and \
+                          the versions Cargo.lock pins for them and for solana-program, \
+                          anchor-lang, anchor-spl and spl-token(-2022). Use to tell which \
+                          Anchor/SPL versions and feature flags are in use instead of reading \
+                          every Cargo.toml."
+                .into(),
+            input_schema: json!({
+                "type": "object",
+                "properties": {
+                    "filter": {
+                        "type": "string",
+                        "description": "Only list dependencies whose name contains this, \
+                                        case-insensitive (e.g. 'spl', 'anchor'). Use on large \
+                                        workspaces."
+                    }
+                }
+            }),
+        },
     ]
 }
 
+/// Definition of [`PROGRAM_STATE`], for agent loops that have an RPC pool.
+pub fn program_state_definition() -> ToolDef {
+    ToolDef {
+        name: PROGRAM_STATE.into(),
+        description: "Fetch an account's deployed state from the configured Solana RPC \
+                      (mainnet unless [solana] says otherwise): owner, lamports, executable \
+                      and data size. For a program, its ProgramData account, last deploy slot \
+                      and upgrade authority with who holds it; for other accounts, the fields \
+                      the RPC decodes (token accounts, mints) and a hex sample of the raw data \
+                      with its 8-byte discriminator. Use to check whether a program declared \
+                      in the repo is deployed and upgradeable, or what a config or vault \
+                      account actually holds, before claiming an issue is live."
+            .into(),
+        input_schema: json!({
+            "type": "object",
+            "properties": {
+                "address": {
+                    "type": "string",
+                    "description": "Base58 account or program address (e.g. a declare_id! value)."
+                },
+                "offset": {
+                    "type": "integer",
+                    "description": "Byte offset of the raw data sample. Omit to start at 0."
+                },
+                "length": {
+                    "type": "integer",
+                    "description": "Bytes of raw data to sample (default 128, max 1024)."
+                }
+            },
+            "required": ["address"]
+        }),
+    }
+}
+
 /// Dispatch a tool call by name. Returns `(result_text, is_error)`.
 pub fn dispatch(repo_root: &Path, tool_name: &str, input: &Value) -> (String, bool) {
     // Canonicalize repo_root so strip_prefix works when the CLI receives a relative path.
@@ -116,19 +216,94 @@ pub fn dispatch(repo_root: &Path, tool_name: &str, input: &Value) -> (String, bo
         "read_file" => handle_read_file(repo_root, input),
         "search_code" => handle_search_code(repo_root, input),
         "get_file_structure" => handle_get_file_structure(repo_root, input),
+        "call_graph" => handle_call_graph(repo_root, input),
+        "read_project_config" => handle_read_project_config(repo_root),
+        "cargo_metadata" => handle_cargo_metadata(repo_root, input),
         _ => (format!("Unknown tool: {tool_name}"), true),
     }
 }
 
+/// A tool call's result and how long it took.
+#[derive(Debug)]
+pub struct ToolOutcome {
+    pub result: String,
+    pub is_error: bool,
+    pub elapsed: Duration,
+    pub timed_out: bool,
+}
+
+/// [`dispatch`] on the blocking pool, cut off after `timeout`. A call that
+/// times out can't be cancelled and finishes in the background, but the model
+/// gets a structured timeout error straight away instead of its result.
+/// [`PROGRAM_STATE`] runs on the runtime instead, through `rpc`.
+pub async fn dispatch_timed(
+    repo_root: &Path,
+    tool_name: &str,
+    input: &Value,
+    timeout: Duration,
+    rpc: Option<&RpcPool>,
+) -> ToolOutcome {
+    let started = Instant::now();
+    let outcome = match rpc.filter(|_| tool_name == PROGRAM_STATE) {
+        Some(rpc) => tokio::time::timeout(timeout, handle_program_state(rpc, input))
+            .await
+            .map(Ok),
+        None => {
+            let (root, name, args) = (
+                repo_root.to_path_buf(),
+                tool_name.to_string(),
+                input.clone(),
+            );
+            let call = tokio::task::spawn_blocking(move || dispatch(&root, &name, &args));
+            tokio::time::timeout(timeout, call).await
+        }
+    };
+    let (result, is_error, timed_out) = match outcome {
+        Ok(Ok((result, is_error))) => (result, is_error, false),
+        Ok(Err(e)) => (format!("Tool {tool_name} failed: {e}"), true, false),
+        Err(_) => (timeout_error(tool_name, timeout), true, true),
+    };
+    ToolOutcome {
+        result,
+        is_error,
+        elapsed: started.elapsed(),
+        timed_out,
+    }
+}
+
+fn timeout_error(tool_name: &str, timeout: Duration) -> String {
+    json!({
+        "error": "timeout",
+        "tool": tool_name,
+        "timeout_secs": timeout.as_secs(),
+        "message": format!(
+            "{tool_name} did not finish within {}s. Narrow the path or pattern and try again.",
+            timeout.as_secs()
+        ),
+    })
+    .to_string()
+}
+
 /// Resolve a user-provided path relative to repo root, rejecting traversal.
 fn safe_resolve(repo_root: &Path, user_path: &str) -> Result<PathBuf, String> {
     let cleaned = user_path.replace('\\', "/");
-    let cleaned = cleaned.trim_start_matches('/');
+    let cleaned = Path::new(cleaned.trim_start_matches('/'));
 
-    // Reject explicit traversal
-    if cleaned.contains("..") {
+    // Reject explicit traversal, and drive/root prefixes that `join` would
+    // treat as absolute (`C:/Windows`).
+    if cleaned
+        .components()
+        .any(|c| matches!(c, Component::ParentDir))
+    {
         return Err("Path traversal (..) is not allowed".into());
     }
+    if cleaned.has_root()
+        || cleaned
+            .components()
+            .any(|c| matches!(c, Component::Prefix(_)))
+    {
+        return Err("Absolute paths are not allowed".into());
+    }
 
     let resolved = repo_root.join(cleaned);
     let canonical_root = repo_root
@@ -189,7 +364,7 @@ fn handle_list_files(repo_root: &Path, input: &Value) -> (String, bool) {
         }
 
         if let Ok(rel) = ep.strip_prefix(repo_root) {
-            let rel_str = rel.to_string_lossy();
+            let rel_str = crate::paths::to_slash(rel);
             if let Some(pat) = pattern
                 && !rel_str.ends_with(pat.trim_start_matches('*'))
             {
@@ -276,12 +451,9 @@ fn handle_search_code(repo_root: &Path, input: &Value) -> (String, bool) {
             continue;
         }
 
-        // Skip hidden, target, test dirs
-        let path_str = ep.to_string_lossy();
-        if path_str.contains("/target/")
-            || path_str.contains("/.git/")
-            || path_str.contains("/node_modules/")
-        {
+        // Skip build output, VCS and vendored JS dirs
+        let rel = ep.strip_prefix(repo_root).unwrap_or(ep);
+        if crate::paths::has_dir(rel, &["target", ".git", "node_modules"]) {
             continue;
         }
 
@@ -297,14 +469,13 @@ fn handle_search_code(repo_root: &Path, input: &Value) -> (String, bool) {
             Err(_) => continue,
         };
 
-        let rel = ep.strip_prefix(repo_root).unwrap_or(ep);
         for (line_num, line) in content.lines().enumerate() {
             if line.contains(pattern) {
                 total_matches += 1;
                 if results.len() < max_matches {
                     results.push(format!(
                         "{}:{}: {}",
-                        rel.display(),
+                        crate::paths::to_slash(rel),
                         line_num + 1,
                         line.trim()
                     ));
@@ -399,3 +570,67 @@ fn handle_get_file_structure(repo_root: &Path, input: &Value) -> (String, bool)
         (truncate(outline.join("\n")), false)
     }
 }
+
+fn handle_call_graph(repo_root: &Path, input: &Value) -> (String, bool) {
+    let name = match input.get("function").and_then(|v| v.as_str()) {
+        Some(n) => n,
+        None => return ("Missing 'function' parameter".into(), true),
+    };
+    debug!(function = name, "call_graph");
+    match super::call_graph::build(repo_root).describe(name) {
+        Some(text) => (truncate(text), false),
+        None => (
+            format!("No function named '{name}' in the call graph"),
+            false,
+        ),
+    }
+}
+
+fn handle_cargo_metadata(repo_root: &Path, input: &Value) -> (String, bool) {
+    let filter = input["filter"].as_str().filter(|f| !f.is_empty());
+    debug!(filter, "cargo_metadata");
+    let metadata = super::cargo_metadata::read(repo_root, filter);
+    if metadata.is_empty() {
+        return (
+            "No Cargo.toml with a [package] or [workspace] found".into(),
+            false,
+        );
+    }
+    // Compact: workspace listings are long and results are capped.
+    match serde_json::to_string(&metadata) {
+        Ok(text) => (truncate(text), false),
+        Err(e) => (format!("Cannot serialize cargo metadata: {e}"), true),
+    }
+}
+
+async fn handle_program_state(rpc: &RpcPool, input: &Value) -> (String, bool) {
+    let address = match input["address"].as_str() {
+        Some(a) => a.trim(),
+        None => return ("Missing 'address' parameter".into(), true),
+    };
+    let offset = input["offset"].as_u64().unwrap_or(0) as usize;
+    let length = input["length"].as_u64().map(|n| n as usize);
+    debug!(address, "program_state");
+    match super::program_state::fetch(rpc, address, offset, length).await {
+        Ok(state) => match serde_json::to_string_pretty(&state) {
+            Ok(text) => (truncate(text), false),
+            Err(e) => (format!("Cannot serialize program state: {e}"), true),
+        },
+        Err(e) => (format!("Cannot fetch account {address}: {e}"), true),
+    }
+}
+
+fn handle_read_project_config(repo_root: &Path) -> (String, bool) {
+    debug!("read_project_config");
+    let config = super::project_config::read(repo_root);
+    if config.is_empty() {
+        return (
+            "No Anchor.toml, program crate or declare_id! found".into(),
+            false,
+        );
+    }
+    match serde_json::to_string_pretty(&config) {
+        Ok(text) => (truncate(text), false),
+        Err(e) => (format!("Cannot serialize project config: {e}"), true),
+    }
+}
@@ -1,18 +1,87 @@
 use super::{Finding, Severity};
 use quote::ToTokens;
 use std::path::Path;
-use syn::visit::Visit;
-use syn::{Attribute, File, Item, ItemFn, ItemStruct};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{Attribute, BinOp, Block, Expr, File, ImplItemFn, Item, ItemFn, ItemStruct};
+
+/// IDs of the checks the AST visitor can emit.
+pub const PATTERN_IDS: &[&str] = &[
+    "SOL-001", "AST-001", "AST-003", "AST-004", "AST-005", "AST-006", "AST-007", "AST-008",
+];
+
+/// Name segments of account fields that authorize an instruction:
+/// `authority`, `pool_admin`, `vault_owner`. An unsigned one is a likelier
+/// SOL-001 than an unsigned `user` or `payer`.
+const AUTHORITY_HINTS: &[&str] = &[
+    "authority",
+    "admin",
+    "owner",
+    "operator",
+    "manager",
+    "governor",
+    "guardian",
+];
+
+/// Ways a handler borrows an account's raw data.
+const DATA_BORROWS: &[&str] = &[
+    ".data.borrow()",
+    ".data.borrow_mut()",
+    ".try_borrow_data()",
+    ".try_borrow_mut_data()",
+];
+
+/// CPI entry points that move value whatever their path.
+const INVOKE_FNS: &[&str] = &[
+    "invoke",
+    "invoke_signed",
+    "invoke_unchecked",
+    "invoke_signed_unchecked",
+];
+
+/// Transfer helpers, counted as CPIs only when path-qualified
+/// (`token::transfer`, `system_program::transfer`) so a program's own
+/// `transfer` fn isn't mistaken for one.
+const TRANSFER_FNS: &[&str] = &["transfer", "transfer_checked"];
+
+/// Pyth and Switchboard calls that return a price.
+const PRICE_READS: &[&str] = &[
+    "get_price_unchecked",
+    "get_ema_price_unchecked",
+    "get_current_price",
+    "get_current_ema_price",
+    "get_price_no_older_than",
+    "get_ema_price_no_older_than",
+    "get_result",
+    "get_value",
+];
+
+/// Price reads that reject a stale feed themselves.
+const FRESH_PRICE_READS: &[&str] = &[
+    "get_price_no_older_than",
+    "get_ema_price_no_older_than",
+    "get_value",
+];
+
+/// Local names that suggest a token account, for `.amount` reads outside
+/// `ctx.accounts`.
+const TOKEN_ACCOUNT_HINTS: &[&str] = &["vault", "token", "ata", "reserve", "pool", "escrow"];
 
 pub fn scan(content: &str, file_path: &Path) -> anyhow::Result<Vec<Finding>> {
     let ast: File = syn::parse_str(content)?;
+    Ok(scan_file(&ast, content, file_path))
+}
+
+/// Run the visitor over an already-parsed file. `content` must be the source
+/// `ast` was parsed from, since findings take their lines from its spans.
+pub fn scan_file(ast: &File, content: &str, file_path: &Path) -> Vec<Finding> {
     let mut visitor = SolanaVisitor {
         findings: Vec::new(),
         file_path: file_path.to_path_buf(),
         source: content.to_string(),
     };
-    visitor.visit_file(&ast);
-    Ok(visitor.findings)
+    visitor.visit_file(ast);
+    visitor.findings
 }
 
 struct SolanaVisitor {
@@ -59,6 +128,15 @@ impl SolanaVisitor {
         }
 
         if let syn::Fields::Named(fields) = &item.fields {
+            let constraints: Vec<AccountConstraints> = fields
+                .named
+                .iter()
+                .map(|f| AccountConstraints::parse(&f.attrs))
+                .collect();
+            for (field, parsed) in fields.named.iter().zip(&constraints) {
+                self.check_signer(field, parsed, fields, &constraints);
+                self.check_owner(field, parsed);
+            }
             for field in &fields.named {
                 let field_str = quote::quote!(#field).to_string();
 
@@ -91,6 +169,190 @@ impl SolanaVisitor {
                     });
                 }
             }
+            self.check_duplicate_mut(fields, &constraints);
+        }
+    }
+
+    /// SOL-001: an account that nothing makes sign. A `has_one` or
+    /// `constraint` comparing its key only proves the caller knows its
+    /// address, which is public. Authority-named fields report at higher
+    /// confidence; other `AccountInfo`/`UncheckedAccount` fields are skipped
+    /// when a `/// CHECK:` comment explains them.
+    fn check_signer(
+        &mut self,
+        field: &syn::Field,
+        parsed: &AccountConstraints,
+        fields: &syn::FieldsNamed,
+        constraints: &[AccountConstraints],
+    ) {
+        let Some(ident) = &field.ident else {
+            return;
+        };
+        let name = ident.to_string();
+        let Some(wrapper) = wrapper_type(&field.ty) else {
+            return;
+        };
+        let authority = name.split('_').any(|seg| AUTHORITY_HINTS.contains(&seg));
+        let unchecked = match wrapper.as_str() {
+            "AccountInfo" | "UncheckedAccount" => authority || !has_check_comment(&field.attrs),
+            "SystemAccount" => authority,
+            _ => false,
+        };
+        if !unchecked
+            || parsed.signer
+            // A PDA authority signs through `invoke_signed`.
+            || parsed.seeds
+            || constraints
+                .iter()
+                .flat_map(|c| &c.constraints)
+                .any(|c| c.contains(&format!("{name}.is_signer")))
+        {
+            return;
+        }
+
+        let mut gates = Vec::new();
+        for (other, c) in fields.named.iter().zip(constraints) {
+            let Some(other) = &other.ident else { continue };
+            let mut how = Vec::new();
+            if c.has_one.contains(&name) {
+                how.push("has_one");
+            }
+            if c.constraints.iter().any(|e| mentions(e, &name)) {
+                how.push("constraint");
+            }
+            if c.close.as_ref() == Some(&name) {
+                how.push("close");
+            }
+            if !how.is_empty() {
+                gates.push(format!("`{other}` ({})", how.join(", ")));
+            }
+        }
+        let gated = if gates.is_empty() {
+            String::new()
+        } else {
+            format!(
+                " {} check it by key only, which any caller can supply.",
+                gates.join(", ")
+            )
+        };
+        let line = self.line_of(ident.span());
+        self.findings.push(Finding {
+            pattern_id: "SOL-001".into(),
+            title: "Missing Signer Constraint".into(),
+            description: format!(
+                "'{name}' is a {wrapper} with no `signer` constraint, so the instruction runs \
+                 without its signature.{gated}"
+            ),
+            severity: Severity::High,
+            file_path: self.file_path.clone(),
+            line_number: line,
+            code_snippet: self.snippet_at(line),
+            remediation: "Add `Signer<'info>` type or `#[account(signer)]` constraint to enforce authorization."
+                .into(),
+            confidence: if authority { 0.65 } else { 0.40 },
+            references: vec!["https://www.soldev.app/course/signer-auth".into()],
+        });
+    }
+
+    /// AST-007: an `AccountInfo`/`UncheckedAccount` whose data the file
+    /// reads, with no `owner`, `address` or `seeds` constraint pinning whose
+    /// account it is and no `.owner` check in the file.
+    fn check_owner(&mut self, field: &syn::Field, parsed: &AccountConstraints) {
+        let Some(ident) = &field.ident else {
+            return;
+        };
+        let name = ident.to_string();
+        if !matches!(
+            wrapper_type(&field.ty).as_deref(),
+            Some("AccountInfo" | "UncheckedAccount")
+        ) || parsed.owner.is_some()
+            || parsed.address.is_some()
+            || parsed.seeds
+            || parsed.init
+            || parsed
+                .constraints
+                .iter()
+                .any(|c| c.contains(&format!("{name}.owner")))
+        {
+            return;
+        }
+        let source = self.source.replace(' ', "");
+        if !DATA_BORROWS
+            .iter()
+            .any(|b| source.contains(&format!("{name}{b}")))
+            || source.contains(&format!("{name}.owner"))
+        {
+            return;
+        }
+        let line = self.line_of(ident.span());
+        self.findings.push(Finding {
+            pattern_id: "AST-007".into(),
+            title: "Unchecked Account Data Read Without Owner Constraint".into(),
+            description: format!(
+                "'{name}' is read as account data, but no `owner`, `address` or `seeds` \
+                 constraint ties it to the expected program. An attacker can pass an account \
+                 they own with the same layout."
+            ),
+            severity: Severity::High,
+            file_path: self.file_path.clone(),
+            line_number: line,
+            code_snippet: self.snippet_at(line),
+            remediation: format!(
+                "Add `owner = <program id>` to '{name}''s `#[account(...)]`, or take it as \
+                 `Account<'info, T>`, which checks the owner."
+            ),
+            confidence: 0.7,
+            references: vec!["https://www.soldev.app/course/owner-checks".into()],
+        });
+    }
+
+    /// Two `mut` fields of the same account type with no `key() !=`
+    /// constraint between them: passing one account for both lets writes
+    /// to the first be overwritten by the second.
+    fn check_duplicate_mut(
+        &mut self,
+        fields: &syn::FieldsNamed,
+        constraints: &[AccountConstraints],
+    ) {
+        let exprs: Vec<&String> = constraints.iter().flat_map(|c| &c.constraints).collect();
+        let mut seen: Vec<(&syn::Ident, String)> = Vec::new();
+        for (field, parsed) in fields.named.iter().zip(constraints) {
+            let (Some(ident), Some(ty)) = (field.ident.as_ref(), data_account_type(&field.ty))
+            else {
+                continue;
+            };
+            if !parsed.mutable {
+                continue;
+            }
+            let distinct = |other: &syn::Ident| {
+                exprs.iter().any(|c| {
+                    c.contains(&format!("{ident}.key()!={other}.key()"))
+                        || c.contains(&format!("{other}.key()!={ident}.key()"))
+                })
+            };
+            if let Some((other, _)) = seen.iter().find(|(other, t)| *t == ty && !distinct(other)) {
+                let line = self.line_of(ident.span());
+                self.findings.push(Finding {
+                    pattern_id: "AST-004".into(),
+                    title: "Duplicate Mutable Accounts".into(),
+                    description: format!(
+                        "Mutable accounts '{other}' and '{ident}' share type {ty} with no constraint \
+                         that they differ. The same account can be passed for both."
+                    ),
+                    severity: Severity::Medium,
+                    file_path: self.file_path.clone(),
+                    line_number: line,
+                    code_snippet: self.snippet_at(line),
+                    remediation: format!(
+                        "Add `constraint = {other}.key() != {ident}.key()` to one of the fields."
+                    ),
+                    confidence: 0.6,
+                    references: vec![
+                        "https://www.soldev.app/course/duplicate-mutable-accounts".into(),
+                    ],
+                });
+            }
+            seen.push((ident, ty));
         }
     }
 
@@ -115,7 +377,495 @@ impl SolanaVisitor {
                 references: vec!["CWE-119".into()],
             });
         }
+        self.check_call_sequence(&func.block);
+        self.check_oracle_use(&func.block);
+    }
+
+    /// AST-008: a Pyth or Switchboard price bound to a local and used in
+    /// arithmetic, in a body that never checks the feed's staleness or the
+    /// price's confidence interval.
+    fn check_oracle_use(&mut self, block: &Block) {
+        let mut oracle = OracleUse::default();
+        oracle.visit_block(block);
+        if oracle.reads.is_empty() {
+            return;
+        }
+        let body = compact(block);
+        for read in &oracle.reads {
+            let name = &read.binding;
+            let stale = !FRESH_PRICE_READS.contains(&read.method.as_str())
+                && !body.contains(&format!("{name}.publish_time"))
+                && !body.contains("check_staleness");
+            let wide = !body.contains(&format!("{name}.conf"))
+                && !body.contains("check_confidence_interval")
+                && !body.contains("std_deviation");
+            let missing: Vec<&str> = [
+                stale.then_some("staleness (`publish_time`)"),
+                wide.then_some("confidence interval (`conf`)"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            if missing.is_empty() {
+                continue;
+            }
+            let Some((line, expr)) = oracle.math.iter().find(|(_, e)| mentions(e, name)) else {
+                continue;
+            };
+            let line = *line;
+            self.findings.push(Finding {
+                pattern_id: "AST-008".into(),
+                title: "Oracle Price Used Without Validation".into(),
+                description: format!(
+                    "`{name}` from `.{}()` at line {} feeds `{expr}` without a check on its {}. \
+                     A stale or contested price flows straight into the amounts.",
+                    read.method,
+                    read.line,
+                    missing.join(" or ")
+                ),
+                severity: Severity::High,
+                file_path: self.file_path.clone(),
+                line_number: line,
+                code_snippet: self.snippet_at(line),
+                remediation: "Read Pyth prices with `get_price_no_older_than` (or check \
+                              `publish_time` against the clock), and reject prices whose `conf` is \
+                              wide relative to `price` before using them."
+                    .into(),
+                confidence: 0.65,
+                references: vec!["https://docs.pyth.network/price-feeds/best-practices".into()],
+            });
+        }
+    }
+
+    /// Checks-effects-interactions: state written after a value-moving CPI
+    /// (AST-005), and token balances read after one without `reload()`
+    /// (AST-006). Solana forbids reentering the caller, but a CPI into an
+    /// attacker-chosen program or a token-2022 transfer hook still runs
+    /// foreign code against half-updated state, and Anchor's deserialized
+    /// token accounts don't see the balance change until reloaded.
+    fn check_call_sequence(&mut self, block: &Block) {
+        let mut seq = Sequence::default();
+        seq.visit_block(block);
+
+        let first_cpi = seq.steps.iter().position(|s| matches!(s, Step::Cpi { .. }));
+        if let Some(i) = first_cpi
+            && let Step::Cpi { line, call } = &seq.steps[i]
+            && let Some((write_line, target)) = seq.steps[i + 1..].iter().find_map(|s| match s {
+                Step::Write { line, target } => Some((*line, target)),
+                _ => None,
+            })
+        {
+            let line = *line;
+            self.findings.push(Finding {
+                pattern_id: "AST-005".into(),
+                title: "State Updated After CPI Transfer".into(),
+                description: format!(
+                    "`{call}` moves value before `{target}` is written at line {write_line}. \
+                     Code reached through the CPI sees the pre-transfer state."
+                ),
+                severity: Severity::Medium,
+                file_path: self.file_path.clone(),
+                line_number: line,
+                code_snippet: self.snippet_at(line),
+                remediation: "Update balances and flags before the CPI (checks-effects-interactions), \
+                              or hold a reentrancy flag across it."
+                    .into(),
+                confidence: 0.6,
+                references: vec![
+                    "https://docs.soliditylang.org/en/latest/security-considerations.html#use-the-checks-effects-interactions-pattern".into(),
+                ],
+            });
+        }
+
+        let mut last_cpi: Option<&str> = None;
+        let mut reloaded: Vec<&str> = Vec::new();
+        let mut flagged: Vec<&str> = Vec::new();
+        for step in &seq.steps {
+            match step {
+                Step::Cpi { call, .. } => {
+                    last_cpi = Some(call);
+                    reloaded.clear();
+                }
+                Step::Reload { account } => reloaded.push(account),
+                Step::BalanceRead { line, account } => {
+                    let Some(call) = last_cpi else { continue };
+                    if reloaded.contains(&account.as_str()) || flagged.contains(&account.as_str()) {
+                        continue;
+                    }
+                    flagged.push(account);
+                    let line = *line;
+                    self.findings.push(Finding {
+                        pattern_id: "AST-006".into(),
+                        title: "Token Balance Read After CPI".into(),
+                        description: format!(
+                            "`{account}.amount` is read after `{call}` without `{account}.reload()`. \
+                             The deserialized account still holds the pre-CPI balance."
+                        ),
+                        severity: Severity::High,
+                        file_path: self.file_path.clone(),
+                        line_number: line,
+                        code_snippet: self.snippet_at(line),
+                        remediation: format!(
+                            "Call `{account}.reload()?` after the CPI, or compute the expected \
+                             balance from the amount transferred."
+                        ),
+                        confidence: 0.7,
+                        references: vec![
+                            "https://www.anchor-lang.com/docs/references/account-types".into(),
+                        ],
+                    });
+                }
+                Step::Write { .. } => {}
+            }
+        }
+    }
+}
+
+/// A side effect of a function body, in source order.
+#[derive(Debug)]
+enum Step {
+    /// Value-moving CPI, by callee as written.
+    Cpi { line: usize, call: String },
+    /// Assignment through a field or deref, e.g. `state.total += amount`.
+    Write { line: usize, target: String },
+    /// `.amount` read on a token account.
+    BalanceRead { line: usize, account: String },
+    /// `account.reload()`.
+    Reload { account: String },
+}
+
+/// Collects the [`Step`]s of one function body. Call arguments and
+/// assignment right-hand sides are visited first, since they run first.
+#[derive(Default)]
+struct Sequence {
+    steps: Vec<Step>,
+}
+
+impl Sequence {
+    fn write(&mut self, target: &Expr) {
+        if matches!(target, Expr::Field(_) | Expr::Unary(_)) {
+            self.steps.push(Step::Write {
+                line: target.span().start().line,
+                target: compact(target),
+            });
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for Sequence {
+    // Nested fns are checked on their own.
+    fn visit_item(&mut self, _: &'ast Item) {}
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        visit::visit_expr_call(self, node);
+        let Expr::Path(path) = &*node.func else {
+            return;
+        };
+        let segments = &path.path.segments;
+        let Some(last) = segments.last() else {
+            return;
+        };
+        let name = last.ident.to_string();
+        if INVOKE_FNS.contains(&name.as_str())
+            || (segments.len() > 1 && TRANSFER_FNS.contains(&name.as_str()))
+        {
+            self.steps.push(Step::Cpi {
+                line: last.ident.span().start().line,
+                call: compact(&node.func),
+            });
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        visit::visit_expr_method_call(self, node);
+        let name = node.method.to_string();
+        if INVOKE_FNS.contains(&name.as_str()) {
+            self.steps.push(Step::Cpi {
+                line: node.method.span().start().line,
+                call: format!(".{name}()"),
+            });
+        } else if name == "reload" {
+            self.steps.push(Step::Reload {
+                account: compact(&node.receiver),
+            });
+        }
+    }
+
+    fn visit_expr_assign(&mut self, node: &'ast syn::ExprAssign) {
+        self.visit_expr(&node.right);
+        self.write(&node.left);
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        let compound = matches!(
+            node.op,
+            BinOp::AddAssign(_)
+                | BinOp::SubAssign(_)
+                | BinOp::MulAssign(_)
+                | BinOp::DivAssign(_)
+                | BinOp::RemAssign(_)
+        );
+        if compound {
+            self.visit_expr(&node.right);
+            self.write(&node.left);
+        } else {
+            visit::visit_expr_binary(self, node);
+        }
+    }
+
+    fn visit_expr_field(&mut self, node: &'ast syn::ExprField) {
+        visit::visit_expr_field(self, node);
+        if !matches!(&node.member, syn::Member::Named(m) if m == "amount") {
+            return;
+        }
+        let account = compact(&node.base);
+        let name = account
+            .rsplit('.')
+            .next()
+            .unwrap_or(&account)
+            .to_lowercase();
+        if account.starts_with("ctx.accounts.")
+            || TOKEN_ACCOUNT_HINTS.iter().any(|h| name.contains(h))
+        {
+            self.steps.push(Step::BalanceRead {
+                line: node.member.span().start().line,
+                account,
+            });
+        }
+    }
+}
+
+/// A price read bound to a local: `let price = feed.get_price_unchecked();`.
+#[derive(Debug)]
+struct PriceRead {
+    binding: String,
+    method: String,
+    line: usize,
+}
+
+/// Collects the price reads of one function body and the arithmetic
+/// (operators and `checked_*`/`saturating_*` calls) that might use them.
+#[derive(Default)]
+struct OracleUse {
+    reads: Vec<PriceRead>,
+    math: Vec<(usize, String)>,
+}
+
+impl OracleUse {
+    /// The price read `expr` ends in, looking through `?`, casts and the
+    /// receivers of `.unwrap()`-like calls.
+    fn price_read(expr: &Expr) -> Option<&syn::ExprMethodCall> {
+        match expr {
+            Expr::Try(e) => Self::price_read(&e.expr),
+            Expr::Cast(e) => Self::price_read(&e.expr),
+            Expr::Paren(e) => Self::price_read(&e.expr),
+            Expr::MethodCall(call) if PRICE_READS.contains(&call.method.to_string().as_str()) => {
+                Some(call)
+            }
+            Expr::MethodCall(call) => Self::price_read(&call.receiver),
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for OracleUse {
+    fn visit_item(&mut self, _: &'ast Item) {}
+
+    fn visit_local(&mut self, node: &'ast syn::Local) {
+        visit::visit_local(self, node);
+        let pat = match &node.pat {
+            syn::Pat::Type(t) => &*t.pat,
+            pat => pat,
+        };
+        if let syn::Pat::Ident(ident) = pat
+            && let Some(init) = &node.init
+            && let Some(call) = Self::price_read(&init.expr)
+        {
+            self.reads.push(PriceRead {
+                binding: ident.ident.to_string(),
+                method: call.method.to_string(),
+                line: call.method.span().start().line,
+            });
+        }
+    }
+
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        visit::visit_expr_binary(self, node);
+        if matches!(
+            node.op,
+            BinOp::Add(_)
+                | BinOp::Sub(_)
+                | BinOp::Mul(_)
+                | BinOp::Div(_)
+                | BinOp::AddAssign(_)
+                | BinOp::SubAssign(_)
+                | BinOp::MulAssign(_)
+                | BinOp::DivAssign(_)
+        ) {
+            self.math.push((node.span().start().line, compact(node)));
+        }
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        visit::visit_expr_method_call(self, node);
+        let name = node.method.to_string();
+        if name.starts_with("checked_") || name.starts_with("saturating_") {
+            self.math
+                .push((node.method.span().start().line, compact(node)));
+        }
+    }
+}
+
+/// Tokens of `tokens` with whitespace removed: `ctx.accounts.vault`.
+fn compact(tokens: &impl ToTokens) -> String {
+    tokens.to_token_stream().to_string().replace(' ', "")
+}
+
+/// `Account<'info, T>`-like field types, rendered without lifetimes so two
+/// fields of the same data type compare equal.
+fn data_account_type(ty: &syn::Type) -> Option<String> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let last = path.path.segments.last()?;
+    if !matches!(
+        last.ident.to_string().as_str(),
+        "Account" | "AccountLoader" | "InterfaceAccount"
+    ) {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &last.arguments else {
+        return None;
+    };
+    let inner = args.args.iter().find_map(|a| match a {
+        syn::GenericArgument::Type(t) => Some(t.to_token_stream().to_string().replace(' ', "")),
+        _ => None,
+    })?;
+    Some(format!("{}<{inner}>", last.ident))
+}
+
+/// Outer type of a field: `Account`, `AccountInfo`, `Signer`.
+fn wrapper_type(ty: &syn::Type) -> Option<String> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    Some(path.path.segments.last()?.ident.to_string())
+}
+
+/// Whether a field's doc comment carries an Anchor `CHECK:` note.
+fn has_check_comment(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        attr.path().is_ident("doc") && attr.to_token_stream().to_string().contains("CHECK")
+    })
+}
+
+/// Whether `expr` (whitespace removed) uses the identifier `name`.
+fn mentions(expr: &str, name: &str) -> bool {
+    expr.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|t| t == name)
+}
+
+/// The constraints of a field's `#[account(...)]` attributes that decide
+/// who can pass what account. Values are whitespace-free token text.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct AccountConstraints {
+    pub mutable: bool,
+    pub signer: bool,
+    /// `init`, `init_if_needed` or `zero`: created by this instruction.
+    pub init: bool,
+    /// Fields named by `has_one = ..`.
+    pub has_one: Vec<String>,
+    /// `seeds = [..]`: a PDA, of this program unless `seeds::program` says
+    /// otherwise.
+    pub seeds: bool,
+    pub owner: Option<String>,
+    pub address: Option<String>,
+    /// `constraint = ..` expressions, without their `@ Error`.
+    pub constraints: Vec<String>,
+    /// Field receiving the lamports of `close = ..`.
+    pub close: Option<String>,
+}
+
+impl AccountConstraints {
+    /// Parse every `#[account(...)]` in `attrs`. Token-level, since
+    /// constraint expressions (`@ ErrorCode::X`) aren't `Meta`; constraints
+    /// it doesn't know (`space`, `payer`, `token::mint`) are skipped.
+    pub fn parse(attrs: &[Attribute]) -> Self {
+        let mut parsed = Self::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("account")) {
+            let Ok(list) = attr.meta.require_list() else {
+                continue;
+            };
+            for item in split_top_level(list.tokens.clone()) {
+                parsed.add(&item);
+            }
+        }
+        parsed
     }
+
+    /// One comma-separated constraint: `key` or `key = value @ Error`.
+    fn add(&mut self, item: &[proc_macro2::TokenTree]) {
+        use proc_macro2::TokenTree;
+        let eq = item
+            .iter()
+            .position(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == '=' && p.spacing() == proc_macro2::Spacing::Alone));
+        let (key, value) = match eq {
+            Some(i) => (&item[..i], Some(&item[i + 1..])),
+            None => (item, None),
+        };
+        // `signer @ ErrorCode::X` carries an error without a value.
+        let key_end = key
+            .iter()
+            .position(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == '@'))
+            .unwrap_or(key.len());
+        let key = tokens_text(&key[..key_end]);
+        let value = value.map(|v| {
+            let end = v
+                .iter()
+                .position(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == '@'))
+                .unwrap_or(v.len());
+            tokens_text(&v[..end])
+        });
+        match (key.as_str(), value) {
+            ("mut", _) => self.mutable = true,
+            ("signer", _) => self.signer = true,
+            ("init" | "init_if_needed" | "zero", _) => {
+                self.init = true;
+                self.mutable = true;
+            }
+            ("has_one", Some(v)) => self.has_one.push(v),
+            ("seeds", _) => self.seeds = true,
+            ("owner", Some(v)) => self.owner = Some(v),
+            ("address", Some(v)) => self.address = Some(v),
+            ("constraint", Some(v)) => self.constraints.push(v),
+            ("close", Some(v)) => {
+                self.close = Some(v);
+                self.mutable = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// `tokens` split at commas outside any group.
+fn split_top_level(tokens: proc_macro2::TokenStream) -> Vec<Vec<proc_macro2::TokenTree>> {
+    let mut items = vec![Vec::new()];
+    for token in tokens {
+        match &token {
+            proc_macro2::TokenTree::Punct(p) if p.as_char() == ',' => items.push(Vec::new()),
+            _ => items.last_mut().expect("never empty").push(token),
+        }
+    }
+    items.retain(|i| !i.is_empty());
+    items
+}
+
+fn tokens_text(tokens: &[proc_macro2::TokenTree]) -> String {
+    tokens
+        .iter()
+        .map(|t| t.to_string())
+        .collect::<String>()
+        .replace(' ', "")
 }
 
 impl<'ast> Visit<'ast> for SolanaVisitor {
@@ -130,6 +880,13 @@ impl<'ast> Visit<'ast> for SolanaVisitor {
         }
         syn::visit::visit_item(self, node);
     }
+
+    // Native processors keep their handlers in `impl Processor`.
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.check_call_sequence(&node.block);
+        self.check_oracle_use(&node.block);
+        syn::visit::visit_impl_item_fn(self, node);
+    }
 }
 
 #[cfg(test)]
@@ -206,6 +963,304 @@ fn safe_function() {
         );
     }
 
+    #[test]
+    fn state_write_after_cpi() {
+        let code = r#"
+impl Processor {
+    fn process_withdraw(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+        let mut state = Vault::try_from_slice(&vault.data.borrow())?;
+        invoke_signed(&ix, accounts, &[seeds])?;
+        state.balance -= amount;
+        Ok(())
+    }
+}
+
+fn transfer(from: &mut Ledger, amount: u64) {
+    from.balance -= amount;
+    transfer(from, amount);
+}
+"#;
+        let findings = scan(code, Path::new("test.rs")).unwrap();
+        let cei: Vec<_> = findings
+            .iter()
+            .filter(|f| f.pattern_id == "AST-005")
+            .collect();
+        assert_eq!(cei.len(), 1, "{findings:?}");
+        assert_eq!(cei[0].line_number, 5);
+        assert!(cei[0].description.contains("`state.balance`"));
+        assert!(cei[0].description.contains("line 6"));
+    }
+
+    #[test]
+    fn balance_read_after_cpi_until_reload() {
+        let code = r#"
+pub fn swap(ctx: Context<Swap>, amount_in: u64, args: SwapArgs) -> Result<()> {
+    let before = ctx.accounts.pool_vault.amount;
+    token::transfer(ctx.accounts.deposit_ctx(), amount_in)?;
+    let minimum = args.amount;
+    ctx.accounts.user_token.reload()?;
+    let received = ctx.accounts.user_token.amount;
+    let after = ctx.accounts.pool_vault.amount;
+    let again = ctx.accounts.pool_vault.amount;
+    Ok(())
+}
+"#;
+        let findings = scan(code, Path::new("test.rs")).unwrap();
+        let stale: Vec<_> = findings
+            .iter()
+            .filter(|f| f.pattern_id == "AST-006")
+            .collect();
+        assert_eq!(stale.len(), 1, "{findings:?}");
+        assert_eq!(stale[0].line_number, 8);
+        assert!(stale[0].description.contains("ctx.accounts.pool_vault"));
+    }
+
+    fn field_attrs(code: &str) -> Vec<AccountConstraints> {
+        let item: ItemStruct = syn::parse_str(code).unwrap();
+        item.fields
+            .iter()
+            .map(|f| AccountConstraints::parse(&f.attrs))
+            .collect()
+    }
+
+    #[test]
+    fn parses_account_constraints() {
+        let parsed = field_attrs(
+            r#"
+struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"vault", pool.key().as_ref()],
+        bump = vault.bump,
+        has_one = pool @ ErrorCode::WrongPool,
+        has_one = authority,
+        constraint = vault.amount >= min_out @ ErrorCode::Slippage,
+        close = authority,
+    )]
+    vault: Account<'info, Vault>,
+    #[account(signer @ ErrorCode::NotSigner, owner = token::ID, address = ADMIN)]
+    authority: AccountInfo<'info>,
+    #[account(init, payer = authority, space = 8 + 32)]
+    receipt: Account<'info, Receipt>,
+}
+"#,
+        );
+        assert_eq!(
+            parsed[0],
+            AccountConstraints {
+                mutable: true,
+                seeds: true,
+                has_one: vec!["pool".into(), "authority".into()],
+                constraints: vec!["vault.amount>=min_out".into()],
+                close: Some("authority".into()),
+                ..Default::default()
+            }
+        );
+        assert!(parsed[1].signer && !parsed[1].mutable);
+        assert_eq!(parsed[1].owner.as_deref(), Some("token::ID"));
+        assert_eq!(parsed[1].address.as_deref(), Some("ADMIN"));
+        assert!(parsed[2].init && parsed[2].mutable && !parsed[2].signer);
+    }
+
+    fn signer_lines(code: &str) -> Vec<usize> {
+        scan(code, Path::new("test.rs"))
+            .unwrap()
+            .into_iter()
+            .filter(|f| f.pattern_id == "SOL-001")
+            .map(|f| f.line_number)
+            .collect()
+    }
+
+    #[test]
+    fn missing_signer_from_parsed_constraints() {
+        let code = r#"
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut, has_one = authority, close = authority)]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: compared against vault.authority by has_one
+    pub authority: AccountInfo<'info>,
+    pub pool_admin: UncheckedAccount<'info>,
+    #[account(signer)]
+    pub owner: AccountInfo<'info>,
+    #[account(seeds = [b"authority"], bump)]
+    pub program_authority: UncheckedAccount<'info>,
+    #[account(constraint = operator.is_signer)]
+    pub operator: AccountInfo<'info>,
+    pub manager: Signer<'info>,
+    pub recipient: AccountInfo<'info>,
+}
+"#;
+        assert_eq!(signer_lines(code), [7, 8, 16]);
+        let findings = scan(code, Path::new("test.rs")).unwrap();
+        let authority = findings
+            .iter()
+            .find(|f| f.pattern_id == "SOL-001" && f.line_number == 7)
+            .unwrap();
+        assert!(
+            authority
+                .description
+                .contains("`vault` (has_one, close) check it by key only"),
+            "{}",
+            authority.description
+        );
+
+        // Not an Accounts struct.
+        assert!(
+            signer_lines("pub struct Args<'info> { pub authority: AccountInfo<'info> }").is_empty()
+        );
+    }
+
+    fn accounts(fields: &str) -> String {
+        format!("#[derive(Accounts)]\npub struct Withdraw<'info> {{\n{fields}\n}}")
+    }
+
+    #[test]
+    fn sol_001_positive() {
+        let findings = scan(
+            &accounts("pub authority: AccountInfo<'info>,"),
+            Path::new("test.rs"),
+        )
+        .unwrap();
+        assert!(
+            findings.iter().any(|f| f.pattern_id == "SOL-001"
+                && f.severity == Severity::High
+                && f.confidence == 0.65),
+            "expected SOL-001 High finding, got: {findings:?}"
+        );
+    }
+
+    #[test]
+    fn sol_001_negative() {
+        assert!(signer_lines(&accounts("pub authority: Signer<'info>,")).is_empty());
+        assert!(
+            signer_lines(&accounts(
+                "#[account(signer)]\npub authority: AccountInfo<'info>,"
+            ))
+            .is_empty()
+        );
+    }
+
+    #[test]
+    fn sol_001_unsigned_accounts_without_authority_names() {
+        for name in ["user", "payer", "signer", "creator"] {
+            for wrapper in ["AccountInfo", "UncheckedAccount"] {
+                let code = accounts(&format!("pub {name}: {wrapper}<'info>,"));
+                let findings = scan(&code, Path::new("test.rs")).unwrap();
+                let finding = findings
+                    .iter()
+                    .find(|f| f.pattern_id == "SOL-001")
+                    .unwrap_or_else(|| panic!("expected SOL-001 for {name}: {wrapper}"));
+                assert_eq!(finding.confidence, 0.40, "{name}: {wrapper}");
+            }
+        }
+        // A named system account is usually a lamport destination.
+        assert!(signer_lines(&accounts("pub user: SystemAccount<'info>,")).is_empty());
+        assert_eq!(
+            signer_lines(&accounts("pub admin: SystemAccount<'info>,")),
+            [3]
+        );
+    }
+
+    #[test]
+    fn sol_001_suppressed_by_check_doc_comment() {
+        let code = accounts(
+            "/// CHECK: validated by the instruction handler\npub user: AccountInfo<'info>,",
+        );
+        assert!(
+            signer_lines(&code).is_empty(),
+            "SOL-001 should be suppressed when CHECK doc comment is present"
+        );
+        // An authority still has to sign, whatever the comment says.
+        let code = accounts(
+            "/// CHECK: validated by the instruction handler\npub authority: AccountInfo<'info>,",
+        );
+        assert_eq!(signer_lines(&code), [4]);
+    }
+
+    #[test]
+    fn account_data_read_without_owner_constraint() {
+        let code = r#"
+#[derive(Accounts)]
+pub struct Read<'info> {
+    /// CHECK: deserialized below
+    pub oracle: AccountInfo<'info>,
+    /// CHECK: owner pinned
+    #[account(owner = pyth::ID)]
+    pub feed: AccountInfo<'info>,
+    /// CHECK: passed through to the CPI
+    pub metadata: UncheckedAccount<'info>,
+}
+
+pub fn read(ctx: Context<Read>) -> Result<()> {
+    let oracle = Oracle::try_from_slice(&ctx.accounts.oracle.data.borrow())?;
+    let feed = Feed::try_from_slice(&ctx.accounts.feed.try_borrow_data()?)?;
+    Ok(())
+}
+"#;
+        let findings = scan(code, Path::new("test.rs")).unwrap();
+        let owner: Vec<usize> = findings
+            .iter()
+            .filter(|f| f.pattern_id == "AST-007")
+            .map(|f| f.line_number)
+            .collect();
+        assert_eq!(owner, [5], "{findings:?}");
+
+        let checked = code.replace(
+            "Ok(())",
+            "require_keys_eq!(*ctx.accounts.oracle.owner, ID);\n    Ok(())",
+        );
+        assert!(
+            !scan(&checked, Path::new("test.rs"))
+                .unwrap()
+                .iter()
+                .any(|f| f.pattern_id == "AST-007")
+        );
+    }
+
+    #[test]
+    fn oracle_price_used_without_validation() {
+        let oracle = |checks: &str| {
+            let code = format!(
+                "pub fn borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {{
+    let price = ctx.accounts.price_feed.get_price_unchecked();
+    {checks}
+    let value = (price.price as u64).checked_mul(amount).unwrap();
+    Ok(())
+}}"
+            );
+            scan(&code, Path::new("test.rs"))
+                .unwrap()
+                .into_iter()
+                .filter(|f| f.pattern_id == "AST-008")
+                .collect::<Vec<_>>()
+        };
+        let findings = oracle("");
+        assert_eq!(findings.len(), 1, "{findings:?}");
+        assert_eq!(findings[0].line_number, 4);
+        assert!(findings[0].description.contains("staleness"));
+        assert!(findings[0].description.contains("confidence interval"));
+
+        let fresh = oracle("require!(now - price.publish_time < MAX_AGE, Stale);");
+        assert!(!fresh[0].description.contains("staleness"));
+        assert!(
+            oracle(
+                "require!(now - price.publish_time < MAX_AGE, Stale);
+    require!(price.conf < MAX_CONF, Wide);"
+            )
+            .is_empty()
+        );
+
+        // Read but never used in math: nothing to flag.
+        let unused = "fn log(feed: &PriceFeed) { let price = feed.get_price_unchecked(); msg!(\"{}\", price.price); }";
+        assert!(
+            !scan(unused, Path::new("test.rs"))
+                .unwrap()
+                .iter()
+                .any(|f| f.pattern_id == "AST-008")
+        );
+    }
+
     #[test]
     fn unparseable_returns_err() {
         assert!(scan("fn {{{", Path::new("test.rs")).is_err());
@@ -0,0 +1,190 @@
+//! Scan baselines (`scan --baseline baseline.json`): accept the findings a
+//! repo has today and report only new ones on later scans.
+//!
+//! A finding's baseline fingerprint is the one run memory gives finding IDs
+//! ([`SecurityFinding::fingerprint_at`] on the repo-relative file), so
+//! reformatting or moving code within a file doesn't resurface it. Identical
+//! findings in one file are counted, so a second copy is still new.
+
+use super::SecurityFinding;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub created_at: String,
+    pub fingerprints: BTreeSet<String>,
+}
+
+/// What [`apply`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// No baseline existed; one was written from these findings.
+    Recorded(usize),
+    /// Findings already in the baseline were dropped.
+    Filtered { suppressed: usize },
+}
+
+impl Baseline {
+    /// A baseline accepting every finding in `findings`.
+    pub fn record(findings: &[SecurityFinding], repo_path: &Path) -> Self {
+        Self {
+            created_at: Utc::now().to_rfc3339(),
+            fingerprints: fingerprints(findings, repo_path).into_iter().collect(),
+        }
+    }
+
+    /// `None` when `path` doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json =
+            std::fs::read_to_string(path).with_context(|| format!("reading {}", path.display()))?;
+        serde_json::from_str(&json)
+            .map(Some)
+            .with_context(|| format!("parsing baseline {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        crate::output::file::write_atomic(path, serde_json::to_string_pretty(self)?.as_bytes())
+            .with_context(|| format!("writing {}", path.display()))
+    }
+
+    /// `findings` minus the ones in the baseline.
+    pub fn retain_new(
+        &self,
+        findings: Vec<SecurityFinding>,
+        repo_path: &Path,
+    ) -> Vec<SecurityFinding> {
+        let prints = fingerprints(&findings, repo_path);
+        findings
+            .into_iter()
+            .zip(prints)
+            .filter(|(_, fp)| !self.fingerprints.contains(fp))
+            .map(|(f, _)| f)
+            .collect()
+    }
+}
+
+/// Filter `findings` through the baseline at `path`, or record one there if
+/// there is none yet (every finding is then reported).
+pub fn apply(
+    path: &Path,
+    repo_path: &Path,
+    findings: &mut Vec<SecurityFinding>,
+) -> Result<Outcome> {
+    match Baseline::load(path)? {
+        Some(baseline) => {
+            let before = findings.len();
+            *findings = baseline.retain_new(std::mem::take(findings), repo_path);
+            Ok(Outcome::Filtered {
+                suppressed: before - findings.len(),
+            })
+        }
+        None => {
+            Baseline::record(findings, repo_path).save(path)?;
+            Ok(Outcome::Recorded(findings.len()))
+        }
+    }
+}
+
+/// Baseline fingerprint of each finding, in order. Keyed without a repo
+/// name, so checkouts of the same repo in different directories agree.
+pub fn fingerprints(findings: &[SecurityFinding], repo_path: &Path) -> Vec<String> {
+    let root = repo_path
+        .canonicalize()
+        .unwrap_or_else(|_| repo_path.to_path_buf());
+    let mut prints = vec![String::new(); findings.len()];
+    for (i, fingerprint) in crate::memory::fingerprints_under("", Some(&root), findings) {
+        prints[i] = fingerprint;
+    }
+    prints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn finding(repo: &Path, line: usize) -> SecurityFinding {
+        SecurityFinding {
+            title: "Unvalidated remaining_accounts Usage".into(),
+            pattern_id: Some("SOL-004".into()),
+            file_path: repo.join("src/lib.rs"),
+            line_number: line,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn survives_moves_and_reformatting() {
+        let repo = std::env::temp_dir().join(format!("solguard-baseline-{}", std::process::id()));
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        let lib = repo.join("src/lib.rs");
+        std::fs::write(
+            &lib,
+            "fn a() {\n    let x = ctx.remaining_accounts[0];\n}\n",
+        )
+        .unwrap();
+        let baseline = Baseline::record(&[finding(&repo, 2)], &repo);
+
+        // Moved down and reformatted: still baselined.
+        std::fs::write(
+            &lib,
+            "// moved\nfn a() {\n  let x =  ctx.remaining_accounts[0];\n  let y = ctx.remaining_accounts[1];\n}\n",
+        )
+        .unwrap();
+        let kept = baseline.retain_new(vec![finding(&repo, 3), finding(&repo, 4)], &repo);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].line_number, 4);
+        std::fs::remove_dir_all(&repo).ok();
+    }
+
+    #[test]
+    fn duplicate_lines_are_counted() {
+        let repo = Path::new("/nonexistent/repo");
+        let agent = SecurityFinding {
+            title: "Oracle staleness".into(),
+            file_path: PathBuf::from("src/oracle.rs"),
+            ..Default::default()
+        };
+        let prints = fingerprints(&[agent.clone(), agent.clone()], repo);
+        assert_ne!(prints[0], prints[1]);
+        let baseline = Baseline {
+            fingerprints: [prints[0].clone()].into(),
+            ..Default::default()
+        };
+        assert_eq!(
+            baseline.retain_new(vec![agent.clone(), agent], repo).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn apply_records_then_filters() {
+        let dir =
+            std::env::temp_dir().join(format!("solguard-baseline-apply-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("baseline.json");
+        let _ = std::fs::remove_file(&path);
+        let repo = Path::new("/nonexistent/repo");
+        let mut findings = vec![finding(repo, 2)];
+        assert_eq!(
+            apply(&path, repo, &mut findings).unwrap(),
+            Outcome::Recorded(1)
+        );
+        assert_eq!(findings.len(), 1);
+
+        let mut findings = vec![finding(repo, 2), finding(repo, 2)];
+        assert_eq!(
+            apply(&path, repo, &mut findings).unwrap(),
+            Outcome::Filtered { suppressed: 1 }
+        );
+        assert_eq!(findings.len(), 1);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
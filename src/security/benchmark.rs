@@ -0,0 +1,690 @@
+//! Calibration summaries for the `test` command: how precise the investigator
+//! was (by the validator's verdicts), what it cost, and how long it took.
+//! Rows append to a CSV or JSONL log so models can be compared over time.
+//!
+//! Every run is also recorded in `~/.solguard/leaderboard.jsonl`, which
+//! `models leaderboard` aggregates per model. Runs scored against a
+//! ground-truth pack (known vulnerabilities of the repo, as JSON) carry
+//! recall as well; without one, only the validator's verdicts are known.
+
+use super::agent_review::ReviewStats;
+use super::validator::{ValidatedFinding, Verdict};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::warn;
+
+/// Severities broken out in the summary, in report order.
+const SEVERITIES: &[&str] = &["Critical", "High", "Medium", "Low"];
+
+/// Verdict counts for one severity. `precision` is confirmed / total, a proxy
+/// that treats the validator as ground truth.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeverityPrecision {
+    pub severity: String,
+    pub findings: usize,
+    pub confirmed: usize,
+    pub precision: f64,
+}
+
+/// One calibration run: investigate + validate on a single repo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationSummary {
+    pub timestamp: DateTime<Utc>,
+    pub repo: String,
+    pub model: String,
+    pub findings: usize,
+    pub confirmed: usize,
+    pub disputed: usize,
+    pub dismissed: usize,
+    /// Overall confirmed / findings; 0.0 with no findings.
+    pub precision: f64,
+    pub by_severity: Vec<SeverityPrecision>,
+    /// Dismissed / findings; 0.0 with no findings.
+    #[serde(default)]
+    pub false_positive_rate: f64,
+    /// Name of the ground-truth pack the run was scored against.
+    #[serde(default)]
+    pub ground_truth: Option<String>,
+    /// Share of the pack's vulnerabilities found; `None` without a pack.
+    #[serde(default)]
+    pub recall: Option<f64>,
+    /// IDs of the pack's vulnerabilities no finding matched.
+    #[serde(default)]
+    pub missed: Vec<String>,
+    pub investigation_turns: u32,
+    pub validation_turns: u32,
+    pub cost_usd: f64,
+    /// `None` when nothing was confirmed.
+    pub cost_per_confirmed_usd: Option<f64>,
+    pub elapsed_secs: f64,
+}
+
+impl CalibrationSummary {
+    pub fn new(
+        repo: &str,
+        model: &str,
+        validated: &[ValidatedFinding],
+        investigation: &ReviewStats,
+        validation: &ReviewStats,
+        elapsed: Duration,
+    ) -> Self {
+        let count = |v: Verdict| validated.iter().filter(|f| f.verdict == v).count();
+        let confirmed = count(Verdict::Confirmed);
+        let by_severity = SEVERITIES
+            .iter()
+            .filter_map(|&severity| {
+                let of_severity: Vec<_> = validated
+                    .iter()
+                    .filter(|f| crate::severity::normalize(&f.finding.severity) == severity)
+                    .collect();
+                if of_severity.is_empty() {
+                    return None;
+                }
+                let confirmed = of_severity
+                    .iter()
+                    .filter(|f| f.verdict == Verdict::Confirmed)
+                    .count();
+                Some(SeverityPrecision {
+                    severity: severity.to_string(),
+                    findings: of_severity.len(),
+                    confirmed,
+                    precision: ratio(confirmed, of_severity.len()),
+                })
+            })
+            .collect();
+        let cost_usd = investigation.total_cost_usd + validation.total_cost_usd;
+        Self {
+            timestamp: Utc::now(),
+            repo: repo.to_string(),
+            model: model.to_string(),
+            findings: validated.len(),
+            confirmed,
+            disputed: count(Verdict::Disputed),
+            dismissed: count(Verdict::Dismissed),
+            precision: ratio(confirmed, validated.len()),
+            by_severity,
+            false_positive_rate: ratio(count(Verdict::Dismissed), validated.len()),
+            ground_truth: None,
+            recall: None,
+            missed: Vec::new(),
+            investigation_turns: investigation.turns,
+            validation_turns: validation.turns,
+            cost_usd,
+            cost_per_confirmed_usd: (confirmed > 0).then(|| cost_usd / confirmed as f64),
+            elapsed_secs: elapsed.as_secs_f64(),
+        }
+    }
+
+    /// Score the run against `pack`: recall, and which known vulnerabilities
+    /// were missed.
+    pub fn with_ground_truth(
+        mut self,
+        pack: &GroundTruthPack,
+        validated: &[ValidatedFinding],
+    ) -> Self {
+        let found = pack.found(validated);
+        self.recall = Some(ratio(found.len(), pack.vulns.len()));
+        self.missed = pack
+            .vulns
+            .iter()
+            .filter(|v| !found.contains(v.id.as_str()))
+            .map(|v| v.id.clone())
+            .collect();
+        self.ground_truth = Some(pack.name.clone());
+        self
+    }
+
+    /// Human-readable summary for stderr.
+    pub fn render(&self) -> String {
+        let mut out = format!(
+            "Calibration summary: {} ({})\n  findings {:>4}   confirmed {} / disputed {} / dismissed {}\n  precision {:.0}%\n",
+            self.repo,
+            self.model,
+            self.findings,
+            self.confirmed,
+            self.disputed,
+            self.dismissed,
+            self.precision * 100.0
+        );
+        if let (Some(pack), Some(recall)) = (&self.ground_truth, self.recall) {
+            out.push_str(&format!("  recall {:.0}% against {pack}", recall * 100.0));
+            if !self.missed.is_empty() {
+                out.push_str(&format!(" (missed {})", self.missed.join(", ")));
+            }
+            out.push('\n');
+        }
+        for s in &self.by_severity {
+            out.push_str(&format!(
+                "    {:<9} {:>3}/{:<3} {:.0}%\n",
+                s.severity,
+                s.confirmed,
+                s.findings,
+                s.precision * 100.0
+            ));
+        }
+        let per_confirmed = self
+            .cost_per_confirmed_usd
+            .map(|c| format!("${c:.4}"))
+            .unwrap_or_else(|| "n/a".into());
+        out.push_str(&format!(
+            "  turns {} investigate + {} validate\n  cost ${:.4} ({per_confirmed} per confirmed)\n  time {:.1}s\n",
+            self.investigation_turns, self.validation_turns, self.cost_usd, self.elapsed_secs
+        ));
+        out
+    }
+
+    /// Append this run to a benchmark log: CSV when the path ends in `.csv`
+    /// (header written on first use), JSON Lines otherwise.
+    pub fn append_to(&self, path: &Path) -> Result<()> {
+        let is_csv = path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("csv"));
+        let is_new = std::fs::metadata(path).map_or(true, |m| m.len() == 0);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        if is_csv {
+            if is_new {
+                writeln!(file, "{}", csv_header())?;
+            }
+            writeln!(file, "{}", self.csv_row())?;
+        } else {
+            writeln!(file, "{}", serde_json::to_string(self)?)?;
+        }
+        Ok(())
+    }
+
+    fn csv_row(&self) -> String {
+        let mut fields = vec![
+            self.timestamp.to_rfc3339(),
+            csv_escape(&self.repo),
+            csv_escape(&self.model),
+            self.findings.to_string(),
+            self.confirmed.to_string(),
+            self.disputed.to_string(),
+            self.dismissed.to_string(),
+            format!("{:.4}", self.precision),
+            format!("{:.4}", self.false_positive_rate),
+            self.recall.map(|r| format!("{r:.4}")).unwrap_or_default(),
+        ];
+        for severity in SEVERITIES {
+            fields.push(
+                self.by_severity
+                    .iter()
+                    .find(|s| s.severity == *severity)
+                    .map(|s| format!("{:.4}", s.precision))
+                    .unwrap_or_default(),
+            );
+        }
+        fields.extend([
+            self.investigation_turns.to_string(),
+            self.validation_turns.to_string(),
+            format!("{:.6}", self.cost_usd),
+            self.cost_per_confirmed_usd
+                .map(|c| format!("{c:.6}"))
+                .unwrap_or_default(),
+            format!("{:.1}", self.elapsed_secs),
+        ]);
+        fields.join(",")
+    }
+}
+
+fn csv_header() -> String {
+    let mut columns = vec![
+        "timestamp".to_string(),
+        "repo".into(),
+        "model".into(),
+        "findings".into(),
+        "confirmed".into(),
+        "disputed".into(),
+        "dismissed".into(),
+        "precision".into(),
+        "false_positive_rate".into(),
+        "recall".into(),
+    ];
+    columns.extend(
+        SEVERITIES
+            .iter()
+            .map(|s| format!("{}_precision", s.to_lowercase())),
+    );
+    columns.extend(
+        [
+            "investigation_turns",
+            "validation_turns",
+            "cost_usd",
+            "cost_per_confirmed_usd",
+            "elapsed_secs",
+        ]
+        .map(String::from),
+    );
+    columns.join(",")
+}
+
+/// Known vulnerabilities of one repo, for scoring recall:
+///
+/// ```json
+/// {"name": "shielded-pool", "vulns": [
+///   {"id": "GT-02", "title": "Recipient not bound to the proof",
+///    "files": ["noir_circuit/src/main.nr"], "keywords": ["recipient"]}
+/// ]}
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct GroundTruthPack {
+    /// Defaults to the file's stem.
+    #[serde(default)]
+    pub name: String,
+    pub vulns: Vec<KnownVuln>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct KnownVuln {
+    pub id: String,
+    pub title: String,
+    /// Paths (or path suffixes) a matching finding must touch.
+    pub files: Vec<String>,
+    /// Words a matching finding's title or description must contain (any
+    /// one, case-insensitive); empty accepts any finding on the files.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl GroundTruthPack {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading ground truth {}", path.display()))?;
+        let mut pack: Self = serde_json::from_str(&text)
+            .with_context(|| format!("parsing ground truth {}", path.display()))?;
+        if pack.name.is_empty() {
+            pack.name = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        }
+        Ok(pack)
+    }
+
+    /// IDs of the vulnerabilities some finding matches. Dismissed findings
+    /// don't count: the validator threw them out, so they wouldn't be reported.
+    fn found(&self, validated: &[ValidatedFinding]) -> BTreeSet<&str> {
+        self.vulns
+            .iter()
+            .filter(|v| {
+                validated
+                    .iter()
+                    .filter(|f| f.verdict != Verdict::Dismissed)
+                    .any(|f| v.matches(f))
+            })
+            .map(|v| v.id.as_str())
+            .collect()
+    }
+}
+
+impl KnownVuln {
+    fn matches(&self, f: &ValidatedFinding) -> bool {
+        let touches = f.finding.affected_files.iter().any(|affected| {
+            self.files
+                .iter()
+                .any(|file| affected.ends_with(file.as_str()) || file.ends_with(affected.as_str()))
+        });
+        let text = format!("{} {}", f.finding.title, f.finding.description).to_lowercase();
+        touches
+            && (self.keywords.is_empty()
+                || self
+                    .keywords
+                    .iter()
+                    .any(|k| text.contains(&k.to_lowercase())))
+    }
+}
+
+/// The leaderboard log every `test` run is recorded in.
+pub fn leaderboard_path() -> PathBuf {
+    crate::memory::solguard_dir().join("leaderboard.jsonl")
+}
+
+/// The runs in a JSON Lines log, oldest first. A missing log has none;
+/// unreadable lines are skipped.
+pub fn load_runs(path: &Path) -> Result<Vec<CalibrationSummary>> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    Ok(text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| warn!(line = i + 1, error = %e, "skipping leaderboard row"))
+                .ok()
+        })
+        .collect())
+}
+
+/// One model's record across its runs.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ModelStanding {
+    pub model: String,
+    pub runs: usize,
+    pub repos: usize,
+    /// Mean over the runs scored against a ground-truth pack.
+    pub recall: Option<f64>,
+    pub scored_runs: usize,
+    /// Confirmed / findings over all runs.
+    pub precision: f64,
+    /// Dismissed / findings over all runs.
+    pub false_positive_rate: f64,
+    pub mean_cost_usd: f64,
+    pub mean_turns: f64,
+}
+
+/// Standings per model: best recall first (unscored models last), then
+/// fewest false positives, then cheapest.
+pub fn leaderboard(runs: &[CalibrationSummary]) -> Vec<ModelStanding> {
+    let mut by_model: BTreeMap<&str, Vec<&CalibrationSummary>> = BTreeMap::new();
+    for run in runs {
+        by_model.entry(&run.model).or_default().push(run);
+    }
+    let mut standings: Vec<ModelStanding> = by_model
+        .into_iter()
+        .map(|(model, runs)| {
+            let sum = |f: fn(&CalibrationSummary) -> f64| runs.iter().map(|r| f(r)).sum::<f64>();
+            let findings = runs.iter().map(|r| r.findings).sum();
+            let recalls: Vec<f64> = runs.iter().filter_map(|r| r.recall).collect();
+            ModelStanding {
+                model: model.to_string(),
+                runs: runs.len(),
+                repos: runs.iter().map(|r| &r.repo).collect::<BTreeSet<_>>().len(),
+                recall: (!recalls.is_empty())
+                    .then(|| recalls.iter().sum::<f64>() / recalls.len() as f64),
+                scored_runs: recalls.len(),
+                precision: ratio(runs.iter().map(|r| r.confirmed).sum(), findings),
+                false_positive_rate: ratio(runs.iter().map(|r| r.dismissed).sum(), findings),
+                mean_cost_usd: sum(|r| r.cost_usd) / runs.len() as f64,
+                mean_turns: sum(|r| f64::from(r.investigation_turns + r.validation_turns))
+                    / runs.len() as f64,
+            }
+        })
+        .collect();
+    standings.sort_by(|a, b| {
+        b.recall
+            .unwrap_or(-1.0)
+            .total_cmp(&a.recall.unwrap_or(-1.0))
+            .then(a.false_positive_rate.total_cmp(&b.false_positive_rate))
+            .then(a.mean_cost_usd.total_cmp(&b.mean_cost_usd))
+    });
+    standings
+}
+
+/// The standings as a table.
+pub fn render_leaderboard(standings: &[ModelStanding]) -> String {
+    let width = standings
+        .iter()
+        .map(|s| s.model.len())
+        .max()
+        .unwrap_or(0)
+        .max("model".len());
+    let mut out = format!(
+        "{:<width$}  runs  repos  recall  precision  fp rate   cost/run  turns/run\n",
+        "model"
+    );
+    for s in standings {
+        let recall = s
+            .recall
+            .map(|r| format!("{:.0}%", r * 100.0))
+            .unwrap_or_else(|| "-".into());
+        out.push_str(&format!(
+            "{:<width$}  {:>4}  {:>5}  {:>6}  {:>8.0}%  {:>6.0}%  {:>9}  {:>9.1}\n",
+            s.model,
+            s.runs,
+            s.repos,
+            recall,
+            s.precision * 100.0,
+            s.false_positive_rate * 100.0,
+            format!("${:.4}", s.mean_cost_usd),
+            s.mean_turns,
+        ));
+    }
+    out
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn ratio(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::agent_review::AgentFinding;
+
+    fn validated(severity: &str, verdict: Verdict) -> ValidatedFinding {
+        ValidatedFinding {
+            finding: AgentFinding {
+                title: format!("{severity} issue"),
+                severity: severity.into(),
+                description: String::new(),
+                evidence: vec![],
+                attack_scenario: String::new(),
+                remediation: String::new(),
+                confidence: 0.8,
+                affected_files: vec![],
+                tags: vec![],
+            },
+            verdict,
+            reasoning: String::new(),
+        }
+    }
+
+    fn summary() -> CalibrationSummary {
+        let findings = vec![
+            validated("High", Verdict::Confirmed),
+            validated("High", Verdict::Dismissed),
+            validated("Medium", Verdict::Confirmed),
+            validated("Medium", Verdict::Disputed),
+        ];
+        let inv = ReviewStats {
+            turns: 12,
+            total_cost_usd: 0.30,
+            ..Default::default()
+        };
+        let val = ReviewStats {
+            turns: 3,
+            total_cost_usd: 0.10,
+            ..Default::default()
+        };
+        CalibrationSummary::new(
+            "vault",
+            "test-model",
+            &findings,
+            &inv,
+            &val,
+            Duration::from_secs(90),
+        )
+    }
+
+    #[test]
+    fn precision_and_cost_per_confirmed() {
+        let s = summary();
+        assert_eq!((s.confirmed, s.disputed, s.dismissed), (2, 1, 1));
+        assert!((s.precision - 0.5).abs() < f64::EPSILON);
+        assert_eq!(s.by_severity.len(), 2);
+        assert_eq!(s.by_severity[0].severity, "High");
+        assert_eq!(s.by_severity[0].confirmed, 1);
+        assert!((s.cost_per_confirmed_usd.unwrap() - 0.20).abs() < 1e-9);
+        assert_eq!(s.investigation_turns + s.validation_turns, 15);
+        assert!(
+            s.render()
+                .contains("confirmed 2 / disputed 1 / dismissed 1")
+        );
+    }
+
+    #[test]
+    fn no_confirmed_has_no_cost_per_confirmed() {
+        let s = CalibrationSummary::new(
+            "vault",
+            "m",
+            &[validated("Low", Verdict::Dismissed)],
+            &ReviewStats::default(),
+            &ReviewStats::default(),
+            Duration::ZERO,
+        );
+        assert_eq!(s.cost_per_confirmed_usd, None);
+        assert_eq!(s.precision, 0.0);
+    }
+
+    #[test]
+    fn appends_csv_with_single_header_and_jsonl() {
+        let dir = std::env::temp_dir().join(format!("solguard-bench-{}", std::process::id()));
+        let csv = dir.join("bench.csv");
+        let jsonl = dir.join("bench.jsonl");
+        let s = summary();
+        s.append_to(&csv).unwrap();
+        s.append_to(&csv).unwrap();
+        s.append_to(&jsonl).unwrap();
+        let csv_text = std::fs::read_to_string(&csv).unwrap();
+        let jsonl_text = std::fs::read_to_string(&jsonl).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let lines: Vec<&str> = csv_text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], csv_header());
+        assert_eq!(
+            lines[1].split(',').count(),
+            lines[0].split(',').count(),
+            "row and header column counts differ"
+        );
+        let row: serde_json::Value = serde_json::from_str(jsonl_text.trim()).unwrap();
+        assert_eq!(row["model"], "test-model");
+        assert_eq!(row["confirmed"], 2);
+    }
+
+    #[test]
+    fn ground_truth_recall_skips_dismissed_findings() {
+        let pack: GroundTruthPack = serde_json::from_str(
+            r#"{"name": "pool", "vulns": [
+                {"id": "GT-1", "title": "Unbound recipient", "files": ["src/main.nr"], "keywords": ["Recipient"]},
+                {"id": "GT-2", "title": "Arbitrary root", "files": ["deposit.rs"]},
+                {"id": "GT-3", "title": "Lamport nullifier", "files": ["withdraw.rs"]}
+            ]}"#,
+        )
+        .unwrap();
+        let on = |file: &str, title: &str, verdict| {
+            let mut f = validated("High", verdict);
+            f.finding.title = title.into();
+            f.finding.affected_files = vec![file.into()];
+            f
+        };
+        let findings = vec![
+            on(
+                "noir_circuit/src/main.nr",
+                "recipient is unconstrained",
+                Verdict::Confirmed,
+            ),
+            on(
+                "program/src/deposit.rs",
+                "Root is not verified",
+                Verdict::Disputed,
+            ),
+            on(
+                "program/src/withdraw.rs",
+                "Nullifier check",
+                Verdict::Dismissed,
+            ),
+            on(
+                "program/src/withdraw.rs",
+                "Missing signer",
+                Verdict::Confirmed,
+            ),
+            on("noir_circuit/src/lib.nr", "recipient", Verdict::Confirmed),
+        ];
+        let s = CalibrationSummary::new(
+            "pool",
+            "m",
+            &findings,
+            &ReviewStats::default(),
+            &ReviewStats::default(),
+            Duration::ZERO,
+        )
+        .with_ground_truth(&pack, &findings);
+
+        // GT-3 has no keywords, so any finding on its file counts.
+        assert_eq!(s.missed, Vec::<String>::new());
+        // Without "Missing signer", its only finding is a dismissed one.
+        let findings = &findings[..3];
+        let s = s.with_ground_truth(&pack, findings);
+        assert_eq!(s.missed, vec!["GT-3"]);
+        assert!((s.recall.unwrap() - 2.0 / 3.0).abs() < 1e-9);
+        assert!((s.false_positive_rate - 0.2).abs() < 1e-9);
+        assert!(s.render().contains("recall 67% against pool (missed GT-3)"));
+    }
+
+    #[test]
+    fn leaderboard_ranks_models_by_recall_then_false_positives() {
+        let run = |model: &str, repo: &str, recall: Option<f64>, dismissed: usize, cost: f64| {
+            let mut s = summary();
+            s.model = model.into();
+            s.repo = repo.into();
+            s.recall = recall;
+            s.dismissed = dismissed;
+            s.cost_usd = cost;
+            s
+        };
+        let dir = std::env::temp_dir().join(format!("solguard-board-{}", std::process::id()));
+        let log = dir.join("leaderboard.jsonl");
+        for s in [
+            run("cheap", "vault", None, 0, 0.1),
+            run("opus", "vault", Some(0.5), 2, 2.0),
+            run("opus", "pool", Some(1.0), 1, 1.0),
+            run("sonnet", "vault", Some(0.75), 1, 0.5),
+        ] {
+            s.append_to(&log).unwrap();
+        }
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&log)
+            .and_then(|mut f| writeln!(f, "not json"))
+            .unwrap();
+        let runs = load_runs(&log).unwrap();
+        let missing = load_runs(&dir.join("missing.jsonl")).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(runs.len(), 4);
+        assert!(missing.is_empty());
+        let board = leaderboard(&runs);
+        let order: Vec<&str> = board.iter().map(|s| s.model.as_str()).collect();
+        // opus and sonnet tie on recall; sonnet dismissed less.
+        assert_eq!(order, vec!["sonnet", "opus", "cheap"]);
+        let opus = &board[1];
+        assert_eq!((opus.runs, opus.repos, opus.scored_runs), (2, 2, 2));
+        assert!((opus.recall.unwrap() - 0.75).abs() < 1e-9);
+        assert!((opus.false_positive_rate - 0.375).abs() < 1e-9);
+        assert!((opus.mean_cost_usd - 1.5).abs() < 1e-9);
+        assert!((opus.mean_turns - 15.0).abs() < 1e-9);
+        let table = render_leaderboard(&board);
+        assert!(
+            table.lines().nth(3).unwrap().starts_with("cheap "),
+            "{table}"
+        );
+    }
+}
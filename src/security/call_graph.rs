@@ -0,0 +1,459 @@
+//! Cross-file call graph of a repo's Rust sources.
+//!
+//! Entry points are Anchor instruction handlers (`pub fn` inside
+//! `#[program]`) and native entrypoints (`entrypoint!(name)` targets).
+//! The scanner uses the graph to record how a flagged function is reached
+//! from an entry point, and to tag findings in functions no entry point
+//! reaches; the agent queries it through the `call_graph` tool.
+//!
+//! Calls are resolved by name, most specific first (`deposit::handler`,
+//! then `handler`); a bare name defined more than once is left unresolved
+//! rather than guessed. Trait dispatch and closures passed around are not
+//! followed.
+
+use super::collect_rust_files;
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Expr, ImplItemFn, ItemFn, ItemMod};
+
+/// Tag for findings in functions no entry point reaches.
+pub const UNREACHABLE_TAG: &str = "unreachable";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FnNode {
+    pub name: String,
+    /// File stem (parent directory for `mod.rs`), or the inline `mod`.
+    pub module: String,
+    pub file: PathBuf,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub entry: bool,
+    /// Indices of resolved callees.
+    pub calls: Vec<usize>,
+}
+
+impl FnNode {
+    /// `module::name`.
+    pub fn qualified(&self) -> String {
+        if self.module.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}::{}", self.module, self.name)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CallGraph {
+    pub functions: Vec<FnNode>,
+}
+
+/// Walk every scanned Rust file under `repo_path`. Files that don't parse
+/// are skipped; paths are stored repo-relative.
+pub fn build(repo_path: &Path) -> CallGraph {
+    let mut builder = Builder::default();
+    for path in collect_rust_files(repo_path).unwrap_or_default() {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(ast) = syn::parse_file(&content) else {
+            continue;
+        };
+        builder.add_file(&ast, path.strip_prefix(repo_path).unwrap_or(&path));
+    }
+    builder.finish()
+}
+
+impl CallGraph {
+    pub fn has_entries(&self) -> bool {
+        self.functions.iter().any(|f| f.entry)
+    }
+
+    /// Innermost function in `file` spanning `line`.
+    pub fn enclosing(&self, file: &Path, line: usize) -> Option<usize> {
+        self.functions
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.file == file && (f.start_line..=f.end_line).contains(&line))
+            .min_by_key(|(_, f)| f.end_line - f.start_line)
+            .map(|(i, _)| i)
+    }
+
+    /// Functions named `name` or `module::name`.
+    pub fn find(&self, name: &str) -> Vec<usize> {
+        self.functions
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.name == name || f.qualified() == name)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn callers(&self, idx: usize) -> Vec<usize> {
+        self.functions
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| f.calls.contains(&idx))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Shortest call chain from any entry point to `idx`, entry point
+    /// first; `None` when unreachable.
+    pub fn entry_path(&self, idx: usize) -> Option<Vec<usize>> {
+        let mut parent: HashMap<usize, Option<usize>> = HashMap::new();
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        for (i, f) in self.functions.iter().enumerate() {
+            if f.entry {
+                parent.insert(i, None);
+                queue.push_back(i);
+            }
+        }
+        while let Some(at) = queue.pop_front() {
+            if at == idx {
+                let mut path = vec![at];
+                let mut cur = at;
+                while let Some(Some(prev)) = parent.get(&cur) {
+                    path.push(*prev);
+                    cur = *prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for &next in &self.functions[at].calls {
+                if let Entry::Vacant(e) = parent.entry(next) {
+                    e.insert(Some(at));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Qualified names along [`Self::entry_path`].
+    pub fn entry_path_names(&self, idx: usize) -> Option<Vec<String>> {
+        self.entry_path(idx).map(|path| {
+            path.into_iter()
+                .map(|i| self.functions[i].qualified())
+                .collect()
+        })
+    }
+
+    /// Callers, callees and entry path of every function matching `name`,
+    /// as text for the agent.
+    pub fn describe(&self, name: &str) -> Option<String> {
+        let matches = self.find(name);
+        if matches.is_empty() {
+            return None;
+        }
+        let list = |ids: &[usize]| {
+            if ids.is_empty() {
+                return "(none)".to_string();
+            }
+            ids.iter()
+                .map(|&i| self.label(i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let mut out = String::new();
+        for idx in matches {
+            let f = &self.functions[idx];
+            let entry = if f.entry { " [entry point]" } else { "" };
+            let _ = writeln!(out, "fn {}{entry}", self.label(idx));
+            let _ = writeln!(out, "  callers: {}", list(&self.callers(idx)));
+            let _ = writeln!(out, "  callees: {}", list(&f.calls));
+            match self.entry_path_names(idx) {
+                Some(path) => {
+                    let _ = writeln!(out, "  reached via: {}", path.join(" → "));
+                }
+                None if self.has_entries() => {
+                    let _ = writeln!(out, "  not reachable from any entry point");
+                }
+                None => {}
+            }
+        }
+        Some(out)
+    }
+
+    fn label(&self, idx: usize) -> String {
+        let f = &self.functions[idx];
+        format!(
+            "{} ({}:{})",
+            f.qualified(),
+            crate::paths::to_slash(&f.file),
+            f.start_line
+        )
+    }
+}
+
+/// Collects functions file by file; calls are resolved in [`Self::finish`].
+#[derive(Default)]
+pub(crate) struct Builder {
+    functions: Vec<FnNode>,
+    /// Per function, each call's keys, most specific first.
+    calls: Vec<Vec<Vec<String>>>,
+    /// Targets of `entrypoint!(..)`.
+    entrypoints: HashSet<String>,
+}
+
+impl Builder {
+    /// Add one parsed file, `rel` being its repo-relative path.
+    pub(crate) fn add_file(&mut self, ast: &syn::File, rel: &Path) {
+        let stem = rel
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let module = if stem == "mod" {
+            rel.parent()
+                .and_then(Path::file_name)
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or(stem)
+        } else {
+            stem
+        };
+        let mut collector = Collector {
+            builder: self,
+            file: rel.to_path_buf(),
+            modules: vec![module],
+            in_program: false,
+        };
+        collector.visit_file(ast);
+    }
+
+    pub(crate) fn finish(mut self) -> CallGraph {
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut ambiguous: HashSet<String> = HashSet::new();
+        for (i, f) in self.functions.iter_mut().enumerate() {
+            f.entry |= self.entrypoints.contains(&f.name);
+            index.insert(f.qualified(), i);
+            if ambiguous.contains(&f.name) {
+                continue;
+            }
+            if index.insert(f.name.clone(), i).is_some() {
+                index.remove(&f.name);
+                ambiguous.insert(f.name.clone());
+            }
+        }
+        for (f, calls) in self.functions.iter_mut().zip(&self.calls) {
+            for keys in calls {
+                if let Some(&target) = keys.iter().find_map(|k| index.get(k))
+                    && !f.calls.contains(&target)
+                {
+                    f.calls.push(target);
+                }
+            }
+        }
+        CallGraph {
+            functions: self.functions,
+        }
+    }
+}
+
+struct Collector<'a> {
+    builder: &'a mut Builder,
+    file: PathBuf,
+    /// File module, then inline `mod` names.
+    modules: Vec<String>,
+    in_program: bool,
+}
+
+impl Collector<'_> {
+    fn add_fn(&mut self, name: String, entry: bool, span: proc_macro2::Span, block: &syn::Block) {
+        let mut calls = CallVisitor::default();
+        calls.visit_block(block);
+        self.builder.functions.push(FnNode {
+            name,
+            module: self.modules.last().cloned().unwrap_or_default(),
+            file: self.file.clone(),
+            start_line: span.start().line,
+            end_line: span.end().line,
+            entry,
+            calls: Vec::new(),
+        });
+        self.builder.calls.push(calls.calls);
+    }
+}
+
+impl<'ast> Visit<'ast> for Collector<'_> {
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        let is_program = node.attrs.iter().any(|a| a.path().is_ident("program"));
+        let outer = std::mem::replace(&mut self.in_program, is_program);
+        self.modules.push(node.ident.to_string());
+        syn::visit::visit_item_mod(self, node);
+        self.modules.pop();
+        self.in_program = outer;
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        let entry = self.in_program && matches!(node.vis, syn::Visibility::Public(_));
+        self.add_fn(node.sig.ident.to_string(), entry, node.span(), &node.block);
+        syn::visit::visit_item_fn(self, node);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.add_fn(node.sig.ident.to_string(), false, node.span(), &node.block);
+        syn::visit::visit_impl_item_fn(self, node);
+    }
+
+    fn visit_macro(&mut self, node: &'ast syn::Macro) {
+        if node
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "entrypoint")
+            && let Ok(target) = node.parse_body::<syn::Path>()
+            && let Some(name) = target.segments.last()
+        {
+            self.builder.entrypoints.insert(name.ident.to_string());
+        }
+    }
+}
+
+/// Call keys in one function body.
+#[derive(Default)]
+struct CallVisitor {
+    calls: Vec<Vec<String>>,
+}
+
+impl<'ast> Visit<'ast> for CallVisitor {
+    fn visit_expr_call(&mut self, call: &'ast syn::ExprCall) {
+        if let Expr::Path(p) = &*call.func {
+            let segments: Vec<String> = p
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect();
+            if let Some(last) = segments.last() {
+                let mut keys = Vec::new();
+                if let [.., parent, _] = segments.as_slice() {
+                    keys.push(format!("{parent}::{last}"));
+                }
+                keys.push(last.clone());
+                self.calls.push(keys);
+            }
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast syn::ExprMethodCall) {
+        self.calls.push(vec![call.method.to_string()]);
+        syn::visit::visit_expr_method_call(self, call);
+    }
+
+    // Nested fns are collected on their own.
+    fn visit_item_fn(&mut self, _: &'ast ItemFn) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(files: &[(&str, &str)]) -> CallGraph {
+        let mut builder = Builder::default();
+        for (path, src) in files {
+            builder.add_file(&syn::parse_file(src).unwrap(), Path::new(path));
+        }
+        builder.finish()
+    }
+
+    const LIB: &str = "\
+#[program]
+pub mod vault {
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        instructions::deposit::handler(ctx, amount)
+    }
+}";
+
+    const DEPOSIT: &str = "\
+pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    ctx.accounts.credit(amount)
+}
+
+impl Deposit<'_> {
+    fn credit(&mut self, amount: u64) -> Result<()> {
+        self.vault.total = math::add(self.vault.total, amount);
+        Ok(())
+    }
+}
+
+fn legacy_migrate() {}";
+
+    const MATH: &str = "pub fn add(a: u64, b: u64) -> u64 { a + b }";
+
+    fn vault() -> CallGraph {
+        graph(&[
+            ("programs/vault/src/lib.rs", LIB),
+            ("programs/vault/src/instructions/deposit.rs", DEPOSIT),
+            ("programs/vault/src/math/mod.rs", MATH),
+        ])
+    }
+
+    #[test]
+    fn entry_path_crosses_files() {
+        let g = vault();
+        let add = g.enclosing(Path::new("programs/vault/src/math/mod.rs"), 1);
+        assert_eq!(
+            g.entry_path_names(add.unwrap()).unwrap(),
+            vec![
+                "vault::deposit",
+                "deposit::handler",
+                "deposit::credit",
+                "math::add"
+            ]
+        );
+        let legacy = g.find("legacy_migrate")[0];
+        assert!(g.entry_path(legacy).is_none());
+    }
+
+    #[test]
+    fn ambiguous_bare_names_stay_unresolved() {
+        let g = graph(&[
+            (
+                "src/lib.rs",
+                "#[program] pub mod p { pub fn a() { helper() } }",
+            ),
+            ("src/x.rs", "fn helper() {}"),
+            ("src/y.rs", "fn helper() {}"),
+        ]);
+        let a = g.find("a")[0];
+        assert!(g.functions[a].calls.is_empty());
+        assert_eq!(g.find("helper").len(), 2);
+        assert_eq!(g.find("y::helper").len(), 1);
+    }
+
+    #[test]
+    fn native_entrypoint_macro() {
+        let g = graph(&[(
+            "src/lib.rs",
+            "entrypoint!(process_instruction);\n\
+             fn process_instruction() { withdraw() }\n\
+             fn withdraw() {}",
+        )]);
+        let withdraw = g.find("withdraw")[0];
+        assert_eq!(
+            g.entry_path_names(withdraw).unwrap(),
+            vec!["lib::process_instruction", "lib::withdraw"]
+        );
+    }
+
+    #[test]
+    fn describe_lists_callers_and_path() {
+        let text = vault().describe("credit").unwrap();
+        assert!(text.contains("fn deposit::credit (programs/vault/src/instructions/deposit.rs:6)"));
+        assert!(text.contains("callers: deposit::handler"));
+        assert!(text.contains("callees: math::add"));
+        assert!(text.contains("reached via: vault::deposit → deposit::handler → deposit::credit"));
+        assert!(vault().describe("nope").is_none());
+        assert!(
+            vault()
+                .describe("legacy_migrate")
+                .unwrap()
+                .contains("not reachable")
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Cargo metadata for the review agent's `cargo_metadata` tool: each crate
+//! of the workspace with its dependencies and feature flags, the root's
+//! `[workspace.dependencies]`, and the versions `Cargo.lock` resolved them
+//! to. Read straight from the manifests rather than by running
+//! `cargo metadata`, so it works offline and executes nothing from the repo.
+
+use super::deps_audit::{self, WATCHED_CRATES};
+use super::project_config::{self, Dependency};
+use super::workspace;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+#[derive(Debug, Serialize)]
+pub struct CargoMetadata {
+    pub members: Vec<Member>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub workspace_dependencies: BTreeMap<String, Dependency>,
+    /// Versions the root `Cargo.lock` pins for the members' dependencies
+    /// and the core Solana crates; more than one when the graph pulls in
+    /// several.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub locked: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Member {
+    pub name: String,
+    /// Crate directory relative to the repo root.
+    pub dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Whether it is an on-chain program rather than a client or library.
+    pub program: bool,
+    /// `[dependencies]`; dev- and build-dependencies are left out.
+    pub dependencies: BTreeMap<String, Dependency>,
+    /// The crate's `[features]` table.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub features: BTreeMap<String, Vec<String>>,
+}
+
+impl CargoMetadata {
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty() && self.workspace_dependencies.is_empty()
+    }
+}
+
+/// Cargo metadata of the repo at `repo_path`. With `filter`, only
+/// dependencies whose name contains it (ignoring case) are listed.
+pub fn read(repo_path: &Path, filter: Option<&str>) -> CargoMetadata {
+    let filter = filter.map(str::to_lowercase);
+    let wanted = |name: &str| {
+        filter
+            .as_deref()
+            .is_none_or(|f| name.to_lowercase().contains(f))
+    };
+    let root = manifest(repo_path);
+
+    let mut dirs = workspace::member_dirs(repo_path);
+    if root.as_ref().is_some_and(|m| m.contains_key("package")) {
+        dirs.insert(0, repo_path.to_path_buf());
+    }
+    let mut programs: Vec<_> = workspace::programs(repo_path)
+        .into_iter()
+        .map(|p| p.dir)
+        .collect();
+    programs.extend(workspace::root_program(repo_path).map(|p| p.dir));
+
+    // Lockfile names of everything declared, for `locked`.
+    let mut declared: BTreeSet<String> = WATCHED_CRATES.iter().map(|c| c.to_string()).collect();
+    let mut members = Vec::new();
+    for dir in dirs {
+        let Some(manifest) = manifest(&dir) else {
+            continue;
+        };
+        let Some(package) = manifest.get("package") else {
+            continue;
+        };
+        let deps = manifest.get("dependencies").and_then(|d| d.as_table());
+        declared.extend(deps.into_iter().flatten().map(|(k, v)| package_name(k, v)));
+        let rel = crate::paths::to_slash(dir.strip_prefix(repo_path).unwrap_or(&dir));
+        members.push(Member {
+            name: package
+                .get("name")
+                .and_then(|n| n.as_str())
+                .map(String::from)
+                .unwrap_or_else(|| rel.clone()),
+            dir: if rel.is_empty() { ".".into() } else { rel },
+            version: package.get("version").map(|v| match v.as_str() {
+                Some(v) => v.to_string(),
+                None => "workspace".into(),
+            }),
+            program: programs.contains(&dir),
+            dependencies: dependencies(deps, &wanted),
+            features: project_config::features(&manifest),
+        });
+    }
+
+    let workspace_deps = root
+        .as_ref()
+        .and_then(|m| m.get("workspace")?.get("dependencies")?.as_table());
+    declared.extend(
+        workspace_deps
+            .into_iter()
+            .flatten()
+            .map(|(k, v)| package_name(k, v)),
+    );
+
+    let mut locked: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    if let Ok(lockfile) = std::fs::read_to_string(repo_path.join("Cargo.lock")) {
+        for pkg in deps_audit::lock_entries(&lockfile) {
+            if declared.contains(&pkg.name) && wanted(&pkg.name) {
+                let versions = locked.entry(pkg.name).or_default();
+                if !versions.contains(&pkg.version) {
+                    versions.push(pkg.version);
+                }
+            }
+        }
+    }
+
+    CargoMetadata {
+        members,
+        workspace_dependencies: dependencies(workspace_deps, &wanted),
+        locked,
+    }
+}
+
+fn manifest(dir: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()
+}
+
+fn dependencies(
+    table: Option<&toml::Table>,
+    wanted: &impl Fn(&str) -> bool,
+) -> BTreeMap<String, Dependency> {
+    table
+        .into_iter()
+        .flatten()
+        .filter(|(name, spec)| wanted(name) || wanted(&package_name(name, spec)))
+        .map(|(name, spec)| (name.clone(), project_config::dependency(spec)))
+        .collect()
+}
+
+/// The crate a dependency entry refers to: its `package` when renamed.
+fn package_name(key: &str, spec: &toml::Value) -> String {
+    spec.get("package")
+        .and_then(|p| p.as_str())
+        .unwrap_or(key)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_members_dependencies_and_locked_versions() {
+        let root =
+            std::env::temp_dir().join(format!("solguard-cargo-metadata-{}", std::process::id()));
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"programs/*\", \"client\"]\n\
+             [workspace.dependencies]\nspl-token = { version = \"4\", features = [\"no-entrypoint\"] }\n",
+        );
+        write(
+            "programs/vault/Cargo.toml",
+            "[package]\nname = \"vault\"\nversion = \"0.1.0\"\n\
+             [features]\nno-entrypoint = []\ndevnet = []\n\
+             [dependencies]\nanchor-lang = { version = \"0.30.1\", features = [\"init-if-needed\"] }\n\
+             token = { package = \"spl-token\", workspace = true }\n\
+             [dev-dependencies]\nlitesvm = \"0.2\"\n",
+        );
+        write(
+            "client/Cargo.toml",
+            "[package]\nname = \"vault-client\"\nversion.workspace = true\n\
+             [dependencies]\nanchor-client = \"0.30.1\"\nserde = \"1\"\n",
+        );
+        write(
+            "Cargo.lock",
+            "version = 3\n\n[[package]]\nname = \"anchor-lang\"\nversion = \"0.30.1\"\n\n\
+             [[package]]\nname = \"spl-token\"\nversion = \"4.0.0\"\n\n\
+             [[package]]\nname = \"spl-token\"\nversion = \"3.5.0\"\n\n\
+             [[package]]\nname = \"solana-program\"\nversion = \"1.18.26\"\n\n\
+             [[package]]\nname = \"borsh\"\nversion = \"0.10.3\"\n",
+        );
+
+        let meta = read(&root, None);
+        let names: Vec<_> = meta.members.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, ["vault-client", "vault"]);
+        let vault = &meta.members[1];
+        assert!(vault.program && !meta.members[0].program);
+        assert_eq!(vault.dir, "programs/vault");
+        assert_eq!(
+            vault.dependencies["anchor-lang"].features,
+            ["init-if-needed"]
+        );
+        assert_eq!(vault.dependencies["token"].version, "workspace");
+        assert!(!vault.dependencies.contains_key("litesvm"));
+        assert_eq!(vault.features.len(), 2);
+        assert_eq!(meta.members[0].version.as_deref(), Some("workspace"));
+        assert_eq!(meta.workspace_dependencies["spl-token"].version, "4");
+        assert_eq!(meta.locked["spl-token"], ["4.0.0", "3.5.0"]);
+        assert_eq!(meta.locked["solana-program"], ["1.18.26"]);
+        assert!(!meta.locked.contains_key("borsh"));
+
+        let spl = read(&root, Some("SPL"));
+        assert!(spl.members[1].dependencies.contains_key("token"));
+        assert!(!spl.members[1].dependencies.contains_key("anchor-lang"));
+        assert!(spl.members[0].dependencies.is_empty());
+        assert_eq!(spl.locked.keys().collect::<Vec<_>>(), ["spl-token"]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,106 @@
+//! Exploit checklists per protocol category, bundled from
+//! `rules/checklists.toml`. The deep-review agent gets the full checklist of
+//! its narrative's category, references included, in its first message.
+//! Adding a category or an item is an edit to that file.
+
+use serde::Deserialize;
+use std::fmt::Write;
+use std::sync::LazyLock;
+
+const BUNDLED: &str = include_str!("../../rules/checklists.toml");
+
+static CHECKLISTS: LazyLock<Vec<Checklist>> = LazyLock::new(|| {
+    toml::from_str::<ChecklistFile>(BUNDLED)
+        .expect("rules/checklists.toml is valid")
+        .category
+});
+
+#[derive(Deserialize)]
+struct ChecklistFile {
+    category: Vec<Checklist>,
+}
+
+/// Known exploit classes for one protocol category.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Checklist {
+    pub name: String,
+    /// Lowercase keywords matched against a category name; empty for the
+    /// fallback.
+    #[serde(rename = "match")]
+    pub keywords: Vec<String>,
+    pub items: Vec<ChecklistItem>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChecklistItem {
+    pub title: String,
+    /// What to verify in the code.
+    pub check: String,
+    #[serde(default)]
+    pub references: Vec<String>,
+}
+
+impl Checklist {
+    /// Markdown list for the agent's initial message.
+    pub fn render(&self) -> String {
+        let mut out = String::from(
+            "Known exploit classes for this category. Check each one explicitly \
+             and say in your analysis why it does or does not apply:",
+        );
+        for item in &self.items {
+            let _ = write!(out, "\n- **{}**: {}", item.title, item.check);
+            if !item.references.is_empty() {
+                let _ = write!(out, " (see: {})", item.references.join("; "));
+            }
+        }
+        out
+    }
+}
+
+/// Every bundled checklist, in match order.
+pub fn all() -> &'static [Checklist] {
+    &CHECKLISTS
+}
+
+/// The checklist for `category`: the first whose keyword occurs in it,
+/// else the fallback.
+pub fn for_category(category: &str) -> &'static Checklist {
+    let category = category.to_lowercase();
+    all()
+        .iter()
+        .find(|c| c.keywords.iter().any(|k| category.contains(k.as_str())))
+        .or_else(|| all().iter().find(|c| c.keywords.is_empty()))
+        .expect("rules/checklists.toml has a fallback category")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bundled_checklists_are_well_formed() {
+        assert!(all().len() >= 8);
+        assert_eq!(all().iter().filter(|c| c.keywords.is_empty()).count(), 1);
+        for c in all() {
+            assert!(c.items.len() >= 3, "{} has too few items", c.name);
+            assert!(
+                c.keywords.iter().all(|k| *k == k.to_lowercase()),
+                "{} keywords must be lowercase",
+                c.name
+            );
+        }
+    }
+
+    #[test]
+    fn category_lookup() {
+        assert_eq!(for_category("DEX").name, "DEX");
+        assert_eq!(for_category("Lending").name, "Lending");
+        assert_eq!(for_category("Vault/Escrow").name, "Vault/Escrow");
+        assert_eq!(for_category("Gaming").name, "General");
+
+        let bridge = for_category("Bridge").render();
+        assert!(bridge.contains("**Message replay**"));
+        assert!(bridge.contains("quorum"));
+        assert!(bridge.contains("(see: Wormhole, Feb 2022"));
+    }
+}
@@ -0,0 +1,228 @@
+//! Program crates with no tests. Nothing here is a vulnerability, but a
+//! program nobody has exercised deserves a harder look from the agent and
+//! from triage than one with a test suite.
+
+use super::workspace::{self, ProgramCrate};
+use super::{Finding, Severity};
+use std::path::Path;
+use tracing::info;
+use walkdir::WalkDir;
+
+/// Pattern ID of untested programs.
+pub const PATTERN_ID: &str = "TEST-001";
+
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", ".git", ".anchor"];
+
+/// Test sources under a repo-level `tests/`: Rust, and the TypeScript or
+/// JavaScript that `anchor test` runs.
+const TEST_EXTENSIONS: &[&str] = &["rs", "ts", "js", "mjs"];
+
+/// A finding for each program crate under `repo_path` missing unit tests
+/// (`#[cfg(test)]` or `#[test]` in its sources), integration tests, or both.
+/// Integration tests are the crate's own `tests/`, or a repo-level `tests/`
+/// file naming the program (`anchor.workspace.MyVault` names `my_vault`);
+/// a single-program repo's `tests/` always counts.
+pub fn untested_programs(repo_path: &Path) -> Vec<Finding> {
+    let mut programs = workspace::programs(repo_path);
+    if programs.is_empty() {
+        programs.extend(workspace::root_program(repo_path));
+    }
+    let repo_tests = if programs.iter().any(|p| p.dir == repo_path) {
+        Vec::new()
+    } else {
+        test_sources(&repo_path.join("tests"))
+    };
+    let single = programs.len() == 1;
+
+    let mut findings = Vec::new();
+    for program in &programs {
+        let unit = has_unit_tests(&program.dir);
+        let integration = !test_sources(&program.dir.join("tests")).is_empty()
+            || (!repo_tests.is_empty() && single)
+            || repo_tests.iter().any(|t| names_program(t, &program.name));
+        if let Some(f) = finding(repo_path, program, unit, integration) {
+            findings.push(f);
+        }
+    }
+    if !findings.is_empty() {
+        info!(count = findings.len(), "program crates missing tests");
+    }
+    findings
+}
+
+/// Whether any source of the crate outside `tests/` holds a test.
+fn has_unit_tests(dir: &Path) -> bool {
+    rust_files(dir)
+        .filter(|path| !path.starts_with(dir.join("tests")))
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .any(|content| {
+            let compact: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+            compact.contains("#[cfg(test)]") || compact.contains("#[test]")
+        })
+}
+
+/// Contents of the test sources under `dir`.
+fn test_sources(dir: &Path) -> Vec<String> {
+    walk(dir)
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|e| TEST_EXTENSIONS.contains(&e.to_string_lossy().as_ref()))
+        })
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .collect()
+}
+
+/// Whether a test file mentions the program, in snake_case or, ignoring
+/// case and separators, as Anchor's workspace accessor spells it.
+fn names_program(content: &str, name: &str) -> bool {
+    let snake = name.replace('-', "_");
+    let compact: String = snake.chars().filter(|&c| c != '_').collect();
+    content.contains(&snake) || content.to_lowercase().contains(&compact.to_lowercase())
+}
+
+fn rust_files(dir: &Path) -> impl Iterator<Item = std::path::PathBuf> {
+    walk(dir).filter(|path| path.extension().is_some_and(|e| e == "rs"))
+}
+
+fn walk(dir: &Path) -> impl Iterator<Item = std::path::PathBuf> {
+    WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| {
+            !(e.file_type().is_dir()
+                && SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+}
+
+fn finding(
+    repo_path: &Path,
+    program: &ProgramCrate,
+    unit: bool,
+    integration: bool,
+) -> Option<Finding> {
+    let (title, severity, missing) = match (unit, integration) {
+        (true, true) => return None,
+        (false, false) => (
+            "Untested Program",
+            Severity::Low,
+            "has no unit tests (`#[cfg(test)]`) and no integration tests",
+        ),
+        (false, true) => (
+            "Program Without Unit Tests",
+            Severity::Info,
+            "has integration tests but no unit tests (`#[cfg(test)]`)",
+        ),
+        (true, false) => (
+            "Program Without Integration Tests",
+            Severity::Info,
+            "has unit tests but no integration tests (`tests/`)",
+        ),
+    };
+    let manifest = program.dir.join("Cargo.toml");
+    let rel = program.dir.strip_prefix(repo_path).unwrap_or(&program.dir);
+    let rel = match crate::paths::to_slash(rel) {
+        dir if dir.is_empty() => ".".to_string(),
+        dir => dir,
+    };
+    Some(Finding {
+        pattern_id: PATTERN_ID.into(),
+        title: title.into(),
+        description: format!(
+            "Program `{}` ({rel}) {missing}. Edge cases in its instruction handlers have \
+             likely never been executed, so any finding in it is more likely to be real.",
+            program.name
+        ),
+        severity,
+        file_path: manifest,
+        line_number: 1,
+        code_snippet: format!("{rel}: unit tests: {unit}, integration tests: {integration}"),
+        remediation: "Add unit tests for account validation and arithmetic helpers, and \
+                      integration tests (`anchor test`, solana-program-test, LiteSVM or Mollusk) \
+                      that send each instruction with both valid and adversarial accounts."
+            .into(),
+        confidence: 0.9,
+        references: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_programs_by_missing_test_kind() {
+        let root = std::env::temp_dir().join(format!("solguard-coverage-{}", std::process::id()));
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        let anchor = "[dependencies]\nanchor-lang = \"0.30\"\n";
+        for name in ["vault", "my-staking", "oracle"] {
+            write(
+                &format!("programs/{name}/Cargo.toml"),
+                &format!("[package]\nname = \"{name}\"\n{anchor}"),
+            );
+            write(&format!("programs/{name}/src/lib.rs"), "pub fn f() {}\n");
+        }
+        write(
+            "programs/vault/src/math.rs",
+            "#[cfg( test )]\nmod tests {}\n",
+        );
+        write(
+            "tests/staking.ts",
+            "const program = anchor.workspace.MyStaking;\n",
+        );
+        write(
+            "programs/vault/tests/deposit.rs",
+            "#[test]\nfn deposit() {}\n",
+        );
+
+        let mut findings = untested_programs(&root);
+        findings.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+        let summary: Vec<_> = findings
+            .iter()
+            .map(|f| (f.title.as_str(), f.severity.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Program Without Unit Tests", Severity::Info),
+                ("Untested Program", Severity::Low),
+            ]
+        );
+        assert!(findings[0].description.contains("`my-staking`"));
+        assert!(
+            findings[1]
+                .file_path
+                .ends_with("programs/oracle/Cargo.toml")
+        );
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn single_program_repo_counts_its_tests_dir() {
+        let root =
+            std::env::temp_dir().join(format!("solguard-coverage-root-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"escrow\"\n[lib]\ncrate-type = [\"cdylib\", \"lib\"]\n\
+             [dependencies]\nsolana-program = \"2\"\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn process() {}\n").unwrap();
+        let findings = untested_programs(&root);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].title, "Untested Program");
+        assert!(findings[0].code_snippet.starts_with(".:"));
+
+        std::fs::create_dir_all(root.join("tests")).unwrap();
+        std::fs::write(root.join("tests/flow.rs"), "#[test]\nfn flow() {}\n").unwrap();
+        let findings = untested_programs(&root);
+        assert_eq!(findings[0].title, "Program Without Unit Tests");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
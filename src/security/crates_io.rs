@@ -0,0 +1,299 @@
+//! Source of crates published to crates.io, for `solguard scan-crate`.
+//!
+//! On-chain logic often lives in library crates that many programs depend
+//! on, so a bug there has a much larger blast radius than one in a single
+//! repo. The `.crate` file (a gzipped tarball with one top-level
+//! `<name>-<version>/` directory) is downloaded, unpacked and scanned like
+//! any checkout.
+//!
+//! Unpacking only writes regular files and directories, and refuses entries
+//! that would land outside the destination.
+
+use crate::http::HttpClient;
+use anyhow::{Context, Result, bail};
+use flate2::read::GzDecoder;
+use std::fmt;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+use tracing::info;
+
+/// Where crates.io serves `.crate` files.
+pub const DOWNLOAD_BASE: &str = "https://static.crates.io/crates";
+
+const BLOCK: usize = 512;
+
+/// A published crate version, written `name@version`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CrateSpec {
+    pub name: String,
+    pub version: String,
+}
+
+impl FromStr for CrateSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((name, version)) = s.split_once('@') else {
+            return Err(format!("expected <name>@<version>, got `{s}`"));
+        };
+        // Both end up in a URL and a directory name.
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            return Err(format!("invalid crate name `{name}`"));
+        }
+        let valid_version = version.starts_with(|c: char| c.is_ascii_digit())
+            && version
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+'));
+        if !valid_version {
+            return Err(format!("invalid version `{version}`"));
+        }
+        Ok(Self {
+            name: name.to_string(),
+            version: version.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for CrateSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.name, self.version)
+    }
+}
+
+impl CrateSpec {
+    pub fn download_url(&self) -> String {
+        format!(
+            "{DOWNLOAD_BASE}/{name}/{name}-{version}.crate",
+            name = self.name,
+            version = self.version
+        )
+    }
+
+    /// Top-level directory of the unpacked crate; also the repo name its
+    /// findings are reported under.
+    pub fn dir_name(&self) -> String {
+        format!("{}-{}", self.name, self.version)
+    }
+
+    /// Tag carried by every finding from this crate.
+    pub fn tag(&self) -> String {
+        format!("crate:{self}")
+    }
+}
+
+/// Download `spec` and unpack it under `dest`. Returns the crate root.
+pub async fn fetch(http: &HttpClient, spec: &CrateSpec, dest: &Path) -> Result<PathBuf> {
+    let url = spec.download_url();
+    info!(krate = %spec, %url, "downloading crate");
+    let bytes = http
+        .get_bytes(&url)
+        .await
+        .with_context(|| format!("downloading {spec}"))?;
+    unpack(&bytes, dest).with_context(|| format!("unpacking {spec}"))?;
+    let root = dest.join(spec.dir_name());
+    if !root.is_dir() {
+        bail!("{spec}: archive has no {}/ directory", spec.dir_name());
+    }
+    Ok(root)
+}
+
+/// Unpack a gzipped tar (ustar, with GNU long names and pax `path` records)
+/// into `dest`. Links, devices and other special entries are skipped.
+pub fn unpack(crate_file: &[u8], dest: &Path) -> Result<()> {
+    let mut data = Vec::new();
+    GzDecoder::new(crate_file)
+        .read_to_end(&mut data)
+        .context("not a gzip archive")?;
+
+    let mut pos = 0;
+    let mut long_name: Option<String> = None;
+    while pos + BLOCK <= data.len() {
+        let header = &data[pos..pos + BLOCK];
+        if header.iter().all(|&b| b == 0) {
+            break;
+        }
+        let size = parse_octal(&header[124..136]).context("bad entry size")?;
+        let body_start = pos + BLOCK;
+        let Some(body) = data.get(body_start..body_start + size) else {
+            bail!("truncated archive");
+        };
+        pos = body_start + size.div_ceil(BLOCK) * BLOCK;
+
+        match header[156] {
+            b'L' => {
+                long_name = Some(c_str(body));
+                continue;
+            }
+            b'x' => {
+                long_name = pax_path(body).or(long_name);
+                continue;
+            }
+            _ => {}
+        }
+        let name = long_name.take().unwrap_or_else(|| header_name(header));
+        let Some(relative) = safe_path(&name) else {
+            bail!("refusing archive entry outside the destination: {name}");
+        };
+        let target = dest.join(relative);
+        match header[156] {
+            b'0' | 0 => {
+                if let Some(parent) = target.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::write(&target, body)?;
+            }
+            b'5' => std::fs::create_dir_all(&target)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// `name`, prefixed with the ustar `prefix` field when present.
+fn header_name(header: &[u8]) -> String {
+    let name = c_str(&header[0..100]);
+    let prefix = if &header[257..262] == b"ustar" {
+        c_str(&header[345..500])
+    } else {
+        String::new()
+    };
+    if prefix.is_empty() {
+        name
+    } else {
+        format!("{prefix}/{name}")
+    }
+}
+
+/// The `path` record of a pax extended header (`"<len> path=<value>\n"`).
+fn pax_path(body: &[u8]) -> Option<String> {
+    String::from_utf8_lossy(body).lines().find_map(|record| {
+        let (_, kv) = record.split_once(' ')?;
+        kv.strip_prefix("path=").map(String::from)
+    })
+}
+
+fn c_str(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn parse_octal(field: &[u8]) -> Option<usize> {
+    let text = c_str(field);
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(0);
+    }
+    usize::from_str_radix(text, 8).ok()
+}
+
+/// `name` as a relative path with no `..`, root or prefix components.
+fn safe_path(name: &str) -> Option<PathBuf> {
+    let path = Path::new(name);
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => out.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    (!out.as_os_str().is_empty()).then_some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::Compression;
+    use flate2::write::GzEncoder;
+    use std::io::Write;
+
+    fn entry(name: &str, typeflag: u8, body: &[u8]) -> Vec<u8> {
+        let mut header = [0u8; BLOCK];
+        header[..name.len()].copy_from_slice(name.as_bytes());
+        let size = format!("{:011o}\0", body.len());
+        header[124..136].copy_from_slice(size.as_bytes());
+        header[156] = typeflag;
+        header[257..263].copy_from_slice(b"ustar\0");
+        let mut out = header.to_vec();
+        out.extend_from_slice(body);
+        out.resize(out.len().div_ceil(BLOCK) * BLOCK, 0);
+        out
+    }
+
+    fn crate_file(entries: &[Vec<u8>]) -> Vec<u8> {
+        let mut tar: Vec<u8> = entries.concat();
+        tar.extend_from_slice(&[0u8; BLOCK * 2]);
+        let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+        gz.write_all(&tar).unwrap();
+        gz.finish().unwrap()
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("solguard-crate-{name}"));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn parses_spec() {
+        let spec: CrateSpec = "spl-token@4.0.0".parse().unwrap();
+        assert_eq!(spec.name, "spl-token");
+        assert_eq!(spec.version, "4.0.0");
+        assert_eq!(
+            spec.download_url(),
+            "https://static.crates.io/crates/spl-token/spl-token-4.0.0.crate"
+        );
+        assert_eq!(spec.dir_name(), "spl-token-4.0.0");
+        assert_eq!(spec.tag(), "crate:spl-token@4.0.0");
+
+        assert!("spl-token".parse::<CrateSpec>().is_err());
+        assert!("../x@1.0.0".parse::<CrateSpec>().is_err());
+        assert!("x@latest".parse::<CrateSpec>().is_err());
+        assert!("x@1.0.0/../../y".parse::<CrateSpec>().is_err());
+    }
+
+    #[test]
+    fn unpacks_files_and_long_names() {
+        let dest = scratch("unpack");
+        let long = format!("vault-1.0.0/src/{}.rs", "a".repeat(120));
+        let archive = crate_file(&[
+            entry("vault-1.0.0/", b'5', b""),
+            entry(
+                "vault-1.0.0/Cargo.toml",
+                b'0',
+                b"[package]\nname = \"vault\"\n",
+            ),
+            entry("././@LongLink", b'L', format!("{long}\0").as_bytes()),
+            entry("vault-1.0.0/src/aaaa", b'0', b"pub fn f() {}\n"),
+            entry("vault-1.0.0/link", b'2', b""),
+        ]);
+        unpack(&archive, &dest).unwrap();
+        assert!(
+            std::fs::read_to_string(dest.join("vault-1.0.0/Cargo.toml"))
+                .unwrap()
+                .contains("vault")
+        );
+        assert_eq!(
+            std::fs::read_to_string(dest.join(&long)).unwrap(),
+            "pub fn f() {}\n"
+        );
+        assert!(!dest.join("vault-1.0.0/link").exists());
+        std::fs::remove_dir_all(dest).unwrap();
+    }
+
+    #[test]
+    fn refuses_path_traversal() {
+        let dest = scratch("traversal");
+        let archive = crate_file(&[entry("../escaped.rs", b'0', b"x")]);
+        let err = unpack(&archive, &dest.join("inner")).unwrap_err();
+        assert!(err.to_string().contains("outside the destination"));
+        assert!(!dest.join("escaped.rs").exists());
+        assert!(safe_path("/etc/passwd").is_none());
+        assert_eq!(safe_path("./a/b.rs"), Some(PathBuf::from("a/b.rs")));
+    }
+}
@@ -0,0 +1,263 @@
+//! Cross-repo CPI graph: which repos of one narrative reference another's
+//! program ID, found by searching each repo's sources and `Anchor.toml` for
+//! the `declare_id!` values of the others. Each repo is scanned on its own,
+//! so a vault that CPIs into a lending pool in a sibling repo is invisible to
+//! both reviews; the edges here are passed to the investigator and validator
+//! as context and drawn in the report.
+
+use super::{collect_rust_files, posture, trust_graph};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// A scanned repo and the program IDs it declares.
+#[derive(Debug, Clone)]
+pub struct RepoPrograms {
+    pub repo: String,
+    pub path: PathBuf,
+    pub program_ids: Vec<String>,
+}
+
+impl RepoPrograms {
+    pub fn index(repo: &str, path: &Path) -> Self {
+        Self {
+            repo: repo.to_string(),
+            path: path.to_path_buf(),
+            program_ids: posture::find_declared_programs(path)
+                .into_iter()
+                .map(|p| p.program_id)
+                .collect(),
+        }
+    }
+}
+
+/// `caller` names `callee`'s program `program_id`, first at `file_path`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CrossRepoCall {
+    pub caller: String,
+    pub callee: String,
+    pub program_id: String,
+    /// Relative to the caller's checkout.
+    pub file_path: PathBuf,
+    pub line_number: usize,
+}
+
+impl CrossRepoCall {
+    /// "acme/vault calls acme/lending's program `Lend…` (src/cpi.rs:12)".
+    pub fn describe(&self) -> String {
+        format!(
+            "{} calls {}'s program `{}` ({}:{})",
+            self.caller,
+            self.callee,
+            self.program_id,
+            crate::paths::to_slash(&self.file_path),
+            self.line_number
+        )
+    }
+}
+
+/// Every reference from `caller` to a program the `others` declare. IDs
+/// `caller` declares itself (a fork, a vendored copy) don't count.
+pub fn calls_from(caller: &RepoPrograms, others: &[&RepoPrograms]) -> Vec<CrossRepoCall> {
+    let targets: Vec<(&str, &str)> = others
+        .iter()
+        .filter(|o| o.repo != caller.repo)
+        .flat_map(|o| {
+            o.program_ids
+                .iter()
+                .map(|id| (o.repo.as_str(), id.as_str()))
+        })
+        .filter(|(_, id)| !caller.program_ids.iter().any(|own| own == id))
+        .collect();
+    if targets.is_empty() {
+        return Vec::new();
+    }
+    let mut files = collect_rust_files(&caller.path).unwrap_or_default();
+    files.sort();
+    files.push(caller.path.join("Anchor.toml"));
+    let mut found: Vec<CrossRepoCall> = Vec::new();
+    for file in files {
+        let Ok(content) = std::fs::read_to_string(&file) else {
+            continue;
+        };
+        for (repo, id) in &targets {
+            if found
+                .iter()
+                .any(|c| c.callee == *repo && c.program_id == *id)
+            {
+                continue;
+            }
+            if let Some(i) = content.lines().position(|l| l.contains(id)) {
+                found.push(CrossRepoCall {
+                    caller: caller.repo.clone(),
+                    callee: repo.to_string(),
+                    program_id: id.to_string(),
+                    file_path: file
+                        .strip_prefix(&caller.path)
+                        .unwrap_or(&file)
+                        .to_path_buf(),
+                    line_number: i + 1,
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Calls between `repo` and the `others`, both directions.
+pub fn calls_involving(repo: &RepoPrograms, others: &[&RepoPrograms]) -> Vec<CrossRepoCall> {
+    let mut calls = calls_from(repo, others);
+    for other in others.iter().filter(|o| o.repo != repo.repo) {
+        calls.extend(calls_from(other, &[repo]));
+    }
+    calls
+}
+
+/// Initial-message section for the investigator or validator, from
+/// [`CrossRepoCall::describe`] lines; empty without any.
+pub fn context_section(calls: &[String]) -> String {
+    if calls.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from(
+        "\n\n## Cross-Repo Calls\n\
+         Repos in the same narrative reference each other's programs, and this repo is one \
+         side of each call below. Check what the caller assumes about the callee (account \
+         layouts, authorities, return values, upgradeability) and what the callee can be \
+         made to do through the caller.",
+    );
+    for call in calls {
+        let _ = write!(out, "\n- {call}");
+    }
+    out
+}
+
+/// The calls among one narrative's repos.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CrossRepoGraph {
+    pub narrative: String,
+    pub calls: Vec<CrossRepoCall>,
+}
+
+impl CrossRepoGraph {
+    /// Graph over `repos`, all of one narrative.
+    pub fn build(narrative: &str, repos: &[RepoPrograms]) -> Self {
+        let all: Vec<&RepoPrograms> = repos.iter().collect();
+        let mut calls: Vec<CrossRepoCall> =
+            repos.iter().flat_map(|r| calls_from(r, &all)).collect();
+        calls.sort();
+        Self {
+            narrative: narrative.to_string(),
+            calls,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.calls.is_empty()
+    }
+
+    /// Mermaid flowchart: a node per repo, an edge per called program.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        let repos: BTreeSet<&str> = self
+            .calls
+            .iter()
+            .flat_map(|c| [c.caller.as_str(), c.callee.as_str()])
+            .collect();
+        for repo in &repos {
+            let _ = writeln!(
+                out,
+                "    {}[\"{}\"]",
+                trust_graph::node("repo", repo),
+                trust_graph::label(repo)
+            );
+        }
+        for call in &self.calls {
+            let _ = writeln!(
+                out,
+                "    {} ==>|\"{}\"| {}",
+                trust_graph::node("repo", &call.caller),
+                short_id(&call.program_id),
+                trust_graph::node("repo", &call.callee)
+            );
+        }
+        out
+    }
+}
+
+/// `Lend…9xQz`: enough of a program ID to tell programs apart in a diagram.
+fn short_id(id: &str) -> String {
+    if id.len() <= 10 {
+        return id.to_string();
+    }
+    format!("{}…{}", &id[..4], &id[id.len() - 4..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VAULT_ID: &str = "Vau1t11111111111111111111111111111111111111";
+    const LEND_ID: &str = "Lend111111111111111111111111111111111111111";
+
+    fn repo(name: &str, files: &[(&str, &str)]) -> RepoPrograms {
+        let dir = std::env::temp_dir().join(format!(
+            "solguard-cross-repo-{}-{}",
+            name.replace('/', "-"),
+            std::process::id()
+        ));
+        for (path, content) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        RepoPrograms::index(name, &dir)
+    }
+
+    #[test]
+    fn finds_calls_between_repos_by_program_id() {
+        let vault = repo(
+            "acme/vault",
+            &[(
+                "programs/vault/src/lib.rs",
+                &format!(
+                    "declare_id!(\"{VAULT_ID}\");\n\npub const LENDING: Pubkey = pubkey!(\"{LEND_ID}\");\n"
+                ),
+            )],
+        );
+        let lending = repo(
+            "acme/lending",
+            &[(
+                "programs/lending/src/lib.rs",
+                &format!("declare_id!(\"{LEND_ID}\");\n"),
+            )],
+        );
+        // A fork declaring the vault's ID doesn't call it.
+        let fork = repo(
+            "other/vault-fork",
+            &[("src/lib.rs", &format!("declare_id!(\"{VAULT_ID}\");\n"))],
+        );
+        let graph =
+            CrossRepoGraph::build("Lending", &[vault.clone(), lending.clone(), fork.clone()]);
+        for r in [&vault, &lending, &fork] {
+            std::fs::remove_dir_all(&r.path).ok();
+        }
+
+        assert_eq!(graph.calls.len(), 1, "{graph:?}");
+        let call = &graph.calls[0];
+        assert_eq!(
+            call.describe(),
+            format!(
+                "acme/vault calls acme/lending's program `{LEND_ID}` (programs/vault/src/lib.rs:3)"
+            )
+        );
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.contains("repo_acme_vault ==>|\"Lend…1111\"| repo_acme_lending"));
+
+        let section = context_section(&[call.describe()]);
+        assert!(section.contains("## Cross-Repo Calls"));
+        assert!(section.contains("\n- acme/vault calls acme/lending's program"));
+        assert!(context_section(&[]).is_empty());
+    }
+}
@@ -0,0 +1,182 @@
+//! A team's own regex patterns, loaded from the TOML file `[scan] patterns`
+//! names and scanned alongside the built-in ones, so protocol-specific
+//! anti-patterns can be enforced without forking the crate:
+//!
+//! ```toml
+//! [[pattern]]
+//! id = "ACME-001"
+//! title = "Oracle price read without staleness check"
+//! regex = 'get_price_unchecked\s*\('
+//! severity = "High"
+//! remediation = "Use `get_price_no_older_than`."
+//! suppress_if = 'max_age|no_older_than'
+//! ```
+//!
+//! Findings carry the pattern's ID, so `disabled_patterns`,
+//! `severity_overrides` and `suppress_window` apply to them as to any other.
+
+use super::{Severity, ast_scan, regex_scan};
+use anyhow::{Context, Result, bail};
+use fancy_regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PatternSpec {
+    pub id: String,
+    /// The ID when unset.
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub description: String,
+    pub severity: Severity,
+    pub regex: String,
+    #[serde(default)]
+    pub remediation: String,
+    /// Suppresses a match when it matches within `suppress_window` lines of
+    /// it, as the built-in patterns' `suppress_if`.
+    #[serde(default)]
+    pub suppress_if: Option<String>,
+    #[serde(default = "default_suppress_window")]
+    pub suppress_window: usize,
+    /// Lines matched against `regex` together, for constructs spanning
+    /// several.
+    #[serde(default = "default_line_span")]
+    pub line_span: usize,
+    #[serde(default = "default_confidence")]
+    pub confidence: f64,
+    #[serde(default)]
+    pub references: Vec<String>,
+}
+
+fn default_suppress_window() -> usize {
+    3
+}
+
+fn default_line_span() -> usize {
+    1
+}
+
+/// Above the default `min_confidence`: a team's own rule is meant to fire.
+fn default_confidence() -> f64 {
+    0.7
+}
+
+#[derive(Debug, Deserialize)]
+struct PatternFile {
+    #[serde(default, rename = "pattern")]
+    patterns: Vec<PatternSpec>,
+}
+
+/// A pattern with its regexes compiled.
+#[derive(Debug, Clone)]
+pub struct CustomPattern {
+    pub spec: PatternSpec,
+    pub(super) regex: Regex,
+    pub(super) suppress_if: Option<Regex>,
+}
+
+impl CustomPattern {
+    pub fn title(&self) -> &str {
+        self.spec.title.as_deref().unwrap_or(&self.spec.id)
+    }
+
+    /// The definition, serialized for the scan cache's key.
+    pub fn definition(&self) -> String {
+        let s = &self.spec;
+        format!(
+            "{}\0{:?}\0{}\0{}\0{:?}\0{}\0{}\0{}",
+            s.id,
+            s.severity,
+            s.regex,
+            s.confidence,
+            s.suppress_if,
+            s.suppress_window,
+            s.line_span,
+            self.title(),
+        )
+    }
+}
+
+/// Load and compile every pattern in `path`. A bad regex, a duplicate ID or
+/// one taken by a built-in pattern fails the load rather than silently
+/// scanning without the rule.
+pub fn load(path: &Path) -> Result<Vec<CustomPattern>> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("reading patterns file {}", path.display()))?;
+    parse(&text).with_context(|| format!("patterns file {}", path.display()))
+}
+
+fn parse(text: &str) -> Result<Vec<CustomPattern>> {
+    let file: PatternFile = toml::from_str(text)?;
+    let builtin: HashSet<&str> = regex_scan::pattern_ids()
+        .chain(ast_scan::PATTERN_IDS.iter().copied())
+        .collect();
+    let mut seen = HashSet::new();
+    file.patterns
+        .into_iter()
+        .map(|spec| {
+            if spec.id.trim().is_empty() {
+                bail!("pattern with an empty id");
+            }
+            if builtin.contains(spec.id.as_str()) {
+                bail!("{}: id of a built-in pattern", spec.id);
+            }
+            if !seen.insert(spec.id.clone()) {
+                bail!("{}: duplicate id", spec.id);
+            }
+            if spec.line_span == 0 {
+                bail!("{}: line_span must be at least 1", spec.id);
+            }
+            let regex = compile(&spec.regex).with_context(|| format!("{}: regex", spec.id))?;
+            let suppress_if = spec
+                .suppress_if
+                .as_deref()
+                .map(compile)
+                .transpose()
+                .with_context(|| format!("{}: suppress_if", spec.id))?;
+            Ok(CustomPattern {
+                spec,
+                regex,
+                suppress_if,
+            })
+        })
+        .collect()
+}
+
+fn compile(regex: &str) -> Result<Regex> {
+    Ok(RegexBuilder::new(regex).backtrack_limit(10_000).build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_rejects_bad_patterns() {
+        let patterns = parse(
+            r#"
+            [[pattern]]
+            id = "ACME-001"
+            regex = 'get_price_unchecked\s*\('
+            severity = "High"
+            suppress_if = 'max_age'
+            "#,
+        )
+        .unwrap();
+        assert_eq!(patterns.len(), 1);
+        assert_eq!(patterns[0].title(), "ACME-001");
+        assert_eq!(patterns[0].spec.confidence, 0.7);
+        assert!(patterns[0].suppress_if.is_some());
+        assert!(parse("").unwrap().is_empty());
+
+        let err = |text: &str| format!("{:#}", parse(text).unwrap_err());
+        let one = |id: &str, regex: &str| {
+            format!("[[pattern]]\nid = \"{id}\"\nregex = '{regex}'\nseverity = \"Low\"\n")
+        };
+        assert!(err(&one("SOL-001", "x")).contains("built-in"));
+        assert!(err(&one("ACME-002", "(unclosed")).contains("ACME-002: regex"));
+        assert!(err(&format!("{}{}", one("A-1", "x"), one("A-1", "y"))).contains("duplicate"));
+    }
+}
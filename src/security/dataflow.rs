@@ -0,0 +1,640 @@
+//! Intra-procedural taint tracking over the `syn` AST.
+//!
+//! Every handler parameter other than the Anchor `Context` is treated as
+//! user-controlled instruction data. Taint follows `let` bindings and plain
+//! assignments through the function body, in order, and is cleared by a
+//! `require!`/`assert!` that mentions the variable or by an `if` on it whose
+//! body returns or errors. A [`Flow`] is recorded when tainted data reaches
+//! one of the [`SinkKind`] sinks first.
+//!
+//! The scanner uses flows to decide the line-regex patterns SOL-003 and
+//! SOL-007: a match backed by a flow is confirmed, one without is dropped
+//! below the confidence threshold.
+
+use super::Finding;
+use proc_macro2::{Spacing, TokenStream, TokenTree};
+use quote::ToTokens;
+use serde::Serialize;
+use std::collections::HashMap;
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+use syn::{BinOp, Block, Expr, FnArg, Pat, Stmt, Type};
+
+/// Where tainted data ended up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SinkKind {
+    /// Lamport balance mutation or system-program transfer amount.
+    LamportTransfer,
+    /// Program invoked by `invoke`/`invoke_signed`/`CpiContext`.
+    CpiTarget,
+    /// Seeds passed to `find_program_address`/`create_program_address`.
+    PdaSeed,
+    /// Operand of unchecked `+`, `-` or `*`.
+    Arithmetic,
+}
+
+impl SinkKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::LamportTransfer => "lamport transfer",
+            Self::CpiTarget => "CPI target",
+            Self::PdaSeed => "PDA seed",
+            Self::Arithmetic => "unchecked arithmetic",
+        }
+    }
+}
+
+/// Unvalidated instruction input reaching a sink.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Flow {
+    pub function: String,
+    /// Parameter the data came from.
+    pub source: String,
+    pub sink: SinkKind,
+    /// 1-based line of the sink expression.
+    pub line: usize,
+}
+
+/// Patterns whose confidence the dataflow pass decides, and the sink that
+/// confirms each.
+const REFINED: &[(&str, SinkKind)] = &[
+    ("SOL-003", SinkKind::Arithmetic),
+    ("SOL-007", SinkKind::CpiTarget),
+];
+
+/// Confidence of a regex match backed by a flow.
+const CONFIRMED: f64 = 0.8;
+
+/// Confidence of a regex match with no flow — below `MIN_CONFIDENCE`.
+const UNCONFIRMED: f64 = 0.3;
+
+/// Flows in `content`, which must parse as a Rust file.
+pub fn analyze(content: &str) -> anyhow::Result<Vec<Flow>> {
+    Ok(analyze_file(&syn::parse_file(content)?))
+}
+
+/// Flows in every function and method of `ast`, by line.
+pub fn analyze_file(ast: &syn::File) -> Vec<Flow> {
+    let mut collector = FnCollector::default();
+    collector.visit_file(ast);
+    let mut flows = collector.flows;
+    flows.sort_by_key(|f| f.line);
+    flows.dedup_by(|a, b| a.line == b.line && a.sink == b.sink && a.function == b.function);
+    flows
+}
+
+/// Confirm or demote the SOL-003/SOL-007 findings of one file against its flows.
+pub(crate) fn refine(findings: &mut [Finding], flows: &[Flow]) {
+    for f in findings {
+        let Some(&(_, sink)) = REFINED.iter().find(|(id, _)| *id == f.pattern_id) else {
+            continue;
+        };
+        match flows
+            .iter()
+            .find(|fl| fl.sink == sink && fl.line == f.line_number)
+        {
+            Some(flow) => {
+                f.confidence = f.confidence.max(CONFIRMED);
+                f.description = format!(
+                    "{} Dataflow: parameter `{}` of `{}` reaches this {} without validation.",
+                    f.description,
+                    flow.source,
+                    flow.function,
+                    flow.sink.label()
+                );
+            }
+            None => f.confidence = f.confidence.min(UNCONFIRMED),
+        }
+    }
+}
+
+#[derive(Default)]
+struct FnCollector {
+    flows: Vec<Flow>,
+}
+
+impl<'ast> Visit<'ast> for FnCollector {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.analyze_fn(&node.sig, &node.block);
+        visit::visit_item_fn(self, node);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.analyze_fn(&node.sig, &node.block);
+        visit::visit_impl_item_fn(self, node);
+    }
+}
+
+impl FnCollector {
+    fn analyze_fn(&mut self, sig: &syn::Signature, body: &Block) {
+        let mut state = FnState {
+            function: sig.ident.to_string(),
+            taint: HashMap::new(),
+            flows: Vec::new(),
+        };
+        for input in &sig.inputs {
+            let FnArg::Typed(arg) = input else { continue };
+            if !is_untrusted_param(&arg.pat, &arg.ty) {
+                continue;
+            }
+            for name in pat_idents(&arg.pat) {
+                state.taint.insert(name.clone(), name);
+            }
+        }
+        if state.taint.is_empty() {
+            return;
+        }
+        state.block(body);
+        self.flows.extend(state.flows);
+    }
+}
+
+/// Instruction parameters, minus the Anchor `Context` (its accounts are
+/// validated by the constraints) and the runtime-supplied `program_id: &Pubkey`.
+fn is_untrusted_param(pat: &Pat, ty: &Type) -> bool {
+    let ty_text = ty.to_token_stream().to_string();
+    if ty_text
+        .split(|c: char| !c.is_alphanumeric())
+        .any(|w| w == "Context")
+    {
+        return false;
+    }
+    let own_program_id = matches!(ty, Type::Reference(r) if r.elem.to_token_stream().to_string() == "Pubkey")
+        && matches!(pat, Pat::Ident(p) if p.ident == "program_id");
+    !own_program_id
+}
+
+fn pat_idents(pat: &Pat) -> Vec<String> {
+    let mut names = Vec::new();
+    collect_pat_idents(pat, &mut names);
+    names
+}
+
+fn collect_pat_idents(pat: &Pat, out: &mut Vec<String>) {
+    match pat {
+        Pat::Ident(p) => out.push(p.ident.to_string()),
+        Pat::Tuple(t) => t.elems.iter().for_each(|p| collect_pat_idents(p, out)),
+        Pat::TupleStruct(t) => t.elems.iter().for_each(|p| collect_pat_idents(p, out)),
+        Pat::Struct(s) => s
+            .fields
+            .iter()
+            .for_each(|f| collect_pat_idents(&f.pat, out)),
+        Pat::Reference(r) => collect_pat_idents(&r.pat, out),
+        Pat::Type(t) => collect_pat_idents(&t.pat, out),
+        Pat::Slice(s) => s.elems.iter().for_each(|p| collect_pat_idents(p, out)),
+        _ => {}
+    }
+}
+
+/// Taint state while walking one function body in statement order.
+struct FnState {
+    function: String,
+    /// Tainted variable → parameter it derives from.
+    taint: HashMap<String, String>,
+    flows: Vec<Flow>,
+}
+
+impl FnState {
+    fn block(&mut self, block: &Block) {
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Local(local) => {
+                    let origin = local.init.as_ref().and_then(|init| {
+                        self.sinks(&init.expr);
+                        if let Some((_, diverge)) = &init.diverge {
+                            self.sinks(diverge);
+                        }
+                        self.origin(init.expr.to_token_stream())
+                    });
+                    self.bind(&local.pat, origin);
+                }
+                Stmt::Expr(expr, _) => self.expr(expr),
+                Stmt::Macro(m) => self.sanitize_macro(&m.mac),
+                Stmt::Item(_) => {}
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Assign(a) => {
+                self.sinks(&a.right);
+                if let Expr::Path(p) = &*a.left
+                    && let Some(ident) = p.path.get_ident()
+                {
+                    let origin = self.origin(a.right.to_token_stream());
+                    self.set(ident.to_string(), origin);
+                } else {
+                    self.sinks(&a.left);
+                }
+            }
+            Expr::If(i) => {
+                self.sinks(&i.cond);
+                if diverges(&i.then_branch) {
+                    self.sanitize(i.cond.to_token_stream());
+                }
+                self.block(&i.then_branch);
+                if let Some((_, else_branch)) = &i.else_branch {
+                    self.expr(else_branch);
+                }
+            }
+            Expr::Block(b) => self.block(&b.block),
+            Expr::Unsafe(u) => self.block(&u.block),
+            Expr::Loop(l) => self.block(&l.body),
+            Expr::While(w) => {
+                self.sinks(&w.cond);
+                self.block(&w.body);
+            }
+            Expr::ForLoop(f) => {
+                self.sinks(&f.expr);
+                let origin = self.origin(f.expr.to_token_stream());
+                self.bind(&f.pat, origin);
+                self.block(&f.body);
+            }
+            Expr::Match(m) => {
+                self.sinks(&m.expr);
+                for arm in &m.arms {
+                    self.expr(&arm.body);
+                }
+            }
+            Expr::Macro(m) => self.sanitize_macro(&m.mac),
+            other => self.sinks(other),
+        }
+    }
+
+    fn bind(&mut self, pat: &Pat, origin: Option<String>) {
+        for name in pat_idents(pat) {
+            self.set(name, origin.clone());
+        }
+    }
+
+    fn set(&mut self, name: String, origin: Option<String>) {
+        match origin {
+            Some(origin) => {
+                self.taint.insert(name, origin);
+            }
+            None => {
+                self.taint.remove(&name);
+            }
+        }
+    }
+
+    /// `require!`, `require_gte!`, `assert_eq!`, ... validate what they mention.
+    fn sanitize_macro(&mut self, mac: &syn::Macro) {
+        let Some(name) = mac.path.segments.last().map(|s| s.ident.to_string()) else {
+            return;
+        };
+        if name.starts_with("require") || name.starts_with("assert") {
+            self.sanitize(mac.tokens.clone());
+        }
+    }
+
+    fn sanitize(&mut self, tokens: TokenStream) {
+        for name in variables(tokens) {
+            self.taint.remove(&name);
+        }
+    }
+
+    /// Parameter behind the first tainted variable in `tokens`.
+    fn origin(&self, tokens: TokenStream) -> Option<String> {
+        variables(tokens)
+            .into_iter()
+            .find_map(|v| self.taint.get(&v).cloned())
+    }
+
+    fn sinks(&mut self, expr: &Expr) {
+        let mut finder = SinkFinder {
+            state: self,
+            found: Vec::new(),
+        };
+        finder.visit_expr(expr);
+        let found = finder.found;
+        self.flows.extend(found);
+    }
+
+    fn flow(&self, source: String, sink: SinkKind, span: proc_macro2::Span) -> Flow {
+        Flow {
+            function: self.function.clone(),
+            source,
+            sink,
+            line: span.start().line,
+        }
+    }
+}
+
+/// Whether a block exits the function: `return`, `Err(..)`, `err!`, `panic!`.
+fn diverges(block: &Block) -> bool {
+    fn walk(tokens: TokenStream) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            TokenTree::Ident(i) => {
+                matches!(i.to_string().as_str(), "return" | "Err" | "err" | "panic")
+            }
+            TokenTree::Group(g) => walk(g.stream()),
+            _ => false,
+        })
+    }
+    walk(block.to_token_stream())
+}
+
+/// Variable names in `tokens`: identifiers not preceded by `.` or `::` nor
+/// followed by a single `:` (so fields, methods, path segments and struct
+/// field labels are skipped).
+fn variables(tokens: TokenStream) -> Vec<String> {
+    let mut out = Vec::new();
+    collect_variables(tokens, &mut out);
+    out
+}
+
+fn collect_variables(tokens: TokenStream, out: &mut Vec<String>) {
+    let mut after_accessor = false;
+    let mut prev_colon = false;
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(tt) = tokens.next() {
+        match tt {
+            TokenTree::Ident(i) => {
+                let label = matches!(
+                    tokens.peek(),
+                    Some(TokenTree::Punct(p)) if p.as_char() == ':' && p.spacing() == Spacing::Alone
+                );
+                if !after_accessor && !label {
+                    out.push(i.to_string());
+                }
+                after_accessor = false;
+                prev_colon = false;
+            }
+            TokenTree::Punct(p) => {
+                let ch = p.as_char();
+                after_accessor = ch == '.' || (ch == ':' && prev_colon);
+                prev_colon = ch == ':' && p.spacing() == Spacing::Joint;
+            }
+            TokenTree::Group(g) => {
+                collect_variables(g.stream(), out);
+                after_accessor = false;
+                prev_colon = false;
+            }
+            TokenTree::Literal(_) => {
+                after_accessor = false;
+                prev_colon = false;
+            }
+        }
+    }
+}
+
+fn is_struct_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Struct(_) => true,
+        Expr::Reference(r) => is_struct_literal(&r.expr),
+        Expr::Paren(p) => is_struct_literal(&p.expr),
+        _ => false,
+    }
+}
+
+/// Finds sinks reached by tainted data within one expression.
+struct SinkFinder<'s> {
+    state: &'s FnState,
+    found: Vec<Flow>,
+}
+
+impl SinkFinder<'_> {
+    fn check(&mut self, tokens: TokenStream, sink: SinkKind, span: proc_macro2::Span) {
+        if let Some(source) = self.state.origin(tokens) {
+            self.found.push(self.state.flow(source, sink, span));
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for SinkFinder<'_> {
+    fn visit_expr_binary(&mut self, node: &'ast syn::ExprBinary) {
+        let arithmetic = matches!(
+            node.op,
+            BinOp::Add(_)
+                | BinOp::Sub(_)
+                | BinOp::Mul(_)
+                | BinOp::AddAssign(_)
+                | BinOp::SubAssign(_)
+                | BinOp::MulAssign(_)
+        );
+        if arithmetic {
+            let operands = {
+                let mut t = node.left.to_token_stream();
+                t.extend(node.right.to_token_stream());
+                t
+            };
+            self.check(operands, SinkKind::Arithmetic, node.span());
+            let lamports = matches!(node.op, BinOp::AddAssign(_) | BinOp::SubAssign(_))
+                && node.left.to_token_stream().to_string().contains("lamports");
+            if lamports {
+                self.check(
+                    node.right.to_token_stream(),
+                    SinkKind::LamportTransfer,
+                    node.span(),
+                );
+            }
+        }
+        visit::visit_expr_binary(self, node);
+    }
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let Expr::Path(p) = &*node.func {
+            let segments: Vec<String> = p
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect();
+            let last = segments.last().map(String::as_str).unwrap_or_default();
+            // An `Instruction { .. }` literal is checked field by field below.
+            let first_arg = node
+                .args
+                .first()
+                .filter(|a| !is_struct_literal(a))
+                .map(ToTokens::to_token_stream);
+            let cpi = matches!(last, "invoke" | "invoke_signed")
+                || (matches!(last, "new" | "new_with_signer")
+                    && segments.iter().any(|s| s == "CpiContext"));
+            let sink = match last {
+                _ if cpi => Some(SinkKind::CpiTarget),
+                "find_program_address" | "create_program_address" => Some(SinkKind::PdaSeed),
+                "transfer"
+                    if segments
+                        .iter()
+                        .any(|s| s == "system_instruction" || s == "system_program") =>
+                {
+                    if let Some(amount) = node.args.last() {
+                        self.check(
+                            amount.to_token_stream(),
+                            SinkKind::LamportTransfer,
+                            node.span(),
+                        );
+                    }
+                    None
+                }
+                _ => None,
+            };
+            if let (Some(sink), Some(arg)) = (sink, first_arg) {
+                self.check(arg, sink, node.span());
+            }
+        }
+        visit::visit_expr_call(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        if matches!(
+            node.method.to_string().as_str(),
+            "add_lamports" | "sub_lamports"
+        ) {
+            self.check(
+                node.args.to_token_stream(),
+                SinkKind::LamportTransfer,
+                node.span(),
+            );
+        }
+        visit::visit_expr_method_call(self, node);
+    }
+
+    fn visit_expr_struct(&mut self, node: &'ast syn::ExprStruct) {
+        if node
+            .path
+            .segments
+            .last()
+            .is_some_and(|s| s.ident == "Instruction")
+        {
+            for field in &node.fields {
+                if let syn::Member::Named(name) = &field.member
+                    && name == "program_id"
+                {
+                    self.check(
+                        field.expr.to_token_stream(),
+                        SinkKind::CpiTarget,
+                        field.span(),
+                    );
+                }
+            }
+        }
+        visit::visit_expr_struct(self, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::Severity;
+
+    fn sinks(src: &str) -> Vec<(SinkKind, usize, String)> {
+        analyze(src)
+            .unwrap()
+            .into_iter()
+            .map(|f| (f.sink, f.line, f.source))
+            .collect()
+    }
+
+    #[test]
+    fn parameter_reaches_cpi_target() {
+        let src = "\
+fn relay(accounts: &[AccountInfo], target_program: &AccountInfo, data: Vec<u8>) -> ProgramResult {
+    let target = target_program.key;
+    invoke(&ix_for(target, data), accounts)
+}";
+        assert_eq!(
+            sinks(src),
+            vec![(SinkKind::CpiTarget, 3, "target_program".into())]
+        );
+    }
+
+    #[test]
+    fn instruction_program_id_field() {
+        let src = "\
+fn forward(program_id: Pubkey, accounts: Vec<AccountMeta>) -> ProgramResult {
+    invoke(&Instruction { program_id, accounts, data: vec![] }, &[])
+}";
+        assert!(sinks(src).contains(&(SinkKind::CpiTarget, 2, "program_id".into())));
+    }
+
+    #[test]
+    fn validation_clears_taint() {
+        let src = "\
+fn forward(ix: Instruction, accounts: &[AccountInfo]) -> ProgramResult {
+    let program_id = spl_token::id();
+    assert_eq!(ix.program_id, program_id);
+    invoke(&ix, accounts)
+}
+fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    if amount > MAX_DEPOSIT {
+        return err!(VaultError::TooLarge);
+    }
+    ctx.accounts.vault.total = ctx.accounts.vault.total + amount;
+    Ok(())
+}";
+        assert!(sinks(src).is_empty());
+    }
+
+    #[test]
+    fn context_and_own_program_id_are_trusted() {
+        let src = "\
+fn handler(ctx: Context<Swap>) -> Result<()> {
+    let cpi = CpiContext::new(ctx.accounts.token_program.to_account_info(), Transfer {});
+    Ok(())
+}
+fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let (pda, _) = Pubkey::find_program_address(&[b\"vault\"], program_id);
+    invoke(&Instruction { program_id: *program_id, accounts: vec![], data: vec![] }, accounts)
+}";
+        assert!(sinks(src).is_empty());
+    }
+
+    #[test]
+    fn lamports_seeds_and_arithmetic() {
+        let src = "\
+fn withdraw(vault: &AccountInfo, user: &AccountInfo, amount: u64, seed: Vec<u8>) -> ProgramResult {
+    let (pda, _) = Pubkey::find_program_address(&[&seed], &crate::ID);
+    **vault.lamports.borrow_mut() -= amount;
+    let fee = amount * 2;
+    let fee = 5;
+    let total = fee + 1;
+    Ok(())
+}";
+        let found = sinks(src);
+        assert!(found.contains(&(SinkKind::PdaSeed, 2, "seed".into())));
+        assert!(found.contains(&(SinkKind::LamportTransfer, 3, "amount".into())));
+        assert!(found.contains(&(SinkKind::Arithmetic, 4, "amount".into())));
+        // `fee` was rebound to a constant before line 6.
+        assert!(!found.iter().any(|(_, line, _)| *line == 6));
+    }
+
+    #[test]
+    fn refine_confirms_and_demotes() {
+        let finding = |id: &str, line| Finding {
+            pattern_id: id.into(),
+            title: String::new(),
+            description: "Regex match.".into(),
+            severity: Severity::High,
+            file_path: "src/lib.rs".into(),
+            line_number: line,
+            code_snippet: String::new(),
+            remediation: String::new(),
+            confidence: 0.65,
+            references: Vec::new(),
+        };
+        let flows = vec![Flow {
+            function: "relay".into(),
+            source: "target_program".into(),
+            sink: SinkKind::CpiTarget,
+            line: 3,
+        }];
+        let mut findings = vec![
+            finding("SOL-007", 3),
+            finding("SOL-007", 9),
+            finding("SOL-001", 9),
+        ];
+        refine(&mut findings, &flows);
+        assert_eq!(findings[0].confidence, CONFIRMED);
+        assert!(
+            findings[0]
+                .description
+                .contains("`target_program` of `relay`")
+        );
+        assert_eq!(findings[1].confidence, UNCONFIRMED);
+        assert_eq!(findings[2].confidence, 0.65);
+    }
+}
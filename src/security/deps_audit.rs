@@ -0,0 +1,439 @@
+//! Dependency audit: the versions a repo's `Cargo.lock` pins for the core
+//! Solana crates, checked against the RustSec advisory database.
+//!
+//! The scan never goes online. Advisories are read from a local checkout of
+//! <https://github.com/rustsec/advisory-db> (`crates/<name>/RUSTSEC-*.md`),
+//! by default `~/.solguard/advisory-db`; refresh it with `git pull`. Without
+//! the cache the audit is skipped.
+
+use super::{Finding, Severity};
+use std::fmt;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+use walkdir::WalkDir;
+
+/// Pattern ID of dependency findings.
+pub const PATTERN_ID: &str = "DEP-001";
+
+/// Crates whose advisories are reported. Advisories for the rest of the
+/// dependency tree are `cargo audit`'s job.
+pub const WATCHED_CRATES: &[&str] = &[
+    "solana-program",
+    "anchor-lang",
+    "anchor-spl",
+    "spl-token",
+    "spl-token-2022",
+];
+
+/// Directories never searched for lockfiles.
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", ".git"];
+
+/// Default advisory cache location.
+pub fn default_db_path() -> PathBuf {
+    crate::memory::solguard_dir().join("advisory-db")
+}
+
+/// A `major.minor.patch` version; pre-release and build tags are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub fn parse(s: &str) -> Option<Self> {
+        let core = s.trim().split(['-', '+']).next()?;
+        let mut parts = core.split('.').map(|p| p.parse::<u64>().ok());
+        Some(Self {
+            major: parts.next()??,
+            minor: parts.next().unwrap_or(Some(0))?,
+            patch: parts.next().unwrap_or(Some(0))?,
+        })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Whether `version` satisfies a Cargo-style requirement such as
+/// `">= 1.9.28, < 1.10.0"` or `"^0.29"`. Unparseable requirements match
+/// nothing.
+pub fn matches_req(version: Version, req: &str) -> bool {
+    req.split(',')
+        .all(|c| matches_comparator(version, c.trim()))
+}
+
+fn matches_comparator(v: Version, comparator: &str) -> bool {
+    let (op, rest) = [">=", "<=", ">", "<", "=", "^", "~"]
+        .iter()
+        .find_map(|op| comparator.strip_prefix(op).map(|r| (*op, r)))
+        .unwrap_or(("^", comparator));
+    let rest = rest.trim();
+    let Some(bound) = Version::parse(rest) else {
+        return false;
+    };
+    let given = rest
+        .split(['-', '+'])
+        .next()
+        .unwrap_or("")
+        .split('.')
+        .count();
+    match op {
+        ">=" => v >= bound,
+        "<=" => v <= bound,
+        ">" => v > bound,
+        "<" => v < bound,
+        "=" => v == bound,
+        "~" => v >= bound && v < tilde_upper(bound, given),
+        _ => v >= bound && v < caret_upper(bound, given),
+    }
+}
+
+fn caret_upper(b: Version, given: usize) -> Version {
+    if b.major > 0 || given == 1 {
+        Version {
+            major: b.major + 1,
+            minor: 0,
+            patch: 0,
+        }
+    } else if b.minor > 0 || given == 2 {
+        Version {
+            minor: b.minor + 1,
+            patch: 0,
+            ..b
+        }
+    } else {
+        Version {
+            patch: b.patch + 1,
+            ..b
+        }
+    }
+}
+
+fn tilde_upper(b: Version, given: usize) -> Version {
+    if given == 1 {
+        Version {
+            major: b.major + 1,
+            minor: 0,
+            patch: 0,
+        }
+    } else {
+        Version {
+            minor: b.minor + 1,
+            patch: 0,
+            ..b
+        }
+    }
+}
+
+/// One RustSec advisory, as much of it as the audit uses.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Advisory {
+    pub id: String,
+    pub package: String,
+    pub title: String,
+    pub url: Option<String>,
+    pub patched: Vec<String>,
+    pub unaffected: Vec<String>,
+    /// Unsound or unmaintained notices rather than vulnerabilities.
+    pub informational: bool,
+    pub withdrawn: bool,
+}
+
+impl Advisory {
+    /// Parse an advisory-db Markdown file: a ```` ```toml ```` front matter
+    /// block followed by a `# Title` heading.
+    pub fn parse(content: &str) -> Option<Self> {
+        let body = content.trim_start().strip_prefix("```toml")?;
+        let (front, rest) = body.split_once("\n```")?;
+        let table: toml::Table = toml::from_str(front).ok()?;
+        let advisory = table.get("advisory")?.as_table()?;
+        let versions = table.get("versions").and_then(|v| v.as_table());
+        let strings = |key: &str| -> Vec<String> {
+            versions
+                .and_then(|v| v.get(key))
+                .and_then(|v| v.as_array())
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|s| s.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let text = |key: &str| advisory.get(key).and_then(|v| v.as_str()).map(String::from);
+        let title = rest
+            .lines()
+            .find_map(|l| l.strip_prefix("# "))
+            .map(|t| t.trim().to_string())
+            .unwrap_or_default();
+        Some(Self {
+            id: text("id")?,
+            package: text("package")?,
+            title,
+            url: text("url"),
+            patched: strings("patched"),
+            unaffected: strings("unaffected"),
+            informational: advisory.contains_key("informational"),
+            withdrawn: advisory.contains_key("withdrawn"),
+        })
+    }
+
+    /// Affected unless a patched or unaffected requirement covers `version`.
+    pub fn affects(&self, version: Version) -> bool {
+        !self
+            .patched
+            .iter()
+            .chain(&self.unaffected)
+            .any(|req| matches_req(version, req))
+    }
+}
+
+/// Advisories for the watched crates in the cache at `db`, skipping
+/// informational and withdrawn ones. A missing cache yields none.
+pub fn load_advisories(db: &Path) -> Vec<Advisory> {
+    let mut advisories = Vec::new();
+    for krate in WATCHED_CRATES {
+        let Ok(entries) = std::fs::read_dir(db.join("crates").join(krate)) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+        paths.sort();
+        for path in paths
+            .iter()
+            .filter(|p| p.extension().is_some_and(|e| e == "md"))
+        {
+            match std::fs::read_to_string(path)
+                .ok()
+                .and_then(|c| Advisory::parse(&c))
+            {
+                Some(a) if !a.informational && !a.withdrawn => advisories.push(a),
+                Some(_) => {}
+                None => debug!(path = %path.display(), "unparseable advisory, skipping"),
+            }
+        }
+    }
+    advisories
+}
+
+/// A package pinned by a lockfile, with the line its entry starts on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LockedPackage {
+    pub name: String,
+    pub version: String,
+    pub line: usize,
+}
+
+/// Watched packages pinned by a `Cargo.lock`.
+pub fn locked_packages(content: &str) -> Vec<LockedPackage> {
+    lock_entries(content)
+        .into_iter()
+        .filter(|pkg| WATCHED_CRATES.contains(&pkg.name.as_str()))
+        .collect()
+}
+
+/// Every package pinned by a `Cargo.lock`.
+pub fn lock_entries(content: &str) -> Vec<LockedPackage> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut out = Vec::new();
+    for (i, line) in lines.iter().enumerate() {
+        let Some(name) = quoted_value(line, "name") else {
+            continue;
+        };
+        if let Some(version) = lines.get(i + 1).and_then(|l| quoted_value(l, "version")) {
+            out.push(LockedPackage {
+                name: name.to_string(),
+                version: version.to_string(),
+                line: i + 1,
+            });
+        }
+    }
+    out
+}
+
+/// `value` of a `key = "value"` lockfile line.
+fn quoted_value<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    line.trim()
+        .strip_prefix(key)?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim()
+        .strip_prefix('"')?
+        .strip_suffix('"')
+}
+
+/// `Cargo.lock` files of the repo and its nested workspaces.
+fn lockfiles(repo_path: &Path) -> Vec<PathBuf> {
+    WalkDir::new(repo_path)
+        .max_depth(4)
+        .into_iter()
+        .filter_entry(|e| {
+            !e.file_type().is_dir()
+                || !SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref())
+        })
+        .flatten()
+        .filter(|e| e.file_type().is_file() && e.file_name() == "Cargo.lock")
+        .map(|e| e.into_path())
+        .collect()
+}
+
+/// One finding per (lockfile, pinned watched package, advisory affecting it).
+pub fn audit(repo_path: &Path, db: &Path) -> Vec<Finding> {
+    if !db.join("crates").is_dir() {
+        debug!(db = %db.display(), "no advisory cache, skipping dependency audit");
+        return Vec::new();
+    }
+    let advisories = load_advisories(db);
+    let mut findings = Vec::new();
+    for lockfile in lockfiles(repo_path) {
+        let Ok(content) = std::fs::read_to_string(&lockfile) else {
+            continue;
+        };
+        for pkg in locked_packages(&content) {
+            let Some(version) = Version::parse(&pkg.version) else {
+                continue;
+            };
+            for advisory in advisories
+                .iter()
+                .filter(|a| a.package == pkg.name && a.affects(version))
+            {
+                findings.push(finding(&lockfile, &content, &pkg, advisory));
+            }
+        }
+    }
+    if !findings.is_empty() {
+        info!(
+            count = findings.len(),
+            "vulnerable dependency versions pinned"
+        );
+    }
+    findings
+}
+
+fn finding(lockfile: &Path, content: &str, pkg: &LockedPackage, advisory: &Advisory) -> Finding {
+    let fix = if advisory.patched.is_empty() {
+        "No patched release is listed; replace the dependency or pin an unaffected version."
+            .to_string()
+    } else {
+        format!(
+            "Upgrade {} to a patched version ({}) and commit the updated Cargo.lock.",
+            pkg.name,
+            advisory.patched.join("; ")
+        )
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let start = pkg.line.saturating_sub(1);
+    let end = (pkg.line + 2).min(lines.len());
+    let mut references = vec![advisory.id.clone()];
+    references.extend(advisory.url.clone());
+    Finding {
+        pattern_id: PATTERN_ID.into(),
+        title: format!("Vulnerable Dependency: {} {}", pkg.name, pkg.version),
+        description: format!(
+            "{}: {}. Cargo.lock pins {} {}, which the advisory affects.",
+            advisory.id, advisory.title, pkg.name, pkg.version
+        ),
+        severity: Severity::High,
+        file_path: lockfile.to_path_buf(),
+        line_number: pkg.line,
+        code_snippet: lines[start..end]
+            .iter()
+            .enumerate()
+            .map(|(i, l)| format!("{:>4} | {l}", start + i + 1))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        remediation: fix,
+        confidence: 0.9,
+        references,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADVISORY: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-0000-0001"
+package = "anchor-lang"
+date = "2024-01-01"
+url = "https://example.com/advisory"
+
+[versions]
+patched = [">= 0.29.1"]
+unaffected = ["< 0.20.0"]
+```
+
+# Missing account validation in generated code
+
+Details.
+"#;
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn requirements() {
+        assert!(matches_req(v("1.9.30"), ">= 1.9.28, < 1.10.0"));
+        assert!(!matches_req(v("1.10.0"), ">= 1.9.28, < 1.10.0"));
+        assert!(matches_req(v("0.29.4"), "^0.29"));
+        assert!(!matches_req(v("0.30.0"), "^0.29"));
+        assert!(matches_req(v("1.18.2"), "^1.16"));
+        assert!(!matches_req(v("2.0.0"), "1.16"));
+        assert!(matches_req(v("0.0.3"), "^0.0.3") && !matches_req(v("0.0.4"), "^0.0.3"));
+        assert!(matches_req(v("4.0.9"), "~4.0.1") && !matches_req(v("4.1.0"), "~4.0.1"));
+        assert!(matches_req(v("1.2.3-beta.1"), "= 1.2.3"));
+        assert!(!matches_req(v("1.0.0"), ">= nonsense"));
+    }
+
+    #[test]
+    fn parses_advisory_and_checks_ranges() {
+        let a = Advisory::parse(ADVISORY).unwrap();
+        assert_eq!(a.id, "RUSTSEC-0000-0001");
+        assert_eq!(a.title, "Missing account validation in generated code");
+        assert!(a.affects(v("0.29.0")));
+        assert!(!a.affects(v("0.29.1")));
+        assert!(!a.affects(v("0.19.0")));
+        assert!(Advisory::parse("# no front matter").is_none());
+    }
+
+    #[test]
+    fn audits_lockfile_against_cache() {
+        let root = std::env::temp_dir().join(format!("solguard-deps-{}", std::process::id()));
+        let db = root.join("db");
+        let repo = root.join("repo");
+        std::fs::create_dir_all(db.join("crates/anchor-lang")).unwrap();
+        std::fs::create_dir_all(repo.join("target")).unwrap();
+        std::fs::write(db.join("crates/anchor-lang/RUSTSEC-0000-0001.md"), ADVISORY).unwrap();
+        std::fs::write(
+            db.join("crates/anchor-lang/RUSTSEC-0000-0002.md"),
+            ADVISORY
+                .replace("0000-0001", "0000-0002")
+                .replace("[advisory]", "[advisory]\ninformational = \"unsound\""),
+        )
+        .unwrap();
+        let lock = "version = 3\n\n[[package]]\nname = \"anchor-lang\"\nversion = \"0.29.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n\n[[package]]\nname = \"serde\"\nversion = \"1.0.0\"\n";
+        std::fs::write(repo.join("Cargo.lock"), lock).unwrap();
+        std::fs::write(repo.join("target/Cargo.lock"), lock).unwrap();
+
+        let findings = audit(&repo, &db);
+        let missing = audit(&repo, &root.join("missing"));
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(findings.len(), 1, "{findings:?}");
+        let f = &findings[0];
+        assert_eq!(f.pattern_id, PATTERN_ID);
+        assert_eq!(f.title, "Vulnerable Dependency: anchor-lang 0.29.0");
+        assert_eq!(f.line_number, 4);
+        assert!(
+            f.description
+                .starts_with("RUSTSEC-0000-0001: Missing account")
+        );
+        assert!(f.remediation.contains(">= 0.29.1"));
+        assert!(missing.is_empty());
+    }
+}
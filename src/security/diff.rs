@@ -0,0 +1,240 @@
+//! Diff-restricted scans (`scan --diff <base-ref>`), for gating pull requests.
+//!
+//! The lines changed since the merge base of `<base-ref>` and `HEAD` come from
+//! `git diff`, working-tree edits and untracked files included. The repo is
+//! still scanned whole, since reachability and dedup need every file, but only
+//! findings on changed lines are kept. Deleted lines leave nothing to flag, so
+//! the lines either side of a deletion count as changed: removing a check
+//! surfaces on the code it used to guard.
+
+use super::SecurityFinding;
+use anyhow::{Context, Result, bail};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Changed line ranges (1-based, inclusive) per repo-relative file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangedLines {
+    files: BTreeMap<PathBuf, Vec<(usize, usize)>>,
+}
+
+impl ChangedLines {
+    /// Lines changed between the merge base of `base` and `HEAD` and the
+    /// working tree of the git checkout at `repo_path`.
+    pub async fn since(repo_path: &Path, base: &str) -> Result<Self> {
+        let merge_base = git(repo_path, &["merge-base", base, "HEAD"])
+            .await
+            .with_context(|| format!("finding the merge base of {base} and HEAD"))?;
+        // `--relative` keeps paths relative to `repo_path` when it is a
+        // subdirectory of the checkout.
+        let diff = git(
+            repo_path,
+            &[
+                "diff",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+                "--relative",
+                merge_base.trim(),
+            ],
+        )
+        .await?;
+        let mut changes = Self::parse(&diff);
+        let untracked = git(repo_path, &["ls-files", "--others", "--exclude-standard"]).await?;
+        for file in untracked.lines().filter(|l| !l.is_empty()) {
+            changes
+                .files
+                .insert(PathBuf::from(file), vec![(1, usize::MAX)]);
+        }
+        Ok(changes)
+    }
+
+    /// Changed lines from `git diff --unified=0` output.
+    pub fn parse(diff: &str) -> Self {
+        let mut files: BTreeMap<PathBuf, Vec<(usize, usize)>> = BTreeMap::new();
+        let mut current: Option<PathBuf> = None;
+        for line in diff.lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                // Deleted files (`+++ /dev/null`) have no lines left to flag.
+                current = path.strip_prefix("b/").map(PathBuf::from);
+            } else if let Some(hunk) = line.strip_prefix("@@ ")
+                && let Some(file) = &current
+                && let Some(range) = new_range(hunk)
+            {
+                files.entry(file.clone()).or_default().push(range);
+            }
+        }
+        Self { files }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Repo-relative paths of the changed files.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.keys().map(PathBuf::as_path)
+    }
+
+    /// Whether `line` of `file` changed. Line 0 (a finding on the file as a
+    /// whole, such as an agent finding) matches any change to the file.
+    pub fn contains(&self, file: &Path, line: usize) -> bool {
+        self.files.get(file).is_some_and(|ranges| {
+            line == 0
+                || ranges
+                    .iter()
+                    .any(|&(start, end)| start <= line && line <= end)
+        })
+    }
+
+    /// Drop findings outside the changed lines; returns how many were dropped.
+    pub fn retain_introduced(
+        &self,
+        findings: &mut Vec<SecurityFinding>,
+        repo_path: &Path,
+    ) -> usize {
+        let before = findings.len();
+        findings.retain(|f| {
+            let rel = f.file_path.strip_prefix(repo_path).unwrap_or(&f.file_path);
+            self.contains(rel, f.line_number)
+        });
+        before - findings.len()
+    }
+}
+
+/// The new-file side of a hunk header (`-a,b +c,d @@ ...`).
+fn new_range(hunk: &str) -> Option<(usize, usize)> {
+    let new = hunk.split_whitespace().find_map(|s| s.strip_prefix('+'))?;
+    let (start, count) = match new.split_once(',') {
+        Some((start, count)) => (start.parse::<usize>().ok()?, count.parse::<usize>().ok()?),
+        None => (new.parse::<usize>().ok()?, 1),
+    };
+    if count == 0 {
+        // Pure deletion after line `start`.
+        Some((start.max(1), start + 1))
+    } else {
+        Some((start, start + count - 1))
+    }
+}
+
+pub(super) async fn git(repo_path: &Path, args: &[&str]) -> Result<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(repo_path)
+        .args(args)
+        .output()
+        .await
+        .context("running git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/programs/vault/src/lib.rs b/programs/vault/src/lib.rs
+index 1111111..2222222 100644
+--- a/programs/vault/src/lib.rs
++++ b/programs/vault/src/lib.rs
+@@ -10,0 +11,3 @@ pub mod vault {
++    let a = 1;
++    let b = 2;
++    let c = 3;
+@@ -40 +43 @@ fn withdraw
+-    require!(ctx.accounts.authority.is_signer);
++    msg!(\"withdraw\");
+@@ -60,2 +62,0 @@ fn close
+-    check_owner(&ctx)?;
+-    check_seeds(&ctx)?;
+diff --git a/old.rs b/old.rs
+deleted file mode 100644
+--- a/old.rs
++++ /dev/null
+@@ -1,2 +0,0 @@
+-fn gone() {}
+-fn also_gone() {}
+";
+
+    #[test]
+    fn parses_added_modified_and_deleted_hunks() {
+        let changes = ChangedLines::parse(DIFF);
+        let lib = Path::new("programs/vault/src/lib.rs");
+        assert_eq!(changes.files().collect::<Vec<_>>(), vec![lib]);
+        assert!(changes.contains(lib, 11) && changes.contains(lib, 13));
+        assert!(!changes.contains(lib, 14));
+        assert!(changes.contains(lib, 43));
+        // Either side of the deletion.
+        assert!(changes.contains(lib, 62) && changes.contains(lib, 63));
+        assert!(!changes.contains(lib, 64));
+        assert!(changes.contains(lib, 0));
+        assert!(!changes.contains(Path::new("old.rs"), 0));
+    }
+
+    #[test]
+    fn keeps_only_findings_on_changed_lines() {
+        let changes = ChangedLines::parse(DIFF);
+        let finding = |file: &str, line| SecurityFinding {
+            file_path: PathBuf::from(file),
+            line_number: line,
+            ..Default::default()
+        };
+        let mut findings = vec![
+            finding("repo/programs/vault/src/lib.rs", 12),
+            finding("repo/programs/vault/src/lib.rs", 30),
+            finding("programs/vault/src/lib.rs", 0),
+            finding("programs/other/src/lib.rs", 12),
+        ];
+        assert_eq!(
+            changes.retain_introduced(&mut findings, Path::new("repo")),
+            2
+        );
+        assert_eq!(
+            findings.iter().map(|f| f.line_number).collect::<Vec<_>>(),
+            vec![12, 0]
+        );
+    }
+
+    #[tokio::test]
+    async fn reads_changes_since_the_merge_base() {
+        let repo = std::env::temp_dir().join(format!("solguard-diff-{}", std::process::id()));
+        std::fs::create_dir_all(&repo).unwrap();
+        let run = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {args:?}");
+        };
+        run(&["init", "-q", "-b", "main"]);
+        std::fs::write(repo.join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-qm", "base"]);
+        run(&["checkout", "-qb", "feature"]);
+        std::fs::write(repo.join("lib.rs"), "fn a() {}\nfn b() { x }\nfn c() {}\n").unwrap();
+        run(&["commit", "-qam", "change"]);
+        std::fs::write(repo.join("new.rs"), "fn d() {}\n").unwrap();
+
+        let changes = ChangedLines::since(&repo, "main").await;
+        let missing = ChangedLines::since(&repo, "no-such-ref").await;
+        std::fs::remove_dir_all(&repo).ok();
+        let changes = changes.unwrap();
+        assert!(!changes.contains(Path::new("lib.rs"), 1));
+        assert!(
+            changes.contains(Path::new("lib.rs"), 2) && changes.contains(Path::new("lib.rs"), 3)
+        );
+        assert!(changes.contains(Path::new("new.rs"), 1));
+        assert!(missing.is_err());
+    }
+}
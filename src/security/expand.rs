@@ -0,0 +1,406 @@
+//! Optional `cargo expand` pass for the AST scanner.
+//!
+//! Handlers and account structs generated by `macro_rules!` or custom derives
+//! never appear in the source the line-based visitor sees. When `cargo-expand`
+//! is installed, each program crate is expanded and the visitor runs over the
+//! result, with compiler/Anchor plumbing stripped out. Findings are mapped
+//! back to the source line with the same text, else to the macro invocation
+//! that names the generated item, else to the crate root.
+
+use super::{DEFAULT_EXCLUDED_DIRS, Finding, ast_scan};
+use anyhow::{Context, Result};
+use quote::ToTokens;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{Attribute, Item};
+use tracing::{info, warn};
+use walkdir::WalkDir;
+
+/// Per-crate limit; expansion compiles the crate's dependencies.
+const EXPAND_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Program crates expanded per repo, so monorepos don't stall the scan.
+const MAX_CRATES: usize = 8;
+
+/// Whether `cargo expand` can run here.
+pub async fn available() -> bool {
+    tokio::process::Command::new("cargo")
+        .args(["expand", "--version"])
+        .output()
+        .await
+        .is_ok_and(|o| o.status.success())
+}
+
+/// Findings from the expanded code of every program crate in `repo_path`.
+/// Crates that fail to expand are logged and skipped.
+pub async fn scan_repo(repo_path: &Path) -> Vec<Finding> {
+    if !available().await {
+        warn!("expand_macros is set but `cargo expand` is not installed, skipping");
+        return Vec::new();
+    }
+    let mut findings = Vec::new();
+    for crate_dir in program_crates(repo_path) {
+        let expanded = match expand_crate(&crate_dir).await {
+            Ok(code) => code,
+            Err(e) => {
+                warn!(krate = %crate_dir.display(), error = %e, "cargo expand failed, skipping");
+                continue;
+            }
+        };
+        match scan_expanded(&expanded, &crate_dir) {
+            Ok(found) => {
+                info!(krate = %crate_dir.display(), count = found.len(), "expanded scan done");
+                findings.extend(found);
+            }
+            Err(e) => {
+                warn!(krate = %crate_dir.display(), error = %e, "expanded code did not parse");
+            }
+        }
+    }
+    findings
+}
+
+/// Directories holding a Solana program crate: a `Cargo.toml` that depends on
+/// a Solana framework, next to `src/lib.rs`.
+pub fn program_crates(repo_path: &Path) -> Vec<PathBuf> {
+    let mut crates: Vec<PathBuf> = WalkDir::new(repo_path)
+        .into_iter()
+        .filter_entry(|e| {
+            e.depth() == 0
+                || !e.file_type().is_dir()
+                || !DEFAULT_EXCLUDED_DIRS.contains(&e.file_name().to_string_lossy().as_ref())
+        })
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_name() == "Cargo.toml")
+        .filter_map(|e| {
+            let dir = e.path().parent()?.to_path_buf();
+            let manifest = std::fs::read_to_string(e.path()).ok()?;
+            (super::is_solana_cargo(&manifest) && dir.join("src/lib.rs").is_file()).then_some(dir)
+        })
+        .collect();
+    crates.sort();
+    crates.truncate(MAX_CRATES);
+    crates
+}
+
+async fn expand_crate(crate_dir: &Path) -> Result<String> {
+    let manifest = crate_dir.join("Cargo.toml");
+    let output = tokio::time::timeout(
+        EXPAND_TIMEOUT,
+        tokio::process::Command::new("cargo")
+            .args(["expand", "--lib", "--color", "never", "--manifest-path"])
+            .arg(&manifest)
+            .output(),
+    )
+    .await
+    .context("timed out")??;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let last = stderr.lines().rev().find(|l| !l.trim().is_empty());
+        anyhow::bail!("{}", last.unwrap_or("cargo expand failed"));
+    }
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Scan expanded source of the crate at `crate_dir` and map findings back to
+/// its files.
+pub fn scan_expanded(expanded: &str, crate_dir: &Path) -> Result<Vec<Finding>> {
+    let mut ast: syn::File = syn::parse_str(expanded)?;
+    let accounts = accounts_impls(&ast.items);
+    strip_generated(&mut ast.items, &accounts);
+
+    let crate_root = crate_dir.join("src/lib.rs");
+    let findings = ast_scan::scan_file(&ast, expanded, &crate_root);
+    if findings.is_empty() {
+        return Ok(findings);
+    }
+
+    let sources = crate_sources(crate_dir);
+    let expanded_lines: Vec<&str> = expanded.lines().collect();
+    let mut items = ItemSpans::default();
+    items.visit_file(&ast);
+
+    Ok(findings
+        .into_iter()
+        .map(|mut f| {
+            let text = expanded_lines
+                .get(f.line_number.saturating_sub(1))
+                .copied()
+                .unwrap_or_default();
+            let item = items.enclosing(f.line_number);
+            if let Some((path, line)) = same_line(text, &sources) {
+                f.file_path = path;
+                f.line_number = line;
+            } else if let Some(name) = item
+                && let Some((path, line)) = invocation_site(name, &sources)
+            {
+                f.description = format!(
+                    "{} Generated by the macro invocation here (`{name}` in `cargo expand` output).",
+                    f.description
+                );
+                f.file_path = path;
+                f.line_number = line;
+            } else {
+                f.description = format!(
+                    "{} Found in `cargo expand` output{}; no matching source line.",
+                    f.description,
+                    item.map(|n| format!(" (`{n}`)")).unwrap_or_default()
+                );
+                f.line_number = 1;
+            }
+            f
+        })
+        .collect())
+}
+
+/// Names of types with an `Accounts` impl. Expansion consumes
+/// `#[derive(Accounts)]`, so this is how account structs are recognized.
+fn accounts_impls(items: &[Item]) -> Vec<String> {
+    let mut names = Vec::new();
+    for item in items {
+        match item {
+            Item::Impl(imp) => {
+                let is_accounts = imp.trait_.as_ref().is_some_and(|(_, path, _)| {
+                    path.segments.last().is_some_and(|s| s.ident == "Accounts")
+                });
+                if is_accounts && let syn::Type::Path(ty) = imp.self_ty.as_ref() {
+                    names.extend(ty.path.segments.last().map(|s| s.ident.to_string()));
+                }
+            }
+            Item::Mod(m) => {
+                if let Some((_, inner)) = &m.content {
+                    names.extend(accounts_impls(inner));
+                }
+            }
+            _ => {}
+        }
+    }
+    names
+}
+
+/// Drop code the toolchain and frameworks generate around the program (derive
+/// impls, `__private` modules, the `#[no_mangle]` entrypoint), and re-mark
+/// account structs with the derive the visitor looks for.
+fn strip_generated(items: &mut Vec<Item>, accounts: &[String]) {
+    items.retain(|item| match item {
+        Item::Mod(m) => !m.ident.to_string().starts_with("__"),
+        Item::Impl(imp) => !has_attr(&imp.attrs, "automatically_derived"),
+        Item::Fn(f) => !has_attr(&f.attrs, "no_mangle"),
+        Item::Const(c) => c.ident != "_",
+        _ => true,
+    });
+    for item in items.iter_mut() {
+        match item {
+            Item::Mod(m) => {
+                if let Some((_, inner)) = &mut m.content {
+                    strip_generated(inner, accounts);
+                }
+            }
+            Item::Struct(s) if accounts.contains(&s.ident.to_string()) => {
+                s.attrs.push(syn::parse_quote!(#[derive(Accounts)]));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Matches `#[name]` and the 2024-edition `#[unsafe(name)]` form.
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    attrs.iter().any(|a| {
+        a.path().is_ident(name)
+            || (a.path().is_ident("unsafe") && a.to_token_stream().to_string().contains(name))
+    })
+}
+
+fn crate_sources(crate_dir: &Path) -> Vec<(PathBuf, String)> {
+    WalkDir::new(crate_dir.join("src"))
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "rs"))
+        .filter_map(|e| {
+            let content = std::fs::read_to_string(e.path()).ok()?;
+            Some((e.into_path(), content))
+        })
+        .collect()
+}
+
+/// The one source line whose text (ignoring whitespace) matches `text`.
+/// Short lines like `}` or ambiguous matches map nowhere.
+fn same_line(text: &str, sources: &[(PathBuf, String)]) -> Option<(PathBuf, usize)> {
+    let needle: String = text.split_whitespace().collect();
+    if needle.len() < 8 {
+        return None;
+    }
+    let mut hits = sources.iter().flat_map(|(path, content)| {
+        content.lines().enumerate().filter_map(|(i, line)| {
+            let squashed: String = line.split_whitespace().collect();
+            (squashed == needle).then(|| (path.clone(), i + 1))
+        })
+    });
+    let first = hits.next()?;
+    hits.next().is_none().then_some(first)
+}
+
+/// First macro invocation line (`name!(…)` or `name! {…}`) that mentions
+/// `ident` as a whole word.
+fn invocation_site(ident: &str, sources: &[(PathBuf, String)]) -> Option<(PathBuf, usize)> {
+    sources.iter().find_map(|(path, content)| {
+        content
+            .lines()
+            .position(|line| {
+                let code = line.split("//").next().unwrap_or_default();
+                code.contains('!')
+                    && !code.trim_start().starts_with("macro_rules!")
+                    && code
+                        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .any(|word| word == ident)
+            })
+            .map(|i| (path.clone(), i + 1))
+    })
+}
+
+/// Line ranges of named functions and structs in the expanded code.
+#[derive(Default)]
+struct ItemSpans {
+    spans: Vec<(String, usize, usize)>,
+}
+
+impl ItemSpans {
+    fn enclosing(&self, line: usize) -> Option<&str> {
+        self.spans
+            .iter()
+            .filter(|(_, start, end)| *start <= line && line <= *end)
+            .min_by_key(|(_, start, end)| end - start)
+            .map(|(name, _, _)| name.as_str())
+    }
+
+    fn record(&mut self, name: String, span: proc_macro2::Span) {
+        self.spans.push((name, span.start().line, span.end().line));
+    }
+}
+
+impl<'ast> Visit<'ast> for ItemSpans {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.record(node.sig.ident.to_string(), node.span());
+        syn::visit::visit_item_fn(self, node);
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast syn::ItemStruct) {
+        self.record(node.ident.to_string(), node.span());
+        syn::visit::visit_item_struct(self, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A crate whose `withdraw_all` handler comes from a `macro_rules!`.
+    fn fixture(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("solguard-expand-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"vault\"\n\n[dependencies]\nanchor-lang = \"0.30\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("src/lib.rs"),
+            r#"use anchor_lang::prelude::*;
+
+macro_rules! raw_handler {
+    ($name:ident) => {
+        pub fn $name(ptr: *const u8) -> u8 {
+            unsafe { *ptr }
+        }
+    };
+}
+
+raw_handler!(withdraw_all);
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub authority: AccountInfo<'info>,
+}
+"#,
+        )
+        .unwrap();
+        dir
+    }
+
+    const EXPANDED: &str = r#"#![feature(prelude_import)]
+#[prelude_import]
+use std::prelude::rust_2021::*;
+use anchor_lang::prelude::*;
+pub fn withdraw_all(ptr: *const u8) -> u8 {
+    unsafe { *ptr }
+}
+pub struct Withdraw<'info> {
+    pub authority: AccountInfo<'info>,
+}
+#[automatically_derived]
+impl<'info> anchor_lang::Accounts<'info> for Withdraw<'info> {
+    fn try_accounts() {
+        unsafe { core::hint::unreachable_unchecked() }
+    }
+}
+pub(crate) mod __client_accounts_withdraw {
+    pub struct Withdraw {
+        pub authority: AccountInfo,
+    }
+}
+#[no_mangle]
+pub unsafe extern "C" fn entrypoint(input: *mut u8) -> u64 {
+    0
+}
+"#;
+
+    #[test]
+    fn finds_macro_generated_items_and_maps_back() {
+        let dir = fixture("scan");
+        let findings = scan_expanded(EXPANDED, &dir).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        let lib = dir.join("src/lib.rs");
+        let unsafe_fn = findings.iter().find(|f| f.pattern_id == "AST-003").unwrap();
+        // No source line reads `pub fn withdraw_all(...)`: mapped to the invocation.
+        assert_eq!(unsafe_fn.file_path, lib);
+        assert_eq!(unsafe_fn.line_number, 11);
+        assert!(unsafe_fn.description.contains("`withdraw_all`"));
+
+        let account = findings.iter().find(|f| f.pattern_id == "AST-001").unwrap();
+        // Derive re-attached from the Accounts impl; field maps to its own line.
+        assert_eq!(account.line_number, 15);
+        let signer = findings.iter().find(|f| f.pattern_id == "SOL-001").unwrap();
+        assert_eq!(signer.line_number, 15);
+
+        // Entrypoint, derive impls and __client_accounts are plumbing.
+        assert_eq!(findings.len(), 3);
+    }
+
+    #[test]
+    fn discovers_program_crates_only() {
+        let dir = fixture("crates");
+        let client = dir.join("client");
+        std::fs::create_dir_all(client.join("src")).unwrap();
+        std::fs::write(
+            client.join("Cargo.toml"),
+            "[dependencies]\nanchor-lang = \"0.30\"\n",
+        )
+        .unwrap();
+        std::fs::write(client.join("src/lib.rs"), "").unwrap();
+        let crates = program_crates(&dir);
+        std::fs::remove_dir_all(&dir).ok();
+        assert_eq!(crates, vec![dir]);
+    }
+
+    #[test]
+    fn ambiguous_or_short_lines_do_not_map() {
+        let sources = vec![(PathBuf::from("a.rs"), "let x = 1;\n}\nlet x = 1;\n".into())];
+        assert_eq!(same_line("    let x = 1;", &sources), None);
+        assert_eq!(same_line("}", &sources), None);
+    }
+}
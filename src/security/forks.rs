@@ -0,0 +1,246 @@
+//! Fork detection and template-level finding deduplication.
+//!
+//! Repos are fingerprinted by hashing each normalized Rust source file; two
+//! repos whose fingerprints mostly overlap are treated as forks of one
+//! template. Findings that recur across a fork family (same title, severity
+//! and repo-relative file) collapse into a single finding whose `forks` lists
+//! every affected repo.
+
+use super::{SecurityFinding, ValidationStatus, collect_rust_files, fnv1a};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Tag carried by every collapsed template-level finding.
+pub const TEMPLATE_TAG: &str = "template";
+/// Minimum Jaccard similarity of source fingerprints for two repos to be forks.
+pub const FORK_SIMILARITY_THRESHOLD: f64 = 0.6;
+
+/// Hashes of every Rust file in the repo, ignoring whitespace and line comments
+/// so cosmetic edits and rebranded headers don't break the match.
+pub fn source_fingerprint(repo_path: &Path) -> HashSet<u64> {
+    collect_rust_files(repo_path)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|f| std::fs::read_to_string(f).ok())
+        .map(|content| {
+            let normalized: Vec<&str> = content
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with("//"))
+                .collect();
+            fnv1a(&normalized.join("\n"))
+        })
+        .collect()
+}
+
+/// Jaccard similarity of two fingerprints; 0.0 when either is empty.
+pub fn similarity(a: &HashSet<u64>, b: &HashSet<u64>) -> f64 {
+    let union = a.union(b).count();
+    if union == 0 {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / union as f64
+}
+
+/// Group repos into fork families. Only families of two or more are returned,
+/// each sorted by name.
+pub fn fork_families(repos: &[(String, HashSet<u64>)], threshold: f64) -> Vec<Vec<String>> {
+    // Union-find over repo indices.
+    let mut parent: Vec<usize> = (0..repos.len()).collect();
+    fn root(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+    for i in 0..repos.len() {
+        for j in i + 1..repos.len() {
+            if similarity(&repos[i].1, &repos[j].1) >= threshold {
+                let (ri, rj) = (root(&mut parent, i), root(&mut parent, j));
+                parent[rj] = ri;
+            }
+        }
+    }
+    let mut families: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+    for (i, (name, _)) in repos.iter().enumerate() {
+        let r = root(&mut parent, i);
+        families.entry(r).or_default().push(name.clone());
+    }
+    families
+        .into_values()
+        .filter(|f| f.len() > 1)
+        .map(|mut f| {
+            f.sort();
+            f
+        })
+        .collect()
+}
+
+/// Collapse findings repeated across a fork family into one template-level
+/// finding. `repos` maps repo name to its checkout path, used to resolve each
+/// finding's repo-relative file. Findings outside any family pass through.
+pub fn collapse_template_findings(
+    findings: Vec<SecurityFinding>,
+    repos: &[(String, PathBuf)],
+    families: &[Vec<String>],
+) -> Vec<SecurityFinding> {
+    type Key = (usize, String, String, PathBuf);
+    let mut groups: BTreeMap<Key, Vec<(String, SecurityFinding)>> = BTreeMap::new();
+    let mut out = Vec::new();
+    let mut order: Vec<Key> = Vec::new();
+
+    for f in findings {
+        let located = repos.iter().find_map(|(name, path)| {
+            let rel = f.file_path.strip_prefix(path).ok()?;
+            let family = families.iter().position(|fam| fam.contains(name))?;
+            Some((name.clone(), family, rel.to_path_buf()))
+        });
+        match located {
+            Some((repo, family, rel)) => {
+                let key = (family, f.title.clone(), f.severity.clone(), rel);
+                if !groups.contains_key(&key) {
+                    order.push(key.clone());
+                }
+                groups.entry(key).or_default().push((repo, f));
+            }
+            None => out.push(f),
+        }
+    }
+
+    let before = out.len() + groups.values().map(Vec::len).sum::<usize>();
+    for key in order {
+        let members = groups.remove(&key).unwrap_or_default();
+        let mut forks: Vec<String> = members.iter().map(|(r, _)| r.clone()).collect();
+        forks.sort();
+        forks.dedup();
+        if forks.len() < 2 {
+            out.extend(members.into_iter().map(|(_, f)| f));
+            continue;
+        }
+        // Keep the best-validated instance as the representative.
+        let mut representative = members
+            .into_iter()
+            .map(|(_, f)| f)
+            .max_by_key(|f| validation_rank(&f.validation_status))
+            .unwrap_or_default();
+        representative.forks = forks;
+        crate::tags::add_tag(&mut representative.tags, TEMPLATE_TAG);
+        out.push(representative);
+    }
+    if out.len() < before {
+        info!(
+            before,
+            after = out.len(),
+            "collapsed findings shared across forks"
+        );
+    }
+    out
+}
+
+/// Fingerprint the scanned repos, detect fork families, and collapse their
+/// shared findings.
+pub fn dedup_fork_findings(
+    findings: Vec<SecurityFinding>,
+    repos: &[(String, PathBuf)],
+) -> Vec<SecurityFinding> {
+    let fingerprints: Vec<(String, HashSet<u64>)> = repos
+        .iter()
+        .map(|(name, path)| (name.clone(), source_fingerprint(path)))
+        .collect();
+    let families = fork_families(&fingerprints, FORK_SIMILARITY_THRESHOLD);
+    for family in &families {
+        info!(repos = ?family, "fork family detected");
+    }
+    collapse_template_findings(findings, repos, &families)
+}
+
+fn validation_rank(status: &ValidationStatus) -> u8 {
+    match status {
+        ValidationStatus::Confirmed => 3,
+        ValidationStatus::Disputed => 2,
+        ValidationStatus::Unvalidated => 1,
+        ValidationStatus::Dismissed => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fp(hashes: &[u64]) -> HashSet<u64> {
+        hashes.iter().copied().collect()
+    }
+
+    fn finding(repo: &str, title: &str, status: ValidationStatus) -> SecurityFinding {
+        SecurityFinding {
+            title: title.into(),
+            severity: "High".into(),
+            file_path: PathBuf::from(format!("repos/{repo}/programs/amm/src/swap.rs")),
+            validation_status: status,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn families_from_similarity() {
+        let repos = vec![
+            ("amm-a".to_string(), fp(&[1, 2, 3, 4, 5])),
+            ("amm-b".to_string(), fp(&[1, 2, 3, 4, 6])),
+            ("amm-c".to_string(), fp(&[1, 2, 3, 4, 5, 7])),
+            ("lending".to_string(), fp(&[8, 9, 10])),
+        ];
+        assert_eq!(
+            fork_families(&repos, FORK_SIMILARITY_THRESHOLD),
+            vec![vec!["amm-a", "amm-b", "amm-c"]]
+        );
+        assert_eq!(similarity(&fp(&[]), &fp(&[])), 0.0);
+    }
+
+    #[test]
+    fn shared_findings_collapse_to_template() {
+        let repos: Vec<(String, PathBuf)> = ["amm-a", "amm-b", "lending"]
+            .iter()
+            .map(|r| (r.to_string(), PathBuf::from(format!("repos/{r}"))))
+            .collect();
+        let families = vec![vec!["amm-a".to_string(), "amm-b".to_string()]];
+        let findings = vec![
+            finding("amm-a", "Unchecked slippage", ValidationStatus::Unvalidated),
+            finding("amm-b", "Unchecked slippage", ValidationStatus::Confirmed),
+            finding("amm-a", "Only in A", ValidationStatus::Unvalidated),
+            finding(
+                "lending",
+                "Unchecked slippage",
+                ValidationStatus::Unvalidated,
+            ),
+        ];
+
+        let out = collapse_template_findings(findings, &repos, &families);
+        assert_eq!(out.len(), 3);
+        let template = out.iter().find(|f| !f.forks.is_empty()).unwrap();
+        assert_eq!(template.forks, vec!["amm-a", "amm-b"]);
+        assert_eq!(template.validation_status, ValidationStatus::Confirmed);
+        assert!(template.tags.contains(&TEMPLATE_TAG.to_string()));
+        assert!(
+            out.iter()
+                .any(|f| f.title == "Only in A" && f.forks.is_empty())
+        );
+    }
+
+    #[test]
+    fn fingerprint_ignores_comments_and_whitespace() {
+        let dir = std::env::temp_dir().join(format!("solguard-forks-{}", std::process::id()));
+        let (a, b) = (dir.join("a/src"), dir.join("b/src"));
+        std::fs::create_dir_all(&a).unwrap();
+        std::fs::create_dir_all(&b).unwrap();
+        std::fs::write(a.join("lib.rs"), "// Vault A\nfn f() {\n    x();\n}\n").unwrap();
+        std::fs::write(b.join("lib.rs"), "// Vault B\n\nfn f() {\n  x();\n}\n").unwrap();
+        let sim = similarity(
+            &source_fingerprint(&dir.join("a")),
+            &source_fingerprint(&dir.join("b")),
+        );
+        std::fs::remove_dir_all(&dir).ok();
+        assert!((sim - 1.0).abs() < f64::EPSILON);
+    }
+}
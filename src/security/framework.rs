@@ -0,0 +1,141 @@
+//! Which program framework a repo or file is written against.
+//!
+//! Most regex patterns are about Anchor constructs (account constraints,
+//! `init_if_needed`, `space =`) and say nothing useful about Pinocchio or raw
+//! `solana-program` code, where the same bugs look different: account data is
+//! cast with `from_bytes_mut`, accounts are pulled off the slice by hand and
+//! signer checks are explicit `is_signer()` calls. Each pattern declares the
+//! frameworks it applies to, and a file is scanned with the profile of its
+//! framework.
+
+use super::seahorse;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Framework {
+    Anchor,
+    Pinocchio,
+    /// Raw `solana-program` (or `solana-sdk`) entrypoints.
+    Native,
+}
+
+impl Framework {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Anchor => "anchor",
+            Self::Pinocchio => "pinocchio",
+            Self::Native => "native",
+        }
+    }
+
+    /// Framework a Cargo.toml depends on, if any.
+    pub fn of_manifest(content: &str) -> Option<Self> {
+        if content.contains("anchor-lang") {
+            Some(Self::Anchor)
+        } else if content.contains("pinocchio") {
+            Some(Self::Pinocchio)
+        } else if content.contains("solana-program") || content.contains("solana-sdk") {
+            Some(Self::Native)
+        } else {
+            None
+        }
+    }
+
+    /// Framework a source file is written against, from its imports and
+    /// macros; `None` when it names none (helpers, shared math).
+    pub fn of_source(content: &str) -> Option<Self> {
+        if content.contains("anchor_lang") || content.contains("#[derive(Accounts)]") {
+            Some(Self::Anchor)
+        } else if content.contains("pinocchio") {
+            Some(Self::Pinocchio)
+        } else if content.contains("solana_program") || content.contains("entrypoint!") {
+            Some(Self::Native)
+        } else {
+            None
+        }
+    }
+}
+
+/// The repo's framework: Anchor for an `Anchor.toml` or Seahorse project,
+/// else from the Cargo.toml at the root or one directory down (`programs/*`,
+/// `program/*` and `src/*` are checked a level deeper). Anchor wins over
+/// Pinocchio, which wins over native, when manifests disagree.
+pub fn detect(root: &Path) -> Option<Framework> {
+    if root.join("Anchor.toml").exists() || seahorse::is_project(root) {
+        return Some(Framework::Anchor);
+    }
+    let mut dirs = vec![root.to_path_buf()];
+    for dir in subdirs(root) {
+        if ["programs", "program", "src"]
+            .iter()
+            .any(|name| dir.ends_with(name))
+        {
+            dirs.extend(subdirs(&dir));
+        }
+        dirs.push(dir);
+    }
+    dirs.iter()
+        .filter_map(|dir| std::fs::read_to_string(dir.join("Cargo.toml")).ok())
+        .filter_map(|manifest| Framework::of_manifest(&manifest))
+        .min_by_key(|f| *f as u8)
+}
+
+fn subdirs(dir: &Path) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_framework_from_sources() {
+        assert_eq!(
+            Framework::of_source("use anchor_lang::prelude::*;"),
+            Some(Framework::Anchor)
+        );
+        assert_eq!(
+            Framework::of_source("use pinocchio::{account_info::AccountInfo, entrypoint};"),
+            Some(Framework::Pinocchio)
+        );
+        assert_eq!(
+            Framework::of_source("use solana_program::account_info::next_account_info;"),
+            Some(Framework::Native)
+        );
+        assert_eq!(Framework::of_source("pub fn mul_div(a: u64) {}"), None);
+    }
+
+    #[test]
+    fn detects_repo_framework_from_manifests() {
+        let root = std::env::temp_dir().join(format!("solguard-framework-{}", std::process::id()));
+        let program = root.join("programs/vault");
+        std::fs::create_dir_all(&program).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"programs/*\"]\n",
+        )
+        .unwrap();
+        std::fs::write(
+            program.join("Cargo.toml"),
+            "[dependencies]\npinocchio = \"0.8\"\n",
+        )
+        .unwrap();
+        let pinocchio = detect(&root);
+        std::fs::write(root.join("Anchor.toml"), "").unwrap();
+        let anchor = detect(&root);
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(pinocchio, Some(Framework::Pinocchio));
+        assert_eq!(anchor, Some(Framework::Anchor));
+        assert_eq!(detect(&root), None);
+    }
+}
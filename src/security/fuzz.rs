@@ -0,0 +1,507 @@
+//! cargo-fuzz harness skeletons for Anchor instruction handlers.
+//!
+//! One fuzz project per program crate, one target per handler. The fuzz
+//! input is the handler's instruction data plus one byte per account slot
+//! picking from a small key pool, so aliased accounts (the same key in two
+//! slots) are explored too. Account metas come from the program's own
+//! `accounts::<Struct>` client type, built from the [`trust_graph`] handler
+//! and its `Accounts` struct; program and sysvar slots get their fixed IDs.
+//!
+//! The generated target builds the `Instruction` and hands it to a `process`
+//! stub: wiring it to an execution environment (LiteSVM, `solana-program-test`)
+//! and asserting the program's invariants is left to the protocol team.
+//!
+//! [`trust_graph`]: super::trust_graph
+
+use super::SecurityFinding;
+use super::trust_graph::{self, AccountKind, Handler, HandlerArg};
+use super::workspace;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Keys in the pool account slots pick from. More slots than keys forces
+/// some aliasing.
+const KEY_POOL: usize = 4;
+
+/// Types the fuzzer can generate directly via `Arbitrary`.
+const PRIMITIVES: &[&str] = &[
+    "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "bool", "String",
+];
+
+/// Well-known program types and their IDs.
+const PROGRAM_IDS: &[(&str, &str)] = &[
+    ("System", "anchor_lang::system_program::ID"),
+    (
+        "Token",
+        "anchor_lang::solana_program::pubkey!(\"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA\")",
+    ),
+    (
+        "TokenInterface",
+        "anchor_lang::solana_program::pubkey!(\"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA\")",
+    ),
+    (
+        "Token2022",
+        "anchor_lang::solana_program::pubkey!(\"TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb\")",
+    ),
+    (
+        "AssociatedToken",
+        "anchor_lang::solana_program::pubkey!(\"ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL\")",
+    ),
+];
+
+/// A cargo-fuzz project for one program crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuzzProject {
+    /// Program crate name; also the project's directory name.
+    pub program: String,
+    /// Handlers with a target, in declaration order.
+    pub targets: Vec<String>,
+    /// Paths relative to the project directory, with their contents.
+    pub files: Vec<(PathBuf, String)>,
+}
+
+/// Fuzz projects for the Anchor programs under `repo_path`. With `findings`,
+/// only handlers that have a finding get a target, and each target lists
+/// them; without, every handler does.
+pub fn generate(repo_path: &Path, findings: Option<&[SecurityFinding]>) -> Vec<FuzzProject> {
+    let mut programs = workspace::programs(repo_path);
+    if programs.is_empty() {
+        programs.push(workspace::ProgramCrate {
+            name: root_package(repo_path).unwrap_or_else(|| {
+                repo_path
+                    .file_name()
+                    .map_or_else(|| "program".into(), |n| n.to_string_lossy().into_owned())
+            }),
+            dir: repo_path.to_path_buf(),
+            framework: None,
+        });
+    }
+    programs
+        .iter()
+        .filter_map(|p| {
+            let graph = trust_graph::build(&p.name, &p.dir);
+            let flagged = |h: &Handler| -> Vec<&SecurityFinding> {
+                findings
+                    .unwrap_or_default()
+                    .iter()
+                    .filter(|f| f.instruction.as_deref() == Some(h.name.as_str()))
+                    .filter(|f| f.program.as_ref().is_none_or(|prog| *prog == p.name))
+                    .collect()
+            };
+            let handlers: Vec<(&Handler, Vec<&SecurityFinding>)> = graph
+                .handlers
+                .iter()
+                .filter(|h| h.context.is_some())
+                .map(|h| (h, flagged(h)))
+                .filter(|(_, f)| findings.is_none() || !f.is_empty())
+                .collect();
+            (!handlers.is_empty()).then(|| project(p, &handlers))
+        })
+        .collect()
+}
+
+fn project(
+    program: &workspace::ProgramCrate,
+    handlers: &[(&Handler, Vec<&SecurityFinding>)],
+) -> FuzzProject {
+    let krate = program.name.replace('-', "_");
+    let mut files = vec![(
+        PathBuf::from("Cargo.toml"),
+        manifest(program, handlers.iter().map(|(h, _)| h.name.as_str())),
+    )];
+    for (handler, flagged) in handlers {
+        files.push((
+            PathBuf::from("fuzz_targets").join(format!("{}.rs", handler.name)),
+            target(&krate, handler, flagged),
+        ));
+    }
+    FuzzProject {
+        program: program.name.clone(),
+        targets: handlers.iter().map(|(h, _)| h.name.clone()).collect(),
+        files,
+    }
+}
+
+fn manifest<'a>(
+    program: &workspace::ProgramCrate,
+    targets: impl Iterator<Item = &'a str>,
+) -> String {
+    let dir = std::path::absolute(&program.dir).unwrap_or_else(|_| program.dir.clone());
+    let anchor = anchor_version(&program.dir).unwrap_or_else(|| "*".into());
+    let mut out = format!(
+        "[package]\n\
+         name = \"{name}-fuzz\"\n\
+         version = \"0.0.0\"\n\
+         publish = false\n\
+         edition = \"2021\"\n\
+         \n\
+         [package.metadata]\n\
+         cargo-fuzz = true\n\
+         \n\
+         [dependencies]\n\
+         libfuzzer-sys = \"0.4\"\n\
+         arbitrary = {{ version = \"1\", features = [\"derive\"] }}\n\
+         anchor-lang = \"{anchor}\"\n\
+         {name} = {{ path = \"{path}\", features = [\"no-entrypoint\"] }}\n\
+         \n\
+         [workspace]\n",
+        name = program.name,
+        path = crate::paths::to_slash(&dir),
+    );
+    for name in targets {
+        let _ = write!(
+            out,
+            "\n[[bin]]\n\
+             name = \"{name}\"\n\
+             path = \"fuzz_targets/{name}.rs\"\n\
+             test = false\n\
+             doc = false\n\
+             bench = false\n"
+        );
+    }
+    out
+}
+
+fn target(krate: &str, handler: &Handler, flagged: &[&SecurityFinding]) -> String {
+    let context = handler.context.as_deref().unwrap_or_default();
+    let mut out = format!(
+        "#![no_main]\n\
+         //! Fuzz target for `{krate}::{name}`, generated by SolGuard.\n",
+        name = handler.name
+    );
+    if !flagged.is_empty() {
+        out.push_str("//!\n//! Findings in this handler:\n");
+        for f in flagged {
+            let _ = writeln!(
+                out,
+                "//! - [{}] {} ({}:{})",
+                f.severity,
+                f.title,
+                crate::paths::to_slash(&f.file_path),
+                f.line_number
+            );
+        }
+    }
+    out.push_str(
+        "\nuse anchor_lang::prelude::*;\n\
+         use anchor_lang::solana_program::instruction::Instruction;\n\
+         use anchor_lang::{InstructionData, ToAccountMetas};\n\
+         use libfuzzer_sys::fuzz_target;\n\n\
+         #[derive(Debug, arbitrary::Arbitrary)]\n\
+         struct Input {\n",
+    );
+    for arg in &handler.args {
+        let _ = writeln!(out, "    {}: {},", arg.name, input_type(&arg.ty));
+    }
+    let slots = handler
+        .accounts
+        .iter()
+        .filter(|a| program_id(a).is_none())
+        .count();
+    if slots > 0 {
+        let _ = writeln!(
+            out,
+            "    /// Key pool index for each account slot; equal indices alias accounts.\n    \
+             accounts: [u8; {slots}],"
+        );
+    }
+    out.push_str("}\n\nfuzz_target!(|input: Input| {\n");
+    if slots > 0 {
+        let _ = writeln!(
+            out,
+            "    let keys: Vec<Pubkey> = (1..={KEY_POOL}u8).map(|i| Pubkey::new_from_array([i; 32])).collect();\n    \
+             let pick = |slot: usize| keys[usize::from(input.accounts[slot]) % keys.len()];"
+        );
+    }
+    for arg in &handler.args {
+        if let Some(decode) = decode_arg(krate, arg) {
+            out.push_str(&decode);
+        }
+    }
+    let _ = writeln!(out, "    let accounts = {krate}::accounts::{context} {{");
+    let mut slot = 0;
+    for a in &handler.accounts {
+        let (value, note) = match program_id(a) {
+            Some(id) => (id.to_string(), format!("{} program", a.ty)),
+            None => {
+                slot += 1;
+                (format!("pick({})", slot - 1), account_note(a))
+            }
+        };
+        let _ = writeln!(out, "        {}: {value}, // {note}", a.name);
+    }
+    for s in &handler.sysvars {
+        let _ = writeln!(
+            out,
+            "        {}: anchor_lang::solana_program::sysvar::{}::ID,",
+            s.name,
+            s.ty.to_lowercase()
+        );
+    }
+    out.push_str("    };\n");
+    let fields: Vec<String> = handler
+        .args
+        .iter()
+        .map(|a| match a.ty.as_str() {
+            "Pubkey" => format!("{}: Pubkey::new_from_array(input.{})", a.name, a.name),
+            ty if is_generated(ty) => format!("{}: input.{}", a.name, a.name),
+            _ => a.name.clone(),
+        })
+        .collect();
+    let _ = write!(
+        out,
+        "    let data = {krate}::instruction::{ix} {fields}.data();\n    \
+         process(Instruction {{\n        \
+         program_id: {krate}::ID,\n        \
+         accounts: accounts.to_account_metas(None),\n        \
+         data,\n    \
+         }});\n\
+         }});\n\n\
+         /// Execute `ix` and check invariants. Load the program into LiteSVM or\n\
+         /// `solana-program-test`, create the accounts it expects, send `ix` and\n\
+         /// assert that balances and state stay consistent.\n\
+         fn process(ix: Instruction) {{\n    \
+         let _ = ix;\n\
+         }}\n",
+        ix = camel_case(&handler.name),
+        fields = if fields.is_empty() {
+            "{}".into()
+        } else {
+            format!("{{ {} }}", fields.join(", "))
+        },
+    );
+    out
+}
+
+/// Field type in `Input` for a handler parameter of type `ty`.
+fn input_type(ty: &str) -> String {
+    if ty == "Pubkey" {
+        "[u8; 32]".into()
+    } else if is_generated(ty) {
+        ty.to_string()
+    } else {
+        // Borsh bytes, decoded in the target.
+        "Vec<u8>".into()
+    }
+}
+
+/// Whether `Arbitrary` can produce `ty` as-is: primitives, byte arrays and
+/// `Vec`/`Option` of those.
+fn is_generated(ty: &str) -> bool {
+    if PRIMITIVES.contains(&ty) {
+        return true;
+    }
+    if let Some(inner) = ty
+        .strip_prefix("Vec<")
+        .or_else(|| ty.strip_prefix("Option<"))
+        .and_then(|t| t.strip_suffix('>'))
+    {
+        return is_generated(inner);
+    }
+    ty.strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .and_then(|t| t.split_once(';'))
+        .is_some_and(|(elem, _)| is_generated(elem))
+}
+
+/// Borsh-decode a parameter the fuzzer can't generate; undecodable inputs
+/// are skipped.
+fn decode_arg(krate: &str, arg: &HandlerArg) -> Option<String> {
+    if arg.ty == "Pubkey" || is_generated(&arg.ty) {
+        return None;
+    }
+    Some(format!(
+        "    let Ok({name}) = <{krate}::{ty} as AnchorDeserialize>::try_from_slice(&input.{name}) else {{\n        \
+         return;\n    \
+         }};\n",
+        name = arg.name,
+        ty = arg.ty,
+    ))
+}
+
+/// Fixed address for a well-known program account.
+fn program_id(account: &trust_graph::AccountUse) -> Option<&'static str> {
+    if account.kind != AccountKind::Program {
+        return None;
+    }
+    PROGRAM_IDS
+        .iter()
+        .find(|(ty, _)| *ty == account.ty)
+        .map(|(_, id)| *id)
+}
+
+fn account_note(a: &trust_graph::AccountUse) -> String {
+    let kind = match a.kind {
+        AccountKind::Signer => "signer",
+        AccountKind::Pda => "PDA",
+        AccountKind::Program => "program",
+        AccountKind::Unchecked => "unchecked",
+        AccountKind::Typed if a.ty.is_empty() => "account",
+        AccountKind::Typed => &a.ty,
+    };
+    if a.writable {
+        format!("{kind}, writable")
+    } else {
+        kind.to_string()
+    }
+}
+
+/// `init_pool` -> `InitPool`, the name of Anchor's instruction data struct.
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map_or_else(String::new, |c| {
+                c.to_uppercase().chain(chars).collect::<String>()
+            })
+        })
+        .collect()
+}
+
+/// `[package] name` of the root manifest.
+fn root_package(root: &Path) -> Option<String> {
+    let manifest: toml::Table = std::fs::read_to_string(root.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()?;
+    Some(manifest.get("package")?.get("name")?.as_str()?.to_string())
+}
+
+/// The `anchor-lang` version the program itself depends on.
+fn anchor_version(dir: &Path) -> Option<String> {
+    let manifest: toml::Table = std::fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()?
+        .parse()
+        .ok()?;
+    let dep = manifest.get("dependencies")?.get("anchor-lang")?;
+    dep.as_str()
+        .or_else(|| dep.get("version")?.as_str())
+        .map(String::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = r#"
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod amm {
+    use super::*;
+
+    pub fn init_pool(ctx: Context<InitPool>, fee_bps: u16, admin: Pubkey) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn swap(ctx: Context<Swap>, args: SwapArgs) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(init, payer = payer, seeds = [b"pool"], bump, space = 64)]
+    pub pool: Account<'info, Pool>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    pub user: Signer<'info>,
+    #[account(mut)]
+    pub pool: Account<'info, Pool>,
+    #[account(mut)]
+    pub user_token: Account<'info, TokenAccount>,
+    pub token_program: Program<'info, Token>,
+}
+"#;
+
+    fn repo(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("solguard-fuzz-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"my-amm\"\n\n[dependencies]\nanchor-lang = \"0.30.1\"\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/lib.rs"), PROGRAM).unwrap();
+        root
+    }
+
+    #[test]
+    fn target_per_handler() {
+        let root = repo("all");
+        let projects = generate(&root, None);
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(projects.len(), 1);
+        let p = &projects[0];
+        assert_eq!(p.program, "my-amm");
+        assert_eq!(p.targets, vec!["init_pool", "swap"]);
+
+        let manifest = &p.files[0].1;
+        assert!(manifest.contains("name = \"my-amm-fuzz\""));
+        assert!(manifest.contains("anchor-lang = \"0.30.1\""));
+        assert!(manifest.contains("path = \"fuzz_targets/swap.rs\""));
+
+        let init = &p.files[1].1;
+        assert!(init.contains("    fee_bps: u16,\n    admin: [u8; 32],\n"));
+        assert!(init.contains("accounts: [u8; 2],"));
+        assert!(init.contains("let accounts = my_amm::accounts::InitPool {"));
+        assert!(init.contains("payer: pick(0), // signer, writable"));
+        assert!(init.contains("pool: pick(1), // PDA, writable"));
+        assert!(init.contains("system_program: anchor_lang::system_program::ID,"));
+        assert!(init.contains("rent: anchor_lang::solana_program::sysvar::rent::ID,"));
+        assert!(init.contains(
+            "my_amm::instruction::InitPool { fee_bps: input.fee_bps, admin: Pubkey::new_from_array(input.admin) }"
+        ));
+
+        let swap = &p.files[2].1;
+        assert!(swap.contains("    args: Vec<u8>,\n"));
+        assert!(
+            swap.contains("<my_amm::SwapArgs as AnchorDeserialize>::try_from_slice(&input.args)")
+        );
+        assert!(swap.contains("user_token: pick(2), // TokenAccount, writable"));
+        assert!(swap.contains("my_amm::instruction::Swap { args }"));
+    }
+
+    #[test]
+    fn findings_select_handlers() {
+        let root = repo("flagged");
+        let finding = SecurityFinding {
+            title: "Missing Signer Constraint".into(),
+            severity: "High".into(),
+            file_path: "src/lib.rs".into(),
+            line_number: 12,
+            instruction: Some("swap".into()),
+            ..Default::default()
+        };
+        let projects = generate(&root, Some(&[finding]));
+        assert!(generate(&root, Some(&[])).is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!(projects[0].targets, vec!["swap"]);
+        assert!(
+            projects[0].files[1]
+                .1
+                .contains("//! - [High] Missing Signer Constraint (src/lib.rs:12)")
+        );
+    }
+
+    #[test]
+    fn names_and_types() {
+        assert_eq!(camel_case("init_pool"), "InitPool");
+        assert_eq!(camel_case("swap"), "Swap");
+        assert!(is_generated("Vec<u64>"));
+        assert!(is_generated("[u8;32]"));
+        assert!(is_generated("Option<bool>"));
+        assert!(!is_generated("SwapArgs"));
+        assert!(!is_generated("Vec<Route>"));
+    }
+}
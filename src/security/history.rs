@@ -0,0 +1,377 @@
+//! Scans of a repo at a series of tags or commits (`history-scan --tags
+//! v1.0..v1.4`), for incident retrospectives: when was the unchecked root
+//! introduced, and was it ever fixed?
+//!
+//! Each revision is checked out in a temporary [`Worktree`] and run through
+//! the static scanner. Findings are matched across revisions by their
+//! baseline fingerprint (pattern, file and flagged line with whitespace
+//! removed), so code moving within a file doesn't read as a fix followed by
+//! a new finding.
+
+use super::revision::Worktree;
+use super::{SecurityFinding, baseline, diff::git};
+use crate::config::ScanConfig;
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Most revisions one history scan checks out.
+pub const MAX_REVISIONS: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Timeline {
+    /// Scanned revisions, oldest first.
+    pub revisions: Vec<Revision>,
+    /// Every finding seen at any revision, in order of first appearance.
+    pub findings: Vec<TimelineFinding>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Revision {
+    /// The tag or commit as given.
+    pub rev: String,
+    pub commit: String,
+    /// Committer date (RFC 3339).
+    pub date: String,
+    pub findings: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelineFinding {
+    pub fingerprint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pattern_id: Option<String>,
+    pub title: String,
+    pub severity: String,
+    /// Repo-relative file and line at the last revision it was seen in.
+    pub file_path: PathBuf,
+    pub line_number: usize,
+    /// Revisions it appeared or disappeared in. Present in the first
+    /// revision reads as appearing there: it predates the range.
+    pub events: Vec<Event>,
+    /// Still present in the last revision.
+    pub open: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Event {
+    pub rev: String,
+    pub change: Change,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Change {
+    Appeared,
+    Disappeared,
+}
+
+/// The revisions `spec` names, oldest first: a comma-separated list, or a
+/// range `A..B`. A range of two tags is every tag between them in version
+/// order; otherwise it is `A` and each first-parent commit up to `B`.
+pub async fn resolve(repo_path: &Path, spec: &str) -> Result<Vec<String>> {
+    let revs: Vec<String> = match spec.split_once("..") {
+        Some((from, to)) => {
+            let (from, to) = (from.trim(), to.trim());
+            let tags: Vec<String> = git(repo_path, &["tag", "--list", "--sort=v:refname"])
+                .await?
+                .lines()
+                .map(String::from)
+                .collect();
+            let position = |t: &str| tags.iter().position(|tag| tag == t);
+            match (position(from), position(to)) {
+                (Some(a), Some(b)) if a <= b => tags[a..=b].to_vec(),
+                (Some(_), Some(_)) => bail!("{from} comes after {to}"),
+                _ => {
+                    let commits = git(
+                        repo_path,
+                        &[
+                            "rev-list",
+                            "--reverse",
+                            "--first-parent",
+                            &format!("{from}..{to}"),
+                        ],
+                    )
+                    .await
+                    .with_context(|| format!("listing commits in {spec}"))?;
+                    std::iter::once(from.to_string())
+                        .chain(commits.lines().map(String::from))
+                        .collect()
+                }
+            }
+        }
+        None => spec
+            .split(',')
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty())
+            .collect(),
+    };
+    if revs.is_empty() {
+        bail!("no revisions in '{spec}'");
+    }
+    if revs.len() > MAX_REVISIONS {
+        bail!(
+            "'{spec}' names {} revisions; narrow it to at most {MAX_REVISIONS}",
+            revs.len()
+        );
+    }
+    Ok(revs)
+}
+
+/// Static-scan the repo at each of `revs` and line the results up.
+pub async fn scan(repo_path: &Path, revs: &[String], scan: &ScanConfig) -> Result<Timeline> {
+    let mut scans = Vec::new();
+    for (i, rev) in revs.iter().enumerate() {
+        let worktree = Worktree::checkout(repo_path, rev).await?;
+        info!(rev = %rev, commit = %worktree.commit, "scanning revision {}/{}", i + 1, revs.len());
+        let mut findings = super::scan_repo_with(&worktree.root, scan).await?;
+        // Fingerprints resolve paths in the checkout, so before the worktree goes.
+        let prints = baseline::fingerprints(&findings, &worktree.root);
+        worktree.attribute(&mut findings);
+        let date = git(repo_path, &["show", "-s", "--format=%cI", &worktree.commit])
+            .await?
+            .trim()
+            .to_string();
+        let revision = Revision {
+            rev: rev.clone(),
+            commit: worktree.commit.clone(),
+            date,
+            findings: findings.len(),
+        };
+        scans.push((revision, prints.into_iter().zip(findings).collect()));
+    }
+    Ok(Timeline::build(scans))
+}
+
+impl Timeline {
+    /// Line up each revision's fingerprinted findings, oldest revision first.
+    pub fn build(scans: Vec<(Revision, Vec<(String, SecurityFinding)>)>) -> Self {
+        let mut findings: Vec<TimelineFinding> = Vec::new();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut revisions = Vec::new();
+        for (revision, scanned) in scans {
+            let mut present = vec![false; findings.len()];
+            for (fingerprint, f) in scanned {
+                let i = *index.entry(fingerprint.clone()).or_insert_with(|| {
+                    findings.push(TimelineFinding {
+                        fingerprint,
+                        pattern_id: f.pattern_id.clone(),
+                        title: f.title.clone(),
+                        severity: f.severity.clone(),
+                        file_path: f.file_path.clone(),
+                        line_number: f.line_number,
+                        events: Vec::new(),
+                        open: false,
+                    });
+                    findings.len() - 1
+                });
+                present.resize(findings.len(), false);
+                if present[i] {
+                    continue;
+                }
+                present[i] = true;
+                let entry = &mut findings[i];
+                entry.file_path = f.file_path;
+                entry.line_number = f.line_number;
+                if !entry.open {
+                    entry.events.push(Event {
+                        rev: revision.rev.clone(),
+                        change: Change::Appeared,
+                    });
+                    entry.open = true;
+                }
+            }
+            for (entry, present) in findings.iter_mut().zip(&present) {
+                if entry.open && !present {
+                    entry.events.push(Event {
+                        rev: revision.rev.clone(),
+                        change: Change::Disappeared,
+                    });
+                    entry.open = false;
+                }
+            }
+            revisions.push(revision);
+        }
+        Self {
+            revisions,
+            findings,
+        }
+    }
+
+    /// One line per revision: what appeared and disappeared there.
+    pub fn summary(&self) -> String {
+        self.revisions
+            .iter()
+            .map(|r| {
+                let changed = |change: Change| {
+                    self.findings
+                        .iter()
+                        .filter(|f| {
+                            f.events
+                                .iter()
+                                .any(|e| e.rev == r.rev && e.change == change)
+                        })
+                        .map(|f| {
+                            format!(
+                                "{} {}:{}",
+                                f.pattern_id.as_deref().unwrap_or(&f.title),
+                                crate::paths::to_slash(&f.file_path),
+                                f.line_number
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                };
+                let (appeared, disappeared) =
+                    (changed(Change::Appeared), changed(Change::Disappeared));
+                let mut line = format!(
+                    "{} ({}, {}): {} finding(s)",
+                    r.rev,
+                    &r.commit[..r.commit.len().min(10)],
+                    r.date.get(..10).unwrap_or(&r.date),
+                    r.findings
+                );
+                if !appeared.is_empty() {
+                    line.push_str(&format!("\n  + {}", appeared.join("\n  + ")));
+                }
+                if !disappeared.is_empty() {
+                    line.push_str(&format!("\n  - {}", disappeared.join("\n  - ")));
+                }
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(rev: &str) -> Revision {
+        Revision {
+            rev: rev.into(),
+            commit: format!("{rev}000000"),
+            date: "2026-01-01T00:00:00Z".into(),
+            findings: 0,
+        }
+    }
+
+    fn finding(title: &str, line: usize) -> SecurityFinding {
+        SecurityFinding {
+            title: title.into(),
+            pattern_id: Some(title.into()),
+            severity: "High".into(),
+            file_path: "programs/vault/src/lib.rs".into(),
+            line_number: line,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn builds_appearances_and_fixes() {
+        let timeline = Timeline::build(vec![
+            (revision("v1"), vec![("a".into(), finding("SOL-003", 10))]),
+            (
+                revision("v2"),
+                vec![
+                    ("a".into(), finding("SOL-003", 14)),
+                    ("b".into(), finding("SOL-001", 3)),
+                ],
+            ),
+            (revision("v3"), vec![("b".into(), finding("SOL-001", 3))]),
+            (revision("v4"), vec![("a".into(), finding("SOL-003", 20))]),
+        ]);
+        let events = |i: usize| -> Vec<(&str, Change)> {
+            timeline.findings[i]
+                .events
+                .iter()
+                .map(|e| (e.rev.as_str(), e.change))
+                .collect()
+        };
+        assert_eq!(timeline.findings.len(), 2);
+        assert_eq!(
+            events(0),
+            [
+                ("v1", Change::Appeared),
+                ("v3", Change::Disappeared),
+                ("v4", Change::Appeared)
+            ]
+        );
+        assert_eq!(
+            events(1),
+            [("v2", Change::Appeared), ("v4", Change::Disappeared)]
+        );
+        assert!(timeline.findings[0].open && !timeline.findings[1].open);
+        assert_eq!(timeline.findings[0].line_number, 20);
+        let summary = timeline.summary();
+        assert!(summary.contains("v3 (v3000000, 2026-01-01): 0 finding(s)\n  - SOL-003"));
+    }
+
+    #[tokio::test]
+    async fn scans_a_tag_range() {
+        let repo = std::env::temp_dir().join(format!("solguard-history-{}", std::process::id()));
+        std::fs::create_dir_all(repo.join("src")).unwrap();
+        let run = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?}");
+        };
+        let commit = |code: &str, tag: &str| {
+            std::fs::write(repo.join("src/lib.rs"), code).unwrap();
+            run(&["add", "."]);
+            run(&["commit", "-q", "--allow-empty", "-m", tag]);
+            run(&["tag", tag]);
+        };
+        run(&["init", "-q"]);
+        std::fs::write(repo.join("Anchor.toml"), "[programs.localnet]\n").unwrap();
+        commit("pub fn deposit() {}\n", "v1.0");
+        commit(
+            "pub fn deposit(ctx: Context) {\n    for a in ctx.remaining_accounts.iter() {}\n}\n",
+            "v1.1",
+        );
+        commit("pub fn deposit() {}\n", "v1.2");
+        commit("pub fn deposit() {}\n", "v2.0");
+
+        let revs = resolve(&repo, "v1.0..v1.2").await.unwrap();
+        let listed = resolve(&repo, "v1.2, v2.0").await.unwrap();
+        let backwards = resolve(&repo, "v1.2..v1.0").await;
+        let scan_config = ScanConfig {
+            cache: false,
+            ..Default::default()
+        };
+        let timeline = scan(&repo, &revs, &scan_config).await;
+        std::fs::remove_dir_all(&repo).ok();
+
+        assert_eq!(revs, ["v1.0", "v1.1", "v1.2"]);
+        assert_eq!(listed, ["v1.2", "v2.0"]);
+        assert!(backwards.is_err());
+        let timeline = timeline.unwrap();
+        assert_eq!(timeline.revisions.len(), 3);
+        let remaining = timeline
+            .findings
+            .iter()
+            .find(|f| f.pattern_id.as_deref() == Some("SOL-004"))
+            .expect("remaining_accounts finding at v1.1");
+        assert_eq!(
+            remaining.events,
+            [
+                Event {
+                    rev: "v1.1".into(),
+                    change: Change::Appeared
+                },
+                Event {
+                    rev: "v1.2".into(),
+                    change: Change::Disappeared
+                }
+            ]
+        );
+        assert_eq!(remaining.file_path, Path::new("src/lib.rs"));
+    }
+}
@@ -0,0 +1,171 @@
+//! Inline waivers: a `// solguard-ignore: SOL-003 reason` comment drops the
+//! listed patterns' findings on the next line of code. Waived findings are
+//! kept as [`Waiver`]s rather than forgotten, so the report can show
+//! auditors what a team chose not to fix and why.
+//!
+//! Several IDs may be listed (`SOL-003, AST-001`); everything after the IDs
+//! is the reason. Directives stack: consecutive comment lines all apply to
+//! the first line after them that isn't a comment or attribute.
+
+use super::Finding;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+pub const DIRECTIVE: &str = "solguard-ignore";
+
+/// A finding a `solguard-ignore` comment waived.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Waiver {
+    pub pattern_id: String,
+    /// Repo-relative.
+    pub file_path: PathBuf,
+    pub line_number: usize,
+    /// Empty when the comment gives none.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub reason: String,
+}
+
+/// One directive: the waived IDs and the reason.
+#[derive(Debug, Clone, PartialEq)]
+struct Directive {
+    ids: Vec<String>,
+    reason: String,
+}
+
+/// The directive in `line`, if it is a `solguard-ignore` comment.
+fn parse(line: &str) -> Option<Directive> {
+    let comment = line.trim_start().strip_prefix("//")?;
+    let rest = comment
+        .trim_start_matches('/')
+        .trim_start()
+        .strip_prefix(DIRECTIVE)?
+        .strip_prefix(':')?;
+    let mut ids = Vec::new();
+    let mut words = rest.split_whitespace().peekable();
+    while let Some(word) = words.peek() {
+        let id = word.trim_end_matches(',');
+        if !is_pattern_id(id) {
+            break;
+        }
+        ids.push(id.to_string());
+        words.next();
+    }
+    (!ids.is_empty()).then(|| Directive {
+        ids,
+        reason: words.collect::<Vec<_>>().join(" "),
+    })
+}
+
+/// `SOL-003`, `AST-001`, a custom pattern's `ACME-12`: upper-case words
+/// joined by dashes, ending in a number.
+fn is_pattern_id(word: &str) -> bool {
+    let mut parts = word.split('-');
+    let last = parts.next_back().unwrap_or_default();
+    word.contains('-')
+        && !last.is_empty()
+        && last.bytes().all(|b| b.is_ascii_digit())
+        && parts.all(|p| {
+            !p.is_empty()
+                && p.bytes()
+                    .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+        })
+}
+
+/// Line (1-based) → directives covering it.
+fn directives(content: &str) -> HashMap<usize, Vec<Directive>> {
+    let mut covering: HashMap<usize, Vec<Directive>> = HashMap::new();
+    let mut pending: Vec<Directive> = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        if let Some(directive) = parse(line) {
+            pending.push(directive);
+            continue;
+        }
+        let trimmed = line.trim_start();
+        if pending.is_empty()
+            || trimmed.is_empty()
+            || trimmed.starts_with("//")
+            || trimmed.starts_with("#[")
+        {
+            continue;
+        }
+        covering.insert(idx + 1, std::mem::take(&mut pending));
+    }
+    covering
+}
+
+/// Drop the findings in `content` a directive waives, returning them as
+/// waivers with paths relative to `repo_path`.
+pub fn apply(
+    findings: &mut Vec<Finding>,
+    content: &str,
+    repo_path: &Path,
+) -> Vec<(Finding, Waiver)> {
+    if !content.contains(DIRECTIVE) {
+        return Vec::new();
+    }
+    let covering = directives(content);
+    let mut waived = Vec::new();
+    findings.retain(|f| {
+        let reason = covering.get(&f.line_number).and_then(|ds| {
+            ds.iter()
+                .find(|d| d.ids.contains(&f.pattern_id))
+                .map(|d| d.reason.clone())
+        });
+        let Some(reason) = reason else {
+            return true;
+        };
+        let waiver = Waiver {
+            pattern_id: f.pattern_id.clone(),
+            file_path: f
+                .file_path
+                .strip_prefix(repo_path)
+                .unwrap_or(&f.file_path)
+                .to_path_buf(),
+            line_number: f.line_number,
+            reason,
+        };
+        waived.push((f.clone(), waiver));
+        false
+    });
+    waived
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_directives() {
+        assert_eq!(
+            parse("    // solguard-ignore: SOL-003, AST-001 fee math bounded by MAX_FEE"),
+            Some(Directive {
+                ids: vec!["SOL-003".into(), "AST-001".into()],
+                reason: "fee math bounded by MAX_FEE".into(),
+            })
+        );
+        assert_eq!(parse("/// solguard-ignore: ACME-12").unwrap().reason, "");
+        assert!(parse("// solguard-ignore: overflow is fine").is_none());
+        assert!(parse("// solguard-ignore SOL-003").is_none());
+        assert!(parse("let x = 1; // solguard-ignore: SOL-003").is_none());
+    }
+
+    #[test]
+    fn directives_cover_the_next_line_of_code() {
+        let content = "\
+// solguard-ignore: SOL-003 capped above
+// solguard-ignore: SOL-010 closed by the crank
+
+#[inline]
+let total = amount + fee;
+let other = amount + fee;
+";
+        let covering = directives(content);
+        assert_eq!(covering.len(), 1);
+        let ids: Vec<&str> = covering[&5]
+            .iter()
+            .flat_map(|d| d.ids.iter().map(String::as_str))
+            .collect();
+        assert_eq!(ids, ["SOL-003", "SOL-010"]);
+    }
+}
@@ -0,0 +1,166 @@
+//! Finding localization: map a file/line to its enclosing function and, for
+//! Anchor programs, the instruction handler it belongs to.
+//!
+//! Handlers are the public functions declared inside a `#[program]` module.
+//! Helper functions called from a handler are reported by function name only.
+
+use proc_macro2::Span;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{ImplItemFn, ItemFn, ItemMod};
+
+/// A function's line range within a source file.
+#[derive(Debug, Clone)]
+struct FnSpan {
+    name: String,
+    start_line: usize,
+    end_line: usize,
+    is_instruction: bool,
+}
+
+/// Line-range index of every function in a single source file.
+#[derive(Debug, Default)]
+pub struct FunctionIndex {
+    spans: Vec<FnSpan>,
+}
+
+impl FunctionIndex {
+    /// Index every free function, method, and `#[program]` handler of a
+    /// parsed file.
+    pub fn from_ast(ast: &syn::File) -> Self {
+        let mut visitor = FnCollector {
+            spans: Vec::new(),
+            in_program_mod: false,
+        };
+        visitor.visit_file(ast);
+        Self {
+            spans: visitor.spans,
+        }
+    }
+
+    /// Innermost function containing `line`, with the instruction handler name
+    /// when the function is a `#[program]` handler.
+    pub fn locate(&self, line: usize) -> (Option<String>, Option<String>) {
+        if line == 0 {
+            return (None, None);
+        }
+        let innermost = self
+            .spans
+            .iter()
+            .filter(|s| s.start_line <= line && line <= s.end_line)
+            .min_by_key(|s| s.end_line - s.start_line);
+        match innermost {
+            Some(s) => (
+                Some(s.name.clone()),
+                s.is_instruction.then(|| s.name.clone()),
+            ),
+            None => (None, None),
+        }
+    }
+}
+
+struct FnCollector {
+    spans: Vec<FnSpan>,
+    in_program_mod: bool,
+}
+
+impl FnCollector {
+    fn push(&mut self, name: String, span: Span, is_instruction: bool) {
+        self.spans.push(FnSpan {
+            name,
+            start_line: span.start().line,
+            end_line: span.end().line,
+            is_instruction,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for FnCollector {
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        let is_program = node.attrs.iter().any(|attr| {
+            attr.path()
+                .segments
+                .iter()
+                .any(|seg| seg.ident == "program")
+        });
+        let outer = self.in_program_mod;
+        self.in_program_mod = outer || is_program;
+        syn::visit::visit_item_mod(self, node);
+        self.in_program_mod = outer;
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        let is_instruction = self.in_program_mod && matches!(node.vis, syn::Visibility::Public(_));
+        self.push(node.sig.ident.to_string(), node.span(), is_instruction);
+        // Nested fns are never handlers.
+        let outer = self.in_program_mod;
+        self.in_program_mod = false;
+        syn::visit::visit_item_fn(self, node);
+        self.in_program_mod = outer;
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.push(node.sig.ident.to_string(), node.span(), false);
+        syn::visit::visit_impl_item_fn(self, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Unparseable files produce an empty index, as in the scanner.
+    fn build(content: &str) -> FunctionIndex {
+        syn::parse_file(content)
+            .map(|ast| FunctionIndex::from_ast(&ast))
+            .unwrap_or_default()
+    }
+
+    const ANCHOR_PROGRAM: &str = r#"
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let vault = &ctx.accounts.vault;
+        transfer_out(vault, amount)
+    }
+}
+
+fn transfer_out(vault: &AccountInfo, amount: u64) -> Result<()> {
+    vault.sub_lamports(amount)?;
+    Ok(())
+}
+"#;
+
+    #[test]
+    fn handler_line_maps_to_instruction() {
+        let idx = build(ANCHOR_PROGRAM);
+        let (function, instruction) = idx.locate(9);
+        assert_eq!(function.as_deref(), Some("withdraw"));
+        assert_eq!(instruction.as_deref(), Some("withdraw"));
+    }
+
+    #[test]
+    fn helper_line_maps_to_function_only() {
+        let idx = build(ANCHOR_PROGRAM);
+        let (function, instruction) = idx.locate(15);
+        assert_eq!(function.as_deref(), Some("transfer_out"));
+        assert!(instruction.is_none());
+    }
+
+    #[test]
+    fn line_outside_any_fn_is_unlocated() {
+        let idx = build(ANCHOR_PROGRAM);
+        assert_eq!(idx.locate(2), (None, None));
+        assert_eq!(idx.locate(0), (None, None));
+    }
+
+    #[test]
+    fn unparseable_source_yields_empty_index() {
+        let idx = build("fn {{{");
+        assert_eq!(idx.locate(1), (None, None));
+    }
+}
@@ -1,13 +1,51 @@
 pub mod agent_review;
 pub mod agent_tools;
 mod ast_scan;
+pub mod baseline;
+pub mod benchmark;
+pub mod call_graph;
+pub mod cargo_metadata;
+pub mod checklists;
+pub mod coverage;
+pub mod crates_io;
+pub mod cross_repo;
+pub mod custom_patterns;
+pub mod dataflow;
+pub mod deps_audit;
+pub mod diff;
+mod expand;
+pub mod forks;
+pub mod framework;
+pub mod fuzz;
+pub mod history;
+pub mod ignore;
+mod locate;
+mod noir_scan;
+pub mod patch;
+pub mod posture;
+pub mod program_state;
+pub mod project_config;
 mod regex_scan;
+pub mod replay;
+pub mod revision;
+pub mod rule_corpus;
+pub mod sampling;
+pub mod scan_cache;
+mod seahorse;
+pub mod secrets;
+pub mod session;
+pub mod trust_graph;
 pub mod validator;
+pub mod workspace;
 
-use crate::config::AgentReviewConfig;
+use crate::config::{AgentReviewConfig, ScanConfig, StaticDedup};
 use crate::llm::LlmClient;
+use crate::narrative::rpc_pool::RpcPool;
+use crate::progress::Progress;
 use anyhow::Result;
+use framework::Framework;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use tracing::info;
 use walkdir::WalkDir;
@@ -23,7 +61,115 @@ pub enum ValidationStatus {
     Dismissed,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How a finding entered a `run` report, in a form downstream tools can
+/// audit without parsing the report's "Source: ..." line.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Position in the report's narratives (risk-sorted) of the narrative
+    /// the finding is linked to; none for unlinked findings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub narrative_index: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub narrative: Option<String>,
+    /// Why the repo was scanned.
+    pub selection: Selection,
+    pub scan_type: ScanType,
+    /// When the repo was scanned (RFC 3339).
+    pub scanned_at: String,
+    /// The validator's verdict; none when the finding wasn't validated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validation: Option<ValidationRecord>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Selection {
+    /// Named by a path on the command line.
+    #[default]
+    Direct,
+    /// Active repo of a detected narrative.
+    Narrative,
+    /// `[targets] always_scan` entry.
+    AlwaysScan,
+    /// Deferred as low urgency by an earlier run.
+    Deferred,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanType {
+    #[default]
+    Static,
+    DeepReview,
+    /// On-chain upgrade posture check.
+    Posture,
+}
+
+impl ScanType {
+    /// Label for the report's provenance line.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Static => "static scan",
+            Self::DeepReview => "deep agent review",
+            Self::Posture => "posture check",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidationRecord {
+    pub verdict: ValidationStatus,
+    /// Validator model; none for verdicts cached before models were recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// When the verdict was given (RFC 3339), even if reused from cache.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validated_at: Option<String>,
+}
+
+/// The investigator's severity and the one a blind validator assigned
+/// without seeing it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlindReview {
+    pub investigator: String,
+    pub validator: String,
+}
+
+impl BlindReview {
+    /// Levels the validator rated it above (positive) or below (negative)
+    /// the investigator.
+    pub fn delta(&self) -> i8 {
+        crate::severity::weight(&self.validator) as i8
+            - crate::severity::weight(&self.investigator) as i8
+    }
+}
+
+impl Provenance {
+    /// Provenance of a finding scanned now.
+    pub fn new(selection: Selection, scan_type: ScanType) -> Self {
+        Self {
+            selection,
+            scan_type,
+            scanned_at: chrono::Utc::now().to_rfc3339(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Start provenance for freshly scanned `findings`: static findings carry
+/// a pattern ID, agent findings don't.
+pub fn stamp_provenance(findings: &mut [SecurityFinding], selection: Selection) {
+    for f in findings {
+        let scan_type = if f.pattern_id.is_some() {
+            ScanType::Static
+        } else {
+            ScanType::DeepReview
+        };
+        f.provenance = Some(Provenance::new(selection, scan_type));
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SecurityFinding {
     pub title: String,
     pub severity: String,
@@ -35,6 +181,100 @@ pub struct SecurityFinding {
     pub validation_status: ValidationStatus,
     #[serde(default)]
     pub validation_reasoning: Option<String>,
+    /// The verdict was reused from an earlier run that validated the same
+    /// finding at the same commit.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub validation_cached: bool,
+    /// Enclosing function of the flagged line, when it could be resolved.
+    #[serde(default)]
+    pub function: Option<String>,
+    /// Anchor instruction handler (a `pub fn` inside `#[program]`) containing the line.
+    #[serde(default)]
+    pub instruction: Option<String>,
+    /// Free-form labels from tag rules or LLM suggestion.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Stable human-readable ID (e.g. `SG-2025-jupiter-0007`), assigned from run memory.
+    #[serde(default)]
+    pub id: Option<String>,
+    /// For a template-level finding, every fork repo that shares it.
+    #[serde(default)]
+    pub forks: Vec<String>,
+    /// Static pattern that produced the finding (`SOL-004`); none for agent findings.
+    #[serde(default)]
+    pub pattern_id: Option<String>,
+    /// Suggested fix as a unified diff, for patterns with a mechanical remedy.
+    #[serde(default)]
+    pub patch: Option<String>,
+    /// Call chain from an entry point to the flagged function
+    /// (`vault::deposit`, `deposit::handler`, ...); empty when unknown.
+    #[serde(default)]
+    pub entry_path: Vec<String>,
+    /// Commit that was scanned, for `--rev` scans.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Branch or tag `commit` was resolved from, when `--rev` named one.
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    pub git_ref: Option<String>,
+    /// Workspace program crate the file belongs to (`[package] name`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub program: Option<String>,
+    /// Set by `run`; see [`Provenance`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+    /// Both severities when the validator assessed it blind (`[validation]
+    /// blind`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blind_review: Option<BlindReview>,
+}
+
+impl SecurityFinding {
+    /// Line-independent identity used to recognize a finding across re-scans.
+    ///
+    /// `occurrence` disambiguates findings that share repo, file, title and
+    /// function (ordered by line), so shifting code doesn't reshuffle IDs.
+    pub fn fingerprint(&self, repo: &str, occurrence: usize) -> String {
+        self.fingerprint_at(repo, &self.file_path, occurrence)
+    }
+
+    /// [`Self::fingerprint`] with `path` standing in for the finding's own
+    /// file path, e.g. the repo-relative one.
+    pub fn fingerprint_at(&self, repo: &str, path: &Path, occurrence: usize) -> String {
+        let key = format!(
+            "{repo}\0{}\0{}\0{}\0{occurrence}",
+            crate::paths::to_slash(path),
+            self.title,
+            self.function.as_deref().unwrap_or("")
+        );
+        format!("{:016x}", fnv1a(&key))
+    }
+}
+
+/// FNV-1a of `key`: stable across Rust versions, unlike `DefaultHasher`, so
+/// safe to persist.
+pub(crate) fn fnv1a(key: &str) -> u64 {
+    key.bytes().fold(0xcbf29ce484222325u64, |h, b| {
+        (h ^ u64::from(b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// What a deep scan did with the static scan's findings once the agent
+/// investigation was over (`[agent_review.static_merge]`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StaticMerge {
+    /// Added to the agent's findings.
+    pub merged: u32,
+    /// Dropped for being below `min_severity`.
+    pub below_threshold: u32,
+    /// Dropped as already covered by an agent finding.
+    pub dominated: u32,
+}
+
+impl StaticMerge {
+    pub fn dropped(&self) -> u32 {
+        self.below_threshold + self.dominated
+    }
 }
 
 // -- Internal types (used by scanners) --
@@ -78,6 +318,7 @@ pub struct Finding {
 impl From<Finding> for SecurityFinding {
     fn from(f: Finding) -> Self {
         Self {
+            pattern_id: Some(f.pattern_id),
             title: f.title,
             severity: f.severity.to_string(),
             description: f.description,
@@ -86,61 +327,127 @@ impl From<Finding> for SecurityFinding {
             remediation: f.remediation,
             validation_status: ValidationStatus::Unvalidated,
             validation_reasoning: None,
+            validation_cached: false,
+            function: None,
+            instruction: None,
+            tags: Vec::new(),
+            id: None,
+            forks: Vec::new(),
+            patch: None,
+            entry_path: Vec::new(),
+            commit: None,
+            git_ref: None,
+            program: None,
+            provenance: None,
+            blind_review: None,
         }
     }
 }
 
-/// Numeric weight for severity sorting (higher = more severe).
-pub fn severity_weight(severity: &str) -> u8 {
-    match severity {
-        "Critical" => 4,
-        "High" => 3,
-        "Medium" => 2,
-        "Low" => 1,
-        _ => 0,
-    }
-}
-
-/// Directories and file patterns that contain test/client/build code, not on-chain programs.
-const EXCLUDED_DIRS: &[&str] = &[
-    "/target/",
-    "/tests/",
-    "/test/",
-    "/main_tests/",
-    "/fixtures/",
-    "/mock/",
-    "/mocks/",
-    "/client/",
-    "/clients/",
-    "/cli/",
-    "/sdk/",
-    "/scripts/",
-    "/migrations/",
-    "/examples/",
-    "/.docs/",
-    "/benches/",
-    "/cpitest/",
-    "/generated/",
+/// An agent finding has no line; it is placed in the first affected file.
+impl From<agent_review::AgentFinding> for SecurityFinding {
+    fn from(af: agent_review::AgentFinding) -> Self {
+        Self {
+            title: af.title,
+            severity: crate::severity::normalize(&af.severity).into(),
+            description: af.description,
+            file_path: af
+                .affected_files
+                .first()
+                .map(PathBuf::from)
+                .unwrap_or_default(),
+            line_number: 0,
+            remediation: af.remediation,
+            tags: af.tags.iter().map(|t| crate::tags::normalize(t)).collect(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<validator::ValidatedFinding> for SecurityFinding {
+    fn from(v: validator::ValidatedFinding) -> Self {
+        Self {
+            validation_status: match v.verdict {
+                validator::Verdict::Confirmed => ValidationStatus::Confirmed,
+                validator::Verdict::Disputed => ValidationStatus::Disputed,
+                validator::Verdict::Dismissed => ValidationStatus::Dismissed,
+            },
+            validation_reasoning: Some(v.reasoning).filter(|r| !r.is_empty()),
+            ..v.finding.into()
+        }
+    }
+}
+
+/// Directory names that contain test/client/build code, not on-chain programs.
+/// The default `[scan] exclude` globs (`**/<dir>/**`), matched against whole
+/// path components, so `attests/` is not excluded. `generated` is kept in
+/// Seahorse projects, see [`seahorse`].
+pub const DEFAULT_EXCLUDED_DIRS: &[&str] = &[
+    "target",
+    "tests",
+    "test",
+    "main_tests",
+    "fixtures",
+    "mock",
+    "mocks",
+    "client",
+    "clients",
+    "cli",
+    "sdk",
+    "scripts",
+    "migrations",
+    "examples",
+    ".docs",
+    "benches",
+    "cpitest",
+    "generated",
 ];
 
-/// File name suffixes that indicate test code.
-const EXCLUDED_SUFFIXES: &[&str] = &["_test.rs", "_tests.rs"];
+/// Pattern ID of the findings that report files a scan skipped.
+pub const SCAN_LIMIT_PATTERN: &str = "SCAN-001";
 
 /// Minimum confidence required for a static finding to enter the LLM triage pipeline.
 ///
 /// Patterns calibrated below this threshold (SOL-003, SOL-005) are too broad for
 /// actionable static detection — the agent catches real cases in context.
-const MIN_CONFIDENCE: f64 = 0.55;
+/// SOL-003 matches confirmed by [`dataflow`] are raised above it. The default
+/// for `[scan] min_confidence`.
+pub(crate) const MIN_CONFIDENCE: f64 = 0.55;
 
 /// Scan a repository for vulnerabilities.
 pub async fn scan_repo(repo_path: &Path) -> Result<Vec<SecurityFinding>> {
+    scan_repo_with(repo_path, &ScanConfig::default()).await
+}
+
+/// [`scan_repo`] with scanner options from `[scan]`.
+pub async fn scan_repo_with(repo_path: &Path, scan: &ScanConfig) -> Result<Vec<SecurityFinding>> {
+    scan_repo_waived(repo_path, scan).await.map(|s| s.findings)
+}
+
+/// A static scan's findings and what `solguard-ignore` comments waived.
+#[derive(Debug, Default)]
+pub struct StaticScan {
+    pub findings: Vec<SecurityFinding>,
+    pub waived: Vec<ignore::Waiver>,
+}
+
+/// [`scan_repo_with`], keeping the findings inline comments waived.
+pub async fn scan_repo_waived(repo_path: &Path, scan: &ScanConfig) -> Result<StaticScan> {
     info!(path = %repo_path.display(), "security scan: starting");
 
     if !repo_path.exists() {
         anyhow::bail!("Repository path does not exist: {}", repo_path.display());
     }
 
-    let solana_project = is_solana_project(repo_path);
+    let framework = framework::detect(repo_path);
+    let solana_project = framework.is_some();
+    if let Some(framework) = framework {
+        info!(framework = framework.label(), "pattern profile");
+    }
+    let python_sources = seahorse::is_project(repo_path).then(|| {
+        info!("Seahorse project: scanning generated Rust");
+        seahorse::python_sources(repo_path)
+    });
     if !solana_project {
         tracing::warn!(
             path = %repo_path.display(),
@@ -148,41 +455,165 @@ pub async fn scan_repo(repo_path: &Path) -> Result<Vec<SecurityFinding>> {
         );
     }
 
-    let rust_files = collect_rust_files(repo_path)?;
+    let collected = collect_source_files(repo_path, &["rs", "nr"], scan)?;
+    let (circuit_files, rust_files): (Vec<PathBuf>, Vec<PathBuf>) = collected
+        .files
+        .into_iter()
+        .partition(|p| p.extension().is_some_and(|ext| ext == "nr"));
     info!(count = rust_files.len(), "found Rust source files");
+    let programs = workspace::programs(repo_path);
+    if !programs.is_empty() {
+        info!(
+            programs = ?programs.iter().map(|p| p.name.as_str()).collect::<Vec<_>>(),
+            "workspace program crates"
+        );
+    }
+
+    let limit_findings: Vec<Finding> = collected
+        .skipped
+        .iter()
+        .map(|s| s.finding(repo_path, scan))
+        .collect();
 
-    if rust_files.is_empty() {
-        info!("no Rust files found, returning empty");
-        return Ok(Vec::new());
+    if rust_files.is_empty() && circuit_files.is_empty() {
+        info!("no Rust or Noir files found, returning empty");
+        return Ok(StaticScan {
+            findings: limit_findings
+                .into_iter()
+                .map(SecurityFinding::from)
+                .collect(),
+            waived: Vec::new(),
+        });
     }
 
     let mut all_findings: Vec<Finding> = Vec::new();
+    let mut waived: Vec<(Finding, ignore::Waiver)> = Vec::new();
+    let mut fn_indexes: HashMap<PathBuf, locate::FunctionIndex> = HashMap::new();
+    let mut sources: HashMap<PathBuf, String> = HashMap::new();
+    let mut calls = call_graph::Builder::default();
+    let cache = scan.cache.then(|| {
+        let root = scan
+            .cache_dir
+            .clone()
+            .unwrap_or_else(scan_cache::default_dir);
+        scan_cache::ScanCache::open(&root, scan)
+    });
+    let mut cache_hits = 0usize;
+
+    // Circuits behind on-chain proof verification.
+    if !circuit_files.is_empty() {
+        info!(count = circuit_files.len(), "found Noir circuit files");
+    }
+    for file_path in &circuit_files {
+        let content = std::fs::read_to_string(file_path)?;
+        all_findings.extend(noir_scan::scan(&content, file_path));
+        sources.insert(file_path.clone(), content);
+    }
 
     for file_path in &rust_files {
         let content = std::fs::read_to_string(file_path)?;
 
+        // A file's own imports decide its profile; helpers that import no
+        // framework get their program's, else the repo's.
+        let file_framework = Framework::of_source(&content)
+            .or_else(|| workspace::program_of(&programs, file_path).and_then(|p| p.framework))
+            .or(framework);
+        let cached = cache
+            .as_ref()
+            .and_then(|c| c.get(&content, file_framework, file_path));
+        cache_hits += usize::from(cached.is_some());
+
         // Regex-based pattern scan
-        tracing::debug!(file = %file_path.display(), "regex scan starting");
-        all_findings.extend(regex_scan::scan(&content, file_path));
-        tracing::debug!(file = %file_path.display(), "regex scan done");
+        let mut file_findings = match &cached {
+            Some(findings) => findings.clone(),
+            None => {
+                tracing::debug!(file = %file_path.display(), "regex scan starting");
+                let findings = regex_scan::scan_as(&content, file_path, file_framework, scan);
+                tracing::debug!(file = %file_path.display(), "regex scan done");
+                findings
+            }
+        };
 
-        // AST-based scan
+        // AST-based scan, and dataflow to confirm or demote regex matches.
+        // Cached files are still parsed for the call graph.
         tracing::debug!(file = %file_path.display(), "AST scan starting");
-        match ast_scan::scan(&content, file_path) {
-            Ok(ast_findings) => all_findings.extend(ast_findings),
+        match syn::parse_file(&content) {
+            Ok(ast) => {
+                fn_indexes.insert(file_path.clone(), locate::FunctionIndex::from_ast(&ast));
+                calls.add_file(&ast, file_path.strip_prefix(repo_path).unwrap_or(file_path));
+                if cached.is_none() {
+                    dataflow::refine(&mut file_findings, &dataflow::analyze_file(&ast));
+                    file_findings.extend(ast_scan::scan_file(&ast, &content, file_path));
+                }
+            }
             Err(e) => {
                 tracing::warn!(file = %file_path.display(), error = %e, "AST parse failed, skipping");
             }
         }
+        if let Some(cache) = &cache
+            && cached.is_none()
+        {
+            cache.put(&content, file_framework, &file_findings);
+        }
+        waived.extend(ignore::apply(&mut file_findings, &content, repo_path));
+        all_findings.extend(file_findings);
+        sources.insert(file_path.clone(), content);
+    }
+
+    if cache.is_some() {
+        info!(hits = cache_hits, files = rust_files.len(), "scan cache");
+    }
+
+    // Known-vulnerable pins of the core Solana crates, from the offline RustSec cache.
+    let advisory_db = scan
+        .advisory_db
+        .clone()
+        .unwrap_or_else(deps_audit::default_db_path);
+    all_findings.extend(deps_audit::audit(repo_path, &advisory_db));
+    all_findings.extend(secrets::committed_keypairs(repo_path));
+    all_findings.extend(posture::program_id_mismatches(repo_path));
+    all_findings.extend(coverage::untested_programs(repo_path));
+
+    // Macro-generated code; duplicates of plain-source findings drop out in dedup.
+    if scan.expand_macros {
+        all_findings.extend(expand::scan_repo(repo_path).await);
     }
 
     // Tag non-Solana repos as low-confidence
     if !solana_project {
-        for f in &mut all_findings {
+        for f in all_findings
+            .iter_mut()
+            .chain(waived.iter_mut().map(|(f, _)| f))
+        {
             f.confidence = 0.2;
         }
     }
 
+    // What the walk skipped is certain, whatever the repo.
+    all_findings.extend(limit_findings);
+
+    // Team tuning from `[scan]`: disabled patterns and severity overrides.
+    all_findings.retain(|f| !scan.disabled_patterns.contains(&f.pattern_id));
+    // Only what would otherwise have been reported counts as waived.
+    let waived: Vec<ignore::Waiver> = waived
+        .into_iter()
+        .filter(|(f, _)| {
+            !scan.disabled_patterns.contains(&f.pattern_id) && f.confidence >= scan.min_confidence
+        })
+        .map(|(_, w)| w)
+        .collect();
+    if !waived.is_empty() {
+        info!(
+            count = waived.len(),
+            "findings waived by solguard-ignore comments"
+        );
+    }
+    for f in &mut all_findings {
+        if let Some(severity) = scan.severity_overrides.get(&f.pattern_id) {
+            f.severity = severity.clone();
+        }
+    }
+
     // Deduplicate
     all_findings.sort_by(|a, b| {
         b.severity
@@ -194,38 +625,185 @@ pub async fn scan_repo(repo_path: &Path) -> Result<Vec<SecurityFinding>> {
         a.file_path == b.file_path && a.line_number == b.line_number && a.pattern_id == b.pattern_id
     });
 
+    let calls = calls.finish();
     let findings: Vec<SecurityFinding> = all_findings
         .into_iter()
-        .filter(|f| f.confidence >= MIN_CONFIDENCE)
+        .filter(|f| f.confidence >= scan.min_confidence)
         .map(|f| {
+            let located = fn_indexes
+                .get(&f.file_path)
+                .map(|idx| idx.locate(f.line_number));
+            let patch = suggest_patch(&f, &sources, repo_path);
+            let program = workspace::program_of(&programs, &f.file_path).map(|p| p.name.clone());
             let mut sf = SecurityFinding::from(f);
+            sf.patch = patch;
+            sf.program = program;
+            if let Some((function, instruction)) = located {
+                sf.function = function;
+                sf.instruction = instruction;
+            }
             if !solana_project {
                 sf.title = format!("[Low Confidence] {}", sf.title);
             }
+            if let Some(python) = &python_sources
+                && seahorse::is_generated(
+                    sf.file_path
+                        .strip_prefix(repo_path)
+                        .unwrap_or(&sf.file_path),
+                )
+            {
+                seahorse::annotate(&mut sf, python);
+            }
+            annotate_reachability(&mut sf, &calls, repo_path);
             sf
         })
         .collect();
 
     info!(count = findings.len(), "security scan complete");
-    Ok(findings)
+    Ok(StaticScan { findings, waived })
+}
+
+/// Record how the flagged function is reached from an entry point, or tag
+/// it [`call_graph::UNREACHABLE_TAG`] when nothing reaches it. Findings
+/// outside any function, and repos without entry points, are left alone.
+fn annotate_reachability(
+    sf: &mut SecurityFinding,
+    calls: &call_graph::CallGraph,
+    repo_path: &Path,
+) {
+    if !calls.has_entries() {
+        return;
+    }
+    let rel = sf
+        .file_path
+        .strip_prefix(repo_path)
+        .unwrap_or(&sf.file_path);
+    let Some(idx) = calls.enclosing(rel, sf.line_number) else {
+        return;
+    };
+    match calls.entry_path_names(idx) {
+        Some(path) => sf.entry_path = path,
+        None => crate::tags::add_tag(&mut sf.tags, call_graph::UNREACHABLE_TAG),
+    }
+}
+
+/// Suggested fix for a patchable static finding, with the diff header
+/// relative to the repo root.
+fn suggest_patch(
+    f: &Finding,
+    sources: &HashMap<PathBuf, String>,
+    repo_path: &Path,
+) -> Option<String> {
+    if !patch::PATCHABLE.contains(&f.pattern_id.as_str()) {
+        return None;
+    }
+    let content = sources.get(&f.file_path)?;
+    let rel = f.file_path.strip_prefix(repo_path).unwrap_or(&f.file_path);
+    patch::suggest(
+        &f.pattern_id,
+        content,
+        f.line_number,
+        &crate::paths::to_slash(rel),
+    )
 }
 
 /// Run the multi-turn agent investigation on a repository.
 ///
 /// Optionally runs the static scanner first to provide triage context.
+/// `rpc` lets the agent look up deployed program state.
 pub async fn scan_repo_deep(
     repo_path: &Path,
     llm: &LlmClient,
     config: &AgentReviewConfig,
+    scan: &ScanConfig,
+    scan_context: Option<&agent_review::ScanContext>,
+    rpc: &RpcPool,
+    progress: &Progress,
+) -> Result<Vec<SecurityFinding>> {
+    scan_repo_deep_with_stats(repo_path, llm, config, scan, scan_context, rpc, progress)
+        .await
+        .map(|(findings, _)| findings)
+}
+
+/// Like [`scan_repo_deep`], also returning what the investigation spent
+/// (zero when it was skipped or failed).
+pub async fn scan_repo_deep_with_stats(
+    repo_path: &Path,
+    llm: &LlmClient,
+    config: &AgentReviewConfig,
+    scan: &ScanConfig,
     scan_context: Option<&agent_review::ScanContext>,
+    rpc: &RpcPool,
+    progress: &Progress,
+) -> Result<(Vec<SecurityFinding>, agent_review::ReviewStats)> {
+    deep_scan(
+        repo_path,
+        llm,
+        config,
+        scan,
+        rpc,
+        progress,
+        Investigation::Start(scan_context),
+    )
+    .await
+}
+
+/// [`scan_repo_deep`] continuing a checkpointed investigation (see
+/// [`session`]) instead of starting one. The static scan runs again to merge
+/// its high-severity findings; its leads are already in the conversation.
+pub async fn resume_deep(
+    session: session::Session,
+    llm: &LlmClient,
+    config: &AgentReviewConfig,
+    scan: &ScanConfig,
+    rpc: &RpcPool,
+    progress: &Progress,
 ) -> Result<Vec<SecurityFinding>> {
+    let repo_path = session.repo_path.clone();
+    deep_scan(
+        &repo_path,
+        llm,
+        config,
+        scan,
+        rpc,
+        progress,
+        Investigation::Resume(Box::new(session)),
+    )
+    .await
+    .map(|(findings, _)| findings)
+}
+
+/// Where [`deep_scan`]'s agent findings come from.
+enum Investigation<'a> {
+    /// A new investigation, given the static scan's leads.
+    Start(Option<&'a agent_review::ScanContext>),
+    Resume(Box<session::Session>),
+}
+
+/// Static scan, then the agent investigation, merged.
+async fn deep_scan(
+    repo_path: &Path,
+    llm: &LlmClient,
+    config: &AgentReviewConfig,
+    scan: &ScanConfig,
+    rpc: &RpcPool,
+    progress: &Progress,
+    investigation: Investigation<'_>,
+) -> Result<(Vec<SecurityFinding>, agent_review::ReviewStats)> {
     // Run static scan first for triage context
-    let static_findings = scan_repo(repo_path).await.unwrap_or_default();
+    let StaticScan {
+        findings: static_findings,
+        waived,
+    } = scan_repo_waived(repo_path, scan).await.unwrap_or_default();
 
     // Skip agent review for non-Solana repos — static findings only
     if !is_solana_project(repo_path) {
         info!(path = %repo_path.display(), "non-Solana repo, skipping agent review");
-        return Ok(static_findings);
+        let stats = agent_review::ReviewStats {
+            waived,
+            ..Default::default()
+        };
+        return Ok((static_findings, stats));
     }
 
     let triage = if static_findings.is_empty() {
@@ -235,8 +813,26 @@ pub async fn scan_repo_deep(
     };
 
     let mut findings: Vec<SecurityFinding> = Vec::new();
+    let mut spent = agent_review::ReviewStats::default();
 
-    match agent_review::investigate(llm, repo_path, config, triage.as_deref(), scan_context).await {
+    let result = match investigation {
+        Investigation::Start(scan_context) => {
+            agent_review::investigate(
+                llm,
+                repo_path,
+                config,
+                triage.as_deref(),
+                scan_context,
+                rpc,
+                progress,
+            )
+            .await
+        }
+        Investigation::Resume(session) => {
+            agent_review::resume(llm, *session, config, rpc, progress).await
+        }
+    };
+    match result {
         Ok((agent_findings, stats)) => {
             info!(
                 agent_findings = agent_findings.len(),
@@ -246,39 +842,37 @@ pub async fn scan_repo_deep(
                 "deep scan complete"
             );
 
-            // Convert agent findings to SecurityFinding
+            let programs = workspace::programs(repo_path);
             findings.extend(agent_findings.into_iter().map(|af| {
-                SecurityFinding {
-                    title: af.title,
-                    severity: af.severity,
-                    description: af.description,
-                    file_path: af
-                        .affected_files
-                        .first()
-                        .map(PathBuf::from)
-                        .unwrap_or_default(),
-                    line_number: 0,
-                    remediation: af.remediation,
-                    validation_status: ValidationStatus::Unvalidated,
-                    validation_reasoning: None,
-                }
+                let mut sf = SecurityFinding::from(af);
+                sf.program = workspace::program_of(&programs, &repo_path.join(&sf.file_path))
+                    .map(|p| p.name.clone());
+                sf
             }));
 
-            // Include high-confidence static findings not covered by agent
+            // Include severe static findings not covered by agent
+            let merge = &config.static_merge;
+            let floor = merge.min_severity.to_string();
+            let mut counts = StaticMerge::default();
             for sf in static_findings {
-                if sf.severity == "Critical" || sf.severity == "High" {
-                    let dominated = findings.iter().any(|af| {
-                        af.title.to_lowercase().contains(&sf.title.to_lowercase())
-                            || sf
-                                .file_path
-                                .to_string_lossy()
-                                .contains(&af.file_path.to_string_lossy().to_string())
-                    });
-                    if !dominated {
-                        findings.push(sf);
-                    }
+                if !crate::severity::at_least(&sf.severity, &floor) {
+                    counts.below_threshold += 1;
+                } else if findings.iter().any(|af| covers(merge.dedup, af, &sf)) {
+                    counts.dominated += 1;
+                } else {
+                    counts.merged += 1;
+                    findings.push(sf);
                 }
             }
+            info!(
+                merged = counts.merged,
+                below_threshold = counts.below_threshold,
+                dominated = counts.dominated,
+                min_severity = %floor,
+                "static findings merged"
+            );
+            spent = stats;
+            spent.static_merge = counts;
         }
         Err(e) => {
             tracing::warn!(error = %e, "deep agent review failed, falling back to static findings");
@@ -286,86 +880,212 @@ pub async fn scan_repo_deep(
             findings.extend(static_findings);
         }
     }
+    spent.waived = waived;
 
-    Ok(findings)
+    Ok((findings, spent))
+}
+
+/// Whether agent finding `af` already reports static finding `sf`.
+fn covers(dedup: StaticDedup, af: &SecurityFinding, sf: &SecurityFinding) -> bool {
+    let title = || af.title.to_lowercase().contains(&sf.title.to_lowercase());
+    // An agent finding without a file says nothing about which file it is in.
+    let file = || {
+        !af.file_path.as_os_str().is_empty()
+            && crate::paths::to_slash(&sf.file_path)
+                .contains(&crate::paths::to_slash(&af.file_path))
+    };
+    match dedup {
+        StaticDedup::TitleOrFile => title() || file(),
+        StaticDedup::Title => title(),
+        StaticDedup::File => file(),
+        StaticDedup::Off => false,
+    }
 }
 
 /// Check if a Cargo.toml contains Solana-related dependencies.
 fn is_solana_cargo(content: &str) -> bool {
-    content.contains("solana-program")
-        || content.contains("anchor-lang")
-        || content.contains("pinocchio")
-        || content.contains("solana-sdk")
+    Framework::of_manifest(content).is_some()
 }
 
 /// Check whether a repository looks like a Solana program project.
 ///
-/// Returns true if Anchor.toml exists at root, or any Cargo.toml in the tree
-/// declares `solana-program`, `anchor-lang`, or `pinocchio` as a dependency.
+/// Returns true if Anchor.toml exists at root, the repo is a Seahorse
+/// project, or a Cargo.toml at the root or one level down (two under
+/// `programs/`) declares `solana-program`, `anchor-lang`, or `pinocchio`
+/// as a dependency. See [`framework::detect`].
 pub fn is_solana_project(root: &Path) -> bool {
-    if root.join("Anchor.toml").exists() {
-        return true;
-    }
-    // Check root Cargo.toml
-    if let Ok(content) = std::fs::read_to_string(root.join("Cargo.toml"))
-        && is_solana_cargo(&content)
-    {
-        return true;
-    }
-    // Check common program directories one level deep
-    for dir_name in ["programs", "program", "src"] {
-        let dir = root.join(dir_name);
-        if dir.is_dir()
-            && let Ok(entries) = std::fs::read_dir(&dir)
-        {
-            for entry in entries.flatten() {
-                let cargo = entry.path().join("Cargo.toml");
-                if let Ok(content) = std::fs::read_to_string(&cargo)
-                    && is_solana_cargo(&content)
-                {
-                    return true;
-                }
-            }
-        }
-    }
-    // Check ALL immediate subdirectories for Cargo.toml with Solana markers.
-    // Catches repos like shielded-pool where the program lives in a
-    // non-standard subdirectory (e.g. shielded_pool_program/).
-    if let Ok(entries) = std::fs::read_dir(root) {
-        for entry in entries.flatten() {
-            if entry.path().is_dir() {
-                let cargo = entry.path().join("Cargo.toml");
-                if let Ok(content) = std::fs::read_to_string(&cargo)
-                    && is_solana_cargo(&content)
-                {
-                    return true;
-                }
-            }
+    framework::detect(root).is_some()
+}
+
+fn collect_rust_files(root: &Path) -> Result<Vec<PathBuf>> {
+    Ok(collect_source_files(root, &["rs"], &ScanConfig::default())?.files)
+}
+
+/// A path the walk left out, reported as a [`SCAN_LIMIT_PATTERN`] finding.
+#[derive(Debug, Clone, PartialEq)]
+enum Skipped {
+    TooLarge {
+        path: PathBuf,
+        bytes: u64,
+    },
+    /// Collection stopped after this many files.
+    TooMany {
+        collected: usize,
+    },
+    /// A symlinked directory that leads back to one of its ancestors.
+    Loop {
+        path: PathBuf,
+    },
+    /// A symlink that resolves outside the repo.
+    OutsideRepo {
+        path: PathBuf,
+    },
+}
+
+impl Skipped {
+    fn finding(&self, root: &Path, scan: &ScanConfig) -> Finding {
+        let (path, title, description) = match self {
+            Self::TooLarge { path, bytes } => (
+                path.clone(),
+                "Scan Incomplete: File Too Large",
+                format!(
+                    "This file is {bytes} bytes, over the {} byte limit, and was not scanned.",
+                    scan.max_file_bytes
+                ),
+            ),
+            Self::TooMany { collected } => (
+                root.to_path_buf(),
+                "Scan Incomplete: Too Many Files",
+                format!(
+                    "File collection stopped after {collected} source files; the rest of the \
+                     repo was not scanned."
+                ),
+            ),
+            Self::Loop { path } => (
+                path.clone(),
+                "Scan Incomplete: Symlink Loop",
+                "This symlink leads back to one of its parent directories and was not \
+                 followed."
+                    .to_string(),
+            ),
+            Self::OutsideRepo { path } => (
+                path.clone(),
+                "Scan Incomplete: Symlink Outside Repo",
+                "This symlink resolves outside the repository and was not followed.".to_string(),
+            ),
+        };
+        Finding {
+            pattern_id: SCAN_LIMIT_PATTERN.into(),
+            title: title.into(),
+            description,
+            severity: Severity::Info,
+            file_path: path,
+            line_number: 0,
+            code_snippet: String::new(),
+            remediation: "Review the skipped code by hand, or raise `[scan] max_file_bytes` / \
+                          `max_files` if it belongs in the scan."
+                .into(),
+            confidence: 1.0,
+            references: Vec::new(),
         }
     }
-    false
 }
 
-fn collect_rust_files(root: &Path) -> Result<Vec<PathBuf>> {
+/// The result of a file walk: what to scan and what was left out.
+struct SourceFiles {
+    files: Vec<PathBuf>,
+    skipped: Vec<Skipped>,
+}
+
+/// Source files with any of `extensions` under `root` that pass the `[scan]`
+/// include/exclude globs (by default, everything outside test, build and
+/// vendored directories) and lie under `scan --path`. A Seahorse project's
+/// `generated` Rust is kept. Symlinks are followed within the repo only;
+/// loops, files over `max_file_bytes` and files past `max_files` are skipped
+/// and listed, bounding what a hostile or pathological repo can make us read.
+fn collect_source_files(
+    root: &Path,
+    extensions: &[&str],
+    scan: &ScanConfig,
+) -> Result<SourceFiles> {
+    let seahorse = seahorse::is_project(root);
+    let canonical_root = root.canonicalize()?;
     let mut files = Vec::new();
-    for entry in WalkDir::new(root)
+    let mut skipped = Vec::new();
+    let mut outside = Vec::new();
+    let walk = WalkDir::new(root)
         .follow_links(true)
         .into_iter()
-        .filter_map(|e| e.ok())
-    {
+        .filter_entry(|e| {
+            let escapes = e.path_is_symlink()
+                && e.path()
+                    .canonicalize()
+                    .is_ok_and(|target| !target.starts_with(&canonical_root));
+            if escapes {
+                outside.push(Skipped::OutsideRepo {
+                    path: e.path().to_path_buf(),
+                });
+            }
+            !escapes
+        });
+    for entry in walk {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                if e.loop_ancestor().is_some()
+                    && let Some(path) = e.path()
+                {
+                    skipped.push(Skipped::Loop {
+                        path: path.to_path_buf(),
+                    });
+                }
+                continue;
+            }
+        };
         let path = entry.path();
         // Use path relative to repo root for exclusion checks, so that
         // the repo's location on disk doesn't affect filtering.
         let rel = path.strip_prefix(root).unwrap_or(path);
-        let rel_str = format!("/{}", rel.to_string_lossy());
-        if path.extension().is_some_and(|ext| ext == "rs")
-            && !EXCLUDED_DIRS.iter().any(|d| rel_str.contains(d))
-            && !EXCLUDED_SUFFIXES.iter().any(|s| rel_str.ends_with(s))
+        // Generated code is what a Seahorse project deploys.
+        let excluded = scan
+            .exclude
+            .iter()
+            .any(|g| crate::paths::matches_glob(g, rel))
+            && !(seahorse && seahorse::is_generated(rel));
+        let included = scan.include.is_empty()
+            || scan
+                .include
+                .iter()
+                .any(|g| crate::paths::matches_glob(g, rel));
+        if path
+            .extension()
+            .is_some_and(|ext| extensions.iter().any(|e| ext == *e))
+            && !excluded
+            && included
+            && scan.subdir.as_ref().is_none_or(|dir| rel.starts_with(dir))
         {
+            let bytes = entry.metadata().map_or(0, |m| m.len());
+            if bytes > scan.max_file_bytes {
+                skipped.push(Skipped::TooLarge {
+                    path: path.to_path_buf(),
+                    bytes,
+                });
+                continue;
+            }
+            if files.len() == scan.max_files {
+                skipped.push(Skipped::TooMany {
+                    collected: files.len(),
+                });
+                break;
+            }
             files.push(path.to_path_buf());
         }
     }
-    Ok(files)
+    skipped.extend(outside);
+    for s in &skipped {
+        tracing::warn!(root = %root.display(), skipped = ?s, "source file skipped");
+    }
+    Ok(SourceFiles { files, skipped })
 }
 
 #[cfg(test)]
@@ -395,6 +1115,34 @@ mod tests {
         assert_eq!(sf.remediation, "fix");
         assert_eq!(sf.validation_status, ValidationStatus::Unvalidated);
         assert!(sf.validation_reasoning.is_none());
+        assert!(sf.function.is_none());
+        assert!(sf.instruction.is_none());
+    }
+
+    #[test]
+    fn static_dedup_modes() {
+        let finding = |title: &str, path: &str| SecurityFinding {
+            title: title.into(),
+            file_path: PathBuf::from(path),
+            ..Default::default()
+        };
+        let sf = finding(
+            "Missing Signer",
+            "/repos/vault/programs/vault/src/withdraw.rs",
+        );
+        let by_title = finding("Missing signer on withdraw authority", "src/lib.rs");
+        let by_file = finding("Unchecked fee", "programs/vault/src/withdraw.rs");
+        let unrelated = finding("Stale oracle", "");
+
+        assert!(covers(StaticDedup::TitleOrFile, &by_title, &sf));
+        assert!(covers(StaticDedup::TitleOrFile, &by_file, &sf));
+        assert!(covers(StaticDedup::Title, &by_title, &sf));
+        assert!(!covers(StaticDedup::Title, &by_file, &sf));
+        assert!(covers(StaticDedup::File, &by_file, &sf));
+        assert!(!covers(StaticDedup::File, &by_title, &sf));
+        assert!(!covers(StaticDedup::Off, &by_title, &sf));
+        // No file on the agent finding isn't every file.
+        assert!(!covers(StaticDedup::TitleOrFile, &unrelated, &sf));
     }
 
     #[test]
@@ -421,4 +1169,127 @@ mod tests {
     fn severity_display_info() {
         assert_eq!(Severity::Info.to_string(), "Info");
     }
+
+    #[test]
+    fn excluded_dirs_match_whole_components() {
+        let root = std::env::temp_dir().join(format!("solguard-excl-{}", std::process::id()));
+        for dir in ["programs/vault/src", "programs/vault/tests", "attests/src"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "programs/vault/src/lib.rs",
+            "programs/vault/src/vault_test.rs",
+            "programs/vault/tests/it.rs",
+            "attests/src/lib.rs",
+        ] {
+            std::fs::write(root.join(file), "fn f() {}").unwrap();
+        }
+        let mut files: Vec<String> = collect_rust_files(&root)
+            .unwrap()
+            .iter()
+            .map(|f| crate::paths::to_slash(f.strip_prefix(&root).unwrap()))
+            .collect();
+        std::fs::remove_dir_all(&root).ok();
+        files.sort();
+        assert_eq!(
+            files,
+            vec!["attests/src/lib.rs", "programs/vault/src/lib.rs"]
+        );
+    }
+
+    #[test]
+    fn include_exclude_globs_and_subdir() {
+        let root = std::env::temp_dir().join(format!("solguard-globs-{}", std::process::id()));
+        for dir in ["programs/vault/src", "programs/amm/src", "sdk/src"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "programs/vault/src/lib.rs",
+            "programs/vault/src/gen.rs",
+            "programs/amm/src/lib.rs",
+            "sdk/src/lib.rs",
+        ] {
+            std::fs::write(root.join(file), "fn f() {}").unwrap();
+        }
+        let scanned = |scan: ScanConfig| {
+            let mut files: Vec<String> = collect_source_files(&root, &["rs"], &scan)
+                .unwrap()
+                .files
+                .iter()
+                .map(|f| crate::paths::to_slash(f.strip_prefix(&root).unwrap()))
+                .collect();
+            files.sort();
+            files
+        };
+        let globbed = scanned(ScanConfig {
+            include: vec!["programs/**".into()],
+            exclude: vec!["**/gen.rs".into()],
+            ..ScanConfig::default()
+        });
+        let everything = scanned(ScanConfig {
+            exclude: Vec::new(),
+            ..ScanConfig::default()
+        });
+        let subdir = scanned(ScanConfig {
+            subdir: Some(PathBuf::from("programs/vault")),
+            ..ScanConfig::default()
+        });
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(
+            globbed,
+            ["programs/amm/src/lib.rs", "programs/vault/src/lib.rs"]
+        );
+        assert_eq!(everything.len(), 4);
+        assert_eq!(
+            subdir,
+            ["programs/vault/src/gen.rs", "programs/vault/src/lib.rs"]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_skips_loops_escapes_and_oversized_files() {
+        use std::os::unix::fs::symlink;
+        let base = std::env::temp_dir().join(format!("solguard-limits-{}", std::process::id()));
+        let root = base.join("repo");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(base.join("home")).unwrap();
+        std::fs::write(base.join("home/secret.rs"), "fn key() {}").unwrap();
+        for (file, bytes) in [("a.rs", 10), ("b.rs", 10), ("c.rs", 10), ("big.rs", 100)] {
+            std::fs::write(root.join("src").join(file), "/".repeat(bytes)).unwrap();
+        }
+        symlink(&root, root.join("src/again")).unwrap();
+        symlink(base.join("home"), root.join("home")).unwrap();
+
+        let limits = |max_files| ScanConfig {
+            max_file_bytes: 50,
+            max_files,
+            ..ScanConfig::default()
+        };
+        let all = collect_source_files(&root, &["rs"], &limits(10)).unwrap();
+        let capped = collect_source_files(&root, &["rs"], &limits(2)).unwrap();
+        std::fs::remove_dir_all(&base).ok();
+
+        let mut names: Vec<_> = all.files.iter().map(|f| f.file_name().unwrap()).collect();
+        names.sort();
+        assert_eq!(names, ["a.rs", "b.rs", "c.rs"]);
+        assert!(all.skipped.contains(&Skipped::TooLarge {
+            path: root.join("src/big.rs"),
+            bytes: 100,
+        }));
+        assert!(all.skipped.contains(&Skipped::Loop {
+            path: root.join("src/again"),
+        }));
+        assert!(all.skipped.contains(&Skipped::OutsideRepo {
+            path: root.join("home"),
+        }));
+        assert_eq!(capped.files.len(), 2);
+        assert!(capped.skipped.contains(&Skipped::TooMany { collected: 2 }));
+
+        let finding = Skipped::TooMany { collected: 2 }.finding(&root, &ScanConfig::default());
+        assert_eq!(finding.pattern_id, SCAN_LIMIT_PATTERN);
+        assert_eq!(finding.severity, Severity::Info);
+        assert_eq!(finding.file_path, root);
+    }
 }
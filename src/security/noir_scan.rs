@@ -0,0 +1,376 @@
+//! Noir circuit checks for `.nr` files. Programs that verify ZK proofs
+//! on-chain are only as sound as the circuit behind them, and the Rust
+//! scanners never see it.
+//!
+//! The checks read the circuit's `main` signature and body as text:
+//!
+//! - `NOIR-001`: a `pub` input that no constraint ties to anything. It is
+//!   either never used or only compared with a literal, as in
+//!   `assert(recipient != 0)`. A proof stays valid whatever value the
+//!   relayer substitutes.
+//! - `NOIR-002`: a spend circuit (it takes a nullifier or a secret) with no
+//!   public nullifier bound by an equality. Nothing on-chain can stop the
+//!   same note from being spent twice.
+//! - `NOIR-003`: an amount-like private `Field` input used without a range
+//!   check. Field arithmetic wraps modulo the prime, so a "negative" amount
+//!   is a huge one. Public inputs come from the program, which already holds
+//!   them as integers.
+
+use super::{Finding, Severity};
+use fancy_regex::Regex;
+use std::path::Path;
+use std::sync::LazyLock;
+
+pub const PATTERN_IDS: &[&str] = &["NOIR-001", "NOIR-002", "NOIR-003"];
+
+static MAIN_FN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\bfn\s+main\s*(?:<[^>]*>)?\s*\(").unwrap());
+
+/// Names that hold a value the circuit must keep in range.
+static AMOUNT_NAME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)amount|balance|value|fee|price|quantity|qty").unwrap());
+
+/// A literal on either side of a comparison.
+const LITERAL: &str = r"(?:-?\d+|0x[0-9a-fA-F]+|true|false)";
+
+/// One parameter of `main`.
+struct Input {
+    name: String,
+    ty: String,
+    public: bool,
+    line: usize,
+}
+
+/// The parts of a circuit's `main` the checks look at.
+struct Circuit {
+    line: usize,
+    inputs: Vec<Input>,
+    /// Body statements, split at `;`, `{` and `}`, comments removed.
+    statements: Vec<String>,
+}
+
+/// Check one `.nr` file. Files without a `main` (libraries) yield nothing.
+pub fn scan(content: &str, file_path: &Path) -> Vec<Finding> {
+    let Some(circuit) = parse_main(&strip_comments(content)) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let finding = |id: &str,
+                   title: &str,
+                   description: String,
+                   severity,
+                   line,
+                   remediation: &str,
+                   confidence| Finding {
+        pattern_id: id.into(),
+        title: title.into(),
+        description,
+        severity,
+        file_path: file_path.to_path_buf(),
+        line_number: line,
+        code_snippet: snippet(&lines, line),
+        remediation: remediation.into(),
+        confidence,
+        references: vec!["https://noir-lang.org/docs/noir/concepts/data_types/fields".into()],
+    };
+    let mut findings = Vec::new();
+
+    for input in circuit.inputs.iter().filter(|i| i.public) {
+        let uses: Vec<&String> = circuit
+            .statements
+            .iter()
+            .filter(|s| mentions(s, &input.name))
+            .collect();
+        if uses.iter().any(|s| !is_literal_check(s, &input.name)) {
+            continue;
+        }
+        let how = match uses.first() {
+            Some(check) => format!("is only compared with a literal (`{}`)", check.trim()),
+            None => "is never used".into(),
+        };
+        findings.push(finding(
+            "NOIR-001",
+            "Unconstrained Public Input",
+            format!(
+                "Public input '{}' {how}, so the proof doesn't bind it. Whoever submits the \
+                 proof can replace it (e.g. redirect a withdrawal) without invalidating it.",
+                input.name
+            ),
+            Severity::Critical,
+            input.line,
+            "Bind the input into a constrained value, e.g. hash it into the commitment or \
+             nullifier the proof opens, so changing it breaks the proof.",
+            0.7,
+        ));
+    }
+
+    let spends = circuit.inputs.iter().any(|i| {
+        let name = i.name.to_lowercase();
+        name.contains("nullifier") || name.contains("secret")
+    });
+    let bound_nullifier = circuit
+        .inputs
+        .iter()
+        .filter(|i| i.public && i.name.to_lowercase().contains("nullifier"))
+        .any(|i| {
+            circuit.statements.iter().any(|s| {
+                mentions(s, &i.name)
+                    && (s.contains("==") || s.contains("assert_eq"))
+                    && !is_literal_check(s, &i.name)
+            })
+        });
+    if spends && !bound_nullifier {
+        findings.push(finding(
+            "NOIR-002",
+            "Missing Nullifier Constraint",
+            "The circuit spends a note (it takes a nullifier or secret) but no public \
+             nullifier input is constrained with `==` or `assert_eq`. The verifier has no \
+             value to record, so the same note can be spent twice."
+                .into(),
+            Severity::High,
+            circuit.line,
+            "Take the nullifier hash as a `pub` input, assert it equals the hash of the \
+             note's nullifier key, and record it on-chain when the proof is accepted.",
+            0.6,
+        ));
+    }
+
+    for input in &circuit.inputs {
+        if input.public
+            || input.ty != "Field"
+            || !AMOUNT_NAME.is_match(&input.name).unwrap_or(false)
+        {
+            continue;
+        }
+        let used = circuit.statements.iter().any(|s| mentions(s, &input.name));
+        let checked = Regex::new(&format!(
+            r"\b{}\s*\.\s*(?:assert_max_bit_size|to_(?:le|be)_(?:bits|bytes|radix))",
+            input.name
+        ))
+        .is_ok_and(|re| {
+            circuit
+                .statements
+                .iter()
+                .any(|s| re.is_match(s).unwrap_or(false))
+        });
+        if used && !checked {
+            findings.push(finding(
+                "NOIR-003",
+                "Missing Range Check",
+                format!(
+                    "Field input '{}' is used without a range check. Field arithmetic wraps \
+                     modulo the prime, so an out-of-range value behaves like a negative one.",
+                    input.name
+                ),
+                Severity::Medium,
+                input.line,
+                "Declare the input as an integer type (`u64`), or constrain it with \
+                 `assert_max_bit_size::<64>()` before using it.",
+                0.6,
+            ));
+        }
+    }
+
+    findings
+}
+
+/// `content` with `//` and `/* */` comments blanked, newlines kept so offsets
+/// still map to the same lines.
+fn strip_comments(content: &str) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut chars = content.chars().peekable();
+    let (mut line_comment, mut block_comment, mut string) = (false, false, false);
+    while let Some(c) = chars.next() {
+        if line_comment {
+            if c == '\n' {
+                line_comment = false;
+                out.push(c);
+            }
+        } else if block_comment {
+            if c == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                block_comment = false;
+            } else if c == '\n' {
+                out.push(c);
+            }
+        } else if string {
+            string = c != '"';
+            out.push(c);
+        } else if c == '/' && chars.peek() == Some(&'/') {
+            line_comment = true;
+        } else if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            block_comment = true;
+        } else {
+            string = c == '"';
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn parse_main(code: &str) -> Option<Circuit> {
+    let found = MAIN_FN.find(code).ok()??;
+    let line_of = |offset: usize| code[..offset].matches('\n').count() + 1;
+    let params_start = found.end();
+    let params_end = params_start + closing(&code[params_start..], '(', ')')?;
+
+    let mut inputs = Vec::new();
+    let mut offset = params_start;
+    for param in split_top_level(&code[params_start..params_end]) {
+        let start = offset + (param.len() - param.trim_start().len());
+        offset += param.len() + 1;
+        let Some((name, ty)) = param.split_once(':') else {
+            continue;
+        };
+        let name = name.trim().trim_start_matches("mut ").trim();
+        let ty = ty.trim();
+        let (public, ty) = match ty.strip_prefix("pub") {
+            Some(rest) if rest.starts_with(char::is_whitespace) => (true, rest.trim()),
+            _ => (false, ty),
+        };
+        if !name.is_empty() {
+            inputs.push(Input {
+                name: name.to_string(),
+                ty: ty.to_string(),
+                public,
+                line: line_of(start),
+            });
+        }
+    }
+
+    let body_start = params_end + code[params_end..].find('{')? + 1;
+    let body_end = body_start + closing(&code[body_start..], '{', '}')?;
+    let statements = code[body_start..body_end]
+        .split([';', '{', '}'])
+        .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|s| !s.is_empty())
+        .collect();
+
+    Some(Circuit {
+        line: line_of(found.start()),
+        inputs,
+        statements,
+    })
+}
+
+/// Offset of the `close` matching an already-opened `open` in `text`.
+fn closing(text: &str, open: char, close: char) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                return Some(i);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Split a parameter list at commas outside brackets (`[Field; 2]`, generics).
+fn split_top_level(params: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0i32, 0);
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' | '[' | '<' | '{' => depth += 1,
+            ')' | ']' | '>' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&params[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&params[start..]);
+    parts
+}
+
+fn mentions(statement: &str, name: &str) -> bool {
+    Regex::new(&format!(r"\b{name}\b")).is_ok_and(|re| re.is_match(statement).unwrap_or(false))
+}
+
+/// `assert(name != 0)`, `assert(0 != name)` and the `==` forms, optionally
+/// with a message: checks that pin the input to (or away from) a constant
+/// without relating it to anything else.
+fn is_literal_check(statement: &str, name: &str) -> bool {
+    let pattern = format!(
+        r"^assert\s*\(\s*(?:{name}\s*[!=]=\s*{LITERAL}|{LITERAL}\s*[!=]=\s*{name})\s*(?:,.*)?\)$"
+    );
+    Regex::new(&pattern).is_ok_and(|re| re.is_match(statement.trim()).unwrap_or(false))
+}
+
+fn snippet(lines: &[&str], line: usize) -> String {
+    let start = line.saturating_sub(3).max(1);
+    let end = (line + 3).min(lines.len());
+    (start..=end)
+        .map(|n| format!("{n:>4} | {}", lines[n - 1]))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WITHDRAW: &str = r#"use dep::std;
+
+fn main(
+    secret: Field,
+    nullifier_key: Field,
+    amount: Field,
+    root: pub Field,
+    nullifier_hash: pub Field,
+    recipient: pub Field, // checked below
+) {
+    let commitment = std::hash::pedersen_hash([secret, nullifier_key, amount]);
+    assert(std::hash::pedersen_hash([nullifier_key]) == nullifier_hash);
+    assert(compute_root(commitment) == root);
+    /* the recipient only has to be set */
+    assert(recipient != 0);
+}
+"#;
+
+    fn ids(findings: &[Finding]) -> Vec<(&str, usize)> {
+        findings
+            .iter()
+            .map(|f| (f.pattern_id.as_str(), f.line_number))
+            .collect()
+    }
+
+    #[test]
+    fn flags_literal_only_public_input_and_unchecked_amount() {
+        let findings = scan(WITHDRAW, Path::new("noir_circuit/src/main.nr"));
+        assert_eq!(ids(&findings), vec![("NOIR-001", 9), ("NOIR-003", 6)]);
+        assert!(
+            findings[0]
+                .description
+                .contains("only compared with a literal (`assert(recipient != 0)`)")
+        );
+    }
+
+    #[test]
+    fn bound_inputs_and_range_checks_pass() {
+        let fixed = WITHDRAW
+            .replace(
+                "[secret, nullifier_key, amount]",
+                "[secret, nullifier_key, amount, recipient]",
+            )
+            .replace(
+                "    let commitment",
+                "    amount.assert_max_bit_size::<64>();\n    let commitment",
+            );
+        assert!(scan(&fixed, Path::new("main.nr")).is_empty());
+    }
+
+    #[test]
+    fn flags_spend_without_public_nullifier() {
+        let src = "fn main(secret: Field, root: pub Field) {\n    \
+                   assert(std::hash::pedersen_hash([secret]) == root);\n}\n";
+        assert_eq!(ids(&scan(src, Path::new("main.nr"))), vec![("NOIR-002", 1)]);
+        assert!(scan("fn helper(x: Field) -> Field { x }", Path::new("lib.nr")).is_empty());
+    }
+}
@@ -0,0 +1,406 @@
+//! Suggested fixes for findings with a mechanical remedy. The flagged
+//! expression is located in the AST and rewritten, and the change is
+//! returned as a unified diff that `git apply` accepts:
+//!
+//! - SOL-003: `a + b` becomes `a.checked_add(b).ok_or(ErrorCode::Overflow)?`
+//!   (nested operators included, compound assignment too).
+//! - SOL-010: `spl_token::instruction::transfer(..)` becomes `transfer_checked`
+//!   with `mint_pubkey` and `mint_decimals` arguments for the author to bind.
+//!
+//! Suggestions are a starting point for review, not a verified fix: the
+//! enclosing function must return a `Result`, and the error variant may need
+//! adding.
+
+use super::SecurityFinding;
+use crate::output::file::OutputFile;
+use anyhow::Result;
+use proc_macro2::LineColumn;
+use std::path::{Path, PathBuf};
+use syn::spanned::Spanned;
+use syn::visit::Visit;
+use syn::{BinOp, Expr, ExprCall, ItemEnum};
+
+/// Pattern IDs [`suggest`] can produce a patch for.
+pub const PATCHABLE: &[&str] = &["SOL-003", "SOL-010"];
+
+/// Unchanged lines shown around each change.
+const CONTEXT: usize = 3;
+
+/// Error enum and variant used when the file declares no `#[error_code]` enum.
+const DEFAULT_ERROR: (&str, &str) = ("ErrorCode", "Overflow");
+
+/// Variant names reused if the file's error enum already has one.
+const OVERFLOW_VARIANTS: &[&str] = &["Overflow", "MathOverflow", "ArithmeticOverflow"];
+
+/// A unified diff fixing the `pattern_id` finding on `line` of `content`.
+/// `path` names the file in the diff header (repo-relative, forward slashes).
+/// `None` when the pattern isn't patchable or the flagged code can't be
+/// found in the AST.
+pub fn suggest(pattern_id: &str, content: &str, line: usize, path: &str) -> Option<String> {
+    let ast: syn::File = syn::parse_str(content).ok()?;
+    let (span_start, span_end, replacement) = match pattern_id {
+        "SOL-003" => checked_arithmetic(&ast, content, line)?,
+        "SOL-010" => transfer_checked(&ast, content, line)?,
+        _ => return None,
+    };
+    let start = offset(content, span_start)?;
+    let end = offset(content, span_end)?;
+    Some(unified_diff(content, start, end, &replacement, path))
+}
+
+/// Write each finding's patch to `dir/{id}.patch` (or
+/// `{pattern}-{index}.patch` for findings without an ID). Existing patch
+/// files are only replaced with `force`, and nothing is written if any would
+/// be refused. Returns the files written.
+pub fn write_all(findings: &[SecurityFinding], dir: &Path, force: bool) -> Result<Vec<PathBuf>> {
+    let files: Vec<(OutputFile, &String)> = findings
+        .iter()
+        .enumerate()
+        .filter_map(|(i, f)| {
+            let patch = f.patch.as_ref()?;
+            let name = f
+                .id
+                .clone()
+                .unwrap_or_else(|| format!("{}-{i}", f.pattern_id.as_deref().unwrap_or("finding")));
+            Some((
+                OutputFile::new(dir.join(format!("{name}.patch")), force),
+                patch,
+            ))
+        })
+        .collect();
+    for (file, _) in &files {
+        file.check()?;
+    }
+    for (file, patch) in &files {
+        file.write(patch)?;
+    }
+    Ok(files
+        .iter()
+        .filter_map(|(file, _)| file.path().map(Path::to_path_buf))
+        .collect())
+}
+
+type Rewrite = (LineColumn, LineColumn, String);
+
+fn checked_arithmetic(ast: &syn::File, content: &str, line: usize) -> Option<Rewrite> {
+    let mut finder = ArithmeticFinder { line, found: None };
+    finder.visit_file(ast);
+    let expr = finder.found?;
+    let error = overflow_error(ast);
+    let text = checked(expr, content, &error)?;
+    Some((expr.span().start(), expr.span().end(), text))
+}
+
+fn transfer_checked(ast: &syn::File, content: &str, line: usize) -> Option<Rewrite> {
+    let mut finder = TransferFinder { line, found: None };
+    finder.visit_file(ast);
+    let call = finder.found?;
+    let func = source(content, &call.func)?;
+    let args: Vec<String> = call
+        .args
+        .iter()
+        .map(|a| source(content, a))
+        .collect::<Option<_>>()?;
+    // transfer(program, source, destination, authority, signers, amount)
+    // transfer_checked(program, source, mint, destination, authority, signers, amount, decimals)
+    let [program, src, dest, authority, signers, amount] = args.as_slice() else {
+        return None;
+    };
+    let text = format!(
+        "{func}_checked({program}, {src}, mint_pubkey, {dest}, {authority}, {signers}, {amount}, mint_decimals)"
+    );
+    Some((call.span().start(), call.span().end(), text))
+}
+
+/// Outermost `+`, `-`, `*` (or compound assignment) starting on `line`.
+struct ArithmeticFinder<'ast> {
+    line: usize,
+    found: Option<&'ast Expr>,
+}
+
+impl<'ast> Visit<'ast> for ArithmeticFinder<'ast> {
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        if self.found.is_some() {
+            return;
+        }
+        if let Expr::Binary(b) = expr
+            && checked_method(&b.op).is_some()
+            && expr.span().start().line == self.line
+        {
+            self.found = Some(expr);
+            return;
+        }
+        syn::visit::visit_expr(self, expr);
+    }
+}
+
+/// A six-argument `spl_token::...::transfer(..)` call starting on `line`.
+struct TransferFinder<'ast> {
+    line: usize,
+    found: Option<&'ast ExprCall>,
+}
+
+impl<'ast> Visit<'ast> for TransferFinder<'ast> {
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        if self.found.is_none()
+            && call.span().start().line == self.line
+            && call.args.len() == 6
+            && let Expr::Path(p) = &*call.func
+            && p.path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident == "transfer")
+            && p.path.segments.iter().any(|s| s.ident == "spl_token")
+        {
+            self.found = Some(call);
+            return;
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+}
+
+/// `checked_*` method for an arithmetic operator, and whether it assigns.
+fn checked_method(op: &BinOp) -> Option<(&'static str, bool)> {
+    match op {
+        BinOp::Add(_) => Some(("checked_add", false)),
+        BinOp::Sub(_) => Some(("checked_sub", false)),
+        BinOp::Mul(_) => Some(("checked_mul", false)),
+        BinOp::AddAssign(_) => Some(("checked_add", true)),
+        BinOp::SubAssign(_) => Some(("checked_sub", true)),
+        BinOp::MulAssign(_) => Some(("checked_mul", true)),
+        _ => None,
+    }
+}
+
+/// `expr` with every arithmetic operator rewritten to its checked form.
+fn checked(expr: &Expr, content: &str, error: &str) -> Option<String> {
+    match expr {
+        Expr::Binary(b) => {
+            let Some((method, assigns)) = checked_method(&b.op) else {
+                return source(content, expr);
+            };
+            let left = checked(&b.left, content, error)?;
+            let right = checked(&b.right, content, error)?;
+            let receiver = if is_postfix_safe(&b.left) {
+                left.clone()
+            } else {
+                format!("({left})")
+            };
+            let call = format!("{receiver}.{method}({right}).ok_or({error})?");
+            Some(if assigns {
+                format!("{left} = {call}")
+            } else {
+                call
+            })
+        }
+        Expr::Paren(p) => Some(format!("({})", checked(&p.expr, content, error)?)),
+        _ => source(content, expr),
+    }
+}
+
+/// Whether `.method()` can follow `expr` without parentheses.
+fn is_postfix_safe(expr: &Expr) -> bool {
+    match expr {
+        Expr::Binary(b) => checked_method(&b.op).is_some(),
+        Expr::Path(_)
+        | Expr::Field(_)
+        | Expr::MethodCall(_)
+        | Expr::Call(_)
+        | Expr::Lit(_)
+        | Expr::Index(_)
+        | Expr::Paren(_)
+        | Expr::Macro(_)
+        | Expr::Try(_) => true,
+        _ => false,
+    }
+}
+
+/// `Enum::Variant` for overflow errors: the file's `#[error_code]` enum and
+/// an existing overflow variant when there is one.
+fn overflow_error(ast: &syn::File) -> String {
+    let error_enum = ast.items.iter().find_map(|item| match item {
+        syn::Item::Enum(e) if e.attrs.iter().any(|a| a.path().is_ident("error_code")) => Some(e),
+        _ => None,
+    });
+    let Some(ItemEnum {
+        ident, variants, ..
+    }) = error_enum
+    else {
+        return format!("{}::{}", DEFAULT_ERROR.0, DEFAULT_ERROR.1);
+    };
+    let variant = variants
+        .iter()
+        .map(|v| v.ident.to_string())
+        .find(|v| OVERFLOW_VARIANTS.contains(&v.as_str()))
+        .unwrap_or_else(|| DEFAULT_ERROR.1.to_string());
+    format!("{ident}::{variant}")
+}
+
+/// Source text of a node.
+fn source(content: &str, node: &impl Spanned) -> Option<String> {
+    let span = node.span();
+    let start = offset(content, span.start())?;
+    let end = offset(content, span.end())?;
+    content.get(start..end).map(String::from)
+}
+
+/// Byte offset of a span position (1-based line, 0-based char column).
+fn offset(content: &str, at: LineColumn) -> Option<usize> {
+    let line_start: usize = content
+        .split_inclusive('\n')
+        .take(at.line.checked_sub(1)?)
+        .map(str::len)
+        .sum();
+    let rest = content.get(line_start..)?;
+    Some(
+        line_start
+            + rest
+                .char_indices()
+                .nth(at.column)
+                .map_or(rest.len(), |(i, _)| i),
+    )
+}
+
+/// One-hunk unified diff replacing `content[start..end]` with `replacement`.
+fn unified_diff(content: &str, start: usize, end: usize, replacement: &str, path: &str) -> String {
+    let patched = format!("{}{replacement}{}", &content[..start], &content[end..]);
+    let old: Vec<&str> = content.lines().collect();
+    let new: Vec<&str> = patched.lines().collect();
+
+    let first = content[..start].matches('\n').count();
+    let last_old = content[..end].matches('\n').count();
+    let last_new = patched[..start + replacement.len()].matches('\n').count();
+    let from = first.saturating_sub(CONTEXT);
+    let trailing = (old.len() - 1 - last_old).min(CONTEXT);
+
+    let mut out = format!(
+        "--- a/{path}\n+++ b/{path}\n@@ -{},{} +{},{} @@\n",
+        from + 1,
+        last_old + trailing + 1 - from,
+        from + 1,
+        last_new + trailing + 1 - from,
+    );
+    for line in &old[from..first] {
+        out.push_str(&format!(" {line}\n"));
+    }
+    for line in &old[first..=last_old] {
+        out.push_str(&format!("-{line}\n"));
+    }
+    for line in &new[first..=last_new] {
+        out.push_str(&format!("+{line}\n"));
+    }
+    for line in &old[last_old + 1..=last_old + trailing] {
+        out.push_str(&format!(" {line}\n"));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARITH: &str = "use anchor_lang::prelude::*;
+
+pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    vault.balance = vault.balance + amount * 2;
+    vault.total += amount;
+    Ok(())
+}
+";
+
+    #[test]
+    fn rewrites_nested_arithmetic() {
+        let diff = suggest("SOL-003", ARITH, 5, "programs/vault/src/lib.rs").unwrap();
+        assert_eq!(
+            diff,
+            "--- a/programs/vault/src/lib.rs\n\
+             +++ b/programs/vault/src/lib.rs\n\
+             @@ -2,7 +2,7 @@\n \n \
+             pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {\n \
+             \x20   let vault = &mut ctx.accounts.vault;\n\
+             -    vault.balance = vault.balance + amount * 2;\n\
+             +    vault.balance = vault.balance.checked_add(amount.checked_mul(2).ok_or(ErrorCode::Overflow)?).ok_or(ErrorCode::Overflow)?;\n \
+             \x20   vault.total += amount;\n \
+             \x20   Ok(())\n \
+             }\n"
+        );
+    }
+
+    #[test]
+    fn compound_assignment_and_declared_error_enum() {
+        let content = format!(
+            "{ARITH}\n#[error_code]\npub enum VaultError {{\n    #[msg(\"overflow\")]\n    MathOverflow,\n}}\n"
+        );
+        let diff = suggest("SOL-003", &content, 6, "src/lib.rs").unwrap();
+        assert!(diff.contains(
+            "+    vault.total = vault.total.checked_add(amount).ok_or(VaultError::MathOverflow)?;\n"
+        ));
+        assert!(diff.contains("-    vault.total += amount;\n"));
+    }
+
+    #[test]
+    fn rewrites_multiline_transfer() {
+        let content = "pub fn pay(ctx: Context<Pay>, amount: u64) -> Result<()> {
+    let ix = spl_token::instruction::transfer(
+        &spl_token::ID,
+        &src,
+        &dst,
+        &authority,
+        &[],
+        amount,
+    )?;
+    Ok(())
+}
+";
+        let diff = suggest("SOL-010", content, 2, "src/pay.rs").unwrap();
+        assert!(diff.starts_with("--- a/src/pay.rs\n+++ b/src/pay.rs\n@@ -1,11 +1,4 @@\n"));
+        assert!(diff.contains(
+            "+    let ix = spl_token::instruction::transfer_checked(&spl_token::ID, &src, mint_pubkey, &dst, &authority, &[], amount, mint_decimals)?;\n"
+        ));
+        assert_eq!(diff.lines().filter(|l| l.starts_with('-')).count(), 9);
+    }
+
+    #[test]
+    fn writes_one_file_per_patch() {
+        let dir = std::env::temp_dir().join("solguard-patch-files");
+        let _ = std::fs::remove_dir_all(&dir);
+        let findings = vec![
+            SecurityFinding {
+                id: Some("SG-2026-vault-0001".into()),
+                patch: Some("--- a/x\n".into()),
+                ..Default::default()
+            },
+            SecurityFinding::default(),
+            SecurityFinding {
+                pattern_id: Some("SOL-010".into()),
+                patch: Some("--- a/y\n".into()),
+                ..Default::default()
+            },
+        ];
+        let written = write_all(&findings, &dir, false).unwrap();
+        assert_eq!(
+            written,
+            vec![
+                dir.join("SG-2026-vault-0001.patch"),
+                dir.join("SOL-010-2.patch")
+            ]
+        );
+        assert_eq!(std::fs::read_to_string(&written[1]).unwrap(), "--- a/y\n");
+        // An existing patch is refused without force, and nothing is written.
+        std::fs::remove_file(&written[0]).unwrap();
+        assert!(write_all(&findings, &dir, false).is_err());
+        assert!(!written[0].exists());
+        assert_eq!(write_all(&findings, &dir, true).unwrap(), written);
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert!(write_all(&findings[1..2], &dir, false).unwrap().is_empty());
+        assert!(!dir.exists());
+    }
+
+    #[test]
+    fn nothing_to_patch() {
+        assert!(suggest("SOL-004", ARITH, 5, "a.rs").is_none());
+        assert!(suggest("SOL-003", ARITH, 4, "a.rs").is_none());
+        assert!(suggest("SOL-003", "fn {{{", 1, "a.rs").is_none());
+    }
+}
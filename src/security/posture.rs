@@ -0,0 +1,642 @@
+//! Deployment posture: who can upgrade each program a repo declares.
+//!
+//! For every `declare_id!` in the repo and every program ID in its
+//! `Anchor.toml`, query RPC for the deployed program account, follow it to
+//! its ProgramData account, and classify the upgrade authority. The same
+//! authority can also close the program for good. A program upgradeable by a
+//! single key is a Medium finding; multisig/governance/program-controlled/PDA
+//! authorities and frozen programs are Info.
+//!
+//! Offline, [`program_id_mismatches`] checks that each `Anchor.toml` entry
+//! matches the `declare_id!` of the program it names. A mismatch means the
+//! program deployed at the configured address checks instructions and
+//! derives PDAs against a different ID.
+
+use super::{Finding, SecurityFinding, Severity, collect_rust_files, workspace};
+use crate::narrative::rpc_pool::RpcPool;
+use anyhow::Result;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Tag carried by every posture finding; the report reads it for the repo summary.
+pub const POSTURE_TAG: &str = "upgrade-authority";
+/// Title prefix of posture findings, followed by the posture label.
+pub const TITLE_PREFIX: &str = "Upgrade Authority: ";
+/// Pattern ID of an `Anchor.toml` entry that disagrees with `declare_id!`.
+pub const ID_MISMATCH_PATTERN: &str = "CFG-001";
+
+pub(crate) const UPGRADEABLE_LOADER: &str = "BPFLoaderUpgradeab1e11111111111111111111111";
+const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+/// Owners whose accounts we treat as multisig or governance controlled.
+const MULTISIG_PROGRAMS: &[(&str, &str)] = &[
+    ("SMPLecH534NA9acpos4G6x7uf3LWbCAwZQE9e8ZekMu", "Squads v3"),
+    ("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf", "Squads v4"),
+    (
+        "GovER5Lthms3bLBqWub97yVrMmEogzX7xNjdXpPPCVZw",
+        "SPL Governance",
+    ),
+];
+
+/// A program ID declared in source, with where it was declared.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeclaredProgram {
+    pub program_id: String,
+    pub file_path: PathBuf,
+    pub line_number: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UpgradePosture {
+    /// Not found on chain at the configured RPC (e.g. devnet-only).
+    NotDeployed,
+    /// Upgrade authority revoked, or deployed with a non-upgradeable loader.
+    Frozen,
+    /// Authority is a plain system account — one private key can upgrade.
+    SingleKey { authority: String },
+    /// Authority is owned by a known multisig or governance program.
+    Multisig { authority: String, program: String },
+    /// Authority is owned by some other program (PDA vault, custom governance).
+    ProgramControlled { authority: String, owner: String },
+    /// Authority is an off-curve address, such as a Squads vault: no private
+    /// key exists, only the program that derived it can sign.
+    ProgramDerived { authority: String },
+}
+
+impl UpgradePosture {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::NotDeployed => "not deployed",
+            Self::Frozen => "frozen",
+            Self::SingleKey { .. } => "single key",
+            Self::Multisig { .. } => "multisig",
+            Self::ProgramControlled { .. } => "program-controlled",
+            Self::ProgramDerived { .. } => "program-derived",
+        }
+    }
+
+    /// Convert to a report finding. `NotDeployed` yields nothing.
+    pub fn to_finding(&self, program: &DeclaredProgram) -> Option<SecurityFinding> {
+        let id = &program.program_id;
+        let (severity, description, remediation) = match self {
+            Self::NotDeployed => return None,
+            Self::Frozen => (
+                "Info",
+                format!(
+                    "Program {id} is immutable: no upgrade authority is set, so it can be \
+                     neither upgraded nor closed."
+                ),
+                "None required. Bugs can only be fixed by redeploying to a new address.".into(),
+            ),
+            Self::SingleKey { authority } => (
+                "Medium",
+                format!(
+                    "Program {id} can be upgraded or closed by a single key ({authority}). \
+                     Compromise of that key lets an attacker replace the program and drain \
+                     everything it controls, or close it and strand every account it owns."
+                ),
+                "Transfer the upgrade authority to a multisig (e.g. Squads) or governance \
+                 program, or revoke it once the program is stable."
+                    .into(),
+            ),
+            Self::Multisig { authority, program } => (
+                "Info",
+                format!("Program {id} upgrade authority is a {program} account ({authority})."),
+                "Review the multisig threshold and signer set.".into(),
+            ),
+            Self::ProgramControlled { authority, owner } => (
+                "Info",
+                format!(
+                    "Program {id} upgrade authority {authority} is owned by program {owner}; \
+                     upgrade control depends on that program's logic."
+                ),
+                "Verify who controls the owning program and how upgrades are approved.".into(),
+            ),
+            Self::ProgramDerived { authority } => (
+                "Info",
+                format!(
+                    "Program {id} upgrade authority {authority} is a program-derived address \
+                     (e.g. a Squads vault); no single key can sign for it."
+                ),
+                "Verify which program derives the authority and how it approves upgrades.".into(),
+            ),
+        };
+        Some(SecurityFinding {
+            title: format!("{TITLE_PREFIX}{}", self.label()),
+            severity: severity.into(),
+            description,
+            file_path: program.file_path.clone(),
+            line_number: program.line_number,
+            remediation,
+            tags: vec![POSTURE_TAG.into()],
+            ..Default::default()
+        })
+    }
+}
+
+/// Posture label of a finding produced by this module, if it is one.
+pub fn posture_label(f: &SecurityFinding) -> Option<&str> {
+    if !f.tags.iter().any(|t| t == POSTURE_TAG) {
+        return None;
+    }
+    f.title.strip_prefix(TITLE_PREFIX)
+}
+
+/// Find every `declare_id!("...")` in the repo's program sources, then any
+/// program ID in `Anchor.toml` (`[programs.<cluster>]`) not declared in source,
+/// such as a mainnet address that differs from the localnet one.
+pub fn find_declared_programs(repo_path: &Path) -> Vec<DeclaredProgram> {
+    let mut programs: Vec<DeclaredProgram> = Vec::new();
+    let declared = source_declarations(repo_path).into_iter().chain(
+        anchor_toml_entries(repo_path)
+            .into_iter()
+            .map(|e| e.program),
+    );
+    for program in declared {
+        if !programs.iter().any(|p| p.program_id == program.program_id) {
+            programs.push(program);
+        }
+    }
+    debug!(path = %repo_path.display(), count = programs.len(), "declared programs found");
+    programs
+}
+
+/// Every `declare_id!` in the repo's sources, duplicates included (a program
+/// may gate one per cluster behind cargo features).
+pub(crate) fn source_declarations(repo_path: &Path) -> Vec<DeclaredProgram> {
+    let mut declared = Vec::new();
+    for file_path in collect_rust_files(repo_path).unwrap_or_default() {
+        let Ok(content) = std::fs::read_to_string(&file_path) else {
+            continue;
+        };
+        for (i, line) in content.lines().enumerate() {
+            if let Some(program_id) = parse_declare_id(line) {
+                declared.push(DeclaredProgram {
+                    program_id,
+                    file_path: file_path.clone(),
+                    line_number: i + 1,
+                });
+            }
+        }
+    }
+    declared
+}
+
+/// One `<name> = "<id>"` line under `[programs.<cluster>]` in `Anchor.toml`.
+pub(crate) struct AnchorTomlEntry {
+    pub cluster: String,
+    pub name: String,
+    pub program: DeclaredProgram,
+}
+
+/// Program entries of every `[programs.<cluster>]` table of `Anchor.toml`,
+/// skipping values that aren't keys.
+pub(crate) fn anchor_toml_entries(repo_path: &Path) -> Vec<AnchorTomlEntry> {
+    let path = repo_path.join("Anchor.toml");
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return Vec::new();
+    };
+    let Ok(manifest) = content.parse::<toml::Table>() else {
+        debug!(path = %path.display(), "Anchor.toml did not parse");
+        return Vec::new();
+    };
+    let Some(clusters) = manifest.get("programs").and_then(|p| p.as_table()) else {
+        return Vec::new();
+    };
+    let mut entries = Vec::new();
+    for (cluster, ids) in clusters
+        .iter()
+        .filter_map(|(cluster, ids)| Some((cluster, ids.as_table()?)))
+    {
+        for (name, id) in ids.iter().filter_map(|(n, v)| Some((n, v.as_str()?))) {
+            if !is_base58_key(id) {
+                continue;
+            }
+            entries.push(AnchorTomlEntry {
+                cluster: cluster.clone(),
+                name: name.clone(),
+                program: DeclaredProgram {
+                    program_id: id.to_string(),
+                    file_path: path.clone(),
+                    line_number: content
+                        .lines()
+                        .position(|l| l.contains(id))
+                        .map_or(0, |i| i + 1),
+                },
+            });
+        }
+    }
+    entries
+}
+
+/// A finding for each `Anchor.toml` program entry whose ID is not declared
+/// by the program it names. Entries are matched to workspace program crates
+/// by name (`my-vault` is `my_vault`); a repo that is itself the only
+/// program matches its single entry per cluster. Entries naming no program
+/// in the repo are skipped.
+pub fn program_id_mismatches(repo_path: &Path) -> Vec<Finding> {
+    let entries = anchor_toml_entries(repo_path);
+    if entries.is_empty() {
+        return Vec::new();
+    }
+    let programs = workspace::programs(repo_path);
+    let declared = source_declarations(repo_path);
+    let mut findings = Vec::new();
+    for entry in &entries {
+        let own: Vec<&DeclaredProgram> = if programs.is_empty() {
+            let single = entries
+                .iter()
+                .filter(|e| e.cluster == entry.cluster)
+                .count()
+                == 1;
+            declared.iter().filter(|_| single).collect()
+        } else {
+            declared
+                .iter()
+                .filter(|d| {
+                    workspace::program_of(&programs, &d.file_path)
+                        .is_some_and(|p| p.name.replace('-', "_") == entry.name)
+                })
+                .collect()
+        };
+        let Some(source) = own.first() else {
+            continue;
+        };
+        if own.iter().any(|d| d.program_id == entry.program.program_id) {
+            continue;
+        }
+        findings.push(mismatch_finding(repo_path, entry, source));
+    }
+    if !findings.is_empty() {
+        info!(count = findings.len(), "Anchor.toml program ID mismatches");
+    }
+    findings
+}
+
+fn mismatch_finding(
+    repo_path: &Path,
+    entry: &AnchorTomlEntry,
+    source: &DeclaredProgram,
+) -> Finding {
+    let rel = |p: &Path| crate::paths::to_slash(p.strip_prefix(repo_path).unwrap_or(p));
+    let toml = &entry.program;
+    Finding {
+        pattern_id: ID_MISMATCH_PATTERN.into(),
+        title: "Program ID Mismatch".into(),
+        description: format!(
+            "`Anchor.toml` [programs.{cluster}] sets `{name}` to {toml_id}, but `declare_id!` \
+             in {file}:{line} is {source_id}. Anchor rejects instructions whose program ID \
+             differs from `declare_id!`, and PDAs and owner checks derived from it point at \
+             the wrong program, so the deployment at {toml_id} validates accounts against an \
+             ID it doesn't have.",
+            cluster = entry.cluster,
+            name = entry.name,
+            toml_id = toml.program_id,
+            file = rel(&source.file_path),
+            line = source.line_number,
+            source_id = source.program_id,
+        ),
+        severity: Severity::Medium,
+        file_path: toml.file_path.clone(),
+        line_number: toml.line_number,
+        code_snippet: format!(
+            "{}:{}: {} = \"{}\"\n{}:{}: declare_id!(\"{}\")",
+            rel(&toml.file_path),
+            toml.line_number,
+            entry.name,
+            toml.program_id,
+            rel(&source.file_path),
+            source.line_number,
+            source.program_id
+        ),
+        remediation: "Run `anchor keys sync` so both use the deploy keypair's address. If \
+                      clusters deliberately use different IDs, gate one `declare_id!` per \
+                      cluster behind a cargo feature."
+            .into(),
+        confidence: 0.9,
+        references: vec!["https://www.anchor-lang.com/docs/references/anchor-toml".into()],
+    }
+}
+
+fn parse_declare_id(line: &str) -> Option<String> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("//") {
+        return None;
+    }
+    let after = &trimmed[trimmed.find("declare_id!(")? + "declare_id!(".len()..];
+    let start = after.find('"')? + 1;
+    let len = after[start..].find('"')?;
+    let id = &after[start..start + len];
+    is_base58_key(id).then(|| id.to_string())
+}
+
+/// Whether `id` looks like a base58 public key.
+pub(crate) fn is_base58_key(id: &str) -> bool {
+    (32..=44).contains(&id.len())
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+/// Check the upgrade posture of every program declared in `repo_path`.
+pub async fn check_repo(repo_path: &Path, rpc: &RpcPool) -> Vec<SecurityFinding> {
+    let mut findings = Vec::new();
+    for program in find_declared_programs(repo_path) {
+        match check_program(&program.program_id, rpc).await {
+            Ok(posture) => {
+                info!(program = %program.program_id, posture = posture.label(), "upgrade posture");
+                findings.extend(posture.to_finding(&program));
+            }
+            Err(e) => {
+                tracing::warn!(program = %program.program_id, error = %e, "posture check failed");
+            }
+        }
+    }
+    findings
+}
+
+async fn check_program(program_id: &str, rpc: &RpcPool) -> Result<UpgradePosture> {
+    let Some(program) = get_account(rpc, program_id).await? else {
+        return Ok(UpgradePosture::NotDeployed);
+    };
+    let Some(program_data) = program_data_address(&program) else {
+        // Legacy loaders (BPFLoader2) are not upgradeable.
+        return Ok(UpgradePosture::Frozen);
+    };
+    let Some(data) = get_account(rpc, &program_data).await? else {
+        return Ok(UpgradePosture::NotDeployed);
+    };
+    let Some(authority) = upgrade_authority(&data) else {
+        return Ok(UpgradePosture::Frozen);
+    };
+    let owner = get_account(rpc, &authority)
+        .await?
+        .and_then(|a| a["owner"].as_str().map(String::from));
+    Ok(classify_authority(authority, owner.as_deref()))
+}
+
+pub(crate) async fn get_account(rpc: &RpcPool, address: &str) -> Result<Option<Value>> {
+    let result: Value = rpc
+        .call(
+            "getAccountInfo",
+            serde_json::json!([address, {"encoding": "jsonParsed"}]),
+        )
+        .await?;
+    Ok(match &result["value"] {
+        Value::Null => None,
+        v => Some(v.clone()),
+    })
+}
+
+/// ProgramData address of an upgradeable-loader program account.
+pub(crate) fn program_data_address(account: &Value) -> Option<String> {
+    if account["owner"].as_str() != Some(UPGRADEABLE_LOADER) {
+        return None;
+    }
+    account["data"]["parsed"]["info"]["programData"]
+        .as_str()
+        .map(String::from)
+}
+
+/// Upgrade authority recorded in a ProgramData account; `None` once revoked.
+pub(crate) fn upgrade_authority(program_data: &Value) -> Option<String> {
+    program_data["data"]["parsed"]["info"]["authority"]
+        .as_str()
+        .map(String::from)
+}
+
+/// Whether `address` is a valid public key off the ed25519 curve, i.e. a PDA
+/// that no private key can sign for.
+pub(crate) fn is_off_curve(address: &str) -> bool {
+    bs58::decode(address)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .is_some_and(|bytes| CompressedEdwardsY(bytes).decompress().is_none())
+}
+
+/// Classify an authority by its owning program. A system-owned or missing
+/// account (no lamports) is a bare keypair unless its address is off-curve:
+/// Squads vaults are system-owned PDAs.
+pub(crate) fn classify_authority(authority: String, owner: Option<&str>) -> UpgradePosture {
+    match owner {
+        None | Some(SYSTEM_PROGRAM) if is_off_curve(&authority) => {
+            UpgradePosture::ProgramDerived { authority }
+        }
+        None | Some(SYSTEM_PROGRAM) => UpgradePosture::SingleKey { authority },
+        Some(owner) => match MULTISIG_PROGRAMS.iter().find(|(id, _)| *id == owner) {
+            Some((_, name)) => UpgradePosture::Multisig {
+                authority,
+                program: (*name).into(),
+            },
+            None => UpgradePosture::ProgramControlled {
+                authority,
+                owner: owner.into(),
+            },
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM_ID: &str = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+
+    #[test]
+    fn declare_id_parsed() {
+        assert_eq!(
+            parse_declare_id(&format!("declare_id!(\"{PROGRAM_ID}\");")).as_deref(),
+            Some(PROGRAM_ID)
+        );
+        assert!(parse_declare_id(&format!("// declare_id!(\"{PROGRAM_ID}\");")).is_none());
+        assert!(parse_declare_id("declare_id!(\"not-a-key\");").is_none());
+        assert!(parse_declare_id("let x = 1;").is_none());
+    }
+
+    #[test]
+    fn anchor_toml_ids_added_after_source() {
+        let root = std::env::temp_dir().join(format!("solguard-posture-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("programs/vault/src")).unwrap();
+        std::fs::write(
+            root.join("programs/vault/src/lib.rs"),
+            format!("declare_id!(\"{PROGRAM_ID}\");\n"),
+        )
+        .unwrap();
+        let mainnet = "VauLt1111111111111111111111111111111111111";
+        std::fs::write(
+            root.join("Anchor.toml"),
+            format!(
+                "[programs.localnet]\nvault = \"{PROGRAM_ID}\"\n\n\
+                 [programs.mainnet]\nvault = \"{mainnet}\"\nbad = \"not-a-key\"\n"
+            ),
+        )
+        .unwrap();
+        let programs = find_declared_programs(&root);
+        std::fs::remove_dir_all(&root).ok();
+
+        let ids: Vec<&str> = programs.iter().map(|p| p.program_id.as_str()).collect();
+        assert_eq!(ids, [PROGRAM_ID, mainnet]);
+        assert!(programs[0].file_path.ends_with("programs/vault/src/lib.rs"));
+        assert!(programs[1].file_path.ends_with("Anchor.toml"));
+        assert_eq!(programs[1].line_number, 5);
+    }
+
+    #[test]
+    fn anchor_toml_ids_checked_against_declare_id() {
+        let root = std::env::temp_dir().join(format!("solguard-ids-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("programs/my-vault/src")).unwrap();
+        std::fs::create_dir_all(root.join("programs/swap/src")).unwrap();
+        std::fs::write(
+            root.join("programs/my-vault/Cargo.toml"),
+            "[package]\nname = \"my-vault\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("programs/swap/Cargo.toml"),
+            "[package]\nname = \"swap\"\n",
+        )
+        .unwrap();
+        let devnet = "DevVau1t11111111111111111111111111111111111";
+        std::fs::write(
+            root.join("programs/my-vault/src/lib.rs"),
+            format!(
+                "#[cfg(not(feature = \"devnet\"))]\ndeclare_id!(\"{PROGRAM_ID}\");\n\
+                 #[cfg(feature = \"devnet\")]\ndeclare_id!(\"{devnet}\");\n"
+            ),
+        )
+        .unwrap();
+        let swap = "Swap111111111111111111111111111111111111111";
+        std::fs::write(
+            root.join("programs/swap/src/lib.rs"),
+            format!("declare_id!(\"{swap}\");\n"),
+        )
+        .unwrap();
+        let stale = "St4te11111111111111111111111111111111111111";
+        std::fs::write(
+            root.join("Anchor.toml"),
+            format!(
+                "[programs.localnet]\nmy_vault = \"{PROGRAM_ID}\"\nswap = \"{stale}\"\n\n\
+                 [programs.devnet]\nmy_vault = \"{devnet}\"\nswap = \"{swap}\"\n\
+                 external = \"{stale}\"\n"
+            ),
+        )
+        .unwrap();
+        let findings = program_id_mismatches(&root);
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(findings.len(), 1, "{findings:?}");
+        let f = &findings[0];
+        assert_eq!(f.pattern_id, ID_MISMATCH_PATTERN);
+        assert!(f.file_path.ends_with("Anchor.toml"));
+        assert_eq!(f.line_number, 3);
+        assert!(f.description.contains("[programs.localnet] sets `swap`"));
+        assert!(f.description.contains("programs/swap/src/lib.rs:1"));
+    }
+
+    #[test]
+    fn single_program_repo_matches_its_entry() {
+        let root = std::env::temp_dir().join(format!("solguard-ids-single-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            format!("declare_id!(\"{PROGRAM_ID}\");\n"),
+        )
+        .unwrap();
+        let other = "Rther1111111111111111111111111111111111111";
+        std::fs::write(
+            root.join("Anchor.toml"),
+            format!("[programs.localnet]\nvault = \"{other}\"\n"),
+        )
+        .unwrap();
+        let findings = program_id_mismatches(&root);
+        std::fs::remove_dir_all(&root).ok();
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].description.contains(other));
+    }
+
+    #[test]
+    fn program_account_followed_to_program_data() {
+        let account = serde_json::json!({
+            "owner": UPGRADEABLE_LOADER,
+            "data": {"parsed": {"type": "program", "info": {"programData": "PD111"}}}
+        });
+        assert_eq!(program_data_address(&account).as_deref(), Some("PD111"));
+
+        let legacy = serde_json::json!({"owner": "BPFLoader2111111111111111111111111111111111"});
+        assert!(program_data_address(&legacy).is_none());
+    }
+
+    #[test]
+    fn revoked_authority_is_none() {
+        let data = serde_json::json!({
+            "data": {"parsed": {"type": "programData", "info": {"authority": null, "slot": 1}}}
+        });
+        assert!(upgrade_authority(&data).is_none());
+    }
+
+    #[test]
+    fn authority_classification() {
+        assert!(matches!(
+            classify_authority("K".into(), Some(SYSTEM_PROGRAM)),
+            UpgradePosture::SingleKey { .. }
+        ));
+        assert!(matches!(
+            classify_authority("K".into(), None),
+            UpgradePosture::SingleKey { .. }
+        ));
+        assert!(matches!(
+            classify_authority(
+                "K".into(),
+                Some("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf")
+            ),
+            UpgradePosture::Multisig { .. }
+        ));
+        assert!(matches!(
+            classify_authority(
+                "K".into(),
+                Some("SomeOtherProgram1111111111111111111111111")
+            ),
+            UpgradePosture::ProgramControlled { .. }
+        ));
+    }
+
+    #[test]
+    fn squads_vault_pda_is_not_single_key() {
+        // Squads v4 vault 0 of multisig PROGRAM_ID.
+        let vault = "9NL6BetV1zcq3S12eeQKAHWv6X5zWWNVFPhqHSr3JR2f";
+        assert!(is_off_curve(vault));
+        assert!(!is_off_curve(PROGRAM_ID));
+        assert_eq!(
+            classify_authority(vault.into(), Some(SYSTEM_PROGRAM)),
+            UpgradePosture::ProgramDerived {
+                authority: vault.into()
+            }
+        );
+        assert!(matches!(
+            classify_authority(PROGRAM_ID.into(), None),
+            UpgradePosture::SingleKey { .. }
+        ));
+    }
+
+    #[test]
+    fn single_key_is_medium_finding() {
+        let program = DeclaredProgram {
+            program_id: PROGRAM_ID.into(),
+            file_path: PathBuf::from("repos/vault/programs/vault/src/lib.rs"),
+            line_number: 3,
+        };
+        let f = UpgradePosture::SingleKey {
+            authority: "Hot1".into(),
+        }
+        .to_finding(&program)
+        .unwrap();
+        assert_eq!(f.severity, "Medium");
+        assert_eq!(posture_label(&f), Some("single key"));
+
+        let frozen = UpgradePosture::Frozen.to_finding(&program).unwrap();
+        assert_eq!(frozen.severity, "Info");
+        assert!(UpgradePosture::NotDeployed.to_finding(&program).is_none());
+    }
+}
@@ -0,0 +1,250 @@
+//! Deployed on-chain state for the review agent's `program_state` tool, so
+//! it can check whether an issue it traced in the source is live: whether
+//! the program is deployed, who can upgrade it, and what a config or vault
+//! account actually holds. Fetched through the shared [`RpcPool`] with the
+//! same account walk as [`posture`].
+
+use super::posture::{self, UpgradePosture};
+use crate::narrative::rpc_pool::RpcPool;
+use anyhow::{Result, bail};
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Bytes of account data sampled when the caller doesn't ask for a length.
+const DEFAULT_SAMPLE_BYTES: usize = 128;
+/// Most bytes one call samples; tool results are capped anyway.
+const MAX_SAMPLE_BYTES: usize = 1024;
+
+#[derive(Debug, Serialize)]
+pub struct ProgramState {
+    pub address: String,
+    /// False when the RPC has no account at the address: never created,
+    /// closed, or on another cluster.
+    pub exists: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    pub lamports: u64,
+    pub executable: bool,
+    pub data_len: u64,
+    /// For programs of the upgradeable loader.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deployment: Option<Deployment>,
+    /// Fields the RPC decodes itself, for accounts of programs it knows
+    /// (SPL Token accounts and mints, stake, ...).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parsed: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sample: Option<DataSample>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Deployment {
+    pub program_data: String,
+    /// Slot of the last deploy or upgrade.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_deploy_slot: Option<u64>,
+    /// `None` once revoked.
+    pub upgrade_authority: Option<String>,
+    /// Who holds the authority, as in the posture findings: "frozen",
+    /// "single key", "multisig", "program-controlled" or "program-derived".
+    pub posture: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DataSample {
+    pub offset: usize,
+    pub hex: String,
+    /// First 8 bytes: the account discriminator of Anchor accounts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<String>,
+}
+
+/// State of the account at `address`, with `length` bytes of its raw data
+/// from `offset`.
+pub async fn fetch(
+    rpc: &RpcPool,
+    address: &str,
+    offset: usize,
+    length: Option<usize>,
+) -> Result<ProgramState> {
+    if !posture::is_base58_key(address) {
+        bail!("'{address}' is not a base58 account address");
+    }
+    let Some(account) = posture::get_account(rpc, address).await? else {
+        return Ok(ProgramState::missing(address));
+    };
+    let mut state = describe(address, &account, offset, length);
+    if let Some(program_data) = posture::program_data_address(&account) {
+        let data = posture::get_account(rpc, &program_data).await?;
+        let authority_owner = match data.as_ref().and_then(posture::upgrade_authority) {
+            Some(authority) => posture::get_account(rpc, &authority)
+                .await?
+                .and_then(|a| a["owner"].as_str().map(String::from)),
+            None => None,
+        };
+        state.deployment = Some(deployment(
+            program_data,
+            data.as_ref(),
+            authority_owner.as_deref(),
+        ));
+    }
+    Ok(state)
+}
+
+impl ProgramState {
+    fn missing(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            exists: false,
+            owner: None,
+            lamports: 0,
+            executable: false,
+            data_len: 0,
+            deployment: None,
+            parsed: None,
+            sample: None,
+        }
+    }
+}
+
+/// An account from `getAccountInfo` (`jsonParsed`), before any deployment
+/// lookup.
+fn describe(address: &str, account: &Value, offset: usize, length: Option<usize>) -> ProgramState {
+    let owner = account["owner"].as_str().map(String::from);
+    let data = &account["data"];
+    // Unparsed data comes back as `[<base64>, "base64"]`.
+    let bytes = data[0]
+        .as_str()
+        .and_then(|d| base64::engine::general_purpose::STANDARD.decode(d).ok());
+    // The loader's parsed ProgramData holds the whole program binary.
+    let parsed = (data.is_object() && owner.as_deref() != Some(posture::UPGRADEABLE_LOADER))
+        .then(|| data["parsed"].clone());
+    let sample = bytes.as_deref().filter(|b| !b.is_empty()).map(|b| {
+        let start = offset.min(b.len());
+        let len = length.unwrap_or(DEFAULT_SAMPLE_BYTES).min(MAX_SAMPLE_BYTES);
+        DataSample {
+            offset: start,
+            hex: hex(&b[start..(start + len).min(b.len())]),
+            discriminator: (b.len() >= 8).then(|| hex(&b[..8])),
+        }
+    });
+    ProgramState {
+        address: address.to_string(),
+        exists: true,
+        lamports: account["lamports"].as_u64().unwrap_or(0),
+        executable: account["executable"].as_bool().unwrap_or(false),
+        data_len: account["space"]
+            .as_u64()
+            .or_else(|| bytes.as_ref().map(|b| b.len() as u64))
+            .unwrap_or(0),
+        owner,
+        deployment: None,
+        parsed,
+        sample,
+    }
+}
+
+/// Deployment of an upgradeable program from its ProgramData account
+/// (`None` when the RPC has none) and the owner of its upgrade authority.
+fn deployment(
+    program_data: String,
+    account: Option<&Value>,
+    authority_owner: Option<&str>,
+) -> Deployment {
+    let upgrade_authority = account.and_then(posture::upgrade_authority);
+    let posture = match (account, &upgrade_authority) {
+        (None, _) => UpgradePosture::NotDeployed,
+        (Some(_), None) => UpgradePosture::Frozen,
+        (Some(_), Some(authority)) => {
+            posture::classify_authority(authority.clone(), authority_owner)
+        }
+    };
+    Deployment {
+        program_data,
+        last_deploy_slot: account.and_then(|a| a["data"]["parsed"]["info"]["slot"].as_u64()),
+        upgrade_authority,
+        posture: posture.label().into(),
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const PROGRAM_ID: &str = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+    const PROGRAM_DATA: &str = "5EpbKNB2jqyN1JmbRrCdpKkeyd6k8QoRSHVqjsXDfAXq";
+    const AUTHORITY: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    #[test]
+    fn describes_upgradeable_program() {
+        let program = json!({
+            "owner": posture::UPGRADEABLE_LOADER,
+            "lamports": 1_141_440,
+            "executable": true,
+            "space": 36,
+            "data": {"program": "bpf-upgradeable-loader", "parsed": {
+                "type": "program", "info": {"programData": PROGRAM_DATA}
+            }}
+        });
+        let program_data = json!({
+            "owner": posture::UPGRADEABLE_LOADER,
+            "data": {"parsed": {"type": "programData", "info": {
+                "authority": AUTHORITY, "slot": 287_654_321, "data": ["f0VMRg==", "base64"]
+            }}}
+        });
+        let state = describe(PROGRAM_ID, &program, 0, None);
+        assert!(state.exists && state.executable);
+        assert_eq!(state.data_len, 36);
+        assert!(state.parsed.is_none() && state.sample.is_none());
+
+        let deployed = deployment(PROGRAM_DATA.into(), Some(&program_data), None);
+        assert_eq!(deployed.upgrade_authority.as_deref(), Some(AUTHORITY));
+        assert_eq!(deployed.last_deploy_slot, Some(287_654_321));
+        assert_eq!(deployed.posture, "single key");
+
+        let revoked = json!({"data": {"parsed": {"info": {"authority": null, "slot": 1}}}});
+        assert_eq!(
+            deployment(PROGRAM_DATA.into(), Some(&revoked), None).posture,
+            "frozen"
+        );
+        assert_eq!(
+            deployment(PROGRAM_DATA.into(), None, None).posture,
+            "not deployed"
+        );
+    }
+
+    #[test]
+    fn samples_raw_account_data() {
+        let bytes: Vec<u8> = (0u8..40).collect();
+        let account = json!({
+            "owner": PROGRAM_ID,
+            "lamports": 2_039_280,
+            "executable": false,
+            "data": [base64::engine::general_purpose::STANDARD.encode(&bytes), "base64"]
+        });
+        let state = describe(AUTHORITY, &account, 8, Some(4));
+        assert_eq!(state.data_len, 40);
+        let sample = state.sample.unwrap();
+        assert_eq!(sample.offset, 8);
+        assert_eq!(sample.hex, "08090a0b");
+        assert_eq!(sample.discriminator.as_deref(), Some("0001020304050607"));
+
+        // Past the end: an empty sample at the end of the data.
+        let past = describe(AUTHORITY, &account, 100, None).sample.unwrap();
+        assert_eq!((past.offset, past.hex.as_str()), (40, ""));
+
+        let token = json!({
+            "owner": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "data": {"program": "spl-token", "parsed": {"type": "account", "info": {"mint": PROGRAM_ID}}}
+        });
+        let state = describe(AUTHORITY, &token, 0, None);
+        assert_eq!(state.parsed.unwrap()["info"]["mint"], PROGRAM_ID);
+        assert!(state.sample.is_none());
+    }
+}
@@ -0,0 +1,365 @@
+//! A repo's project configuration in one structured summary, for the review
+//! agent's `read_project_config` tool: each program crate with its
+//! framework, Anchor dependency, cargo features and the IDs it declares in
+//! `declare_id!` and in `Anchor.toml` per cluster, plus the toolchain and
+//! provider settings of `Anchor.toml`. Saves the agent reading and
+//! re-parsing the raw manifests turn after turn.
+
+use super::posture::{self, AnchorTomlEntry, DeclaredProgram};
+use super::workspace::{self, ProgramCrate};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+#[derive(Debug, Serialize)]
+pub struct ProjectConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor_toml: Option<AnchorToml>,
+    pub programs: Vec<ProgramConfig>,
+    /// `declare_id!`s outside every program crate.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub other_declared_ids: Vec<DeclaredId>,
+}
+
+/// Settings of `Anchor.toml` other than the program IDs, which are listed
+/// per program.
+#[derive(Debug, Default, Serialize)]
+pub struct AnchorToml {
+    /// `[toolchain] anchor_version`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor_version: Option<String>,
+    /// `[toolchain] solana_version`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub solana_version: Option<String>,
+    /// `[provider] cluster`: where `anchor deploy` and `anchor test` go.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_cluster: Option<String>,
+    /// The `[features]` table (`seeds`, `resolution`, `skip-lint`).
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub features: BTreeMap<String, toml::Value>,
+    /// Clusters with a `[programs.<cluster>]` table.
+    pub clusters: Vec<String>,
+    /// `[scripts] test`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub test_script: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProgramConfig {
+    pub name: String,
+    /// Crate directory relative to the repo root.
+    pub dir: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub framework: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor_lang: Option<Dependency>,
+    /// The crate's `[features]` table.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub features: BTreeMap<String, Vec<String>>,
+    pub declared_ids: Vec<DeclaredId>,
+    /// `Anchor.toml` program ID per cluster.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub anchor_toml_ids: BTreeMap<String, String>,
+    /// Clusters whose `Anchor.toml` ID matches none of `declared_ids`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mismatched_clusters: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Dependency {
+    /// Version requirement, `workspace` for `workspace = true`, or the git
+    /// URL or path it comes from.
+    pub version: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeclaredId {
+    pub id: String,
+    pub file: String,
+    pub line: usize,
+    /// `#[cfg(...)]` on the line above, for per-cluster IDs behind features.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cfg: Option<String>,
+}
+
+impl ProjectConfig {
+    /// Whether there was nothing to summarize.
+    pub fn is_empty(&self) -> bool {
+        self.anchor_toml.is_none() && self.programs.is_empty() && self.other_declared_ids.is_empty()
+    }
+}
+
+/// Summarize the project configuration of the repo at `repo_path`.
+pub fn read(repo_path: &Path) -> ProjectConfig {
+    let mut crates = workspace::programs(repo_path);
+    if crates.is_empty() {
+        crates.extend(workspace::root_program(repo_path));
+    }
+    let entries = posture::anchor_toml_entries(repo_path);
+    let mut declared = posture::source_declarations(repo_path);
+
+    let mut programs = Vec::new();
+    for program in &crates {
+        let (own, rest): (Vec<_>, Vec<_>) = declared.into_iter().partition(|d| {
+            workspace::program_of(&crates, &d.file_path).is_some_and(|p| p.dir == program.dir)
+        });
+        declared = rest;
+        programs.push(program_config(
+            repo_path,
+            program,
+            &own,
+            &entries,
+            crates.len(),
+        ));
+    }
+
+    ProjectConfig {
+        anchor_toml: anchor_toml(repo_path),
+        programs,
+        other_declared_ids: declared.iter().map(|d| declared_id(repo_path, d)).collect(),
+    }
+}
+
+fn program_config(
+    repo_path: &Path,
+    program: &ProgramCrate,
+    declared: &[DeclaredProgram],
+    entries: &[AnchorTomlEntry],
+    program_count: usize,
+) -> ProgramConfig {
+    let manifest = std::fs::read_to_string(program.dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|text| text.parse::<toml::Table>().ok())
+        .unwrap_or_default();
+    let snake = program.name.replace('-', "_");
+    // A lone program owns its cluster's only entry whatever it is called.
+    let anchor_toml_ids: BTreeMap<String, String> = entries
+        .iter()
+        .filter(|e| {
+            e.name == snake
+                || (program_count == 1
+                    && entries.iter().filter(|o| o.cluster == e.cluster).count() == 1)
+        })
+        .map(|e| (e.cluster.clone(), e.program.program_id.clone()))
+        .collect();
+    let mismatched_clusters = if declared.is_empty() {
+        Vec::new()
+    } else {
+        anchor_toml_ids
+            .iter()
+            .filter(|(_, id)| !declared.iter().any(|d| &d.program_id == *id))
+            .map(|(cluster, _)| cluster.clone())
+            .collect()
+    };
+    let dir = crate::paths::to_slash(program.dir.strip_prefix(repo_path).unwrap_or(&program.dir));
+    ProgramConfig {
+        name: program.name.clone(),
+        dir: if dir.is_empty() { ".".into() } else { dir },
+        framework: program.framework.map(|f| f.label()),
+        anchor_lang: manifest
+            .get("dependencies")
+            .and_then(|deps| deps.get("anchor-lang"))
+            .map(dependency),
+        features: features(&manifest),
+        declared_ids: declared.iter().map(|d| declared_id(repo_path, d)).collect(),
+        anchor_toml_ids,
+        mismatched_clusters,
+    }
+}
+
+fn anchor_toml(repo_path: &Path) -> Option<AnchorToml> {
+    let manifest: toml::Table = std::fs::read_to_string(repo_path.join("Anchor.toml"))
+        .ok()?
+        .parse()
+        .ok()?;
+    let string =
+        |section: &str, key: &str| manifest.get(section)?.get(key)?.as_str().map(String::from);
+    Some(AnchorToml {
+        anchor_version: string("toolchain", "anchor_version"),
+        solana_version: string("toolchain", "solana_version"),
+        provider_cluster: string("provider", "cluster"),
+        features: manifest
+            .get("features")
+            .and_then(|f| f.as_table())
+            .map(|t| t.clone().into_iter().collect())
+            .unwrap_or_default(),
+        clusters: manifest
+            .get("programs")
+            .and_then(|p| p.as_table())
+            .map(|t| t.keys().cloned().collect())
+            .unwrap_or_default(),
+        test_script: string("scripts", "test"),
+    })
+}
+
+/// A manifest's `[features]` table.
+pub(crate) fn features(manifest: &toml::Table) -> BTreeMap<String, Vec<String>> {
+    manifest
+        .get("features")
+        .and_then(|f| f.as_table())
+        .map(|table| {
+            table
+                .iter()
+                .map(|(name, enables)| (name.clone(), strings(enables)))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A dependency spec: `"0.30"`, or a table with `version`, `workspace`,
+/// `git` or `path`, and `features`.
+pub(crate) fn dependency(spec: &toml::Value) -> Dependency {
+    let version = match spec {
+        toml::Value::String(v) => v.clone(),
+        toml::Value::Table(t) => ["version", "git", "path"]
+            .iter()
+            .find_map(|key| t.get(*key)?.as_str().map(String::from))
+            .or_else(|| {
+                t.get("workspace")
+                    .and_then(|w| w.as_bool())
+                    .filter(|w| *w)
+                    .map(|_| "workspace".to_string())
+            })
+            .unwrap_or_else(|| "*".into()),
+        _ => "*".into(),
+    };
+    Dependency {
+        version,
+        features: spec.get("features").map(strings).unwrap_or_default(),
+    }
+}
+
+fn strings(value: &toml::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn declared_id(repo_path: &Path, d: &DeclaredProgram) -> DeclaredId {
+    let cfg = std::fs::read_to_string(&d.file_path)
+        .ok()
+        .and_then(|content| {
+            let above = content.lines().nth(d.line_number.checked_sub(2)?)?.trim();
+            above.starts_with("#[cfg(").then(|| above.to_string())
+        });
+    DeclaredId {
+        id: d.program_id.clone(),
+        file: crate::paths::to_slash(d.file_path.strip_prefix(repo_path).unwrap_or(&d.file_path)),
+        line: d.line_number,
+        cfg,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAINNET_ID: &str = "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS";
+    const DEVNET_ID: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+
+    #[test]
+    fn summarizes_programs_ids_and_toolchain() {
+        let root = std::env::temp_dir().join(format!("solguard-project-{}", std::process::id()));
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "Anchor.toml",
+            &format!(
+                "[toolchain]\nanchor_version = \"0.30.1\"\n\n[features]\nseeds = true\n\n\
+                 [programs.localnet]\nmy_vault = \"{MAINNET_ID}\"\n\n\
+                 [programs.devnet]\nmy_vault = \"{MAINNET_ID}\"\n\n\
+                 [programs.mainnet]\nmy_vault = \"{DEVNET_ID}\"\n\n\
+                 [provider]\ncluster = \"devnet\"\nwallet = \"~/.config/solana/id.json\"\n\n\
+                 [scripts]\ntest = \"yarn run ts-mocha tests/**/*.ts\"\n"
+            ),
+        );
+        write(
+            "programs/my-vault/Cargo.toml",
+            "[package]\nname = \"my-vault\"\n\n[features]\ndefault = []\n\
+             devnet = []\nno-entrypoint = []\n\n[dependencies]\n\
+             anchor-lang = { version = \"0.30.1\", features = [\"init-if-needed\"] }\n",
+        );
+        write(
+            "programs/my-vault/src/lib.rs",
+            &format!(
+                "use anchor_lang::prelude::*;\n\n#[cfg(feature = \"devnet\")]\n\
+                 declare_id!(\"{DEVNET_ID}\");\ndeclare_id!(\"{MAINNET_ID}\");\n"
+            ),
+        );
+        write(
+            "common/src/ids.rs",
+            "declare_id!(\"11111111111111111111111111111111\");\n",
+        );
+
+        let config = read(&root);
+        let anchor = config.anchor_toml.as_ref().unwrap();
+        assert_eq!(anchor.anchor_version.as_deref(), Some("0.30.1"));
+        assert_eq!(anchor.provider_cluster.as_deref(), Some("devnet"));
+        assert_eq!(anchor.clusters, vec!["devnet", "localnet", "mainnet"]);
+        assert_eq!(anchor.features["seeds"], toml::Value::Boolean(true));
+
+        let [vault] = config.programs.as_slice() else {
+            panic!("expected one program: {config:#?}");
+        };
+        assert_eq!(vault.dir, "programs/my-vault");
+        assert_eq!(vault.framework, Some("anchor"));
+        let anchor_lang = vault.anchor_lang.as_ref().unwrap();
+        assert_eq!(anchor_lang.version, "0.30.1");
+        assert_eq!(anchor_lang.features, vec!["init-if-needed"]);
+        assert!(vault.features.contains_key("no-entrypoint"));
+        assert_eq!(vault.declared_ids.len(), 2);
+        assert_eq!(
+            vault.declared_ids[0].cfg.as_deref(),
+            Some("#[cfg(feature = \"devnet\")]")
+        );
+        assert!(vault.declared_ids[1].cfg.is_none());
+        assert_eq!(vault.anchor_toml_ids["mainnet"], DEVNET_ID);
+        // Both IDs are declared somewhere, so no cluster is out of sync.
+        assert!(vault.mismatched_clusters.is_empty());
+
+        assert_eq!(config.other_declared_ids.len(), 1);
+        assert_eq!(config.other_declared_ids[0].file, "common/src/ids.rs");
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn flags_clusters_out_of_sync_with_declare_id() {
+        let root =
+            std::env::temp_dir().join(format!("solguard-project-root-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"escrow\"\n[lib]\ncrate-type = [\"cdylib\", \"lib\"]\n\
+             [dependencies]\nanchor-lang = { workspace = true }\n",
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("Anchor.toml"),
+            format!("[programs.localnet]\nsome_other_name = \"{DEVNET_ID}\"\n"),
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src/lib.rs"),
+            format!("declare_id!(\"{MAINNET_ID}\");\n"),
+        )
+        .unwrap();
+
+        let config = read(&root);
+        let escrow = &config.programs[0];
+        assert_eq!(escrow.dir, ".");
+        assert_eq!(escrow.anchor_lang.as_ref().unwrap().version, "workspace");
+        assert_eq!(escrow.mismatched_clusters, vec!["localnet"]);
+        assert!(!config.is_empty());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -1,7 +1,11 @@
+use super::framework::Framework;
 use super::{Finding, Severity};
+use crate::config::ScanConfig;
 use fancy_regex::RegexBuilder;
 use std::path::Path;
 use std::sync::LazyLock;
+use syn::spanned::Spanned;
+use syn::visit::Visit;
 
 struct Pattern {
     id: &'static str,
@@ -16,25 +20,57 @@ struct Pattern {
     line_span: usize,
     /// Per-pattern confidence score. Reflects historical false-positive rate.
     confidence: f64,
-    /// If this regex matches in the ±3-line context window, suppress the finding.
+    /// If this regex matches in the suppress context, suppress the finding.
     /// Used to eliminate known false-positive cases without breaking the base pattern.
     suppress_if: Option<&'static str>,
+    /// Lines either side of the match checked for `suppress_if`; overridable
+    /// per pattern with `[scan.suppress_window]`.
+    suppress_window: usize,
+    suppress_scope: SuppressScope,
+    /// If this regex matches anywhere in the file, the pattern doesn't apply.
+    /// For risks whose mitigation usually lives in another handler (a revoke
+    /// after an approve).
+    suppress_in_file: Option<&'static str>,
+    /// Frameworks whose code the pattern applies to.
+    frameworks: &'static [Framework],
 }
 
+/// Where `suppress_if` looks besides the line window.
+#[derive(Clone, Copy, PartialEq)]
+enum SuppressScope {
+    /// Only `suppress_window` lines either side of the match.
+    Lines,
+    /// Also, for a match on a struct field, the field's attributes and doc
+    /// comments and the struct's attributes, however far above the field
+    /// they start. Long Anchor constraint blocks push `#[account(...)]` out
+    /// of any fixed window.
+    Field,
+}
+
+/// Pattern profiles. Anchor-only patterns look for constructs (account
+/// constraints, `init_if_needed`, `space =`) that Pinocchio and native
+/// programs don't have; manual ones for checks Anchor does for you.
+const ANY: &[Framework] = &[Framework::Anchor, Framework::Pinocchio, Framework::Native];
+const ANCHOR: &[Framework] = &[Framework::Anchor];
+const MANUAL: &[Framework] = &[Framework::Pinocchio, Framework::Native];
+
 static PATTERNS: &[Pattern] = &[
     Pattern {
-        id: "SOL-001",
-        title: "Missing Signer Constraint",
-        description: "Account used in privileged operation without #[account(signer)] or Signer<> type. \
-                      An attacker could call this instruction with any account, bypassing authorization.",
+        id: "SOL-002",
+        title: "Missing Owner Check",
+        description: "Account data deserialized straight from an AccountInfo with no owner check nearby. \
+                      An attacker can create an account owned by another program with the same layout and pass it in.",
         severity: Severity::High,
-        regex: r"(?m)^[^/]*pub\s+(\w+)\s*:\s*(?:Account|AccountInfo|UncheckedAccount)(?!.*(?:has_one|constraint|signer))",
-        remediation: "Add `Signer<'info>` type or `#[account(signer)]` constraint to enforce authorization.",
-        references: &["https://www.soldev.app/course/signer-auth"],
+        regex: r"(?:try_from_slice|try_deserialize(?:_unchecked)?|deserialize|unpack(?:_unchecked|_from_slice)?)\s*\(\s*(?:&\s*)*(?:mut\s+)?(?:\*\s*)?\w+\.(?:data\.borrow(?:_mut)?\(\)|data\.try_borrow\(\)|try_borrow_(?:mut_)?data\(\))",
+        remediation: "Check `account.owner == program_id` before deserializing, or take the account as `Account<'info, T>`, which verifies the owner.",
+        references: &["https://www.soldev.app/course/owner-checks"],
         line_span: 1,
-        confidence: 0.65,
-        // Suppress when Anchor account attributes or CHECK doc comments appear nearby.
-        suppress_if: Some(r"#\[account|has_one\s*=|///\s*CHECK:|Signer\s*<"),
+        confidence: 0.6,
+        suppress_if: Some(r"\.owner\b|owner\s*[!=]=|is_owned_by|owned_by\s*\("),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
     },
     Pattern {
         id: "SOL-003",
@@ -50,6 +86,10 @@ static PATTERNS: &[Pattern] = &[
         // Filtered by MIN_CONFIDENCE in the pipeline; agent catches real cases in context.
         confidence: 0.45,
         suppress_if: None,
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
     },
     Pattern {
         id: "SOL-004",
@@ -63,6 +103,10 @@ static PATTERNS: &[Pattern] = &[
         line_span: 1,
         confidence: 0.75,
         suppress_if: None,
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANCHOR,
     },
     Pattern {
         id: "SOL-005",
@@ -78,6 +122,10 @@ static PATTERNS: &[Pattern] = &[
         // requires dataflow analysis. Filtered by MIN_CONFIDENCE; agent investigates.
         confidence: 0.45,
         suppress_if: None,
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
     },
     Pattern {
         id: "SOL-006",
@@ -94,6 +142,10 @@ static PATTERNS: &[Pattern] = &[
         suppress_if: Some(
             r"fill\s*\(\s*0\s*\)|borrow_mut\s*\(\s*\)\s*\.\s*fill|assign\s*\(|realloc\s*\(|#\[account[^\]]*close",
         ),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
     },
     Pattern {
         id: "SOL-007",
@@ -110,6 +162,10 @@ static PATTERNS: &[Pattern] = &[
         suppress_if: Some(
             r"TOKEN_PROGRAM_ID|spl_token::id\(\)|spl_associated_token_account::id\(\)|system_program::id\(\)|System(?:Program)?::id\(\)|::ID\b",
         ),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
     },
     Pattern {
         id: "SOL-008",
@@ -124,6 +180,10 @@ static PATTERNS: &[Pattern] = &[
         confidence: 0.62,
         // Suppress when Anchor's Account<> wrapper is used nearby — it handles discriminators automatically.
         suppress_if: Some(r"Account\s*<'info\s*,|AccountLoader\s*<'info"),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
     },
     Pattern {
         id: "SOL-009",
@@ -137,6 +197,10 @@ static PATTERNS: &[Pattern] = &[
         line_span: 1,
         confidence: 0.68,
         suppress_if: None,
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
     },
     Pattern {
         id: "SOL-010",
@@ -150,10 +214,486 @@ static PATTERNS: &[Pattern] = &[
         line_span: 1,
         confidence: 0.78,
         suppress_if: None,
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-011",
+        title: "Account Space Missing Discriminator",
+        description: "Account space computed from the struct size without the 8-byte Anchor discriminator. \
+                      The account is allocated too small: serialization fails once the last field is written, \
+                      or trailing data is silently truncated.",
+        severity: Severity::Medium,
+        regex: r"space\s*=\s*(?![^,\)\]\n]*(?:\b8\s*\+|\+\s*8\b|DISCRIMINATOR))[^,\)\]\n]*(?:size_of|INIT_SPACE|::LEN\b|::SIZE\b)",
+        remediation: "Allocate `8 + T::INIT_SPACE` (or `8 + size_of::<T>()`), or include the discriminator in the `LEN` constant and document it.",
+        references: &["https://www.anchor-lang.com/docs/space"],
+        line_span: 1,
+        // Hand-written LEN constants often already include the discriminator.
+        confidence: 0.6,
+        suppress_if: Some(r"DISCRIMINATOR|const\s+LEN\s*:\s*usize\s*=\s*8\s*\+"),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANCHOR,
+    },
+    Pattern {
+        id: "SOL-012",
+        title: "Realloc Without Rent Top-Up",
+        description: "Account grown with AccountInfo::realloc/resize without transferring lamports to cover the new size. \
+                      The account drops below rent exemption and the transaction fails, or the program pays from the wrong account.",
+        severity: Severity::Medium,
+        regex: r"\.\s*(?:realloc|resize)\s*\(",
+        remediation: "Compute `Rent::get()?.minimum_balance(new_len)` and transfer the shortfall before resizing, \
+                      or use Anchor's `realloc`/`realloc::payer` constraint.",
+        references: &[
+            "https://docs.rs/solana-program/latest/solana_program/account_info/struct.AccountInfo.html#method.realloc",
+        ],
+        line_span: 1,
+        confidence: 0.6,
+        // Suppress when rent is computed or lamports are moved around the resize.
+        suppress_if: Some(
+            r"minimum_balance|Rent::|rent\.|lamports|system_instruction::transfer|realloc::payer",
+        ),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-013",
+        title: "Fixed Buffer Written With Unchecked Length",
+        description: "Slice of a fixed-size buffer sized by a caller-supplied length and then copied into. \
+                      A length larger than the buffer panics the program (DoS); with raw pointer copies it corrupts memory.",
+        severity: Severity::High,
+        regex: r"\[\s*(?:\w+\s*)?\.\.\s*[\w.]*len(?:\(\))?\s*\]\s*\.\s*copy_from_slice|copy_nonoverlapping\s*\([^;]*len",
+        remediation: "Check the length against the buffer size (`require!(data.len() <= MAX_LEN, ...)`) before copying, \
+                      and avoid raw pointer copies for account data.",
+        references: &["CWE-120"],
+        line_span: 1,
+        confidence: 0.65,
+        // Suppress when the length is bounded nearby.
+        suppress_if: Some(r"(?:require|assert)!\s*\([^;]*len|len\(\)\s*(?:<=|<|>|>=)|\.min\s*\("),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-014",
+        title: "Token Approval Without Revoke",
+        description: "Token account delegate approved via CPI, but nothing in this file revokes it. \
+                      The delegate keeps its spending authority after the operation that needed it, \
+                      so a later bug in (or compromise of) the delegate can drain the account.",
+        severity: Severity::Medium,
+        regex: r"(?<![\w.])(?<!fn )(?:(?:token(?:_interface|_2022)?|spl_token(?:_2022)?::instruction)::)?approve(?:_checked)?\s*\(",
+        remediation: "Call `token::revoke` once the delegated operation completes, \
+                      or approve exactly the amount that operation consumes.",
+        references: &["https://spl.solana.com/token#authority-delegation"],
+        line_span: 1,
+        // The revoke may legitimately live in another file (a shared helper).
+        confidence: 0.55,
+        suppress_if: None,
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: Some(r"\brevoke(?:_checked)?\s*\(|\bRevoke\s*\{"),
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-015",
+        title: "Unbounded Token Delegation",
+        description: "Delegate approved for `u64::MAX` instead of the amount the operation needs. \
+                      The delegate can move the account's entire balance, including deposits made after the approval.",
+        severity: Severity::High,
+        // Anchored to the first line of the window so only the approve line reports.
+        regex: r"^[^\n]*approve(?:_checked)?\s*\([^;]*u64::MAX",
+        remediation: "Approve the exact amount being transferred and revoke afterwards.",
+        references: &["https://spl.solana.com/token#authority-delegation"],
+        line_span: 4,
+        confidence: 0.7,
+        suppress_if: None,
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-016",
+        title: "Approval To Unconstrained Delegate",
+        description: "Delegate account taken as an unconstrained AccountInfo/UncheckedAccount. \
+                      A caller can pass their own address and have the program approve them to spend its tokens.",
+        severity: Severity::High,
+        regex: r"^\s*pub\s+\w*delegate\w*\s*:\s*(?:UncheckedAccount|AccountInfo)\b",
+        remediation: "Derive the delegate as a PDA (`seeds = [...]`) or pin it with `address =`, `has_one` or a `constraint`.",
+        references: &["https://spl.solana.com/token#authority-delegation"],
+        line_span: 1,
+        confidence: 0.6,
+        // Constraint attributes sit just above the field.
+        suppress_if: Some(r"seeds\s*=|address\s*=|has_one|constraint\s*="),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Field,
+        suppress_in_file: None,
+        frameworks: ANCHOR,
+    },
+    Pattern {
+        id: "SOL-017",
+        title: "Reinitialization via init_if_needed",
+        description: "Account created with `init_if_needed` and no initialized flag checked anywhere in the file. \
+                      Calling the instruction again on an existing account resets its state (owner, balances, authority).",
+        severity: Severity::Medium,
+        regex: r"\binit_if_needed\b",
+        remediation: "Use `init`, or store an `is_initialized` flag (or compare a set field against its default) and reject the call when it is already set.",
+        references: &["https://www.soldev.app/course/reinitialization-attacks"],
+        line_span: 1,
+        confidence: 0.6,
+        suppress_if: None,
+        // The guard lives in the handler, often far from the accounts struct.
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: Some(r"is_initialized|\binitialized\s*[=:]|[!=]=\s*Pubkey::default\(\)"),
+        frameworks: ANCHOR,
+    },
+    Pattern {
+        id: "SOL-018",
+        title: "Sysvar Address Not Verified",
+        description: "Sysvar read from an account whose address is never checked. \
+                      A caller can pass a fake account with forged clock, rent or instruction data (the Wormhole exploit).",
+        severity: Severity::High,
+        regex: r"\bload_(?:instruction_at|current_index)\s*\(|^\s*pub\s+(?:\w+_)?(?:clock|rent|instructions?|ix_sysvar|slot_hashes|stake_history|epoch_schedule|sysvar\w*)\s*:\s*(?:AccountInfo|UncheckedAccount)\b",
+        remediation: "Use the `_checked` instruction-sysvar helpers, `Sysvar<'info, T>`, or pin the account with `#[account(address = sysvar::<name>::ID)]`.",
+        references: &["CWE-345"],
+        line_span: 1,
+        confidence: 0.65,
+        suppress_if: Some(r"address\s*=|check_id\s*\(|sysvar::\w+::(?:ID|id\(\))"),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Field,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-019",
+        title: "Positional Account Access",
+        description: "Account taken from the instruction's account list by position with no key check nearby. \
+                      The handler assumes the caller ordered the accounts as expected; a reordered or substituted list goes unnoticed.",
+        severity: Severity::Medium,
+        regex: r"(?:(?<!remaining_)\baccounts|to_account_infos\(\))\s*\[\s*\d+\s*\]",
+        remediation: "Verify each positional account's key (or owner and type) before use, or declare the accounts in an Anchor `Accounts` struct.",
+        references: &["https://www.soldev.app/course/account-data-matching"],
+        line_span: 1,
+        confidence: 0.55,
+        suppress_if: Some(
+            r"key\(\)\s*[!=]=|\.key\s*[!=]=|require_keys_n?eq|\.owner\b|check_id\s*\(",
+        ),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-020",
+        title: "Unchecked Zero-Copy Cast",
+        description: "Account data reinterpreted in place as a struct (`from_bytes_mut`, `load_mut_unchecked`) \
+                      with no owner, length or discriminator check nearby. Any account with enough bytes \
+                      passes as that type, so a caller can hand in one they control.",
+        severity: Severity::High,
+        regex: r"\b(?:from_bytes_mut|from_bytes_unchecked|load_mut_unchecked|load_unchecked)\s*(?:::<[^>]*>\s*)?\(",
+        remediation: "Check the account's owner against the program ID, its data length against the struct size \
+                      and its discriminator before casting, or use a checked loader that does all three.",
+        references: &["https://www.soldev.app/course/owner-checks"],
+        line_span: 1,
+        confidence: 0.65,
+        suppress_if: Some(
+            r"\.owner\(\)|owner\s*[!=]=|is_owned_by|owned_by\s*\(|DISCRIMINATOR|data_len\(\)\s*[!=<>]|len\(\)\s*[!=]=",
+        ),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: MANUAL,
+    },
+    Pattern {
+        id: "SOL-021",
+        title: "Accounts Taken From Slice Unverified",
+        description: "Accounts pulled off the instruction's account slice by hand (`next_account_info`, \
+                      slice destructuring) with no key, owner or signer check nearby. Without an Anchor \
+                      `Accounts` struct nothing validates them, so any account can sit at that position.",
+        severity: Severity::Medium,
+        regex: r"next_account_info\s*\(|^\s*let\s*\[[^\]]*\]\s*=\s*accounts\b",
+        remediation: "Check each account's key (or PDA derivation), owner and signer status right after taking it.",
+        references: &["https://www.soldev.app/course/account-data-matching"],
+        line_span: 1,
+        confidence: 0.55,
+        suppress_if: Some(
+            r"key\(\)\s*[!=]=|\.key\s*[!=]=|require_keys_n?eq|\.owner\b|is_owned_by|is_signer|check_id\s*\(|(?:find|create)_program_address",
+        ),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: MANUAL,
+    },
+    Pattern {
+        id: "SOL-022",
+        title: "Missing Manual Signer Check",
+        description: "Authority account taken from the account slice, but nothing in this file checks `is_signer`. \
+                      Pinocchio and native programs don't check signers for you: anyone can pass the \
+                      authority's address unsigned and act as it.",
+        severity: Severity::High,
+        regex: r"^\s*let\s+(?:\w+_)?(?:authority|owner|admin|signer)\w*\s*=\s*next_account_info|^\s*let\s*\[[^\]]*\b(?:\w+_)?(?:authority|owner|admin|signer)\b[^\]]*\]\s*=\s*accounts\b",
+        remediation: "Reject the instruction unless `authority.is_signer()` before acting on the authority's behalf.",
+        references: &["https://www.soldev.app/course/signer-auth"],
+        line_span: 1,
+        confidence: 0.7,
+        suppress_if: None,
+        // The check may sit in a shared validation helper.
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: Some(r"\bis_signer\b"),
+        frameworks: MANUAL,
+    },
+    Pattern {
+        id: "SOL-023",
+        title: "Hardcoded Keypair",
+        description: "A private key is embedded in source: a keypair built from a byte or base58 literal, a \
+                      64-byte key array, or a 64-byte base58 string. Anyone with the repo holds that key, \
+                      and devnet keys tend to end up as mainnet upgrade or admin authorities.",
+        severity: Severity::Critical,
+        regex: r#"Keypair::from_(?:bytes\s*\(\s*&\s*\[\s*(?:\d|$)|base58_string\s*\(\s*")|\b(?:const|static|let)\s+\w+\s*:\s*(?:&\s*)?\[u8\s*;\s*64\s*\]\s*=\s*\[\s*(?:\d|$)|"[1-9A-HJ-NP-Za-km-z]{86,88}""#,
+        remediation: "Remove the key from the source and rotate it: any authority it holds must be assumed \
+                      compromised. Load keypairs at runtime from a file outside the repo or a secrets manager.",
+        references: &["CWE-798"],
+        line_span: 1,
+        confidence: 0.8,
+        // 64-byte base58 strings are also transaction signatures.
+        suppress_if: Some(r"(?i)Signature::from_str|\bsignature\b|\bsig\b"),
+        suppress_window: 1,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-024",
+        title: "Unbounded Loop Over remaining_accounts",
+        description: "Loop over every account in remaining_accounts with no cap on how many are passed. \
+                      Each iteration costs compute, so a caller can pad the list until the instruction \
+                      exceeds the compute budget, or until a crank that must process the list can't.",
+        severity: Severity::Medium,
+        regex: r"for\s+\S+\s+in\s+(?:&\s*)?(?:ctx\s*\.\s*)?remaining_accounts\b|remaining_accounts\s*\.\s*(?:iter|into_iter|chunks(?:_exact)?)\s*\(",
+        remediation: "Reject more than a fixed number of accounts (`require!(ctx.remaining_accounts.len() <= MAX_ACCOUNTS)`) \
+                      before the loop, or `.take(MAX_ACCOUNTS)` and process the rest in a later call.",
+        references: &["https://solana.com/docs/core/fees#compute-budget"],
+        line_span: 1,
+        confidence: 0.55,
+        suppress_if: Some(
+            r"remaining_accounts\s*\.\s*len\(\)\s*(?:<|<=|==|>|>=)|\.take\s*\(|\bMAX_\w+",
+        ),
+        // The cap usually sits at the top of the handler.
+        suppress_window: 6,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANCHOR,
+    },
+    Pattern {
+        id: "SOL-025",
+        title: "Unbounded Vec Growth in Account Data",
+        description: "Pushes onto a Vec stored in account data with no length check. Once the list reaches \
+                      the account's allocated space every push fails, and anyone who can append (or \
+                      spam the instruction) can fill it and lock the instruction for everyone.",
+        severity: Severity::Medium,
+        regex: r"ctx\s*\.\s*accounts\s*\.\s*\w+\s*\.\s*\w+\s*\.\s*push\s*\(|^\s*(?:\w+_)?(?:state|account|pool|vault|config|registry|queue|book|market|list)\s*\.\s*\w+\s*\.\s*push\s*\(",
+        remediation: "Bound the list: check `len() < MAX_ITEMS` before pushing and size the account for \
+                      `MAX_ITEMS` (`#[max_len]`), or store entries in their own PDAs.",
+        references: &["https://solana.com/docs/core/accounts"],
+        line_span: 1,
+        confidence: 0.55,
+        suppress_if: Some(
+            r"\.len\(\)\s*(?:<|<=|>=|>|==)|\bMAX_\w+|\bmax_len\b|\bcapacity\(\)|\bis_full\b",
+        ),
+        suppress_window: 5,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-026",
+        title: "Self-Invoking CPI",
+        description: "Cross-program invocation whose target is this program. CPI depth is capped at 4, so \
+                      a call that re-enters the program (possibly recursively) can be driven past the \
+                      cap by nesting it in outer CPIs, making the instruction fail for those callers, \
+                      and re-entry exposes state mid-update.",
+        severity: Severity::Medium,
+        regex: r"^\s*program_id\s*:\s*(?:crate::ID|crate::id\(\)|\*?program_id|ID)\s*,|Instruction::new_with_(?:bytes|borsh|bincode)\s*\(\s*(?:crate::ID|crate::id\(\)|\*?program_id|ID)\s*,",
+        remediation: "Call the logic directly instead of through a CPI to this program; if re-entry is needed, \
+                      track depth explicitly and reject re-entrant calls while state is mid-update.",
+        references: &["https://solana.com/docs/core/cpi"],
+        line_span: 1,
+        confidence: 0.55,
+        suppress_if: None,
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-027",
+        title: "Token Account Mint Not Constrained",
+        description: "Token account taken with no constraint on its mint. `Account<'info, TokenAccount>` only checks \
+                      that the SPL Token program owns it, so a caller can pass an account for a worthless mint \
+                      they control and have it credited or debited as if it held the expected token.",
+        severity: Severity::High,
+        regex: r"^\s*pub\s+\w+\s*:\s*(?:Box\s*<\s*)?(?:Interface)?Account\s*<\s*'info\s*,\s*(?:\w+::)*TokenAccount\b",
+        remediation: "Add `token::mint = <mint>` (or `associated_token::mint`) to the account's constraints, \
+                      or `constraint = account.mint == expected_mint.key()`.",
+        references: &["https://www.soldev.app/course/account-data-matching"],
+        line_span: 1,
+        confidence: 0.55,
+        suppress_if: Some(
+            r"(?:associated_)?token::mint\s*=|\.mint\s*==|==\s*[\w.]+\.mint\b|has_one\s*=\s*mint\b|address\s*=",
+        ),
+        // Constraints belong to this field; neighbours' `token::mint` says nothing about it.
+        suppress_window: 0,
+        suppress_scope: SuppressScope::Field,
+        suppress_in_file: None,
+        frameworks: ANCHOR,
+    },
+    Pattern {
+        id: "SOL-028",
+        title: "Vault Token Account Not Bound to Program",
+        description: "Vault, treasury or escrow token account with no constraint on who owns it. A caller can \
+                      substitute a token account of their own as the vault, so deposits land in their account \
+                      and withdrawals are checked against balances they control.",
+        severity: Severity::High,
+        regex: r"^\s*pub\s+\w*(?:vault|treasury|escrow|reserve|fee_(?:receiver|account|destination))\w*\s*:\s*(?:Box\s*<\s*)?(?:Interface)?Account\s*<\s*'info\s*,\s*(?:\w+::)*TokenAccount\b",
+        remediation: "Derive the vault as a PDA (`seeds = [...]`), pin its owner with `token::authority = <pda>`, \
+                      or pin the address with `address =` / `has_one` on the state that records it.",
+        references: &["https://www.soldev.app/course/account-data-matching"],
+        line_span: 1,
+        confidence: 0.55,
+        suppress_if: Some(
+            r"(?:associated_)?token::authority\s*=|seeds\s*=|address\s*=|\.owner\s*==|==\s*[\w.]+\.owner\b|has_one\s*=\s*\w*(?:vault|treasury|escrow|reserve|fee_\w+)\b",
+        ),
+        // A `has_one = vault` on the state account usually sits a field or two above.
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Field,
+        suppress_in_file: None,
+        frameworks: ANCHOR,
+    },
+    Pattern {
+        id: "SOL-029",
+        title: "Associated Token Account Not Derived",
+        description: "Account treated as an associated token account with no check that it is the canonical ATA \
+                      for its wallet and mint. A caller can pass any token account with the right owner, \
+                      including one with a delegate or close authority they set beforehand.",
+        severity: Severity::Medium,
+        regex: r"^\s*pub\s+(?:\w+_)?(?:ata|associated_token_account)\s*:\s*(?:Box\s*<\s*)?(?:Interface)?Account\s*<\s*'info\s*,\s*(?:\w+::)*TokenAccount\b|\blet\s+(?:\w+_)?ata(?:_info)?\s*=\s*next_account_info\s*\(",
+        remediation: "Use `associated_token::mint` and `associated_token::authority` constraints, or compare the key \
+                      against `get_associated_token_address(&wallet, &mint)` before use.",
+        references: &["https://spl.solana.com/associated-token-account"],
+        line_span: 1,
+        confidence: 0.55,
+        suppress_if: Some(r"associated_token::|get_associated_token_address|seeds\s*=|address\s*="),
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Field,
+        // Native programs derive the expected ATA once and compare later in the handler.
+        suppress_in_file: Some(r"get_associated_token_address"),
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-030",
+        title: "Oracle Price Read Without Staleness Check",
+        description: "Pyth or Switchboard price read with no check on when it was published. A feed that \
+                      stopped updating keeps returning its last price, so during an outage or a fast move \
+                      borrows, liquidations and swaps are priced off a value the market has left behind.",
+        severity: Severity::High,
+        regex: r"\.(?:get_price_unchecked|get_ema_price_unchecked|get_current_price|get_current_ema_price)\s*\(|\.get_result\s*\(\s*\)",
+        remediation: "Use `get_price_no_older_than(&clock, MAX_AGE, &feed_id)` for Pyth, or call \
+                      `check_staleness(clock.unix_timestamp, MAX_AGE)` on a Switchboard aggregator before \
+                      reading its result.",
+        references: &[
+            "https://docs.pyth.network/price-feeds/best-practices#price-availability",
+            "https://docs.switchboard.xyz/",
+        ],
+        line_span: 1,
+        confidence: 0.6,
+        suppress_if: Some(
+            r"publish_time|no_older_than|check_staleness|max_age|round_open_timestamp|staleness",
+        ),
+        suppress_window: 6,
+        suppress_scope: SuppressScope::Lines,
+        suppress_in_file: None,
+        frameworks: ANY,
+    },
+    Pattern {
+        id: "SOL-031",
+        title: "Oracle Confidence Interval Ignored",
+        description: "Oracle price used without looking at its confidence interval. When publishers disagree \
+                      the interval widens and the aggregate can sit far from any tradable price; a program \
+                      that takes it at face value lends against, or liquidates at, a price nobody quoted.",
+        severity: Severity::Medium,
+        regex: r"\.(?:get_price_no_older_than|get_ema_price_no_older_than|get_price_unchecked|get_ema_price_unchecked|get_current_price|get_current_ema_price)\s*\(|\.get_result\s*\(\s*\)",
+        remediation: "Reject prices whose `conf` is too wide relative to `price` (e.g. `conf * 10_000 / price > \
+                      MAX_CONF_BPS`), or call `check_confidence_interval` on a Switchboard aggregator; value \
+                      collateral at `price - conf` and debt at `price + conf`.",
+        references: &["https://docs.pyth.network/price-feeds/best-practices#confidence-intervals"],
+        line_span: 1,
+        confidence: 0.55,
+        suppress_if: None,
+        suppress_window: 3,
+        suppress_scope: SuppressScope::Lines,
+        // The check often lives in a shared helper away from the read.
+        suppress_in_file: Some(r"\.conf\b|check_confidence_interval|std_deviation|confidence"),
+        frameworks: ANY,
     },
 ];
 
+/// IDs of every regex pattern, in declaration order.
+pub fn pattern_ids() -> impl Iterator<Item = &'static str> {
+    PATTERNS.iter().map(|p| p.id)
+}
+
+/// Every pattern definition, serialized for the scan cache's key: editing a
+/// regex, window or confidence invalidates cached results.
+pub fn definitions() -> String {
+    PATTERNS
+        .iter()
+        .map(|p| {
+            let frameworks: Vec<&str> = p.frameworks.iter().map(|f| f.label()).collect();
+            format!(
+                "{}\0{}\0{}\0{}\0{:?}\0{:?}\0{}\0{}\0{}\0{}\0{}",
+                p.id,
+                p.severity,
+                p.regex,
+                p.confidence,
+                p.suppress_if,
+                p.suppress_in_file,
+                p.suppress_window,
+                p.suppress_scope == SuppressScope::Field,
+                p.line_span,
+                p.title,
+                frameworks.join(","),
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Scan with the profile of the framework `content` names; files that name
+/// none get every pattern.
 pub fn scan(content: &str, file_path: &Path) -> Vec<Finding> {
+    scan_as(
+        content,
+        file_path,
+        Framework::of_source(content),
+        &ScanConfig::default(),
+    )
+}
+
+/// Scan with the patterns for `framework`, or every pattern for `None`.
+pub fn scan_as(
+    content: &str,
+    file_path: &Path,
+    framework: Option<Framework>,
+    config: &ScanConfig,
+) -> Vec<Finding> {
     static COMPILED: LazyLock<Vec<(fancy_regex::Regex, usize)>> = LazyLock::new(|| {
         PATTERNS
             .iter()
@@ -178,13 +718,114 @@ pub fn scan(content: &str, file_path: &Path) -> Vec<Finding> {
             .collect()
     });
 
+    static SUPPRESS_FILE_RE: LazyLock<Vec<Option<fancy_regex::Regex>>> = LazyLock::new(|| {
+        PATTERNS
+            .iter()
+            .map(|p| {
+                p.suppress_in_file
+                    .and_then(|s| RegexBuilder::new(s).backtrack_limit(10_000).build().ok())
+            })
+            .collect()
+    });
+
     let lines: Vec<&str> = content.lines().collect();
     let mut findings = Vec::new();
+    // Parsed on first use by a field-scoped pattern.
+    let mut fields: Option<Vec<FieldContext>> = None;
 
     for (regex, pattern_idx) in COMPILED.iter() {
         let pattern = &PATTERNS[*pattern_idx];
-        let span = pattern.line_span;
+        if framework.is_some_and(|f| !pattern.frameworks.contains(&f)) {
+            continue;
+        }
 
+        if let Some(Some(file_re)) = SUPPRESS_FILE_RE.get(*pattern_idx)
+            && file_re.is_match(content).unwrap_or(false)
+        {
+            continue;
+        }
+
+        let matcher = Matcher {
+            regex,
+            line_span: pattern.line_span,
+            suppress_if: SUPPRESS_RE.get(*pattern_idx).and_then(Option::as_ref),
+            suppress_window: config
+                .suppress_window
+                .get(pattern.id)
+                .copied()
+                .unwrap_or(pattern.suppress_window),
+            field_scope: pattern.suppress_scope == SuppressScope::Field,
+        };
+        for line_number in matcher.matches(content, &lines, &mut fields) {
+            findings.push(Finding {
+                pattern_id: pattern.id.to_string(),
+                title: pattern.title.to_string(),
+                description: pattern.description.to_string(),
+                severity: pattern.severity.clone(),
+                file_path: file_path.to_path_buf(),
+                line_number,
+                code_snippet: snippet(&lines, line_number),
+                remediation: pattern.remediation.to_string(),
+                confidence: pattern.confidence,
+                references: pattern.references.iter().map(|s| s.to_string()).collect(),
+            });
+        }
+    }
+
+    // The team's own patterns apply whatever the framework.
+    for pattern in &config.custom_patterns {
+        let spec = &pattern.spec;
+        let matcher = Matcher {
+            regex: &pattern.regex,
+            line_span: spec.line_span,
+            suppress_if: pattern.suppress_if.as_ref(),
+            suppress_window: config
+                .suppress_window
+                .get(&spec.id)
+                .copied()
+                .unwrap_or(spec.suppress_window),
+            field_scope: false,
+        };
+        for line_number in matcher.matches(content, &lines, &mut fields) {
+            findings.push(Finding {
+                pattern_id: spec.id.clone(),
+                title: pattern.title().to_string(),
+                description: spec.description.clone(),
+                severity: spec.severity.clone(),
+                file_path: file_path.to_path_buf(),
+                line_number,
+                code_snippet: snippet(&lines, line_number),
+                remediation: spec.remediation.clone(),
+                confidence: spec.confidence,
+                references: spec.references.clone(),
+            });
+        }
+    }
+
+    findings
+}
+
+/// How one pattern matches, built-in or custom.
+struct Matcher<'a> {
+    regex: &'a fancy_regex::Regex,
+    line_span: usize,
+    suppress_if: Option<&'a fancy_regex::Regex>,
+    /// Lines either side of a match checked for `suppress_if`.
+    suppress_window: usize,
+    /// Also check the attributes of a matched struct field; see
+    /// [`SuppressScope::Field`].
+    field_scope: bool,
+}
+
+impl Matcher<'_> {
+    /// 1-based lines the pattern matches on, after suppression.
+    fn matches(
+        &self,
+        content: &str,
+        lines: &[&str],
+        fields: &mut Option<Vec<FieldContext>>,
+    ) -> Vec<usize> {
+        let mut hits = Vec::new();
         for line_idx in 0..lines.len() {
             let line_number = line_idx + 1;
 
@@ -194,48 +835,97 @@ pub fn scan(content: &str, file_path: &Path) -> Vec<Finding> {
                 continue;
             }
 
-            let window_end = (line_idx + span).min(lines.len());
+            let window_end = (line_idx + self.line_span).min(lines.len());
             let window: String = lines[line_idx..window_end].join("\n");
 
-            if !regex.is_match(&window).unwrap_or(false) {
+            if !self.regex.is_match(&window).unwrap_or(false) {
                 continue;
             }
 
-            // Check suppress context in a ±3-line window around the match
-            if let Some(Some(suppress_re)) = SUPPRESS_RE.get(*pattern_idx) {
-                let ctx_start = line_idx.saturating_sub(3);
-                let ctx_end = (line_idx + 4).min(lines.len());
+            if let Some(suppress_re) = self.suppress_if {
+                let ctx_start = line_idx.saturating_sub(self.suppress_window);
+                let ctx_end = (line_idx + self.suppress_window + 1).min(lines.len());
                 let ctx_window = lines[ctx_start..ctx_end].join("\n");
                 if suppress_re.is_match(&ctx_window).unwrap_or(false) {
                     continue;
                 }
+                if self.field_scope
+                    && fields
+                        .get_or_insert_with(|| field_contexts(content))
+                        .iter()
+                        .any(|f| {
+                            f.lines.contains(&line_number)
+                                && suppress_re.is_match(&f.text).unwrap_or(false)
+                        })
+                {
+                    continue;
+                }
             }
+            hits.push(line_number);
+        }
+        hits
+    }
+}
 
-            let start = line_number.saturating_sub(3);
-            let end = (line_number + 3).min(lines.len());
-            let snippet: String = lines[start..end]
-                .iter()
-                .enumerate()
-                .map(|(i, l)| format!("{:>4} | {l}", start + i + 1))
-                .collect::<Vec<_>>()
-                .join("\n");
+/// Numbered lines around `line_number`.
+fn snippet(lines: &[&str], line_number: usize) -> String {
+    let start = line_number.saturating_sub(3);
+    let end = (line_number + 3).min(lines.len());
+    lines[start..end]
+        .iter()
+        .enumerate()
+        .map(|(i, l)| format!("{:>4} | {l}", start + i + 1))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-            findings.push(Finding {
-                pattern_id: pattern.id.to_string(),
-                title: pattern.title.to_string(),
-                description: pattern.description.to_string(),
-                severity: pattern.severity.clone(),
-                file_path: file_path.to_path_buf(),
-                line_number,
-                code_snippet: snippet,
-                remediation: pattern.remediation.to_string(),
-                confidence: pattern.confidence,
-                references: pattern.references.iter().map(|s| s.to_string()).collect(),
+/// A struct field's lines (1-based) and the source of its attributes, doc
+/// comments and type, after the enclosing struct's attributes.
+struct FieldContext {
+    lines: std::ops::RangeInclusive<usize>,
+    text: String,
+}
+
+/// Every struct field in `content`; empty when it doesn't parse.
+fn field_contexts(content: &str) -> Vec<FieldContext> {
+    let Ok(ast) = syn::parse_file(content) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let mut collector = FieldCollector {
+        lines: &lines,
+        fields: Vec::new(),
+    };
+    collector.visit_file(&ast);
+    collector.fields
+}
+
+struct FieldCollector<'a> {
+    lines: &'a [&'a str],
+    fields: Vec<FieldContext>,
+}
+
+impl FieldCollector<'_> {
+    fn text(&self, span: proc_macro2::Span) -> String {
+        let start = span.start().line.saturating_sub(1);
+        let end = span.end().line.min(self.lines.len());
+        self.lines.get(start..end).unwrap_or_default().join("\n")
+    }
+}
+
+impl<'ast> Visit<'ast> for FieldCollector<'_> {
+    fn visit_item_struct(&mut self, item: &'ast syn::ItemStruct) {
+        let struct_attrs: Vec<String> = item.attrs.iter().map(|a| self.text(a.span())).collect();
+        for field in &item.fields {
+            // A field's span starts at its first attribute or doc comment.
+            let span = field.span();
+            self.fields.push(FieldContext {
+                lines: span.start().line..=span.end().line,
+                text: format!("{}\n{}", struct_attrs.join("\n"), self.text(span)),
             });
         }
+        syn::visit::visit_item_struct(self, item);
     }
-
-    findings
 }
 
 #[cfg(test)]
@@ -247,44 +937,53 @@ mod tests {
         scan(code, Path::new("test.rs"))
     }
 
-    // -- SOL-001: Missing Signer Constraint --
-
     #[test]
-    fn sol_001_positive() {
-        let findings = scan_one("pub authority: AccountInfo<'info>");
-        assert!(
-            findings
-                .iter()
-                .any(|f| f.pattern_id == "SOL-001" && f.severity == Severity::High),
-            "expected SOL-001 High finding, got: {findings:?}"
-        );
+    fn suppress_window_is_configurable_per_pattern() {
+        let code = "account.sub_lamports(amount)?;\n\n\n\n\naccount.data.borrow_mut().fill(0);";
+        assert!(scan_one(code).iter().any(|f| f.pattern_id == "SOL-006"));
+        let config = ScanConfig {
+            suppress_window: [("SOL-006".to_string(), 5)].into(),
+            ..Default::default()
+        };
+        let findings = scan_as(code, Path::new("test.rs"), None, &config);
+        assert!(!findings.iter().any(|f| f.pattern_id == "SOL-006"));
     }
 
     #[test]
-    fn sol_001_negative() {
-        let findings = scan_one("pub authority: Signer<'info>");
-        assert!(!findings.iter().any(|f| f.pattern_id == "SOL-001"));
-    }
-
-    #[test]
-    fn sol_001_suppressed_by_anchor_attribute_above() {
-        let code = "#[account(signer)]\npub authority: AccountInfo<'info>";
-        let findings = scan_one(code);
-        assert!(
-            !findings.iter().any(|f| f.pattern_id == "SOL-001"),
-            "SOL-001 should be suppressed when #[account] is nearby: {findings:?}"
-        );
-    }
-
-    #[test]
-    fn sol_001_suppressed_by_check_doc_comment() {
-        let code =
-            "/// CHECK: validated by the instruction handler\npub authority: AccountInfo<'info>";
-        let findings = scan_one(code);
-        assert!(
-            !findings.iter().any(|f| f.pattern_id == "SOL-001"),
-            "SOL-001 should be suppressed when CHECK doc comment is present: {findings:?}"
-        );
+    fn custom_patterns_scan_alongside_builtins() {
+        let file =
+            std::env::temp_dir().join(format!("solguard-patterns-{}.toml", std::process::id()));
+        std::fs::write(
+            &file,
+            r#"
+            [[pattern]]
+            id = "ACME-001"
+            title = "Oracle price read without staleness check"
+            regex = 'get_price_unchecked\s*\('
+            severity = "High"
+            remediation = "Use get_price_no_older_than."
+            suppress_if = 'max_age'
+            "#,
+        )
+        .unwrap();
+        let custom_patterns = super::super::custom_patterns::load(&file).unwrap();
+        std::fs::remove_file(&file).ok();
+        let config = ScanConfig {
+            custom_patterns,
+            ..Default::default()
+        };
+        let code = "let price = feed.get_price_unchecked();\nlet total = amount + balance;\n\n\n\n\n// max_age checked by caller\nlet p = feed.get_price_unchecked();";
+        let findings = scan_as(code, Path::new("test.rs"), Some(Framework::Anchor), &config);
+        let custom: Vec<_> = findings
+            .iter()
+            .filter(|f| f.pattern_id == "ACME-001")
+            .collect();
+        assert_eq!(custom.len(), 1, "{findings:?}");
+        assert_eq!(custom[0].line_number, 1);
+        assert_eq!(custom[0].severity, Severity::High);
+        assert_eq!(custom[0].title, "Oracle price read without staleness check");
+        assert!(findings.iter().any(|f| f.pattern_id == "SOL-003"));
+        assert!(!scan_one(code).iter().any(|f| f.pattern_id == "ACME-001"));
     }
 
     // -- SOL-003: Unchecked Arithmetic --
@@ -506,6 +1205,437 @@ mod tests {
         assert!(!findings.iter().any(|f| f.pattern_id == "SOL-010"));
     }
 
+    // -- SOL-011: Account Space Missing Discriminator --
+
+    #[test]
+    fn sol_011_positive() {
+        let findings =
+            scan_one("#[account(init, payer = user, space = std::mem::size_of::<Vault>())]");
+        assert!(
+            findings
+                .iter()
+                .any(|f| f.pattern_id == "SOL-011" && f.severity == Severity::Medium),
+            "expected SOL-011 Medium finding, got: {findings:?}"
+        );
+        assert!(
+            scan_one("#[account(init, payer = user, space = Vault::INIT_SPACE)]")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-011")
+        );
+    }
+
+    #[test]
+    fn sol_011_negative() {
+        for code in [
+            "#[account(init, payer = user, space = 8 + Vault::INIT_SPACE)]",
+            "#[account(init, payer = user, space = Vault::LEN + 8)]",
+            "#[account(init, payer = user, space = Vault::DISCRIMINATOR.len() + Vault::INIT_SPACE)]",
+        ] {
+            let findings = scan_one(code);
+            assert!(
+                !findings.iter().any(|f| f.pattern_id == "SOL-011"),
+                "{code}: {findings:?}"
+            );
+        }
+    }
+
+    // -- SOL-012: Realloc Without Rent Top-Up --
+
+    #[test]
+    fn sol_012_positive() {
+        let findings = scan_one("config.realloc(new_len, false)?;");
+        assert!(
+            findings
+                .iter()
+                .any(|f| f.pattern_id == "SOL-012" && f.severity == Severity::Medium),
+            "expected SOL-012 Medium finding, got: {findings:?}"
+        );
+    }
+
+    #[test]
+    fn sol_012_negative() {
+        let code = "let needed = Rent::get()?.minimum_balance(new_len);\n\
+                    transfer_shortfall(payer, config, needed)?;\n\
+                    config.realloc(new_len, false)?;";
+        let findings = scan_one(code);
+        assert!(!findings.iter().any(|f| f.pattern_id == "SOL-012"));
+    }
+
+    // -- SOL-013: Fixed Buffer Written With Unchecked Length --
+
+    #[test]
+    fn sol_013_positive() {
+        let findings = scan_one("state.name[..name.len()].copy_from_slice(name.as_bytes());");
+        assert!(
+            findings
+                .iter()
+                .any(|f| f.pattern_id == "SOL-013" && f.severity == Severity::High),
+            "expected SOL-013 High finding, got: {findings:?}"
+        );
+        assert!(
+            scan_one("unsafe { std::ptr::copy_nonoverlapping(src, dst.as_mut_ptr(), data_len) }")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-013")
+        );
+    }
+
+    #[test]
+    fn sol_013_negative() {
+        let code = "require!(name.len() <= MAX_NAME_LEN, ErrorCode::NameTooLong);\n\
+                    state.name[..name.len()].copy_from_slice(name.as_bytes());";
+        let findings = scan_one(code);
+        assert!(!findings.iter().any(|f| f.pattern_id == "SOL-013"));
+        assert!(
+            !scan_one("state.name.copy_from_slice(&name);")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-013")
+        );
+    }
+
+    // -- SOL-014/015/016: Token delegation --
+
+    #[test]
+    fn sol_014_approve_without_revoke() {
+        let code = "token::approve(cpi_ctx, amount)?;";
+        assert!(
+            scan_one(code)
+                .iter()
+                .any(|f| f.pattern_id == "SOL-014" && f.severity == Severity::Medium)
+        );
+        let revoked = "token::approve(cpi_ctx, amount)?;\n\n\n\n\n\ntoken::revoke(revoke_ctx)?;";
+        assert!(!scan_one(revoked).iter().any(|f| f.pattern_id == "SOL-014"));
+        // The handler definition is not a call.
+        assert!(
+            !scan_one("pub fn approve(ctx: Context<Approve>) -> Result<()> {")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-014")
+        );
+    }
+
+    #[test]
+    fn sol_015_unbounded_approval() {
+        let code = "token::approve(\n    cpi_ctx,\n    u64::MAX,\n)?;";
+        let hits: Vec<_> = scan_one(code)
+            .into_iter()
+            .filter(|f| f.pattern_id == "SOL-015")
+            .collect();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].line_number, 1);
+        assert!(
+            !scan_one("token::approve(cpi_ctx, amount)?;")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-015")
+        );
+    }
+
+    #[test]
+    fn sol_016_unconstrained_delegate() {
+        let code = "    /// CHECK: delegate\n    pub delegate: UncheckedAccount<'info>,";
+        assert!(
+            scan_one(code)
+                .iter()
+                .any(|f| f.pattern_id == "SOL-016" && f.severity == Severity::High)
+        );
+        let pda = "    #[account(seeds = [b\"delegate\", vault.key().as_ref()], bump)]\n    /// CHECK: PDA\n    pub delegate: UncheckedAccount<'info>,";
+        assert!(!scan_one(pda).iter().any(|f| f.pattern_id == "SOL-016"));
+    }
+
+    #[test]
+    fn sol_002_deserialize_without_owner_check() {
+        let code = "    let vault = Vault::try_from_slice(&vault_info.data.borrow())?;";
+        assert!(
+            scan_one(code)
+                .iter()
+                .any(|f| f.pattern_id == "SOL-002" && f.title.contains("Missing Owner"))
+        );
+        let checked = "    if vault_info.owner != program_id {\n        return Err(ProgramError::IncorrectProgramId);\n    }\n    let vault = Vault::try_from_slice(&vault_info.data.borrow())?;";
+        assert!(!scan_one(checked).iter().any(|f| f.pattern_id == "SOL-002"));
+    }
+
+    #[test]
+    fn sol_017_init_if_needed_without_guard() {
+        let code = "    #[account(init_if_needed, payer = user, space = 8 + 40)]\n    pub profile: Account<'info, Profile>,";
+        assert!(scan_one(code).iter().any(|f| f.pattern_id == "SOL-017"));
+        let guarded = format!("{code}\n    require!(!profile.is_initialized, AlreadyInit);");
+        assert!(!scan_one(&guarded).iter().any(|f| f.pattern_id == "SOL-017"));
+    }
+
+    #[test]
+    fn sol_018_unverified_sysvar() {
+        let ix = "    let ix = load_instruction_at(0, &ix_sysvar.data.borrow())?;";
+        assert!(scan_one(ix).iter().any(|f| f.pattern_id == "SOL-018"));
+        let checked = "    let ix = load_instruction_at_checked(0, &ix_sysvar)?;";
+        assert!(!scan_one(checked).iter().any(|f| f.pattern_id == "SOL-018"));
+
+        let field = "    /// CHECK: sysvar\n    pub instructions: AccountInfo<'info>,";
+        assert!(scan_one(field).iter().any(|f| f.pattern_id == "SOL-018"));
+        let pinned = "    #[account(address = sysvar::instructions::ID)]\n    /// CHECK: sysvar\n    pub instructions: AccountInfo<'info>,";
+        assert!(!scan_one(pinned).iter().any(|f| f.pattern_id == "SOL-018"));
+    }
+
+    #[test]
+    fn sol_019_positional_account_access() {
+        assert!(
+            scan_one("    let vault = &accounts[2];")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-019")
+        );
+        assert!(
+            scan_one("    let a = ctx.accounts.to_account_infos()[1].clone();")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-019")
+        );
+        // remaining_accounts is SOL-004's.
+        assert!(
+            !scan_one("    let a = ctx.remaining_accounts[0].clone();")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-019")
+        );
+        let checked = "    let vault = &accounts[2];\n    if vault.key != &expected_vault {\n        return Err(ProgramError::InvalidAccountData);\n    }";
+        assert!(!scan_one(checked).iter().any(|f| f.pattern_id == "SOL-019"));
+    }
+
+    // -- SOL-020/021/022: Pinocchio and native programs --
+
+    #[test]
+    fn sol_020_unchecked_zero_copy_cast() {
+        let code = "    let vault = Vault::from_bytes_mut(&mut data);";
+        assert!(
+            scan_one(code)
+                .iter()
+                .any(|f| f.pattern_id == "SOL-020" && f.severity == Severity::High)
+        );
+        assert!(
+            scan_one("    let v = bytemuck::from_bytes_mut::<Vault>(&mut data[..Vault::LEN]);")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-020")
+        );
+        let checked = "    if !vault_info.is_owned_by(&crate::ID) {\n        return Err(ProgramError::IllegalOwner);\n    }\n    let vault = Vault::from_bytes_mut(&mut data);";
+        assert!(!scan_one(checked).iter().any(|f| f.pattern_id == "SOL-020"));
+    }
+
+    #[test]
+    fn sol_021_accounts_taken_unverified() {
+        let native = "    let vault = next_account_info(account_iter)?;";
+        assert!(scan_one(native).iter().any(|f| f.pattern_id == "SOL-021"));
+        let pinocchio = "    let [vault, mint, ..] = accounts else {\n        return Err(ProgramError::NotEnoughAccountKeys);\n    };";
+        assert!(
+            scan_one(pinocchio)
+                .iter()
+                .any(|f| f.pattern_id == "SOL-021")
+        );
+        let checked = "    let vault = next_account_info(account_iter)?;\n    if vault.owner != program_id {\n        return Err(ProgramError::IncorrectProgramId);\n    }";
+        assert!(!scan_one(checked).iter().any(|f| f.pattern_id == "SOL-021"));
+    }
+
+    #[test]
+    fn sol_022_missing_manual_signer_check() {
+        let code = "    let [vault, authority, _system] = accounts else {\n        return Err(ProgramError::NotEnoughAccountKeys);\n    };";
+        assert!(
+            scan_one(code)
+                .iter()
+                .any(|f| f.pattern_id == "SOL-022" && f.line_number == 1)
+        );
+        assert!(
+            scan_one("    let pool_authority = next_account_info(iter)?;")
+                .iter()
+                .any(|f| f.pattern_id == "SOL-022")
+        );
+        let checked = format!(
+            "{code}\n\n\n\n    if !authority.is_signer() {{ return Err(MissingRequiredSignature); }}"
+        );
+        assert!(!scan_one(&checked).iter().any(|f| f.pattern_id == "SOL-022"));
+    }
+
+    #[test]
+    fn sol_023_hardcoded_keypair() {
+        let key = "MASi45ub7Qe4ZE36UT5G6cU4ud8Fhhe4deS4F3cw9KTAb8dLcukC7edhDQ7cn5d4gEYkbUrMWeWQLGsCmrG6dLaY";
+        for code in [
+            "    let admin = Keypair::from_bytes(&[12, 201, 7, 88]).unwrap();".to_string(),
+            "    let admin = Keypair::from_bytes(&[\n        12, 201,\n    ]).unwrap();".into(),
+            "const ADMIN_KEY: [u8; 64] = [\n    12, 201,\n];".into(),
+            format!("    let admin = Keypair::from_base58_string(\"{key}\");"),
+            format!("pub const DEPLOYER: &str = \"{key}\";"),
+        ] {
+            let findings = scan_one(&code);
+            assert!(
+                findings
+                    .iter()
+                    .any(|f| f.pattern_id == "SOL-023" && f.severity == Severity::Critical),
+                "{code}"
+            );
+        }
+        for code in [
+            "    let admin = Keypair::from_bytes(&bytes).unwrap();".to_string(),
+            "    let admin = read_keypair_file(&path)?;".into(),
+            format!("    let signature = Signature::from_str(\"{key}\")?;"),
+            "const ADMIN: Pubkey = pubkey!(\"9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin\");"
+                .into(),
+        ] {
+            assert!(
+                !scan_one(&code).iter().any(|f| f.pattern_id == "SOL-023"),
+                "{code}"
+            );
+        }
+    }
+
+    #[test]
+    fn compute_budget_patterns() {
+        let has = |code: &str, id: &str| scan_one(code).iter().any(|f| f.pattern_id == id);
+        assert!(has(
+            "    for account in ctx.remaining_accounts.iter() {\n        settle(account)?;\n    }",
+            "SOL-024"
+        ));
+        assert!(has("    for acc in &ctx.remaining_accounts {", "SOL-024"));
+        assert!(!has(
+            "    require!(ctx.remaining_accounts.len() <= 8, TooMany);\n    for acc in ctx.remaining_accounts.iter() {",
+            "SOL-024"
+        ));
+
+        assert!(has(
+            "    ctx.accounts.registry.members.push(member);",
+            "SOL-025"
+        ));
+        assert!(has("    pool.depositors.push(user);", "SOL-025"));
+        assert!(!has(
+            "    require!(pool.depositors.len() < MAX_DEPOSITORS, Full);\n    pool.depositors.push(user);",
+            "SOL-025"
+        ));
+        assert!(!has(
+            "    let mut ixs = Vec::new();\n    ixs.push(ix);",
+            "SOL-025"
+        ));
+
+        assert!(has(
+            "    let ix = Instruction {\n        program_id: crate::ID,\n        accounts,",
+            "SOL-026"
+        ));
+        assert!(has(
+            "    let ix = Instruction::new_with_bytes(*program_id, &data, metas);",
+            "SOL-026"
+        ));
+        assert!(!has(
+            "    let ix = Instruction {\n        program_id: spl_token::ID,",
+            "SOL-026"
+        ));
+    }
+
+    #[test]
+    fn token_account_patterns() {
+        let has = |code: &str, id: &str| scan_one(code).iter().any(|f| f.pattern_id == id);
+        let anchor = |attrs: &str, field: &str| {
+            format!(
+                "#[derive(Accounts)]\npub struct Deposit<'info> {{\n    {attrs}\n    pub {field}: Account<'info, TokenAccount>,\n}}"
+            )
+        };
+
+        assert!(has(&anchor("#[account(mut)]", "user_token"), "SOL-027"));
+        assert!(!has(
+            &anchor("#[account(mut, token::mint = mint)]", "user_token"),
+            "SOL-027"
+        ));
+        assert!(!has(
+            &anchor(
+                "#[account(mut, constraint = user_token.mint == pool.mint)]",
+                "user_token"
+            ),
+            "SOL-027"
+        ));
+
+        assert!(has(
+            &anchor("#[account(mut, token::mint = mint)]", "vault"),
+            "SOL-028"
+        ));
+        assert!(!has(
+            &anchor(
+                "#[account(mut, token::mint = mint, token::authority = pool)]",
+                "vault"
+            ),
+            "SOL-028"
+        ));
+        assert!(!has(
+            &anchor("#[account(mut, seeds = [b\"vault\"], bump)]", "pool_vault"),
+            "SOL-028"
+        ));
+        // Any account is fine as the user's own destination.
+        assert!(!has(&anchor("#[account(mut)]", "user_token"), "SOL-028"));
+
+        assert!(has(
+            &anchor("#[account(mut, token::mint = mint)]", "user_ata"),
+            "SOL-029"
+        ));
+        assert!(!has(
+            &anchor(
+                "#[account(mut, associated_token::mint = mint, associated_token::authority = user)]",
+                "user_ata"
+            ),
+            "SOL-029"
+        ));
+        assert!(has(
+            "let user_ata = next_account_info(accounts_iter)?;",
+            "SOL-029"
+        ));
+        assert!(!has(
+            "let user_ata = next_account_info(accounts_iter)?;\nlet expected = get_associated_token_address(user.key, mint.key);",
+            "SOL-029"
+        ));
+    }
+
+    #[test]
+    fn oracle_patterns() {
+        let has = |code: &str, id: &str| scan_one(code).iter().any(|f| f.pattern_id == id);
+
+        let unchecked = "let price = price_feed.get_price_unchecked();\nlet value = price.price as u64 * amount;";
+        assert!(has(unchecked, "SOL-030"));
+        assert!(has(unchecked, "SOL-031"));
+        assert!(has("let result = aggregator.get_result()?;", "SOL-030"));
+        assert!(!has(
+            "aggregator.check_staleness(clock.unix_timestamp, 300)?;\nlet result = aggregator.get_result()?;",
+            "SOL-030"
+        ));
+        assert!(!has(
+            "let price = price_feed.get_price_unchecked();\nrequire!(now - price.publish_time < MAX_AGE, Stale);",
+            "SOL-030"
+        ));
+
+        let fresh = "let price = update.get_price_no_older_than(&clock, MAX_AGE, &feed_id)?;";
+        assert!(!has(fresh, "SOL-030"));
+        assert!(has(fresh, "SOL-031"));
+        assert!(!has(
+            &format!("{fresh}\nrequire!(price.conf * 100 < price.price as u64, Wide);"),
+            "SOL-031"
+        ));
+    }
+
+    #[test]
+    fn patterns_follow_the_file_framework() {
+        let remaining = "let extra = ctx.remaining_accounts[0].clone();";
+        let cast = "    let vault = Vault::from_bytes_mut(&mut data);";
+        let ids = |code: &str, fw| -> Vec<String> {
+            scan_as(code, Path::new("test.rs"), fw, &ScanConfig::default())
+                .into_iter()
+                .map(|f| f.pattern_id)
+                .collect()
+        };
+        assert!(ids(remaining, Some(Framework::Anchor)).contains(&"SOL-004".into()));
+        assert!(!ids(remaining, Some(Framework::Pinocchio)).contains(&"SOL-004".into()));
+        assert!(ids(cast, Some(Framework::Native)).contains(&"SOL-020".into()));
+        assert!(!ids(cast, Some(Framework::Anchor)).contains(&"SOL-020".into()));
+        // Imports pick the profile when none is given.
+        let anchor_file = format!("use anchor_lang::prelude::*;\n{cast}");
+        assert!(
+            !scan_one(&anchor_file)
+                .iter()
+                .any(|f| f.pattern_id == "SOL-020")
+        );
+        let pinocchio_file = format!("use pinocchio::account_info::AccountInfo;\n{cast}");
+        assert!(
+            scan_one(&pinocchio_file)
+                .iter()
+                .any(|f| f.pattern_id == "SOL-020")
+        );
+    }
+
     // -- Edge cases --
 
     #[test]
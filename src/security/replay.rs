@@ -0,0 +1,244 @@
+//! Offline replay of saved agent transcripts through the post-LLM logic:
+//! finding extraction, severity normalization, verdict parsing and matching,
+//! and the disputed/dismissed handling of `validate_findings`. Lets parser
+//! changes be regression-tested against real model outputs with no API calls.
+
+use super::agent_review::extract_findings;
+use super::validator::{Verdict, extract_verdicts, match_verdict, parse_verdict};
+use crate::llm::{ContentBlock, ConversationMessage, Role};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// One transcript message. Only text is kept; tool traffic doesn't affect parsing.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TranscriptMessage {
+    /// `"user"` or `"assistant"`.
+    pub role: String,
+    pub text: String,
+}
+
+/// A saved session: the investigator and validator transcripts, plus what a
+/// correct parse should produce.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReplaySession {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub investigation: Vec<TranscriptMessage>,
+    /// Omit to replay extraction only.
+    #[serde(default)]
+    pub validation: Vec<TranscriptMessage>,
+    #[serde(default)]
+    pub expected: Expected,
+}
+
+/// Expected results; every field is optional and only set ones are checked.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Expected {
+    pub findings: Option<usize>,
+    pub confirmed: Option<usize>,
+    pub disputed: Option<usize>,
+    pub dismissed: Option<usize>,
+    /// Finding count per normalized severity, before validation.
+    pub severities: Option<BTreeMap<String, usize>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayedFinding {
+    pub title: String,
+    /// Severity as the model wrote it.
+    pub raw_severity: String,
+    pub severity: String,
+    /// `None` when no validation transcript was replayed.
+    pub verdict: Option<Verdict>,
+    /// Severity after validation: downgraded if disputed, `None` if dismissed.
+    pub final_severity: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayOutcome {
+    pub name: String,
+    pub findings: Vec<ReplayedFinding>,
+    pub verdicts_parsed: usize,
+    /// Expectations that did not hold; empty means the session passed.
+    pub mismatches: Vec<String>,
+}
+
+impl ReplaySession {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("reading session {}", path.display()))?;
+        let mut session: Self = serde_json::from_str(&text)
+            .with_context(|| format!("parsing session {}", path.display()))?;
+        if session.name.is_none() {
+            session.name = path.file_stem().map(|s| s.to_string_lossy().into_owned());
+        }
+        Ok(session)
+    }
+
+    pub fn replay(&self) -> ReplayOutcome {
+        let agent_findings = extract_findings(&to_messages(&self.investigation));
+        let verdicts = if self.validation.is_empty() {
+            Vec::new()
+        } else {
+            extract_verdicts(&to_messages(&self.validation))
+        };
+
+        let findings: Vec<ReplayedFinding> = agent_findings
+            .iter()
+            .enumerate()
+            .map(|(i, f)| {
+                let severity = crate::severity::normalize(&f.severity);
+                // Mirrors validate_findings: unmatched findings count as disputed.
+                let verdict = (!self.validation.is_empty()).then(|| {
+                    match_verdict(&verdicts, i, &f.title)
+                        .map(|v| parse_verdict(&v.verdict))
+                        .unwrap_or(Verdict::Disputed)
+                });
+                let final_severity = match verdict {
+                    Some(Verdict::Dismissed) => None,
+                    Some(Verdict::Disputed) => Some(crate::severity::downgrade(severity).into()),
+                    _ => Some(severity.into()),
+                };
+                ReplayedFinding {
+                    title: f.title.clone(),
+                    raw_severity: f.severity.clone(),
+                    severity: severity.into(),
+                    verdict,
+                    final_severity,
+                }
+            })
+            .collect();
+
+        let mut outcome = ReplayOutcome {
+            name: self.name.clone().unwrap_or_else(|| "session".into()),
+            findings,
+            verdicts_parsed: verdicts.len(),
+            mismatches: Vec::new(),
+        };
+        outcome.mismatches = self.expected.check(&outcome);
+        outcome
+    }
+}
+
+impl Expected {
+    fn check(&self, outcome: &ReplayOutcome) -> Vec<String> {
+        let mut mismatches = Vec::new();
+        let mut expect = |what: &str, want: Option<usize>, got: usize| {
+            if let Some(want) = want
+                && want != got
+            {
+                mismatches.push(format!("{what}: expected {want}, got {got}"));
+            }
+        };
+        let verdicts = |v: Verdict| {
+            outcome
+                .findings
+                .iter()
+                .filter(|f| f.verdict.as_ref() == Some(&v))
+                .count()
+        };
+        expect("findings", self.findings, outcome.findings.len());
+        expect("confirmed", self.confirmed, verdicts(Verdict::Confirmed));
+        expect("disputed", self.disputed, verdicts(Verdict::Disputed));
+        expect("dismissed", self.dismissed, verdicts(Verdict::Dismissed));
+        if let Some(ref severities) = self.severities {
+            for (severity, &want) in severities {
+                let got = outcome
+                    .findings
+                    .iter()
+                    .filter(|f| &f.severity == severity)
+                    .count();
+                expect(&format!("{severity} findings"), Some(want), got);
+            }
+        }
+        mismatches
+    }
+}
+
+fn to_messages(transcript: &[TranscriptMessage]) -> Vec<ConversationMessage> {
+    transcript
+        .iter()
+        .map(|m| ConversationMessage {
+            role: if m.role.eq_ignore_ascii_case("assistant") {
+                Role::Assistant
+            } else {
+                Role::User
+            },
+            content: vec![ContentBlock::Text {
+                text: m.text.clone(),
+            }],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(role: &str, text: &str) -> TranscriptMessage {
+        TranscriptMessage {
+            role: role.into(),
+            text: text.into(),
+        }
+    }
+
+    const FINDINGS: &str = r#"Here is what I found:
+```json
+[
+  {"title":"Unchecked owner on vault","severity":"critical","description":"d","evidence":["lib.rs:10"],"attack_scenario":"a","remediation":"r","confidence":0.9,"affected_files":["src/lib.rs"]},
+  {"title":"Stale oracle price","severity":"High","description":"d","evidence":["oracle.rs:4"],"attack_scenario":"a","remediation":"r","confidence":0.7,"affected_files":["src/oracle.rs"]},
+  {"title":"Missing event emission","severity":"Moderate","description":"d","evidence":["lib.rs:40"],"attack_scenario":"a","remediation":"r","confidence":0.5,"affected_files":["src/lib.rs"]}
+]
+```"#;
+
+    fn session(validation: Vec<TranscriptMessage>) -> ReplaySession {
+        ReplaySession {
+            name: Some("unit".into()),
+            investigation: vec![msg("user", "Investigate."), msg("assistant", FINDINGS)],
+            validation,
+            expected: Expected::default(),
+        }
+    }
+
+    #[test]
+    fn extraction_only_normalizes_severity() {
+        let out = session(Vec::new()).replay();
+        let severities: Vec<&str> = out.findings.iter().map(|f| f.severity.as_str()).collect();
+        assert_eq!(severities, vec!["Critical", "High", "Medium"]);
+        assert!(out.findings.iter().all(|f| f.verdict.is_none()));
+        assert_eq!(out.findings[0].raw_severity, "critical");
+    }
+
+    #[test]
+    fn verdicts_matched_by_index_then_title() {
+        let verdicts = r#"```json
+[
+  {"index": 0, "title": "Owner check", "verdict": "Confirmed", "reasoning": "real"},
+  {"title": "stale oracle", "verdict": "dismissed", "reasoning": "checked elsewhere"}
+]
+```"#;
+        let out = session(vec![msg("assistant", verdicts)]).replay();
+        assert_eq!(out.verdicts_parsed, 2);
+        assert_eq!(out.findings[0].verdict, Some(Verdict::Confirmed));
+        assert_eq!(out.findings[1].verdict, Some(Verdict::Dismissed));
+        assert_eq!(out.findings[1].final_severity, None);
+        // No verdict for the third: disputed and downgraded.
+        assert_eq!(out.findings[2].verdict, Some(Verdict::Disputed));
+        assert_eq!(out.findings[2].final_severity.as_deref(), Some("Low"));
+    }
+
+    #[test]
+    fn expectation_mismatches_reported() {
+        let mut s = session(Vec::new());
+        s.expected = Expected {
+            findings: Some(3),
+            severities: Some(BTreeMap::from([("Critical".to_string(), 2)])),
+            ..Default::default()
+        };
+        let out = s.replay();
+        assert_eq!(out.mismatches, vec!["Critical findings: expected 2, got 1"]);
+    }
+}
@@ -0,0 +1,242 @@
+//! Scans of a given revision (`scan --rev`, `investigate --rev`).
+//!
+//! The commit is checked out into a detached `git worktree` under the temp
+//! dir, so the caller's checkout, index and branch are left alone. Findings
+//! from it carry repo-relative paths (the worktree is gone after the scan),
+//! the commit hash and the branch or tag it was named by, so a result can be
+//! traced back to exactly what was read.
+//!
+//! `scan` also takes a repo URL: it is cloned into the temp dir (blobs fetched
+//! on demand) and scanned through a worktree the same way, at `HEAD` unless a
+//! revision is given.
+
+use super::SecurityFinding;
+use super::diff::git;
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// A detached checkout of one commit, removed on drop.
+pub struct Worktree {
+    /// The worktree's top level.
+    dir: PathBuf,
+    /// Where the scanned repo sits in it; differs from `dir` when the repo
+    /// path was a subdirectory of the checkout.
+    pub root: PathBuf,
+    /// Full hash of the checked-out commit.
+    pub commit: String,
+    /// The branch or tag it was checked out by; `None` for a hash or `HEAD`.
+    pub git_ref: Option<String>,
+    repo_path: PathBuf,
+}
+
+impl Worktree {
+    /// Check out `rev` (a commit, branch or tag) of the repo at `repo_path`.
+    pub async fn checkout(repo_path: &Path, rev: &str) -> Result<Self> {
+        let commit = git(
+            repo_path,
+            &["rev-parse", "--verify", &format!("{rev}^{{commit}}")],
+        )
+        .await
+        .with_context(|| format!("resolving revision {rev}"))?
+        .trim()
+        .to_string();
+        let prefix = git(repo_path, &["rev-parse", "--show-prefix"]).await?;
+        let dir = std::env::temp_dir().join(format!(
+            "solguard-rev-{}-{}",
+            &commit[..commit.len().min(12)],
+            std::process::id()
+        ));
+        let dir_arg = dir.to_string_lossy().into_owned();
+        git(
+            repo_path,
+            &["worktree", "add", "--detach", "--force", &dir_arg, &commit],
+        )
+        .await
+        .with_context(|| format!("checking out {rev} into {}", dir.display()))?;
+        let named = rev != "HEAD" && !commit.starts_with(rev);
+        Ok(Self {
+            root: dir.join(prefix.trim()),
+            dir,
+            git_ref: named.then(|| rev.to_string()),
+            commit,
+            repo_path: repo_path.to_path_buf(),
+        })
+    }
+
+    /// Make findings' paths relative to the repo and stamp them with the
+    /// commit and ref.
+    pub fn attribute(&self, findings: &mut [SecurityFinding]) {
+        for f in findings {
+            if let Ok(relative) = f.file_path.strip_prefix(&self.root) {
+                f.file_path = relative.to_path_buf();
+            }
+            f.commit = Some(self.commit.clone());
+            f.git_ref = self.git_ref.clone();
+        }
+    }
+}
+
+/// Whether a `scan` target is a git URL rather than a local path.
+pub fn is_url(target: &str) -> bool {
+    ["https://", "http://", "ssh://", "git://", "file://", "git@"]
+        .iter()
+        .any(|scheme| target.starts_with(scheme))
+}
+
+/// A temporary clone of a remote repo, removed on drop.
+pub struct RemoteClone {
+    /// The checkout, named after the repo so finding IDs use its name.
+    pub dir: PathBuf,
+    parent: PathBuf,
+}
+
+impl RemoteClone {
+    /// Clone `url` without a checkout; scan it through [`Worktree::checkout`].
+    pub async fn fetch(url: &str) -> Result<Self> {
+        let name = url
+            .trim_end_matches('/')
+            .trim_end_matches(".git")
+            .rsplit(['/', ':'])
+            .next()
+            .filter(|n| !n.is_empty())
+            .unwrap_or("repo");
+        let parent =
+            std::env::temp_dir().join(format!("solguard-clone-{}-{name}", std::process::id()));
+        let dir = parent.join(name);
+        std::fs::create_dir_all(&parent)?;
+        let clone = Self { dir, parent };
+        let dir_arg = clone.dir.to_string_lossy().into_owned();
+        git(
+            &clone.parent,
+            &[
+                "clone",
+                "--quiet",
+                "--no-checkout",
+                "--filter=blob:none",
+                url,
+                &dir_arg,
+            ],
+        )
+        .await
+        .with_context(|| format!("cloning {url}"))?;
+        Ok(clone)
+    }
+}
+
+impl Drop for RemoteClone {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.parent).ok();
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        let removed = std::process::Command::new("git")
+            .arg("-C")
+            .arg(&self.repo_path)
+            .args(["worktree", "remove", "--force"])
+            .arg(&self.dir)
+            .output()
+            .is_ok_and(|o| o.status.success());
+        if !removed {
+            warn!(path = %self.dir.display(), "failed to remove scan worktree");
+            std::fs::remove_dir_all(&self.dir).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn checks_out_a_past_commit_and_cleans_up() {
+        let repo = std::env::temp_dir().join(format!("solguard-revision-{}", std::process::id()));
+        std::fs::create_dir_all(repo.join("programs")).unwrap();
+        let run = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .arg("-C")
+                .arg(&repo)
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?}");
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        };
+        run(&["init", "-q"]);
+        std::fs::write(repo.join("programs/lib.rs"), "fn old() {}\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-qm", "v1"]);
+        run(&["tag", "v1"]);
+        let v1 = run(&["rev-parse", "HEAD"]);
+        std::fs::write(repo.join("programs/lib.rs"), "fn new() {}\n").unwrap();
+        run(&["commit", "-qam", "v2"]);
+
+        // A subdirectory of the checkout maps to the same subdirectory.
+        let worktree = Worktree::checkout(&repo.join("programs"), "v1")
+            .await
+            .unwrap();
+        assert_eq!(worktree.commit, v1);
+        let content = std::fs::read_to_string(worktree.root.join("lib.rs")).unwrap();
+        let mut findings = vec![SecurityFinding {
+            file_path: worktree.root.join("lib.rs"),
+            ..Default::default()
+        }];
+        worktree.attribute(&mut findings);
+        let dir = worktree.dir.clone();
+        drop(worktree);
+        let missing = Worktree::checkout(&repo, "no-such-rev").await;
+        let still_new = std::fs::read_to_string(repo.join("programs/lib.rs")).unwrap();
+        std::fs::remove_dir_all(&repo).ok();
+
+        assert_eq!(content, "fn old() {}\n");
+        assert_eq!(still_new, "fn new() {}\n");
+        assert_eq!(findings[0].file_path, PathBuf::from("lib.rs"));
+        assert_eq!(findings[0].commit.as_deref(), Some(v1.as_str()));
+        assert_eq!(findings[0].git_ref.as_deref(), Some("v1"));
+        assert!(!dir.exists());
+        assert!(missing.is_err());
+    }
+
+    #[tokio::test]
+    async fn clones_a_url_and_checks_out_head() {
+        let origin = std::env::temp_dir().join(format!("solguard-origin-{}", std::process::id()));
+        std::fs::create_dir_all(&origin).unwrap();
+        let run = |args: &[&str]| {
+            let output = std::process::Command::new("git")
+                .arg("-C")
+                .arg(&origin)
+                .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(output.status.success(), "git {args:?}");
+        };
+        run(&["init", "-q"]);
+        std::fs::write(origin.join("lib.rs"), "fn f() {}\n").unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-qm", "init"]);
+
+        let url = format!("file://{}/", origin.display());
+        assert!(is_url(&url) && is_url("git@github.com:acme/vault.git"));
+        assert!(!is_url("repos/vault"));
+        let clone = RemoteClone::fetch(&url).await.unwrap();
+        let worktree = Worktree::checkout(&clone.dir, "HEAD").await.unwrap();
+        let content = std::fs::read_to_string(worktree.root.join("lib.rs"));
+        let (name, parent) = (
+            clone.dir.file_name().unwrap().to_owned(),
+            clone.parent.clone(),
+        );
+        let git_ref = worktree.git_ref.clone();
+        drop(worktree);
+        drop(clone);
+        std::fs::remove_dir_all(&origin).ok();
+
+        assert_eq!(content.unwrap(), "fn f() {}\n");
+        assert_eq!(name, origin.file_name().unwrap());
+        assert_eq!(git_ref, None);
+        assert!(!parent.exists());
+    }
+}
@@ -0,0 +1,290 @@
+//! Data-driven tests for the static patterns. Each pattern ID has a corpus
+//! directory of snippets it must flag and snippets it must not:
+//!
+//! ```text
+//! rules/corpus/
+//!   SOL-006/
+//!     vulnerable/sub_lamports_no_zero.rs
+//!     safe/anchor_close.rs
+//! ```
+//!
+//! `solguard rules test` runs the scanner over every snippet and reports
+//! false negatives (a vulnerable snippet the pattern missed) and false
+//! positives (a safe snippet it flagged). Several corpus roots can be given;
+//! snippets for the same pattern are pooled, so a project can keep its own
+//! cases next to the built-in ones. Noir patterns (`NOIR-*`) take `.nr`
+//! snippets.
+
+use super::{Finding, ast_scan, noir_scan, regex_scan};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+/// Default corpus location, relative to the working directory.
+pub const DEFAULT_CORPUS: &str = "rules/corpus";
+
+/// Outcome for one pattern across all corpus roots.
+#[derive(Debug, Default, Serialize)]
+pub struct PatternResult {
+    pub pattern_id: String,
+    pub vulnerable: usize,
+    pub safe: usize,
+    /// Vulnerable snippets the pattern did not flag.
+    pub false_negatives: Vec<PathBuf>,
+    /// Safe snippets the pattern flagged.
+    pub false_positives: Vec<PathBuf>,
+}
+
+impl PatternResult {
+    pub fn passed(&self) -> bool {
+        self.false_negatives.is_empty() && self.false_positives.is_empty()
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct CorpusReport {
+    pub patterns: Vec<PatternResult>,
+    /// Known patterns with no snippets in any corpus root.
+    pub untested: Vec<String>,
+    /// Corpus directories that don't name a known pattern (usually a typo).
+    pub unknown: Vec<PathBuf>,
+}
+
+impl CorpusReport {
+    /// No misses and no stray directories. Untested patterns are reported
+    /// but don't fail the run.
+    pub fn passed(&self) -> bool {
+        self.unknown.is_empty() && self.patterns.iter().all(PatternResult::passed)
+    }
+
+    /// Per-pattern table followed by every miss.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "{:<10} {:>5} {:>5} {:>4} {:>4}",
+            "pattern", "vuln", "safe", "FN", "FP"
+        );
+        for p in &self.patterns {
+            let _ = writeln!(
+                out,
+                "{:<10} {:>5} {:>5} {:>4} {:>4}{}",
+                p.pattern_id,
+                p.vulnerable,
+                p.safe,
+                p.false_negatives.len(),
+                p.false_positives.len(),
+                if p.passed() { "" } else { "  FAIL" }
+            );
+        }
+        for p in &self.patterns {
+            for path in &p.false_negatives {
+                let _ = writeln!(
+                    out,
+                    "  FN {}: not flagged: {}",
+                    p.pattern_id,
+                    path.display()
+                );
+            }
+            for path in &p.false_positives {
+                let _ = writeln!(out, "  FP {}: flagged: {}", p.pattern_id, path.display());
+            }
+        }
+        if !self.untested.is_empty() {
+            let _ = writeln!(out, "no corpus: {}", self.untested.join(", "));
+        }
+        for dir in &self.unknown {
+            let _ = writeln!(out, "unknown pattern directory: {}", dir.display());
+        }
+        out
+    }
+}
+
+/// Every pattern ID the static scanner can report.
+pub fn known_patterns() -> Vec<&'static str> {
+    regex_scan::pattern_ids()
+        .chain(ast_scan::PATTERN_IDS.iter().copied())
+        .chain(noir_scan::PATTERN_IDS.iter().copied())
+        .collect()
+}
+
+/// Run the static scanners over one snippet. Unlike a repo scan there is no
+/// confidence floor, dedup or path exclusion: the corpus tests the raw pattern.
+pub fn scan_snippet(content: &str, path: &Path) -> Vec<Finding> {
+    if path.extension().is_some_and(|e| e == "nr") {
+        return noir_scan::scan(content, path);
+    }
+    let mut findings = regex_scan::scan(content, path);
+    if let Ok(ast) = ast_scan::scan(content, path) {
+        findings.extend(ast);
+    }
+    findings
+}
+
+/// Test every pattern against the snippets under `roots`.
+pub fn run(roots: &[PathBuf]) -> Result<CorpusReport> {
+    let known = known_patterns();
+    let mut results: BTreeMap<String, PatternResult> = BTreeMap::new();
+    let mut report = CorpusReport::default();
+
+    for root in roots {
+        let entries = std::fs::read_dir(root)
+            .with_context(|| format!("reading corpus {}", root.display()))?;
+        let mut dirs: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect();
+        dirs.sort();
+
+        for dir in dirs {
+            let id = dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if !known.contains(&id.as_str()) {
+                report.unknown.push(dir);
+                continue;
+            }
+            let result = results.entry(id.clone()).or_insert_with(|| PatternResult {
+                pattern_id: id.clone(),
+                ..Default::default()
+            });
+            for snippet in snippets(&dir.join("vulnerable"))? {
+                result.vulnerable += 1;
+                if !flags(&snippet, &id)? {
+                    result.false_negatives.push(snippet);
+                }
+            }
+            for snippet in snippets(&dir.join("safe"))? {
+                result.safe += 1;
+                if flags(&snippet, &id)? {
+                    result.false_positives.push(snippet);
+                }
+            }
+        }
+    }
+
+    report.untested = known
+        .iter()
+        .filter(|id| {
+            results
+                .get(**id)
+                .is_none_or(|r| r.vulnerable == 0 && r.safe == 0)
+        })
+        .map(|id| id.to_string())
+        .collect();
+    // Report in the scanner's own pattern order.
+    report.patterns = known.iter().filter_map(|id| results.remove(*id)).collect();
+    Ok(report)
+}
+
+/// Whether scanning `path` yields a finding for `pattern_id`.
+fn flags(path: &Path, pattern_id: &str) -> Result<bool> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("reading snippet {}", path.display()))?;
+    Ok(scan_snippet(&content, path)
+        .iter()
+        .any(|f| f.pattern_id == pattern_id))
+}
+
+/// `.rs` and `.nr` files directly under `dir`, sorted; none if it doesn't exist.
+fn snippets(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "rs" || e == "nr"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("solguard-corpus-{name}"));
+        let _ = std::fs::remove_dir_all(&root);
+        for (path, content) in files {
+            let path = root.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        root
+    }
+
+    #[test]
+    fn counts_false_negatives_and_positives() {
+        let root = corpus(
+            "counts",
+            &[
+                (
+                    "SOL-004/vulnerable/index.rs",
+                    "let a = &ctx.remaining_accounts[0];\n",
+                ),
+                (
+                    "SOL-004/vulnerable/missed.rs",
+                    "let a = &ctx.accounts.vault;\n",
+                ),
+                ("SOL-004/safe/named.rs", "let a = &ctx.accounts.vault;\n"),
+                (
+                    "SOL-004/safe/flagged.rs",
+                    "for a in ctx.remaining_accounts.iter() {}\n",
+                ),
+            ],
+        );
+        let report = run(std::slice::from_ref(&root)).unwrap();
+        let sol004 = &report.patterns[0];
+        assert_eq!(sol004.pattern_id, "SOL-004");
+        assert_eq!((sol004.vulnerable, sol004.safe), (2, 2));
+        assert_eq!(
+            sol004.false_negatives,
+            vec![root.join("SOL-004/vulnerable/missed.rs")]
+        );
+        assert_eq!(
+            sol004.false_positives,
+            vec![root.join("SOL-004/safe/flagged.rs")]
+        );
+        assert!(!report.passed());
+        assert!(report.untested.contains(&"SOL-001".to_string()));
+        assert!(!report.untested.contains(&"SOL-004".to_string()));
+
+        let text = report.render();
+        assert!(text.contains("SOL-004"));
+        assert!(text.contains("FAIL"));
+        assert!(text.contains("FN SOL-004: not flagged"));
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn roots_pool_and_unknown_dirs_fail() {
+        let builtin = corpus(
+            "pool-a",
+            &[("SOL-004/vulnerable/a.rs", "ctx.remaining_accounts[0];\n")],
+        );
+        let extra = corpus(
+            "pool-b",
+            &[
+                (
+                    "SOL-004/vulnerable/b.rs",
+                    "ctx.remaining_accounts.iter();\n",
+                ),
+                ("SOL-999/vulnerable/c.rs", "\n"),
+            ],
+        );
+        let report = run(&[builtin.clone(), extra.clone()]).unwrap();
+        assert_eq!(report.patterns.len(), 1);
+        assert_eq!(report.patterns[0].vulnerable, 2);
+        assert!(report.patterns[0].passed());
+        assert_eq!(report.unknown, vec![extra.join("SOL-999")]);
+        assert!(!report.passed());
+        std::fs::remove_dir_all(builtin).unwrap();
+        std::fs::remove_dir_all(extra).unwrap();
+    }
+}
@@ -0,0 +1,193 @@
+//! Stratified validation sampling. A static scan can flag hundreds of
+//! findings on one repo; validating all of them is cost-prohibitive and
+//! validating none leaves everything Unvalidated.
+//!
+//! Above `[validation] sample_above` findings, every Critical and High is
+//! validated, and each pattern's Medium/Low/Info findings are sampled at
+//! `sample_percent` (at least one per pattern). Each pattern's verdicts are
+//! then extrapolated to its unsampled findings as a [`PatternSample`].
+
+use super::{SecurityFinding, ValidationStatus};
+use crate::config::ValidationConfig;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Verdicts for the sampled findings of one pattern in one repo.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PatternSample {
+    pub repo: String,
+    /// Pattern ID, or the title for agent findings.
+    pub pattern: String,
+    /// Medium/Low/Info findings of this pattern.
+    pub total: usize,
+    pub sampled: usize,
+    pub confirmed: usize,
+    pub disputed: usize,
+    pub dismissed: usize,
+}
+
+impl PatternSample {
+    /// Share of sampled findings that survived validation (confirmed or
+    /// disputed); `None` when the validator returned nothing usable.
+    pub fn precision(&self) -> Option<f64> {
+        let judged = self.confirmed + self.disputed + self.dismissed;
+        (judged > 0).then(|| (self.confirmed + self.disputed) as f64 / judged as f64)
+    }
+
+    /// Expected real findings among the ones left unvalidated.
+    pub fn estimated_real(&self) -> Option<usize> {
+        self.precision()
+            .map(|p| (p * (self.total - self.sampled) as f64).round() as usize)
+    }
+}
+
+/// Sampling stratum: static findings by pattern, agent findings by title.
+pub fn stratum(finding: &SecurityFinding) -> String {
+    finding
+        .pattern_id
+        .clone()
+        .unwrap_or_else(|| finding.title.clone())
+}
+
+/// Critical and High findings are never sampled out.
+fn always_validated(finding: &SecurityFinding) -> bool {
+    crate::severity::at_least(&finding.severity, "High")
+}
+
+/// Indices (ascending) of the findings to validate, or `None` when the set
+/// is small enough to validate in full.
+pub fn select(findings: &[SecurityFinding], cfg: &ValidationConfig) -> Option<Vec<usize>> {
+    if findings.len() <= cfg.sample_above {
+        return None;
+    }
+    let mut selected = Vec::new();
+    for (i, f) in findings.iter().enumerate() {
+        if always_validated(f) {
+            selected.push(i);
+        }
+    }
+    for members in strata(findings).values() {
+        let want = (members.len() * cfg.sample_percent.min(100) as usize)
+            .div_ceil(100)
+            .max(1);
+        // Spread the sample across the stratum (findings arrive in file
+        // order) rather than taking the first files.
+        selected.extend((0..want).map(|k| members[k * members.len() / want]));
+    }
+    selected.sort_unstable();
+    Some(selected)
+}
+
+/// Tally verdicts per stratum. Call after validation has annotated the
+/// `selected` findings and before dismissed ones are removed. Strata that
+/// were validated in full are left out: there is nothing to extrapolate.
+pub fn summarize(findings: &[SecurityFinding], selected: &[usize]) -> Vec<PatternSample> {
+    strata(findings)
+        .into_iter()
+        .filter_map(|(pattern, members)| {
+            let sampled: Vec<&SecurityFinding> = members
+                .iter()
+                .filter(|i| selected.binary_search(i).is_ok())
+                .map(|&i| &findings[i])
+                .collect();
+            if sampled.len() == members.len() {
+                return None;
+            }
+            let count = |status: ValidationStatus| {
+                sampled
+                    .iter()
+                    .filter(|f| f.validation_status == status)
+                    .count()
+            };
+            Some(PatternSample {
+                repo: String::new(),
+                pattern,
+                total: members.len(),
+                sampled: sampled.len(),
+                confirmed: count(ValidationStatus::Confirmed),
+                disputed: count(ValidationStatus::Disputed),
+                dismissed: count(ValidationStatus::Dismissed),
+            })
+        })
+        .collect()
+}
+
+/// Indices of the sampleable (below High) findings, by stratum.
+fn strata(findings: &[SecurityFinding]) -> BTreeMap<String, Vec<usize>> {
+    let mut strata: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+    for (i, f) in findings.iter().enumerate() {
+        if !always_validated(f) {
+            strata.entry(stratum(f)).or_default().push(i);
+        }
+    }
+    strata
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(pattern: &str, severity: &str) -> SecurityFinding {
+        SecurityFinding {
+            title: format!("{pattern} finding"),
+            severity: severity.into(),
+            pattern_id: Some(pattern.into()),
+            ..Default::default()
+        }
+    }
+
+    fn cfg(sample_above: usize, sample_percent: u32) -> ValidationConfig {
+        ValidationConfig {
+            sample_above,
+            sample_percent,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn small_sets_are_validated_in_full() {
+        let findings = vec![finding("SOL-004", "Low"); 3];
+        assert_eq!(select(&findings, &cfg(3, 10)), None);
+    }
+
+    #[test]
+    fn keeps_critical_high_and_samples_each_pattern() {
+        let mut findings = vec![finding("SOL-001", "Critical"), finding("SOL-002", "High")];
+        findings.extend(vec![finding("SOL-004", "Medium"); 10]);
+        findings.extend(vec![finding("SOL-007", "Low"); 2]);
+        let selected = select(&findings, &cfg(5, 20)).unwrap();
+        // Both severe, 2 of 10 SOL-004 (spread out), 1 of 2 SOL-007.
+        assert_eq!(selected, vec![0, 1, 2, 7, 12]);
+    }
+
+    #[test]
+    fn extrapolates_per_pattern() {
+        let mut findings = vec![finding("SOL-004", "Medium"); 10];
+        findings.push(finding("SOL-001", "High"));
+        let selected = select(&findings, &cfg(5, 30)).unwrap();
+        assert_eq!(selected.len(), 4);
+        for (n, &i) in selected.iter().enumerate() {
+            findings[i].validation_status = match n {
+                0 => ValidationStatus::Confirmed,
+                1 => ValidationStatus::Disputed,
+                _ => ValidationStatus::Dismissed,
+            };
+        }
+
+        let samples = summarize(&findings, &selected);
+        assert_eq!(samples.len(), 1, "fully validated strata are omitted");
+        let s = &samples[0];
+        assert_eq!(s.pattern, "SOL-004");
+        assert_eq!((s.total, s.sampled), (10, 3));
+        assert_eq!((s.confirmed, s.disputed, s.dismissed), (1, 1, 1));
+        assert!((s.precision().unwrap() - 2.0 / 3.0).abs() < 1e-9);
+        assert_eq!(s.estimated_real(), Some(5));
+
+        let unjudged = PatternSample {
+            total: 4,
+            sampled: 1,
+            ..Default::default()
+        };
+        assert_eq!(unjudged.precision(), None);
+    }
+}
@@ -0,0 +1,142 @@
+//! Per-file pattern results cached by content hash, so nightly runs over the
+//! same clones skip the regex, AST and dataflow passes on unchanged files.
+//!
+//! Entries live in `~/.solguard/cache/<pattern set>/<file>.json`. The pattern
+//! set is a hash of the crate version, the binary's build time, every regex
+//! pattern definition (custom patterns included) and `[scan]
+//! suppress_window`, so a scanner change (or a rebuild during development)
+//! starts a fresh directory instead of serving stale results; old
+//! directories are left for the user to delete. A file is keyed by its
+//! content and the framework profile it was scanned with, not its path:
+//! identical files in forks share an entry. Files are still parsed for the
+//! call graph and function index on a hit.
+
+use super::framework::Framework;
+use super::{Finding, fnv1a, regex_scan};
+use crate::config::ScanConfig;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+pub fn default_dir() -> PathBuf {
+    crate::memory::solguard_dir().join("cache")
+}
+
+/// The cache directory for one pattern set.
+pub struct ScanCache {
+    dir: PathBuf,
+}
+
+impl ScanCache {
+    /// The cache under `root` for the patterns as `scan` configures them.
+    pub fn open(root: &Path, scan: &ScanConfig) -> Self {
+        let mut windows: Vec<_> = scan.suppress_window.iter().collect();
+        windows.sort();
+        // AST and dataflow passes are code, not data; a new binary may
+        // scan differently.
+        let build = std::env::current_exe()
+            .and_then(|exe| exe.metadata()?.modified())
+            .ok();
+        let custom: Vec<String> = scan
+            .custom_patterns
+            .iter()
+            .map(|p| p.definition())
+            .collect();
+        let version = fnv1a(&format!(
+            "{}\0{build:?}\0{}\0{windows:?}\0{}",
+            env!("CARGO_PKG_VERSION"),
+            regex_scan::definitions(),
+            custom.join("\n")
+        ));
+        Self {
+            dir: root.join(format!("{version:016x}")),
+        }
+    }
+
+    /// Cached findings for `content`, placed at `file_path`.
+    pub fn get(
+        &self,
+        content: &str,
+        framework: Option<Framework>,
+        file_path: &Path,
+    ) -> Option<Vec<Finding>> {
+        let text = std::fs::read_to_string(self.entry(content, framework)).ok()?;
+        let mut findings: Vec<Finding> = serde_json::from_str(&text).ok()?;
+        for f in &mut findings {
+            f.file_path = file_path.to_path_buf();
+        }
+        Some(findings)
+    }
+
+    /// Store the findings for `content`. A failed write only costs a rescan
+    /// next time.
+    pub fn put(&self, content: &str, framework: Option<Framework>, findings: &[Finding]) {
+        let path = self.entry(content, framework);
+        let written = std::fs::create_dir_all(&self.dir)
+            .and_then(|()| Ok(serde_json::to_vec(findings)?))
+            .and_then(|json| {
+                // Write then rename, so concurrent scans never read half an entry.
+                let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+                std::fs::write(&tmp, json)?;
+                std::fs::rename(&tmp, &path)
+            });
+        if let Err(e) = written {
+            debug!(path = %path.display(), error = %e, "scan cache write failed");
+        }
+    }
+
+    fn entry(&self, content: &str, framework: Option<Framework>) -> PathBuf {
+        let label = framework.map_or("none", |f| f.label());
+        let key = fnv1a(&format!("{label}\0{}\0{content}", content.len()));
+        self.dir.join(format!("{key:016x}.json"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::Severity;
+
+    fn finding(path: &str) -> Finding {
+        Finding {
+            pattern_id: "SOL-001".into(),
+            title: "Missing Signer Constraint".into(),
+            description: String::new(),
+            severity: Severity::High,
+            file_path: path.into(),
+            line_number: 3,
+            code_snippet: String::new(),
+            remediation: String::new(),
+            confidence: 0.65,
+            references: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn entries_are_keyed_by_content_framework_and_pattern_set() {
+        let root = std::env::temp_dir().join(format!("solguard-cache-{}", std::process::id()));
+        let scan = ScanConfig::default();
+        let cache = ScanCache::open(&root, &scan);
+        let anchor = Some(Framework::Anchor);
+        cache.put("fn a() {}", anchor, &[finding("vault/src/lib.rs")]);
+        cache.put("fn clean() {}", anchor, &[]);
+
+        let hit = cache.get("fn a() {}", anchor, Path::new("fork/src/lib.rs"));
+        let clean = cache.get("fn clean() {}", anchor, Path::new("x.rs"));
+        let other_profile = cache.get("fn a() {}", None, Path::new("x.rs"));
+        let edited = cache.get("fn a() { }", anchor, Path::new("x.rs"));
+        let tuned = ScanConfig {
+            suppress_window: [("SOL-001".to_string(), 8)].into(),
+            ..ScanConfig::default()
+        };
+        let retuned = ScanCache::open(&root, &tuned).get("fn a() {}", anchor, Path::new("x.rs"));
+        std::fs::remove_dir_all(&root).ok();
+
+        let hit = hit.unwrap();
+        assert_eq!(hit.len(), 1);
+        assert_eq!(hit[0].file_path, Path::new("fork/src/lib.rs"));
+        assert_eq!(clean.map(|f| f.len()), Some(0));
+        assert!(other_profile.is_none());
+        assert!(edited.is_none());
+        assert!(retuned.is_none());
+    }
+}
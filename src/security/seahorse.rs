@@ -0,0 +1,106 @@
+//! Seahorse projects: Anchor programs written in Python and compiled to Rust.
+//!
+//! The compiled Rust under `programs_py/**/generated/` is what gets deployed,
+//! so it is scanned even though `generated/` is otherwise excluded. Findings
+//! there are tagged [`TAG`] and their remediation points at the Python
+//! source, since edits to the generated code are lost on the next build.
+
+use super::SecurityFinding;
+use std::path::Path;
+
+/// Tag carried by findings in Seahorse-generated Rust.
+pub const TAG: &str = "seahorse-generated";
+
+/// Python program sources live here; generated Rust sits beneath it.
+const PY_DIR: &str = "programs_py";
+
+/// Whether `root` is a Seahorse project: a `seahorse.json`, or Python
+/// sources in `programs_py/`.
+pub fn is_project(root: &Path) -> bool {
+    if root.join("seahorse.json").exists() {
+        return true;
+    }
+    std::fs::read_dir(root.join(PY_DIR)).is_ok_and(|entries| {
+        entries
+            .flatten()
+            .any(|e| e.path().extension().is_some_and(|ext| ext == "py"))
+    })
+}
+
+/// Whether repo-relative `rel` is Seahorse-generated Rust.
+pub fn is_generated(rel: &Path) -> bool {
+    crate::paths::has_dir(rel, &[PY_DIR]) && crate::paths::has_dir(rel, &["generated"])
+}
+
+/// Repo-relative Python program sources (`programs_py/*.py`), sorted.
+pub fn python_sources(root: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(root.join(PY_DIR)) else {
+        return Vec::new();
+    };
+    let mut sources: Vec<String> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "py"))
+        .filter_map(|p| {
+            p.file_name()
+                .map(|n| format!("{PY_DIR}/{}", n.to_string_lossy()))
+        })
+        .collect();
+    sources.sort();
+    sources
+}
+
+/// Tag a finding in generated code and redirect its fix to `python`.
+pub fn annotate(f: &mut SecurityFinding, python: &[String]) {
+    crate::tags::add_tag(&mut f.tags, TAG);
+    let source = if python.is_empty() {
+        format!("the Python source in {PY_DIR}/")
+    } else {
+        python.join(", ")
+    };
+    f.remediation = format!(
+        "Generated code — fix in Python source ({source}), then rebuild. {}",
+        f.remediation
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn generated_only_under_programs_py() {
+        assert!(is_generated(Path::new(
+            "programs_py/vault/generated/lib.rs"
+        )));
+        assert!(!is_generated(Path::new("programs/vault/generated/lib.rs")));
+        assert!(!is_generated(Path::new("programs_py/vault/lib.rs")));
+    }
+
+    #[test]
+    fn detects_project_and_annotates() {
+        let root = std::env::temp_dir().join(format!("solguard-seahorse-{}", std::process::id()));
+        std::fs::create_dir_all(root.join(PY_DIR)).unwrap();
+        assert!(!is_project(&root));
+        std::fs::write(root.join(PY_DIR).join("vault.py"), "").unwrap();
+        std::fs::write(root.join(PY_DIR).join("README.md"), "").unwrap();
+        assert!(is_project(&root));
+        let python = python_sources(&root);
+        std::fs::remove_dir_all(&root).ok();
+        assert_eq!(python, vec!["programs_py/vault.py"]);
+
+        let mut f = SecurityFinding {
+            file_path: PathBuf::from("programs_py/vault/generated/lib.rs"),
+            remediation: "Validate each account.".into(),
+            ..Default::default()
+        };
+        annotate(&mut f, &python);
+        assert_eq!(f.tags, vec![TAG]);
+        assert_eq!(
+            f.remediation,
+            "Generated code — fix in Python source (programs_py/vault.py), then rebuild. \
+             Validate each account."
+        );
+    }
+}
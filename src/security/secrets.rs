@@ -0,0 +1,100 @@
+//! Keypair files committed to the repo. `solana-keygen` writes a keypair as a
+//! JSON array of its 64 secret-key bytes; one sitting in the tree is a leaked
+//! key, whatever cluster it was generated for. Keys embedded in source are the
+//! regex pattern `SOL-023`.
+
+use super::{Finding, Severity};
+use std::path::Path;
+use tracing::info;
+use walkdir::WalkDir;
+
+/// Pattern ID of committed keypair files.
+pub const PATTERN_ID: &str = "KEY-001";
+
+/// Build output and local validator state; keypairs there are generated,
+/// not committed (`target/deploy/*-keypair.json`, `test-ledger/`).
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules", ".git", ".anchor", "test-ledger"];
+
+/// A finding for every keypair file under `root`.
+pub fn committed_keypairs(root: &Path) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let entries = WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| {
+            !(e.file_type().is_dir()
+                && SKIPPED_DIRS.contains(&e.file_name().to_string_lossy().as_ref()))
+        })
+        .filter_map(|e| e.ok());
+    for entry in entries {
+        let path = entry.path();
+        if path.extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(path) else {
+            continue;
+        };
+        if is_keypair(&content) {
+            findings.push(finding(path, &content));
+        }
+    }
+    if !findings.is_empty() {
+        info!(count = findings.len(), "committed keypair files");
+    }
+    findings
+}
+
+/// Whether `content` is a `solana-keygen` keypair: 64 bytes as a JSON array.
+pub fn is_keypair(content: &str) -> bool {
+    // Cheap guard before parsing every JSON file in the repo.
+    content.trim_start().starts_with('[')
+        && serde_json::from_str::<Vec<u8>>(content).is_ok_and(|bytes| bytes.len() == 64)
+}
+
+fn finding(path: &Path, content: &str) -> Finding {
+    let first = content.lines().next().unwrap_or_default();
+    let preview: String = first.chars().take(24).collect();
+    Finding {
+        pattern_id: PATTERN_ID.into(),
+        title: "Committed Keypair File".into(),
+        description: "A Solana keypair (64 secret-key bytes) is committed to the repo. Anyone with \
+                      the repo holds the key, and devnet keys tend to end up as mainnet upgrade or \
+                      admin authorities."
+            .into(),
+        severity: Severity::Critical,
+        file_path: path.to_path_buf(),
+        line_number: 1,
+        code_snippet: format!("   1 | {preview}..."),
+        remediation: "Delete the file, add it to .gitignore and rotate the key: move any authority \
+                      it holds to a new keypair. It stays in git history until the history is rewritten."
+            .into(),
+        confidence: 0.85,
+        references: vec!["CWE-798".into()],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_keypair_json_outside_build_dirs() {
+        let root = std::env::temp_dir().join(format!("solguard-secrets-{}", std::process::id()));
+        for dir in ["keys", "target/deploy", "app"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        let keypair = format!("[{}]", vec!["174"; 64].join(","));
+        std::fs::write(root.join("keys/admin.json"), &keypair).unwrap();
+        std::fs::write(root.join("target/deploy/vault-keypair.json"), &keypair).unwrap();
+        std::fs::write(root.join("app/package.json"), "{\"name\": \"app\"}").unwrap();
+        std::fs::write(root.join("app/short.json"), "[1, 2, 3]").unwrap();
+        let findings = committed_keypairs(&root);
+        std::fs::remove_dir_all(&root).ok();
+
+        assert_eq!(findings.len(), 1, "{findings:?}");
+        assert!(findings[0].file_path.ends_with("keys/admin.json"));
+        assert_eq!(findings[0].severity, Severity::Critical);
+        // The snippet never carries the whole key.
+        assert!(findings[0].code_snippet.len() < keypair.len());
+        assert!(!is_keypair(&format!("[{}]", vec!["256"; 64].join(","))));
+    }
+}
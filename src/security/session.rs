@@ -0,0 +1,231 @@
+//! Checkpointed agent investigations. Every investigation writes its
+//! conversation, stats and stuck-loop history to
+//! `~/.solguard/sessions/{id}.json` after each turn, so one that crashed or
+//! hit its cost limit can continue with `investigate --resume <id>` instead
+//! of starting over. Finished sessions are deleted a week after their last
+//! save.
+
+use super::agent_review::ReviewStats;
+use crate::llm::{ContentBlock, ConversationMessage, Role};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::warn;
+
+/// How long a finished session stays resumable.
+const FINISHED_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// One investigation's saved state.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub id: String,
+    /// Absolute path of the repo under investigation.
+    pub repo_path: PathBuf,
+    pub model: String,
+    pub started_at: String,
+    pub updated_at: String,
+    /// Whether the investigation ran to the end: findings were extracted,
+    /// not lost to a crash.
+    #[serde(default)]
+    pub finished: bool,
+    pub messages: Vec<ConversationMessage>,
+    #[serde(default)]
+    pub stats: ReviewStats,
+    /// (tool name, input hash) of every tool call, for stuck-loop detection.
+    #[serde(default)]
+    pub recent_calls: Vec<(String, u64)>,
+}
+
+impl Session {
+    /// A new session whose conversation starts with `initial_msg`. The ID
+    /// carries the process ID, so two runs starting the same second on the
+    /// same repo don't overwrite each other.
+    pub fn new(repo_name: &str, repo_path: &Path, model: &str, initial_msg: String) -> Self {
+        let now = Utc::now();
+        Self {
+            id: format!(
+                "{}_{}_{}",
+                now.format("%Y%m%d_%H%M%S"),
+                crate::memory::id_slug(repo_name),
+                std::process::id()
+            ),
+            repo_path: repo_path.to_path_buf(),
+            model: model.to_string(),
+            started_at: now.to_rfc3339(),
+            updated_at: now.to_rfc3339(),
+            finished: false,
+            messages: vec![ConversationMessage {
+                role: Role::User,
+                content: vec![ContentBlock::Text { text: initial_msg }],
+            }],
+            stats: ReviewStats::default(),
+            recent_calls: Vec::new(),
+        }
+    }
+
+    /// Load session `id` from the sessions directory.
+    pub fn load(id: &str) -> Result<Self> {
+        Self::load_from(&sessions_dir(), id)
+    }
+
+    pub fn load_from(dir: &Path, id: &str) -> Result<Self> {
+        let path = dir.join(format!("{id}.json"));
+        let text = std::fs::read_to_string(&path)
+            .with_context(|| format!("no saved session at {}", path.display()))?;
+        serde_json::from_str(&text).with_context(|| format!("parsing {}", path.display()))
+    }
+
+    pub fn save_to(&mut self, dir: &Path) -> Result<PathBuf> {
+        self.updated_at = Utc::now().to_rfc3339();
+        let path = dir.join(format!("{}.json", self.id));
+        crate::output::file::write_atomic(&path, serde_json::to_string(self)?.as_bytes())?;
+        Ok(path)
+    }
+
+    /// Save to the sessions directory on the blocking pool, and once the
+    /// session is finished, prune the finished ones past [`FINISHED_TTL`]. A
+    /// failed save is logged, not fatal: losing the checkpoint must not lose
+    /// the investigation.
+    pub async fn checkpoint(&mut self) {
+        self.updated_at = Utc::now().to_rfc3339();
+        let dir = sessions_dir();
+        let path = dir.join(format!("{}.json", self.id));
+        let finished = self.finished;
+        let saved = match serde_json::to_vec(self) {
+            Ok(json) => tokio::task::spawn_blocking(move || -> Result<()> {
+                crate::output::file::write_atomic(&path, &json)?;
+                if finished {
+                    prune_finished(&dir, FINISHED_TTL);
+                }
+                Ok(())
+            })
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|r| r),
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = saved {
+            warn!(session = %self.id, error = %e, "failed to checkpoint agent session");
+        }
+    }
+
+    /// Name of the repo, for logs and progress events.
+    pub fn repo_name(&self) -> String {
+        self.repo_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "unknown".into())
+    }
+}
+
+pub fn sessions_dir() -> PathBuf {
+    crate::memory::solguard_dir().join("sessions")
+}
+
+/// Delete the finished sessions in `dir` last saved `ttl` or more ago.
+/// Unfinished ones stay until resumed, whatever their age.
+fn prune_finished(dir: &Path, ttl: Duration) {
+    #[derive(Deserialize)]
+    struct Status {
+        #[serde(default)]
+        finished: bool,
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let now = SystemTime::now();
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.extension().is_none_or(|e| e != "json") {
+            continue;
+        }
+        let stale = std::fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .is_ok_and(|t| now.duration_since(t).unwrap_or_default() >= ttl);
+        if !stale {
+            continue;
+        }
+        let finished = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| serde_json::from_str::<Status>(&text).ok())
+            .is_some_and(|s| s.finished);
+        if finished && let Err(e) = std::fs::remove_file(&path) {
+            warn!(path = %path.display(), error = %e, "failed to delete finished session");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_conversation_and_stats() {
+        let dir = std::env::temp_dir().join(format!("solguard-sessions-{}", std::process::id()));
+        let mut session = Session::new(
+            "Marginfi V2",
+            Path::new("/tmp/marginfi-v2"),
+            "model-x",
+            "Investigate".into(),
+        );
+        assert!(
+            session
+                .id
+                .ends_with(&format!("_marginfi-v2_{}", std::process::id()))
+        );
+        session.messages.push(ConversationMessage {
+            role: Role::Assistant,
+            content: vec![ContentBlock::ToolUse {
+                id: "t1".into(),
+                name: "read_file".into(),
+                input: serde_json::json!({"path": "src/lib.rs"}),
+            }],
+        });
+        session.messages.push(ConversationMessage {
+            role: Role::User,
+            content: vec![ContentBlock::ToolResult {
+                tool_use_id: "t1".into(),
+                content: "   1 | use anchor_lang::prelude::*;".into(),
+                is_error: false,
+            }],
+        });
+        session.stats.turns = 1;
+        session.stats.total_cost_usd = 0.42;
+        session.recent_calls.push(("read_file".into(), 7));
+        session.save_to(&dir).unwrap();
+
+        let loaded = Session::load_from(&dir, &session.id).unwrap();
+        assert_eq!(loaded.messages.len(), 3);
+        assert!(matches!(
+            &loaded.messages[1].content[0],
+            ContentBlock::ToolUse { name, .. } if name == "read_file"
+        ));
+        assert_eq!(loaded.messages[2].role, Role::User);
+        assert_eq!(loaded.stats.turns, 1);
+        assert!((loaded.stats.total_cost_usd - 0.42).abs() < f64::EPSILON);
+        assert_eq!(loaded.recent_calls, vec![("read_file".to_string(), 7)]);
+        assert!(!loaded.finished);
+        assert!(Session::load_from(&dir, "missing").is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn prunes_only_finished_sessions_past_the_ttl() {
+        let dir = std::env::temp_dir().join(format!("solguard-prune-{}", std::process::id()));
+        let mut running = Session::new("a", Path::new("/tmp/a"), "m", "go".into());
+        let mut finished = Session::new("b", Path::new("/tmp/b"), "m", "go".into());
+        finished.finished = true;
+        running.save_to(&dir).unwrap();
+        let path = finished.save_to(&dir).unwrap();
+
+        prune_finished(&dir, FINISHED_TTL);
+        assert!(path.exists(), "a fresh finished session is kept");
+
+        prune_finished(&dir, Duration::ZERO);
+        assert!(!path.exists());
+        assert!(Session::load_from(&dir, &running.id).is_ok());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,769 @@
+//! Trust-boundary graph: instruction handlers, the accounts each one takes,
+//! and the CPIs it makes, extracted from the AST of every program source file.
+//!
+//! Handlers are the `pub fn`s of a `#[program]` module; their accounts come
+//! from the `#[derive(Accounts)]` struct named in `Context<..>`. CPIs are
+//! `CpiContext::new*` and `invoke*` calls in the handler body or in any repo
+//! function it calls. Rendered as a Mermaid flowchart whose `program`
+//! subgraph (handlers and PDAs) is the trust boundary: everything outside it
+//! is supplied by the caller or another program.
+
+use super::ast_scan::AccountConstraints;
+use super::collect_rust_files;
+use quote::ToTokens;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fmt::Write;
+use std::path::Path;
+use syn::visit::Visit;
+use syn::{Expr, ExprCall, ExprMethodCall, FnArg, ImplItemFn, ItemFn, ItemMod, ItemStruct};
+
+/// Call-graph depth followed from a handler when collecting its CPIs.
+const MAX_CALL_DEPTH: usize = 4;
+
+/// Handlers drawn per repo; beyond this the diagram is unreadable anyway.
+const MAX_HANDLERS: usize = 40;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AccountKind {
+    /// `Signer<'info>` or `#[account(signer)]`.
+    Signer,
+    /// Derived from `seeds = [..]`: controlled by the program itself.
+    Pda,
+    /// `Program<..>`/`Interface<..>`: a CPI target.
+    Program,
+    /// `AccountInfo`/`UncheckedAccount`: nothing checked by Anchor.
+    Unchecked,
+    /// Any other typed account (owner and discriminator checked).
+    Typed,
+}
+
+/// One account field of a handler's `Accounts` struct.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountUse {
+    pub name: String,
+    pub kind: AccountKind,
+    /// Inner type for typed and program accounts (`TokenAccount`, `Token`),
+    /// else empty.
+    pub ty: String,
+    pub writable: bool,
+}
+
+/// A cross-program invocation made (directly or through a helper) by a handler.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Cpi {
+    /// Program account field (`token_program`) or instruction builder
+    /// module (`system_instruction`) the call targets.
+    pub program: String,
+    /// Signed with PDA seeds (`new_with_signer`, `invoke_signed`).
+    pub signed: bool,
+}
+
+/// A `Sysvar<'info, T>` field. Its address is fixed, so it isn't drawn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SysvarUse {
+    pub name: String,
+    /// `Rent`, `Clock`, ...
+    pub ty: String,
+}
+
+/// An instruction data parameter of a handler, after the `Context`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HandlerArg {
+    pub name: String,
+    /// Type as written, without spaces (`u64`, `Vec<u8>`, `SwapArgs`).
+    pub ty: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Handler {
+    pub name: String,
+    pub accounts: Vec<AccountUse>,
+    pub cpis: Vec<Cpi>,
+    /// `Accounts` struct named in `Context<..>`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<HandlerArg>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sysvars: Vec<SysvarUse>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrustGraph {
+    pub repo: String,
+    pub handlers: Vec<Handler>,
+}
+
+impl TrustGraph {
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// Mermaid flowchart of the graph.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        // First declaration of each account wins when structs disagree.
+        let mut accounts: BTreeMap<&str, &AccountUse> = BTreeMap::new();
+        let mut programs: BTreeSet<&str> = BTreeSet::new();
+        for h in &self.handlers {
+            for a in &h.accounts {
+                accounts.entry(&a.name).or_insert(a);
+            }
+            for c in &h.cpis {
+                programs.insert(&c.program);
+            }
+        }
+
+        let _ = writeln!(out, "    subgraph program[\"{}\"]", label(&self.repo));
+        for h in &self.handlers {
+            let _ = writeln!(
+                out,
+                "        {}([\"{}\"])",
+                node("ix", &h.name),
+                label(&h.name)
+            );
+        }
+        for a in accounts.values().filter(|a| a.kind == AccountKind::Pda) {
+            let _ = writeln!(out, "        {}", account_node(a));
+        }
+        out.push_str("    end\n");
+        for a in accounts.values() {
+            // Program accounts are drawn as CPI targets below.
+            if !matches!(a.kind, AccountKind::Pda | AccountKind::Program) {
+                let _ = writeln!(out, "    {}", account_node(a));
+            }
+        }
+        for p in &programs {
+            let _ = writeln!(out, "    {}[[\"{}\"]]", node("prog", p), label(p));
+        }
+
+        for h in &self.handlers {
+            let ix = node("ix", &h.name);
+            for a in &h.accounts {
+                let acc = node("acc", &a.name);
+                match a.kind {
+                    AccountKind::Program => {}
+                    AccountKind::Signer => {
+                        let _ = writeln!(out, "    {acc} -->|signs| {ix}");
+                    }
+                    _ if a.writable => {
+                        let _ = writeln!(out, "    {ix} -->|writes| {acc}");
+                    }
+                    _ => {
+                        let _ = writeln!(out, "    {ix} -.-> {acc}");
+                    }
+                }
+            }
+            for c in &h.cpis {
+                let edge = if c.signed { "CPI, PDA-signed" } else { "CPI" };
+                let _ = writeln!(out, "    {ix} ==>|\"{edge}\"| {}", node("prog", &c.program));
+            }
+        }
+        out
+    }
+
+    /// Initial-message section for the investigation agent.
+    pub fn context_section(&self) -> String {
+        format!(
+            "\n\n## Trust Boundaries\n\
+             Extracted from the source: instruction handlers, the accounts each takes \
+             (signers sign, `writes` edges are mutable, dotted edges are read-only) and \
+             their CPIs. Handlers and PDAs form the program boundary; every other account \
+             is attacker-suppliable unless a constraint pins it.\n\
+             ```mermaid\n{}```",
+            self.to_mermaid()
+        )
+    }
+}
+
+/// Build the graph for `repo_path`. Unparseable files are skipped; a repo
+/// without a `#[program]` module yields an empty graph.
+pub fn build(repo: &str, repo_path: &Path) -> TrustGraph {
+    let mut catalog = Catalog::default();
+    for path in collect_rust_files(repo_path).unwrap_or_default() {
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(ast) = syn::parse_file(&content) else {
+            continue;
+        };
+        let module = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        catalog.add_file(&ast, &module);
+    }
+    catalog.graph(repo)
+}
+
+/// Everything the graph is assembled from, across all files of a repo.
+#[derive(Default)]
+struct Catalog {
+    /// `Accounts` struct name -> fields.
+    structs: HashMap<String, Vec<AccountUse>>,
+    /// `Accounts` struct name -> its sysvar fields.
+    sysvars: HashMap<String, Vec<SysvarUse>>,
+    /// Handler name -> `Accounts` struct name and data parameters, in
+    /// declaration order.
+    handlers: Vec<(String, Option<String>, Vec<HandlerArg>)>,
+    /// Function key -> its direct CPIs and callees. Keys are `module::name`
+    /// and bare `name`; bare names shared by several functions are dropped.
+    functions: HashMap<String, FnFacts>,
+    ambiguous: HashSet<String>,
+}
+
+#[derive(Default, Clone)]
+struct FnFacts {
+    cpis: Vec<Cpi>,
+    /// Call keys, most specific form first (`deposit::handler`, `handler`).
+    calls: Vec<Vec<String>>,
+}
+
+impl Catalog {
+    fn add_file(&mut self, ast: &syn::File, module: &str) {
+        let mut collector = Collector {
+            catalog: self,
+            modules: vec![module.to_string()],
+            in_program: false,
+        };
+        collector.visit_file(ast);
+    }
+
+    fn insert_fn(&mut self, module: &str, name: &str, facts: FnFacts) {
+        self.functions
+            .insert(format!("{module}::{name}"), facts.clone());
+        if self.ambiguous.contains(name) {
+            return;
+        }
+        if self.functions.insert(name.to_string(), facts).is_some() {
+            self.functions.remove(name);
+            self.ambiguous.insert(name.to_string());
+        }
+    }
+
+    fn graph(&self, repo: &str) -> TrustGraph {
+        let handlers = self
+            .handlers
+            .iter()
+            .take(MAX_HANDLERS)
+            .map(|(name, context, args)| {
+                let mut cpis = Vec::new();
+                let mut seen = HashSet::new();
+                self.collect_cpis(&[format!("program::{name}")], 0, &mut seen, &mut cpis);
+                cpis.sort();
+                cpis.dedup();
+                Handler {
+                    name: name.clone(),
+                    accounts: fields_of(&self.structs, context.as_ref()),
+                    cpis,
+                    context: context.clone(),
+                    args: args.clone(),
+                    sysvars: fields_of(&self.sysvars, context.as_ref()),
+                }
+            })
+            .collect();
+        TrustGraph {
+            repo: repo.to_string(),
+            handlers,
+        }
+    }
+
+    fn collect_cpis(
+        &self,
+        keys: &[String],
+        depth: usize,
+        seen: &mut HashSet<String>,
+        out: &mut Vec<Cpi>,
+    ) {
+        let Some((key, facts)) = keys
+            .iter()
+            .find_map(|k| self.functions.get(k).map(|f| (k, f)))
+        else {
+            return;
+        };
+        if !seen.insert(key.clone()) {
+            return;
+        }
+        out.extend(facts.cpis.iter().cloned());
+        if depth < MAX_CALL_DEPTH {
+            for call in &facts.calls {
+                self.collect_cpis(call, depth + 1, seen, out);
+            }
+        }
+    }
+}
+
+struct Collector<'a> {
+    catalog: &'a mut Catalog,
+    /// File stem, then inline `mod` names.
+    modules: Vec<String>,
+    in_program: bool,
+}
+
+impl Collector<'_> {
+    fn module(&self) -> &str {
+        self.modules.last().map_or("", String::as_str)
+    }
+
+    fn add_fn(&mut self, name: &str, block: &syn::Block) {
+        let mut body = BodyVisitor::default();
+        body.visit_block(block);
+        let facts = FnFacts {
+            cpis: body.cpis,
+            calls: body.calls,
+        };
+        let module = if self.in_program {
+            "program".to_string()
+        } else {
+            self.module().to_string()
+        };
+        self.catalog.insert_fn(&module, name, facts);
+    }
+}
+
+impl<'ast> Visit<'ast> for Collector<'_> {
+    fn visit_item_mod(&mut self, node: &'ast ItemMod) {
+        let is_program = node.attrs.iter().any(|a| a.path().is_ident("program"));
+        let outer = std::mem::replace(&mut self.in_program, is_program);
+        self.modules.push(node.ident.to_string());
+        syn::visit::visit_item_mod(self, node);
+        self.modules.pop();
+        self.in_program = outer;
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        let name = node.sig.ident.to_string();
+        if self.in_program && matches!(node.vis, syn::Visibility::Public(_)) {
+            self.catalog.handlers.push((
+                name.clone(),
+                context_struct(&node.sig),
+                handler_args(&node.sig),
+            ));
+        }
+        self.add_fn(&name, &node.block);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.add_fn(&node.sig.ident.to_string(), &node.block);
+    }
+
+    fn visit_item_struct(&mut self, node: &'ast ItemStruct) {
+        if is_accounts_struct(node) {
+            let fields = node.fields.iter().filter_map(account_use).collect();
+            self.catalog.structs.insert(node.ident.to_string(), fields);
+            let sysvars = node.fields.iter().filter_map(sysvar_use).collect();
+            self.catalog.sysvars.insert(node.ident.to_string(), sysvars);
+        }
+    }
+}
+
+/// CPIs and calls in one function body.
+#[derive(Default)]
+struct BodyVisitor {
+    cpis: Vec<Cpi>,
+    calls: Vec<Vec<String>>,
+}
+
+impl<'ast> Visit<'ast> for BodyVisitor {
+    fn visit_expr_call(&mut self, call: &'ast ExprCall) {
+        if let Expr::Path(p) = &*call.func {
+            let segments: Vec<String> = p
+                .path
+                .segments
+                .iter()
+                .map(|s| s.ident.to_string())
+                .collect();
+            let last = segments.last().map_or("", String::as_str);
+            let parent = segments
+                .len()
+                .checked_sub(2)
+                .map_or("", |i| segments[i].as_str());
+            match (parent, last) {
+                ("CpiContext", "new" | "new_with_signer") => {
+                    if let Some(program) = call.args.first().map(program_of) {
+                        self.cpis.push(Cpi {
+                            program,
+                            signed: last == "new_with_signer",
+                        });
+                    }
+                }
+                (_, "invoke" | "invoke_signed") => {
+                    let program = call
+                        .args
+                        .first()
+                        .and_then(instruction_module)
+                        .unwrap_or_else(|| "unknown program".into());
+                    self.cpis.push(Cpi {
+                        program,
+                        signed: last == "invoke_signed",
+                    });
+                }
+                _ => {
+                    let mut keys = Vec::new();
+                    if !parent.is_empty() {
+                        keys.push(format!("{parent}::{last}"));
+                    }
+                    keys.push(last.to_string());
+                    self.calls.push(keys);
+                }
+            }
+        }
+        syn::visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast ExprMethodCall) {
+        // Children first, so a `CpiContext::new(..).with_signer(..)` receiver
+        // is the last CPI recorded.
+        syn::visit::visit_expr_method_call(self, call);
+        let method = call.method.to_string();
+        if method == "with_signer"
+            && receiver_is_cpi_context(&call.receiver)
+            && let Some(last) = self.cpis.last_mut()
+        {
+            last.signed = true;
+        }
+        self.calls.push(vec![method]);
+    }
+}
+
+/// Whether a `with_signer` receiver is a `CpiContext::new(..)` call.
+fn receiver_is_cpi_context(expr: &Expr) -> bool {
+    let text = expr.to_token_stream().to_string().replace(' ', "");
+    text.contains("CpiContext::new(")
+}
+
+/// `ctx.accounts.token_program.to_account_info()` -> `token_program`.
+fn program_of(expr: &Expr) -> String {
+    let text = expr.to_token_stream().to_string().replace(' ', "");
+    let field = text
+        .split('.')
+        .skip_while(|s| *s != "accounts")
+        .nth(1)
+        .or_else(|| text.split('.').find(|s| s.contains("program")))
+        .unwrap_or(&text);
+    field
+        .trim_start_matches('&')
+        .split(['(', ')'])
+        .next()
+        .unwrap_or(field)
+        .to_string()
+}
+
+/// `&system_instruction::transfer(..)` -> `system_instruction`.
+fn instruction_module(expr: &Expr) -> Option<String> {
+    match expr {
+        Expr::Reference(r) => instruction_module(&r.expr),
+        Expr::Call(c) => match &*c.func {
+            Expr::Path(p) if p.path.segments.len() >= 2 => {
+                let n = p.path.segments.len();
+                Some(p.path.segments[n - 2].ident.to_string())
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `X` from a `Context<X>` first parameter.
+fn context_struct(sig: &syn::Signature) -> Option<String> {
+    let FnArg::Typed(arg) = sig.inputs.first()? else {
+        return None;
+    };
+    let syn::Type::Path(tp) = &*arg.ty else {
+        return None;
+    };
+    let seg = tp.path.segments.last()?;
+    if seg.ident != "Context" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &seg.arguments else {
+        return None;
+    };
+    args.args.iter().find_map(|a| match a {
+        syn::GenericArgument::Type(syn::Type::Path(p)) => {
+            p.path.segments.last().map(|s| s.ident.to_string())
+        }
+        _ => None,
+    })
+}
+
+/// The fields recorded for `Accounts` struct `name`, if any.
+fn fields_of<T: Clone>(map: &HashMap<String, Vec<T>>, name: Option<&String>) -> Vec<T> {
+    name.and_then(|s| map.get(s)).cloned().unwrap_or_default()
+}
+
+/// Every typed parameter after the first (the `Context`).
+fn handler_args(sig: &syn::Signature) -> Vec<HandlerArg> {
+    sig.inputs
+        .iter()
+        .skip(1)
+        .filter_map(|arg| match arg {
+            FnArg::Typed(t) => Some(HandlerArg {
+                name: t.pat.to_token_stream().to_string().replace(' ', ""),
+                ty: t.ty.to_token_stream().to_string().replace(' ', ""),
+            }),
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+fn is_accounts_struct(item: &ItemStruct) -> bool {
+    item.attrs.iter().any(|a| {
+        a.path().is_ident("derive") && a.to_token_stream().to_string().contains("Accounts")
+    })
+}
+
+fn sysvar_use(field: &syn::Field) -> Option<SysvarUse> {
+    let syn::Type::Path(tp) = &field.ty else {
+        return None;
+    };
+    let seg = tp.path.segments.last()?;
+    if seg.ident != "Sysvar" {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &seg.arguments else {
+        return None;
+    };
+    let ty = args.args.iter().find_map(|a| match a {
+        syn::GenericArgument::Type(syn::Type::Path(p)) => {
+            p.path.segments.last().map(|s| s.ident.to_string())
+        }
+        _ => None,
+    })?;
+    Some(SysvarUse {
+        name: field.ident.as_ref()?.to_string(),
+        ty,
+    })
+}
+
+fn account_use(field: &syn::Field) -> Option<AccountUse> {
+    let name = field.ident.as_ref()?.to_string();
+    let constraints = AccountConstraints::parse(&field.attrs);
+    let syn::Type::Path(tp) = &field.ty else {
+        return None;
+    };
+    let seg = tp.path.segments.last()?;
+    let wrapper = seg.ident.to_string();
+    if wrapper == "Sysvar" {
+        return None;
+    }
+    let inner = match &seg.arguments {
+        syn::PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|a| match a {
+                syn::GenericArgument::Type(syn::Type::Path(p)) => {
+                    p.path.segments.last().map(|s| s.ident.to_string())
+                }
+                _ => None,
+            })
+            .next_back()
+            .unwrap_or_default(),
+        _ => String::new(),
+    };
+    let kind = if wrapper == "Signer" || constraints.signer {
+        AccountKind::Signer
+    } else if matches!(wrapper.as_str(), "Program" | "Interface") {
+        AccountKind::Program
+    } else if constraints.seeds {
+        AccountKind::Pda
+    } else if matches!(wrapper.as_str(), "AccountInfo" | "UncheckedAccount") {
+        AccountKind::Unchecked
+    } else {
+        AccountKind::Typed
+    };
+    let ty = if matches!(kind, AccountKind::Typed | AccountKind::Program) {
+        inner
+    } else {
+        String::new()
+    };
+    Some(AccountUse {
+        name,
+        kind,
+        ty,
+        writable: constraints.mutable,
+    })
+}
+
+/// Mermaid node ID: `{prefix}_{name}` with anything but `[A-Za-z0-9_]` replaced.
+pub(super) fn node(prefix: &str, name: &str) -> String {
+    let id: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{prefix}_{id}")
+}
+
+/// Text safe inside a quoted Mermaid label.
+pub(super) fn label(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
+
+fn account_node(a: &AccountUse) -> String {
+    let id = node("acc", &a.name);
+    let name = label(&a.name);
+    match a.kind {
+        AccountKind::Signer => format!("{id}[/\"{name} (signer)\"/]"),
+        AccountKind::Pda => format!("{id}[(\"{name} (PDA)\")]"),
+        AccountKind::Unchecked => format!("{id}{{{{\"{name} (unchecked)\"}}}}"),
+        AccountKind::Program => format!("{id}[[\"{name}\"]]"),
+        AccountKind::Typed if a.ty.is_empty() => format!("{id}[\"{name}\"]"),
+        AccountKind::Typed => format!("{id}[\"{name}: {}\"]", label(&a.ty)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_from_source(repo: &str, content: &str) -> TrustGraph {
+        let mut catalog = Catalog::default();
+        catalog.add_file(&syn::parse_file(content).unwrap(), "lib");
+        catalog.graph(repo)
+    }
+
+    const VAULT: &str = r#"
+use anchor_lang::prelude::*;
+
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn deposit(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        instructions::deposit::handler(ctx, amount)
+    }
+
+    pub fn withdraw(ctx: Context<Withdraw>, amount: u64) -> Result<()> {
+        let seeds: &[&[u8]] = &[b"vault", &[ctx.accounts.vault.bump]];
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer { from: x, to: y, authority: z },
+            )
+            .with_signer(&[seeds]),
+            amount,
+        )?;
+        Ok(())
+    }
+}
+
+mod deposit {
+    pub fn handler(ctx: Context<Deposit>, amount: u64) -> Result<()> {
+        invoke(&system_instruction::transfer(&a, &b, amount), &[])?;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Deposit<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"vault"], bump)]
+    pub vault: Account<'info, Vault>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    pub user: Signer<'info>,
+    #[account(mut, seeds = [b"vault"], bump)]
+    pub vault: Account<'info, Vault>,
+    /// CHECK: any destination
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+    pub mint: Account<'info, Mint>,
+    pub token_program: Program<'info, Token>,
+}
+"#;
+
+    #[test]
+    fn extracts_handlers_accounts_and_cpis() {
+        let graph = build_from_source("vault", VAULT);
+        let names: Vec<&str> = graph.handlers.iter().map(|h| h.name.as_str()).collect();
+        assert_eq!(names, vec!["deposit", "withdraw"]);
+
+        let deposit = &graph.handlers[0];
+        let kinds: Vec<(&str, AccountKind, bool)> = deposit
+            .accounts
+            .iter()
+            .map(|a| (a.name.as_str(), a.kind, a.writable))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("user", AccountKind::Signer, true),
+                ("vault", AccountKind::Pda, true),
+                ("system_program", AccountKind::Program, false),
+            ]
+        );
+        // Followed through `instructions::deposit::handler`.
+        assert_eq!(
+            deposit.cpis,
+            vec![Cpi {
+                program: "system_instruction".into(),
+                signed: false
+            }]
+        );
+
+        let withdraw = &graph.handlers[1];
+        assert_eq!(
+            withdraw.cpis,
+            vec![Cpi {
+                program: "token_program".into(),
+                signed: true
+            }]
+        );
+        let mint = withdraw.accounts.iter().find(|a| a.name == "mint").unwrap();
+        assert_eq!((mint.kind, mint.ty.as_str()), (AccountKind::Typed, "Mint"));
+
+        assert_eq!(deposit.context.as_deref(), Some("Deposit"));
+        assert_eq!(
+            deposit.args,
+            vec![HandlerArg {
+                name: "amount".into(),
+                ty: "u64".into()
+            }]
+        );
+        assert_eq!(
+            deposit.sysvars,
+            vec![SysvarUse {
+                name: "rent".into(),
+                ty: "Rent".into()
+            }]
+        );
+        assert!(withdraw.sysvars.is_empty());
+    }
+
+    #[test]
+    fn renders_trust_boundary() {
+        let mermaid = build_from_source("vault", VAULT).to_mermaid();
+        assert!(mermaid.starts_with("flowchart LR\n    subgraph program[\"vault\"]\n"));
+        let boundary = &mermaid[..mermaid.find("    end\n").unwrap()];
+        assert!(boundary.contains("ix_deposit([\"deposit\"])"));
+        assert!(boundary.contains("acc_vault[(\"vault (PDA)\")]"));
+        assert!(!boundary.contains("acc_user"));
+        for line in [
+            "acc_user[/\"user (signer)\"/]",
+            "acc_destination{{\"destination (unchecked)\"}}",
+            "acc_mint[\"mint: Mint\"]",
+            "prog_token_program[[\"token_program\"]]",
+            "acc_user -->|signs| ix_withdraw",
+            "ix_withdraw -->|writes| acc_destination",
+            "ix_withdraw -.-> acc_mint",
+            "ix_withdraw ==>|\"CPI, PDA-signed\"| prog_token_program",
+            "ix_deposit ==>|\"CPI\"| prog_system_instruction",
+        ] {
+            assert!(mermaid.contains(line), "missing `{line}` in:\n{mermaid}");
+        }
+        // Declared but never invoked.
+        assert!(!mermaid.contains("prog_system_program"));
+    }
+
+    #[test]
+    fn no_program_module_no_graph() {
+        let graph = build_from_source("lib", "pub fn helper() {}");
+        assert!(graph.is_empty());
+    }
+}
@@ -3,12 +3,13 @@
 //! Dismissed) with reasoning.
 
 use super::SecurityFinding;
-use super::ValidationStatus;
-use super::agent_review::AgentFinding;
-use crate::config::AgentReviewConfig;
+use super::agent_review::{AgentFinding, ReviewStats, timed_turn};
+use super::sampling::{self, PatternSample};
+use super::{BlindReview, ValidationRecord, ValidationStatus};
+use crate::config::{AgentReviewConfig, ValidationConfig};
 use crate::llm::{
     ContentBlock, ConversationMessage, ConverseContext, LlmClient, ModelRouter, Role, StopReason,
-    TaskKind, estimate_cost_usd,
+    TaskKind,
 };
 use crate::security::agent_tools;
 use anyhow::Result;
@@ -16,7 +17,7 @@ use serde::{Deserialize, Serialize};
 use std::path::Path;
 use tracing::{debug, info, warn};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Verdict {
     Confirmed,
     Disputed,
@@ -38,6 +39,47 @@ pub struct VerdictEntry {
     pub title: String,
     pub verdict: String,
     pub reasoning: String,
+    /// The validator's own severity, asked for in blind reviews.
+    #[serde(default)]
+    pub severity: Option<String>,
+}
+
+/// What one validation conversation is told besides the findings.
+#[derive(Debug, Clone, Copy, Default)]
+struct Brief<'a> {
+    /// Extra initial-message sections, such as
+    /// [`cross_repo::context_section`](super::cross_repo::context_section).
+    context: &'a str,
+    /// Withhold the investigator's severity and ask for the validator's own.
+    blind: bool,
+}
+
+/// Appended to the initial message of a blind review.
+const BLIND_INSTRUCTIONS: &str = "\n\nThe investigator's severity for these findings is \
+     withheld. Rate each one yourself from what you read in the code (impact, \
+     reachability, preconditions), independently of your verdict, and add it to \
+     each entry as \"severity\": \"Critical|High|Medium|Low|Info\".";
+
+/// Fields of a finding a blind validator doesn't see: the investigator's
+/// severity and earlier verdicts.
+const BLIND_WITHHELD: &[&str] = &[
+    "severity",
+    "validation_status",
+    "validation_reasoning",
+    "validation_cached",
+    "provenance",
+    "blind_review",
+];
+
+/// `finding` as the validator sees it.
+fn finding_json(finding: &SecurityFinding, blind: bool) -> String {
+    let mut value = serde_json::to_value(finding).unwrap_or_default();
+    if blind && let Some(fields) = value.as_object_mut() {
+        for key in BLIND_WITHHELD {
+            fields.remove(*key);
+        }
+    }
+    value.to_string()
 }
 
 const VALIDATOR_PROMPT: &str = r#"You are a security auditor reviewing another auditor's findings against a Solana program repository. Your job is adversarial: for each finding below, use the provided tools to read the cited code and try to DISPROVE it.
@@ -71,14 +113,27 @@ pub async fn validate(
     findings: &[AgentFinding],
     config: &AgentReviewConfig,
 ) -> Result<Vec<ValidatedFinding>> {
+    validate_with_stats(llm, repo_path, findings, config)
+        .await
+        .map(|(validated, _)| validated)
+}
+
+/// Like [`validate`], also returning the turns and cost the validator spent.
+pub async fn validate_with_stats(
+    llm: &LlmClient,
+    repo_path: &Path,
+    findings: &[AgentFinding],
+    config: &AgentReviewConfig,
+) -> Result<(Vec<ValidatedFinding>, ReviewStats)> {
     if findings.is_empty() {
-        return Ok(Vec::new());
+        return Ok((Vec::new(), ReviewStats::default()));
     }
 
     let tools = agent_tools::tool_definitions();
     let mut messages: Vec<ConversationMessage> = Vec::new();
     let mut turns: u32 = 0;
     let mut total_cost_usd: f64 = 0.0;
+    let mut timings = ReviewStats::default();
     // Validator gets 30% of investigator budget, capped at 5 turns
     let max_turns = (config.max_turns * 30 / 100).clamp(2, 5);
 
@@ -133,9 +188,12 @@ pub async fn validate(
             break;
         }
 
-        let response = match llm
-            .converse(VALIDATOR_PROMPT, &messages, &tools, Some(&ctx))
-            .await
+        let response = match timed_turn(
+            config.turn_timeout(),
+            &mut timings,
+            llm.converse_compacting(VALIDATOR_PROMPT, &mut messages, &tools, Some(&ctx)),
+        )
+        .await
         {
             Ok(r) => r,
             Err(e) => {
@@ -148,7 +206,7 @@ pub async fn validate(
         };
 
         turns += 1;
-        let cost = estimate_cost_usd(&response.usage, llm.model());
+        let cost = llm.estimate_cost(&response.usage);
         total_cost_usd += cost;
 
         debug!(
@@ -183,11 +241,14 @@ pub async fn validate(
         let mut tool_results = Vec::new();
         for (id, name, input) in &tool_uses {
             debug!(tool = %name, "validator executing tool");
-            let (result, is_error) = agent_tools::dispatch(repo_path, name, input);
+            let outcome =
+                agent_tools::dispatch_timed(repo_path, name, input, config.tool_timeout(), None)
+                    .await;
+            timings.record_tool(name, &outcome);
             tool_results.push(ContentBlock::ToolResult {
                 tool_use_id: id.clone(),
-                content: result,
-                is_error,
+                content: outcome.result,
+                is_error: outcome.is_error,
             });
         }
 
@@ -212,11 +273,14 @@ pub async fn validate(
                     .into(),
             }],
         });
-        if let Ok(response) = llm
-            .converse(VALIDATOR_PROMPT, &messages, &[], Some(&ctx))
-            .await
+        if let Ok(response) = timed_turn(
+            config.turn_timeout(),
+            &mut timings,
+            llm.converse_compacting(VALIDATOR_PROMPT, &mut messages, &[], Some(&ctx)),
+        )
+        .await
         {
-            let cost = estimate_cost_usd(&response.usage, llm.model());
+            let cost = llm.estimate_cost(&response.usage);
             total_cost_usd += cost;
             turns += 1;
             for block in &response.content {
@@ -241,29 +305,17 @@ pub async fn validate(
     let validated: Vec<ValidatedFinding> = findings
         .iter()
         .enumerate()
-        .map(|(i, f)| {
-            // Try index match first
-            let matched = verdicts.iter().find(|v| v.index == Some(i)).or_else(|| {
-                // Fall back to fuzzy title match
-                verdicts.iter().find(|v| {
-                    let vt = v.title.to_lowercase();
-                    let ft = f.title.to_lowercase();
-                    ft.contains(&vt) || vt.contains(&ft)
-                })
-            });
-
-            match matched {
-                Some(v) => ValidatedFinding {
-                    finding: f.clone(),
-                    verdict: parse_verdict(&v.verdict),
-                    reasoning: v.reasoning.clone(),
-                },
-                None => ValidatedFinding {
-                    finding: f.clone(),
-                    verdict: Verdict::Disputed,
-                    reasoning: "No verdict provided by validator".into(),
-                },
-            }
+        .map(|(i, f)| match match_verdict(&verdicts, i, &f.title) {
+            Some(v) => ValidatedFinding {
+                finding: f.clone(),
+                verdict: parse_verdict(&v.verdict),
+                reasoning: v.reasoning.clone(),
+            },
+            None => ValidatedFinding {
+                finding: f.clone(),
+                verdict: Verdict::Disputed,
+                reasoning: "No verdict provided by validator".into(),
+            },
         })
         .collect();
 
@@ -274,36 +326,46 @@ pub async fn validate(
         "validator pass complete"
     );
 
-    Ok(validated)
+    let stats = ReviewStats {
+        turns,
+        total_cost_usd,
+        ..timings
+    };
+    Ok((validated, stats))
 }
 
 const VALIDATION_BATCH_SIZE: usize = 20;
+/// Turn bounds for one batch. The floor gives every batch a chance at
+/// verdicts even when the repo budget is spent.
+const MIN_BATCH_TURNS: u32 = 2;
+const MAX_BATCH_TURNS: u32 = 5;
 
-/// Run one validation conversation on a batch of findings.
+/// Run one validation conversation on a batch of findings, within
+/// `max_turns` and `cost_limit_usd`.
 ///
 /// `batch` carries `(original_index, finding_ref)` so verdicts use global indices.
 async fn validate_batch(
     llm: &LlmClient,
     repo_path: &Path,
     batch: &[(usize, &SecurityFinding)],
+    max_turns: u32,
+    cost_limit_usd: f64,
     config: &AgentReviewConfig,
-) -> Vec<VerdictEntry> {
+    brief: Brief<'_>,
+) -> (Vec<VerdictEntry>, ReviewStats) {
     let tools = agent_tools::tool_definitions();
     let mut messages: Vec<ConversationMessage> = Vec::new();
     let mut turns: u32 = 0;
     let mut total_cost_usd: f64 = 0.0;
-    let max_turns = (config.max_turns * 30 / 100).clamp(2, 5);
+    let mut timings = ReviewStats::default();
 
     let indexed_findings: String = batch
         .iter()
-        .map(|(i, f)| {
-            format!(
-                "Finding #{i}: {}",
-                serde_json::to_string(&f).unwrap_or_else(|_| format!("{f:?}"))
-            )
-        })
+        .map(|(i, f)| format!("Finding #{i}: {}", finding_json(f, brief.blind)))
         .collect::<Vec<_>>()
         .join("\n");
+    let context = brief.context;
+    let blind = if brief.blind { BLIND_INSTRUCTIONS } else { "" };
 
     let repo_abs = repo_path
         .canonicalize()
@@ -314,7 +376,7 @@ async fn validate_batch(
          For each finding, read the cited code and determine whether \
          the vulnerability is real, overstated, or a false positive.\n\
          Include the finding index (e.g. #0, #1) in your verdict for reliable matching.\n\n\
-         {indexed_findings}",
+         {indexed_findings}{context}{blind}",
         batch.len(),
         repo_abs.display(),
     );
@@ -327,13 +389,16 @@ async fn validate_batch(
     let ctx = ConverseContext { repo_path };
 
     loop {
-        if turns >= max_turns || total_cost_usd >= config.cost_limit_usd {
+        if turns >= max_turns || total_cost_usd >= cost_limit_usd {
             break;
         }
 
-        let response = match llm
-            .converse(VALIDATOR_PROMPT, &messages, &tools, Some(&ctx))
-            .await
+        let response = match timed_turn(
+            config.turn_timeout(),
+            &mut timings,
+            llm.converse_compacting(VALIDATOR_PROMPT, &mut messages, &tools, Some(&ctx)),
+        )
+        .await
         {
             Ok(r) => r,
             Err(e) => {
@@ -343,7 +408,7 @@ async fn validate_batch(
         };
 
         turns += 1;
-        total_cost_usd += estimate_cost_usd(&response.usage, llm.model());
+        total_cost_usd += llm.estimate_cost(&response.usage);
 
         let tool_uses: Vec<_> = response
             .content
@@ -367,11 +432,14 @@ async fn validate_batch(
 
         let mut tool_results = Vec::new();
         for (id, name, input) in &tool_uses {
-            let (result, is_error) = agent_tools::dispatch(repo_path, name, input);
+            let outcome =
+                agent_tools::dispatch_timed(repo_path, name, input, config.tool_timeout(), None)
+                    .await;
+            timings.record_tool(name, &outcome);
             tool_results.push(ContentBlock::ToolResult {
                 tool_use_id: id.clone(),
-                content: result,
-                is_error,
+                content: outcome.result,
+                is_error: outcome.is_error,
             });
         }
 
@@ -384,19 +452,31 @@ async fn validate_batch(
     let mut verdicts = extract_verdicts(&messages);
 
     if verdicts.is_empty() {
+        let severity = if brief.blind {
+            ", severity (Critical|High|Medium|Low|Info)"
+        } else {
+            ""
+        };
         messages.push(ConversationMessage {
             role: Role::User,
             content: vec![ContentBlock::Text {
-                text: "You have run out of investigation turns. Based on everything you have \
-                       read so far, produce your final verdicts NOW as a JSON array. \
-                       Each entry must have: index, title, verdict (Confirmed|Disputed|Dismissed), reasoning."
-                    .into(),
+                text: format!(
+                    "You have run out of investigation turns. Based on everything you have \
+                     read so far, produce your final verdicts NOW as a JSON array. \
+                     Each entry must have: index, title, verdict (Confirmed|Disputed|Dismissed), \
+                     reasoning{severity}."
+                ),
             }],
         });
-        if let Ok(response) = llm
-            .converse(VALIDATOR_PROMPT, &messages, &[], Some(&ctx))
-            .await
+        if let Ok(response) = timed_turn(
+            config.turn_timeout(),
+            &mut timings,
+            llm.converse_compacting(VALIDATOR_PROMPT, &mut messages, &[], Some(&ctx)),
+        )
+        .await
         {
+            turns += 1;
+            total_cost_usd += llm.estimate_cost(&response.usage);
             messages.push(ConversationMessage {
                 role: Role::Assistant,
                 content: response.content,
@@ -405,7 +485,12 @@ async fn validate_batch(
         }
     }
 
-    verdicts
+    let stats = ReviewStats {
+        turns,
+        total_cost_usd,
+        ..timings
+    };
+    (verdicts, stats)
 }
 
 /// Validate findings in-place using the ModelRouter, then filter/downgrade.
@@ -414,14 +499,105 @@ async fn validate_batch(
 /// - Annotates each `SecurityFinding` with `ValidationStatus` and `validation_reasoning`
 /// - Removes findings with `Dismissed` status
 /// - Downgrades severity by one level for `Disputed` findings
+///
+/// `config` is the whole validation budget, shared by all batches (see
+/// [`RepoBudget::validation`](super::agent_review::RepoBudget::validation)).
+/// Returns what was actually spent.
 pub async fn validate_findings(
     findings: &mut Vec<SecurityFinding>,
     router: &ModelRouter,
     repo_path: &Path,
     config: &AgentReviewConfig,
-) -> Result<()> {
+) -> Result<ReviewStats> {
+    let spent = annotate_findings(findings, router, repo_path, config, Brief::default()).await?;
+    apply_verdicts(findings);
+    info!(
+        remaining = findings.len(),
+        turns = spent.turns,
+        cost = format!("${:.4}", spent.total_cost_usd),
+        "validate_findings pass complete"
+    );
+    Ok(spent)
+}
+
+/// Like [`validate_findings`], but large sets are sampled per
+/// [`sampling::select`]: only the selected findings are sent to the
+/// validator, the rest stay Unvalidated. Returns the spend and, when
+/// sampling kicked in, the per-pattern verdict tallies (repo left blank).
+pub async fn validate_sampled(
+    findings: &mut Vec<SecurityFinding>,
+    router: &ModelRouter,
+    repo_path: &Path,
+    config: &AgentReviewConfig,
+    sampling_cfg: &ValidationConfig,
+) -> Result<(ReviewStats, Vec<PatternSample>)> {
+    let result = annotate_sampled(findings, router, repo_path, config, sampling_cfg, "").await?;
+    apply_verdicts(findings);
+    Ok(result)
+}
+
+/// [`validate_sampled`] without [`apply_verdicts`], so the verdicts can be
+/// recorded before Dismissed findings are dropped. Findings whose verdict is
+/// already `validation_cached` are neither sent nor sampled. `context` is
+/// extra initial-message sections, such as
+/// [`cross_repo::context_section`](super::cross_repo::context_section).
+/// With `sampling_cfg.blind` the validator doesn't see the findings'
+/// severities and rates them itself.
+pub async fn annotate_sampled(
+    findings: &mut [SecurityFinding],
+    router: &ModelRouter,
+    repo_path: &Path,
+    config: &AgentReviewConfig,
+    sampling_cfg: &ValidationConfig,
+    context: &str,
+) -> Result<(ReviewStats, Vec<PatternSample>)> {
+    let pending: Vec<usize> = (0..findings.len())
+        .filter(|&i| !findings[i].validation_cached)
+        .collect();
+    let mut batch: Vec<SecurityFinding> = pending.iter().map(|&i| findings[i].clone()).collect();
+    let brief = Brief {
+        context,
+        blind: sampling_cfg.blind,
+    };
+    let (spent, samples) = match sampling::select(&batch, sampling_cfg) {
+        None => (
+            annotate_findings(&mut batch, router, repo_path, config, brief).await?,
+            Vec::new(),
+        ),
+        Some(selected) => {
+            info!(
+                findings = batch.len(),
+                sampled = selected.len(),
+                "sampling findings for validation"
+            );
+            let mut sample: Vec<SecurityFinding> =
+                selected.iter().map(|&i| batch[i].clone()).collect();
+            let spent = annotate_findings(&mut sample, router, repo_path, config, brief).await?;
+            for (&i, validated) in selected.iter().zip(sample) {
+                batch[i] = validated;
+            }
+            (spent, sampling::summarize(&batch, &selected))
+        }
+    };
+    for (&i, validated) in pending.iter().zip(batch) {
+        findings[i] = validated;
+    }
+    Ok((spent, samples))
+}
+
+/// Run the validator over `findings` in batches and record each verdict on
+/// its finding, and in a blind review the validator's severity. Nothing is
+/// removed or downgraded yet.
+async fn annotate_findings(
+    findings: &mut [SecurityFinding],
+    router: &ModelRouter,
+    repo_path: &Path,
+    config: &AgentReviewConfig,
+    brief: Brief<'_>,
+) -> Result<ReviewStats> {
+    let mut spent = ReviewStats::default();
     if findings.is_empty() {
-        return Ok(());
+        return Ok(spent);
     }
 
     let llm = router.client_for(TaskKind::Validation);
@@ -443,29 +619,30 @@ pub async fn validate_findings(
             size = batch.len(),
             "validating batch"
         );
-        let verdicts = validate_batch(llm, repo_path, batch, config).await;
+        let max_turns = config
+            .max_turns
+            .saturating_sub(spent.turns)
+            .clamp(MIN_BATCH_TURNS, MAX_BATCH_TURNS);
+        let cost_left = (config.cost_limit_usd - spent.total_cost_usd).max(0.0);
+        let (verdicts, stats) =
+            validate_batch(llm, repo_path, batch, max_turns, cost_left, config, brief).await;
         info!(
             batch = batch_num + 1,
             verdicts = verdicts.len(),
+            turns = stats.turns,
+            cost = format!("${:.4}", stats.total_cost_usd),
             "batch complete"
         );
+        spent.turns += stats.turns;
+        spent.total_cost_usd += stats.total_cost_usd;
+        spent.add_timings(&stats);
         all_verdicts.extend(verdicts);
     }
 
     // Annotate findings in-place (index-first, fuzzy title fallback).
+    let validated_at = chrono::Utc::now().to_rfc3339();
     for (i, finding) in findings.iter_mut().enumerate() {
-        let matched = all_verdicts
-            .iter()
-            .find(|v| v.index == Some(i))
-            .or_else(|| {
-                all_verdicts.iter().find(|v| {
-                    let vt = v.title.to_lowercase();
-                    let ft = finding.title.to_lowercase();
-                    ft.contains(&vt) || vt.contains(&ft)
-                })
-            });
-
-        match matched {
+        match match_verdict(&all_verdicts, i, &finding.title) {
             Some(v) => {
                 finding.validation_status = match parse_verdict(&v.verdict) {
                     Verdict::Confirmed => ValidationStatus::Confirmed,
@@ -473,42 +650,66 @@ pub async fn validate_findings(
                     Verdict::Dismissed => ValidationStatus::Dismissed,
                 };
                 finding.validation_reasoning = Some(v.reasoning.clone());
+                finding.blind_review = blind_review(brief.blind, &finding.severity, v);
             }
             None => {
                 finding.validation_status = ValidationStatus::Disputed;
                 finding.validation_reasoning = Some("No verdict provided by validator".into());
             }
         }
+        if let Some(p) = &mut finding.provenance {
+            p.validation = Some(ValidationRecord {
+                verdict: finding.validation_status.clone(),
+                model: Some(llm.model().to_string()),
+                validated_at: Some(validated_at.clone()),
+            });
+        }
     }
+    Ok(spent)
+}
 
-    // Remove Dismissed findings.
-    findings.retain(|f| f.validation_status != ValidationStatus::Dismissed);
+/// Both severities of a blind review; none when the review wasn't blind or
+/// the validator gave no severity.
+fn blind_review(blind: bool, investigator: &str, verdict: &VerdictEntry) -> Option<BlindReview> {
+    let validator = verdict
+        .severity
+        .as_deref()
+        .filter(|s| blind && !s.trim().is_empty())?;
+    Some(BlindReview {
+        investigator: crate::severity::normalize(investigator).into(),
+        validator: crate::severity::normalize(validator).into(),
+    })
+}
 
-    // Downgrade severity for Disputed findings.
+/// Drop Dismissed findings and downgrade Disputed ones by one level.
+pub fn apply_verdicts(findings: &mut Vec<SecurityFinding>) {
+    findings.retain(|f| f.validation_status != ValidationStatus::Dismissed);
     for finding in findings.iter_mut() {
         if finding.validation_status == ValidationStatus::Disputed {
-            finding.severity = downgrade_severity(&finding.severity);
+            finding.severity = crate::severity::downgrade(&finding.severity).into();
         }
     }
-
-    info!(
-        remaining = findings.len(),
-        "validate_findings pass complete"
-    );
-
-    Ok(())
 }
 
-fn downgrade_severity(severity: &str) -> String {
-    match severity {
-        "Critical" => "High".into(),
-        "High" => "Medium".into(),
-        "Medium" => "Low".into(),
-        _ => "Info".into(),
-    }
+/// The verdict for finding `index`: by index first, then fuzzy title match.
+pub(crate) fn match_verdict<'a>(
+    verdicts: &'a [VerdictEntry],
+    index: usize,
+    title: &str,
+) -> Option<&'a VerdictEntry> {
+    verdicts
+        .iter()
+        .find(|v| v.index == Some(index))
+        .or_else(|| {
+            let ft = title.to_lowercase();
+            verdicts.iter().find(|v| {
+                let vt = v.title.to_lowercase();
+                ft.contains(&vt) || vt.contains(&ft)
+            })
+        })
 }
 
-fn parse_verdict(s: &str) -> Verdict {
+pub(crate) fn parse_verdict(s: &str) -> Verdict {
     match s.to_lowercase().as_str() {
         "confirmed" => Verdict::Confirmed,
         "dismissed" => Verdict::Dismissed,
@@ -517,7 +718,7 @@ fn parse_verdict(s: &str) -> Verdict {
 }
 
 /// Search assistant messages in reverse for a JSON verdicts array.
-fn extract_verdicts(messages: &[ConversationMessage]) -> Vec<VerdictEntry> {
+pub(crate) fn extract_verdicts(messages: &[ConversationMessage]) -> Vec<VerdictEntry> {
     for msg in messages.iter().rev() {
         if msg.role != Role::Assistant {
             continue;
@@ -615,33 +816,6 @@ mod tests {
         assert!(matches!(parse_verdict(""), Verdict::Disputed));
     }
 
-    // -- downgrade_severity --
-
-    #[test]
-    fn downgrade_critical() {
-        assert_eq!(downgrade_severity("Critical"), "High");
-    }
-
-    #[test]
-    fn downgrade_high() {
-        assert_eq!(downgrade_severity("High"), "Medium");
-    }
-
-    #[test]
-    fn downgrade_medium() {
-        assert_eq!(downgrade_severity("Medium"), "Low");
-    }
-
-    #[test]
-    fn downgrade_low() {
-        assert_eq!(downgrade_severity("Low"), "Info");
-    }
-
-    #[test]
-    fn downgrade_info() {
-        assert_eq!(downgrade_severity("Info"), "Info");
-    }
-
     // -- try_parse_verdicts --
 
     const SAMPLE_VERDICT_JSON: &str = r#"[{"title":"Missing Signer","verdict":"Confirmed","reasoning":"The check is indeed missing"}]"#;
@@ -681,6 +855,37 @@ mod tests {
         assert!(try_parse_verdicts("No issues found.").is_none());
     }
 
+    #[test]
+    fn blind_review_withholds_and_records_severity() {
+        let finding = SecurityFinding {
+            title: "Missing Signer".into(),
+            severity: "High".into(),
+            validation_status: ValidationStatus::Disputed,
+            ..Default::default()
+        };
+        let blind: serde_json::Value = serde_json::from_str(&finding_json(&finding, true)).unwrap();
+        assert_eq!(blind["title"], "Missing Signer");
+        assert!(blind.get("severity").is_none());
+        assert!(blind.get("validation_status").is_none());
+        let sighted: serde_json::Value =
+            serde_json::from_str(&finding_json(&finding, false)).unwrap();
+        assert_eq!(sighted["severity"], "High");
+
+        let entries = try_parse_verdicts(
+            r#"[{"index": 0, "title": "Missing Signer", "verdict": "Confirmed", "reasoning": "r", "severity": "medium"}]"#,
+        )
+        .unwrap();
+        let review = blind_review(true, "high", &entries[0]).unwrap();
+        assert_eq!(
+            (review.investigator.as_str(), review.validator.as_str()),
+            ("High", "Medium")
+        );
+        assert_eq!(review.delta(), -1);
+        assert!(blind_review(false, "High", &entries[0]).is_none());
+        let unrated = try_parse_verdicts(SAMPLE_VERDICT_JSON).unwrap();
+        assert!(blind_review(true, "High", &unrated[0]).is_none());
+    }
+
     #[test]
     fn parse_verdicts_malformed() {
         assert!(try_parse_verdicts("[{broken").is_none());
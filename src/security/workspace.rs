@@ -0,0 +1,164 @@
+//! On-chain program crates of a Cargo workspace. A repo often holds several
+//! programs (`programs/vault`, `programs/staking`) next to clients and SDKs,
+//! sometimes on different frameworks. Each program's files are scanned with
+//! the profile of its own manifest, and its findings carry its name. The
+//! call graph stays repo-wide, so calls into shared crates still resolve.
+//!
+//! Members come from the root `[workspace] members` (a trailing `/*` is
+//! expanded one level) plus any `programs/*` crate. A member is a program
+//! when it sits under `programs/`, or builds a `cdylib` against a Solana
+//! framework; clients and shared libraries are not.
+
+use super::framework::Framework;
+use std::path::{Path, PathBuf};
+
+/// One on-chain program crate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramCrate {
+    /// `[package] name`, else the directory name.
+    pub name: String,
+    pub dir: PathBuf,
+    /// Framework its manifest depends on.
+    pub framework: Option<Framework>,
+}
+
+/// The program crates under `root`, sorted by directory.
+pub fn programs(root: &Path) -> Vec<ProgramCrate> {
+    member_dirs(root)
+        .into_iter()
+        .filter_map(|dir| program_crate(root, dir))
+        .collect()
+}
+
+/// Directories of the root's workspace members and `programs/*`, sorted.
+/// Not every one need hold a `Cargo.toml`.
+pub fn member_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = subdirs(&root.join("programs"));
+    if let Some(members) = std::fs::read_to_string(root.join("Cargo.toml"))
+        .ok()
+        .and_then(|text| text.parse::<toml::Table>().ok())
+        .and_then(|manifest| {
+            manifest
+                .get("workspace")?
+                .get("members")?
+                .as_array()
+                .cloned()
+        })
+    {
+        for member in members.iter().filter_map(|m| m.as_str()) {
+            match member.strip_suffix("/*") {
+                Some(parent) => dirs.extend(subdirs(&root.join(parent))),
+                None => dirs.push(root.join(member)),
+            }
+        }
+    }
+    dirs.sort();
+    dirs.dedup();
+    dirs
+}
+
+fn program_crate(root: &Path, dir: PathBuf) -> Option<ProgramCrate> {
+    let text = std::fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+    let manifest: toml::Table = text.parse().ok()?;
+    let framework = Framework::of_manifest(&text);
+    let under_programs = dir
+        .strip_prefix(root)
+        .is_ok_and(|rel| rel.starts_with("programs"));
+    let cdylib = manifest
+        .get("lib")
+        .and_then(|lib| lib.get("crate-type"))
+        .and_then(|types| types.as_array())
+        .is_some_and(|types| types.iter().any(|t| t.as_str() == Some("cdylib")));
+    let on_chain = under_programs || (cdylib && framework.is_some());
+    if !on_chain {
+        return None;
+    }
+    let name = manifest
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(String::from)
+        .or_else(|| Some(dir.file_name()?.to_string_lossy().into_owned()))?;
+    Some(ProgramCrate {
+        name,
+        dir,
+        framework,
+    })
+}
+
+/// `root` itself as a program crate, for single-program repos without a
+/// workspace.
+pub fn root_program(root: &Path) -> Option<ProgramCrate> {
+    program_crate(root, root.to_path_buf())
+}
+
+/// The program `file` belongs to: the one with the deepest directory
+/// containing it.
+pub fn program_of<'a>(programs: &'a [ProgramCrate], file: &Path) -> Option<&'a ProgramCrate> {
+    programs
+        .iter()
+        .filter(|p| file.starts_with(&p.dir))
+        .max_by_key(|p| p.dir.components().count())
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_program_members_and_skips_clients() {
+        let root = std::env::temp_dir().join(format!("solguard-workspace-{}", std::process::id()));
+        let write = |rel: &str, content: &str| {
+            let path = root.join(rel);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"programs/*\", \"crates/*\", \"oracle\"]\n",
+        );
+        write(
+            "programs/vault/Cargo.toml",
+            "[package]\nname = \"vault\"\n[dependencies]\nanchor-lang = \"0.30\"\n",
+        );
+        write(
+            "programs/staking/Cargo.toml",
+            "[package]\nname = \"staking-program\"\n[dependencies]\npinocchio = \"0.8\"\n",
+        );
+        write(
+            "crates/client/Cargo.toml",
+            "[package]\nname = \"client\"\n[dependencies]\nsolana-sdk = \"2\"\n",
+        );
+        write(
+            "oracle/Cargo.toml",
+            "[package]\nname = \"oracle\"\n[lib]\ncrate-type = [\"cdylib\", \"lib\"]\n\
+             [dependencies]\nsolana-program = \"2\"\n",
+        );
+        let found = programs(&root);
+        std::fs::remove_dir_all(&root).ok();
+
+        let names: Vec<&str> = found.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["oracle", "staking-program", "vault"]);
+        assert_eq!(found[1].framework, Some(Framework::Pinocchio));
+        let file = root.join("programs/vault/src/lib.rs");
+        assert_eq!(
+            program_of(&found, &file).map(|p| p.name.as_str()),
+            Some("vault")
+        );
+        assert_eq!(
+            program_of(&found, &root.join("crates/client/src/lib.rs")),
+            None
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Severity labels as they appear in `SecurityFinding::severity` and the
+//! JSON output. Every ordering, filter and count in the crate goes through
+//! these functions; consumers of the JSON can use them to get identical
+//! semantics.
+//!
+//! Labels are matched case-insensitively and unknown labels rank as `Info`,
+//! so sloppy model output never outranks a real finding.
+
+/// Canonical labels, most severe first.
+pub const LEVELS: [&str; 5] = ["Critical", "High", "Medium", "Low", "Info"];
+
+/// Canonical label for `severity` ("critical", " HIGH ", "Moderate").
+pub fn normalize(severity: &str) -> &'static str {
+    match severity.trim().to_lowercase().as_str() {
+        "critical" => "Critical",
+        "high" => "High",
+        "medium" | "moderate" => "Medium",
+        "low" => "Low",
+        _ => "Info",
+    }
+}
+
+/// Numeric weight, higher = more severe: Critical 4 down to Info 0.
+pub fn weight(severity: &str) -> u8 {
+    match normalize(severity) {
+        "Critical" => 4,
+        "High" => 3,
+        "Medium" => 2,
+        "Low" => 1,
+        _ => 0,
+    }
+}
+
+/// Sort key, most severe first: Critical 0 down to Info 4. The index into
+/// [`LEVELS`].
+pub fn rank(severity: &str) -> usize {
+    usize::from(4 - weight(severity))
+}
+
+/// Whether `severity` is `floor` or worse.
+pub fn at_least(severity: &str, floor: &str) -> bool {
+    weight(severity) >= weight(floor)
+}
+
+/// One level lower; `Info` stays `Info`.
+pub fn downgrade(severity: &str) -> &'static str {
+    LEVELS[(rank(severity) + 1).min(LEVELS.len() - 1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_model_output() {
+        assert_eq!(normalize("critical"), "Critical");
+        assert_eq!(normalize(" HIGH "), "High");
+        assert_eq!(normalize("Moderate"), "Medium");
+        assert_eq!(normalize("severe-ish"), "Info");
+        for level in LEVELS {
+            assert_eq!(normalize(level), level);
+        }
+    }
+
+    #[test]
+    fn weight_and_rank_agree() {
+        assert_eq!(weight("Critical"), 4);
+        assert_eq!(weight("high"), 3);
+        assert_eq!(weight("Info"), 0);
+        assert_eq!(weight("unknown"), 0);
+        for (i, level) in LEVELS.iter().enumerate() {
+            assert_eq!(rank(level), i);
+        }
+        assert!(rank("Critical") < rank("High"));
+        assert!(at_least("CRITICAL", "High"));
+        assert!(at_least("High", "High"));
+        assert!(!at_least("Medium", "High"));
+    }
+
+    #[test]
+    fn downgrade_steps_one_level() {
+        assert_eq!(downgrade("Critical"), "High");
+        assert_eq!(downgrade("high"), "Medium");
+        assert_eq!(downgrade("Medium"), "Low");
+        assert_eq!(downgrade("Low"), "Info");
+        assert_eq!(downgrade("Info"), "Info");
+        assert_eq!(downgrade("bogus"), "Info");
+    }
+}
@@ -0,0 +1,233 @@
+//! Free-form labels on narratives and findings.
+//!
+//! Tags come from two places: `[[tagging.rules]]` in config (path globs for
+//! findings, protocol categories for narratives) and tags the LLM suggests
+//! during synthesis or investigation. Consumers slice results with `--filter-tag`.
+
+use crate::agent::infer_protocol_category;
+use crate::config::TagRule;
+use crate::narrative::Narrative;
+use crate::security::SecurityFinding;
+use std::collections::HashMap;
+
+/// Apply path-glob rules to findings. Matching is done on the finding's
+/// `file_path` as written (forward slashes).
+pub fn tag_findings(findings: &mut [SecurityFinding], rules: &[TagRule]) {
+    for f in findings {
+        let path = crate::paths::to_slash(&f.file_path);
+        for rule in rules {
+            if rule.paths.iter().any(|p| glob_match(p, &path)) {
+                add_tag(&mut f.tags, &rule.tag);
+            }
+        }
+    }
+}
+
+/// Apply category rules to narratives. A narrative's category is the one
+/// inferred from its title/summary, plus any tags it already carries.
+pub fn tag_narratives(narratives: &mut [Narrative], rules: &[TagRule]) {
+    for n in narratives {
+        let category = infer_protocol_category(n);
+        for rule in rules {
+            let hit = rule.categories.iter().any(|c| {
+                category
+                    .as_deref()
+                    .is_some_and(|cat| cat.eq_ignore_ascii_case(c))
+                    || n.tags.iter().any(|t| t.eq_ignore_ascii_case(c))
+            });
+            if hit {
+                add_tag(&mut n.tags, &rule.tag);
+            }
+        }
+    }
+}
+
+/// Normalize and append a tag, skipping duplicates and blanks.
+pub fn add_tag(tags: &mut Vec<String>, tag: &str) {
+    let tag = normalize(tag);
+    if !tag.is_empty() && !tags.contains(&tag) {
+        tags.push(tag);
+    }
+}
+
+/// Lowercase, trimmed, spaces collapsed to `-`.
+pub fn normalize(tag: &str) -> String {
+    tag.split_whitespace()
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+/// True when `filter` is empty or any of `tags` appears in it.
+pub fn matches_filter(tags: &[String], filter: &[String]) -> bool {
+    filter.is_empty() || filter.iter().any(|f| tags.contains(&normalize(f)))
+}
+
+/// Slice a report's inputs by tag. Findings are kept when they match; a
+/// narrative is kept when it matches or still links to a kept finding.
+/// Narrative `repo_findings` indices are remapped onto the filtered list.
+pub fn filter_report(
+    narratives: Vec<Narrative>,
+    findings: Vec<SecurityFinding>,
+    filter: &[String],
+) -> (Vec<Narrative>, Vec<SecurityFinding>) {
+    if filter.is_empty() {
+        return (narratives, findings);
+    }
+    let mut remap = HashMap::new();
+    let mut kept = Vec::new();
+    for (old, f) in findings.into_iter().enumerate() {
+        if matches_filter(&f.tags, filter) {
+            remap.insert(old, kept.len());
+            kept.push(f);
+        }
+    }
+    let narratives = narratives
+        .into_iter()
+        .filter_map(|mut n| {
+            for (_, indices) in &mut n.repo_findings {
+                *indices = indices
+                    .iter()
+                    .filter_map(|i| remap.get(i).copied())
+                    .collect();
+            }
+            n.repo_findings.retain(|(_, indices)| !indices.is_empty());
+            n.finding_count = n.repo_findings.iter().map(|(_, v)| v.len()).sum();
+            (matches_filter(&n.tags, filter) || n.finding_count > 0).then_some(n)
+        })
+        .collect();
+    (narratives, kept)
+}
+
+/// Minimal glob matcher: `*` matches any run of characters (including `/`),
+/// `?` matches exactly one. Good enough for `**/treasury/**`-style rules.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == t[ti]) {
+            pi += 1;
+            ti += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ti));
+            pi += 1;
+        } else if let Some((sp, st)) = star {
+            pi = sp + 1;
+            ti = st + 1;
+            star = Some((sp, st + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn rule(tag: &str, paths: &[&str], categories: &[&str]) -> TagRule {
+        TagRule {
+            tag: tag.into(),
+            paths: paths.iter().map(|s| s.to_string()).collect(),
+            categories: categories.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn glob_basics() {
+        assert!(glob_match(
+            "**/treasury/**",
+            "repos/vault/programs/treasury/src/lib.rs"
+        ));
+        assert!(glob_match("*.rs", "src/lib.rs"));
+        assert!(glob_match("src/?ib.rs", "src/lib.rs"));
+        assert!(!glob_match("**/treasury/**", "repos/vault/src/lib.rs"));
+        assert!(!glob_match("src/*.toml", "src/lib.rs"));
+    }
+
+    #[test]
+    fn findings_tagged_by_path() {
+        let mut findings = vec![
+            SecurityFinding {
+                file_path: PathBuf::from("repos/vault/programs/treasury/src/lib.rs"),
+                ..Default::default()
+            },
+            SecurityFinding {
+                file_path: PathBuf::from("repos/vault/src/lib.rs"),
+                ..Default::default()
+            },
+        ];
+        tag_findings(&mut findings, &[rule("Treasury", &["**/treasury/**"], &[])]);
+        assert_eq!(findings[0].tags, vec!["treasury"]);
+        assert!(findings[1].tags.is_empty());
+    }
+
+    #[test]
+    fn narratives_tagged_by_category() {
+        let mut narratives = vec![Narrative {
+            title: "AMM swap volume surges".into(),
+            tags: vec!["dex".into()],
+            ..Default::default()
+        }];
+        tag_narratives(
+            &mut narratives,
+            &[
+                rule("defi", &[], &["DEX", "Lending"]),
+                rule("nft", &[], &["NFT"]),
+            ],
+        );
+        assert_eq!(narratives[0].tags, vec!["dex", "defi"]);
+    }
+
+    #[test]
+    fn add_tag_normalizes_and_dedups() {
+        let mut tags = Vec::new();
+        add_tag(&mut tags, " Liquid Staking ");
+        add_tag(&mut tags, "liquid staking");
+        add_tag(&mut tags, "  ");
+        assert_eq!(tags, vec!["liquid-staking"]);
+    }
+
+    #[test]
+    fn filter_report_remaps_narrative_links() {
+        let tagged = |tags: &[&str]| SecurityFinding {
+            tags: tags.iter().map(|s| s.to_string()).collect(),
+            ..Default::default()
+        };
+        let findings = vec![tagged(&[]), tagged(&["treasury"]), tagged(&["treasury"])];
+        let narratives = vec![
+            Narrative {
+                title: "linked".into(),
+                repo_findings: vec![("vault".into(), vec![0, 2])],
+                finding_count: 2,
+                ..Default::default()
+            },
+            Narrative {
+                title: "unrelated".into(),
+                repo_findings: vec![("other".into(), vec![0])],
+                finding_count: 1,
+                ..Default::default()
+            },
+        ];
+        let (narratives, findings) = filter_report(narratives, findings, &["treasury".to_string()]);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(narratives.len(), 1);
+        assert_eq!(
+            narratives[0].repo_findings,
+            vec![("vault".to_string(), vec![1])]
+        );
+        assert_eq!(narratives[0].finding_count, 1);
+    }
+
+    #[test]
+    fn filter_matching() {
+        let tags = vec!["defi".to_string()];
+        assert!(matches_filter(&tags, &[]));
+        assert!(matches_filter(&tags, &["DeFi".into()]));
+        assert!(!matches_filter(&tags, &["nft".into()]));
+    }
+}
@@ -1,6 +1,9 @@
 use serde_json::json;
+use st_solguard::config::SolanaConfig;
+use st_solguard::narrative::rpc_pool::RpcPool;
 use st_solguard::security::agent_tools;
 use std::path::Path;
+use std::time::Duration;
 
 #[test]
 fn list_files_root() {
@@ -88,3 +91,165 @@ fn unknown_tool_returns_error() {
         "error should mention unknown tool: {result}"
     );
 }
+
+#[test]
+fn windows_style_paths_resolve_and_drives_are_rejected() {
+    let repo = Path::new("tests/fixtures/vulnerable_repo");
+    let (result, is_error) =
+        agent_tools::dispatch(repo, "read_file", &json!({"path": r"src\lib.rs"}));
+    assert!(!is_error, "backslash path should resolve: {result}");
+
+    let (result, is_error) =
+        agent_tools::dispatch(repo, "read_file", &json!({"path": r"..\..\Cargo.toml"}));
+    assert!(is_error && result.contains("traversal"), "{result}");
+
+    let (_, is_error) =
+        agent_tools::dispatch(repo, "read_file", &json!({"path": r"C:\Windows\win.ini"}));
+    assert!(is_error, "drive-qualified path must not escape the repo");
+}
+
+#[test]
+fn search_results_use_forward_slashes() {
+    let repo = Path::new("tests/fixtures/vulnerable_repo");
+    let (result, is_error) = agent_tools::dispatch(
+        repo,
+        "search_code",
+        &json!({"pattern": "remaining_accounts"}),
+    );
+    assert!(!is_error, "search_code failed: {result}");
+    assert!(result.contains("src/lib.rs:"), "{result}");
+}
+
+#[test]
+fn call_graph_reports_entry_points() {
+    let repo = Path::new("tests/fixtures/seahorse_repo");
+    let (result, is_error) =
+        agent_tools::dispatch(repo, "call_graph", &json!({"function": "sweep"}));
+    assert!(!is_error, "call_graph failed: {result}");
+    assert!(
+        result.starts_with("fn vault::sweep (programs_py/vault/generated/lib.rs:11) [entry point]"),
+        "{result}"
+    );
+
+    let (result, is_error) =
+        agent_tools::dispatch(repo, "call_graph", &json!({"function": "missing"}));
+    assert!(!is_error && result.contains("No function named 'missing'"));
+}
+
+#[tokio::test]
+async fn timed_dispatch_reports_timeouts_to_the_model() {
+    let repo = Path::new("tests/fixtures/vulnerable_repo");
+    let outcome = agent_tools::dispatch_timed(
+        repo,
+        "read_file",
+        &json!({"path": "src/lib.rs"}),
+        Duration::from_secs(30),
+        None,
+    )
+    .await;
+    assert!(!outcome.is_error && !outcome.timed_out);
+    assert!(outcome.result.contains("authority"));
+
+    // Searching the whole crate can't finish in zero time.
+    let outcome = agent_tools::dispatch_timed(
+        Path::new("."),
+        "search_code",
+        &json!({"pattern": "fn main"}),
+        Duration::ZERO,
+        None,
+    )
+    .await;
+    assert!(outcome.is_error && outcome.timed_out);
+    let error: serde_json::Value = serde_json::from_str(&outcome.result).unwrap();
+    assert_eq!(error["error"], "timeout");
+    assert_eq!(error["tool"], "search_code");
+}
+
+#[test]
+fn read_project_config_lists_workspace_programs() {
+    let repo = Path::new("tests/fixtures/workspace_repo");
+    let (result, is_error) = agent_tools::dispatch(repo, "read_project_config", &json!({}));
+    assert!(!is_error, "read_project_config failed: {result}");
+    let config: serde_json::Value = serde_json::from_str(&result).unwrap();
+    let programs: Vec<(&str, &str)> = config["programs"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| {
+            (
+                p["name"].as_str().unwrap(),
+                p["framework"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        programs,
+        vec![("swap-program", "pinocchio"), ("vault", "anchor")]
+    );
+    assert_eq!(config["programs"][1]["anchor_lang"]["version"], "0.30");
+}
+
+#[test]
+fn cargo_metadata_lists_members_and_dependencies() {
+    let repo = Path::new("tests/fixtures/workspace_repo");
+    let (result, is_error) = agent_tools::dispatch(repo, "cargo_metadata", &json!({}));
+    assert!(!is_error, "cargo_metadata failed: {result}");
+    let metadata: serde_json::Value = serde_json::from_str(&result).unwrap();
+    let members: Vec<(&str, bool)> = metadata["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|m| (m["name"].as_str().unwrap(), m["program"].as_bool().unwrap()))
+        .collect();
+    assert_eq!(
+        members,
+        vec![("cli", false), ("swap-program", true), ("vault", true)]
+    );
+    assert_eq!(
+        metadata["members"][2]["dependencies"]["anchor-lang"]["version"],
+        "0.30"
+    );
+
+    let (result, _) = agent_tools::dispatch(repo, "cargo_metadata", &json!({"filter": "anchor"}));
+    let metadata: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert!(
+        metadata["members"][0]["dependencies"]
+            .as_object()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn program_state_needs_rpc_and_a_valid_address() {
+    let repo = Path::new("tests/fixtures/vulnerable_repo");
+    let input = json!({"address": "not-an-address"});
+    // Without an RPC pool, as in validation, the tool isn't available.
+    let outcome =
+        agent_tools::dispatch_timed(repo, "program_state", &input, Duration::from_secs(5), None)
+            .await;
+    assert!(outcome.is_error && outcome.result.contains("Unknown tool"));
+
+    // Rejected before any request is sent.
+    let http = st_solguard::http::HttpClient::new("st-solguard-test").unwrap();
+    let rpc = RpcPool::new(&SolanaConfig::default(), &http);
+    let outcome = agent_tools::dispatch_timed(
+        repo,
+        "program_state",
+        &input,
+        Duration::from_secs(5),
+        Some(&rpc),
+    )
+    .await;
+    assert!(outcome.is_error && !outcome.timed_out);
+    assert!(outcome.result.contains("not a base58 account address"));
+    let outcome = agent_tools::dispatch_timed(
+        repo,
+        "program_state",
+        &json!({}),
+        Duration::from_secs(5),
+        Some(&rpc),
+    )
+    .await;
+    assert!(outcome.is_error && outcome.result.contains("Missing 'address'"));
+}
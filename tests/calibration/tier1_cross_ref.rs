@@ -29,6 +29,7 @@ fn make_narrative_for_pool(confidence: f64) -> Narrative {
         risk_score: 0.0,
         risk_level: String::new(),
         repo_findings: vec![],
+        ..Default::default()
     }
 }
 
@@ -48,6 +49,7 @@ fn gt_to_security_finding(
         remediation: "See ground truth".into(),
         validation_status: validation,
         validation_reasoning: None,
+        ..Default::default()
     }
 }
 
@@ -17,6 +17,7 @@ fn make_narrative_with_findings(finding_count: usize) -> Narrative {
         risk_score: 37.4,
         risk_level: "Critical".into(),
         repo_findings: vec![("shielded-pool-pinocchio-solana".into(), indices)],
+        ..Default::default()
     }
 }
 
@@ -35,6 +36,7 @@ fn gt_findings_confirmed() -> Vec<SecurityFinding> {
             remediation: "See ground truth".into(),
             validation_status: ValidationStatus::Confirmed,
             validation_reasoning: None,
+            ..Default::default()
         })
         .collect()
 }
@@ -13,6 +13,7 @@ fn make_narrative(title: &str, summary: &str) -> Narrative {
         risk_score: 0.0,
         risk_level: String::new(),
         repo_findings: vec![],
+        ..Default::default()
     }
 }
 
@@ -36,6 +36,7 @@ async fn full_pipeline_data_flow() {
         risk_score: 0.0,
         risk_level: String::new(),
         repo_findings: vec![],
+        ..Default::default()
     }];
 
     // Phase 2-3: Ground truth as SecurityFindings (simulates scanner + agent output)
@@ -53,6 +54,7 @@ async fn full_pipeline_data_flow() {
             remediation: "See ground truth".into(),
             validation_status: ValidationStatus::Confirmed,
             validation_reasoning: Some("Manually verified in T32 clean-room analysis".into()),
+            ..Default::default()
         })
         .collect();
 
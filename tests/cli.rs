@@ -0,0 +1,25 @@
+use std::process::Command;
+
+#[test]
+fn scan_fails_on_a_broken_custom_pattern() {
+    let dir = std::env::temp_dir().join(format!("solguard-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("config.toml"),
+        "[scan]\npatterns = \"patterns.toml\"\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("patterns.toml"),
+        "[[pattern]]\nid = \"ACME-001\"\nregex = '(unclosed'\nseverity = \"High\"\n",
+    )
+    .unwrap();
+    let out = Command::new(env!("CARGO_BIN_EXE_st-solguard"))
+        .args(["scan", "tests/fixtures/vulnerable_repo", "--config"])
+        .arg(dir.join("config.toml"))
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&dir).ok();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("ACME-001: regex"));
+}
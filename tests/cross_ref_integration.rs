@@ -15,6 +15,7 @@ fn make_narrative(title: &str, repos: Vec<&str>, confidence: f64) -> Narrative {
         risk_score: 0.0,
         risk_level: String::new(),
         repo_findings: vec![],
+        ..Default::default()
     }
 }
 
@@ -33,6 +34,7 @@ fn make_finding(
         remediation: "fix".into(),
         validation_status: validation,
         validation_reasoning: None,
+        ..Default::default()
     }
 }
 
@@ -143,3 +145,91 @@ async fn risk_level_thresholds() {
     // 3 * 10.0 * 1.0 * 1.0 = 30.0 -> Critical
     assert_eq!(narratives[0].risk_level, "Critical");
 }
+
+#[tokio::test]
+async fn offline_analysis_scores_without_llm() {
+    let mut narratives = vec![make_narrative("Test", vec!["owner/my-repo"], 0.8)];
+    let findings = vec![make_finding(
+        "Bug",
+        "Critical",
+        "repos/my-repo/src/lib.rs",
+        ValidationStatus::Confirmed,
+    )];
+
+    let links = cross_ref::analyze_offline(&mut narratives, &findings)
+        .await
+        .unwrap();
+    assert_eq!(links.len(), 1);
+    assert!(
+        links[0]
+            .relevance
+            .contains("linked to narrative via active_repos")
+    );
+    assert!((narratives[0].risk_score - 8.0).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn template_finding_counts_once_per_narrative() {
+    let mut narratives = vec![make_narrative(
+        "AMM Forks",
+        vec!["a/amm-a", "b/amm-b", "c/amm-c", "d/amm-d"],
+        1.0,
+    )];
+    let mut template = make_finding(
+        "Unchecked slippage",
+        "Critical",
+        "repos/amm-a/src/swap.rs",
+        ValidationStatus::Confirmed,
+    );
+    template.forks = vec![
+        "amm-a".into(),
+        "amm-b".into(),
+        "amm-c".into(),
+        "amm-d".into(),
+    ];
+
+    let links = cross_ref::analyze_offline(&mut narratives, &[template])
+        .await
+        .unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(narratives[0].finding_count, 1);
+    // 10 (Critical) * 1.0 (Confirmed) * 2.0 (4 forks) * 1.0 confidence
+    assert!((narratives[0].risk_score - 20.0).abs() < 0.01);
+}
+
+#[tokio::test]
+async fn risk_terms_sum_to_risk_score() {
+    let mut narratives = vec![make_narrative("Lending", vec!["o/lend", "o/vault"], 0.8)];
+    let findings = vec![
+        make_finding(
+            "Oracle",
+            "High",
+            "repos/lend/src/oracle.rs",
+            ValidationStatus::Confirmed,
+        ),
+        make_finding(
+            "Rounding",
+            "Medium",
+            "repos/vault/src/math.rs",
+            ValidationStatus::Disputed,
+        ),
+        make_finding(
+            "Elsewhere",
+            "Critical",
+            "repos/other/src/lib.rs",
+            ValidationStatus::Confirmed,
+        ),
+    ];
+    cross_ref::analyze_offline(&mut narratives, &findings)
+        .await
+        .unwrap();
+
+    let terms = cross_ref::risk_terms(&narratives[0], &findings);
+    assert_eq!(terms.len(), 2);
+    // 5 * 1.0 * 1.0 * 0.8
+    assert!((terms[0].contribution() - 4.0).abs() < 1e-9);
+    assert_eq!(terms[1].repo, "vault");
+    assert!((terms[1].validation_multiplier - 0.5).abs() < f64::EPSILON);
+    let total: f64 = terms.iter().map(|t| t.contribution()).sum();
+    assert!((total - narratives[0].risk_score).abs() < 1e-9);
+}
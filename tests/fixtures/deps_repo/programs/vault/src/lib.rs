@@ -0,0 +1,5 @@
+// Clean program code: the only finding should come from Cargo.lock.
+
+pub fn safe_add(a: u64, b: u64) -> Option<u64> {
+    a.checked_add(b)
+}
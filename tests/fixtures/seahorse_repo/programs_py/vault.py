@@ -0,0 +1,17 @@
+# vault
+# Built with Seahorse v0.2.7
+
+from seahorse.prelude import *
+
+declare_id('Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS')
+
+
+class Vault(Account):
+    owner: Pubkey
+    balance: u64
+
+
+@instruction
+def sweep(vault: Vault, owner: Signer):
+    assert owner.key() == vault.owner, 'not the owner'
+    vault.balance = 0
@@ -0,0 +1,31 @@
+// ===== Generated by Seahorse, do not edit =====
+
+use anchor_lang::prelude::*;
+
+declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
+
+#[program]
+pub mod vault {
+    use super::*;
+
+    pub fn sweep(ctx: Context<Sweep>) -> Result<()> {
+        for account in ctx.remaining_accounts.iter() {
+            msg!("sweeping {}", account.key());
+        }
+        ctx.accounts.vault.balance = 0;
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct Sweep<'info> {
+    #[account(mut)]
+    pub vault: Account<'info, Vault>,
+    pub owner: Signer<'info>,
+}
+
+#[account]
+pub struct Vault {
+    pub owner: Pubkey,
+    pub balance: u64,
+}
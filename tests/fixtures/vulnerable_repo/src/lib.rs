@@ -1,5 +1,5 @@
 // Synthetic vulnerable Solana program for testing.
-// Triggers security scanner patterns (SOL-001..010, AST-001..003).
+// Triggers security scanner patterns (SOL-001..013, AST-001..003).
 
 use anchor_lang::prelude::*;
 
@@ -61,6 +61,36 @@ pub fn old_transfer() {
     spl_token::instruction::transfer(program_id, src, dst, auth, &[], amount);
 }
 
+// SOL-011: Account space missing discriminator
+#[derive(Accounts)]
+pub struct InitVault<'info> {
+    #[account(init, payer = payer, space = std::mem::size_of::<Vault>())]
+    pub vault: Account<'info, Vault>,
+}
+
+// SOL-012: Realloc without rent top-up
+pub fn grow(account: &AccountInfo, new_len: usize) {
+    account.realloc(new_len, false).unwrap();
+}
+
+// SOL-013: Fixed buffer written with unchecked length
+pub fn set_name(buf: &mut [u8; 32], name: &[u8]) {
+    buf[..name.len()].copy_from_slice(name);
+}
+
+// SOL-014 + SOL-015: Unbounded approval, never revoked
+pub fn enable_strategy(ctx: Context<Enable>) -> Result<()> {
+    token::approve(ctx.accounts.approve_ctx(), u64::MAX)?;
+    Ok(())
+}
+
+// SOL-016: Approval to unconstrained delegate
+#[derive(Accounts)]
+pub struct Enable<'info> {
+    /// CHECK: caller-supplied
+    pub delegate: UncheckedAccount<'info>,
+}
+
 // AST-002: Verbose key logging
 fn log_keys(account: &AccountInfo) {
     msg!("{}", account.key());
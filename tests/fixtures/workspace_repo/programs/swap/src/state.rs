@@ -0,0 +1,9 @@
+// No framework import: the profile comes from this crate's manifest.
+use crate::error::SwapError;
+
+pub fn credit_pool(pool_info: &AccountInfo, amount: u64) -> Result<(), SwapError> {
+    let mut data = pool_info.try_borrow_mut_data()?;
+    let pool = Pool::from_bytes_mut(&mut data);
+    pool.reserve = pool.reserve.checked_add(amount).ok_or(SwapError::Overflow)?;
+    Ok(())
+}
@@ -50,3 +50,34 @@ fn override_returns_custom_client() {
         "default-model"
     );
 }
+
+#[test]
+fn cheap_passes_fall_back_to_cross_reference() {
+    let client = |model: &str| {
+        LlmClient::new(
+            Provider::OpenRouter,
+            "key".into(),
+            model.into(),
+            100,
+            Some("http://localhost:1".into()),
+        )
+        .unwrap()
+    };
+    let router = ModelRouter::new(client("default-model"))
+        .with_client(TaskKind::CrossReference, client("cheap-model"))
+        .with_client(TaskKind::Summary, client("summary-model"));
+
+    assert_eq!(router.client_for(TaskKind::Triage).model(), "cheap-model");
+    assert_eq!(
+        router.client_for(TaskKind::Translation).model(),
+        "cheap-model"
+    );
+    assert_eq!(
+        router.client_for(TaskKind::Summary).model(),
+        "summary-model"
+    );
+    assert_eq!(
+        router.client_for(TaskKind::Validation).model(),
+        "default-model"
+    );
+}
@@ -0,0 +1,23 @@
+use st_solguard::security::replay::ReplaySession;
+use std::path::Path;
+
+#[test]
+fn replay_corpus_meets_expectations() {
+    let mut sessions: Vec<_> = std::fs::read_dir(Path::new("tests/fixtures/replay"))
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .collect();
+    sessions.sort();
+    assert!(!sessions.is_empty(), "replay corpus is empty");
+
+    for path in sessions {
+        let outcome = ReplaySession::load(&path).unwrap().replay();
+        assert!(
+            outcome.mismatches.is_empty(),
+            "{}: {:?}",
+            outcome.name,
+            outcome.mismatches
+        );
+    }
+}
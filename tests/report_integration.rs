@@ -1,6 +1,8 @@
 use st_solguard::narrative::Narrative;
 use st_solguard::output;
-use st_solguard::security::{SecurityFinding, ValidationStatus};
+use st_solguard::security::{
+    Provenance, ScanType, SecurityFinding, Selection, ValidationRecord, ValidationStatus,
+};
 use std::path::PathBuf;
 
 fn make_narrative(title: &str, repos: Vec<&str>, risk_score: f64, risk_level: &str) -> Narrative {
@@ -14,6 +16,7 @@ fn make_narrative(title: &str, repos: Vec<&str>, risk_score: f64, risk_level: &s
         risk_score,
         risk_level: risk_level.into(),
         repo_findings: vec![],
+        ..Default::default()
     }
 }
 
@@ -32,6 +35,7 @@ fn make_finding(
         remediation: "Fix it".into(),
         validation_status: validation,
         validation_reasoning: Some("test reasoning".into()),
+        ..Default::default()
     }
 }
 
@@ -95,6 +99,38 @@ fn report_has_orphan_findings() {
     assert!(html.contains("Orphan Bug"));
 }
 
+#[test]
+fn report_shows_orphan_root_cause_themes() {
+    let findings = vec![
+        make_finding(
+            "Missing Owner Check",
+            "High",
+            "repos/amm/src/lib.rs",
+            ValidationStatus::Unvalidated,
+        ),
+        make_finding(
+            "Missing Owner Check",
+            "Critical",
+            "repos/vault/src/lib.rs",
+            ValidationStatus::Unvalidated,
+        ),
+    ];
+    let notices = output::ReportNotices {
+        orphan_themes: vec![st_solguard::agent::cross_ref::OrphanTheme {
+            theme: "Accounts trusted without owner checks".into(),
+            summary: "Deserialized accounts are never checked against the program ID.".into(),
+            findings: vec![0, 1],
+        }],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &findings, None, &notices).unwrap();
+    assert!(html.contains("Root causes"));
+    assert!(html.contains("Accounts trusted without owner checks"));
+    assert!(html.contains("2 findings</span>"));
+    let plain = output::render_combined_report(&[], &findings, None).unwrap();
+    assert!(!plain.contains("Root causes"));
+}
+
 #[test]
 fn empty_narratives_and_findings_produce_valid_html() {
     let html = output::render_combined_report(&[], &[], None).unwrap();
@@ -103,3 +139,622 @@ fn empty_narratives_and_findings_produce_valid_html() {
         "should produce valid HTML"
     );
 }
+
+#[test]
+fn report_shows_tags() {
+    let mut n = make_narrative("Tagged", vec!["owner/test"], 5.0, "Medium");
+    n.tags = vec!["defi".into()];
+    let mut f = make_finding(
+        "Treasury Drain",
+        "High",
+        "repos/test/programs/treasury/src/lib.rs",
+        ValidationStatus::Unvalidated,
+    );
+    f.tags = vec!["treasury".into()];
+    let html = output::render_combined_report(&[n], &[f], None).unwrap();
+    assert!(html.contains("#defi"));
+    assert!(html.contains("#treasury"));
+}
+
+#[test]
+fn report_marks_mismatched_repos() {
+    let mut n = make_narrative("DEX Volume", vec!["owner/dex"], 0.0, "None");
+    n.mismatched_repos = vec!["acme/ts-sdk".into()];
+    let html = output::render_combined_report(&[n], &[], None).unwrap();
+    assert!(html.contains("match this narrative: acme/ts-sdk"));
+
+    let clean = make_narrative("DEX Volume", vec!["owner/dex"], 0.0, "None");
+    let html = output::render_combined_report(&[clean], &[], None).unwrap();
+    assert!(!html.contains("match this narrative"));
+}
+
+#[test]
+fn report_shows_finding_ids() {
+    let mut f = make_finding(
+        "Missing Signer",
+        "High",
+        "repos/jupiter/src/lib.rs",
+        ValidationStatus::Unvalidated,
+    );
+    f.id = Some("SG-2025-jupiter-0007".into());
+    let html = output::render_combined_report(&[], &[f], None).unwrap();
+    assert!(html.contains("SG-2025-jupiter-0007"));
+}
+
+#[test]
+fn repo_summary_shows_upgrade_posture() {
+    use st_solguard::security::posture::{DeclaredProgram, UpgradePosture};
+    let program = DeclaredProgram {
+        program_id: "Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS".into(),
+        file_path: PathBuf::from("repos/vault/programs/vault/src/lib.rs"),
+        line_number: 3,
+    };
+    let f = UpgradePosture::SingleKey {
+        authority: "HotKey111".into(),
+    }
+    .to_finding(&program)
+    .unwrap();
+    let html = output::render_combined_report(&[], &[f], None).unwrap();
+    assert!(html.contains("Upgrade Authority"));
+    assert!(html.contains("single key"));
+}
+
+#[test]
+fn repo_summary_breaks_down_workspace_programs() {
+    let in_program = |program: Option<&str>, severity: &str| SecurityFinding {
+        program: program.map(String::from),
+        ..make_finding(
+            "Missing Owner Check",
+            severity,
+            "repos/amm/programs/x/src/lib.rs",
+            ValidationStatus::Unvalidated,
+        )
+    };
+    let findings = vec![
+        in_program(Some("amm-router"), "High"),
+        in_program(Some("amm-pool"), "Critical"),
+        in_program(Some("amm-pool"), "Low"),
+        in_program(None, "Medium"),
+    ];
+    let html = output::render_combined_report(&[], &findings, None).unwrap();
+    let (pool, router, other) = (
+        html.find(">amm-pool</td>").expect("pool row"),
+        html.find(">amm-router</td>").expect("router row"),
+        html.find(">other</td>")
+            .expect("row for files outside programs"),
+    );
+    assert!(pool < router && router < other);
+
+    // A repo with one program has no breakdown.
+    let single = output::render_combined_report(&[], &findings[1..3], None).unwrap();
+    assert!(!single.contains(">amm-pool</td>"));
+}
+
+#[test]
+fn report_lists_forks_of_template_finding() {
+    let mut f = make_finding(
+        "Unchecked slippage",
+        "High",
+        "repos/amm-a/src/swap.rs",
+        ValidationStatus::Unvalidated,
+    );
+    f.forks = vec!["amm-a".into(), "amm-b".into()];
+    let html = output::render_combined_report(&[], &[f], None).unwrap();
+    assert!(html.contains("template, in forks: amm-a, amm-b"));
+}
+
+#[test]
+fn degraded_run_banner() {
+    let notice =
+        "Deep agent review and validation skipped (missing OPENROUTER_API_KEY)".to_string();
+    let notices = output::ReportNotices {
+        degraded: vec![notice],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("Degraded Run"));
+    assert!(html.contains("missing OPENROUTER_API_KEY"));
+
+    let clean = output::render_combined_report(&[], &[], None).unwrap();
+    assert!(!clean.contains("Degraded Run"));
+}
+
+#[test]
+fn sampled_validation_table() {
+    use st_solguard::security::sampling::PatternSample;
+    let notices = output::ReportNotices {
+        validation_samples: vec![PatternSample {
+            repo: "amm-a".into(),
+            pattern: "SOL-004".into(),
+            total: 40,
+            sampled: 8,
+            confirmed: 2,
+            disputed: 2,
+            dismissed: 4,
+        }],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("Validation was sampled"));
+    assert!(html.contains("8 / 40"));
+    assert!(html.contains("50%"));
+    assert!(html.contains("~16 of 32"));
+
+    let clean = output::render_combined_report(&[], &[], None).unwrap();
+    assert!(!clean.contains("Validation was sampled"));
+}
+
+#[test]
+fn blind_review_shows_both_severities() {
+    use st_solguard::security::BlindReview;
+    let reviewed = |title: &str, investigator: &str, validator: &str| SecurityFinding {
+        blind_review: Some(BlindReview {
+            investigator: investigator.into(),
+            validator: validator.into(),
+        }),
+        ..make_finding(
+            title,
+            investigator,
+            "repos/lender/src/lib.rs",
+            ValidationStatus::Confirmed,
+        )
+    };
+    let findings = vec![
+        reviewed("Stale Oracle", "High", "Medium"),
+        reviewed("Missing Signer", "Critical", "Critical"),
+        reviewed("Fee Rounding", "Low", "Medium"),
+        reviewed("Unbounded Loop", "Medium", "Low"),
+    ];
+    let html = output::render_combined_report(&[], &findings, None).unwrap();
+    assert!(html.contains("Validation was blind"));
+    assert!(html.contains("validator Medium vs investigator High (-1)"));
+    assert!(html.contains("validator Medium vs investigator Low (+1)"));
+    assert!(html.contains("-0.2"));
+
+    let clean = output::render_combined_report(
+        &[],
+        &[make_finding(
+            "Stale Oracle",
+            "High",
+            "repos/lender/src/lib.rs",
+            ValidationStatus::Confirmed,
+        )],
+        None,
+    )
+    .unwrap();
+    assert!(!clean.contains("Validation was blind"));
+    assert!(!clean.contains("Blind review:"));
+}
+
+#[test]
+fn budget_allocation_table() {
+    use st_solguard::memory::{BudgetSplit, RepoResult};
+    use st_solguard::security::StaticMerge;
+    let notices = output::ReportNotices {
+        budgets: vec![RepoResult {
+            name: "vault-a".into(),
+            findings_count: 2,
+            errors: vec![],
+            finding_ids: Vec::new(),
+            skipped: None,
+            waived: Vec::new(),
+            summary: None,
+            budget: Some(BudgetSplit {
+                total_turns: 24,
+                total_cost_usd: 6.0,
+                investigation_turns: 11,
+                investigation_cost_usd: 2.25,
+                validation_turns: 3,
+                validation_cost_usd: 0.5,
+                narrative: Some("Liquid Restaking".into()),
+                narrative_confidence: Some(0.85),
+                static_merge: Some(StaticMerge {
+                    merged: 2,
+                    below_threshold: 4,
+                    dominated: 1,
+                }),
+                ..Default::default()
+            }),
+        }],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("Liquid Restaking"));
+    assert!(html.contains("85%"));
+    assert!(html.contains("14 / 24"));
+    assert!(html.contains("$2.75 / $6.00"));
+    assert!(html.contains("vault-a"));
+    assert!(html.contains(">2 / 5<"));
+    assert!(html.contains("4 below the severity threshold, 1 covered by agent findings"));
+
+    let clean = output::render_combined_report(&[], &[], None).unwrap();
+    assert!(!clean.contains("Narrative / Repository"));
+}
+
+#[test]
+fn deferred_targets_table() {
+    use st_solguard::memory::CarriedTarget;
+    let notices = output::ReportNotices {
+        deferred: vec![
+            CarriedTarget {
+                target: "acme/oracle".into(),
+                reason: "run budget exhausted".into(),
+                narrative: Some(make_narrative(
+                    "Oracle Networks",
+                    vec!["acme/oracle"],
+                    0.0,
+                    "None",
+                )),
+                since_run: 3,
+            },
+            CarriedTarget {
+                target: "acme/amm".into(),
+                reason: "low urgency".into(),
+                narrative: None,
+                since_run: 4,
+            },
+        ],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("Deferred Target"));
+    assert!(html.contains("acme/oracle"));
+    assert!(html.contains("Oracle Networks"));
+    assert!(html.contains("run budget exhausted"));
+    assert!(html.contains("low urgency"));
+
+    let clean = output::render_combined_report(&[], &[], None).unwrap();
+    assert!(!clean.contains("Deferred targets:"));
+}
+
+#[test]
+fn trust_boundary_diagrams() {
+    use st_solguard::output::redact::Audience;
+    use st_solguard::security::trust_graph::{AccountKind, AccountUse, Handler, TrustGraph};
+    let mut notices = output::ReportNotices {
+        trust_graphs: vec![TrustGraph {
+            repo: "vault-a".into(),
+            handlers: vec![Handler {
+                name: "withdraw".into(),
+                accounts: vec![AccountUse {
+                    name: "authority".into(),
+                    kind: AccountKind::Signer,
+                    ty: String::new(),
+                    writable: false,
+                }],
+                cpis: Vec::new(),
+                context: None,
+                args: Vec::new(),
+                sysvars: Vec::new(),
+            }],
+        }],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("<pre class=\"mermaid\">flowchart LR"));
+    assert!(html.contains("acc_authority --&#62;|signs| ix_withdraw"));
+
+    notices.audience = Audience::Public;
+    let public = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(!public.contains("<pre class=\"mermaid\">"));
+    let clean = output::render_combined_report(&[], &[], None).unwrap();
+    assert!(!clean.contains("<pre class=\"mermaid\">"));
+}
+
+#[test]
+fn cross_repo_call_graph() {
+    use st_solguard::output::redact::Audience;
+    use st_solguard::security::cross_repo::{CrossRepoCall, CrossRepoGraph};
+    let mut notices = output::ReportNotices {
+        cross_repo: vec![CrossRepoGraph {
+            narrative: "Lending on Solana".into(),
+            calls: vec![CrossRepoCall {
+                caller: "acme/vault".into(),
+                callee: "acme/lending".into(),
+                program_id: "Lend111111111111111111111111111111111111111".into(),
+                file_path: PathBuf::from("programs/vault/src/cpi.rs"),
+                line_number: 12,
+            }],
+        }],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("Cross-Repo Calls</h2>"));
+    assert!(html.contains("repo_acme_vault ==&#62;|&#34;Lend…1111&#34;| repo_acme_lending"));
+    assert!(html.contains("programs/vault/src/cpi.rs:12"));
+    assert!(html.contains("mermaid.initialize"));
+
+    notices.audience = Audience::Public;
+    let public = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(public.contains("Cross-Repo Calls</h2>"));
+    assert!(!public.contains("cpi.rs"));
+}
+
+#[test]
+fn isolated_run_links_clone_manifest() {
+    use st_solguard::output::redact::Audience;
+    let mut notices = output::ReportNotices {
+        manifest: Some(PathBuf::from(
+            "/work/repos/runs/20261016T120000Z-42/manifest.json",
+        )),
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("href=\"file:///work/repos/runs/20261016T120000Z-42/manifest.json\""));
+
+    notices.audience = Audience::Public;
+    let public = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(!public.contains("manifest.json"));
+    let clean = output::render_combined_report(&[], &[], None).unwrap();
+    assert!(!clean.contains("isolated workspace"));
+}
+
+#[test]
+fn exploration_run_disclaimer() {
+    let notices = output::ReportNotices {
+        exploration: true,
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("Exploration Run"));
+    assert!(html.contains("Exploration quality: produced with free models"));
+
+    let clean = output::render_combined_report(&[], &[], None).unwrap();
+    assert!(!clean.contains("Exploration Run"));
+}
+
+#[test]
+fn scan_targets_show_last_commit_age() {
+    use st_solguard::agent::freshness::{Activity, TargetActivity};
+    let notices = output::ReportNotices {
+        targets: vec![
+            TargetActivity {
+                repo: "amm-live".into(),
+                last_commit_days: Some(3),
+                activity: Activity::Active,
+            },
+            TargetActivity {
+                repo: "amm-dead".into(),
+                last_commit_days: Some(900),
+                activity: Activity::Skipped,
+            },
+        ],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("Scan Target"));
+    assert!(html.contains("3 days ago"));
+    assert!(html.contains("900 days ago"));
+    assert!(html.contains("stale, skipped"));
+}
+
+#[test]
+fn embedded_data_round_trips_through_html() {
+    let mut narrative = make_narrative("Perp DEXes", vec!["drift"], 7.0, "High");
+    narrative.repo_findings = vec![("drift".into(), vec![0])];
+    let findings = vec![make_finding(
+        "Oracle staleness",
+        "High",
+        "repos/drift/src/oracle.rs",
+        ValidationStatus::Confirmed,
+    )];
+    let html =
+        output::render_combined_report(std::slice::from_ref(&narrative), &findings, None).unwrap();
+    assert!(html.contains("id=\"solguard-data\""));
+
+    let data = output::embed::extract(&html).unwrap();
+    assert_eq!(data.narratives.len(), 1);
+    assert_eq!(data.narratives[0].repo_findings, narrative.repo_findings);
+    assert_eq!(data.findings[0].title, "Oracle staleness");
+    assert_eq!(
+        data.findings[0].validation_status,
+        ValidationStatus::Confirmed
+    );
+
+    // Extracted data re-renders the same report content.
+    let again = output::render_combined_report(&data.narratives, &data.findings, None).unwrap();
+    assert!(again.contains("Oracle staleness"));
+    assert!(again.contains("Perp DEXes"));
+}
+
+#[test]
+fn structured_provenance_round_trips_and_labels_source() {
+    let mut narrative = make_narrative("Perp DEXes", vec!["drift"], 7.0, "High");
+    narrative.repo_findings = vec![("drift".into(), vec![0])];
+    let mut posture = make_finding(
+        "Single-key upgrade authority",
+        "Medium",
+        "repos/drift/Anchor.toml",
+        ValidationStatus::Unvalidated,
+    );
+    posture.line_number = 0;
+    posture.provenance = Some(Provenance {
+        narrative_index: Some(0),
+        narrative: Some("Perp DEXes".into()),
+        selection: Selection::Narrative,
+        scan_type: ScanType::Posture,
+        scanned_at: "2026-03-01T12:00:00+00:00".into(),
+        validation: Some(ValidationRecord {
+            verdict: ValidationStatus::Unvalidated,
+            model: Some("validator-model".into()),
+            validated_at: None,
+        }),
+    });
+    let findings = vec![posture];
+    let html =
+        output::render_combined_report(std::slice::from_ref(&narrative), &findings, None).unwrap();
+    // The recorded scan type wins over the no-line heuristic.
+    assert!(html.contains("target selection \u{2192} posture check"));
+
+    let data = output::embed::extract(&html).unwrap();
+    assert_eq!(data.findings[0].provenance, findings[0].provenance);
+    let json = serde_json::to_value(&data.findings[0]).unwrap();
+    assert_eq!(json["provenance"]["selection"], "narrative");
+    assert_eq!(json["provenance"]["scan_type"], "posture");
+    assert_eq!(json["provenance"]["narrative_index"], 0);
+}
+
+#[test]
+fn risk_score_breakdown_lists_each_term() {
+    let mut narrative = make_narrative("Lending", vec!["o/lend"], 4.7, "Medium");
+    narrative.repo_findings = vec![("lend".into(), vec![0, 1])];
+    let findings = vec![
+        make_finding(
+            "Oracle staleness",
+            "High",
+            "repos/lend/src/oracle.rs",
+            ValidationStatus::Confirmed,
+        ),
+        make_finding(
+            "Rounding",
+            "Low",
+            "repos/lend/src/math.rs",
+            ValidationStatus::Unvalidated,
+        ),
+    ];
+    let html = output::render_combined_report(&[narrative], &findings, None).unwrap();
+    assert!(html.contains("Risk score breakdown"));
+    // 5 * 1.0 * 1.0 * 0.8 and 0.5 * 0.7 * 1.0 * 0.8
+    assert!(html.contains("4.00"));
+    assert!(html.contains("0.28"));
+    assert!(html.contains("4.3"));
+    // Stored 4.7 doesn't match the visible terms.
+    assert!(html.contains("Stored score 4.7"));
+}
+
+#[test]
+fn audience_redacts_report_and_embedded_data() {
+    let findings = vec![SecurityFinding {
+        description: "Vault authority is never checked.\n\nAttack scenario:\n1. Pass any key as authority.\n2. Drain the vault."
+            .into(),
+        validation_reasoning: Some("Confirmed: anyone can sign as authority".into()),
+        instruction: Some("withdraw".into()),
+        ..make_finding(
+            "Missing Signer",
+            "Critical",
+            "repos/vault/programs/vault/src/lib.rs",
+            ValidationStatus::Confirmed,
+        )
+    }];
+    let mut narrative = make_narrative("Vault Growth", vec!["acme/vault"], 10.0, "High");
+    narrative.repo_findings = vec![("vault".into(), vec![0])];
+    let render = |audience| {
+        let notices = output::ReportNotices {
+            audience,
+            ..Default::default()
+        };
+        output::render_combined_report_with_notices(
+            std::slice::from_ref(&narrative),
+            &findings,
+            None,
+            &notices,
+        )
+        .unwrap()
+    };
+
+    let internal = render(output::Audience::Internal);
+    assert!(internal.contains("programs/vault/src/lib.rs:42"));
+    assert!(internal.contains("Drain the vault"));
+    assert!(internal.contains("anyone can sign as authority"));
+    assert!(!internal.contains("attack steps have been removed"));
+
+    let partner = render(output::Audience::Partner);
+    assert!(partner.contains("programs/vault/src/lib.rs:42"));
+    assert!(partner.contains("Vault authority is never checked."));
+    assert!(!partner.contains("Drain the vault"));
+    assert!(!partner.contains("anyone can sign as authority"));
+    assert!(partner.contains("Partner report: attack steps have been removed."));
+
+    let public = render(output::Audience::Public);
+    assert!(!public.contains("src/lib.rs"));
+    assert!(!public.contains("withdraw handler"));
+    assert!(!public.contains("Drain the vault"));
+    assert!(public.contains("Missing Signer"));
+    assert!(public.contains("vault"));
+    assert!(public.contains("Public report"));
+
+    // `extract` can't recover what the HTML leaves out.
+    let data = output::embed::extract(&public).unwrap();
+    assert_eq!(data.findings[0].file_path, PathBuf::from("vault"));
+    assert!(data.findings[0].validation_reasoning.is_none());
+    assert!(data.findings[0].instruction.is_none());
+}
+
+#[test]
+fn waived_findings_table() {
+    use st_solguard::memory::RepoResult;
+    use st_solguard::security::ignore::Waiver;
+    let waiver = |pattern: &str, line: usize, reason: &str| Waiver {
+        pattern_id: pattern.into(),
+        file_path: "programs/vault/src/lib.rs".into(),
+        line_number: line,
+        reason: reason.into(),
+    };
+    let notices = output::ReportNotices {
+        budgets: vec![RepoResult {
+            name: "vault-a".into(),
+            waived: vec![
+                waiver("SOL-003", 40, "fee capped at MAX_FEE_BPS"),
+                waiver("SOL-003", 52, ""),
+                waiver("AST-001", 12, "admin-only, checked in handler"),
+            ],
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("AST-001, SOL-003 \u{d7}2"));
+    assert!(html.contains("programs/vault/src/lib.rs:40"));
+    assert!(html.contains("fee capped at MAX_FEE_BPS"));
+    assert!(html.contains("(no reason given)"));
+
+    let public = output::ReportNotices {
+        audience: output::Audience::Public,
+        ..notices
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &public).unwrap();
+    assert!(html.contains("AST-001, SOL-003 \u{d7}2"));
+    assert!(!html.contains("programs/vault/src/lib.rs:40"));
+
+    let clean = output::render_combined_report(&[], &[], None).unwrap();
+    assert!(!clean.contains("solguard-ignore"));
+}
+
+#[test]
+fn repo_overviews_head_the_security_summary() {
+    use st_solguard::memory::RepoResult;
+    use st_solguard::output::redact::Audience;
+    let mut notices = output::ReportNotices {
+        budgets: vec![
+            RepoResult {
+                name: "vault-a".into(),
+                summary: Some(
+                    "An Anchor vault with four handlers. Withdrawals trust an unchecked authority.\n\n\
+                     Attack scenario: pass any key as authority and drain the vault."
+                        .into(),
+                ),
+                ..Default::default()
+            },
+            RepoResult {
+                name: "vault-b".into(),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+    let html = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(html.contains("Withdrawals trust an unchecked authority."));
+    assert!(html.contains("drain the vault"));
+    assert!(html.find("Security Summary</h2>") < html.find("An Anchor vault"));
+    assert!(!html.contains(">vault-b</h3>"));
+
+    notices.audience = Audience::Partner;
+    let partner = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(partner.contains("Withdrawals trust an unchecked authority."));
+    assert!(!partner.contains("drain the vault"));
+
+    notices.audience = Audience::Public;
+    let public = output::render_combined_report_with_notices(&[], &[], None, &notices).unwrap();
+    assert!(!public.contains("An Anchor vault"));
+    assert!(!public.contains(">vault-a</h3>"));
+}
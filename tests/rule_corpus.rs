@@ -0,0 +1,32 @@
+//! Runs every static pattern over the built-in corpus in `rules/corpus/`.
+//! A new pattern, or a change to an existing one, has to keep this green.
+
+use st_solguard::security::rule_corpus;
+use std::path::PathBuf;
+
+fn builtin_corpus() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(rule_corpus::DEFAULT_CORPUS)
+}
+
+#[test]
+fn builtin_corpus_has_no_misses() {
+    let report = rule_corpus::run(&[builtin_corpus()]).unwrap();
+    assert!(report.passed(), "\n{}", report.render());
+}
+
+#[test]
+fn every_pattern_has_vulnerable_and_safe_cases() {
+    let report = rule_corpus::run(&[builtin_corpus()]).unwrap();
+    assert!(
+        report.untested.is_empty(),
+        "no corpus: {:?}",
+        report.untested
+    );
+    for p in &report.patterns {
+        assert!(
+            p.vulnerable > 0 && p.safe > 0,
+            "{} needs both vulnerable and safe snippets",
+            p.pattern_id
+        );
+    }
+}